@@ -0,0 +1,56 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use webby_global_common::response::{CmsRowResponse, CmsRowResponseRef, ListResponse, RawRow};
+
+fn list_payload(rows: usize) -> String {
+    let items = (0..rows)
+        .map(|i| {
+            serde_json::json!({
+                "fields": {
+                    "_id": format!("0190e2b0-4d2a-7c1e-9a53-{i:012}"),
+                    "title": format!("Row Title Number {i}"),
+                    "description": "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
+                    "tags": ["alpha", "beta", "gamma"],
+                    "count": i,
+                    "price": 9.99,
+                    "published": true,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "items": items,
+        "offset": 0,
+        "limit": rows,
+        "total": rows,
+    })
+    .to_string()
+}
+
+fn deserialize_rows(c: &mut Criterion) {
+    let payload = list_payload(5_000);
+
+    let mut group = c.benchmark_group("list_5000_rows");
+
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            serde_json::from_str::<ListResponse<CmsRowResponse>>(black_box(&payload)).unwrap()
+        })
+    });
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            serde_json::from_str::<ListResponse<CmsRowResponseRef<'_>>>(black_box(&payload))
+                .unwrap()
+        })
+    });
+
+    group.bench_function("raw", |b| {
+        b.iter(|| serde_json::from_str::<ListResponse<RawRow<'_>>>(black_box(&payload)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, deserialize_rows);
+criterion_main!(benches);
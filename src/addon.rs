@@ -0,0 +1,289 @@
+//! The manifest an addon is published with.
+//!
+//! It declares everything the addon needs from a site, and is shown to the owner before they
+//! install it.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use url::Url;
+
+use crate::{
+    error::{GlobalError, Result},
+    events::WebhookEventType,
+    id::AddonUuid,
+    permissions::{NamespaceGrant, Permission},
+    schema::{
+        SchemaFieldMap, SchematicFieldType, SchematicFieldValue, constraints::FieldConstraints,
+        version::SchemaVersion,
+    },
+    uuid::CollectionName,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonManifest {
+    pub id: AddonUuid,
+    pub name: String,
+    pub version: AddonVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// What the addon is allowed to access once installed.
+    #[serde(default)]
+    pub scopes: Vec<AddonScope>,
+    /// Collections created in the site when the addon is installed.
+    #[serde(default)]
+    pub schemas: Vec<AddonSchema>,
+    #[serde(default)]
+    pub webhooks: Vec<AddonWebhook>,
+    #[serde(default)]
+    pub widgets: Vec<AddonWidget>,
+    /// Settings the site owner fills in.
+    #[serde(default)]
+    pub settings: Vec<AddonSetting>,
+}
+
+/// `major.minor.patch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AddonVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum AddonScope {
+    /// Records of the site's collections.
+    Collections(NamespaceGrant),
+    Members(Vec<Permission>),
+    Uploads(Vec<Permission>),
+    Pages(Vec<Permission>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonSchema {
+    pub id: String,
+    pub display_name: String,
+    pub primary_field: String,
+    #[serde(default)]
+    pub version: SchemaVersion,
+    pub fields: SchemaFieldMap,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddonWebhook {
+    /// Has to use HTTPS.
+    pub url: Url,
+    pub events: Vec<WebhookEventType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonWidget {
+    /// Unique in the manifest.
+    pub key: String,
+    pub name: String,
+    /// Path of the script inside the addon bundle, eg. `widgets/cart.js`.
+    pub entry: String,
+    pub placement: WidgetPlacement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WidgetPlacement {
+    /// Added to pages of the website by the editor.
+    Page,
+    /// Shown in the site dashboard.
+    Dashboard,
+    /// A panel in the editor's sidebar.
+    Panel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddonSetting {
+    /// Unique in the manifest.
+    pub key: String,
+    pub label: String,
+    pub field_type: SchematicFieldType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<SchematicFieldValue>,
+    #[serde(default, skip_serializing_if = "FieldConstraints::is_empty")]
+    pub constraints: FieldConstraints,
+}
+
+impl AddonManifest {
+    /// Check the manifest is consistent before it's published.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(GlobalError::InvalidManifest(reason));
+
+        if self.name.trim().is_empty() {
+            return invalid(String::from("The name is empty"));
+        }
+
+        let mut schema_ids = HashSet::new();
+
+        for schema in &self.schemas {
+            if let Err(e) = CollectionName::parse(&schema.id) {
+                return invalid(format!("Schema {}: {e}", schema.id));
+            }
+
+            if !schema_ids.insert(schema.id.as_str()) {
+                return invalid(format!("Schema {} is declared twice", schema.id));
+            }
+
+            if !schema
+                .fields
+                .keys()
+                .any(|key| key.as_str() == schema.primary_field)
+            {
+                return invalid(format!(
+                    "Schema {}: primary field {} doesn't exist",
+                    schema.id, schema.primary_field
+                ));
+            }
+        }
+
+        for webhook in &self.webhooks {
+            if webhook.url.scheme() != "https" {
+                return invalid(format!("Webhook {} doesn't use HTTPS", webhook.url));
+            }
+
+            if webhook.events.is_empty() {
+                return invalid(format!("Webhook {} has no events", webhook.url));
+            }
+
+            let row_events = webhook.events.iter().any(|v| {
+                !matches!(
+                    v,
+                    WebhookEventType::SchemaChanged | WebhookEventType::UploadCompleted
+                )
+            });
+
+            if row_events && !self.has_collection_permission(Permission::Read) {
+                return invalid(format!(
+                    "Webhook {} receives row events without a Collections scope to read them",
+                    webhook.url
+                ));
+            }
+        }
+
+        let mut widget_keys = HashSet::new();
+
+        for widget in &self.widgets {
+            if !widget_keys.insert(widget.key.as_str()) {
+                return invalid(format!("Widget {} is declared twice", widget.key));
+            }
+
+            if !is_bundle_path(&widget.entry) {
+                return invalid(format!(
+                    "Widget {}: {} isn't a path inside the bundle",
+                    widget.key, widget.entry
+                ));
+            }
+        }
+
+        let mut setting_keys = HashSet::new();
+
+        for setting in &self.settings {
+            if setting.key.trim().is_empty() {
+                return invalid(String::from("A setting has an empty key"));
+            }
+
+            if !setting_keys.insert(setting.key.as_str()) {
+                return invalid(format!("Setting {} is declared twice", setting.key));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a Collections scope gives the permission in any namespace.
+    pub fn has_collection_permission(&self, permission: Permission) -> bool {
+        self.scopes.iter().any(|scope| match scope {
+            AddonScope::Collections(grant) => grant.permissions.contains(&permission),
+            _ => false,
+        })
+    }
+
+    /// Scopes which weren't requested by the previous version. The site owner has to accept
+    /// them before the addon is updated.
+    pub fn new_scopes<'a>(&'a self, previous: &AddonManifest) -> Vec<&'a AddonScope> {
+        self.scopes
+            .iter()
+            .filter(|scope| !previous.scopes.contains(scope))
+            .collect()
+    }
+}
+
+/// Relative, without `..`, and not empty.
+fn is_bundle_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && !path.contains(':')
+        && path.split('/').all(|v| !v.is_empty() && v != "..")
+}
+
+impl AddonVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GlobalError::ParseError(format!("Addon Version: {value}"));
+
+        let mut parts = value.trim().trim_start_matches('v').split('.');
+        let mut next = || -> Result<u32> {
+            parts
+                .next()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(invalid)
+        };
+
+        let version = Self::new(next()?, next()?, next()?);
+
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(version)
+    }
+}
+
+impl Display for AddonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for AddonVersion {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for AddonVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AddonVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,113 @@
+//! Records of who changed what, kept the same way by every service.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    id::{AnyPublicId, SchemaDataPublicId},
+    publish::{FieldChange, RowFields, diff_fields},
+    schema::{Operations, SchematicFieldKey},
+    uuid::{CollectionName, UuidType},
+    value::SimpleValue,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub actor: UuidType,
+    pub action: Operations,
+    pub target: AuditTarget,
+    /// Sorted by field name. Empty for actions which don't change fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<FieldChange>,
+    pub occurred_at: OffsetDateTime,
+    /// The [`TraceContext::request_id`](crate::trace::TraceContext::request_id) of the request
+    /// which made the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum AuditTarget {
+    Collection(CollectionName),
+    Row {
+        collection: CollectionName,
+        id: SchemaDataPublicId,
+    },
+    /// Anything else with a typed ID, eg. a member or an upload.
+    Entity(AnyPublicId),
+}
+
+impl AuditEntry {
+    pub fn new(
+        actor: UuidType,
+        action: Operations,
+        target: AuditTarget,
+        occurred_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            actor,
+            action,
+            target,
+            changes: Vec::new(),
+            occurred_at,
+            request_id: None,
+        }
+    }
+
+    /// An entry for a change to a row, with the fields which changed.
+    pub fn row_change(
+        actor: UuidType,
+        action: Operations,
+        collection: CollectionName,
+        id: SchemaDataPublicId,
+        before: Option<&RowFields>,
+        after: Option<&RowFields>,
+        occurred_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            changes: diff(before, after),
+            ..Self::new(
+                actor,
+                action,
+                AuditTarget::Row { collection, id },
+                occurred_at,
+            )
+        }
+    }
+
+    pub fn with_request_id(mut self, request_id: Uuid) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    pub fn change_of(&self, field: &SchematicFieldKey) -> Option<&FieldChange> {
+        self.changes.iter().find(|v| v.field == *field)
+    }
+
+    /// Replace the values of the fields with a placeholder, keeping that they changed.
+    ///
+    /// For fields which can't be stored in the log, like passwords or payment details.
+    pub fn redact(&mut self, fields: &[SchematicFieldKey]) {
+        let placeholder = || SimpleValue::Text(String::from("[redacted]"));
+
+        for change in &mut self.changes {
+            if fields.contains(&change.field) {
+                change.before = change.before.as_ref().map(|_| placeholder());
+                change.after = change.after.as_ref().map(|_| placeholder());
+            }
+        }
+    }
+}
+
+/// The changed fields between two versions of a row, sorted by field name.
+///
+/// A row without a `before` was inserted, every field is added. A row without an `after` was
+/// removed, every field is removed.
+pub fn diff(before: Option<&RowFields>, after: Option<&RowFields>) -> Vec<FieldChange> {
+    let empty = RowFields::new();
+
+    diff_fields(before.unwrap_or(&empty), after.unwrap_or(&empty))
+}
@@ -0,0 +1,195 @@
+//! API keys used by services and addons to call the CMS.
+//!
+//! A key is handed out once as an [`ApiKeyToken`] (`wbk_<prefix>_<secret>`). Only the prefix and
+//! a SHA-256 hash of the secret are stored, so the token can't be recovered from an [`ApiKey`].
+
+use std::fmt::{self, Debug, Display};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+use crate::{
+    error::{GlobalError, Result},
+    id::ApiKeyId,
+    schema::Operations,
+};
+
+const TOKEN_PREFIX: &str = "wbk_";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    /// Public part of the token, used to look the key up and shown in dashboards.
+    pub prefix: String,
+    /// Hex encoded SHA-256 of the secret.
+    pub hashed_secret: String,
+    pub scopes: Vec<Scope>,
+    /// None = Never Expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// What an API key is allowed to do.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Scope {
+    /// A single operation on the rows of collections.
+    Cms(Operations),
+    /// Create, change and delete collections.
+    ManageSchemas,
+    ManageMembers,
+    ManageUploads,
+    ManageWebhooks,
+    /// Every scope.
+    Admin,
+}
+
+/// The token a key is used with. The secret is never displayed, use [`ApiKeyToken::expose`] to
+/// hand it out.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKeyToken {
+    prefix: String,
+    secret: String,
+}
+
+impl ApiKey {
+    /// A key for the token, hashing its secret.
+    pub fn new(
+        id: ApiKeyId,
+        token: &ApiKeyToken,
+        scopes: Vec<Scope>,
+        expires_at: Option<OffsetDateTime>,
+    ) -> Self {
+        Self {
+            id,
+            prefix: token.prefix.clone(),
+            hashed_secret: hash_secret(&token.secret),
+            scopes,
+            expires_at,
+        }
+    }
+
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at.is_some_and(|v| now >= v)
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes
+            .iter()
+            .any(|v| *v == scope || *v == Scope::Admin)
+    }
+
+    pub fn allows(&self, operation: Operations) -> bool {
+        self.has_scope(Scope::Cms(operation))
+    }
+
+    /// Check the token belongs to this key and it hasn't expired. The secret is compared in
+    /// constant time.
+    pub fn verify(&self, token: &ApiKeyToken, now: OffsetDateTime) -> Result<()> {
+        if token.prefix != self.prefix
+            || !constant_time_eq(
+                hash_secret(&token.secret).as_bytes(),
+                self.hashed_secret.as_bytes(),
+            )
+        {
+            return Err(GlobalError::InvalidApiKey(String::from(
+                "the secret doesn't match",
+            )));
+        }
+
+        if self.is_expired(now) {
+            return Err(GlobalError::InvalidApiKey(format!("{self} has expired")));
+        }
+
+        Ok(())
+    }
+}
+
+/// Only the prefix, eg. `wbk_3f9a1c2e_…`.
+impl Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{TOKEN_PREFIX}{}_…", self.prefix)
+    }
+}
+
+impl Scope {
+    /// The scopes needed to read rows.
+    pub fn read_only() -> Vec<Self> {
+        Operations::ALL
+            .into_iter()
+            .filter(|v| v.is_read_only())
+            .map(Self::Cms)
+            .collect()
+    }
+}
+
+impl ApiKeyToken {
+    /// The prefix and secret can't be empty or contain `_`.
+    pub fn new<P: Into<String>, S: Into<String>>(prefix: P, secret: S) -> Result<Self> {
+        let prefix = prefix.into();
+        let secret = secret.into();
+
+        if !is_token_part(&prefix) || !is_token_part(&secret) {
+            return Err(GlobalError::InvalidApiKey(String::from(
+                "the prefix and secret have to be alphanumeric",
+            )));
+        }
+
+        Ok(Self { prefix, secret })
+    }
+
+    /// Parse `wbk_<prefix>_<secret>`, eg. from an `Authorization: Bearer` header.
+    pub fn parse(value: &str) -> Result<Self> {
+        let (prefix, secret) = value
+            .trim()
+            .strip_prefix(TOKEN_PREFIX)
+            .and_then(|v| v.split_once('_'))
+            .ok_or_else(|| GlobalError::InvalidApiKey(String::from("malformed token")))?;
+
+        Self::new(prefix, secret)
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The full token, including the secret.
+    pub fn expose(&self) -> String {
+        format!("{TOKEN_PREFIX}{}_{}", self.prefix, self.secret)
+    }
+}
+
+impl Display for ApiKeyToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{TOKEN_PREFIX}{}_…", self.prefix)
+    }
+}
+
+impl Debug for ApiKeyToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyToken")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_token_part(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|v| v.is_ascii_alphanumeric())
+}
+
+/// Hex encoded SHA-256, as stored in [`ApiKey::hashed_secret`].
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Compare without returning early, so the time taken doesn't reveal how much of a secret
+/// matched. Only the length can be learned.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
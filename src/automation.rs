@@ -0,0 +1,471 @@
+//! Automations: a trigger followed by a graph of steps, eg. "when a form is submitted and the
+//! budget is over 1000, email sales then wait a day and call the CRM webhook".
+//!
+//! The editor builds and validates them, the runner walks the steps with
+//! [`Automation::first_step`] and [`Automation::next_step`].
+
+pub mod cron;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use url::Url;
+
+use self::cron::CronSchedule;
+use crate::{
+    duration,
+    error::{GlobalError, Result},
+    events::WebhookEventType,
+    expr::Expression,
+    filter::FilterExpr,
+    id::{AutomationPublicId, FormPublicId},
+    schema::SchematicFieldKey,
+    tz,
+    uuid::CollectionName,
+    value::SimpleValue,
+};
+
+/// Automations can't have more steps than this.
+pub const MAX_STEPS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Automation {
+    pub id: AutomationPublicId,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: Trigger,
+    /// Checked before the first step. The automation doesn't run if it's false.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    /// The first step, None for an automation which doesn't do anything yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+/// What starts an automation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Trigger {
+    RowCreated {
+        collection: CollectionName,
+    },
+    RowUpdated {
+        collection: CollectionName,
+        /// Only run when one of these fields changed. Empty for any change.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        fields: Vec<SchematicFieldKey>,
+    },
+    FormSubmitted {
+        form: FormPublicId,
+    },
+    Schedule(Schedule),
+    /// A request to the automation's webhook URL. The key is the secret part of the URL.
+    WebhookReceived {
+        key: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub cron: CronSchedule,
+    /// The IANA time zone the cron runs in, UTC if None.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+/// Whether the automation should run, checked against the row which triggered it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Condition {
+    Filter(FilterExpr),
+    Expression(Expression),
+}
+
+/// One node of the graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    /// Unique within the automation.
+    pub id: String,
+    pub kind: StepKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum StepKind {
+    /// Run the action, then go to `next`. The automation ends if it's None.
+    Action {
+        action: Action,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        next: Option<String>,
+    },
+    /// Go to `then` if the condition is true, otherwise to `otherwise`.
+    Branch {
+        condition: Condition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        then: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        otherwise: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Action {
+    /// The recipients, subject and body support `{{field}}` placeholders.
+    SendEmail {
+        to: Vec<String>,
+        subject: String,
+        body: String,
+    },
+    /// Posts the triggering row as JSON unless a body is given, which supports `{{field}}`
+    /// placeholders.
+    CallWebhook {
+        url: Url,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+    },
+    /// Set fields of the triggering row, or of the rows of another collection matching the
+    /// filter.
+    UpdateRow {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        collection: Option<CollectionName>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filter: Option<FilterExpr>,
+        /// Evaluated against the triggering row.
+        values: HashMap<SchematicFieldKey, Expression>,
+    },
+    /// Wait before going to the next step.
+    Delay {
+        #[serde(with = "duration::iso8601")]
+        duration: time::Duration,
+    },
+}
+
+impl Automation {
+    /// Check the graph: step ids are unique, every step referenced exists, it has no cycles
+    /// and the expressions are valid.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(GlobalError::InvalidAutomation(reason));
+
+        if self.name.trim().is_empty() {
+            return invalid(String::from("The name can't be empty"));
+        }
+
+        if self.steps.len() > MAX_STEPS {
+            return invalid(format!("Automations can have at most {MAX_STEPS} steps"));
+        }
+
+        let mut ids = HashSet::new();
+
+        for step in &self.steps {
+            if step.id.is_empty() {
+                return invalid(String::from("Step ids can't be empty"));
+            }
+
+            if !ids.insert(step.id.as_str()) {
+                return invalid(format!("Step {} is defined twice", step.id));
+            }
+        }
+
+        for id in self
+            .start
+            .iter()
+            .chain(self.steps.iter().flat_map(Step::next_ids))
+        {
+            if !ids.contains(id.as_str()) {
+                return invalid(format!("Step {id} doesn't exist"));
+            }
+        }
+
+        if let Some(condition) = &self.condition {
+            condition.validate()?;
+        }
+
+        for step in &self.steps {
+            step.validate()?;
+        }
+
+        if let Trigger::Schedule(schedule) = &self.trigger
+            && let Some(zone) = &schedule.time_zone
+            && tz::database().is_some()
+            && tz::find_zone_by_id(zone).is_none()
+        {
+            return invalid(format!("Unknown time zone {zone}"));
+        }
+
+        self.check_cycles()
+    }
+
+    pub fn step(&self, id: &str) -> Option<&Step> {
+        self.steps.iter().find(|v| v.id == id)
+    }
+
+    /// The step to start with, None if the condition is false or there are no steps.
+    pub fn first_step(
+        &self,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<Option<&Step>> {
+        if let Some(condition) = &self.condition
+            && !condition.evaluate(fields)?
+        {
+            return Ok(None);
+        }
+
+        self.find(self.start.as_deref())
+    }
+
+    /// The step after the one which just ran, following branches. None once it's done.
+    pub fn next_step(
+        &self,
+        current: &Step,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<Option<&Step>> {
+        let next = match &current.kind {
+            StepKind::Action { next, .. } => next,
+            StepKind::Branch {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if condition.evaluate(fields)? {
+                    then
+                } else {
+                    otherwise
+                }
+            }
+        };
+
+        self.find(next.as_deref())
+    }
+
+    fn find(&self, id: Option<&str>) -> Result<Option<&Step>> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        self.step(id)
+            .map(Some)
+            .ok_or_else(|| GlobalError::InvalidAutomation(format!("Step {id} doesn't exist")))
+    }
+
+    /// Depth first search from every step, failing on a step already on the path.
+    fn check_cycles(&self) -> Result<()> {
+        let mut done = HashSet::new();
+
+        for step in &self.steps {
+            self.visit(step, &mut Vec::new(), &mut done)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit<'a>(
+        &'a self,
+        step: &'a Step,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Result<()> {
+        if done.contains(step.id.as_str()) {
+            return Ok(());
+        }
+
+        if path.contains(&step.id.as_str()) {
+            return Err(GlobalError::InvalidAutomation(format!(
+                "Step {} leads back to itself",
+                step.id
+            )));
+        }
+
+        path.push(&step.id);
+
+        for next in step.next_ids() {
+            if let Some(next) = self.step(next) {
+                self.visit(next, path, done)?;
+            }
+        }
+
+        path.pop();
+        done.insert(&step.id);
+
+        Ok(())
+    }
+}
+
+impl Trigger {
+    /// The webhook event which fires the trigger, if it's fired by one.
+    pub fn event_type(&self) -> Option<WebhookEventType> {
+        match self {
+            Self::RowCreated { .. } => Some(WebhookEventType::RowCreated),
+            Self::RowUpdated { .. } => Some(WebhookEventType::RowUpdated),
+            Self::FormSubmitted { .. } | Self::Schedule(_) | Self::WebhookReceived { .. } => None,
+        }
+    }
+
+    /// The collection whose rows fire the trigger.
+    pub fn collection(&self) -> Option<&CollectionName> {
+        match self {
+            Self::RowCreated { collection } | Self::RowUpdated { collection, .. } => {
+                Some(collection)
+            }
+            Self::FormSubmitted { .. } | Self::Schedule(_) | Self::WebhookReceived { .. } => None,
+        }
+    }
+
+    /// Whether an update to the fields fires the trigger.
+    pub fn watches_update(&self, changed: &[SchematicFieldKey]) -> bool {
+        match self {
+            Self::RowUpdated { fields, .. } => {
+                fields.is_empty() || fields.iter().any(|v| changed.contains(v))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Schedule {
+    /// The next run after the instant, in the schedule's time zone.
+    ///
+    /// Fails if the time zone isn't in the [`tz`] database.
+    pub fn next_after(&self, after: OffsetDateTime) -> Result<Option<OffsetDateTime>> {
+        let offset = match &self.time_zone {
+            Some(zone) => tz::find_offset_at(zone, after).ok_or_else(|| self.unknown_zone())?,
+            None => UtcOffset::UTC,
+        };
+
+        let local = after.to_offset(offset);
+
+        let Some(next) = self
+            .cron
+            .next_after(PrimitiveDateTime::new(local.date(), local.time()))
+        else {
+            return Ok(None);
+        };
+
+        let offset = match &self.time_zone {
+            Some(zone) => tz::find_local_offset(zone, next).ok_or_else(|| self.unknown_zone())?,
+            None => UtcOffset::UTC,
+        };
+
+        Ok(Some(next.assume_offset(offset)))
+    }
+
+    fn unknown_zone(&self) -> GlobalError {
+        GlobalError::InvalidAutomation(format!(
+            "Unknown time zone {}",
+            self.time_zone.as_deref().unwrap_or_default()
+        ))
+    }
+}
+
+impl Condition {
+    pub fn evaluate(&self, fields: &HashMap<SchematicFieldKey, SimpleValue>) -> Result<bool> {
+        match self {
+            Self::Filter(v) => v.evaluate(fields),
+            Self::Expression(v) => v.evaluate_condition(fields),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Filter(_) => Ok(()),
+            Self::Expression(v) => v.validate(),
+        }
+    }
+}
+
+impl From<FilterExpr> for Condition {
+    fn from(value: FilterExpr) -> Self {
+        Self::Filter(value)
+    }
+}
+
+impl From<Expression> for Condition {
+    fn from(value: Expression) -> Self {
+        Self::Expression(value)
+    }
+}
+
+impl Step {
+    pub fn action<I: Into<String>>(id: I, action: Action, next: Option<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: StepKind::Action { action, next },
+        }
+    }
+
+    pub fn branch<I: Into<String>>(
+        id: I,
+        condition: Condition,
+        then: Option<String>,
+        otherwise: Option<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            kind: StepKind::Branch {
+                condition,
+                then,
+                otherwise,
+            },
+        }
+    }
+
+    /// The steps which can run after this one.
+    pub fn next_ids(&self) -> impl Iterator<Item = &String> {
+        let (a, b) = match &self.kind {
+            StepKind::Action { next, .. } => (next, &None),
+            StepKind::Branch {
+                then, otherwise, ..
+            } => (then, otherwise),
+        };
+
+        a.iter().chain(b)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| {
+            Err(GlobalError::InvalidAutomation(format!(
+                "Step {}: {reason}",
+                self.id
+            )))
+        };
+
+        let action = match &self.kind {
+            StepKind::Branch { condition, .. } => return condition.validate(),
+            StepKind::Action { action, .. } => action,
+        };
+
+        match action {
+            Action::SendEmail { to, .. } if to.is_empty() => invalid("an email needs a recipient"),
+            Action::CallWebhook { url, .. } if !matches!(url.scheme(), "http" | "https") => {
+                invalid("webhooks have to use http or https")
+            }
+            Action::UpdateRow {
+                collection,
+                filter,
+                values,
+            } => {
+                if values.is_empty() {
+                    return invalid("no fields are updated");
+                }
+
+                if filter.is_some() && collection.is_none() {
+                    return invalid("a filter needs a collection");
+                }
+
+                values.values().try_for_each(Expression::validate)
+            }
+            Action::Delay { duration } if !duration.is_positive() => {
+                invalid("a delay has to be positive")
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Cron expressions for scheduled automations.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use time::{Date, Duration, PrimitiveDateTime, Time};
+
+use crate::error::{GlobalError, Result};
+
+/// How far ahead [`CronSchedule::next_after`] looks, enough for `0 0 29 2 *`.
+const MAX_DAYS_AHEAD: u32 = 8 * 366;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A standard 5 field cron expression: `minute hour day-of-month month day-of-week`.
+///
+/// Fields accept `*`, numbers, ranges (`1-5`), steps (`*/15`, `0-30/10`) and lists (`1,15`).
+/// Months and weekdays can also be written as `JAN` and `MON`, and weekdays as 0-7 with both 0
+/// and 7 being Sunday. `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are shortcuts.
+///
+/// Like cron, when both the day of the month and the day of the week are restricted, a day
+/// matching either runs. Anything other than a bare `*` is restricted, including `*/2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: String| GlobalError::InvalidAutomation(format!("Cron {value}: {reason}"));
+
+        let expanded = match value.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            v if v.starts_with('@') => return Err(invalid(String::from("unknown shortcut"))),
+            v => v,
+        };
+
+        let parts = expanded.split_whitespace().collect::<Vec<_>>();
+
+        let [minute, hour, day, month, weekday] = parts[..] else {
+            return Err(invalid(format!("expected 5 fields, found {}", parts.len())));
+        };
+
+        let field = |text: &str, name: &str, min: u32, max: u32, names: &[&str]| {
+            parse_field(text, min, max, names).map_err(|reason| invalid(format!("{name} {reason}")))
+        };
+
+        let mut weekdays = field(weekday, "day of the week", 0, 7, &WEEKDAYS)?;
+
+        // 7 is also Sunday.
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: value.trim().to_string(),
+            minutes: field(minute, "minute", 0, 59, &[])?,
+            hours: field(hour, "hour", 0, 23, &[])?,
+            days: field(day, "day of the month", 1, 31, &[])?,
+            months: field(month, "month", 1, 12, &MONTHS)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether the schedule runs in the minute of the date & time.
+    pub fn matches(&self, at: PrimitiveDateTime) -> bool {
+        self.matches_date(at.date())
+            && has(self.hours, at.hour() as u32)
+            && has(self.minutes, at.minute() as u32)
+    }
+
+    /// The first minute after `after` the schedule runs in.
+    ///
+    /// The times are local to wherever the schedule runs. None if it never runs, eg.
+    /// `0 0 31 2 *`.
+    pub fn next_after(&self, after: PrimitiveDateTime) -> Option<PrimitiveDateTime> {
+        let start = after.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::MINUTE;
+
+        let mut date = start.date();
+        let mut from = Some(start.time());
+
+        for _ in 0..MAX_DAYS_AHEAD {
+            if self.matches_date(date)
+                && let Some(time) = self.first_time(from.unwrap_or(Time::MIDNIGHT))
+            {
+                return Some(PrimitiveDateTime::new(date, time));
+            }
+
+            date = date.next_day()?;
+            from = None;
+        }
+
+        None
+    }
+
+    fn matches_date(&self, date: Date) -> bool {
+        if !has(self.months, u8::from(date.month()) as u32) {
+            return false;
+        }
+
+        let day = has(self.days, date.day() as u32);
+        let weekday = has(
+            self.weekdays,
+            date.weekday().number_days_from_sunday() as u32,
+        );
+
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => day,
+            (true, false) => weekday,
+            (false, false) => day || weekday,
+        }
+    }
+
+    /// The first time of the day at or after `from` the schedule runs at.
+    fn first_time(&self, from: Time) -> Option<Time> {
+        (from.hour() as u32..24)
+            .filter(|h| has(self.hours, *h))
+            .find_map(|h| {
+                let first_minute = if h == from.hour() as u32 {
+                    from.minute() as u32
+                } else {
+                    0
+                };
+                let minute = (first_minute..60).find(|m| has(self.minutes, *m))?;
+
+                Time::from_hms(h as u8, minute as u8, 0).ok()
+            })
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parse one field into a bit set of the values it contains.
+fn parse_field(text: &str, min: u32, max: u32, names: &[&str]) -> std::result::Result<u64, String> {
+    let value = |v: &str| -> std::result::Result<u32, String> {
+        let found = match names.iter().position(|n| n.eq_ignore_ascii_case(v)) {
+            // Names start at the lowest value, eg. JAN = 1 and SUN = 0.
+            Some(i) => i as u32 + min,
+            None => v.parse().map_err(|_| format!("has an invalid value {v}"))?,
+        };
+
+        if found < min || found > max {
+            return Err(format!("has to be {min} to {max}, got {found}"));
+        }
+
+        Ok(found)
+    };
+
+    let mut set = 0;
+
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|v| *v > 0)
+                    .ok_or_else(|| format!("has an invalid step {step}"))?;
+
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            v => match v.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` runs from 5 to the end.
+                None if step > 1 => (value(v)?, max),
+                None => {
+                    let v = value(v)?;
+                    (v, v)
+                }
+            },
+        };
+
+        if start > end {
+            return Err(format!("has a reversed range {range}"));
+        }
+
+        for v in (start..=end).step_by(step as usize) {
+            set |= 1 << v;
+        }
+    }
+
+    Ok(set)
+}
+
+impl FromStr for CronSchedule {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(value: CronSchedule) -> Self {
+        value.expression
+    }
+}
+
+impl Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
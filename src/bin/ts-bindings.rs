@@ -0,0 +1,19 @@
+//! Write the TypeScript definitions of the wire types to a `.d.ts`.
+//!
+//! `ts-bindings [path]`, `webby-global-common.d.ts` by default.
+
+use std::{env, fs, process::ExitCode};
+
+fn main() -> ExitCode {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("webby-global-common.d.ts"));
+
+    match fs::write(&path, webby_global_common::typescript::bundle()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Failed to write {path}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
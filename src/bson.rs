@@ -0,0 +1,278 @@
+//! Conversions between values or rows and BSON, for rows stored in MongoDB.
+//!
+//! Unlike a round trip through JSON the types are kept:
+//!
+//! - A DateTime is a BSON date time, which only has millisecond precision and is always UTC.
+//! - A Byte is an Int32 and an Integer an Int64, so reading them back gives the same variant.
+//!   An UInteger is an Int64 when it fits, otherwise a Decimal128 holding the exact value.
+//! - Dates and Times are strings in their canonical formats, and are read back like
+//!   [`SimpleValue::from_json`] reads strings.
+//! - UUIDs (binary subtype 4) and ObjectIds are read as Text. The `_id` of a row is written
+//!   as a UUID when it is one.
+//!
+//! Values inside an ArrayUnknown or ObjectUnknown are converted as JSON would be.
+
+use ::bson::{
+    Binary, Bson, DateTime, Decimal128, Document,
+    spec::{BinarySubtype, ElementType},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{GlobalError, Result},
+    response::CmsRowResponse,
+    schema::SchematicFieldKey,
+    value::{Number, SimpleValue},
+};
+
+/// The key of [`CmsRowResponse::files`] in a row's document, left out when there are none.
+pub const FILES_KEY: &str = "_files";
+/// The key of [`CmsRowResponse::meta`].
+pub const META_KEY: &str = "_meta";
+/// The key of [`CmsRowResponse::seo`].
+pub const SEO_KEY: &str = "_seo";
+
+/// A Decimal128 is a sign bit, a 14 bit exponent and a 113 bit coefficient. This is the sign and
+/// exponent of a positive number without decimals, the exponent is biased by 6176.
+const DECIMAL128_WHOLE: u128 = 6176;
+const DECIMAL128_COEFFICIENT_BITS: u32 = 113;
+
+impl From<SimpleValue> for Bson {
+    fn from(value: SimpleValue) -> Self {
+        match value {
+            SimpleValue::Text(v) => Bson::String(v),
+            SimpleValue::Number(v) => v.into(),
+            SimpleValue::Boolean(v) => Bson::Boolean(v),
+            SimpleValue::DateTime(v) => Bson::DateTime(DateTime::from_time_0_3(v)),
+            v @ (SimpleValue::Date(_) | SimpleValue::Time(_)) => json_to_bson(v.into_json()),
+            SimpleValue::ListString(v) => Bson::Array(v.into_iter().map(Bson::String).collect()),
+            SimpleValue::ListNumber(v) => Bson::Array(v.into_iter().map(Bson::from).collect()),
+            SimpleValue::ArrayUnknown(v) => Bson::Array(v.into_iter().map(json_to_bson).collect()),
+            SimpleValue::ObjectUnknown(v) => json_to_bson(v),
+        }
+    }
+}
+
+impl From<Number> for Bson {
+    fn from(value: Number) -> Self {
+        match value {
+            Number::Byte(v) => Bson::Int32(v.into()),
+            Number::Integer(v) => Bson::Int64(v),
+            Number::UInteger(v) => match i64::try_from(v) {
+                Ok(v) => Bson::Int64(v),
+                Err(_) => Bson::Decimal128(Decimal128::from_bytes(
+                    ((DECIMAL128_WHOLE << DECIMAL128_COEFFICIENT_BITS) | u128::from(v))
+                        .to_le_bytes(),
+                )),
+            },
+            Number::Float(v) => Bson::Double(v),
+        }
+    }
+}
+
+impl TryFrom<Bson> for SimpleValue {
+    type Error = GlobalError;
+
+    fn try_from(value: Bson) -> Result<Self> {
+        if let Some(v) = number(&value) {
+            return Ok(Self::Number(v));
+        }
+
+        Ok(match value {
+            Bson::Boolean(v) => Self::Boolean(v),
+            Bson::DateTime(v) => Self::DateTime(v.to_time_0_3()),
+            Bson::String(v) => Self::from_json(serde_json::Value::String(v))?,
+            Bson::Array(items) => {
+                if items.iter().all(|v| matches!(v, Bson::String(_))) {
+                    Self::ListString(
+                        items
+                            .into_iter()
+                            .filter_map(|v| match v {
+                                Bson::String(v) => Some(v),
+                                _ => None,
+                            })
+                            .collect(),
+                    )
+                } else if items.iter().all(|v| number(v).is_some()) {
+                    Self::ListNumber(items.iter().filter_map(number).collect())
+                } else {
+                    Self::ArrayUnknown(items.into_iter().map(bson_to_json).collect::<Result<_>>()?)
+                }
+            }
+            v @ Bson::Document(_) => Self::ObjectUnknown(bson_to_json(v)?),
+            v => match text(&v) {
+                Some(text) => Self::Text(text),
+                None => {
+                    return Err(GlobalError::type_mismatch("SimpleValue", type_name(&v)));
+                }
+            },
+        })
+    }
+}
+
+impl TryFrom<CmsRowResponse> for Document {
+    type Error = GlobalError;
+
+    fn try_from(value: CmsRowResponse) -> Result<Self> {
+        let mut document = Document::new();
+
+        for (key, value) in value.fields {
+            let value = match (&key, value) {
+                (SchematicFieldKey::Id, SimpleValue::Text(v)) => match Uuid::parse_str(&v) {
+                    Ok(id) => Bson::Binary(Binary {
+                        subtype: BinarySubtype::Uuid,
+                        bytes: id.as_bytes().to_vec(),
+                    }),
+                    Err(_) => Bson::String(v),
+                },
+                (_, v) => v.into(),
+            };
+
+            document.insert(key.as_str(), value);
+        }
+
+        if !value.files.is_empty() {
+            document.insert(FILES_KEY, to_bson(&value.files)?);
+        }
+
+        if let Some(meta) = value.meta {
+            document.insert(META_KEY, to_bson(&meta)?);
+        }
+
+        if let Some(seo) = value.seo {
+            document.insert(SEO_KEY, to_bson(&seo)?);
+        }
+
+        Ok(document)
+    }
+}
+
+impl TryFrom<Document> for CmsRowResponse {
+    type Error = GlobalError;
+
+    fn try_from(mut value: Document) -> Result<Self> {
+        let files = value
+            .remove(FILES_KEY)
+            .map(from_bson)
+            .transpose()?
+            .unwrap_or_default();
+        let meta = value.remove(META_KEY).map(from_bson).transpose()?;
+        let seo = value.remove(SEO_KEY).map(from_bson).transpose()?;
+
+        let fields = value
+            .into_iter()
+            // Missing and empty are the same.
+            .filter(|(_, v)| !matches!(v, Bson::Null))
+            .map(|(k, v)| {
+                Ok((
+                    SchematicFieldKey::from(k.as_str()),
+                    SimpleValue::try_from(v)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            files,
+            fields,
+            meta,
+            seo,
+        })
+    }
+}
+
+fn to_bson<T: serde::Serialize>(value: &T) -> Result<Bson> {
+    ::bson::to_bson(value).map_err(|e| GlobalError::Bson(e.to_string()))
+}
+
+fn from_bson<T: serde::de::DeserializeOwned>(value: Bson) -> Result<T> {
+    ::bson::from_bson(value).map_err(|e| GlobalError::Bson(e.to_string()))
+}
+
+/// The number in the variant [`From<Number>`](Bson::from) writes it as.
+fn number(value: &Bson) -> Option<Number> {
+    match *value {
+        Bson::Int32(v) => Some(match u8::try_from(v) {
+            Ok(v) => Number::Byte(v),
+            Err(_) => Number::Integer(v.into()),
+        }),
+        Bson::Int64(v) => Some(Number::Integer(v)),
+        Bson::Double(v) => Some(Number::Float(v)),
+        Bson::Decimal128(v) => {
+            let bits = u128::from_le_bytes(v.bytes());
+
+            if bits >> DECIMAL128_COEFFICIENT_BITS == DECIMAL128_WHOLE {
+                u64::try_from(bits & ((1 << DECIMAL128_COEFFICIENT_BITS) - 1))
+                    .ok()
+                    .map(Number::UInteger)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// UUIDs and ObjectIds as text.
+fn text(value: &Bson) -> Option<String> {
+    match value {
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes,
+        }) => Uuid::from_slice(bytes).ok().map(|v| v.to_string()),
+        Bson::ObjectId(v) => Some(v.to_hex()),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Bson) -> &'static str {
+    match value.element_type() {
+        ElementType::Null | ElementType::Undefined => "Null",
+        ElementType::Binary => "Binary",
+        ElementType::Decimal128 => "Decimal128",
+        ElementType::RegularExpression => "Regular Expression",
+        ElementType::JavaScriptCode | ElementType::JavaScriptCodeWithScope => "JavaScript",
+        ElementType::Timestamp => "Timestamp",
+        _ => "BSON",
+    }
+}
+
+fn json_to_bson(value: serde_json::Value) -> Bson {
+    match value {
+        serde_json::Value::Null => Bson::Null,
+        serde_json::Value::Bool(v) => Bson::Boolean(v),
+        serde_json::Value::Number(v) => match v.as_i64() {
+            Some(v) => Bson::Int64(v),
+            None => Bson::Double(v.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(v) => Bson::String(v),
+        serde_json::Value::Array(v) => Bson::Array(v.into_iter().map(json_to_bson).collect()),
+        serde_json::Value::Object(v) => {
+            Bson::Document(v.into_iter().map(|(k, v)| (k, json_to_bson(v))).collect())
+        }
+    }
+}
+
+fn bson_to_json(value: Bson) -> Result<serde_json::Value> {
+    if let Some(v) = number(&value) {
+        return Ok(v.into());
+    }
+
+    Ok(match value {
+        Bson::Null => serde_json::Value::Null,
+        Bson::Boolean(v) => serde_json::Value::Bool(v),
+        Bson::String(v) => serde_json::Value::String(v),
+        Bson::DateTime(v) => SimpleValue::DateTime(v.to_time_0_3()).into_json(),
+        Bson::Array(v) => {
+            serde_json::Value::Array(v.into_iter().map(bson_to_json).collect::<Result<_>>()?)
+        }
+        Bson::Document(v) => serde_json::Value::Object(
+            v.into_iter()
+                .map(|(k, v)| Ok((k, bson_to_json(v)?)))
+                .collect::<Result<_>>()?,
+        ),
+        v => match text(&v) {
+            Some(text) => serde_json::Value::String(text),
+            None => return Err(GlobalError::type_mismatch("JSON", type_name(&v))),
+        },
+    })
+}
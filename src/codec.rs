@@ -0,0 +1,52 @@
+//! Compact binary encoding for requests between internal services, where JSON is a
+//! measurable cost for bulk row transfers.
+//!
+//! Values are CBOR (RFC 8949) after a [`FORMAT_VERSION`] byte. Unlike bincode it describes
+//! itself, so everything which round trips through JSON does here too, including untagged
+//! enums and skipped fields. UUIDs are written as bytes, the dates & times of a `SimpleValue`
+//! as the same text as in JSON.
+//!
+//! The encoding of `CmsRowResponse`, `SimpleValue`, `Filter` and `Schematic` is pinned by the
+//! golden tests in `tests/codec.rs`. A change which fails them needs a new version.
+
+use std::io::{Read, Write};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::{GlobalError, Result};
+
+/// Written before every value so a service can tell which encoding it got.
+///
+/// Version 1 used snake_case names for some fields. They're still accepted, so it can be
+/// decoded too.
+pub const FORMAT_VERSION: u8 = 2;
+
+pub fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    encode_into(value, &mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn encode_into<T: Serialize + ?Sized, W: Write>(value: &T, mut writer: W) -> Result<()> {
+    writer.write_all(&[FORMAT_VERSION])?;
+
+    ciborium::into_writer(value, writer).map_err(|e| GlobalError::Codec(e.to_string()))
+}
+
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    decode_from(bytes)
+}
+
+pub fn decode_from<T: DeserializeOwned, R: Read>(mut reader: R) -> Result<T> {
+    let mut version = [0];
+    reader.read_exact(&mut version)?;
+
+    if !(1..=FORMAT_VERSION).contains(&version[0]) {
+        return Err(GlobalError::Codec(format!(
+            "Unsupported version {}, expected 1 to {FORMAT_VERSION}",
+            version[0]
+        )));
+    }
+
+    ciborium::from_reader(reader).map_err(|e| GlobalError::Codec(e.to_string()))
+}
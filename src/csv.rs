@@ -0,0 +1,159 @@
+//! CSV import/export of CMS rows.
+//!
+//! Columns are written in field index order. Values are written in the canonical formats of
+//! [`SimpleValue::into_json`], lists are joined with commas and arrays/objects are written as JSON.
+//! When importing, headers are matched against the field key or display name (case-insensitive)
+//! and cells are converted with [`SimpleValue::coerce_to`].
+
+use std::{collections::HashMap, io};
+
+use crate::{
+    error::{GlobalError, Result},
+    response::CmsRowResponse,
+    schema::{SchemaFieldMap, SchematicField, SchematicFieldKey},
+    value::SimpleValue,
+};
+
+/// What to write in the header row.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CsvHeader {
+    /// The field key. Always round-trips.
+    #[default]
+    Key,
+    /// The display name of the field, for exports opened by people.
+    DisplayName,
+}
+
+/// Write the rows as CSV.
+///
+/// Deleted fields and upload fields (which hold raw bytes) are skipped.
+pub fn write_rows<W: io::Write>(
+    writer: W,
+    rows: &[CmsRowResponse],
+    fields: &SchemaFieldMap,
+    header: CsvHeader,
+) -> Result<()> {
+    let columns = export_columns(fields);
+
+    let mut writer = ::csv::Writer::from_writer(writer);
+
+    writer.write_record(columns.iter().map(|(key, field)| match header {
+        CsvHeader::Key => key.as_str(),
+        CsvHeader::DisplayName => field.display_name.as_str(),
+    }))?;
+
+    for row in rows {
+        writer.write_record(columns.iter().map(|(key, _)| {
+            row.fields
+                .get(*key)
+                .map(|v| cell_text(v.clone()))
+                .unwrap_or_default()
+        }))?;
+    }
+
+    writer.flush().map_err(::csv::Error::from)?;
+
+    Ok(())
+}
+
+/// Write the rows into a CSV string.
+pub fn rows_to_string(
+    rows: &[CmsRowResponse],
+    fields: &SchemaFieldMap,
+    header: CsvHeader,
+) -> Result<String> {
+    let mut output = Vec::new();
+
+    write_rows(&mut output, rows, fields, header)?;
+
+    String::from_utf8(output).map_err(GlobalError::from)
+}
+
+/// Read CSV into columns of values suitable for `CmsCreate::data`.
+///
+/// Every column has one value per row, so empty cells are kept as an empty Text.
+/// Headers which don't match a field are an error.
+pub fn read_columns<R: io::Read>(
+    reader: R,
+    fields: &SchemaFieldMap,
+) -> Result<HashMap<String, Vec<SimpleValue>>> {
+    let mut reader = ::csv::Reader::from_reader(reader);
+
+    let columns = reader
+        .headers()?
+        .iter()
+        .map(|header| {
+            find_field(fields, header).ok_or_else(|| GlobalError::UnknownField(header.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut data: HashMap<String, Vec<SimpleValue>> = columns
+        .iter()
+        .map(|(key, _)| (key.to_string(), Vec::new()))
+        .collect();
+
+    for record in reader.records() {
+        let record = record?;
+
+        for ((key, field), cell) in columns.iter().zip(record.iter()) {
+            let value = if cell.is_empty() {
+                SimpleValue::Text(String::new())
+            } else {
+                SimpleValue::Text(cell.to_string())
+                    .coerce_to(field.field_type)?
+                    .into()
+            };
+
+            if let Some(values) = data.get_mut(key.as_str()) {
+                values.push(value);
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+pub fn columns_from_str(
+    value: &str,
+    fields: &SchemaFieldMap,
+) -> Result<HashMap<String, Vec<SimpleValue>>> {
+    read_columns(value.as_bytes(), fields)
+}
+
+fn export_columns(fields: &SchemaFieldMap) -> Vec<(&SchematicFieldKey, &SchematicField)> {
+    fields
+        .iter_ordered()
+        .filter(|(_, f)| !f.is_deleted && !f.field_type.is_upload_file_type())
+        .collect()
+}
+
+fn find_field<'a>(
+    fields: &'a SchemaFieldMap,
+    header: &str,
+) -> Option<(&'a SchematicFieldKey, &'a SchematicField)> {
+    let header = header.trim();
+
+    fields
+        .get_key_value(header)
+        .or_else(|| {
+            fields
+                .iter()
+                .find(|(_, f)| f.display_name.eq_ignore_ascii_case(header))
+        })
+        .filter(|(_, f)| !f.is_deleted)
+}
+
+fn cell_text(value: SimpleValue) -> String {
+    match value {
+        SimpleValue::ListString(v) => v.join(","),
+        SimpleValue::ListNumber(v) => v
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+        v => match v.into_json() {
+            serde_json::Value::String(v) => v,
+            v => v.to_string(),
+        },
+    }
+}
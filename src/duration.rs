@@ -0,0 +1,264 @@
+//! Lengths of time, eg. of an event or a booking.
+//!
+//! Values are [`time::Duration`]s, written as ISO 8601 durations like `P1DT2H30M`.
+
+use time::Duration;
+
+use crate::error::{GlobalError, Result};
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// Parse an ISO 8601 duration (`P1DT2H30M`, `PT90M`, `-PT1.5S`) or `HH:MM[:SS[.f]]` where the
+/// hours can go past 24 (`36:00:00`).
+///
+/// Years and months are rejected since their length depends on when they start. A day is
+/// always 24 hours.
+pub fn parse(value: &str) -> Result<Duration> {
+    let value = value.trim();
+
+    let (negative, rest) = match value.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, value),
+    };
+
+    let duration = if rest.starts_with(['P', 'p']) {
+        parse_iso8601(&rest[1..]).map_err(|reason| invalid(value, reason))?
+    } else {
+        parse_clock(rest).map_err(|reason| invalid(value, reason))?
+    };
+
+    Ok(if negative { -duration } else { duration })
+}
+
+/// The ISO 8601 form, eg. `P1DT2H30M`, `PT0.25S` or `PT0S` for zero.
+pub fn to_iso8601(duration: Duration) -> String {
+    let sign = if duration.is_negative() { "-" } else { "" };
+    let seconds = duration.whole_seconds().unsigned_abs();
+    let nanos = duration.subsec_nanoseconds().unsigned_abs();
+
+    let (days, hours, minutes, seconds) = split(seconds);
+
+    let mut value = format!("{sign}P");
+
+    if days > 0 {
+        value.push_str(&format!("{days}D"));
+    }
+
+    if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 || days == 0 {
+        value.push('T');
+
+        if hours > 0 {
+            value.push_str(&format!("{hours}H"));
+        }
+
+        if minutes > 0 {
+            value.push_str(&format!("{minutes}M"));
+        }
+
+        if nanos > 0 {
+            let fraction = format!("{nanos:09}");
+            value.push_str(&format!("{seconds}.{}S", fraction.trim_end_matches('0')));
+        } else if seconds > 0 || (hours == 0 && minutes == 0) {
+            value.push_str(&format!("{seconds}S"));
+        }
+    }
+
+    value
+}
+
+/// A readable form in English, eg. `1 day 2 hours 30 minutes`. Fractions of a second are left
+/// out.
+pub fn humanize(duration: Duration) -> String {
+    let (days, hours, minutes, seconds) = split(duration.whole_seconds().unsigned_abs());
+
+    let parts = [
+        (days, "day"),
+        (hours, "hour"),
+        (minutes, "minute"),
+        (seconds, "second"),
+    ]
+    .into_iter()
+    .filter(|(v, _)| *v > 0)
+    .map(|(v, unit)| format!("{v} {unit}{}", if v == 1 { "" } else { "s" }))
+    .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return String::from("0 seconds");
+    }
+
+    let sign = if duration.whole_seconds() < 0 {
+        "-"
+    } else {
+        ""
+    };
+
+    format!("{sign}{}", parts.join(" "))
+}
+
+/// (De)serialize a [`Duration`] as an ISO 8601 string, eg.
+/// `#[serde(with = "webby_global_common::duration::iso8601")]`.
+///
+/// `HH:MM:SS` is also accepted when deserializing.
+pub mod iso8601 {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::to_iso8601(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        super::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+fn invalid(value: &str, reason: &str) -> GlobalError {
+    GlobalError::ParseError(format!("Duration {value:?}: {reason}"))
+}
+
+/// Days, hours, minutes and seconds.
+fn split(seconds: u64) -> (u64, u64, u64, u64) {
+    let (day, hour, minute) = (DAY as u64, HOUR as u64, MINUTE as u64);
+
+    (
+        seconds / day,
+        seconds % day / hour,
+        seconds % hour / minute,
+        seconds % minute,
+    )
+}
+
+/// The part after the `P`.
+fn parse_iso8601(value: &str) -> std::result::Result<Duration, &'static str> {
+    let value = value.to_ascii_uppercase();
+
+    let (date, time) = match value.split_once('T') {
+        Some((_, "")) => return Err("expected a time after T"),
+        Some((date, time)) => (date, Some(time)),
+        None => (value.as_str(), None),
+    };
+
+    if date.is_empty() && time.is_none() {
+        return Err("expected at least one part");
+    }
+
+    let mut total = Duration::ZERO;
+
+    for (part, is_time, units) in [
+        (date, false, &[('W', WEEK), ('D', DAY)][..]),
+        (
+            time.unwrap_or_default(),
+            true,
+            &[('H', HOUR), ('M', MINUTE), ('S', 1)][..],
+        ),
+    ] {
+        let mut rest = part;
+        let mut units = units.iter();
+
+        while !rest.is_empty() {
+            let end = rest
+                .find(|v: char| !v.is_ascii_digit() && v != '.' && v != ',')
+                .ok_or("expected a unit after the number")?;
+
+            let (number, designator) = (&rest[..end], rest[end..].chars().next().unwrap());
+            rest = &rest[end + designator.len_utf8()..];
+
+            if designator == 'Y' || (designator == 'M' && !is_time) {
+                return Err("years and months aren't supported");
+            }
+
+            // Units have to be in order and only appear once.
+            let (_, unit) = units
+                .by_ref()
+                .find(|(v, _)| *v == designator)
+                .ok_or("unexpected or repeated unit")?;
+
+            let amount = if *unit == 1 {
+                parse_seconds(number)?
+            } else {
+                let number = number
+                    .parse::<i64>()
+                    .map_err(|_| "expected a whole number")?;
+
+                number
+                    .checked_mul(*unit)
+                    .map(Duration::seconds)
+                    .ok_or("too long")?
+            };
+
+            total = total.checked_add(amount).ok_or("too long")?;
+        }
+    }
+
+    Ok(total)
+}
+
+/// `HH:MM[:SS[.f]]`
+fn parse_clock(value: &str) -> std::result::Result<Duration, &'static str> {
+    const EXPECTED: &str = "expected an ISO 8601 duration or HH:MM:SS";
+
+    let mut parts = value.split(':');
+
+    let (Some(hours), Some(minutes), seconds, None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(EXPECTED);
+    };
+
+    let hours = hours
+        .parse::<i64>()
+        .ok()
+        .filter(|_| hours.bytes().all(|v| v.is_ascii_digit()))
+        .ok_or(EXPECTED)?;
+
+    let minutes = sexagesimal(minutes).ok_or(EXPECTED)?;
+
+    let seconds = match seconds {
+        Some(v) => {
+            let whole = v.split(['.', ',']).next().unwrap_or_default();
+            sexagesimal(whole).ok_or(EXPECTED)?;
+            parse_seconds(v)?
+        }
+        None => Duration::ZERO,
+    };
+
+    hours
+        .checked_mul(HOUR)
+        .and_then(|v| v.checked_add(minutes * MINUTE))
+        .map(Duration::seconds)
+        .and_then(|v| v.checked_add(seconds))
+        .ok_or("too long")
+}
+
+/// Two digits from 00 to 59.
+fn sexagesimal(value: &str) -> Option<i64> {
+    (value.len() == 2 && value.bytes().all(|v| v.is_ascii_digit()))
+        .then(|| value.parse().ok())
+        .flatten()
+        .filter(|v| *v < 60)
+}
+
+/// Whole seconds with up to 9 decimals.
+fn parse_seconds(value: &str) -> std::result::Result<Duration, &'static str> {
+    const EXPECTED: &str = "expected seconds with up to 9 decimals";
+
+    let (whole, fraction) = value.split_once(['.', ',']).unwrap_or((value, ""));
+
+    if whole.is_empty()
+        || fraction.len() > 9
+        || !whole.bytes().all(|v| v.is_ascii_digit())
+        || !fraction.bytes().all(|v| v.is_ascii_digit())
+    {
+        return Err(EXPECTED);
+    }
+
+    let seconds = whole.parse::<i64>().map_err(|_| "too long")?;
+    let nanos = format!("{fraction:0<9}")
+        .parse::<i32>()
+        .map_err(|_| EXPECTED)?;
+
+    Ok(Duration::new(seconds, nanos))
+}
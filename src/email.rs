@@ -0,0 +1,491 @@
+//! Emails sent by automations and the transactional mail service.
+//!
+//! Messages either carry their own bodies or point to an [`EmailTemplate`]. Either way
+//! `{{name}}` placeholders are filled from the message's merge variables when it's rendered.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    id::TemplatePublicId,
+    notify::render_template_with,
+    schema::SchematicFieldKey,
+    validators::EmailAddress,
+    value::SimpleValue,
+};
+
+/// A message can't be sent to more addresses than this.
+pub const MAX_RECIPIENTS: usize = 50;
+
+/// An address with an optional display name, written as `Jane Doe <jane@example.com>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Mailbox {
+    name: Option<String>,
+    address: EmailAddress,
+}
+
+/// Recipients without duplicate addresses, at most [`MAX_RECIPIENTS`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Mailbox>", into = "Vec<Mailbox>")]
+pub struct AddressList(Vec<Mailbox>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailMessage {
+    pub to: AddressList,
+    pub from: Mailbox,
+    /// Can be left empty when the template has one.
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+    /// Used for whatever the message leaves empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<TemplatePublicId>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub merge_vars: HashMap<String, SimpleValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailTemplate {
+    pub id: TemplatePublicId,
+    pub name: String,
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+}
+
+/// A message with its template applied and every placeholder replaced, ready to send.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedEmail {
+    pub to: AddressList,
+    pub from: Mailbox,
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+}
+
+impl Mailbox {
+    pub fn new(address: EmailAddress) -> Self {
+        Self {
+            name: None,
+            address,
+        }
+    }
+
+    /// Fails if the name has a line break or another control character. An empty name is
+    /// left out.
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Result<Self> {
+        let name = name.into();
+
+        check_name(&name)?;
+
+        self.name = (!name.is_empty()).then_some(name);
+        Ok(self)
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn address(&self) -> &EmailAddress {
+        &self.address
+    }
+
+    /// Parse `jane@example.com`, `Jane Doe <jane@example.com>` or
+    /// `"Doe, Jane" <jane@example.com>`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+
+        let Some((name, address)) = value.strip_suffix('>').and_then(|v| v.rsplit_once('<')) else {
+            return Ok(Self::new(EmailAddress::parse(value)?));
+        };
+
+        let name = name.trim();
+
+        let name = match name.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(quoted) => unquote(quoted),
+            None => name.to_string(),
+        };
+
+        check_name(&name)?;
+
+        Ok(Self {
+            name: (!name.is_empty()).then_some(name),
+            address: EmailAddress::parse(address)?,
+        })
+    }
+}
+
+impl AddressList {
+    /// Fails if there are more than [`MAX_RECIPIENTS`]. Repeated addresses are dropped.
+    pub fn new(mailboxes: Vec<Mailbox>) -> Result<Self> {
+        let mut list = Self::default();
+
+        for mailbox in mailboxes {
+            list.push(mailbox)?;
+        }
+
+        Ok(list)
+    }
+
+    /// Parse addresses separated by `,` or `;`. Separators inside quoted names are kept.
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut mailboxes = Vec::new();
+        let mut start = 0;
+        let mut quoted = false;
+        let mut escaped = false;
+
+        for (i, c) in value.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                ',' | ';' if !quoted => {
+                    mailboxes.push(&value[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        mailboxes.push(&value[start..]);
+
+        Self::new(
+            mailboxes
+                .into_iter()
+                .filter(|v| !v.trim().is_empty())
+                .map(Mailbox::parse)
+                .collect::<Result<_>>()?,
+        )
+    }
+
+    /// Add a mailbox unless its address is already in the list.
+    pub fn push(&mut self, mailbox: Mailbox) -> Result<()> {
+        if self.contains(&mailbox.address) {
+            return Ok(());
+        }
+
+        if self.0.len() >= MAX_RECIPIENTS {
+            return Err(GlobalError::InvalidEmail(format!(
+                "at most {MAX_RECIPIENTS} recipients are allowed"
+            )));
+        }
+
+        self.0.push(mailbox);
+
+        Ok(())
+    }
+
+    pub fn contains(&self, address: &EmailAddress) -> bool {
+        self.0.iter().any(|v| v.address == *address)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Mailbox> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl EmailMessage {
+    pub fn new<S: Into<String>>(from: Mailbox, to: AddressList, subject: S) -> Self {
+        Self {
+            to,
+            from,
+            subject: subject.into(),
+            html_body: None,
+            text_body: None,
+            template_id: None,
+            merge_vars: HashMap::new(),
+        }
+    }
+
+    pub fn html_body<S: Into<String>>(mut self, body: S) -> Self {
+        self.html_body = Some(body.into());
+        self
+    }
+
+    pub fn text_body<S: Into<String>>(mut self, body: S) -> Self {
+        self.text_body = Some(body.into());
+        self
+    }
+
+    pub fn template(mut self, id: TemplatePublicId) -> Self {
+        self.template_id = Some(id);
+        self
+    }
+
+    pub fn merge_var<K: Into<String>, V: Into<SimpleValue>>(mut self, key: K, value: V) -> Self {
+        self.merge_vars.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add every field of a row as a merge variable, eg. `{{_createdAt}}`.
+    pub fn merge_row(mut self, row: &HashMap<SchematicFieldKey, SimpleValue>) -> Self {
+        self.merge_vars
+            .extend(row.iter().map(|(k, v)| (k.to_string(), v.clone())));
+        self
+    }
+
+    /// Check there's a recipient, something to send and every address is valid.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(GlobalError::InvalidEmail(reason.to_string()));
+
+        if self.to.is_empty() {
+            return invalid("the message needs a recipient");
+        }
+
+        if let Some(v) = self
+            .to
+            .iter()
+            .chain([&self.from])
+            .find(|v| !v.address.is_valid())
+        {
+            return Err(GlobalError::InvalidEmail(format!(
+                "{}: isn't a valid address",
+                v.address
+            )));
+        }
+
+        if self.template_id.is_none() {
+            if self.subject.trim().is_empty() {
+                return invalid("the message needs a subject or a template");
+            }
+
+            if self.html_body.is_none() && self.text_body.is_none() {
+                return invalid("the message needs a body or a template");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply the template and fill in the merge variables.
+    ///
+    /// Values are HTML escaped in the HTML body, and line breaks in the subject become spaces.
+    /// Fails if the message has a template but a different one (or none) is given.
+    pub fn render(&self, template: Option<&EmailTemplate>) -> Result<RenderedEmail> {
+        self.validate()?;
+
+        let template = match (self.template_id, template) {
+            (Some(id), Some(template)) if template.id == id => Some(template),
+            (Some(id), _) => {
+                return Err(GlobalError::InvalidEmail(format!(
+                    "the message uses the template {id}"
+                )));
+            }
+            (None, _) => None,
+        };
+
+        let subject = match self.subject.trim() {
+            "" => template.map(|v| v.subject.as_str()).unwrap_or_default(),
+            v => v,
+        };
+
+        let html_body = self
+            .html_body
+            .as_deref()
+            .or(template.and_then(|v| v.html_body.as_deref()));
+
+        let text_body = self
+            .text_body
+            .as_deref()
+            .or(template.and_then(|v| v.text_body.as_deref()));
+
+        Ok(RenderedEmail {
+            to: self.to.clone(),
+            from: self.from.clone(),
+            subject: self
+                .substitute(subject, |v| v.to_string())
+                .replace(['\r', '\n'], " "),
+            html_body: html_body.map(|v| self.substitute(v, |v| escape_html(&v.to_string()))),
+            text_body: text_body.map(|v| self.substitute(v, |v| v.to_string())),
+        })
+    }
+
+    fn substitute(&self, template: &str, format: impl Fn(&SimpleValue) -> String) -> String {
+        render_template_with(template, |name| self.merge_vars.get(name).map(&format))
+    }
+}
+
+impl EmailTemplate {
+    /// The names of the placeholders used in the subject and bodies, in order and without
+    /// repeats.
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::<String>::new();
+
+        for text in [
+            Some(&self.subject),
+            self.html_body.as_ref(),
+            self.text_body.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            render_template_with(text, |name| {
+                if !names.iter().any(|v| v == name) {
+                    names.push(name.to_string());
+                }
+
+                None
+            });
+        }
+
+        names
+    }
+}
+
+/// Names end up in headers, so line breaks would let them add their own.
+fn check_name(name: &str) -> Result<()> {
+    if name.chars().any(|c| c.is_control() || c == '<' || c == '>') {
+        return Err(GlobalError::InvalidEmail(format!(
+            "{name}: the name has an unexpected character"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Whether the name can be written as is, as a phrase of RFC 5322 atoms.
+fn is_phrase(name: &str) -> bool {
+    !name.is_empty()
+        && name.split(' ').all(|atom| {
+            !atom.is_empty()
+                && atom.chars().all(|c| {
+                    !c.is_ascii() || c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+                })
+        })
+}
+
+fn unquote(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => output.extend(chars.next()),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl Display for Mailbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            // Anything but plain words is a quoted-string, eg. `"J. Doe"`.
+            Some(name) if !is_phrase(name) => write!(
+                f,
+                "\"{}\" <{}>",
+                name.replace('\\', "\\\\").replace('"', "\\\""),
+                self.address
+            ),
+            Some(name) => write!(f, "{name} <{}>", self.address),
+            None => self.address.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Mailbox {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Mailbox {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Mailbox> for String {
+    fn from(value: Mailbox) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<EmailAddress> for Mailbox {
+    fn from(value: EmailAddress) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Display for AddressList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, mailbox) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            mailbox.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for AddressList {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<Vec<Mailbox>> for AddressList {
+    type Error = GlobalError;
+
+    fn try_from(value: Vec<Mailbox>) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<AddressList> for Vec<Mailbox> {
+    fn from(value: AddressList) -> Self {
+        value.0
+    }
+}
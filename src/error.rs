@@ -0,0 +1,149 @@
+//! The error type returned throughout the crate.
+
+use std::{
+    num::{ParseFloatError, ParseIntError},
+    str::ParseBoolError,
+    string::FromUtf8Error,
+};
+
+use crate::schema::version::SchemaVersion;
+
+pub type Result<T, E = GlobalError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GlobalError {
+    #[error("Unable to convert {found} to {expected}")]
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("Parse Error: {0}")]
+    ParseError(String),
+
+    #[error("Invalid UUID: {0}")]
+    InvalidUuid(#[from] uuid::Error),
+
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    #[error("Invalid Date/Time: {0}")]
+    InvalidDateTime(#[from] time::error::Parse),
+
+    #[error("Unknown Time Zone: {0}")]
+    UnknownTimeZone(String),
+
+    #[error("Invalid Email: {0}")]
+    InvalidEmail(String),
+
+    #[error("Invalid Phone Number: {0}")]
+    InvalidPhoneNumber(String),
+
+    #[error("Invalid Upload: {0}")]
+    InvalidUpload(String),
+
+    #[error("Invalid UTF-8: {0}")]
+    InvalidUtf8(#[from] FromUtf8Error),
+
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON Error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unknown Field: {0}")]
+    UnknownField(String),
+
+    /// Keys of a JSON object which a [strict](crate::parse::ParseMode::Strict) parse didn't know.
+    #[error("Unknown Keys: {0}")]
+    UnknownKeys(String),
+
+    #[error("Invalid Filter: {0}")]
+    InvalidFilter(String),
+
+    #[error("Invalid Schema: {0}")]
+    InvalidSchema(String),
+
+    #[error("Invalid Cursor: {0}")]
+    InvalidCursor(String),
+
+    #[error("Conflict: expected revision {expected} but the row is at {found}")]
+    RevisionConflict { expected: u64, found: u64 },
+
+    #[error(
+        "Incompatible Schema: the client is at version {client} but the schema is at {current}"
+    )]
+    IncompatibleSchema {
+        client: SchemaVersion,
+        current: SchemaVersion,
+    },
+
+    #[error("Invalid Expression: {0}")]
+    InvalidExpression(String),
+
+    #[error("Invalid Addon Manifest: {0}")]
+    InvalidManifest(String),
+
+    #[error("Invalid API Key: {0}")]
+    InvalidApiKey(String),
+
+    #[error("Invalid Session: {0}")]
+    InvalidSession(String),
+
+    #[error("Invalid Role: {0}")]
+    InvalidRole(String),
+
+    #[error("Invalid Money: {0}")]
+    InvalidMoney(String),
+
+    #[error("Invalid Page Tree: {0}")]
+    InvalidPageTree(String),
+
+    #[error("Invalid SEO: {0}")]
+    InvalidSeo(String),
+
+    #[error("Invalid Signature: {0}")]
+    InvalidSignature(String),
+
+    #[error("Invalid Automation: {0}")]
+    InvalidAutomation(String),
+
+    #[error("Invalid Recurrence: {0}")]
+    InvalidRecurrence(String),
+
+    #[cfg(feature = "csv")]
+    #[error("CSV Error: {0}")]
+    Csv(#[from] ::csv::Error),
+
+    #[cfg(feature = "codec")]
+    #[error("Codec Error: {0}")]
+    Codec(String),
+
+    #[cfg(feature = "bson")]
+    #[error("BSON Error: {0}")]
+    Bson(String),
+}
+
+impl GlobalError {
+    pub fn type_mismatch(expected: &'static str, found: &'static str) -> Self {
+        Self::TypeMismatch { expected, found }
+    }
+}
+
+impl From<ParseBoolError> for GlobalError {
+    fn from(value: ParseBoolError) -> Self {
+        Self::ParseError(value.to_string())
+    }
+}
+
+impl From<ParseIntError> for GlobalError {
+    fn from(value: ParseIntError) -> Self {
+        Self::ParseError(value.to_string())
+    }
+}
+
+impl From<ParseFloatError> for GlobalError {
+    fn from(value: ParseFloatError) -> Self {
+        Self::ParseError(value.to_string())
+    }
+}
@@ -0,0 +1,157 @@
+//! Events sent to webhooks when something changes in the CMS.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::Sha256;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::error::{GlobalError, Result};
+
+/// The envelope version written by this crate.
+pub const WEBHOOK_EVENT_VERSION: u16 = 1;
+
+/// The header containing the [`WebhookEvent::sign`] signature of the body.
+pub const SIGNATURE_HEADER: &str = "X-Webby-Signature";
+
+/// How far the signing time of a delivery may be from the receiver's clock, so captured
+/// deliveries can't be replayed later.
+pub const SIGNATURE_TOLERANCE: Duration = Duration::minutes(5);
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookEventType {
+    RowCreated,
+    RowUpdated,
+    /// Moved to the trash.
+    RowDeleted,
+    RowRestored,
+    /// Permanently deleted.
+    RowPurged,
+    SchemaChanged,
+    UploadCompleted,
+}
+
+/// A change in the CMS, posted as JSON to every subscribed webhook.
+///
+/// New fields may be added to the envelope without bumping the version, so receivers should
+/// ignore unknown fields. The shape of the payload depends on the event type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent {
+    pub version: u16,
+    /// Unique per event, use it to skip deliveries which are retried.
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub occurred_at: OffsetDateTime,
+    /// Where the change happened.
+    ///
+    /// None = Main Website
+    /// Some() = Addon
+    pub namespace: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn new<P: Serialize>(
+        event_type: WebhookEventType,
+        namespace: Option<String>,
+        payload: &P,
+    ) -> Result<Self> {
+        Ok(Self {
+            version: WEBHOOK_EVENT_VERSION,
+            id: Uuid::now_v7(),
+            event_type,
+            occurred_at: OffsetDateTime::now_utc(),
+            namespace,
+            payload: serde_json::to_value(payload)?,
+        })
+    }
+
+    pub fn payload_as<P: DeserializeOwned>(&self) -> Result<P> {
+        Ok(serde_json::from_value(self.payload.clone())?)
+    }
+
+    /// The body which is sent and signed.
+    pub fn to_body(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// The [`SIGNATURE_HEADER`] value for [`WebhookEvent::to_body`], sent at `now`.
+    pub fn sign(&self, secret: &[u8], now: OffsetDateTime) -> Result<String> {
+        Ok(sign_body(secret, &self.to_body()?, now))
+    }
+
+    /// Check a signature made by [`WebhookEvent::sign`].
+    ///
+    /// Receivers should prefer [`WebhookEvent::from_signed_body`] since the body they received
+    /// may not serialize back to the same bytes.
+    pub fn verify(&self, secret: &[u8], header: &str, now: OffsetDateTime) -> Result<()> {
+        verify_body(secret, &self.to_body()?, header, now)
+    }
+
+    /// Verify the signature of a received body, then read the event from it.
+    pub fn from_signed_body(
+        secret: &[u8],
+        body: &[u8],
+        header: &str,
+        now: OffsetDateTime,
+    ) -> Result<Self> {
+        verify_body(secret, body, header, now)?;
+
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+/// `t=` with the unix timestamp of `now`, then `,sha256=` followed by the hex encoded
+/// HMAC-SHA256 of the timestamp, a `.` and the body.
+pub fn sign_body(secret: &[u8], body: &[u8], now: OffsetDateTime) -> String {
+    let timestamp = now.unix_timestamp();
+
+    format!(
+        "t={timestamp},{SIGNATURE_PREFIX}{}",
+        hex::encode(new_mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// Check a [`sign_body`] signature in constant time, and that it was made within
+/// [`SIGNATURE_TOLERANCE`] of `now`.
+pub fn verify_body(secret: &[u8], body: &[u8], header: &str, now: OffsetDateTime) -> Result<()> {
+    let malformed = || GlobalError::InvalidSignature(String::from("malformed header"));
+
+    let (timestamp, signature) = header.trim().split_once(',').ok_or_else(malformed)?;
+
+    let timestamp = timestamp
+        .strip_prefix("t=")
+        .and_then(|v| v.parse::<i64>().ok())
+        .ok_or_else(malformed)?;
+
+    let signature = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|v| hex::decode(v).ok())
+        .ok_or_else(malformed)?;
+
+    new_mac(secret, timestamp, body)
+        .verify_slice(&signature)
+        .map_err(|_| GlobalError::InvalidSignature(String::from("signature doesn't match")))?;
+
+    if now.unix_timestamp().abs_diff(timestamp) > SIGNATURE_TOLERANCE.whole_seconds() as u64 {
+        return Err(GlobalError::InvalidSignature(format!(
+            "signed at {timestamp}, which is too far from {}",
+            now.unix_timestamp()
+        )));
+    }
+
+    Ok(())
+}
+
+fn new_mac(secret: &[u8], timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    // HMAC accepts keys of any length.
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC key");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+
+    mac
+}
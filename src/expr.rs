@@ -0,0 +1,417 @@
+//! Expressions over the fields of a row, eg. `price * quantity > 100 && status == "open"`.
+//!
+//! Filters, computed fields and automation conditions share this evaluator so a condition
+//! means the same thing wherever it's used. Expressions are parsed from text with
+//! [`Expression::parse`] and stored as the serialized tree.
+
+pub mod functions;
+mod parse;
+
+use std::{collections::HashMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    filter::{Filter, FilterConditionType, FilterExpr, FilterValue},
+    schema::{SchematicFieldKey, path::FieldPath},
+    value::{Number, SimpleValue},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(tag = "type", content = "value")]
+pub enum Expression {
+    Literal(SimpleValue),
+    /// The value of a field of the row, or of a path into it.
+    Field(FieldPath),
+    /// Compare two values the same way a [`Filter`] does.
+    ///
+    /// For `between` the right side is a list of the start and end.
+    Compare {
+        op: FilterConditionType,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    /// Every condition is true. Empty is true.
+    And(Vec<Expression>),
+    /// Any condition is true. Empty is false.
+    Or(Vec<Expression>),
+    Not(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Sub(Box<Expression>, Box<Expression>),
+    Mul(Box<Expression>, Box<Expression>),
+    Div(Box<Expression>, Box<Expression>),
+    /// Only the chosen branch is evaluated.
+    If {
+        condition: Box<Expression>,
+        then: Box<Expression>,
+        otherwise: Box<Expression>,
+    },
+    /// A function from the [`functions`] registry.
+    Call {
+        function: String,
+        args: Vec<Expression>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateUnit {
+    Weeks,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl Expression {
+    /// Parse the text form, eg. `concat(first, " ", last)` or `age >= 18 && !blocked`.
+    ///
+    /// Fields are written as is (`address.city`, `items[0].price`) or between backticks when
+    /// the key has other characters. Functions are checked with [`Expression::validate`].
+    pub fn parse(value: &str) -> Result<Self> {
+        let expression = parse::parse(value)?;
+        expression.validate()?;
+
+        Ok(expression)
+    }
+
+    pub fn literal<V: Into<SimpleValue>>(value: V) -> Self {
+        Self::Literal(value.into())
+    }
+
+    pub fn field<K: Into<SchematicFieldKey>>(key: K) -> Self {
+        Self::Field(FieldPath::from(key.into()))
+    }
+
+    pub fn path(path: FieldPath) -> Self {
+        Self::Field(path)
+    }
+
+    pub fn compare(op: FilterConditionType, left: Expression, right: Expression) -> Self {
+        Self::Compare {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    pub fn call<F: Into<String>, I: IntoIterator<Item = Expression>>(function: F, args: I) -> Self {
+        Self::Call {
+            function: function.into(),
+            args: args.into_iter().collect(),
+        }
+    }
+
+    pub fn concat<I: IntoIterator<Item = Expression>>(parts: I) -> Self {
+        Self::call("concat", parts)
+    }
+
+    pub fn date_diff(start: Expression, end: Expression, unit: DateUnit) -> Self {
+        Self::call("date_diff", [start, end, Self::literal(unit.as_str())])
+    }
+
+    /// Calculate the value from the fields of a row.
+    ///
+    /// Fails if there's no value, see [`Expression::evaluate_optional`].
+    pub fn evaluate(
+        &self,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<SimpleValue> {
+        self.evaluate_optional(fields)?.ok_or_else(|| {
+            GlobalError::InvalidExpression(String::from("A field it reads has no value"))
+        })
+    }
+
+    /// Calculate the value from the fields of a row, None if a field it needs has no value.
+    ///
+    /// Missing values are false in conditions and skipped by functions like `concat` and
+    /// `coalesce`. Fails if a value has the wrong type or the arithmetic overflows.
+    pub fn evaluate_optional(
+        &self,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<Option<SimpleValue>> {
+        Ok(match self {
+            Self::Literal(v) => Some(v.clone()),
+            Self::Field(path) => path.get(fields),
+            Self::Compare { op, left, right } => {
+                let left = left.evaluate_optional(fields)?;
+
+                let matched = match right.evaluate_optional(fields)? {
+                    Some(right) => op.test(left.as_ref(), &filter_value(op, right)?)?,
+                    None => matches!(op, FilterConditionType::Neq | FilterConditionType::Dnc),
+                };
+
+                Some(SimpleValue::Boolean(matched))
+            }
+            Self::And(conditions) => {
+                for condition in conditions {
+                    if !condition.evaluate_condition(fields)? {
+                        return Ok(Some(SimpleValue::Boolean(false)));
+                    }
+                }
+
+                Some(SimpleValue::Boolean(true))
+            }
+            Self::Or(conditions) => {
+                for condition in conditions {
+                    if condition.evaluate_condition(fields)? {
+                        return Ok(Some(SimpleValue::Boolean(true)));
+                    }
+                }
+
+                Some(SimpleValue::Boolean(false))
+            }
+            Self::Not(condition) => {
+                Some(SimpleValue::Boolean(!condition.evaluate_condition(fields)?))
+            }
+            Self::Add(a, b) => Self::arithmetic(a, b, fields, Number::checked_add)?,
+            Self::Sub(a, b) => Self::arithmetic(a, b, fields, Number::checked_sub)?,
+            Self::Mul(a, b) => Self::arithmetic(a, b, fields, Number::checked_mul)?,
+            Self::Div(a, b) => Self::arithmetic(a, b, fields, Number::checked_div)?,
+            Self::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if condition.evaluate_condition(fields)? {
+                    then.evaluate_optional(fields)?
+                } else {
+                    otherwise.evaluate_optional(fields)?
+                }
+            }
+            Self::Call { function, args } => {
+                let function = functions::get(function)?;
+
+                let args = args
+                    .iter()
+                    .map(|v| v.evaluate_optional(fields))
+                    .collect::<Result<Vec<_>>>()?;
+
+                function.call(&args)?
+            }
+        })
+    }
+
+    /// Evaluate as a condition. A missing value is false, anything but a Boolean fails.
+    pub fn evaluate_condition(
+        &self,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<bool> {
+        match self.evaluate_optional(fields)? {
+            Some(v) => v.try_as_boolean(),
+            None => Ok(false),
+        }
+    }
+
+    /// Check every function exists and is given the right number of arguments.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Literal(_) | Self::Field(_) => Ok(()),
+            Self::Compare { left, right, .. }
+            | Self::Add(left, right)
+            | Self::Sub(left, right)
+            | Self::Mul(left, right)
+            | Self::Div(left, right) => {
+                left.validate()?;
+                right.validate()
+            }
+            Self::And(v) | Self::Or(v) => v.iter().try_for_each(Self::validate),
+            Self::Not(v) => v.validate(),
+            Self::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.validate()?;
+                then.validate()?;
+                otherwise.validate()
+            }
+            Self::Call { function, args } => {
+                functions::get(function)?.check_arity(args.len())?;
+                args.iter().try_for_each(Self::validate)
+            }
+        }
+    }
+
+    /// The fields the expression reads.
+    pub fn fields(&self) -> Vec<&SchematicFieldKey> {
+        let mut keys = Vec::new();
+        self.collect_fields(&mut keys);
+        keys
+    }
+
+    fn collect_fields<'a>(&'a self, keys: &mut Vec<&'a SchematicFieldKey>) {
+        match self {
+            Self::Literal(_) => {}
+            Self::Field(path) => {
+                if !keys.contains(&path.key()) {
+                    keys.push(path.key());
+                }
+            }
+            Self::Compare { left, right, .. }
+            | Self::Add(left, right)
+            | Self::Sub(left, right)
+            | Self::Mul(left, right)
+            | Self::Div(left, right) => {
+                left.collect_fields(keys);
+                right.collect_fields(keys);
+            }
+            Self::And(v) | Self::Or(v) | Self::Call { args: v, .. } => {
+                v.iter().for_each(|v| v.collect_fields(keys))
+            }
+            Self::Not(v) => v.collect_fields(keys),
+            Self::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition.collect_fields(keys);
+                then.collect_fields(keys);
+                otherwise.collect_fields(keys);
+            }
+        }
+    }
+
+    fn arithmetic(
+        a: &Expression,
+        b: &Expression,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+        op: fn(Number, Number) -> Option<Number>,
+    ) -> Result<Option<SimpleValue>> {
+        let (Some(a), Some(b)) = (a.evaluate_optional(fields)?, b.evaluate_optional(fields)?)
+        else {
+            return Ok(None);
+        };
+
+        op(a.try_as_number()?, b.try_as_number()?)
+            .map(|v| Some(SimpleValue::Number(v)))
+            .ok_or_else(|| {
+                GlobalError::InvalidExpression(String::from(
+                    "Arithmetic overflowed or divided by zero",
+                ))
+            })
+    }
+}
+
+impl FromStr for Expression {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// A filter becomes a comparison of its field against its value.
+impl TryFrom<Filter> for Expression {
+    type Error = GlobalError;
+
+    fn try_from(value: Filter) -> Result<Self> {
+        if value.cond == FilterConditionType::Near {
+            return Err(GlobalError::InvalidExpression(String::from(
+                "Near filters can't be written as expressions",
+            )));
+        }
+
+        let path = value.field_path()?;
+
+        Ok(Self::compare(
+            value.cond,
+            Self::Field(path),
+            Self::Literal(SimpleValue::from(value.value)),
+        ))
+    }
+}
+
+impl TryFrom<FilterExpr> for Expression {
+    type Error = GlobalError;
+
+    fn try_from(value: FilterExpr) -> Result<Self> {
+        Ok(match value {
+            FilterExpr::Condition(v) => Self::try_from(v)?,
+            FilterExpr::And(v) => {
+                Self::And(v.into_iter().map(Self::try_from).collect::<Result<_>>()?)
+            }
+            FilterExpr::Or(v) => {
+                Self::Or(v.into_iter().map(Self::try_from).collect::<Result<_>>()?)
+            }
+            FilterExpr::Not(v) => Self::Not(Box::new(Self::try_from(*v)?)),
+        })
+    }
+}
+
+impl DateUnit {
+    /// The name used in expressions, eg. `days`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weeks => "weeks",
+            Self::Days => "days",
+            Self::Hours => "hours",
+            Self::Minutes => "minutes",
+            Self::Seconds => "seconds",
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Self::Weeks => 7 * 24 * 60 * 60,
+            Self::Days => 24 * 60 * 60,
+            Self::Hours => 60 * 60,
+            Self::Minutes => 60,
+            Self::Seconds => 1,
+        }
+    }
+}
+
+impl FromStr for DateUnit {
+    type Err = GlobalError;
+
+    /// Accepts the singular too, eg. `day`.
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.to_lowercase().trim_end_matches('s') {
+            "week" => Self::Weeks,
+            "day" => Self::Days,
+            "hour" => Self::Hours,
+            "minute" => Self::Minutes,
+            "second" => Self::Seconds,
+            _ => {
+                return Err(GlobalError::InvalidExpression(format!(
+                    "Unknown date unit {s}"
+                )));
+            }
+        })
+    }
+}
+
+/// Turn the right side of a comparison into what the filter checks against.
+///
+/// Dates and times are written the way [`SimpleValue::into_json`] does so the filter parses
+/// them back.
+pub(crate) fn filter_value(op: &FilterConditionType, value: SimpleValue) -> Result<FilterValue> {
+    Ok(match value {
+        SimpleValue::ListNumber(v) if *op == FilterConditionType::Between => match v[..] {
+            [start, end] => FilterValue::Range((start, end)),
+            _ => {
+                return Err(GlobalError::InvalidExpression(String::from(
+                    "Between requires a start and an end",
+                )));
+            }
+        },
+        SimpleValue::Number(v) => FilterValue::Number(v),
+        SimpleValue::Text(v) => FilterValue::Text(v),
+        SimpleValue::ListString(v) => FilterValue::IdList(v),
+        v @ (SimpleValue::Boolean(_)
+        | SimpleValue::DateTime(_)
+        | SimpleValue::Date(_)
+        | SimpleValue::Time(_)) => match v.into_json() {
+            serde_json::Value::String(v) => FilterValue::Text(v),
+            v => FilterValue::Text(v.to_string()),
+        },
+        v => {
+            return Err(GlobalError::type_mismatch(
+                "Number, Text or ListString",
+                v.type_name(),
+            ));
+        }
+    })
+}
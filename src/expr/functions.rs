@@ -0,0 +1,408 @@
+//! The functions expressions can call.
+//!
+//! Functions are given None for arguments without a value. Unless said otherwise they return
+//! None when one of their arguments is None.
+
+use std::fmt;
+
+use time::{Date, Duration, OffsetDateTime, format_description::well_known::Rfc3339};
+
+use super::{DateUnit, filter_value};
+use crate::{
+    error::{GlobalError, Result},
+    filter::FilterConditionType,
+    value::{Number, SimpleValue},
+};
+
+type Args<'a> = &'a [Option<SimpleValue>];
+
+pub struct Function {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub min_args: usize,
+    /// None for any number of arguments.
+    pub max_args: Option<usize>,
+    func: fn(Args) -> Result<Option<SimpleValue>>,
+}
+
+#[rustfmt::skip]
+pub static FUNCTIONS: &[Function] = &[
+    // Text
+    Function::new("concat", "Joins the text of every argument which has a value", 0, None, concat),
+    Function::new("lower", "The text in lowercase", 1, Some(1), lower),
+    Function::new("upper", "The text in uppercase", 1, Some(1), upper),
+    Function::new("trim", "The text without leading or trailing whitespace", 1, Some(1), trim),
+    Function::new("length", "The number of characters of text or items of a list", 1, Some(1), length),
+    Function::new("contains", "Whether the text or list contains the value, ignoring case", 2, Some(2), contains),
+    Function::new("starts_with", "Whether the text starts with the prefix, ignoring case", 2, Some(2), starts_with),
+    Function::new("ends_with", "Whether the text ends with the suffix, ignoring case", 2, Some(2), ends_with),
+    Function::new("replace", "The text with every match replaced", 3, Some(3), replace),
+    // Number
+    Function::new("abs", "The number without its sign", 1, Some(1), abs),
+    Function::new("round", "The number rounded to a number of decimals, 0 by default", 1, Some(2), round),
+    Function::new("floor", "The largest whole number less than or equal to the number", 1, Some(1), floor),
+    Function::new("ceil", "The smallest whole number greater than or equal to the number", 1, Some(1), ceil),
+    Function::new("min", "The smallest number which has a value", 1, None, min),
+    Function::new("max", "The largest number which has a value", 1, None, max),
+    // Date
+    Function::new("now", "The current date and time in UTC", 0, Some(0), now),
+    Function::new("today", "The current date in UTC", 0, Some(0), today),
+    Function::new("year", "The year of a date", 1, Some(1), year),
+    Function::new("month", "The month of a date, 1 to 12", 1, Some(1), month),
+    Function::new("day", "The day of the month of a date", 1, Some(1), day),
+    Function::new("date_add", "The date plus an amount of weeks, days, hours, minutes or seconds", 3, Some(3), date_add),
+    Function::new("date_diff", "The whole number of units from start to end", 3, Some(3), date_diff),
+    // Other
+    Function::new("coalesce", "The first argument which has a value", 1, None, coalesce),
+];
+
+/// Look up a function by name.
+pub fn get(name: &str) -> Result<&'static Function> {
+    FUNCTIONS
+        .iter()
+        .find(|v| v.name == name)
+        .ok_or_else(|| GlobalError::InvalidExpression(format!("Unknown function {name}")))
+}
+
+impl Function {
+    const fn new(
+        name: &'static str,
+        description: &'static str,
+        min_args: usize,
+        max_args: Option<usize>,
+        func: fn(Args) -> Result<Option<SimpleValue>>,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            min_args,
+            max_args,
+            func,
+        }
+    }
+
+    pub fn check_arity(&self, count: usize) -> Result<()> {
+        if count < self.min_args || self.max_args.is_some_and(|max| count > max) {
+            return Err(GlobalError::InvalidExpression(format!(
+                "{} takes {}, got {count}",
+                self.name,
+                match self.max_args {
+                    Some(max) if max == self.min_args => format!("{max} arguments"),
+                    Some(max) => format!("{} to {max} arguments", self.min_args),
+                    None => format!("at least {} arguments", self.min_args),
+                }
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn call(&self, args: Args) -> Result<Option<SimpleValue>> {
+        self.check_arity(args.len())?;
+
+        (self.func)(args)
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("min_args", &self.min_args)
+            .field("max_args", &self.max_args)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Bind the arguments by name, or return None from the function if one is missing.
+macro_rules! required {
+    ($args:expr, [$($name:ident),+]) => {
+        let [$(Some($name)),+] = $args else {
+            return Ok(None);
+        };
+    };
+}
+
+fn text(value: &SimpleValue, func: fn(&str) -> String) -> Result<Option<SimpleValue>> {
+    Ok(Some(SimpleValue::Text(func(&value.any_as_text()?))))
+}
+
+fn concat(args: Args) -> Result<Option<SimpleValue>> {
+    let mut value = None::<String>;
+
+    for arg in args.iter().flatten() {
+        value.get_or_insert_default().push_str(&arg.any_as_text()?);
+    }
+
+    Ok(value.map(SimpleValue::Text))
+}
+
+fn lower(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    text(value, str::to_lowercase)
+}
+
+fn upper(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    text(value, str::to_uppercase)
+}
+
+fn trim(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    text(value, |v| v.trim().to_string())
+}
+
+fn length(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    let length = match value {
+        SimpleValue::ListString(v) => v.len(),
+        SimpleValue::ListNumber(v) => v.len(),
+        SimpleValue::ArrayUnknown(v) => v.len(),
+        v => v.any_as_text()?.chars().count(),
+    };
+
+    Ok(Some(SimpleValue::Number(Number::Integer(length as i64))))
+}
+
+fn contains(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value, search]);
+
+    let op = FilterConditionType::Cont;
+    let matched = op.test(Some(value), &filter_value(&op, search.clone())?)?;
+
+    Ok(Some(SimpleValue::Boolean(matched)))
+}
+
+fn affix(args: Args, func: fn(&str, &str) -> bool) -> Result<Option<SimpleValue>> {
+    required!(args, [value, affix]);
+
+    let value = value.any_as_text()?.to_lowercase();
+    let affix = affix.any_as_text()?.to_lowercase();
+
+    Ok(Some(SimpleValue::Boolean(func(&value, &affix))))
+}
+
+fn starts_with(args: Args) -> Result<Option<SimpleValue>> {
+    affix(args, |v, a| v.starts_with(a))
+}
+
+fn ends_with(args: Args) -> Result<Option<SimpleValue>> {
+    affix(args, |v, a| v.ends_with(a))
+}
+
+fn replace(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value, from, to]);
+
+    let from = from.any_as_text()?;
+
+    if from.is_empty() {
+        return Err(GlobalError::InvalidExpression(String::from(
+            "replace can't replace empty text",
+        )));
+    }
+
+    Ok(Some(SimpleValue::Text(
+        value.any_as_text()?.replace(&from, &to.any_as_text()?),
+    )))
+}
+
+fn abs(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    let value = match value.try_as_number()? {
+        Number::Integer(v) => Number::Integer(v.checked_abs().ok_or_else(overflow)?),
+        Number::Float(v) => Number::Float(v.abs()),
+        v @ (Number::Byte(_) | Number::UInteger(_)) => v,
+    };
+
+    Ok(Some(SimpleValue::Number(value)))
+}
+
+/// Apply a rounding function to a Float, keeping whole numbers as Integers.
+fn whole(value: Number, func: fn(f64) -> f64) -> Result<Number> {
+    let Number::Float(v) = value else {
+        return Ok(value);
+    };
+
+    let v = func(v);
+
+    // Casting saturates, so out of range values come back different.
+    if v.is_finite() && v as i64 as f64 == v {
+        Ok(Number::Integer(v as i64))
+    } else {
+        Err(overflow())
+    }
+}
+
+fn round(args: Args) -> Result<Option<SimpleValue>> {
+    let (value, decimals) = match args {
+        [Some(value)] => (value.try_as_number()?, 0),
+        [Some(value), Some(decimals)] => (
+            value.try_as_number()?,
+            decimals.try_as_number()?.convert_i64(),
+        ),
+        _ => return Ok(None),
+    };
+
+    if decimals == 0 {
+        return Ok(Some(SimpleValue::Number(whole(value, f64::round)?)));
+    }
+
+    if !(1..=15).contains(&decimals) {
+        return Err(GlobalError::InvalidExpression(format!(
+            "round takes 0 to 15 decimals, got {decimals}"
+        )));
+    }
+
+    let value = match value {
+        Number::Float(v) => {
+            let scale = 10f64.powi(decimals as i32);
+
+            Number::Float((v * scale).round() / scale)
+        }
+        v => v,
+    };
+
+    Ok(Some(SimpleValue::Number(value)))
+}
+
+fn floor(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    Ok(Some(SimpleValue::Number(whole(
+        value.try_as_number()?,
+        f64::floor,
+    )?)))
+}
+
+fn ceil(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    Ok(Some(SimpleValue::Number(whole(
+        value.try_as_number()?,
+        f64::ceil,
+    )?)))
+}
+
+fn extreme(args: Args, keep: fn(std::cmp::Ordering) -> bool) -> Result<Option<SimpleValue>> {
+    let mut value = None::<Number>;
+
+    for arg in args.iter().flatten() {
+        let arg = arg.try_as_number()?;
+
+        if value.is_none_or(|v| keep(arg.total_cmp(&v))) {
+            value = Some(arg);
+        }
+    }
+
+    Ok(value.map(SimpleValue::Number))
+}
+
+fn min(args: Args) -> Result<Option<SimpleValue>> {
+    extreme(args, |v| v.is_lt())
+}
+
+fn max(args: Args) -> Result<Option<SimpleValue>> {
+    extreme(args, |v| v.is_gt())
+}
+
+fn now(_: Args) -> Result<Option<SimpleValue>> {
+    Ok(Some(SimpleValue::DateTime(OffsetDateTime::now_utc())))
+}
+
+fn today(_: Args) -> Result<Option<SimpleValue>> {
+    Ok(Some(SimpleValue::Date(OffsetDateTime::now_utc().date())))
+}
+
+fn date_part(args: Args, func: fn(Date) -> i64) -> Result<Option<SimpleValue>> {
+    required!(args, [value]);
+
+    let date = as_date_time(value)?.date();
+
+    Ok(Some(SimpleValue::Number(Number::Integer(func(date)))))
+}
+
+fn year(args: Args) -> Result<Option<SimpleValue>> {
+    date_part(args, |v| v.year() as i64)
+}
+
+fn month(args: Args) -> Result<Option<SimpleValue>> {
+    date_part(args, |v| u8::from(v.month()) as i64)
+}
+
+fn day(args: Args) -> Result<Option<SimpleValue>> {
+    date_part(args, |v| v.day() as i64)
+}
+
+/// Dates stay Dates, so adding hours or less to one drops the time.
+fn date_add(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [value, amount, unit]);
+
+    let unit = unit.any_as_text()?.parse::<DateUnit>()?;
+
+    let amount = match amount.try_as_number()? {
+        Number::Float(_) => {
+            return Err(GlobalError::InvalidExpression(String::from(
+                "date_add takes a whole number of units",
+            )));
+        }
+        v => v.convert_i64(),
+    };
+
+    let duration = amount
+        .checked_mul(unit.seconds())
+        .map(Duration::seconds)
+        .ok_or_else(overflow)?;
+
+    let value = match value {
+        SimpleValue::Date(v) => SimpleValue::Date(v.checked_add(duration).ok_or_else(overflow)?),
+        v => SimpleValue::DateTime(
+            as_date_time(v)?
+                .checked_add(duration)
+                .ok_or_else(overflow)?,
+        ),
+    };
+
+    Ok(Some(value))
+}
+
+/// Negative if `end` is earlier. Dates are at midnight UTC.
+fn date_diff(args: Args) -> Result<Option<SimpleValue>> {
+    required!(args, [start, end, unit]);
+
+    let unit = unit.any_as_text()?.parse::<DateUnit>()?;
+    let seconds = (as_date_time(end)? - as_date_time(start)?).whole_seconds();
+
+    Ok(Some(SimpleValue::Number(Number::Integer(
+        seconds / unit.seconds(),
+    ))))
+}
+
+fn coalesce(args: Args) -> Result<Option<SimpleValue>> {
+    Ok(args.iter().flatten().next().cloned())
+}
+
+fn as_date_time(value: &SimpleValue) -> Result<OffsetDateTime> {
+    match value {
+        SimpleValue::DateTime(v) => Ok(*v),
+        SimpleValue::Date(v) => Ok(v.midnight().assume_utc()),
+        SimpleValue::Text(v) => match OffsetDateTime::parse(v, &Rfc3339) {
+            Ok(v) => Ok(v),
+            Err(_) => Ok(Date::parse(
+                v,
+                time::macros::format_description!("[year]-[month]-[day]"),
+            )?
+            .midnight()
+            .assume_utc()),
+        },
+        v => Err(GlobalError::type_mismatch("DateTime", v.type_name())),
+    }
+}
+
+fn overflow() -> GlobalError {
+    GlobalError::InvalidExpression(String::from("The result is out of range"))
+}
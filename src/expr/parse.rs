@@ -0,0 +1,351 @@
+//! The text form of expressions.
+//!
+//! From loosest to tightest binding: `||`, `&&`, `!`, comparisons (`==`, `!=`, `<`, `<=`, `>`,
+//! `>=`), `+` `-`, `*` `/`, unary `-`. `and`, `or` and `not` can be used instead of the symbols.
+
+use super::Expression;
+use crate::{
+    error::{GlobalError, Result},
+    filter::FilterConditionType,
+    schema::path::FieldPath,
+    value::{Number, SimpleValue},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Number),
+    Text(String),
+    /// A function name, keyword or unquoted field.
+    Ident(String),
+    /// A field between backticks.
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+/// How deeply groups, calls, `!` and unary `-` can be nested, so parsing can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+const SYMBOLS: [&str; 15] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "(", ")",
+];
+
+pub(super) fn parse(value: &str) -> Result<Expression> {
+    let mut parser = Parser {
+        tokens: tokenize(value)?,
+        pos: 0,
+        depth: 0,
+    };
+
+    let expression = parser.or()?;
+
+    match parser.tokens.get(parser.pos) {
+        None => Ok(expression),
+        Some(token) => Err(invalid(format!("Unexpected {token:?}"))),
+    }
+}
+
+fn invalid(reason: String) -> GlobalError {
+    GlobalError::InvalidExpression(reason)
+}
+
+fn tokenize(value: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = value.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == ',' {
+            chars.next();
+            tokens.push(Token::Symbol(","));
+        } else if c == '"' || c == '\'' || c == '`' {
+            chars.next();
+
+            let mut text = String::new();
+
+            loop {
+                match chars.next() {
+                    Some((_, v)) if v == c => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, 'n')) => text.push('\n'),
+                        Some((_, 't')) => text.push('\t'),
+                        Some((_, v)) => text.push(v),
+                        None => return Err(invalid(String::from("Unclosed quote"))),
+                    },
+                    Some((_, v)) => text.push(v),
+                    None => return Err(invalid(String::from("Unclosed quote"))),
+                }
+            }
+
+            tokens.push(if c == '`' {
+                Token::Quoted(text)
+            } else {
+                Token::Text(text)
+            });
+        } else if c.is_ascii_digit() {
+            let mut end = start;
+
+            while let Some(&(i, v)) = chars.peek()
+                && (v.is_ascii_digit() || v == '.')
+            {
+                end = i + v.len_utf8();
+                chars.next();
+            }
+
+            let number = &value[start..end];
+
+            tokens.push(Token::Number(if let Ok(v) = number.parse::<i64>() {
+                Number::Integer(v)
+            } else if let Ok(v) = number.parse::<u64>() {
+                Number::UInteger(v)
+            } else {
+                Number::Float(
+                    number
+                        .parse()
+                        .map_err(|_| invalid(format!("Invalid number {number}")))?,
+                )
+            }));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+
+            // Paths into a field are part of the name, eg. `items[0].price`.
+            while let Some(&(i, v)) = chars.peek()
+                && (v.is_alphanumeric() || matches!(v, '_' | '.' | '[' | ']'))
+            {
+                end = i + v.len_utf8();
+                chars.next();
+            }
+
+            tokens.push(Token::Ident(value[start..end].to_string()));
+        } else {
+            let rest = &value[start..];
+
+            let symbol = SYMBOLS
+                .into_iter()
+                .find(|v| rest.starts_with(v))
+                .ok_or_else(|| invalid(format!("Unexpected {c} at {start}")))?;
+
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+
+            tokens.push(Token::Symbol(symbol));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// Parse something nested one level deeper.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth == MAX_DEPTH {
+            return Err(invalid(format!("Nested more than {MAX_DEPTH} levels deep")));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume the next token if it's one of the symbols or keywords.
+    fn eat(&mut self, options: &[&str]) -> Option<&'static str> {
+        let found = match self.peek()? {
+            Token::Symbol(v) => options.iter().find(|o| *o == v).map(|_| *v),
+            Token::Ident(v) => match v.as_str() {
+                "and" => Some("&&"),
+                "or" => Some("||"),
+                "not" => Some("!"),
+                _ => None,
+            }
+            .filter(|v| options.contains(v)),
+            _ => None,
+        }?;
+
+        self.pos += 1;
+
+        Some(found)
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<()> {
+        match self.eat(&[symbol]) {
+            Some(_) => Ok(()),
+            None => Err(invalid(match self.peek() {
+                Some(v) => format!("Expected {symbol}, found {v:?}"),
+                None => format!("Expected {symbol}"),
+            })),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expression> {
+        let mut conditions = vec![self.and()?];
+
+        while self.eat(&["||"]).is_some() {
+            conditions.push(self.and()?);
+        }
+
+        Ok(single_or(conditions, Expression::Or))
+    }
+
+    fn and(&mut self) -> Result<Expression> {
+        let mut conditions = vec![self.not()?];
+
+        while self.eat(&["&&"]).is_some() {
+            conditions.push(self.not()?);
+        }
+
+        Ok(single_or(conditions, Expression::And))
+    }
+
+    fn not(&mut self) -> Result<Expression> {
+        if self.eat(&["!"]).is_some() {
+            return Ok(Expression::Not(Box::new(self.nested(Self::not)?)));
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expression> {
+        let left = self.additive()?;
+
+        let op = match self.eat(&["==", "!=", "<=", ">=", "<", ">"]) {
+            Some("==") => FilterConditionType::Eq,
+            Some("!=") => FilterConditionType::Neq,
+            Some("<=") => FilterConditionType::Lte,
+            Some(">=") => FilterConditionType::Gte,
+            Some("<") => FilterConditionType::Lt,
+            Some(">") => FilterConditionType::Gt,
+            _ => return Ok(left),
+        };
+
+        Ok(Expression::compare(op, left, self.additive()?))
+    }
+
+    fn additive(&mut self) -> Result<Expression> {
+        let mut left = self.multiplicative()?;
+
+        while let Some(op) = self.eat(&["+", "-"]) {
+            let right = Box::new(self.multiplicative()?);
+
+            left = match op {
+                "+" => Expression::Add(Box::new(left), right),
+                _ => Expression::Sub(Box::new(left), right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expression> {
+        let mut left = self.unary()?;
+
+        while let Some(op) = self.eat(&["*", "/"]) {
+            let right = Box::new(self.unary()?);
+
+            left = match op {
+                "*" => Expression::Mul(Box::new(left), right),
+                _ => Expression::Div(Box::new(left), right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        if self.eat(&["-"]).is_none() {
+            return self.primary();
+        }
+
+        Ok(match self.nested(Self::unary)? {
+            Expression::Literal(SimpleValue::Number(Number::Integer(v))) => {
+                Expression::literal(Number::Integer(-v))
+            }
+            Expression::Literal(SimpleValue::Number(Number::Float(v))) => {
+                Expression::literal(Number::Float(-v))
+            }
+            v => Expression::Sub(
+                Box::new(Expression::literal(Number::Integer(0))),
+                Box::new(v),
+            ),
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expression> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(invalid(String::from("Unexpected end")));
+        };
+
+        self.pos += 1;
+
+        Ok(match token {
+            Token::Number(v) => Expression::literal(v),
+            Token::Text(v) => Expression::literal(v),
+            Token::Quoted(v) => Expression::path(FieldPath::parse(&v)?),
+            Token::Symbol("(") => {
+                let expression = self.nested(Self::or)?;
+                self.expect(")")?;
+                expression
+            }
+            Token::Ident(name) => match name.as_str() {
+                "true" => Expression::literal(true),
+                "false" => Expression::literal(false),
+                _ if self.eat(&["("]).is_some() => self.call(name)?,
+                _ => Expression::path(FieldPath::parse(&name)?),
+            },
+            Token::Symbol(v) => return Err(invalid(format!("Unexpected {v}"))),
+        })
+    }
+
+    fn call(&mut self, name: String) -> Result<Expression> {
+        let mut args = Vec::new();
+
+        if self.eat(&[")"]).is_none() {
+            loop {
+                args.push(self.nested(Self::or)?);
+
+                if self.eat(&[","]).is_none() {
+                    break;
+                }
+            }
+
+            self.expect(")")?;
+        }
+
+        if name != "if" {
+            return Ok(Expression::call(name, args));
+        }
+
+        let Ok([condition, then, otherwise]) = <[Expression; 3]>::try_from(args) else {
+            return Err(invalid(String::from("if takes 3 arguments")));
+        };
+
+        Ok(Expression::If {
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        })
+    }
+}
+
+fn single_or(
+    mut conditions: Vec<Expression>,
+    group: fn(Vec<Expression>) -> Expression,
+) -> Expression {
+    if conditions.len() == 1 {
+        conditions.remove(0)
+    } else {
+        group(conditions)
+    }
+}
@@ -1,17 +1,139 @@
-use std::fmt::{Display, Formatter};
+#[cfg(feature = "sqlx")]
+pub mod sql;
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
 
 use serde::{Deserialize, Serialize};
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, Time, format_description::well_known::Rfc3339,
+    macros::format_description,
+};
 
-use crate::value::Number;
+use crate::{
+    error::{GlobalError, Result},
+    geo::GeoPoint,
+    response::CmsRowResponse,
+    schema::{SchematicFieldKey, path::FieldPath},
+    value::{Number, SimpleValue},
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct Filter {
     pub name: String,
     pub cond: FilterConditionType,
     pub value: FilterValue,
+    /// The meters around the value a `near` filter matches within.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+    /// Whether the name is a [`FieldPath`] into an Object or Array field, eg. `address.city`.
+    /// Otherwise it's the name of a field, even if it contains `.` or `[`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_path: bool,
+}
+
+impl Filter {
+    pub fn new<N: Into<String>, V: Into<FilterValue>>(
+        name: N,
+        cond: FilterConditionType,
+        value: V,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            cond,
+            value: value.into(),
+            radius: None,
+            is_path: false,
+        }
+    }
+
+    /// A filter on a value inside an Object or Array field.
+    pub fn path<V: Into<FilterValue>>(
+        path: FieldPath,
+        cond: FilterConditionType,
+        value: V,
+    ) -> Self {
+        Self {
+            name: path.to_string(),
+            cond,
+            value: value.into(),
+            radius: None,
+            is_path: true,
+        }
+    }
+
+    /// Rows with a Location within `radius` meters of the center.
+    pub fn near<N: Into<String>>(name: N, center: GeoPoint, radius: f64) -> Self {
+        Self {
+            name: name.into(),
+            cond: FilterConditionType::Near,
+            value: FilterValue::Point(center),
+            radius: Some(radius),
+            is_path: false,
+        }
+    }
+
+    /// The field, or the path into it, the filter is on.
+    pub fn field_path(&self) -> Result<FieldPath> {
+        if self.is_path {
+            FieldPath::parse(&self.name)
+        } else {
+            Ok(FieldPath::from(SchematicFieldKey::from(self.name.as_str())))
+        }
+    }
+
+    /// Check if the row matches the filter.
+    ///
+    /// Applies the same rules the server does so it can be used client-side.
+    pub fn matches(&self, row: &CmsRowResponse) -> Result<bool> {
+        self.evaluate(&row.fields)
+    }
+
+    /// Check if the fields match the filter.
+    ///
+    /// Filters made with [`Filter::path`] look inside Object and Array fields.
+    pub fn evaluate(&self, fields: &HashMap<SchematicFieldKey, SimpleValue>) -> Result<bool> {
+        let nested;
+
+        let value = if self.is_path {
+            nested = FieldPath::parse(&self.name)?.get(fields);
+            nested.as_ref()
+        } else {
+            fields.get(self.name.as_str())
+        };
+
+        if self.cond == FilterConditionType::Near {
+            return self.is_near(value);
+        }
+
+        self.cond.test(value, &self.value)
+    }
+
+    fn is_near(&self, value: Option<&SimpleValue>) -> Result<bool> {
+        let (FilterValue::Point(center), Some(radius)) = (&self.value, self.radius) else {
+            return Err(GlobalError::InvalidFilter(String::from(
+                "Near requires a Point and a radius",
+            )));
+        };
+
+        if !radius.is_finite() || radius < 0.0 {
+            return Err(GlobalError::InvalidFilter(format!(
+                "Near requires a radius of 0 or more, got {radius}"
+            )));
+        }
+
+        Ok(value
+            .is_some_and(|v| GeoPoint::from_value(v).is_ok_and(|v| v.is_within(center, radius))))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "lowercase")]
 pub enum FilterConditionType {
     Eq,
@@ -23,15 +145,77 @@ pub enum FilterConditionType {
     Lte,
     Lt,
     Between,
+    /// A Location within the filter's `radius` meters of its value, a Point.
+    Near,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(untagged)]
 pub enum FilterValue {
     Number(Number),
     Text(String),
     IdList(Vec<String>),
     Range((Number, Number)),
+    Point(GeoPoint),
+}
+
+impl FilterConditionType {
+    /// The name used on the wire, eg. `gte`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Neq => "neq",
+            Self::Cont => "cont",
+            Self::Dnc => "dnc",
+            Self::Gte => "gte",
+            Self::Gt => "gt",
+            Self::Lte => "lte",
+            Self::Lt => "lt",
+            Self::Between => "between",
+            Self::Near => "near",
+        }
+    }
+
+    /// Check a value against the filter's value, None being a field without a value.
+    ///
+    /// A missing value only matches `neq` and `dnc`.
+    pub fn test(&self, value: Option<&SimpleValue>, against: &FilterValue) -> Result<bool> {
+        let Some(value) = value else {
+            return Ok(matches!(self, Self::Neq | Self::Dnc));
+        };
+
+        Ok(match self {
+            Self::Eq => is_equal(value, against),
+            Self::Neq => !is_equal(value, against),
+            Self::Cont => contains(value, against),
+            Self::Dnc => !contains(value, against),
+            Self::Gte => compare(value, against)?.is_some_and(|v| v.is_ge()),
+            Self::Gt => compare(value, against)?.is_some_and(|v| v.is_gt()),
+            Self::Lte => compare(value, against)?.is_some_and(|v| v.is_le()),
+            Self::Lt => compare(value, against)?.is_some_and(|v| v.is_lt()),
+            Self::Between => {
+                let FilterValue::Range((start, end)) = against else {
+                    return Err(GlobalError::InvalidFilter(String::from(
+                        "Between requires a Range",
+                    )));
+                };
+
+                let value = match value {
+                    SimpleValue::Number(v) => v.convert_f64(),
+                    SimpleValue::DateTime(v) => v.unix_timestamp() as f64,
+                    _ => return Ok(false),
+                };
+
+                start.convert_f64() <= value && value <= end.convert_f64()
+            }
+            Self::Near => {
+                return Err(GlobalError::InvalidFilter(String::from(
+                    "Near requires the radius of its Filter, use Filter::evaluate",
+                )));
+            }
+        })
+    }
 }
 
 impl FilterValue {
@@ -40,6 +224,73 @@ impl FilterValue {
     }
 }
 
+impl From<Number> for FilterValue {
+    fn from(value: Number) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<i32> for FilterValue {
+    fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<i64> for FilterValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<f64> for FilterValue {
+    fn from(value: f64) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<Vec<String>> for FilterValue {
+    fn from(value: Vec<String>) -> Self {
+        Self::IdList(value)
+    }
+}
+
+impl From<(Number, Number)> for FilterValue {
+    fn from(value: (Number, Number)) -> Self {
+        Self::Range(value)
+    }
+}
+
+impl From<GeoPoint> for FilterValue {
+    fn from(value: GeoPoint) -> Self {
+        Self::Point(value)
+    }
+}
+
+/// Lists become a ListString, Ranges a ListNumber of the start and end and Points an Object.
+impl From<FilterValue> for SimpleValue {
+    fn from(value: FilterValue) -> Self {
+        match value {
+            FilterValue::Number(v) => Self::Number(v),
+            FilterValue::Text(v) => Self::Text(v),
+            FilterValue::IdList(v) => Self::ListString(v),
+            FilterValue::Range((start, end)) => Self::ListNumber(vec![start, end]),
+            FilterValue::Point(v) => Self::from(v),
+        }
+    }
+}
+
 impl Display for FilterValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -47,6 +298,213 @@ impl Display for FilterValue {
             FilterValue::Number(n) => write!(f, "{n}"),
             FilterValue::IdList(ids) => write!(f, "{}", ids.join(",")),
             FilterValue::Range((start, end)) => write!(f, "{start}-{end}"),
+            FilterValue::Point(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// A filter expression with nested AND/OR/NOT groups.
+///
+/// Serialized as the filter itself, `{ "and": [..] }`, `{ "or": [..] }` or `{ "not": .. }`.
+/// A flat list of filters is also accepted and treated as an implicit AND.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "FilterExprRepr", into = "FilterExprRepr")]
+pub enum FilterExpr {
+    Condition(Filter),
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+impl FilterExpr {
+    /// Convert the current flat form into an expression.
+    pub fn from_flat(filters: Vec<Filter>) -> Self {
+        Self::And(filters.into_iter().map(Self::Condition).collect())
+    }
+
+    /// Check if the row matches the expression.
+    pub fn matches(&self, row: &CmsRowResponse) -> Result<bool> {
+        self.evaluate(&row.fields)
+    }
+
+    /// Check if the fields match the expression.
+    ///
+    /// An empty AND matches everything and an empty OR matches nothing.
+    pub fn evaluate(&self, fields: &HashMap<SchematicFieldKey, SimpleValue>) -> Result<bool> {
+        Ok(match self {
+            Self::Condition(filter) => filter.evaluate(fields)?,
+            Self::And(exprs) => {
+                for expr in exprs {
+                    if !expr.evaluate(fields)? {
+                        return Ok(false);
+                    }
+                }
+
+                true
+            }
+            Self::Or(exprs) => {
+                for expr in exprs {
+                    if expr.evaluate(fields)? {
+                        return Ok(true);
+                    }
+                }
+
+                false
+            }
+            Self::Not(expr) => !expr.evaluate(fields)?,
+        })
+    }
+
+    /// Iterate over every condition in the expression.
+    pub fn conditions(&self) -> Vec<&Filter> {
+        match self {
+            Self::Condition(filter) => vec![filter],
+            Self::And(exprs) | Self::Or(exprs) => {
+                exprs.iter().flat_map(|v| v.conditions()).collect()
+            }
+            Self::Not(expr) => expr.conditions(),
+        }
+    }
+}
+
+impl From<Filter> for FilterExpr {
+    fn from(value: Filter) -> Self {
+        Self::Condition(value)
+    }
+}
+
+impl From<Vec<Filter>> for FilterExpr {
+    fn from(value: Vec<Filter>) -> Self {
+        Self::from_flat(value)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum FilterExprRepr {
+    Condition(Filter),
+    Group(FilterGroup),
+    Flat(Vec<FilterExpr>),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FilterGroup {
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+impl From<FilterExprRepr> for FilterExpr {
+    fn from(value: FilterExprRepr) -> Self {
+        match value {
+            FilterExprRepr::Condition(v) => Self::Condition(v),
+            FilterExprRepr::Group(FilterGroup::And(v)) | FilterExprRepr::Flat(v) => Self::And(v),
+            FilterExprRepr::Group(FilterGroup::Or(v)) => Self::Or(v),
+            FilterExprRepr::Group(FilterGroup::Not(v)) => Self::Not(v),
+        }
+    }
+}
+
+impl From<FilterExpr> for FilterExprRepr {
+    fn from(value: FilterExpr) -> Self {
+        match value {
+            FilterExpr::Condition(v) => Self::Condition(v),
+            FilterExpr::And(v) => Self::Group(FilterGroup::And(v)),
+            FilterExpr::Or(v) => Self::Group(FilterGroup::Or(v)),
+            FilterExpr::Not(v) => Self::Group(FilterGroup::Not(v)),
+        }
+    }
+}
+
+fn is_equal(value: &SimpleValue, filter: &FilterValue) -> bool {
+    match (value, filter) {
+        (SimpleValue::Number(a), FilterValue::Number(b)) => a.value_eq(b),
+        (SimpleValue::ListString(list), FilterValue::Text(b)) => list.iter().any(|v| v == b),
+        (value, FilterValue::IdList(ids)) => value.any_as_text().is_ok_and(|v| ids.contains(&v)),
+        (value, FilterValue::Text(b)) => match compare_temporal(value, b) {
+            Some(ord) => ord.is_eq(),
+            None => value.any_as_text().is_ok_and(|v| v == *b),
+        },
+        _ => false,
+    }
+}
+
+fn contains(value: &SimpleValue, filter: &FilterValue) -> bool {
+    match (value, filter) {
+        (SimpleValue::ListString(list), FilterValue::Text(b)) => list.iter().any(|v| v == b),
+        (SimpleValue::ListString(list), FilterValue::IdList(ids)) => {
+            list.iter().any(|v| ids.contains(v))
+        }
+        (SimpleValue::ListNumber(list), FilterValue::Number(b)) => {
+            list.iter().any(|v| v.value_eq(b))
+        }
+        (value, FilterValue::Text(b)) => value
+            .any_as_text()
+            .is_ok_and(|v| v.to_lowercase().contains(&b.to_lowercase())),
+        _ => false,
+    }
+}
+
+fn compare(value: &SimpleValue, filter: &FilterValue) -> Result<Option<Ordering>> {
+    Ok(match (value, filter) {
+        (SimpleValue::Number(a), FilterValue::Number(b)) => Some(a.total_cmp(b)),
+        (value, FilterValue::Text(b)) => compare_temporal(value, b)
+            .or_else(|| value.any_as_text().ok().map(|v| v.as_str().cmp(b))),
+        (_, FilterValue::IdList(_) | FilterValue::Range(_)) => {
+            return Err(GlobalError::InvalidFilter(String::from(
+                "Unable to compare against a List or Range",
+            )));
+        }
+        _ => None,
+    })
+}
+
+/// Compare a date/time value against the text of a filter.
+///
+/// Returns None if the value isn't temporal or the text can't be parsed into the same type.
+fn compare_temporal(value: &SimpleValue, text: &str) -> Option<Ordering> {
+    let date_format = format_description!("[year]-[month]-[day]");
+
+    match value {
+        SimpleValue::DateTime(v) => {
+            let other = if let Ok(v) = OffsetDateTime::parse(text, &Rfc3339) {
+                v
+            } else if let Ok(v) = PrimitiveDateTime::parse(
+                text,
+                format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+            ) {
+                v.assume_utc()
+            } else if let Ok(v) = PrimitiveDateTime::parse(
+                text,
+                format_description!("[year]-[month]-[day]T[hour]:[minute]"),
+            ) {
+                v.assume_utc()
+            } else {
+                Date::parse(text, date_format).ok()?.midnight().assume_utc()
+            };
+
+            Some(v.cmp(&other))
+        }
+        SimpleValue::Date(v) => {
+            let other = if let Ok(v) = Date::parse(text, date_format) {
+                v
+            } else {
+                OffsetDateTime::parse(text, &Rfc3339).ok()?.date()
+            };
+
+            Some(v.cmp(&other))
+        }
+        SimpleValue::Time(v) => {
+            let other =
+                if let Ok(v) = Time::parse(text, format_description!("[hour]:[minute]:[second]")) {
+                    v
+                } else {
+                    Time::parse(text, format_description!("[hour]:[minute]")).ok()?
+                };
+
+            Some(v.cmp(&other))
         }
+        _ => None,
     }
 }
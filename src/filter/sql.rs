@@ -0,0 +1,507 @@
+//! Translation of filters into parameterized SQL WHERE clauses.
+
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, Time, format_description::well_known::Rfc3339,
+    macros::format_description,
+};
+use uuid::Uuid;
+
+use super::{Filter, FilterConditionType, FilterExpr, FilterValue};
+use crate::{
+    error::{GlobalError, Result},
+    schema::{
+        SchemaFieldMap, SchematicFieldKey, SchematicFieldType,
+        path::{FieldPath, PathSegment},
+    },
+    value::Number,
+};
+
+/// How bind parameters are written into the clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `$1`, `$2`, .. (Postgres)
+    Dollar,
+    /// `?` (SQLite)
+    ///
+    /// Not for MySQL: identifiers are quoted with `"`, which MySQL reads as a string unless
+    /// `ANSI_QUOTES` is on, and it reads the `\` of `ESCAPE '\'` as an escape itself.
+    Question,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlBind {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    DateTime(OffsetDateTime),
+    Date(Date),
+    Time(Time),
+    Uuid(Uuid),
+}
+
+/// A WHERE clause (without the `WHERE` keyword) and the values to bind, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlWhere {
+    pub clause: String,
+    pub binds: Vec<SqlBind>,
+}
+
+/// Converts filters into SQL using the field types of a schema.
+///
+/// Only fields which exist in the schema (or are system fields) can be filtered on, which also
+/// ensures no user input is written into the clause as an identifier.
+///
+/// The clause matches the same rows as [`FilterExpr::evaluate`]: `cont` and `dnc` ignore case,
+/// and `neq`, `dnc` and `NOT` match rows without a value. Filters `evaluate` never matches, such
+/// as a Number on a Text field, are an error instead.
+///
+/// - Tags, Multi Reference and Multi Document columns have to hold a JSON array. `eq` and `cont`
+///   match rows where the filter's Text is one of the items.
+/// - `between` compares Date & Time columns with the whole seconds since the epoch.
+/// - Boolean columns can only be compared to `true` or `false`.
+/// - `cont` and `dnc` only work on text columns, numbers aren't searched as text.
+///
+/// Filters made with [`Filter::path`] read inside Object, Array and Address columns, which have
+/// to hold JSON. Their values are compared as numbers when the filter's value is a Number or
+/// Range, and as text otherwise.
+pub struct SqlFilterBuilder<'a> {
+    fields: &'a SchemaFieldMap,
+    placeholder: Placeholder,
+    binds: Vec<SqlBind>,
+}
+
+impl<'a> SqlFilterBuilder<'a> {
+    pub fn new(fields: &'a SchemaFieldMap, placeholder: Placeholder) -> Self {
+        Self {
+            fields,
+            placeholder,
+            binds: Vec::new(),
+        }
+    }
+
+    /// Build the clause for a flat list of filters. They're joined with AND.
+    pub fn build_flat(self, filters: &[Filter]) -> Result<SqlWhere> {
+        self.build(&FilterExpr::from_flat(filters.to_vec()))
+    }
+
+    pub fn build(mut self, expr: &FilterExpr) -> Result<SqlWhere> {
+        let clause = self.expr(expr)?;
+
+        Ok(SqlWhere {
+            clause,
+            binds: self.binds,
+        })
+    }
+
+    fn expr(&mut self, expr: &FilterExpr) -> Result<String> {
+        Ok(match expr {
+            FilterExpr::Condition(filter) => self.condition(filter)?,
+            FilterExpr::And(exprs) => self.group(exprs, " AND ", "1 = 1")?,
+            FilterExpr::Or(exprs) => self.group(exprs, " OR ", "1 = 0")?,
+            // Unlike `NOT`, also true when the condition is NULL because a value is missing.
+            FilterExpr::Not(expr) => format!("({}) IS NOT TRUE", self.expr(expr)?),
+        })
+    }
+
+    fn group(&mut self, exprs: &[FilterExpr], sep: &str, empty: &str) -> Result<String> {
+        if exprs.is_empty() {
+            return Ok(empty.to_string());
+        }
+
+        let parts = exprs
+            .iter()
+            .map(|v| self.expr(v))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(format!("({})", parts.join(sep)))
+    }
+
+    fn condition(&mut self, filter: &Filter) -> Result<String> {
+        let path = filter.field_path()?;
+        let mut field_type = self.field_type(path.key())?;
+        let mut column = quote_identifier(path.key().as_str());
+
+        if !path.segments().is_empty() {
+            if !matches!(
+                field_type,
+                SchematicFieldType::Object
+                    | SchematicFieldType::Array
+                    | SchematicFieldType::Address
+            ) {
+                return Err(invalid_filter(format!(
+                    "Unable to filter inside a {} field",
+                    field_type.as_name()
+                )));
+            }
+
+            field_type = match filter.value {
+                FilterValue::Number(_) | FilterValue::Range(_) => SchematicFieldType::Number,
+                _ => SchematicFieldType::Text,
+            };
+
+            column = self.json_path(&column, &path, field_type)?;
+        } else if is_list(field_type) {
+            return self.list_condition(&column, filter);
+        }
+
+        Ok(match (&filter.cond, &filter.value) {
+            (FilterConditionType::Eq | FilterConditionType::Neq, FilterValue::IdList(ids)) => {
+                if ids.is_empty() {
+                    return Ok(String::from(if filter.cond == FilterConditionType::Eq {
+                        "1 = 0"
+                    } else {
+                        "1 = 1"
+                    }));
+                }
+
+                let params = ids
+                    .iter()
+                    .map(|id| {
+                        let bind = text_bind(field_type, id)?;
+                        Ok(self.push(bind))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                if filter.cond == FilterConditionType::Eq {
+                    format!("{column} IN ({})", params.join(", "))
+                } else {
+                    format!(
+                        "({column} NOT IN ({}) OR {column} IS NULL)",
+                        params.join(", ")
+                    )
+                }
+            }
+
+            (FilterConditionType::Near, _) => {
+                return Err(invalid_filter(
+                    "Near can't be translated into SQL, filter the rows with Filter::evaluate",
+                ));
+            }
+
+            (FilterConditionType::Between, FilterValue::Range((start, end))) => {
+                match field_type {
+                    SchematicFieldType::Number => {
+                        let start = self.push(number_bind(field_type, *start)?);
+                        let end = self.push(number_bind(field_type, *end)?);
+
+                        format!("{column} BETWEEN {start} AND {end}")
+                    }
+                    // The whole seconds of the value have to be in the range.
+                    SchematicFieldType::DateTime => {
+                        let start = timestamp(start.convert_f64().ceil())?;
+                        let end = timestamp(end.convert_f64().floor() + 1.0)?;
+
+                        let start = self.push(SqlBind::DateTime(start));
+                        let end = self.push(SqlBind::DateTime(end));
+
+                        format!("({column} >= {start} AND {column} < {end})")
+                    }
+                    v => {
+                        return Err(invalid_filter(format!(
+                            "Between can't be used on a {} field",
+                            v.as_name()
+                        )));
+                    }
+                }
+            }
+            (FilterConditionType::Between, _) => {
+                return Err(invalid_filter("Between requires a Range"));
+            }
+            (_, FilterValue::Range(_)) => {
+                return Err(invalid_filter("A Range can only be used with Between"));
+            }
+            (_, FilterValue::IdList(_)) => {
+                return Err(invalid_filter("A List can only be used with Eq or Neq"));
+            }
+            (_, FilterValue::Point(_)) => {
+                return Err(invalid_filter("A Point can only be used with Near"));
+            }
+
+            (FilterConditionType::Cont | FilterConditionType::Dnc, value) => {
+                if !is_textual(field_type) {
+                    return Err(invalid_filter(format!(
+                        "{} can't be used on a {} field",
+                        filter.cond.as_str(),
+                        field_type.as_name()
+                    )));
+                }
+
+                let param = self.push(SqlBind::Text(format!(
+                    "%{}%",
+                    escape_like(&value.to_string().to_lowercase())
+                )));
+
+                if filter.cond == FilterConditionType::Cont {
+                    format!("LOWER({column}) LIKE {param} ESCAPE '\\'")
+                } else {
+                    format!("(LOWER({column}) NOT LIKE {param} ESCAPE '\\' OR {column} IS NULL)")
+                }
+            }
+
+            (cond, value) => {
+                let op = match cond {
+                    FilterConditionType::Eq => "=",
+                    FilterConditionType::Neq => "<>",
+                    FilterConditionType::Gte => ">=",
+                    FilterConditionType::Gt => ">",
+                    FilterConditionType::Lte => "<=",
+                    FilterConditionType::Lt => "<",
+                    _ => unreachable!(),
+                };
+
+                let bind = match value {
+                    FilterValue::Number(v) => number_bind(field_type, *v)?,
+                    FilterValue::Text(v) => text_bind(field_type, v)?,
+                    _ => unreachable!(),
+                };
+
+                let param = self.push(bind);
+
+                if *cond == FilterConditionType::Neq {
+                    format!("({column} <> {param} OR {column} IS NULL)")
+                } else {
+                    format!("{column} {op} {param}")
+                }
+            }
+        })
+    }
+
+    /// A list column holds a JSON array, a Text matches if it's one of the items.
+    fn list_condition(&mut self, column: &str, filter: &Filter) -> Result<String> {
+        let negate = match filter.cond {
+            FilterConditionType::Eq | FilterConditionType::Cont => false,
+            FilterConditionType::Neq | FilterConditionType::Dnc => true,
+            ref v => {
+                return Err(invalid_filter(format!(
+                    "{} can't be used on a list field",
+                    v.as_str()
+                )));
+            }
+        };
+
+        let FilterValue::Text(value) = &filter.value else {
+            return Err(invalid_filter(
+                "A list field can only be compared to a Text",
+            ));
+        };
+
+        let param = self.push(SqlBind::Text(value.clone()));
+
+        let exists = match self.placeholder {
+            Placeholder::Dollar => format!(
+                "EXISTS (SELECT 1 FROM jsonb_array_elements_text({column}) AS item WHERE item = {param})"
+            ),
+            Placeholder::Question => {
+                format!("EXISTS (SELECT 1 FROM json_each({column}) WHERE value = {param})")
+            }
+        };
+
+        // No items is the same as a missing value, so NULL needs no check.
+        Ok(if negate {
+            format!("NOT {exists}")
+        } else {
+            exists
+        })
+    }
+
+    fn field_type(&self, key: &SchematicFieldKey) -> Result<SchematicFieldType> {
+        if let Some(field) = self.fields.get(key).filter(|f| !f.is_deleted) {
+            return Ok(field.field_type);
+        }
+
+        Ok(match key {
+            SchematicFieldKey::Id | SchematicFieldKey::Owner => SchematicFieldType::Text,
+            SchematicFieldKey::CreatedAt | SchematicFieldKey::UpdatedAt => {
+                SchematicFieldType::DateTime
+            }
+            _ => return Err(GlobalError::UnknownField(key.to_string())),
+        })
+    }
+
+    /// The value at the path inside a JSON column. Postgres reads it as text, so numbers are cast.
+    fn json_path(
+        &self,
+        column: &str,
+        path: &FieldPath,
+        field_type: SchematicFieldType,
+    ) -> Result<String> {
+        // Keys are written into the clause, so only plain names are allowed.
+        if let Some(key) = path.segments().iter().find_map(|v| match v {
+            PathSegment::Key(k) if !k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+                Some(k)
+            }
+            _ => None,
+        }) {
+            return Err(invalid_filter(format!(
+                "The key {key:?} can't be used in SQL, only letters, digits and _ can"
+            )));
+        }
+
+        Ok(match self.placeholder {
+            Placeholder::Dollar => {
+                let segments = path
+                    .segments()
+                    .iter()
+                    .map(|v| match v {
+                        PathSegment::Key(k) => k.clone(),
+                        PathSegment::Index(i) => i.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+
+                if field_type == SchematicFieldType::Number {
+                    format!("(({column} #>> '{{{}}}')::numeric)", segments.join(","))
+                } else {
+                    format!("({column} #>> '{{{}}}')", segments.join(","))
+                }
+            }
+            Placeholder::Question => {
+                let mut json_path = String::from("$");
+
+                for segment in path.segments() {
+                    match segment {
+                        PathSegment::Key(k) => json_path.push_str(&format!(".{k}")),
+                        PathSegment::Index(i) => json_path.push_str(&format!("[{i}]")),
+                    }
+                }
+
+                format!("({column} ->> '{json_path}')")
+            }
+        })
+    }
+
+    fn push(&mut self, bind: SqlBind) -> String {
+        self.binds.push(bind);
+
+        match self.placeholder {
+            Placeholder::Dollar => format!("${}", self.binds.len()),
+            Placeholder::Question => String::from("?"),
+        }
+    }
+}
+
+fn invalid_filter<S: Into<String>>(value: S) -> GlobalError {
+    GlobalError::InvalidFilter(value.into())
+}
+
+fn quote_identifier(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn timestamp(seconds: f64) -> Result<OffsetDateTime> {
+    if !seconds.is_finite() {
+        return Err(invalid_filter(format!("{seconds} isn't a timestamp")));
+    }
+
+    OffsetDateTime::from_unix_timestamp(seconds as i64)
+        .map_err(|_| invalid_filter(format!("{seconds} is out of range for a timestamp")))
+}
+
+/// Only Number fields equal a Number, any other field is compared as text.
+fn number_bind(field_type: SchematicFieldType, value: Number) -> Result<SqlBind> {
+    Ok(match field_type {
+        SchematicFieldType::Number => match value {
+            Number::Float(v) => SqlBind::Float(v),
+            // Above the range of INT8.
+            Number::UInteger(v) if i64::try_from(v).is_err() => SqlBind::Float(v as f64),
+            v => SqlBind::Integer(v.convert_i64()),
+        },
+        v => {
+            return Err(invalid_filter(format!(
+                "Unable to compare a {} field to a Number",
+                v.as_name()
+            )));
+        }
+    })
+}
+
+fn text_bind(field_type: SchematicFieldType, value: &str) -> Result<SqlBind> {
+    Ok(match field_type {
+        SchematicFieldType::Number => {
+            if let Ok(v) = value.parse::<i64>() {
+                SqlBind::Integer(v)
+            } else {
+                SqlBind::Float(value.parse()?)
+            }
+        }
+        // Booleans are compared by their text, so other spellings never match.
+        SchematicFieldType::Boolean => SqlBind::Boolean(match value {
+            "true" => true,
+            "false" => false,
+            v => {
+                return Err(invalid_filter(format!(
+                    "A Boolean field can only be compared to true or false, got {v:?}"
+                )));
+            }
+        }),
+        // The same formats `FilterExpr::evaluate` reads.
+        SchematicFieldType::DateTime => {
+            SqlBind::DateTime(if let Ok(v) = OffsetDateTime::parse(value, &Rfc3339) {
+                v
+            } else if let Ok(v) = PrimitiveDateTime::parse(
+                value,
+                format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+            ) {
+                v.assume_utc()
+            } else if let Ok(v) = PrimitiveDateTime::parse(
+                value,
+                format_description!("[year]-[month]-[day]T[hour]:[minute]"),
+            ) {
+                v.assume_utc()
+            } else {
+                Date::parse(value, format_description!("[year]-[month]-[day]"))?
+                    .midnight()
+                    .assume_utc()
+            })
+        }
+        SchematicFieldType::Date => SqlBind::Date(
+            match Date::parse(value, format_description!("[year]-[month]-[day]")) {
+                Ok(v) => v,
+                Err(_) => OffsetDateTime::parse(value, &Rfc3339)?.date(),
+            },
+        ),
+        SchematicFieldType::Time => SqlBind::Time(
+            match Time::parse(value, format_description!("[hour]:[minute]:[second]")) {
+                Ok(v) => v,
+                Err(_) => Time::parse(value, format_description!("[hour]:[minute]"))?,
+            },
+        ),
+        SchematicFieldType::Reference => SqlBind::Uuid(Uuid::parse_str(value)?),
+        v if is_textual(v) => SqlBind::Text(value.to_string()),
+        v => {
+            return Err(invalid_filter(format!(
+                "Unable to filter on a {} field",
+                v.as_name()
+            )));
+        }
+    })
+}
+
+fn is_textual(field_type: SchematicFieldType) -> bool {
+    matches!(
+        field_type,
+        SchematicFieldType::Text
+            | SchematicFieldType::URL
+            | SchematicFieldType::Email
+            | SchematicFieldType::Phone
+            | SchematicFieldType::Slug
+            | SchematicFieldType::RichContent
+            | SchematicFieldType::RichText
+    )
+}
+
+fn is_list(field_type: SchematicFieldType) -> bool {
+    matches!(
+        field_type,
+        SchematicFieldType::Tags
+            | SchematicFieldType::MultiReference
+            | SchematicFieldType::MultiDocument
+    )
+}
@@ -0,0 +1,207 @@
+//! Forms shown on the website and the submissions they receive.
+//!
+//! Every submission is stored as a row of the collection the form belongs to.
+
+use std::{collections::HashMap, net::IpAddr};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    error::{GlobalError, Result},
+    id::{FormPublicId, MemberPublicId},
+    idempotency::IdempotencyKey,
+    request::CmsBulkInsert,
+    schema::{
+        SchematicFieldKey, SchematicFieldType,
+        constraints::FieldConstraints,
+        validation::{ValidationError, ValidationErrorKind},
+    },
+    uuid::CollectionName,
+    value::SimpleValue,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormDefinition {
+    pub id: FormPublicId,
+    pub name: String,
+    /// Where submissions are stored.
+    pub collection: CollectionName,
+    /// Pages of the form, shown one after another. Single page forms have one step.
+    pub steps: Vec<FormStep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submit_label: Option<String>,
+    /// Shown once the form is submitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_message: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FormStep {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub fields: Vec<FormField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormField {
+    /// The answer key, and the field of the collection it's stored in.
+    pub key: String,
+    pub field_type: SchematicFieldType,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_text: Option<String>,
+    #[serde(default, skip_serializing_if = "FieldConstraints::is_empty")]
+    pub validation: FieldConstraints,
+}
+
+impl FormDefinition {
+    /// Every field of every step, in order.
+    pub fn fields(&self) -> impl Iterator<Item = &FormField> {
+        self.steps.iter().flat_map(|step| &step.fields)
+    }
+
+    pub fn field(&self, key: &str) -> Option<&FormField> {
+        self.fields().find(|field| field.key == key)
+    }
+
+    /// Validate every answer of a submission.
+    ///
+    /// Returns an empty list if the submission is valid.
+    pub fn validate(&self, submission: &FormSubmission) -> Result<Vec<ValidationError>> {
+        let mut errors = validate_fields(self.fields(), &submission.answers)?;
+
+        for key in submission.answers.keys() {
+            if self.field(key).is_none() {
+                errors.push(ValidationError {
+                    field: SchematicFieldKey::from(key.as_str()),
+                    kind: ValidationErrorKind::UnknownField,
+                });
+            }
+        }
+
+        errors.sort_by(|a, b| a.field.as_str().cmp(b.field.as_str()));
+
+        Ok(errors)
+    }
+
+    /// Validate the answers of a single step, eg. before moving on to the next page.
+    ///
+    /// Answers to fields of other steps are ignored.
+    pub fn validate_step(
+        &self,
+        step: usize,
+        answers: &HashMap<String, SimpleValue>,
+    ) -> Result<Vec<ValidationError>> {
+        let step = self
+            .steps
+            .get(step)
+            .ok_or_else(|| GlobalError::ParseError(format!("Form Step {step} doesn't exist")))?;
+
+        validate_fields(step.fields.iter(), answers)
+    }
+}
+
+fn validate_fields<'a>(
+    fields: impl Iterator<Item = &'a FormField>,
+    answers: &HashMap<String, SimpleValue>,
+) -> Result<Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    for field in fields {
+        let kind = match answers.get(&field.key) {
+            None => field
+                .validation
+                .required
+                .then_some(ValidationErrorKind::Required),
+            Some(value) => match value.coerce_to(field.field_type) {
+                Ok(value) => field
+                    .validation
+                    .check(field.field_type, &SimpleValue::from(value))?,
+                Err(e) => Some(ValidationErrorKind::InvalidType {
+                    expected: field.field_type,
+                    reason: e.to_string(),
+                }),
+            },
+        };
+
+        if let Some(kind) = kind {
+            errors.push(ValidationError {
+                field: SchematicFieldKey::from(field.key.as_str()),
+                kind,
+            });
+        }
+    }
+
+    Ok(errors)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormSubmission {
+    pub form_id: FormPublicId,
+    pub answers: HashMap<String, SimpleValue>,
+    /// Never read from the request, so clients can't fill it in.
+    #[serde(skip_deserializing)]
+    pub metadata: SubmissionMetadata,
+    /// Sent again when a submission is retried, so it's only stored once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+/// Filled in by the server, anything sent by the client is dropped when the submission is read.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<IpAddr>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// The page the form was submitted from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referrer: Option<String>,
+    /// The member who was signed in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<MemberPublicId>,
+}
+
+impl FormSubmission {
+    /// The row stored in the collection of the form, with every answer
+    /// [coerced](SimpleValue::coerce_to) into the type of its field. Answers to fields which
+    /// aren't in the form are dropped.
+    ///
+    /// The submission should be [validated](FormDefinition::validate) first.
+    pub fn to_row(&self, form: &FormDefinition) -> Result<HashMap<SchematicFieldKey, SimpleValue>> {
+        if self.form_id != form.id {
+            return Err(GlobalError::ParseError(format!(
+                "Submission is for form {} instead of {}",
+                self.form_id, form.id
+            )));
+        }
+
+        form.fields()
+            .filter_map(|field| Some((field, self.answers.get(&field.key)?)))
+            .map(|(field, value)| {
+                Ok((
+                    SchematicFieldKey::from(field.key.as_str()),
+                    SimpleValue::from(value.coerce_to(field.field_type)?),
+                ))
+            })
+            .collect()
+    }
+
+    /// Insert the submission into the collection of the form.
+    pub fn to_insert(&self, form: &FormDefinition) -> Result<CmsBulkInsert> {
+        Ok(CmsBulkInsert {
+            rows: vec![self.to_row(form)?],
+            schema_version: None,
+            idempotency_key: self.idempotency_key.clone(),
+        })
+    }
+}
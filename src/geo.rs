@@ -0,0 +1,119 @@
+//! Points on the earth, for store-locator style collections.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    value::SimpleValue,
+};
+
+/// The mean radius of the earth, used for [`GeoPoint::distance_to`].
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// A WGS 84 coordinate in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(try_from = "RawGeoPoint")]
+pub struct GeoPoint {
+    /// -90 (south) to 90 (north).
+    pub lat: f64,
+    /// -180 (west) to 180 (east).
+    pub lon: f64,
+}
+
+#[derive(Deserialize)]
+struct RawGeoPoint {
+    lat: f64,
+    lon: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Result<Self> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(GlobalError::ParseError(format!(
+                "Latitude {lat} has to be between -90 and 90"
+            )));
+        }
+
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(GlobalError::ParseError(format!(
+                "Longitude {lon} has to be between -180 and 180"
+            )));
+        }
+
+        Ok(Self { lat, lon })
+    }
+
+    /// Read a point from an object, a text containing a JSON object, or a text like
+    /// `51.5072,-0.1276`.
+    pub fn from_value(value: &SimpleValue) -> Result<Self> {
+        match value {
+            SimpleValue::ObjectUnknown(v) => Ok(serde_json::from_value(v.clone())?),
+            SimpleValue::Text(v) if v.trim_start().starts_with('{') => Ok(serde_json::from_str(v)?),
+            SimpleValue::Text(v) => v.parse(),
+            v => Err(GlobalError::type_mismatch("Location", v.type_name())),
+        }
+    }
+
+    /// The great-circle distance in meters, using the haversine formula.
+    ///
+    /// Treats the earth as a sphere so it can be off by up to 0.5%, plenty for finding what's
+    /// nearby.
+    pub fn distance_to(&self, other: &Self) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon - self.lon).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().min(1.0).asin()
+    }
+
+    /// Whether the point is at most `radius` meters from the center.
+    pub fn is_within(&self, center: &Self, radius: f64) -> bool {
+        self.distance_to(center) <= radius
+    }
+}
+
+impl TryFrom<RawGeoPoint> for GeoPoint {
+    type Error = GlobalError;
+
+    fn try_from(value: RawGeoPoint) -> Result<Self> {
+        Self::new(value.lat, value.lon)
+    }
+}
+
+/// `lat,lon`
+impl Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lon)
+    }
+}
+
+impl FromStr for GeoPoint {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || GlobalError::ParseError(format!("Location {s:?}: expected lat,lon"));
+
+        let (lat, lon) = s.split_once(',').ok_or_else(invalid)?;
+
+        Self::new(
+            lat.trim().parse().map_err(|_| invalid())?,
+            lon.trim().parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+impl From<GeoPoint> for SimpleValue {
+    fn from(value: GeoPoint) -> Self {
+        SimpleValue::ObjectUnknown(
+            serde_json::to_value(value).expect("GeoPoint serializes to JSON"),
+        )
+    }
+}
@@ -0,0 +1,214 @@
+//! Values translated into several languages.
+//!
+//! Text fields marked as [`localized`](crate::schema::SchematicField::localized) store a
+//! [`LocalizedString`] instead of plain text.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    value::SimpleValue,
+};
+
+/// A BCP 47 language tag, eg. `en`, `de-CH` or `zh-Hant-TW`.
+///
+/// Only the common `language[-script][-region][-variant...]` form is accepted. Subtags are
+/// normalized to their usual case, so `EN-us` is stored as `en-US`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LanguageTag(String);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedString {
+    /// Used when there's no value for the requested language. Always has a value.
+    pub default_language: LanguageTag,
+    pub values: HashMap<LanguageTag, String>,
+}
+
+impl LanguageTag {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| GlobalError::ParseError(format!("Language Tag {value}: {reason}"));
+
+        let mut subtags = value.trim().split(['-', '_']);
+        let mut normalized = Vec::new();
+
+        let language = subtags.next().unwrap_or_default();
+
+        if !(2..=3).contains(&language.len()) || !language.bytes().all(|v| v.is_ascii_alphabetic())
+        {
+            return Err(invalid("the language has to be 2 or 3 letters"));
+        }
+
+        normalized.push(language.to_ascii_lowercase());
+
+        for (i, subtag) in subtags.enumerate() {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.bytes().all(|v| v.is_ascii_alphanumeric())
+            {
+                return Err(invalid("a subtag has to be 1 to 8 letters or digits"));
+            }
+
+            let is_alpha = subtag.bytes().all(|v| v.is_ascii_alphabetic());
+            let is_digit = subtag.bytes().all(|v| v.is_ascii_digit());
+
+            normalized.push(match subtag.len() {
+                // Script
+                4 if i == 0 && is_alpha => {
+                    let (first, rest) = subtag.split_at(1);
+                    format!(
+                        "{}{}",
+                        first.to_ascii_uppercase(),
+                        rest.to_ascii_lowercase()
+                    )
+                }
+                // Region
+                2 if is_alpha => subtag.to_ascii_uppercase(),
+                3 if is_digit => subtag.to_string(),
+                _ => subtag.to_ascii_lowercase(),
+            });
+        }
+
+        Ok(Self(normalized.join("-")))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language, eg. `de` for `de-CH`.
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+
+    /// The tag and the tags it falls back to, most specific first.
+    ///
+    /// `zh-Hant-TW` gives `zh-Hant-TW`, `zh-Hant` and `zh`.
+    pub fn fallbacks(&self) -> impl Iterator<Item = &str> {
+        let tag = self.0.as_str();
+
+        std::iter::successors(Some(tag), |v| v.rsplit_once('-').map(|(v, _)| v))
+    }
+}
+
+impl Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for LanguageTag {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<LanguageTag> for String {
+    fn from(value: LanguageTag) -> Self {
+        value.0
+    }
+}
+
+impl LocalizedString {
+    pub fn new<S: Into<String>>(default_language: LanguageTag, value: S) -> Self {
+        Self {
+            values: HashMap::from([(default_language.clone(), value.into())]),
+            default_language,
+        }
+    }
+
+    /// Set the value of a language, replacing the previous one.
+    pub fn set<S: Into<String>>(&mut self, language: LanguageTag, value: S) {
+        self.values.insert(language, value.into());
+    }
+
+    /// The value of exactly this language.
+    pub fn get(&self, language: &LanguageTag) -> Option<&str> {
+        self.values.get(language).map(String::as_str)
+    }
+
+    pub fn default_value(&self) -> &str {
+        self.get(&self.default_language).unwrap_or_default()
+    }
+
+    /// The value for the language, falling back to less specific tags (`de-CH` to `de`) and then
+    /// to the default language.
+    pub fn resolve(&self, language: &LanguageTag) -> &str {
+        self.resolve_preferred(std::slice::from_ref(language))
+    }
+
+    /// The value for the first language which has one, eg. from an `Accept-Language` header,
+    /// falling back to the default language.
+    pub fn resolve_preferred(&self, languages: &[LanguageTag]) -> &str {
+        languages
+            .iter()
+            .flat_map(|v| v.fallbacks())
+            .find_map(|tag| {
+                self.values
+                    .iter()
+                    .find(|(k, _)| k.as_str() == tag)
+                    .map(|(_, v)| v.as_str())
+            })
+            .unwrap_or_else(|| self.default_value())
+    }
+
+    /// Languages without a value, out of the ones a site is translated into.
+    pub fn missing<'a>(&self, languages: &'a [LanguageTag]) -> Vec<&'a LanguageTag> {
+        languages
+            .iter()
+            .filter(|v| self.values.get(*v).is_none_or(|v| v.is_empty()))
+            .collect()
+    }
+
+    /// Read the value of a localized field.
+    ///
+    /// Plain text, eg. stored before the field was localized, becomes the value of the default
+    /// language.
+    pub fn from_value(value: &SimpleValue, default_language: &LanguageTag) -> Result<Self> {
+        match value {
+            SimpleValue::Text(v) => Ok(Self::new(default_language.clone(), v.as_str())),
+            SimpleValue::ObjectUnknown(v) => Self::from_json(v),
+            v => Err(GlobalError::type_mismatch("Localized Text", v.type_name())),
+        }
+    }
+
+    /// Read a value stored as JSON, checking the default language has a value.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self> {
+        let value: Self = serde_json::from_value(value.clone())?;
+
+        if !value.values.contains_key(&value.default_language) {
+            return Err(GlobalError::ParseError(format!(
+                "Localized Text has no value for its default language {}",
+                value.default_language
+            )));
+        }
+
+        Ok(value)
+    }
+}
+
+impl From<LocalizedString> for SimpleValue {
+    fn from(value: LocalizedString) -> Self {
+        SimpleValue::ObjectUnknown(
+            serde_json::to_value(value).expect("LocalizedString serializes to JSON"),
+        )
+    }
+}
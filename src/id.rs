@@ -7,11 +7,14 @@ use std::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use uuid::{Error as UuidError, Uuid};
 
+use crate::error::{GlobalError, Result};
+
 #[macro_export]
 macro_rules! create_uuid {
     ($($name:ident, $type_of:ident),+) => {$(
         #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
         #[cfg_attr(feature = "sqlx", derive(::sqlx::Type), sqlx(transparent))]
+        #[cfg_attr(feature = "ts", derive(::ts_rs::TS), ts(type = "string"))]
         #[repr(transparent)]
         pub struct $name($type_of);
 
@@ -27,6 +30,25 @@ macro_rules! create_uuid {
             pub fn is_none(self) -> bool {
                 self.0 == Self::none().0
             }
+
+            /// The UUID as 22 base62 characters, eg. for URLs.
+            pub fn to_short_string(&self) -> String {
+                $crate::id::encode_short(self.0.as_u128())
+            }
+
+            pub fn from_short_string(value: &str) -> $crate::error::Result<Self> {
+                $crate::id::decode_short(value).map(|v| Self($type_of::from_u128(v)))
+            }
+        }
+
+        impl $crate::id::ShortId for $name {
+            fn to_short_string(&self) -> String {
+                self.to_short_string()
+            }
+
+            fn from_short_string(value: &str) -> $crate::error::Result<Self> {
+                Self::from_short_string(value)
+            }
         }
 
         impl<'de> Deserialize<'de> for $name {
@@ -103,6 +125,216 @@ macro_rules! create_uuid {
     )+};
 }
 
+/// Like [`create_uuid!`] but displayed and serialized with a prefix naming the entity,
+/// eg. `form_0190a5b87c4e7d219c3a2f1e5d6c7b8a`, so IDs in URLs and logs can be told apart.
+///
+/// Parsing also accepts a plain UUID. A different prefix is rejected.
+#[macro_export]
+macro_rules! create_prefixed_id {
+    ($($name:ident, $prefix:literal),+) => {$(
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "sqlx", derive(::sqlx::Type), sqlx(transparent))]
+        #[cfg_attr(feature = "ts", derive(::ts_rs::TS), ts(type = "string"))]
+        #[repr(transparent)]
+        pub struct $name(::uuid::Uuid);
+
+        impl $name {
+            pub const PREFIX: &'static str = $prefix;
+
+            pub fn new() -> Self {
+                Self(::uuid::Uuid::now_v7())
+            }
+
+            pub fn none() -> Self {
+                Self(::uuid::Uuid::nil())
+            }
+
+            pub fn is_none(self) -> bool {
+                self.0.is_nil()
+            }
+
+            /// The UUID as 22 base62 characters, without the prefix.
+            pub fn to_short_string(&self) -> String {
+                $crate::id::encode_short(self.0.as_u128())
+            }
+
+            pub fn from_short_string(value: &str) -> $crate::error::Result<Self> {
+                $crate::id::decode_short(value).map(|v| Self(::uuid::Uuid::from_u128(v)))
+            }
+        }
+
+        impl $crate::id::ShortId for $name {
+            fn to_short_string(&self) -> String {
+                self.to_short_string()
+            }
+
+            fn from_short_string(value: &str) -> $crate::error::Result<Self> {
+                Self::from_short_string(value)
+            }
+        }
+
+        impl ::std::ops::Deref for $name {
+            type Target = ::uuid::Uuid;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                write!(f, "{}_{}", $prefix, self.0.simple())
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::none()
+            }
+        }
+
+        impl From<::uuid::Uuid> for $name {
+            fn from(value: ::uuid::Uuid) -> Self {
+                Self(value)
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::error::GlobalError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let uuid = match s.split_once('_') {
+                    Some(($prefix, uuid)) => uuid,
+                    Some((prefix, _)) => {
+                        return Err($crate::error::GlobalError::ParseError(format!(
+                            "Expected a {} ID, found a {prefix} ID",
+                            $prefix
+                        )));
+                    }
+                    None => s,
+                };
+
+                Ok(Self(::uuid::Uuid::parse_str(uuid)?))
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = $crate::error::GlobalError;
+
+            fn try_from(value: String) -> ::std::result::Result<Self, Self::Error> {
+                value.parse()
+            }
+        }
+
+        impl ::serde::Serialize for $name {
+            fn serialize<S: ::serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> ::std::result::Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.collect_str(self)
+                } else {
+                    ::serde::Serialize::serialize(&self.0, serializer)
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $name {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::std::result::Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    let value: ::std::borrow::Cow<'de, str> =
+                        ::serde::Deserialize::deserialize(deserializer)?;
+
+                    value.parse().map_err(::serde::de::Error::custom)
+                } else {
+                    ::serde::Deserialize::deserialize(deserializer).map(Self)
+                }
+            }
+        }
+    )+};
+}
+
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Characters needed for any 128 bit value.
+const SHORT_LEN: usize = 22;
+
+/// Encode 128 bits as base62, padded to 22 characters.
+pub fn encode_short(mut value: u128) -> String {
+    let mut output = [b'0'; SHORT_LEN];
+
+    for c in output.iter_mut().rev() {
+        *c = BASE62[(value % 62) as usize];
+        value /= 62;
+    }
+
+    String::from_utf8(output.to_vec()).unwrap()
+}
+
+pub fn decode_short(value: &str) -> Result<u128> {
+    let invalid = || GlobalError::ParseError(format!("Invalid short ID {value}"));
+
+    if value.len() != SHORT_LEN {
+        return Err(invalid());
+    }
+
+    value.bytes().try_fold(0u128, |acc, c| {
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'A'..=b'Z' => c - b'A' + 10,
+            b'a'..=b'z' => c - b'a' + 36,
+            _ => return Err(invalid()),
+        };
+
+        acc.checked_mul(62)
+            .and_then(|v| v.checked_add(digit as u128))
+            .ok_or_else(invalid)
+    })
+}
+
+/// An ID which has a short form, see [`Short`].
+pub trait ShortId: Sized + From<Uuid> {
+    fn to_short_string(&self) -> String;
+
+    fn from_short_string(value: &str) -> Result<Self>;
+}
+
+/// Serializes the ID in its short form.
+///
+/// Deserializing also accepts a regular UUID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Short<T>(pub T);
+
+impl<T: ShortId> Serialize for Short<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_short_string())
+    }
+}
+
+impl<'de, T: ShortId> Deserialize<'de> for Short<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = std::borrow::Cow::<'de, str>::deserialize(deserializer)?;
+
+        let id = if value.len() == SHORT_LEN {
+            T::from_short_string(&value).map_err(serde::de::Error::custom)?
+        } else {
+            T::from(Uuid::parse_str(&value).map_err(serde::de::Error::custom)?)
+        };
+
+        Ok(Self(id))
+    }
+}
+
+impl<T> Deref for Short<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 create_uuid!(AddonUuid, Uuid);
 create_uuid!(AddonInstanceUuid, Uuid);
 create_uuid!(WebsitePublicId, Uuid);
@@ -114,3 +346,80 @@ create_uuid!(AddonWidgetPanelPublicId, Uuid);
 create_uuid!(AddonCompiledPublicId, Uuid);
 create_uuid!(AddonCompiledWidgetPublicId, Uuid);
 create_uuid!(AddonCompiledPagePublicId, Uuid);
+
+create_uuid!(MemberPublicId, Uuid);
+create_uuid!(PagePublicId, Uuid);
+create_uuid!(UploadPublicId, Uuid);
+create_uuid!(OrderPublicId, Uuid);
+create_uuid!(AutomationPublicId, Uuid);
+create_uuid!(ApiKeyId, Uuid);
+create_uuid!(TemplatePublicId, Uuid);
+create_uuid!(NotificationPublicId, Uuid);
+
+macro_rules! any_public_id {
+    ($($variant:ident($id:ident)),+ $(,)?) => {
+        /// Any of the typed IDs, tagged with its kind so it can be stored and read back without
+        /// losing the type, eg. in audit logs.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(tag = "type", content = "value")]
+        pub enum AnyPublicId {
+            $($variant($id),)+
+        }
+
+        /// The kind of an [`AnyPublicId`].
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum IdKind {
+            $($variant,)+
+        }
+
+        impl AnyPublicId {
+            pub fn kind(&self) -> IdKind {
+                match self {
+                    $(Self::$variant(_) => IdKind::$variant,)+
+                }
+            }
+
+            pub fn uuid(&self) -> Uuid {
+                match self {
+                    $(Self::$variant(v) => **v,)+
+                }
+            }
+
+            /// Give a UUID its kind.
+            pub fn new(kind: IdKind, uuid: Uuid) -> Self {
+                match kind {
+                    $(IdKind::$variant => Self::$variant($id::from(uuid)),)+
+                }
+            }
+        }
+
+        $(
+            impl From<$id> for AnyPublicId {
+                fn from(value: $id) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )+
+    };
+}
+
+any_public_id!(
+    Addon(AddonUuid),
+    AddonInstance(AddonInstanceUuid),
+    Website(WebsitePublicId),
+    SchemaData(SchemaDataPublicId),
+    Form(FormPublicId),
+    AddonWidget(AddonWidgetPublicId),
+    AddonWidgetPanel(AddonWidgetPanelPublicId),
+    AddonCompiled(AddonCompiledPublicId),
+    AddonCompiledWidget(AddonCompiledWidgetPublicId),
+    AddonCompiledPage(AddonCompiledPagePublicId),
+    Member(MemberPublicId),
+    Page(PagePublicId),
+    Upload(UploadPublicId),
+    Order(OrderPublicId),
+    Automation(AutomationPublicId),
+    ApiKey(ApiKeyId),
+    Template(TemplatePublicId),
+    Notification(NotificationPublicId),
+);
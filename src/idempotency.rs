@@ -0,0 +1,97 @@
+//! Keys making retried mutations safe.
+//!
+//! A client sends the same key with every attempt of a request. The server stores the response
+//! of the first attempt and returns it for the others as
+//! [`WrappingResponse::Replayed`](crate::response::WrappingResponse::Replayed) instead of
+//! applying the request again.
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{GlobalError, Result};
+
+/// The header the key can be sent in instead of the body.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// A UUID, or any other string the client picks.
+///
+/// Between 1 and [`IdempotencyKey::MAX_LEN`] characters of ASCII letters, digits, `-`, `_`,
+/// `.` and `:`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(try_from = "String", into = "String")]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub const MAX_LEN: usize = 255;
+
+    /// A new unique key.
+    pub fn new() -> Self {
+        Self::from(Uuid::now_v7())
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| GlobalError::ParseError(format!("Idempotency Key {value:?}: {reason}"));
+
+        if value.is_empty() || value.len() > Self::MAX_LEN {
+            return Err(invalid(&format!(
+                "has to be between 1 and {} characters",
+                Self::MAX_LEN
+            )));
+        }
+
+        if !value
+            .bytes()
+            .all(|v| v.is_ascii_alphanumeric() || b"-_.:".contains(&v))
+        {
+            return Err(invalid("has an unexpected character"));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The key scoped to who sent it, so two clients picking the same key don't share a
+    /// response. Used as the storage key.
+    pub fn scoped(&self, scope: &str) -> String {
+        format!("{scope}:{}", self.0)
+    }
+}
+
+impl Default for IdempotencyKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Uuid> for IdempotencyKey {
+    fn from(value: Uuid) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl TryFrom<String> for IdempotencyKey {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<IdempotencyKey> for String {
+    fn from(value: IdempotencyKey) -> Self {
+        value.0
+    }
+}
+
+impl Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -1,15 +1,69 @@
+//! Types shared by the Webby services and their clients.
+//!
+//! Field names on the wire are camelCase. Names which used to be snake_case are still accepted
+//! when deserializing, so older clients and stored data keep working.
+
+use std::iter::FusedIterator;
+
 use serde::{Deserialize, Serialize};
 
+pub mod addon;
+pub mod audit;
+pub mod auth;
+pub mod automation;
+#[cfg(feature = "bson")]
+pub mod bson;
+#[cfg(feature = "codec")]
+pub mod codec;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod duration;
+pub mod email;
+pub mod error;
+pub mod events;
+pub mod expr;
 pub mod filter;
+pub mod forms;
+pub mod geo;
+pub mod i18n;
 pub mod id;
+pub mod idempotency;
+pub mod limits;
+pub mod members;
+pub mod money;
+pub mod notifications;
+pub mod notify;
 pub mod object_id;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod pages;
+pub mod parse;
+pub mod permissions;
+pub mod publish;
+pub mod realtime;
+#[cfg(feature = "redis")]
+pub mod redis;
 pub mod request;
 pub mod response;
+pub mod richtext;
+pub mod schedule;
 pub mod schema;
+pub mod search;
+pub mod seo;
+pub mod slug;
+pub mod sort;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+pub mod trace;
+#[cfg(feature = "ts")]
+pub mod typescript;
 pub mod tz;
 pub mod upload;
 pub mod uuid;
+pub mod validators;
 pub mod value;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -19,6 +73,72 @@ pub enum Either<L, R> {
 }
 
 impl<L, R> Either<L, R> {
+    pub fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(self, Either::Right(_))
+    }
+
+    pub fn left(self) -> Option<L> {
+        match self {
+            Either::Left(v) => Some(v),
+            Either::Right(_) => None,
+        }
+    }
+
+    pub fn right(self) -> Option<R> {
+        match self {
+            Either::Left(_) => None,
+            Either::Right(v) => Some(v),
+        }
+    }
+
+    pub fn as_ref(&self) -> Either<&L, &R> {
+        match self {
+            Either::Left(v) => Either::Left(v),
+            Either::Right(v) => Either::Right(v),
+        }
+    }
+
+    pub fn as_mut(&mut self) -> Either<&mut L, &mut R> {
+        match self {
+            Either::Left(v) => Either::Left(v),
+            Either::Right(v) => Either::Right(v),
+        }
+    }
+
+    /// Swap the sides, a Left becomes a Right.
+    pub fn flip(self) -> Either<R, L> {
+        match self {
+            Either::Left(v) => Either::Right(v),
+            Either::Right(v) => Either::Left(v),
+        }
+    }
+
+    pub fn map_left<T, F: FnOnce(L) -> T>(self, func: F) -> Either<T, R> {
+        match self {
+            Either::Left(v) => Either::Left(func(v)),
+            Either::Right(v) => Either::Right(v),
+        }
+    }
+
+    pub fn map_right<T, F: FnOnce(R) -> T>(self, func: F) -> Either<L, T> {
+        match self {
+            Either::Left(v) => Either::Left(v),
+            Either::Right(v) => Either::Right(func(v)),
+        }
+    }
+
+    /// Convert whichever side is set into a `T`.
+    pub fn either<T, F: FnOnce(L) -> T, G: FnOnce(R) -> T>(self, left: F, right: G) -> T {
+        match self {
+            Either::Left(v) => left(v),
+            Either::Right(v) => right(v),
+        }
+    }
+
     pub fn unwrap_left(self) -> L {
         match self {
             Either::Left(v) => v,
@@ -34,6 +154,71 @@ impl<L, R> Either<L, R> {
     }
 }
 
+impl<T> Either<T, T> {
+    /// The value of whichever side is set.
+    pub fn into_inner(self) -> T {
+        match self {
+            Either::Left(v) | Either::Right(v) => v,
+        }
+    }
+}
+
+/// An `Err` is a Left and an `Ok` a Right.
+impl<L, R> From<Result<R, L>> for Either<L, R> {
+    fn from(value: Result<R, L>) -> Self {
+        match value {
+            Ok(v) => Either::Right(v),
+            Err(v) => Either::Left(v),
+        }
+    }
+}
+
+impl<L, R> From<Either<L, R>> for Result<R, L> {
+    fn from(value: Either<L, R>) -> Self {
+        match value {
+            Either::Left(v) => Err(v),
+            Either::Right(v) => Ok(v),
+        }
+    }
+}
+
+/// Either of two iterators over the same items, eg. to return one of two iterator types.
+impl<L: Iterator, R: Iterator<Item = L::Item>> Iterator for Either<L, R> {
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Either::Left(v) => v.next(),
+            Either::Right(v) => v.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Either::Left(v) => v.size_hint(),
+            Either::Right(v) => v.size_hint(),
+        }
+    }
+}
+
+impl<L: DoubleEndedIterator, R: DoubleEndedIterator<Item = L::Item>> DoubleEndedIterator
+    for Either<L, R>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Either::Left(v) => v.next_back(),
+            Either::Right(v) => v.next_back(),
+        }
+    }
+}
+
+impl<L: ExactSizeIterator, R: ExactSizeIterator<Item = L::Item>> ExactSizeIterator
+    for Either<L, R>
+{
+}
+
+impl<L: FusedIterator, R: FusedIterator<Item = L::Item>> FusedIterator for Either<L, R> {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SingleOrMulti<V> {
@@ -0,0 +1,201 @@
+//! Plan/entitlement limits shared between billing and the CMS.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+/// The limits of a plan. `None` means unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanLimits {
+    pub max_collections: Option<u64>,
+    pub max_rows_per_collection: Option<u64>,
+    pub max_storage_bytes: Option<u64>,
+    pub max_requests_per_minute: Option<u64>,
+}
+
+impl PlanLimits {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn limit(&self, kind: LimitKind) -> Limit {
+        Limit {
+            kind,
+            max: match kind {
+                LimitKind::Collections => self.max_collections,
+                LimitKind::RowsPerCollection => self.max_rows_per_collection,
+                LimitKind::StorageBytes => self.max_storage_bytes,
+                LimitKind::RequestsPerMinute => self.max_requests_per_minute,
+            },
+        }
+    }
+
+    pub fn enforce(&self, kind: LimitKind, current: u64, requested: u64) -> Result<(), QuotaError> {
+        enforce(self.limit(kind), current, requested)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitKind {
+    Collections,
+    RowsPerCollection,
+    StorageBytes,
+    RequestsPerMinute,
+}
+
+impl Display for LimitKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Collections => "collections",
+            Self::RowsPerCollection => "rows per collection",
+            Self::StorageBytes => "storage bytes",
+            Self::RequestsPerMinute => "requests per minute",
+        }
+        .fmt(f)
+    }
+}
+
+/// A single limit of a plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limit {
+    pub kind: LimitKind,
+    pub max: Option<u64>,
+}
+
+impl Limit {
+    pub fn is_unlimited(&self) -> bool {
+        self.max.is_none()
+    }
+
+    /// How many more can be used. `None` if unlimited.
+    pub fn remaining(&self, current: u64) -> Option<u64> {
+        self.max.map(|max| max.saturating_sub(current))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "type", content = "value")]
+pub enum QuotaError {
+    #[error("Quota Exceeded for {kind}: {current} used + {requested} requested > {limit}")]
+    Exceeded {
+        kind: LimitKind,
+        limit: u64,
+        current: u64,
+        requested: u64,
+    },
+}
+
+/// Ensure that `requested` more can be used on top of `current` without going over the limit.
+pub fn enforce(limit: Limit, current: u64, requested: u64) -> Result<(), QuotaError> {
+    let Some(max) = limit.max else {
+        return Ok(());
+    };
+
+    if current.saturating_add(requested) > max {
+        Err(QuotaError::Exceeded {
+            kind: limit.kind,
+            limit: max,
+            current,
+            requested,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// The state of a rate limit, sent with responses so clients can back off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// Requests allowed in the window.
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the window ends and `remaining` goes back to `limit`.
+    #[serde(alias = "reset_at", with = "time::serde::timestamp")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub reset_at: OffsetDateTime,
+}
+
+impl RateLimit {
+    pub const LIMIT_HEADER: &str = "X-RateLimit-Limit";
+    pub const REMAINING_HEADER: &str = "X-RateLimit-Remaining";
+    /// Unix timestamp in seconds.
+    pub const RESET_HEADER: &str = "X-RateLimit-Reset";
+    /// Seconds to wait. Only sent once the limit is reached.
+    pub const RETRY_AFTER_HEADER: &str = "Retry-After";
+
+    pub fn is_exceeded(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// How long to wait before sending another request. Zero if requests can be sent now.
+    pub fn retry_after(&self, now: OffsetDateTime) -> Duration {
+        if self.is_exceeded() {
+            (self.reset_at - now).max(Duration::ZERO)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    /// The headers to send the limit with.
+    pub fn to_headers(&self, now: OffsetDateTime) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            (Self::LIMIT_HEADER, self.limit.to_string()),
+            (Self::REMAINING_HEADER, self.remaining.to_string()),
+            (
+                Self::RESET_HEADER,
+                self.reset_at.unix_timestamp().to_string(),
+            ),
+        ];
+
+        if self.is_exceeded() {
+            // Rounded up so clients don't retry a moment too early.
+            let wait = self.retry_after(now);
+            let seconds = wait.whole_seconds() + i64::from(wait.subsec_nanoseconds() > 0);
+
+            headers.push((Self::RETRY_AFTER_HEADER, seconds.to_string()));
+        }
+
+        headers
+    }
+
+    /// Read a limit from response headers. `get` looks a header up by name, ignoring its case.
+    ///
+    /// Without the `X-RateLimit-*` headers, a `Retry-After` in seconds is read as an exceeded
+    /// limit of unknown size.
+    pub fn from_headers<'a>(
+        get: impl Fn(&str) -> Option<&'a str>,
+        now: OffsetDateTime,
+    ) -> Option<Self> {
+        let number = |name: &str| get(name)?.trim().parse::<i64>().ok();
+
+        let retry_after = number(Self::RETRY_AFTER_HEADER)
+            .filter(|v| *v >= 0)
+            // Too far in the future to represent is as invalid as a negative value.
+            .and_then(|v| now.checked_add(Duration::seconds(v)));
+
+        match (
+            number(Self::LIMIT_HEADER),
+            number(Self::REMAINING_HEADER),
+            number(Self::RESET_HEADER),
+        ) {
+            (Some(limit), Some(remaining), reset) if limit >= 0 && remaining >= 0 => Some(Self {
+                limit: limit as u64,
+                remaining: remaining as u64,
+                reset_at: reset
+                    .and_then(|v| OffsetDateTime::from_unix_timestamp(v).ok())
+                    .or(retry_after)
+                    .unwrap_or(now),
+            }),
+            _ => retry_after.map(|reset_at| Self {
+                limit: 0,
+                remaining: 0,
+                reset_at,
+            }),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Members: the accounts visitors create on a website.
+//!
+//! They're stored in the `Members` namespace, and referenced by collections through their
+//! [`MemberPublicId`].
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{
+    error::{GlobalError, Result},
+    id::{MemberPublicId, UploadPublicId, WebsitePublicId},
+    permissions::RequestContext,
+    validators::EmailAddress,
+};
+
+/// A member as other members and addons can see them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicMember {
+    pub id: MemberPublicId,
+    pub email: EmailAddress,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<UploadPublicId>,
+    /// Names used in [`PermissionsUser::Role`](crate::schema::PermissionsUser::Role).
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub status: MemberStatus,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MemberStatus {
+    /// Signed up but the email address isn't verified yet.
+    #[default]
+    Pending,
+    Active,
+    /// Blocked by the site owner. Can't sign in.
+    Blocked,
+}
+
+impl PublicMember {
+    /// The display name, or the local part of the email address.
+    pub fn name(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| self.email.local_part())
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|v| v == role)
+    }
+
+    pub fn can_sign_in(&self) -> bool {
+        self.status == MemberStatus::Active
+    }
+}
+
+/// What a member's session token holds.
+///
+/// Uses the registered JWT claim names: the member is the `sub`, and `exp` and `iat` are Unix
+/// timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionClaims {
+    #[serde(rename = "sub")]
+    pub member_id: MemberPublicId,
+    #[serde(rename = "site")]
+    pub site_id: WebsitePublicId,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(rename = "exp", with = "time::serde::timestamp")]
+    pub expires_at: OffsetDateTime,
+    #[serde(
+        rename = "iat",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "time::serde::timestamp::option"
+    )]
+    pub issued_at: Option<OffsetDateTime>,
+}
+
+impl SessionClaims {
+    /// Claims for a member, failing unless they [can sign in](PublicMember::can_sign_in).
+    pub fn new(
+        member: &PublicMember,
+        site_id: WebsitePublicId,
+        issued_at: OffsetDateTime,
+        lifetime: time::Duration,
+    ) -> Result<Self> {
+        if !member.can_sign_in() {
+            return Err(GlobalError::InvalidSession(format!(
+                "the member {} is {:?}",
+                member.id, member.status
+            )));
+        }
+
+        Ok(Self {
+            member_id: member.id,
+            site_id,
+            roles: member.roles.clone(),
+            expires_at: issued_at + lifetime,
+            issued_at: Some(issued_at),
+        })
+    }
+
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Whether the session can be used on the site.
+    pub fn is_valid_for(&self, site_id: WebsitePublicId, now: OffsetDateTime) -> bool {
+        self.site_id == site_id && !self.is_expired(now)
+    }
+
+    /// The context collection permissions are checked against.
+    pub fn request_context(&self) -> RequestContext {
+        RequestContext::user(*self.member_id).with_roles(self.roles.iter().cloned())
+    }
+}
@@ -0,0 +1,389 @@
+//! Amounts of money, stored as an integer number of minor units (eg. cents) so they never
+//! pick up floating point rounding errors.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{
+    error::{GlobalError, Result},
+    i18n::LanguageTag,
+    value::SimpleValue,
+};
+
+/// Active ISO 4217 codes and the number of digits of their minor unit.
+#[rustfmt::skip]
+const CURRENCIES: &[(&str, u8)] = &[
+    ("AED", 2), ("AFN", 2), ("ALL", 2), ("AMD", 2), ("ANG", 2), ("AOA", 2), ("ARS", 2),
+    ("AUD", 2), ("AWG", 2), ("AZN", 2), ("BAM", 2), ("BBD", 2), ("BDT", 2), ("BGN", 2),
+    ("BHD", 3), ("BIF", 0), ("BMD", 2), ("BND", 2), ("BOB", 2), ("BRL", 2), ("BSD", 2),
+    ("BTN", 2), ("BWP", 2), ("BYN", 2), ("BZD", 2), ("CAD", 2), ("CDF", 2), ("CHF", 2),
+    ("CLP", 0), ("CNY", 2), ("COP", 2), ("CRC", 2), ("CUP", 2), ("CVE", 2), ("CZK", 2),
+    ("DJF", 0), ("DKK", 2), ("DOP", 2), ("DZD", 2), ("EGP", 2), ("ERN", 2), ("ETB", 2),
+    ("EUR", 2), ("FJD", 2), ("FKP", 2), ("GBP", 2), ("GEL", 2), ("GHS", 2), ("GIP", 2),
+    ("GMD", 2), ("GNF", 0), ("GTQ", 2), ("GYD", 2), ("HKD", 2), ("HNL", 2), ("HTG", 2),
+    ("HUF", 2), ("IDR", 2), ("ILS", 2), ("INR", 2), ("IQD", 3), ("IRR", 2), ("ISK", 0),
+    ("JMD", 2), ("JOD", 3), ("JPY", 0), ("KES", 2), ("KGS", 2), ("KHR", 2), ("KMF", 0),
+    ("KPW", 2), ("KRW", 0), ("KWD", 3), ("KYD", 2), ("KZT", 2), ("LAK", 2), ("LBP", 2),
+    ("LKR", 2), ("LRD", 2), ("LSL", 2), ("LYD", 3), ("MAD", 2), ("MDL", 2), ("MGA", 2),
+    ("MKD", 2), ("MMK", 2), ("MNT", 2), ("MOP", 2), ("MRU", 2), ("MUR", 2), ("MVR", 2),
+    ("MWK", 2), ("MXN", 2), ("MYR", 2), ("MZN", 2), ("NAD", 2), ("NGN", 2), ("NIO", 2),
+    ("NOK", 2), ("NPR", 2), ("NZD", 2), ("OMR", 3), ("PAB", 2), ("PEN", 2), ("PGK", 2),
+    ("PHP", 2), ("PKR", 2), ("PLN", 2), ("PYG", 0), ("QAR", 2), ("RON", 2), ("RSD", 2),
+    ("RUB", 2), ("RWF", 0), ("SAR", 2), ("SBD", 2), ("SCR", 2), ("SDG", 2), ("SEK", 2),
+    ("SGD", 2), ("SHP", 2), ("SLE", 2), ("SOS", 2), ("SRD", 2), ("SSP", 2), ("STN", 2),
+    ("SVC", 2), ("SYP", 2), ("SZL", 2), ("THB", 2), ("TJS", 2), ("TMT", 2), ("TND", 3),
+    ("TOP", 2), ("TRY", 2), ("TTD", 2), ("TWD", 2), ("TZS", 2), ("UAH", 2), ("UGX", 0),
+    ("USD", 2), ("UYU", 2), ("UZS", 2), ("VES", 2), ("VND", 0), ("VUV", 0), ("WST", 2),
+    ("XAF", 0), ("XCD", 2), ("XOF", 0), ("XPF", 0), ("YER", 2), ("ZAR", 2), ("ZMW", 2),
+    ("ZWG", 2),
+];
+
+/// An ISO 4217 currency code, eg. `USD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+pub struct CurrencyCode([u8; 3]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct Money {
+    /// In the smallest unit of the currency, eg. cents for `USD` or yen for `JPY`.
+    pub amount_minor: i64,
+    pub currency: CurrencyCode,
+}
+
+impl CurrencyCode {
+    pub const USD: Self = Self(*b"USD");
+    pub const EUR: Self = Self(*b"EUR");
+    pub const GBP: Self = Self(*b"GBP");
+    pub const JPY: Self = Self(*b"JPY");
+
+    /// Parse a code, case-insensitively. Fails for codes which aren't an active currency.
+    pub fn parse(value: &str) -> Result<Self> {
+        let code = value.trim().to_ascii_uppercase();
+
+        if !CURRENCIES.iter().any(|(v, _)| *v == code) {
+            return Err(GlobalError::ParseError(format!(
+                "Unknown currency {value:?}, expected an ISO 4217 code"
+            )));
+        }
+
+        let mut bytes = [0; 3];
+        bytes.copy_from_slice(code.as_bytes());
+
+        Ok(Self(bytes))
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+
+    /// The number of digits after the decimal point, eg. 2 for `USD`, 0 for `JPY` and 3 for `KWD`.
+    pub fn minor_units(&self) -> u8 {
+        CURRENCIES
+            .iter()
+            .find(|(v, _)| *v == self.as_str())
+            .map(|(_, v)| *v)
+            .unwrap_or(2)
+    }
+
+    /// The symbol used in front of or after the amount, eg. `€`. The code for currencies
+    /// without a widely recognized symbol.
+    pub fn symbol(&self) -> &str {
+        match self.as_str() {
+            "USD" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" => "¥",
+            "CNY" => "CN¥",
+            "INR" => "₹",
+            "KRW" => "₩",
+            "CAD" => "CA$",
+            "AUD" => "A$",
+            "BRL" => "R$",
+            code => code,
+        }
+    }
+}
+
+impl Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for CurrencyCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl Money {
+    pub fn new(amount_minor: i64, currency: CurrencyCode) -> Self {
+        Self {
+            amount_minor,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: CurrencyCode) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Parse a decimal amount like `-1234.5` in the currency.
+    ///
+    /// Fails instead of rounding when there are more digits than the currency's minor unit.
+    pub fn from_decimal_str(amount: &str, currency: CurrencyCode) -> Result<Self> {
+        let invalid =
+            |reason: &str| GlobalError::ParseError(format!("Amount {amount:?}: {reason}"));
+
+        let value = amount.trim();
+
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(v) => (true, v),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+        if whole.is_empty()
+            || !whole.bytes().all(|v| v.is_ascii_digit())
+            || !fraction.bytes().all(|v| v.is_ascii_digit())
+        {
+            return Err(invalid("expected a decimal number"));
+        }
+
+        let digits = currency.minor_units() as usize;
+
+        if fraction.len() > digits {
+            return Err(invalid(&format!(
+                "{currency} only has {digits} decimal places"
+            )));
+        }
+
+        let minor = format!("{whole}{fraction:0<digits$}")
+            .parse::<i64>()
+            .map_err(|_| invalid("too large"))?;
+
+        Ok(Self::new(if negative { -minor } else { minor }, currency))
+    }
+
+    /// Read a stored value: an object, a text containing a JSON object, or a text like
+    /// `12.34 USD`.
+    pub fn from_value(value: &SimpleValue) -> Result<Self> {
+        match value {
+            SimpleValue::ObjectUnknown(v) => Ok(serde_json::from_value(v.clone())?),
+            SimpleValue::Text(v) if v.trim_start().starts_with('{') => Ok(serde_json::from_str(v)?),
+            SimpleValue::Text(v) => v.parse(),
+            v => Err(GlobalError::type_mismatch("Money", v.type_name())),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount_minor == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount_minor < 0
+    }
+
+    /// The amount without a currency, eg. `12.34` or `-5` for `JPY`.
+    pub fn to_decimal_string(&self) -> String {
+        let digits = self.currency.minor_units() as u32;
+        let divisor = 10u64.pow(digits);
+        let abs = self.amount_minor.unsigned_abs();
+        let sign = if self.is_negative() { "-" } else { "" };
+
+        if digits == 0 {
+            format!("{sign}{abs}")
+        } else {
+            format!(
+                "{sign}{}.{:0width$}",
+                abs / divisor,
+                abs % divisor,
+                width = digits as usize
+            )
+        }
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+
+        self.amount_minor
+            .checked_add(other.amount_minor)
+            .map(|v| Self::new(v, self.currency))
+            .ok_or_else(|| self.overflow())
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self> {
+        self.ensure_same_currency(other)?;
+
+        self.amount_minor
+            .checked_sub(other.amount_minor)
+            .map(|v| Self::new(v, self.currency))
+            .ok_or_else(|| self.overflow())
+    }
+
+    /// Multiply by a whole number, eg. a quantity.
+    pub fn checked_mul(self, factor: i64) -> Result<Self> {
+        self.amount_minor
+            .checked_mul(factor)
+            .map(|v| Self::new(v, self.currency))
+            .ok_or_else(|| self.overflow())
+    }
+
+    pub fn checked_neg(self) -> Result<Self> {
+        self.amount_minor
+            .checked_neg()
+            .map(|v| Self::new(v, self.currency))
+            .ok_or_else(|| self.overflow())
+    }
+
+    /// Add up amounts of the same currency. `None` when there's nothing to add up.
+    pub fn checked_sum<I: IntoIterator<Item = Self>>(values: I) -> Result<Option<Self>> {
+        values.into_iter().try_fold(None, |total, v| match total {
+            None => Ok(Some(v)),
+            Some(total) => Self::checked_add(total, v).map(Some),
+        })
+    }
+
+    /// Format the amount for display in the locale, eg. `$1,234.56` for `en-US` or
+    /// `1.234,56 €` for `de-DE`.
+    ///
+    /// Covers the separators and symbol placement of common languages, anything else uses
+    /// English conventions.
+    pub fn format(&self, locale: &LanguageTag) -> String {
+        let style = LocaleStyle::of(locale);
+        let decimal = self.to_decimal_string();
+        let decimal = decimal.trim_start_matches('-');
+        let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+
+        let mut number = String::new();
+
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                number.push(style.group);
+            }
+
+            number.push(digit);
+        }
+
+        if !fraction.is_empty() {
+            number.push(style.decimal);
+            number.push_str(fraction);
+        }
+
+        let symbol = self.currency.symbol();
+        // Codes are always separated from the number, eg. `CHF 12.00`.
+        let separator = if style.spaced || symbol == self.currency.as_str() {
+            "\u{a0}"
+        } else {
+            ""
+        };
+        let sign = if self.is_negative() { "-" } else { "" };
+
+        if style.symbol_first {
+            format!("{sign}{symbol}{separator}{number}")
+        } else {
+            format!("{sign}{number}{separator}{symbol}")
+        }
+    }
+
+    fn ensure_same_currency(&self, other: Self) -> Result<()> {
+        if self.currency != other.currency {
+            return Err(GlobalError::InvalidMoney(format!(
+                "can't combine {} with {}",
+                self.currency, other.currency
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn overflow(&self) -> GlobalError {
+        GlobalError::InvalidMoney(format!("{} amount overflowed", self.currency))
+    }
+}
+
+/// `12.34 USD`.
+impl Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.to_decimal_string(), self.currency)
+    }
+}
+
+/// Parses `12.34 USD` or `USD 12.34`.
+impl FromStr for Money {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((first, second)) = s.trim().split_once(' ') else {
+            return Err(GlobalError::ParseError(format!(
+                "Money {s:?}: expected an amount and a currency code"
+            )));
+        };
+
+        let (amount, currency) = match first.parse::<CurrencyCode>() {
+            Ok(currency) => (second, currency),
+            Err(_) => (first, second.parse()?),
+        };
+
+        Self::from_decimal_str(amount, currency)
+    }
+}
+
+impl From<Money> for SimpleValue {
+    fn from(value: Money) -> Self {
+        SimpleValue::ObjectUnknown(serde_json::to_value(value).expect("Money serializes to JSON"))
+    }
+}
+
+struct LocaleStyle {
+    group: char,
+    decimal: char,
+    symbol_first: bool,
+    spaced: bool,
+}
+
+impl LocaleStyle {
+    fn of(locale: &LanguageTag) -> Self {
+        let style = |group, decimal, symbol_first, spaced| Self {
+            group,
+            decimal,
+            symbol_first,
+            spaced,
+        };
+
+        if locale.as_str().ends_with("-CH") {
+            return style('\'', '.', true, true);
+        }
+
+        match locale.language() {
+            "de" | "es" | "it" | "pt" | "da" | "id" | "el" | "ro" | "hr" | "sl" | "sr" | "tr" => {
+                style('.', ',', false, true)
+            }
+            "nl" => style('.', ',', true, true),
+            "fr" | "sv" | "nb" | "nn" | "no" | "fi" | "cs" | "sk" | "pl" | "ru" | "uk" | "hu"
+            | "bg" | "et" | "lt" | "lv" => style('\u{a0}', ',', false, true),
+            _ => style(',', '.', true, false),
+        }
+    }
+}
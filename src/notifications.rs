@@ -0,0 +1,154 @@
+//! Notifications shown in the dashboard's notification center, and sent to other channels.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    id::{MemberPublicId, NotificationPublicId},
+    notify::NotificationChannel,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Recipient {
+    /// A dashboard account.
+    User(Uuid),
+    Member(MemberPublicId),
+    /// Every member with the role.
+    Role(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: NotificationPublicId,
+    pub recipient: Recipient,
+    pub channel: NotificationChannel,
+    pub title: String,
+    pub body: String,
+    /// Where clicking the notification goes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_url: Option<Url>,
+    pub created_at: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_at: Option<OffsetDateTime>,
+}
+
+impl Notification {
+    pub fn new(
+        id: NotificationPublicId,
+        recipient: Recipient,
+        channel: NotificationChannel,
+        title: impl Into<String>,
+        body: impl Into<String>,
+        created_at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            id,
+            recipient,
+            channel,
+            title: title.into(),
+            body: body.into(),
+            action_url: None,
+            created_at,
+            read_at: None,
+        }
+    }
+
+    pub fn action_url(mut self, value: Url) -> Self {
+        self.action_url = Some(value);
+        self
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+
+    /// Keeps the time it was first read at. Returns whether it changed.
+    pub fn mark_read(&mut self, at: OffsetDateTime) -> bool {
+        if self.is_read() {
+            return false;
+        }
+
+        self.read_at = Some(at);
+        true
+    }
+
+    /// Returns whether it changed.
+    pub fn mark_unread(&mut self) -> bool {
+        self.read_at.take().is_some()
+    }
+}
+
+/// Mark notifications as read or unread.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationReadRequest {
+    pub selection: NotificationSelection,
+    /// False marks them as unread.
+    pub read: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum NotificationSelection {
+    Ids(Vec<NotificationPublicId>),
+    /// Every notification of the recipient, eg. for "Mark all as read".
+    ///
+    /// `before` leaves out anything which arrived after the list was loaded.
+    All {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        before: Option<OffsetDateTime>,
+    },
+}
+
+impl NotificationSelection {
+    pub fn matches(&self, notification: &Notification) -> bool {
+        match self {
+            Self::Ids(ids) => ids.contains(&notification.id),
+            Self::All { before } => before.is_none_or(|v| notification.created_at < v),
+        }
+    }
+}
+
+impl NotificationReadRequest {
+    pub fn read(selection: NotificationSelection) -> Self {
+        Self {
+            selection,
+            read: true,
+        }
+    }
+
+    pub fn unread(selection: NotificationSelection) -> Self {
+        Self {
+            selection,
+            read: false,
+        }
+    }
+
+    /// Update the notifications the request selects, returning how many changed.
+    pub fn apply(&self, notifications: &mut [Notification], at: OffsetDateTime) -> usize {
+        notifications
+            .iter_mut()
+            .filter(|v| self.selection.matches(v))
+            .map(|v| {
+                if self.read {
+                    v.mark_read(at)
+                } else {
+                    v.mark_unread()
+                }
+            })
+            .filter(|changed| *changed)
+            .count()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationReadResponse {
+    pub updated: usize,
+    /// Unread notifications left afterwards, for the badge.
+    pub unread: usize,
+}
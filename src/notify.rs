@@ -0,0 +1,87 @@
+//! Message templates used by form-submission notifications and automations.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{schema::SchematicFieldKey, value::SimpleValue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationChannel {
+    Email,
+    Sms,
+    InApp,
+    Push,
+    Webhook,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationTemplate {
+    pub channel: NotificationChannel,
+    /// Only used by channels which have a subject line.
+    ///
+    /// Supports `{{field}}` placeholders.
+    pub subject: Option<String>,
+    /// Supports `{{field}}` placeholders.
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedNotification {
+    pub channel: NotificationChannel,
+    pub subject: Option<String>,
+    pub body: String,
+}
+
+impl NotificationTemplate {
+    pub fn render(&self, row: &HashMap<SchematicFieldKey, SimpleValue>) -> RenderedNotification {
+        RenderedNotification {
+            channel: self.channel,
+            subject: self.subject.as_deref().map(|v| render_template(v, row)),
+            body: render_template(&self.body, row),
+        }
+    }
+}
+
+/// Replace every `{{field}}` placeholder with the displayed value of the field.
+///
+/// Unknown fields are replaced with an empty string. Unclosed placeholders are kept as is.
+pub fn render_template(template: &str, row: &HashMap<SchematicFieldKey, SimpleValue>) -> String {
+    render_template_with(template, |name| row.get(name).map(|v| v.to_string()))
+}
+
+/// Replace every `{{name}}` placeholder with what `lookup` returns for the trimmed name.
+///
+/// Placeholders it returns None for are replaced with an empty string. Unclosed placeholders
+/// are kept as is.
+pub fn render_template_with(
+    template: &str,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        if let Some(value) = lookup(after[..end].trim()) {
+            output.push_str(&value);
+        }
+
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+
+    output
+}
@@ -0,0 +1,357 @@
+//! OpenAPI component schemas for the request and response types, as utoipa [`ToSchema`]
+//! impls.
+//!
+//! They're written by hand instead of derived so they describe what serde writes, eg. the
+//! flattened views of a [`CmsCreate`] or the `type`/`value` tagging of a [`WrappingResponse`].
+//! Register them like derived ones, `#[openapi(components(schemas(CmsQuery, ...)))]`.
+//!
+//! The generic responses hold the schema of their value inline. `#[openapi(..)]` names them
+//! after it, eg. `WrappingResponse_ListResponse_String`.
+
+use serde_json::{Value, json};
+use utoipa::{
+    __dev::ComposeSchema,
+    PartialSchema, ToSchema,
+    openapi::{RefOr, schema::Schema},
+};
+
+use crate::{
+    request::{CmsCreate, CmsQuery},
+    response::{ApiErrorResponse, ListResponse, WrappingResponse},
+};
+
+type Schemas = Vec<(String, RefOr<Schema>)>;
+
+impl PartialSchema for CmsQuery {
+    fn schema() -> RefOr<Schema> {
+        schema(json!({
+            "type": "object",
+            "properties": {
+                "filters": { "type": ["array", "null"], "items": filter() },
+                "sort": {
+                    "type": ["object", "null"],
+                    "description": "The direction to sort by each field, highest priority first, eg. `sort[name]=ASC`.",
+                    "additionalProperties": { "type": "string", "enum": ["ASC", "DESC"] },
+                },
+                "columns": {
+                    "type": ["string", "null"],
+                    "description": "The fields to return, eg. `name,-notes`. `*` is every field.",
+                },
+                "limit": { "type": ["integer", "null"], "minimum": 0 },
+                "offset": { "type": ["integer", "null"], "minimum": 0 },
+                "includeFiles": { "type": "boolean", "default": false },
+            },
+        }))
+    }
+}
+
+impl ToSchema for CmsQuery {}
+
+impl PartialSchema for CmsCreate {
+    fn schema() -> RefOr<Schema> {
+        schema(json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "The collection name, `[addon:][Local/]name`.",
+                },
+                "name": { "type": "string" },
+                "isExternal": {
+                    "type": "boolean",
+                    "description": "Hosted on the addon's servers, `data` has to be null.",
+                },
+                "isSingle": { "type": "boolean" },
+                "views": { "type": ["array", "null"], "items": view() },
+                "columns": {
+                    "type": ["array", "null"],
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string" },
+                            "name": { "type": "string" },
+                            "typeOf": field_type(),
+                            "referencedSchema": { "type": ["string", "null"] },
+                        },
+                        "required": ["id", "name", "typeOf"],
+                    },
+                },
+                "data": {
+                    "type": ["object", "null"],
+                    "description": "The values of each column, by column id.",
+                    "additionalProperties": { "type": "array" },
+                },
+            },
+            "required": ["id", "name", "isExternal", "isSingle"],
+        }))
+    }
+}
+
+impl ToSchema for CmsCreate {}
+
+// Generic types implement `PartialSchema` through `ComposeSchema`, which `#[openapi(..)]` calls
+// with the schemas of the generics.
+impl<T: ToSchema> ComposeSchema for ListResponse<T> {
+    fn compose(generics: Vec<RefOr<Schema>>) -> RefOr<Schema> {
+        list_response(generics.into_iter().next().unwrap_or_else(T::schema))
+    }
+}
+
+impl<T: ToSchema> ToSchema for ListResponse<T> {
+    fn schemas(schemas: &mut Schemas) {
+        T::schemas(schemas);
+    }
+}
+
+impl<V: ToSchema> ComposeSchema for WrappingResponse<V> {
+    fn compose(generics: Vec<RefOr<Schema>>) -> RefOr<Schema> {
+        wrapping_response(generics.into_iter().next().unwrap_or_else(V::schema))
+    }
+}
+
+impl<V: ToSchema> ToSchema for WrappingResponse<V> {
+    fn schemas(schemas: &mut Schemas) {
+        schemas.push((ApiErrorResponse::name().into(), ApiErrorResponse::schema()));
+        V::schemas(schemas);
+    }
+}
+
+impl PartialSchema for ApiErrorResponse {
+    fn schema() -> RefOr<Schema> {
+        schema(json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "enum": [
+                        "NotFound",
+                        "PermissionDenied",
+                        "Validation",
+                        "RateLimited",
+                        "Conflict",
+                        "Internal",
+                        "Unknown",
+                    ],
+                    "default": "Unknown",
+                },
+                "description": { "type": "string" },
+                "fieldErrors": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "field": { "type": "string" },
+                            "message": { "type": "string" },
+                        },
+                        "required": ["field", "message"],
+                    },
+                },
+                "rateLimit": {
+                    "type": "object",
+                    "properties": {
+                        "limit": { "type": "integer", "minimum": 0 },
+                        "remaining": { "type": "integer", "minimum": 0 },
+                        "resetAt": {
+                            "type": "integer",
+                            "description": "Unix timestamp in seconds.",
+                        },
+                    },
+                    "required": ["limit", "remaining", "resetAt"],
+                },
+            },
+            "required": ["description"],
+        }))
+    }
+}
+
+impl ToSchema for ApiErrorResponse {}
+
+/// Every schema is valid, a failure is a typo in this file. Keywords utoipa doesn't know are
+/// dropped silently instead, so debug builds check nothing was.
+fn schema(value: Value) -> RefOr<Schema> {
+    let schema = serde_json::from_value(value.clone()).expect("valid schema");
+    debug_assert_eq!(serde_json::to_value(&schema).ok(), Some(value));
+    schema
+}
+
+fn list_response(items: RefOr<Schema>) -> RefOr<Schema> {
+    schema(json!({
+        "type": "object",
+        "properties": {
+            "items": { "type": "array", "items": items },
+            "offset": { "type": "integer" },
+            "limit": { "type": "integer" },
+            "total": { "type": "integer" },
+            "nextCursor": {
+                "type": "string",
+                "description": "The page after this one, missing on the last page.",
+            },
+            "prevCursor": {
+                "type": "string",
+                "description": "The page before this one, missing on the first page.",
+            },
+        },
+        "required": ["items", "offset", "limit", "total"],
+    }))
+}
+
+fn wrapping_response(value: RefOr<Schema>) -> RefOr<Schema> {
+    let error = json!({ "$ref": reference::<ApiErrorResponse>() });
+    let replayed = json!({
+        "type": "object",
+        "properties": {
+            "idempotencyKey": { "type": "string", "pattern": "^[A-Za-z0-9._:-]{1,255}$" },
+            "handledAt": { "type": "string" },
+            "response": value,
+        },
+        "required": ["idempotencyKey", "handledAt", "response"],
+    });
+
+    schema(json!({
+        "oneOf": [
+            tagged("Resp", json!(value)),
+            tagged("Error", error),
+            tagged("Replayed", replayed),
+        ],
+    }))
+}
+
+fn reference<T: ToSchema>() -> String {
+    format!("#/components/schemas/{}", T::name())
+}
+
+/// A variant of an enum tagged with `type` and `value`.
+fn tagged(tag: &str, value: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "type": { "type": "string", "enum": [tag] },
+            "value": value,
+        },
+        "required": ["type", "value"],
+    })
+}
+
+fn filter() -> Value {
+    let number = json!({ "type": "number" });
+
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "cond": {
+                "type": "string",
+                "enum": ["eq", "neq", "cont", "dnc", "gte", "gt", "lte", "lt", "between", "near"],
+            },
+            "value": {
+                "oneOf": [
+                    number,
+                    { "type": "string" },
+                    { "type": "array", "items": { "type": "string" } },
+                    { "type": "array", "items": number, "minItems": 2, "maxItems": 2 },
+                    {
+                        "type": "object",
+                        "description": "The center of a `near` filter.",
+                        "properties": { "lat": number, "lon": number },
+                        "required": ["lat", "lon"],
+                    },
+                ],
+            },
+            "radius": {
+                "type": "number",
+                "description": "The meters around the value a `near` filter matches within.",
+            },
+            "isPath": {
+                "type": "boolean",
+                "description": "Whether the name is a path into an Object or Array field, eg. `address.city`.",
+            },
+        },
+        "required": ["name", "cond", "value"],
+    })
+}
+
+fn view() -> Value {
+    let item = json!({
+        "type": "object",
+        "properties": {
+            "hiddenFields": { "type": "array", "items": { "type": "string" } },
+        },
+    });
+
+    json!({
+        "type": "object",
+        "properties": {
+            "name": { "type": "string" },
+            "query": {
+                "type": "object",
+                "properties": {
+                    "sort": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "field": { "type": "string" },
+                                "order": { "type": "string", "enum": ["asc", "desc"] },
+                            },
+                            "required": ["field", "order"],
+                        },
+                    },
+                    "filter": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "field": { "type": "string" },
+                                "condition": { "type": "string" },
+                                // Any value, which utoipa can't read back.
+                            },
+                            "required": ["field", "condition", "value"],
+                        },
+                    },
+                },
+                "required": ["sort", "filter"],
+            },
+            "viewType": {
+                "type": "object",
+                "properties": { "form": item, "gallery": item, "list": item, "table": item },
+                "required": ["form", "gallery", "list", "table"],
+            },
+        },
+        "required": ["name", "query", "viewType"],
+    })
+}
+
+fn field_type() -> Value {
+    json!({
+        "type": "string",
+        "enum": [
+            "Text",
+            "Number",
+            "URL",
+            "Email",
+            "Address",
+            "Phone",
+            "Boolean",
+            "DateTime",
+            "Date",
+            "Time",
+            "RichContent",
+            "RichText",
+            "Reference",
+            "MultiReference",
+            "MediaGallery",
+            "Document",
+            "MultiDocument",
+            "Image",
+            "Video",
+            "Audio",
+            "Tags",
+            "Array",
+            "Object",
+            "Currency",
+            "Location",
+            "Slug",
+            "Duration",
+        ],
+    })
+}
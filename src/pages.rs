@@ -0,0 +1,245 @@
+//! Pages of a website and the tree they're arranged in.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    id::PagePublicId,
+    object_id::{ObjectId, ObjectIdTuple},
+    seo::SeoMeta,
+    slug::Slug,
+};
+
+/// A page as it's stored by the editor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page {
+    pub id: ObjectIdTuple,
+    /// The last part of the path, a valid [`Slug`]. Empty for the home page.
+    pub slug: String,
+    /// None = Top Level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ObjectId>,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seo: Option<SeoMeta>,
+    #[serde(default)]
+    pub visibility: PageVisibility,
+    /// The position among the pages with the same parent.
+    #[serde(default)]
+    pub sort_index: u32,
+}
+
+/// A page as it's exposed outside of the editor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct PublicPage {
+    pub id: PagePublicId,
+    /// The full path, starting with `/`.
+    pub path: String,
+    pub title: String,
+    /// The layout the page is rendered in. None = Site Default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    #[serde(default)]
+    pub visibility: PageVisibility,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum PageVisibility {
+    #[default]
+    Public,
+    /// Only signed in members can view it.
+    Members,
+    /// Reachable by its path, but left out of the navigation and the sitemap.
+    Hidden,
+    /// Only visible in the editor.
+    Draft,
+}
+
+impl PageVisibility {
+    /// Whether the page is listed in the navigation and the sitemap.
+    pub fn is_listed(self) -> bool {
+        matches!(self, Self::Public | Self::Members)
+    }
+}
+
+/// Pages used to be sent as free-form JSON. Anything which isn't a list of pages is skipped.
+pub(crate) fn deserialize_public_pages<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<PublicPage>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Every page of a site, arranged by their parents and sorted by their [`Page::sort_index`].
+#[derive(Debug, Clone, Default)]
+pub struct PageTree {
+    roots: Vec<PageNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PageNode {
+    pub page: Page,
+    /// The full path, starting with `/`.
+    pub path: String,
+    pub children: Vec<PageNode>,
+}
+
+impl PageTree {
+    /// Arrange the pages into a tree.
+    ///
+    /// Fails if an ID is used twice, a parent doesn't exist, parents form a cycle, or a slug is
+    /// invalid or used twice under the same parent.
+    pub fn build(pages: Vec<Page>) -> Result<Self> {
+        let total = pages.len();
+        let mut ids = HashSet::new();
+
+        for page in &pages {
+            if !ids.insert(page.id.id.clone()) {
+                return Err(invalid_tree(format!("Page {} exists twice", page.id.id)));
+            }
+        }
+
+        let mut by_parent: HashMap<Option<ObjectId>, Vec<Page>> = HashMap::new();
+
+        for page in pages {
+            if let Some(parent) = &page.parent
+                && !ids.contains(parent)
+            {
+                return Err(invalid_tree(format!(
+                    "The parent of page {} doesn't exist",
+                    page.id.id
+                )));
+            }
+
+            by_parent.entry(page.parent.clone()).or_default().push(page);
+        }
+
+        let roots = build_level(&mut by_parent, None, "")?;
+        let tree = Self { roots };
+
+        if tree.iter().count() != total {
+            return Err(invalid_tree(String::from(
+                "The parents of some pages form a cycle",
+            )));
+        }
+
+        Ok(tree)
+    }
+
+    pub fn roots(&self) -> &[PageNode] {
+        &self.roots
+    }
+
+    /// Every page, parents before their children.
+    pub fn iter(&self) -> impl Iterator<Item = &PageNode> {
+        let mut stack = self.roots.iter().rev().collect::<Vec<_>>();
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+
+            Some(node)
+        })
+    }
+
+    pub fn find(&self, id: &ObjectId) -> Option<&PageNode> {
+        self.iter().find(|node| node.page.id.id == *id)
+    }
+
+    /// Trailing slashes are ignored.
+    pub fn find_by_path(&self, path: &str) -> Option<&PageNode> {
+        let path = match path.trim_end_matches('/') {
+            "" => "/",
+            v => v,
+        };
+
+        self.iter().find(|node| node.path == path)
+    }
+
+    pub fn path_of(&self, id: &ObjectId) -> Option<&str> {
+        self.find(id).map(|node| node.path.as_str())
+    }
+
+    pub fn public_pages(&self) -> Vec<PublicPage> {
+        self.iter().map(PageNode::to_public).collect()
+    }
+}
+
+impl PageNode {
+    pub fn to_public(&self) -> PublicPage {
+        PublicPage {
+            id: PagePublicId::from(*self.page.id.guid),
+            path: self.path.clone(),
+            title: self.page.title.clone(),
+            layout: None,
+            visibility: self.page.visibility,
+        }
+    }
+}
+
+fn build_level(
+    by_parent: &mut HashMap<Option<ObjectId>, Vec<Page>>,
+    parent: Option<&ObjectId>,
+    parent_path: &str,
+) -> Result<Vec<PageNode>> {
+    let mut pages = by_parent.remove(&parent.cloned()).unwrap_or_default();
+    pages.sort_by(|a, b| (a.sort_index, &a.slug).cmp(&(b.sort_index, &b.slug)));
+
+    let mut slugs = HashSet::new();
+    let mut nodes = Vec::with_capacity(pages.len());
+
+    for page in pages {
+        let is_home = page.slug.is_empty() && parent.is_none();
+
+        if !is_home && !Slug::is_valid(&page.slug) {
+            return Err(invalid_tree(format!(
+                "Page {} has an invalid slug: {:?}",
+                page.id.id, page.slug
+            )));
+        }
+
+        if !slugs.insert(page.slug.clone()) {
+            return Err(invalid_tree(format!(
+                "Slug {:?} is used twice in {}",
+                page.slug,
+                if parent_path.is_empty() {
+                    "/"
+                } else {
+                    parent_path
+                }
+            )));
+        }
+
+        // Children of the home page are at the root, eg. `/about` rather than `//about`.
+        let path = if page.slug.is_empty() {
+            String::from("/")
+        } else {
+            format!("{}/{}", parent_path.trim_end_matches('/'), page.slug)
+        };
+
+        let children = build_level(by_parent, Some(&page.id.id), &path)?;
+
+        nodes.push(PageNode {
+            page,
+            path,
+            children,
+        });
+    }
+
+    Ok(nodes)
+}
+
+fn invalid_tree(reason: String) -> GlobalError {
+    GlobalError::InvalidPageTree(reason)
+}
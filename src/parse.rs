@@ -0,0 +1,81 @@
+//! Parsing JSON with or without rejecting unknown keys.
+//!
+//! Internal tooling parses leniently so it keeps working against newer or older payloads. The
+//! API gateway parses strictly, so a typo in a key (eg. `"limt"` in a [`CmsQuery`]) is an
+//! error instead of silently falling back to the default.
+//!
+//! Old names which are accepted as aliases aren't unknown. Keys inside untagged values, eg. a
+//! [`SimpleValue`](crate::value::SimpleValue) object, are data and never checked.
+//!
+//! [`CmsQuery`]: crate::request::CmsQuery
+
+use serde::{Deserializer, de::DeserializeOwned};
+use serde_ignored::Path;
+
+use crate::error::{GlobalError, Result};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Unknown keys are ignored.
+    #[default]
+    Lenient,
+    /// Unknown keys are a [`GlobalError::UnknownKeys`] listing every one of them.
+    Strict,
+}
+
+pub fn from_str<T: DeserializeOwned>(value: &str, mode: ParseMode) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_str(value);
+    let value = deserialize(&mut deserializer, mode)?;
+    deserializer.end()?;
+
+    Ok(value)
+}
+
+pub fn from_slice<T: DeserializeOwned>(value: &[u8], mode: ParseMode) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_slice(value);
+    let value = deserialize(&mut deserializer, mode)?;
+    deserializer.end()?;
+
+    Ok(value)
+}
+
+pub fn from_value<T: DeserializeOwned>(value: serde_json::Value, mode: ParseMode) -> Result<T> {
+    deserialize(value, mode)
+}
+
+fn deserialize<'de, T, D>(deserializer: D, mode: ParseMode) -> Result<T>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de, Error = serde_json::Error>,
+{
+    if mode == ParseMode::Lenient {
+        return Ok(T::deserialize(deserializer)?);
+    }
+
+    let mut unknown = Vec::new();
+    let value = serde_ignored::deserialize(deserializer, |path| unknown.push(path_name(&path)))?;
+
+    if unknown.is_empty() {
+        Ok(value)
+    } else {
+        Err(GlobalError::UnknownKeys(unknown.join(", ")))
+    }
+}
+
+/// The path as `filters.0.name`, without the Options and newtypes it went through.
+fn path_name(path: &Path) -> String {
+    let (parent, key) = match path {
+        Path::Root => return String::new(),
+        Path::Seq { parent, index } => (path_name(parent), index.to_string()),
+        Path::Map { parent, key } => (path_name(parent), key.clone()),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => return path_name(parent),
+    };
+
+    if parent.is_empty() {
+        key
+    } else {
+        format!("{parent}.{key}")
+    }
+}
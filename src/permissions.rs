@@ -0,0 +1,264 @@
+//! Evaluation of [`SchematicPermissions`] against the user making a request, and the site
+//! roles users are given.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    error::{GlobalError, Result},
+    schema::{Operations, PermissionsUser, SchematicPermissions},
+};
+
+/// The user making a request and the record it's for.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    /// None = Signed Out
+    pub user_id: Option<Uuid>,
+    pub roles: Vec<String>,
+    pub is_admin: bool,
+    /// The owner of the record being accessed. None = No Record / No Owner
+    pub owner_id: Option<Uuid>,
+}
+
+impl RequestContext {
+    /// A signed out visitor.
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    pub fn user(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_roles<I: IntoIterator<Item = S>, S: Into<String>>(mut self, roles: I) -> Self {
+        self.roles.extend(roles.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_owner(mut self, owner_id: Uuid) -> Self {
+        self.owner_id = Some(owner_id);
+        self
+    }
+
+    /// Add the names of the roles, and admin rights if one of them is [`Role::Admin`].
+    pub fn with_site_roles(mut self, roles: &[Role]) -> Self {
+        self.is_admin |= roles.contains(&Role::Admin);
+        self.roles
+            .extend(roles.iter().map(|v| v.name().to_string()));
+        self
+    }
+
+    pub fn is_owner(&self) -> bool {
+        self.user_id.is_some() && self.user_id == self.owner_id
+    }
+}
+
+impl PermissionsUser {
+    pub fn allows(&self, ctx: &RequestContext) -> bool {
+        ctx.is_admin
+            || match self {
+                Self::Anyone => true,
+                Self::Admin => false,
+                Self::Owner => ctx.is_owner(),
+                Self::Authenticated => ctx.user_id.is_some(),
+                Self::Role(role) => ctx.roles.contains(role),
+            }
+    }
+}
+
+impl SchematicPermissions {
+    /// Whether the user may perform the operation.
+    pub fn can(&self, op: Operations, ctx: &RequestContext) -> bool {
+        op.required_permissions()
+            .iter()
+            .all(|v| self.get(*v).allows(ctx))
+    }
+
+    pub fn get(&self, permission: Permission) -> &PermissionsUser {
+        match permission {
+            Permission::Read => &self.read,
+            Permission::Insert => &self.insert,
+            Permission::Update => &self.update,
+            Permission::Remove => &self.remove,
+        }
+    }
+}
+
+/// What a user is allowed to do with the records of a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Permission {
+    Read,
+    Insert,
+    Update,
+    Remove,
+}
+
+impl Permission {
+    pub const ALL: [Self; 4] = [Self::Read, Self::Insert, Self::Update, Self::Remove];
+}
+
+impl Operations {
+    /// Saves (upserts) need both the insert and update permission. Reference operations
+    /// need the update permission since they change the record. Unknown operations need every
+    /// permission.
+    pub fn required_permissions(&self) -> &'static [Permission] {
+        match self {
+            Self::Count
+            | Self::Get
+            | Self::Find
+            | Self::QueryReferenced
+            | Self::IsReferenced
+            | Self::Distinct
+            | Self::Aggregate => &[Permission::Read],
+
+            Self::Insert | Self::BulkInsert => &[Permission::Insert],
+
+            Self::Save | Self::BulkSave => &[Permission::Insert, Permission::Update],
+
+            Self::Update
+            | Self::BulkUpdate
+            | Self::InsertReference
+            | Self::RemoveReference
+            | Self::ReplaceReferences => &[Permission::Update],
+
+            Self::Remove | Self::BulkRemove | Self::Truncate => &[Permission::Remove],
+
+            Self::Unknown(_) => &[
+                Permission::Read,
+                Permission::Insert,
+                Permission::Update,
+                Permission::Remove,
+            ],
+        }
+    }
+}
+
+/// The role of a member of the site team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Role {
+    /// Everything, including the site settings.
+    Admin,
+    /// Every permission in every namespace, but can't truncate collections.
+    Editor,
+    /// Read, insert and update in every namespace.
+    Contributor,
+    Custom(CustomRole),
+}
+
+/// A role with its own grants. Its name can't be one of the [builtin](Role::BUILTIN_NAMES)
+/// roles', or it would be given their access wherever roles are checked by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedCustomRole")]
+pub struct CustomRole {
+    name: String,
+    pub grants: Vec<NamespaceGrant>,
+}
+
+#[derive(Deserialize)]
+struct UncheckedCustomRole {
+    name: String,
+    grants: Vec<NamespaceGrant>,
+}
+
+impl CustomRole {
+    pub fn new<N: Into<String>>(name: N, grants: Vec<NamespaceGrant>) -> Result<Self> {
+        let name = name.into();
+
+        if name.trim().is_empty() {
+            return Err(GlobalError::InvalidRole(String::from("the name is empty")));
+        }
+
+        if Role::BUILTIN_NAMES
+            .iter()
+            .any(|v| v.eq_ignore_ascii_case(name.trim()))
+        {
+            return Err(GlobalError::InvalidRole(format!(
+                "{name}: the name is reserved for a builtin role"
+            )));
+        }
+
+        Ok(Self { name, grants })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl TryFrom<UncheckedCustomRole> for CustomRole {
+    type Error = GlobalError;
+
+    fn try_from(value: UncheckedCustomRole) -> Result<Self> {
+        Self::new(value.name, value.grants)
+    }
+}
+
+/// Permissions given in a namespace (eg. `Blog`), or in every namespace with `*`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceGrant {
+    pub namespace: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl NamespaceGrant {
+    pub const ANY_NAMESPACE: &str = "*";
+
+    fn applies_to(&self, namespace: &str) -> bool {
+        self.namespace == Self::ANY_NAMESPACE || self.namespace == namespace
+    }
+}
+
+impl Role {
+    pub const BUILTIN_NAMES: [&str; 3] = ["admin", "editor", "contributor"];
+
+    /// The name used in [`PermissionsUser::Role`] and [`RequestContext::roles`].
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Admin => "admin",
+            Self::Editor => "editor",
+            Self::Contributor => "contributor",
+            Self::Custom(v) => &v.name,
+        }
+    }
+
+    /// The permissions the role has in the namespace, sorted.
+    pub fn permissions_in(&self, namespace: &str) -> Vec<Permission> {
+        match self {
+            Self::Admin | Self::Editor => Permission::ALL.to_vec(),
+            Self::Contributor => vec![Permission::Read, Permission::Insert, Permission::Update],
+            Self::Custom(v) => {
+                let mut permissions = v
+                    .grants
+                    .iter()
+                    .filter(|v| v.applies_to(namespace))
+                    .flat_map(|v| v.permissions.iter().copied())
+                    .collect::<Vec<_>>();
+
+                permissions.sort();
+                permissions.dedup();
+
+                permissions
+            }
+        }
+    }
+
+    /// Whether the role allows the operation on collections of the namespace. Only admins are
+    /// granted unknown operations.
+    pub fn grants(&self, op: Operations, namespace: &str) -> bool {
+        match (self, &op) {
+            (Self::Admin, _) => true,
+            (Self::Editor, Operations::Truncate) | (_, Operations::Unknown(_)) => false,
+            _ => {
+                let permissions = self.permissions_in(namespace);
+
+                op.required_permissions()
+                    .iter()
+                    .all(|v| permissions.contains(v))
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Draft vs live content semantics shared by the CMS, renderer and editor.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{schema::SchematicFieldKey, value::SimpleValue};
+
+pub type RowFields = HashMap<SchematicFieldKey, SimpleValue>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum PublishState {
+    #[default]
+    Draft,
+    Published,
+    /// Will become published at the specified time.
+    Scheduled(OffsetDateTime),
+    Archived,
+}
+
+impl PublishState {
+    /// Is the content visible on the live site at `now`?
+    pub fn is_live_at(&self, now: OffsetDateTime) -> bool {
+        match *self {
+            Self::Published => true,
+            Self::Scheduled(at) => at <= now,
+            Self::Draft | Self::Archived => false,
+        }
+    }
+
+    /// Turns a scheduled state which has passed into [`PublishState::Published`].
+    pub fn resolve(self, now: OffsetDateTime) -> Self {
+        match self {
+            Self::Scheduled(at) if at <= now => Self::Published,
+            v => v,
+        }
+    }
+
+    pub fn is_draft(&self) -> bool {
+        matches!(self, Self::Draft)
+    }
+
+    pub fn is_archived(&self) -> bool {
+        matches!(self, Self::Archived)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowRevision {
+    pub revision_id: Uuid,
+    pub data: RowFields,
+    /// Who created the revision. None = System
+    pub created_by: Option<Uuid>,
+    pub created_at: OffsetDateTime,
+}
+
+impl RowRevision {
+    pub fn new(data: RowFields, created_by: Option<Uuid>) -> Self {
+        Self::new_at(data, created_by, Uuid::now_v7(), OffsetDateTime::now_utc())
+    }
+
+    /// A revision with the given id, created at `at`.
+    pub fn new_at(
+        data: RowFields,
+        created_by: Option<Uuid>,
+        revision_id: Uuid,
+        at: OffsetDateTime,
+    ) -> Self {
+        Self {
+            revision_id,
+            data,
+            created_by,
+            created_at: at,
+        }
+    }
+
+    /// The changes needed to go from this revision to `newer`.
+    pub fn diff(&self, newer: &RowRevision) -> Vec<FieldChange> {
+        diff_fields(&self.data, &newer.data)
+    }
+
+    /// Restore the whole row back to this revision.
+    pub fn restore(&self) -> RowFields {
+        self.data.clone()
+    }
+
+    /// Restore only the specified fields back to this revision.
+    ///
+    /// Fields which didn't exist in this revision are removed.
+    pub fn restore_fields(&self, current: &mut RowFields, keys: &[SchematicFieldKey]) {
+        for key in keys {
+            match self.data.get(key) {
+                Some(value) => {
+                    current.insert(key.clone(), value.clone());
+                }
+                None => {
+                    current.remove(key);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: SchematicFieldKey,
+    /// None = Field was added
+    pub before: Option<SimpleValue>,
+    /// None = Field was removed
+    pub after: Option<SimpleValue>,
+}
+
+impl FieldChange {
+    pub fn is_added(&self) -> bool {
+        self.before.is_none()
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.after.is_none()
+    }
+}
+
+/// Compute the changed fields between two field maps, sorted by field name.
+pub fn diff_fields(before: &RowFields, after: &RowFields) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+
+    for (key, old) in before {
+        match after.get(key) {
+            Some(new) if new == old => (),
+            new => changes.push(FieldChange {
+                field: key.clone(),
+                before: Some(old.clone()),
+                after: new.cloned(),
+            }),
+        }
+    }
+
+    for (key, new) in after {
+        if !before.contains_key(key) {
+            changes.push(FieldChange {
+                field: key.clone(),
+                before: None,
+                after: Some(new.clone()),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.field.as_str().cmp(b.field.as_str()));
+
+    changes
+}
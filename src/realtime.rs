@@ -0,0 +1,124 @@
+//! The WebSocket protocol for live updates of collections.
+//!
+//! Messages are sent as JSON text frames.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Result,
+    filter::FilterExpr,
+    id::SchemaDataPublicId,
+    request::{CmsUpdateDataRow, FieldPatch},
+    response::{ApiErrorResponse, CmsRowResponse},
+    schema::SchematicFieldKey,
+    uuid::CollectionName,
+};
+
+/// Sent by the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ClientMessage {
+    /// Receive changes to the rows of a collection. Subscribing again replaces the filters.
+    Subscribe {
+        collection: CollectionName,
+        /// Only rows matching these are sent. None = Every Row
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filters: Option<FilterExpr>,
+    },
+    Unsubscribe {
+        collection: CollectionName,
+    },
+    Ping,
+}
+
+/// Sent by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ServerMessage {
+    Subscribed {
+        collection: CollectionName,
+    },
+    /// A row was inserted, or changed so it now matches the filters.
+    RowInserted {
+        collection: CollectionName,
+        row: CmsRowResponse,
+    },
+    RowPatched {
+        collection: CollectionName,
+        id: SchemaDataPublicId,
+        patches: HashMap<String, FieldPatch>,
+        /// The revision of the row after the patches.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        revision: Option<u64>,
+    },
+    /// A row was removed, or changed so it no longer matches the filters.
+    RowRemoved {
+        collection: CollectionName,
+        id: SchemaDataPublicId,
+    },
+    Error(ApiErrorResponse),
+    Pong,
+}
+
+impl ServerMessage {
+    /// The collection the message is about. None for messages about the connection.
+    pub fn collection(&self) -> Option<&CollectionName> {
+        match self {
+            Self::Subscribed { collection }
+            | Self::RowInserted { collection, .. }
+            | Self::RowPatched { collection, .. }
+            | Self::RowRemoved { collection, .. } => Some(collection),
+            Self::Error(_) | Self::Pong => None,
+        }
+    }
+
+    /// Update a local copy of the subscribed rows. Rows are told apart by their `_id`.
+    ///
+    /// Patches to rows which aren't in `rows` are ignored.
+    pub fn apply_to(&self, rows: &mut Vec<CmsRowResponse>) -> Result<()> {
+        match self {
+            Self::RowInserted { row, .. } => {
+                let id = row_id(row).map(str::to_string);
+
+                rows.retain(|v| id.is_none() || row_id(v) != id.as_deref());
+                rows.push(row.clone());
+            }
+            Self::RowPatched {
+                id,
+                patches,
+                revision,
+                ..
+            } => {
+                let update = CmsUpdateDataRow {
+                    id: *id,
+                    patches: patches.clone(),
+                    expected_revision: None,
+                    schema_version: None,
+                };
+                let id = id.to_string();
+
+                if let Some(row) = rows.iter_mut().find(|v| row_id(v) == Some(&id)) {
+                    update.apply_to(&mut row.fields)?;
+
+                    if let (Some(meta), Some(revision)) = (&mut row.meta, revision) {
+                        meta.revision = *revision;
+                    }
+                }
+            }
+            Self::RowRemoved { id, .. } => {
+                let id = id.to_string();
+
+                rows.retain(|v| row_id(v) != Some(&id));
+            }
+            Self::Subscribed { .. } | Self::Error(_) | Self::Pong => (),
+        }
+
+        Ok(())
+    }
+}
+
+fn row_id(row: &CmsRowResponse) -> Option<&str> {
+    row.fields.get(&SchematicFieldKey::Id)?.as_text()
+}
@@ -0,0 +1,133 @@
+//! Values and keys of the cache shared by the API replicas.
+//!
+//! Cached values are JSON after a [`CACHE_VERSION`] byte. A value written with another version
+//! fails to parse, so callers treat it as a miss and replace it instead of reading data in a
+//! shape they don't know.
+
+use ::redis::{ErrorKind, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs, Value};
+use serde::{Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{GlobalError, Result},
+    filter::Filter,
+    id::WebsitePublicId,
+    request::{CmsQuery, projection::Projection},
+    response::{CmsRowResponse, ListResponse, PublicSchema},
+    schema::{Schematic, SortOrder},
+    uuid::CollectionName,
+};
+
+/// Written before every cached value. Bump it when the JSON of a cached type changes in a way
+/// older replicas can't read.
+pub const CACHE_VERSION: u8 = 1;
+
+/// The first part of every key.
+pub const KEY_PREFIX: &str = "webby";
+
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut bytes = vec![CACHE_VERSION];
+    serde_json::to_writer(&mut bytes, value)?;
+    Ok(bytes)
+}
+
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    match bytes.split_first() {
+        Some((&CACHE_VERSION, json)) => Ok(serde_json::from_slice(json)?),
+        Some((version, _)) => Err(GlobalError::ParseError(format!(
+            "Cache version {version} isn't {CACHE_VERSION}"
+        ))),
+        None => Err(GlobalError::ParseError(String::from("Empty cache value"))),
+    }
+}
+
+/// The key of a collection's [`Schematic`].
+pub fn schema_cache_key(site: WebsitePublicId, collection: &CollectionName) -> String {
+    format!("{KEY_PREFIX}:schema:{site}:{}", key_part(collection))
+}
+
+/// The key of a collection's [`PublicSchema`].
+pub fn public_schema_cache_key(site: WebsitePublicId, collection: &CollectionName) -> String {
+    format!("{KEY_PREFIX}:public-schema:{site}:{}", key_part(collection))
+}
+
+/// The key of a page of rows.
+pub fn list_cache_key(
+    site: WebsitePublicId,
+    collection: &CollectionName,
+    query: &CmsQuery,
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct QueryKey<'a> {
+        filters: &'a Option<Vec<Filter>>,
+        sort: &'a Option<Vec<(String, SortOrder)>>,
+        columns: &'a Option<Projection>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        include_files: bool,
+    }
+
+    let key = QueryKey {
+        filters: &query.filters,
+        sort: &query.sort,
+        columns: &query.columns,
+        limit: query.limit,
+        offset: query.offset,
+        include_files: query.include_files,
+    };
+
+    let hash = Sha256::digest(serde_json::to_vec(&key)?);
+
+    Ok(format!(
+        "{}:{}",
+        list_cache_prefix(site, collection),
+        hex::encode(&hash[..16])
+    ))
+}
+
+/// A `SCAN`/`KEYS` pattern matching every page of rows of a collection, to invalidate them
+/// when a row changes.
+pub fn list_cache_pattern(site: WebsitePublicId, collection: &CollectionName) -> String {
+    format!("{}:*", list_cache_prefix(site, collection))
+}
+
+fn list_cache_prefix(site: WebsitePublicId, collection: &CollectionName) -> String {
+    format!("{KEY_PREFIX}:rows:{site}:{}", key_part(collection))
+}
+
+/// The collection with `.` instead of the `:` after its namespace, so it's a single part of the
+/// key. Names can't contain dots.
+fn key_part(collection: &CollectionName) -> String {
+    collection.to_string().replace(':', ".")
+}
+
+macro_rules! cache_value {
+    ($($type:ty),+) => {$(
+        impl ToRedisArgs for $type {
+            fn write_redis_args<W: ?Sized + RedisWrite>(&self, out: &mut W) {
+                out.write_arg(&encode(self).expect("cached values serialize to JSON"));
+            }
+        }
+
+        impl FromRedisValue for $type {
+            fn from_redis_value(v: &Value) -> RedisResult<Self> {
+                match v {
+                    Value::BulkString(bytes) => decode(bytes).map_err(|e| {
+                        RedisError::from((
+                            ErrorKind::TypeError,
+                            "Invalid cached value",
+                            e.to_string(),
+                        ))
+                    }),
+                    v => Err(RedisError::from((
+                        ErrorKind::TypeError,
+                        "Response was of incompatible type",
+                        format!("{v:?} isn't a cached {}", stringify!($type)),
+                    ))),
+                }
+            }
+        }
+    )+};
+}
+
+cache_value!(PublicSchema, Schematic, ListResponse<CmsRowResponse>);
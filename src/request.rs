@@ -1,16 +1,32 @@
-use std::collections::HashMap;
+pub mod projection;
+pub mod query;
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
 
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
+use self::projection::Projection;
 use crate::{
-    filter::Filter,
-    schema::{SchemaView, SchematicFieldType},
+    error::{GlobalError, Result},
+    filter::{Filter, FilterExpr},
+    id::SchemaDataPublicId,
+    idempotency::IdempotencyKey,
+    response::{Cursor, RowMeta},
+    schema::{
+        Operations, SchemaView, SchematicFieldKey, SchematicFieldType, SortOrder,
+        version::SchemaVersion,
+    },
     uuid::CollectionName,
-    value::SimpleValue,
+    value::{Number, SimpleValue},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", from = "CmsCreateRepr")]
 pub struct CmsCreate {
     pub id: CollectionName,
     pub name: String,
@@ -25,14 +41,48 @@ pub struct CmsCreate {
     pub data: Option<HashMap<String, Vec<SimpleValue>>>,
 }
 
+/// [`CmsCreate`] without the flattened [`CmsUpdate`], which would hide unknown keys from a
+/// [strict](crate::parse::ParseMode::Strict) parse.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CmsCreateRepr {
+    id: CollectionName,
+    name: String,
+    is_external: bool,
+    is_single: bool,
+    views: Option<Vec<SchemaView>>,
+    columns: Option<Vec<CmsCreateDataColumn>>,
+    data: Option<HashMap<String, Vec<SimpleValue>>>,
+}
+
+impl From<CmsCreateRepr> for CmsCreate {
+    fn from(value: CmsCreateRepr) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            is_external: value.is_external,
+            is_single: value.is_single,
+            update: CmsUpdate { views: value.views },
+            columns: value.columns,
+            data: value.data,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct CmsQuery {
     pub filters: Option<Vec<Filter>>,
-    // sort[name]=ASC
-    pub sort: Option<HashMap<String, String>>,
+    /// Highest priority first. `sort[name]=ASC`, and an object in the same order in JSON.
+    #[serde(default, with = "sort_fields")]
+    #[cfg_attr(
+        feature = "ts",
+        ts(type = "{ [key in string]?: \"ASC\" | \"DESC\" } | null")
+    )]
+    pub sort: Option<Vec<(String, SortOrder)>>,
     /// Columns which should be returned
-    pub columns: Option<String>,
+    pub columns: Option<Projection>,
 
     pub limit: Option<u64>,
     pub offset: Option<u64>,
@@ -40,20 +90,474 @@ pub struct CmsQuery {
     pub include_files: bool,
 }
 
+/// How a sort order is written in queries.
+fn sort_order_name(order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Ascending => "ASC",
+        SortOrder::Descending => "DESC",
+    }
+}
+
+/// [`CmsQuery::sort`] as an object, keeping the order of its keys. Orders are read
+/// case-insensitively.
+mod sort_fields {
+    use std::fmt::{self, Formatter};
+
+    use serde::{
+        Deserializer, Serializer,
+        de::{Error, MapAccess, Visitor},
+    };
+
+    use super::sort_order_name;
+    use crate::schema::SortOrder;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<(String, SortOrder)>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(fields) => serializer.collect_map(
+                fields
+                    .iter()
+                    .map(|(field, order)| (field, sort_order_name(*order))),
+            ),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<(String, SortOrder)>>, D::Error> {
+        deserializer.deserialize_option(SortVisitor)
+    }
+
+    struct SortVisitor;
+
+    impl<'de> Visitor<'de> for SortVisitor {
+        type Value = Option<Vec<(String, SortOrder)>>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("an object of fields to ASC or DESC")
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_map(self)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut fields = Vec::<(String, SortOrder)>::new();
+
+            while let Some((field, order)) = map.next_entry::<String, String>()? {
+                let order = if order.eq_ignore_ascii_case("asc") {
+                    SortOrder::Ascending
+                } else if order.eq_ignore_ascii_case("desc") {
+                    SortOrder::Descending
+                } else {
+                    return Err(A::Error::custom(format!(
+                        "Unknown sort order {order}, expected ASC or DESC"
+                    )));
+                };
+
+                fields.retain(|(v, _)| *v != field);
+                fields.push((field, order));
+            }
+
+            Ok(Some(fields))
+        }
+    }
+}
+
+/// Group rows and compute aggregates over them.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsAggregate {
+    /// Fields to group by. Empty = One group with every row
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    pub aggregations: Vec<Aggregation>,
+    pub filters: Option<FilterExpr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct Aggregation {
+    pub function: AggregateFunction,
+    /// None is only valid for Count, which then counts rows.
+    pub field: Option<String>,
+    /// The name of the result. Defaults to [`Aggregation::name`].
+    pub alias: Option<String>,
+}
+
+impl Aggregation {
+    pub fn new<S: Into<String>>(function: AggregateFunction, field: S) -> Self {
+        Self {
+            function,
+            field: Some(field.into()),
+            alias: None,
+        }
+    }
+
+    pub fn count() -> Self {
+        Self {
+            function: AggregateFunction::Count,
+            field: None,
+            alias: None,
+        }
+    }
+
+    /// The key the result is stored under in [`AggregateGroup::values`](crate::response::AggregateGroup::values).
+    pub fn name(&self) -> String {
+        match (&self.alias, &self.field) {
+            (Some(alias), _) => alias.clone(),
+            (None, Some(field)) => format!("{}_{field}", self.function),
+            (None, None) => self.function.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    pub fn requires_field(self) -> bool {
+        !matches!(self, Self::Count)
+    }
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Count => "count",
+            Self::Sum => "sum",
+            Self::Avg => "avg",
+            Self::Min => "min",
+            Self::Max => "max",
+        })
+    }
+}
+
+/// Cursor based pagination. Used instead of `limit`/`offset` for large collections.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CursorQuery {
+    /// None = First Page
+    pub cursor: Option<Cursor>,
+    pub limit: Option<u64>,
+}
+
+/// Insert many rows at once. Every row is inserted on its own, see
+/// [`BulkOperationResponse`](crate::response::BulkOperationResponse).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsBulkInsert {
+    pub rows: Vec<HashMap<SchematicFieldKey, SimpleValue>>,
+    /// The schema version the client was built against. None = Don't Check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<SchemaVersion>,
+    /// Sending the request again with the same key returns the first response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+impl CmsBulkInsert {
+    pub const OPERATION: Operations = Operations::BulkInsert;
+}
+
+/// Update many rows at once, only changing the given fields of each.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsBulkUpdate {
+    pub rows: Vec<CmsBulkUpdateRow>,
+    /// The schema version the client was built against. None = Don't Check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<SchemaVersion>,
+    /// Sending the request again with the same key returns the first response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+impl CmsBulkUpdate {
+    pub const OPERATION: Operations = Operations::BulkUpdate;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsBulkUpdateRow {
+    pub id: SchemaDataPublicId,
+    /// Fields without a patch are left as they are.
+    pub patches: HashMap<String, FieldPatch>,
+    /// Fail with a conflict if the row has been changed since this revision. None = Always Update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_revision: Option<u64>,
+}
+
+impl CmsBulkUpdateRow {
+    /// Apply every patch to the fields of the row. Nothing is changed if a patch fails.
+    pub fn apply_to(&self, fields: &mut HashMap<SchematicFieldKey, SimpleValue>) -> Result<()> {
+        apply_patches(&self.patches, fields)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsBulkRemove {
+    pub ids: Vec<SchemaDataPublicId>,
+    /// Sending the request again with the same key returns the first response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+impl CmsBulkRemove {
+    pub const OPERATION: Operations = Operations::BulkRemove;
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct CmsUpdate {
     pub views: Option<Vec<SchemaView>>,
 }
 
+/// Take soft-deleted rows out of the trash.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CmsRestoreRequest {
+    pub ids: Vec<SchemaDataPublicId>,
+}
+
+/// Permanently delete rows which are in the trash. Rows which aren't are left alone.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsPurgeRequest {
+    #[serde(default)]
+    pub ids: Vec<SchemaDataPublicId>,
+    /// Also purge every row deleted before this, eg. to empty the trash after 30 days.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub deleted_before: Option<OffsetDateTime>,
+}
+
+impl CmsPurgeRequest {
+    /// Whether the row is purged by this request.
+    pub fn matches(&self, id: SchemaDataPublicId, meta: &RowMeta) -> bool {
+        let Some(deleted_at) = meta.deleted_at else {
+            return false;
+        };
+
+        self.ids.contains(&id) || self.deleted_before.is_some_and(|v| deleted_at < v)
+    }
+}
+
+/// List the rows in the trash, most recently deleted first.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TrashQuery {
+    pub filters: Option<Vec<Filter>>,
+    /// None = First Page
+    pub cursor: Option<Cursor>,
+    pub limit: Option<u64>,
+    /// Only rows deleted after this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub deleted_after: Option<OffsetDateTime>,
+}
+
+/// See [`CmsUpdateDataRow`] to clear fields or change them relative to their value.
 #[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct CmsUpdateDataCell {
+    #[serde(alias = "field_name")]
     pub field_name: String,
     pub value: Option<SimpleValue>,
 }
 
+/// A change to a single field of a row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(tag = "type", content = "value")]
+pub enum FieldPatch {
+    Set(SimpleValue),
+    /// Clear the field.
+    Unset,
+    /// Add to a Number. An empty field counts as 0.
+    Increment(Number),
+    /// Add a value, or every value of a list, to the end of a list.
+    Append(SimpleValue),
+    /// Remove every occurrence of a value, or of every value of a list, from a list.
+    RemoveFromList(SimpleValue),
+}
+
+impl FieldPatch {
+    /// The new value of a field which is currently `current`. None = Empty
+    pub fn apply(&self, current: Option<&SimpleValue>) -> Result<Option<SimpleValue>> {
+        Ok(match self {
+            Self::Set(v) => Some(v.clone()),
+            Self::Unset => None,
+            Self::Increment(by) => {
+                let current = match current {
+                    Some(v) => v.try_as_number()?,
+                    None => Number::Byte(0),
+                };
+
+                let value = current.checked_add(*by).ok_or_else(|| {
+                    GlobalError::ParseError(format!("Incrementing {current} by {by} overflows"))
+                })?;
+
+                Some(SimpleValue::Number(value))
+            }
+            Self::Append(value) => Some(append(current, value)?),
+            Self::RemoveFromList(value) => match current {
+                Some(current) => Some(remove_from_list(current, value)?),
+                None => None,
+            },
+        })
+    }
+}
+
+fn append(current: Option<&SimpleValue>, value: &SimpleValue) -> Result<SimpleValue> {
+    Ok(match (current, value) {
+        (None, SimpleValue::Text(v)) => SimpleValue::ListString(vec![v.clone()]),
+        (None, SimpleValue::Number(v)) => SimpleValue::ListNumber(vec![*v]),
+        (None, v @ (SimpleValue::ListString(_) | SimpleValue::ListNumber(_))) => v.clone(),
+        (None, SimpleValue::ArrayUnknown(v)) => SimpleValue::ArrayUnknown(v.clone()),
+        (None, v) => SimpleValue::ArrayUnknown(vec![v.clone().into_json()]),
+
+        (Some(SimpleValue::ListString(list)), SimpleValue::Text(v)) => {
+            SimpleValue::ListString(list.iter().chain([v]).cloned().collect())
+        }
+        (Some(SimpleValue::ListString(list)), SimpleValue::ListString(v)) => {
+            SimpleValue::ListString(list.iter().chain(v).cloned().collect())
+        }
+        (Some(SimpleValue::ListNumber(list)), SimpleValue::Number(v)) => {
+            SimpleValue::ListNumber(list.iter().chain([v]).copied().collect())
+        }
+        (Some(SimpleValue::ListNumber(list)), SimpleValue::ListNumber(v)) => {
+            SimpleValue::ListNumber(list.iter().chain(v).copied().collect())
+        }
+        (Some(SimpleValue::ArrayUnknown(list)), SimpleValue::ArrayUnknown(v)) => {
+            SimpleValue::ArrayUnknown(list.iter().chain(v).cloned().collect())
+        }
+        (Some(SimpleValue::ArrayUnknown(list)), v) => {
+            let mut list = list.clone();
+            list.push(v.clone().into_json());
+            SimpleValue::ArrayUnknown(list)
+        }
+
+        (Some(current), v) => {
+            return Err(GlobalError::type_mismatch(
+                current.type_name(),
+                v.type_name(),
+            ));
+        }
+    })
+}
+
+fn remove_from_list(current: &SimpleValue, value: &SimpleValue) -> Result<SimpleValue> {
+    Ok(match (current, value) {
+        (SimpleValue::ListString(list), SimpleValue::Text(v)) => {
+            SimpleValue::ListString(list.iter().filter(|i| *i != v).cloned().collect())
+        }
+        (SimpleValue::ListString(list), SimpleValue::ListString(v)) => {
+            SimpleValue::ListString(list.iter().filter(|i| !v.contains(i)).cloned().collect())
+        }
+        (SimpleValue::ListNumber(list), SimpleValue::Number(v)) => {
+            SimpleValue::ListNumber(list.iter().filter(|i| !i.value_eq(v)).copied().collect())
+        }
+        (SimpleValue::ListNumber(list), SimpleValue::ListNumber(v)) => SimpleValue::ListNumber(
+            list.iter()
+                .filter(|i| !v.iter().any(|v| i.value_eq(v)))
+                .copied()
+                .collect(),
+        ),
+        (SimpleValue::ArrayUnknown(list), v) => {
+            let v = v.clone().into_json();
+
+            SimpleValue::ArrayUnknown(list.iter().filter(|i| **i != v).cloned().collect())
+        }
+        (current, v) => {
+            return Err(GlobalError::type_mismatch(
+                current.type_name(),
+                v.type_name(),
+            ));
+        }
+    })
+}
+
+/// A partial update of a row. Fields without a patch are left as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CmsUpdateDataRow {
+    pub id: SchemaDataPublicId,
+    pub patches: HashMap<String, FieldPatch>,
+    /// Fail with a conflict if the row has been changed since this revision. None = Always Update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_revision: Option<u64>,
+    /// The schema version the client was built against. None = Don't Check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<SchemaVersion>,
+}
+
+impl CmsUpdateDataRow {
+    /// Apply every patch to the fields of the row. Nothing is changed if a patch fails.
+    pub fn apply_to(&self, fields: &mut HashMap<SchematicFieldKey, SimpleValue>) -> Result<()> {
+        apply_patches(&self.patches, fields)
+    }
+}
+
+fn apply_patches(
+    patches: &HashMap<String, FieldPatch>,
+    fields: &mut HashMap<SchematicFieldKey, SimpleValue>,
+) -> Result<()> {
+    let mut changes = Vec::with_capacity(patches.len());
+
+    for (name, patch) in patches {
+        changes.push((name, patch.apply(fields.get(name.as_str()))?));
+    }
+
+    for (name, value) in changes {
+        let key = SchematicFieldKey::from(name.as_str());
+
+        match value {
+            Some(v) => fields.insert(key, v),
+            None => fields.remove(&key),
+        };
+    }
+
+    Ok(())
+}
+
 // Column
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct CmsCreateDataColumn {
     pub id: String,
@@ -66,6 +570,7 @@ pub struct CmsCreateDataColumn {
 // Tags
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct CmsCreateDataColumnTag {
     pub tag: String,
 }
@@ -0,0 +1,300 @@
+//! The `columns` of a [`CmsQuery`](super::CmsQuery).
+
+use std::{
+    convert::Infallible,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::Result,
+    response::CmsRowResponse,
+    schema::{
+        SchematicFieldKey,
+        path::{FieldPath, PathSegment},
+    },
+    value::SimpleValue,
+};
+
+/// Which fields to return, written as a comma separated list, eg. `name,address.city,-notes`.
+///
+/// - `*` selects every field, which is also the default.
+/// - `-field` removes a field. A list of only removals starts from every field.
+/// - A [`FieldPath`] such as `object.sub` or `items[0]` selects (or removes) a value inside an
+///   Object or Array field.
+///
+/// `_id` is always returned so rows can still be told apart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(from = "String", into = "String")]
+pub struct Projection {
+    wildcard: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl Projection {
+    /// Every field.
+    pub fn all() -> Self {
+        Self {
+            wildcard: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Build from the entries of the list, eg. `["name", "-notes"]`.
+    pub fn fields<I: IntoIterator<Item = S>, S: AsRef<str>>(entries: I) -> Self {
+        let mut projection = Self {
+            wildcard: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+
+        for entry in entries {
+            let entry = entry.as_ref().trim();
+
+            if entry.is_empty() {
+                continue;
+            } else if entry == "*" {
+                projection.wildcard = true;
+            } else if let Some(path) = entry.strip_prefix('-') {
+                projection.exclude.push(path.trim().to_string());
+            } else {
+                projection.include.push(entry.to_string());
+            }
+        }
+
+        if projection.include.is_empty() {
+            projection.wildcard = true;
+        }
+
+        if projection.wildcard {
+            projection.include.clear();
+        }
+
+        projection
+    }
+
+    /// Parse a comma separated list. Empty entries are skipped.
+    pub fn parse(value: &str) -> Self {
+        Self::fields(value.split(','))
+    }
+
+    /// Remove a field or path from the selection.
+    pub fn exclude<S: Into<String>>(mut self, path: S) -> Self {
+        self.exclude.push(path.into());
+        self
+    }
+
+    /// Whether every field is returned as is.
+    pub fn is_all(&self) -> bool {
+        self.wildcard && self.exclude.is_empty()
+    }
+
+    /// The selected fields. Empty if it starts from every field (`*`).
+    pub fn keys(&self) -> Vec<SchematicFieldKey> {
+        top_level_keys(&self.include)
+    }
+
+    /// The fields which are removed completely or have a path inside them removed.
+    pub fn excluded_keys(&self) -> Vec<SchematicFieldKey> {
+        top_level_keys(&self.exclude)
+    }
+
+    /// Every selected and removed path. Fails if one isn't a valid [`FieldPath`].
+    pub fn paths(&self) -> Result<Vec<FieldPath>> {
+        self.include
+            .iter()
+            .chain(&self.exclude)
+            .map(|v| FieldPath::parse(v))
+            .collect()
+    }
+
+    /// Whether the field is returned, fully or partly.
+    pub fn selects(&self, key: &str) -> bool {
+        if key == SchematicFieldKey::Id.as_str() {
+            return true;
+        }
+
+        if self.exclude.iter().any(|v| v == key) {
+            return false;
+        }
+
+        self.wildcard || self.include.iter().any(|v| top_level(v) == key)
+    }
+
+    /// A copy of the row with only the selected fields. Files are kept.
+    pub fn apply(&self, row: &CmsRowResponse) -> CmsRowResponse {
+        let fields = row
+            .fields
+            .iter()
+            .filter(|(key, _)| self.selects(key.as_str()))
+            .map(|(key, value)| (key.clone(), self.apply_to_value(key.as_str(), value)))
+            .collect();
+
+        CmsRowResponse {
+            files: row.files.clone(),
+            fields,
+            meta: row.meta,
+            seo: row.seo.clone(),
+        }
+    }
+
+    fn apply_to_value(&self, key: &str, value: &SimpleValue) -> SimpleValue {
+        let mut json = match value {
+            SimpleValue::ObjectUnknown(v) => v.clone(),
+            SimpleValue::ArrayUnknown(v) => Value::Array(v.clone()),
+            v => return v.clone(),
+        };
+
+        if !self.wildcard && !self.include.iter().any(|v| v == key) {
+            json = pick(&json, &sub_paths(&self.include, key));
+        }
+
+        for path in sub_paths(&self.exclude, key) {
+            remove(&mut json, &path);
+        }
+
+        match json {
+            Value::Array(v) => SimpleValue::ArrayUnknown(v),
+            v => SimpleValue::ObjectUnknown(v),
+        }
+    }
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+fn top_level(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+fn top_level_keys(paths: &[String]) -> Vec<SchematicFieldKey> {
+    let mut keys = Vec::<SchematicFieldKey>::new();
+
+    for key in paths.iter().map(|v| top_level(v)) {
+        if !keys.iter().any(|v| v == &key) {
+            keys.push(SchematicFieldKey::from(key));
+        }
+    }
+
+    keys
+}
+
+/// The segments of every path inside the field. Invalid paths are skipped.
+fn sub_paths(paths: &[String], key: &str) -> Vec<Vec<PathSegment>> {
+    paths
+        .iter()
+        .filter(|v| top_level(v) == key)
+        .filter_map(|v| FieldPath::parse(v).ok())
+        .filter(|v| !v.segments().is_empty())
+        .map(|v| v.segments().to_vec())
+        .collect()
+}
+
+/// Keep only what the paths lead to. Picked array items keep their order.
+fn pick(value: &Value, paths: &[Vec<PathSegment>]) -> Value {
+    let inner = |segment: &PathSegment| {
+        paths
+            .iter()
+            .filter(|v| v[0] == *segment)
+            .map(|v| v[1..].to_vec())
+            .collect::<Vec<_>>()
+    };
+
+    let pick_inner = |value: &Value, inner: Vec<Vec<PathSegment>>| {
+        if inner.is_empty() {
+            None
+        } else if inner.iter().any(|v| v.is_empty()) {
+            Some(value.clone())
+        } else {
+            Some(pick(value, &inner))
+        }
+    };
+
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let picked = pick_inner(value, inner(&PathSegment::Key(key.clone())))?;
+                    Some((key.clone(), picked))
+                })
+                .collect(),
+        ),
+        Value::Array(array) => Value::Array(
+            array
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| pick_inner(value, inner(&PathSegment::Index(i))))
+                .collect(),
+        ),
+        v => v.clone(),
+    }
+}
+
+fn remove(value: &mut Value, path: &[PathSegment]) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+
+    match (value, first) {
+        (Value::Object(object), PathSegment::Key(key)) if rest.is_empty() => {
+            object.remove(key);
+        }
+        (Value::Array(array), PathSegment::Index(i)) if rest.is_empty() && *i < array.len() => {
+            array.remove(*i);
+        }
+        (Value::Object(object), PathSegment::Key(key)) => {
+            if let Some(v) = object.get_mut(key) {
+                remove(v, rest);
+            }
+        }
+        (Value::Array(array), PathSegment::Index(i)) => {
+            if let Some(v) = array.get_mut(*i) {
+                remove(v, rest);
+            }
+        }
+        _ => (),
+    }
+}
+
+impl Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .wildcard
+            .then(|| String::from("*"))
+            .into_iter()
+            .chain(self.include.iter().cloned())
+            .chain(self.exclude.iter().map(|v| format!("-{v}")));
+
+        f.write_str(&entries.collect::<Vec<_>>().join(","))
+    }
+}
+
+impl FromStr for Projection {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl From<String> for Projection {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<Projection> for String {
+    fn from(value: Projection) -> Self {
+        value.to_string()
+    }
+}
@@ -0,0 +1,210 @@
+//! Fluent construction of a [`CmsQuery`].
+
+use url::form_urlencoded;
+
+use super::{CmsQuery, projection::Projection, sort_order_name};
+use crate::{
+    error::{GlobalError, Result},
+    filter::{Filter, FilterConditionType, FilterValue},
+    geo::GeoPoint,
+    schema::{SchemaFieldMap, SchematicFieldKey, SortOrder, path::FieldPath},
+};
+
+/// Builds a [`CmsQuery`], eg.
+/// `CmsQueryBuilder::new().filter("age", Gte, 18).sort_desc("_createdAt").limit(50)`
+#[derive(Debug, Default, Clone)]
+pub struct CmsQueryBuilder {
+    filters: Vec<Filter>,
+    sort: Vec<(String, SortOrder)>,
+    columns: Option<Projection>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    include_files: bool,
+}
+
+impl CmsQueryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn filter<N: Into<String>, V: Into<FilterValue>>(
+        mut self,
+        name: N,
+        cond: FilterConditionType,
+        value: V,
+    ) -> Self {
+        self.filters.push(Filter::new(name, cond, value));
+        self
+    }
+
+    /// Filter on a value inside an Object or Array field.
+    pub fn filter_path<V: Into<FilterValue>>(
+        mut self,
+        path: FieldPath,
+        cond: FilterConditionType,
+        value: V,
+    ) -> Self {
+        self.filters.push(Filter::path(path, cond, value));
+        self
+    }
+
+    /// Rows with a Location within `radius` meters of the center.
+    pub fn near<N: Into<String>>(mut self, name: N, center: GeoPoint, radius: f64) -> Self {
+        self.filters.push(Filter::near(name, center, radius));
+        self
+    }
+
+    /// Sorting by a field again replaces its order.
+    pub fn sort<S: Into<String>>(mut self, field: S, order: SortOrder) -> Self {
+        let field = field.into();
+
+        self.sort.retain(|(v, _)| *v != field);
+        self.sort.push((field, order));
+        self
+    }
+
+    pub fn sort_asc<S: Into<String>>(self, field: S) -> Self {
+        self.sort(field, SortOrder::Ascending)
+    }
+
+    pub fn sort_desc<S: Into<String>>(self, field: S) -> Self {
+        self.sort(field, SortOrder::Descending)
+    }
+
+    /// Only return these columns, see [`Projection`]. Replaces any previous columns.
+    pub fn columns<I: IntoIterator<Item = S>, S: AsRef<str>>(mut self, columns: I) -> Self {
+        self.columns = Some(Projection::fields(columns));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn include_files(mut self) -> Self {
+        self.include_files = true;
+        self
+    }
+
+    pub fn build(self) -> CmsQuery {
+        CmsQuery {
+            filters: (!self.filters.is_empty()).then_some(self.filters),
+            sort: (!self.sort.is_empty()).then_some(self.sort),
+            columns: self.columns.filter(|v| !v.is_all()),
+            limit: self.limit,
+            offset: self.offset,
+            include_files: self.include_files,
+        }
+    }
+
+    /// Build the query, checking every filtered, sorted and selected field exists and every
+    /// path is valid.
+    ///
+    /// System fields (eg. `_createdAt`) always exist.
+    pub fn build_checked(self, fields: &SchemaFieldMap) -> Result<CmsQuery> {
+        let mut paths = self
+            .filters
+            .iter()
+            .map(Filter::field_path)
+            .collect::<Result<Vec<_>>>()?;
+
+        paths.extend(
+            self.sort
+                .iter()
+                .map(|(v, _)| FieldPath::from(SchematicFieldKey::from(v.as_str()))),
+        );
+
+        if let Some(columns) = &self.columns {
+            paths.extend(columns.paths()?);
+        }
+
+        for key in paths.iter().map(FieldPath::key) {
+            if key.is_other() && fields.get(key).is_none_or(|f| f.is_deleted) {
+                return Err(GlobalError::UnknownField(key.to_string()));
+            }
+        }
+
+        Ok(self.build())
+    }
+}
+
+impl CmsQuery {
+    /// The query string the API expects, without the leading `?`.
+    ///
+    /// `filters[0][name]=age&filters[0][cond]=gte&filters[0][value]=18&sort[name]=ASC&limit=50`
+    ///
+    /// Sort fields are written highest priority first.
+    pub fn to_query_string(&self) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+
+        for (index, filter) in self.filters.iter().flatten().enumerate() {
+            let prefix = format!("filters[{index}]");
+
+            query.append_pair(&format!("{prefix}[name]"), &filter.name);
+            query.append_pair(&format!("{prefix}[cond]"), filter.cond.as_str());
+
+            if filter.is_path {
+                query.append_pair(&format!("{prefix}[isPath]"), "true");
+            }
+
+            match &filter.value {
+                FilterValue::Number(v) => {
+                    query.append_pair(&format!("{prefix}[value]"), &v.to_string());
+                }
+                FilterValue::Text(v) => {
+                    query.append_pair(&format!("{prefix}[value]"), v);
+                }
+                FilterValue::IdList(ids) => {
+                    for (i, id) in ids.iter().enumerate() {
+                        query.append_pair(&format!("{prefix}[value][{i}]"), id);
+                    }
+                }
+                FilterValue::Range((start, end)) => {
+                    query.append_pair(&format!("{prefix}[value][0]"), &start.to_string());
+                    query.append_pair(&format!("{prefix}[value][1]"), &end.to_string());
+                }
+                FilterValue::Point(v) => {
+                    query.append_pair(&format!("{prefix}[value]"), &v.to_string());
+                }
+            }
+
+            if let Some(radius) = filter.radius {
+                query.append_pair(&format!("{prefix}[radius]"), &radius.to_string());
+            }
+        }
+
+        for (field, order) in self.sort.iter().flatten() {
+            query.append_pair(&format!("sort[{field}]"), sort_order_name(*order));
+        }
+
+        if let Some(columns) = &self.columns {
+            query.append_pair("columns", &columns.to_string());
+        }
+
+        if let Some(limit) = self.limit {
+            query.append_pair("limit", &limit.to_string());
+        }
+
+        if let Some(offset) = self.offset {
+            query.append_pair("offset", &offset.to_string());
+        }
+
+        if self.include_files {
+            query.append_pair("includeFiles", "true");
+        }
+
+        query.finish()
+    }
+}
+
+impl From<CmsQueryBuilder> for CmsQuery {
+    fn from(value: CmsQueryBuilder) -> Self {
+        value.build()
+    }
+}
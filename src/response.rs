@@ -1,29 +1,44 @@
-use std::collections::HashMap;
+pub mod stream;
 
-use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::value::RawValue;
 use time::OffsetDateTime;
+use uuid::Uuid;
 
 use crate::{
-    id::{AddonInstanceUuid, FormPublicId, SchemaDataPublicId},
-    schema::{SchemaFieldMap, SchemaView, SchematicFieldKey, SchematicPermissions},
+    error::{GlobalError, Result},
+    id::{AddonInstanceUuid, FormPublicId, SchemaDataPublicId, WebsitePublicId},
+    idempotency::IdempotencyKey,
+    limits::RateLimit,
+    pages::PublicPage,
+    schema::{
+        Operations, SchemaFieldMap, SchemaView, Schematic, SchematicFieldKey, SchematicPermissions,
+        validation::ValidationError, version::SchemaVersion,
+    },
+    seo::SeoMeta,
     upload::WebsiteUpload,
-    value::SimpleValue,
+    value::{CowStr, SimpleValue, SimpleValueRef},
 };
 
 // Addon
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct AddonInstallResponse {
     pub instance_uuid: AddonInstanceUuid,
-    // TODO: Replace with Vec<PublicPage>
-    pub new_pages: serde_json::Value,
+    #[serde(deserialize_with = "crate::pages::deserialize_public_pages")]
+    pub new_pages: Vec<PublicPage>,
     pub is_update: bool,
 }
 
 // CMS
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct CmsCreateResponse {
     pub id: String,
@@ -33,6 +48,7 @@ pub struct CmsCreateResponse {
 }
 
 #[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct CmsResponse {
     pub collection: PublicSchema,
@@ -43,17 +59,208 @@ pub struct CmsResponse {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct CmsRowResponse {
     #[serde(default)]
     pub files: Vec<WebsiteUpload>,
     pub fields: HashMap<SchematicFieldKey, SimpleValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<RowMeta>,
+    /// Used when the collection has item pages. Boxed since most rows don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seo: Option<Box<SeoMeta>>,
+}
+
+impl CmsRowResponse {
+    /// Whether the row is in the trash. Rows without [`RowMeta`] never are.
+    pub fn is_deleted(&self) -> bool {
+        self.meta.is_some_and(|v| v.is_deleted())
+    }
+
+    pub fn deleted_at(&self) -> Option<OffsetDateTime> {
+        self.meta.and_then(|v| v.deleted_at)
+    }
+}
+
+/// Bookkeeping of a row, shared by every collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RowMeta {
+    /// Incremented on every change. Send it back as the `expected_revision` of an update to
+    /// fail instead of overwriting someone else's change.
+    pub revision: u64,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub created_at: OffsetDateTime,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub updated_at: OffsetDateTime,
+    pub owner: Option<Uuid>,
+    /// When the row was moved to the trash. None = Not Deleted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+impl RowMeta {
+    pub fn new(created_at: OffsetDateTime, owner: Option<Uuid>) -> Self {
+        Self {
+            revision: 0,
+            created_at,
+            updated_at: created_at,
+            owner,
+            deleted_at: None,
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Move the row to the trash. Deleting it again keeps the first time.
+    pub fn soft_delete(&mut self, at: OffsetDateTime) {
+        if self.deleted_at.is_none() {
+            self.deleted_at = Some(at);
+            self.bump(at);
+        }
+    }
+
+    /// Take the row out of the trash.
+    pub fn restore(&mut self, at: OffsetDateTime) {
+        if self.deleted_at.take().is_some() {
+            self.bump(at);
+        }
+    }
+
+    /// Fails with [`GlobalError::RevisionConflict`] if the row isn't at the expected revision.
+    pub fn check_revision(&self, expected: Option<u64>) -> Result<()> {
+        match expected {
+            Some(expected) if expected != self.revision => Err(GlobalError::RevisionConflict {
+                expected,
+                found: self.revision,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a change.
+    pub fn bump(&mut self, updated_at: OffsetDateTime) {
+        self.revision += 1;
+        self.updated_at = updated_at;
+    }
+}
+
+/// A borrowed version of [`CmsRowResponse`] for reading large list responses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmsRowResponseRef<'a> {
+    #[serde(default)]
+    pub files: Vec<WebsiteUpload>,
+    #[serde(borrow)]
+    pub fields: HashMap<CowStr<'a>, SimpleValueRef<'a>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<RowMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seo: Option<Box<SeoMeta>>,
+}
+
+impl CmsRowResponseRef<'_> {
+    pub fn get(&self, key: &str) -> Option<&SimpleValueRef<'_>> {
+        self.fields.get(key)
+    }
+
+    pub fn into_owned(self) -> CmsRowResponse {
+        CmsRowResponse {
+            files: self.files,
+            fields: self
+                .fields
+                .into_iter()
+                .map(|(k, v)| (SchematicFieldKey::from(&*k), v.into_owned()))
+                .collect(),
+            meta: self.meta,
+            seo: self.seo,
+        }
+    }
+}
+
+/// A row whose values stay as JSON until they're read.
+///
+/// For services which pass most of a large list through, or only look at a few fields of each
+/// row. Values are parsed by [`RawRow::get`] and serialized back as they came. Only works with
+/// `serde_json::from_str` and `from_slice`, since the values borrow from the input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawRow<'a> {
+    #[serde(default)]
+    pub files: Vec<WebsiteUpload>,
+    #[serde(borrow)]
+    pub fields: HashMap<CowStr<'a>, &'a RawValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<RowMeta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seo: Option<Box<SeoMeta>>,
+}
+
+impl<'a> RawRow<'a> {
+    /// The JSON of a value.
+    pub fn raw(&self, key: &str) -> Option<&'a RawValue> {
+        self.fields.get(key).copied()
+    }
+
+    /// Parse a value. Text borrows from the input like in [`CmsRowResponseRef`].
+    pub fn get(&self, key: &str) -> Result<Option<SimpleValueRef<'a>>> {
+        self.get_as(key)
+    }
+
+    /// Parse a value into any type, eg. a `&str` or an id.
+    pub fn get_as<T: Deserialize<'a>>(&self, key: &str) -> Result<Option<T>> {
+        match self.raw(key) {
+            Some(raw) => Ok(Some(serde_json::from_str(raw.get())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse every value.
+    pub fn to_borrowed(&self) -> Result<CmsRowResponseRef<'a>> {
+        Ok(CmsRowResponseRef {
+            files: self.files.clone(),
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), serde_json::from_str(v.get())?)))
+                .collect::<Result<_>>()?,
+            meta: self.meta,
+            seo: self.seo.clone(),
+        })
+    }
+
+    pub fn into_owned(self) -> Result<CmsRowResponse> {
+        Ok(self.to_borrowed()?.into_owned())
+    }
+}
+
+/// The result of a [`CmsAggregate`](crate::request::CmsAggregate) request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct CmsAggregateResponse {
+    pub groups: Vec<AggregateGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct AggregateGroup {
+    /// The value of each `group_by` field for this group.
+    pub key: HashMap<SchematicFieldKey, SimpleValue>,
+    /// Results by [`Aggregation::name`](crate::request::Aggregation::name).
+    /// Missing if there was nothing to aggregate (e.g. the min of an empty group).
+    pub values: HashMap<String, SimpleValue>,
 }
 
 // TODO: Remove - make public version
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaTag {
     pub id: i64,
+    #[serde(alias = "row_id")]
     pub row_id: String,
 
     pub name: String,
@@ -61,6 +268,7 @@ pub struct SchemaTag {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct BasicCmsInfo {
     pub id: String,
@@ -70,39 +278,173 @@ pub struct BasicCmsInfo {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct PublicSchema {
+    #[serde(alias = "schema_id")]
     pub schema_id: String,
 
     pub namespace: Option<String>,
+    #[serde(alias = "primary_field")]
     pub primary_field: String,
+    #[serde(alias = "display_name")]
     pub display_name: String,
 
     pub permissions: SchematicPermissions,
 
-    pub version: f32,
+    pub version: SchemaVersion,
 
-    pub allowed_operations: Vec<String>,
+    #[serde(alias = "allowed_operations")]
+    pub allowed_operations: Vec<Operations>,
+    #[serde(alias = "is_single")]
     pub is_single: bool,
 
     pub fields: SchemaFieldMap,
 
     pub ttl: Option<i32>,
+    #[serde(alias = "default_sort")]
     pub default_sort: Option<String>,
     pub views: Vec<SchemaView>,
 
+    #[serde(alias = "created_at")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub created_at: OffsetDateTime,
+    #[serde(alias = "updated_at")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub updated_at: OffsetDateTime,
+    #[serde(alias = "deleted_at")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+/// What a [`PublicSchema`] has which a [`Schematic`] doesn't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicSchemaMeta {
+    pub is_single: bool,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
 }
 
+impl PublicSchema {
+    pub fn meta(&self) -> PublicSchemaMeta {
+        PublicSchemaMeta {
+            is_single: self.is_single,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            deleted_at: self.deleted_at,
+        }
+    }
+}
+
+/// The TTL is whole seconds, an empty namespace is None and the default sort is written as
+/// `field:order`.
+impl TryFrom<(Schematic, PublicSchemaMeta)> for PublicSchema {
+    type Error = GlobalError;
+
+    fn try_from((schema, meta): (Schematic, PublicSchemaMeta)) -> Result<Self> {
+        if schema.is_deleted != meta.deleted_at.is_some() {
+            return Err(GlobalError::InvalidSchema(format!(
+                "Schema {} is_deleted doesn't match deleted_at",
+                schema.id
+            )));
+        }
+
+        let ttl = schema
+            .ttl
+            .map(|ttl| {
+                i32::try_from(ttl.as_secs())
+                    .ok()
+                    .filter(|_| ttl.subsec_nanos() == 0)
+                    .ok_or_else(|| {
+                        GlobalError::InvalidSchema(format!(
+                            "Schema {} TTL of {ttl:?} isn't a whole number of seconds",
+                            schema.id
+                        ))
+                    })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            schema_id: schema.id,
+            namespace: Some(schema.namespace).filter(|v| !v.is_empty()),
+            primary_field: schema.primary_field,
+            display_name: schema.display_name,
+            permissions: schema.permissions,
+            version: schema.version,
+            allowed_operations: schema.allowed_operations,
+            is_single: meta.is_single,
+            fields: schema.fields,
+            ttl,
+            default_sort: schema.default_sort.map(|v| v.to_string()),
+            views: schema.views,
+            created_at: meta.created_at,
+            updated_at: meta.updated_at,
+            deleted_at: meta.deleted_at,
+        })
+    }
+}
+
+/// The schema of the owning app. See [`PublicSchema::meta`] for the rest.
+impl TryFrom<(PublicSchema, WebsitePublicId)> for Schematic {
+    type Error = GlobalError;
+
+    fn try_from((schema, owner_app_id): (PublicSchema, WebsitePublicId)) -> Result<Self> {
+        let ttl = schema
+            .ttl
+            .map(|ttl| {
+                u64::try_from(ttl).map(Duration::from_secs).map_err(|_| {
+                    GlobalError::InvalidSchema(format!(
+                        "Schema {} has a negative TTL",
+                        schema.schema_id
+                    ))
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            is_deleted: schema.deleted_at.is_some(),
+            default_sort: schema.default_sort.as_deref().map(str::parse).transpose()?,
+            id: schema.schema_id,
+            namespace: schema.namespace.unwrap_or_default(),
+            primary_field: schema.primary_field,
+            display_name: schema.display_name,
+            permissions: schema.permissions,
+            version: schema.version,
+            allowed_operations: schema.allowed_operations,
+            owner_app_id,
+            fields: schema.fields,
+            ttl,
+            views: schema.views,
+        })
+    }
+}
+
 // GENERAL
 
 #[derive(Debug, Serialize, PartialEq, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct ListResponse<T> {
     pub items: Vec<T>,
     pub offset: i64,
     pub limit: i64,
     pub total: i64,
+
+    /// Cursor for the page after this one. None = Last Page
+    #[serde(
+        alias = "next_cursor",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_cursor: Option<Cursor>,
+    /// Cursor for the page before this one. None = First Page
+    #[serde(
+        alias = "prev_cursor",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub prev_cursor: Option<Cursor>,
 }
 
 impl<T> ListResponse<T> {
@@ -112,6 +454,8 @@ impl<T> ListResponse<T> {
             offset: 0,
             limit: 0,
             total: 0,
+            next_cursor: None,
+            prev_cursor: None,
         }
     }
 
@@ -121,18 +465,145 @@ impl<T> ListResponse<T> {
             limit: value.len() as i64,
             total: value.len() as i64,
             items: value,
+            next_cursor: None,
+            prev_cursor: None,
         }
     }
+
+    pub fn with_cursors(mut self, next: Option<Cursor>, prev: Option<Cursor>) -> Self {
+        self.next_cursor = next;
+        self.prev_cursor = prev;
+        self
+    }
+}
+
+/// An opaque pagination cursor.
+///
+/// The position is serialized as JSON and base64 (URL safe, no padding) encoded so it can be
+/// passed around in query strings. Clients should never look inside it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(try_from = "String", into = "String")]
+pub struct Cursor(String);
+
+impl Cursor {
+    pub fn encode<V: Serialize>(position: &V) -> Result<Self> {
+        Ok(Self(URL_SAFE_NO_PAD.encode(serde_json::to_vec(position)?)))
+    }
+
+    pub fn decode<V: DeserializeOwned>(&self) -> Result<V> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(&self.0)
+            .map_err(|e| GlobalError::InvalidCursor(e.to_string()))?;
+
+        serde_json::from_slice(&bytes).map_err(|e| GlobalError::InvalidCursor(e.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for Cursor {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|e| GlobalError::InvalidCursor(e.to_string()))?;
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for Cursor {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<Cursor> for String {
+    fn from(value: Cursor) -> Self {
+        value.0
+    }
+}
+
+/// The result of a bulk request. Items are processed on their own so some may fail while the
+/// rest succeed.
+///
+/// Indexes are positions in the request.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct BulkOperationResponse {
+    /// The row each succeeded item inserted, updated or removed.
+    pub succeeded: Vec<(usize, SchemaDataPublicId)>,
+    pub failed: Vec<(usize, ApiErrorResponse)>,
+}
+
+impl BulkOperationResponse {
+    /// Collect the result of each item, in request order.
+    pub fn from_results<
+        I: IntoIterator<Item = std::result::Result<SchemaDataPublicId, ApiErrorResponse>>,
+    >(
+        results: I,
+    ) -> Self {
+        let mut response = Self::default();
+
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(id) => response.succeeded.push((index, id)),
+                Err(e) => response.failed.push((index, e)),
+            }
+        }
+
+        response
+    }
+
+    /// Whether every item succeeded.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn error_for(&self, index: usize) -> Option<&ApiErrorResponse> {
+        self.failed
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, e)| e)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct SingleValue<V>(pub V);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(tag = "type", content = "value")]
 pub enum WrappingResponse<V> {
     Resp(V),
     Error(ApiErrorResponse),
+    /// The request was already handled, this is the response it got the first time.
+    Replayed(ReplayedResponse<V>),
+}
+
+/// A stored response returned for a request sent again with the same [`IdempotencyKey`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayedResponse<V> {
+    pub idempotency_key: IdempotencyKey,
+    /// When the first request was handled.
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub handled_at: OffsetDateTime,
+    pub response: V,
 }
 
 impl<V> WrappingResponse<V> {
@@ -144,10 +615,20 @@ impl<V> WrappingResponse<V> {
         Self::Error(ApiErrorResponse::new(value))
     }
 
+    pub fn replayed(idempotency_key: IdempotencyKey, handled_at: OffsetDateTime, value: V) -> Self {
+        Self::Replayed(ReplayedResponse {
+            idempotency_key,
+            handled_at,
+            response: value,
+        })
+    }
+
+    /// The value, whether it's new or [replayed](Self::Replayed).
     pub fn ok(self) -> std::result::Result<V, ApiErrorResponse> {
         match self {
             Self::Resp(v) => Ok(v),
             Self::Error(e) => Err(e),
+            Self::Replayed(v) => Ok(v.response),
         }
     }
 
@@ -155,26 +636,73 @@ impl<V> WrappingResponse<V> {
         match self {
             Self::Resp(v) => Ok(v),
             Self::Error(e) => Err(e),
+            Self::Replayed(v) => Ok(&v.response),
         }
     }
 
+    pub fn is_replayed(&self) -> bool {
+        matches!(self, Self::Replayed(_))
+    }
+
     pub fn map<N, F: Fn(V) -> N>(self, func: F) -> WrappingResponse<N> {
         match self {
             Self::Resp(v) => WrappingResponse::Resp(func(v)),
             Self::Error(e) => WrappingResponse::Error(e),
+            Self::Replayed(v) => WrappingResponse::Replayed(ReplayedResponse {
+                idempotency_key: v.idempotency_key,
+                handled_at: v.handled_at,
+                response: func(v.response),
+            }),
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, thiserror::Error)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct ApiErrorResponse {
+    #[serde(default)]
+    pub code: ApiErrorCode,
     pub description: String,
+    #[serde(alias = "field_errors", default, skip_serializing_if = "Vec::is_empty")]
+    pub field_errors: Vec<FieldError>,
+    /// Sent with [`ApiErrorCode::RateLimited`] errors.
+    #[serde(alias = "rate_limit", default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl ApiErrorResponse {
     pub fn new<S: Into<String>>(value: S) -> Self {
+        Self::with_code(ApiErrorCode::Internal, value)
+    }
+
+    pub fn with_code<S: Into<String>>(code: ApiErrorCode, value: S) -> Self {
         Self {
+            code,
             description: value.into(),
+            field_errors: Vec::new(),
+            rate_limit: None,
+        }
+    }
+
+    pub fn validation<S: Into<String>>(value: S, field_errors: Vec<FieldError>) -> Self {
+        Self {
+            code: ApiErrorCode::Validation,
+            description: value.into(),
+            field_errors,
+            rate_limit: None,
+        }
+    }
+
+    pub fn rate_limited(rate_limit: RateLimit) -> Self {
+        Self {
+            code: ApiErrorCode::RateLimited,
+            description: format!(
+                "Rate limit of {} requests exceeded, resets at {}",
+                rate_limit.limit, rate_limit.reset_at
+            ),
+            field_errors: Vec::new(),
+            rate_limit: Some(rate_limit),
         }
     }
 }
@@ -184,3 +712,65 @@ impl std::fmt::Display for ApiErrorResponse {
         write!(f, "Api Error Occurred: {}", self.description)
     }
 }
+
+/// Machine-readable kind of an [`ApiErrorResponse`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum ApiErrorCode {
+    NotFound,
+    PermissionDenied,
+    Validation,
+    RateLimited,
+    /// The row was changed by someone else, see [`RowMeta::check_revision`].
+    Conflict,
+    Internal,
+    /// A missing code, eg. from an older server, or one this version doesn't know. Not
+    /// retryable since the cause isn't known.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl ApiErrorCode {
+    pub fn status_code(self) -> u16 {
+        match self {
+            Self::NotFound => 404,
+            Self::PermissionDenied => 403,
+            Self::Validation => 422,
+            Self::RateLimited => 429,
+            Self::Conflict => 409,
+            Self::Internal | Self::Unknown => 500,
+        }
+    }
+
+    /// Whether the same request may succeed if it's sent again later.
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimited | Self::Internal)
+    }
+}
+
+/// An error tied to a single field of a submitted form or record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new<F: Into<String>, M: Into<String>>(field: F, message: M) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ValidationError> for FieldError {
+    fn from(value: ValidationError) -> Self {
+        Self {
+            field: value.field.to_string(),
+            message: value.kind.to_string(),
+        }
+    }
+}
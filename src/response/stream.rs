@@ -0,0 +1,242 @@
+//! Lists streamed as newline delimited JSON, for exports larger than memory.
+//!
+//! Every line is a [`StreamRecord`]: the items, then a [`StreamSummary`] with the total and the
+//! cursor [`ListResponse`] would have had. A stream which ends without the summary was cut off.
+
+use std::{
+    io::{BufRead, Lines, Write},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{Cursor, ListResponse};
+use crate::error::{GlobalError, Result};
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// One line of the stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum StreamRecord<T> {
+    Item(T),
+    Summary(StreamSummary),
+}
+
+/// The last line of the stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSummary {
+    /// Items in the collection, which can be more than were streamed.
+    pub total: i64,
+    /// Items in the stream, to tell if any went missing.
+    pub count: u64,
+    /// Cursor for the items after these. None = Last Page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<Cursor>,
+}
+
+/// A list whose items are written one at a time.
+pub struct StreamedList<I> {
+    pub items: I,
+    pub total: i64,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<I: Iterator<Item = T>, T: Serialize> StreamedList<I> {
+    pub fn new(items: I, total: i64) -> Self {
+        Self {
+            items,
+            total,
+            next_cursor: None,
+        }
+    }
+
+    pub fn next_cursor(mut self, cursor: Option<Cursor>) -> Self {
+        self.next_cursor = cursor;
+        self
+    }
+
+    /// Write every item and the summary, returning the writer.
+    pub fn write_ndjson<W: Write>(self, writer: W) -> Result<W> {
+        let mut writer = NdjsonWriter::new(writer);
+
+        for item in self.items {
+            writer.write_item(&item)?;
+        }
+
+        writer.finish(self.total, self.next_cursor)
+    }
+}
+
+impl<T> From<ListResponse<T>> for StreamedList<std::vec::IntoIter<T>> {
+    fn from(value: ListResponse<T>) -> Self {
+        Self {
+            items: value.items.into_iter(),
+            total: value.total,
+            next_cursor: value.next_cursor,
+        }
+    }
+}
+
+/// Writes items as they're loaded, eg. from a database cursor.
+pub struct NdjsonWriter<W> {
+    writer: W,
+    count: u64,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+
+    pub fn write_item<T: Serialize>(&mut self, item: &T) -> Result<()> {
+        self.write_record(&StreamRecord::Item(item))?;
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// The items written so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Write the summary and flush.
+    pub fn finish(mut self, total: i64, next_cursor: Option<Cursor>) -> Result<W> {
+        let summary = StreamSummary {
+            total,
+            count: self.count,
+            next_cursor,
+        };
+
+        self.write_record(&StreamRecord::<()>::Summary(summary))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    fn write_record<T: Serialize>(&mut self, record: &StreamRecord<T>) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+
+        Ok(())
+    }
+}
+
+/// Reads the items of a stream, then checks the summary.
+///
+/// Iterating fails if the stream ends without a summary, has a different number of items than
+/// the summary says, or continues after it.
+pub struct NdjsonReader<R, T> {
+    lines: Lines<R>,
+    count: u64,
+    summary: Option<StreamSummary>,
+    /// Read to the end or failed.
+    finished: bool,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> NdjsonReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            count: 0,
+            summary: None,
+            finished: false,
+            item: PhantomData,
+        }
+    }
+
+    /// The summary, once every item has been read.
+    pub fn summary(&self) -> Option<&StreamSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Read the whole stream into a [`ListResponse`].
+    pub fn into_list(mut self) -> Result<ListResponse<T>> {
+        let items = self.by_ref().collect::<Result<Vec<_>>>()?;
+
+        let summary = self
+            .summary
+            .ok_or_else(|| invalid(String::from("missing the summary")))?;
+
+        Ok(ListResponse {
+            offset: 0,
+            limit: items.len() as i64,
+            total: summary.total,
+            items,
+            next_cursor: summary.next_cursor,
+            prev_cursor: None,
+        })
+    }
+
+    fn next_line(&mut self) -> Option<Result<String>> {
+        loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => return Some(Ok(line)),
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for NdjsonReader<R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let line = match self.next_line() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => return self.fail(e),
+            None => {
+                self.finished = true;
+
+                return self.summary.is_none().then(|| {
+                    Err(invalid(format!(
+                        "the stream ended after {} items without a summary",
+                        self.count
+                    )))
+                });
+            }
+        };
+
+        if self.summary.is_some() {
+            return self.fail(invalid(String::from("a record after the summary")));
+        }
+
+        match serde_json::from_str::<StreamRecord<T>>(&line) {
+            Ok(StreamRecord::Item(item)) => {
+                self.count += 1;
+                Some(Ok(item))
+            }
+            Ok(StreamRecord::Summary(summary)) if summary.count != self.count => {
+                self.fail(invalid(format!(
+                    "the summary has {} items but the stream had {}",
+                    summary.count, self.count
+                )))
+            }
+            Ok(StreamRecord::Summary(summary)) => {
+                self.summary = Some(summary);
+                // Nothing else should follow.
+                self.next()
+            }
+            Err(e) => self.fail(e.into()),
+        }
+    }
+}
+
+impl<R, T> NdjsonReader<R, T> {
+    fn fail(&mut self, error: GlobalError) -> Option<Result<T>> {
+        self.finished = true;
+        Some(Err(error))
+    }
+}
+
+fn invalid(reason: String) -> GlobalError {
+    GlobalError::ParseError(format!("NDJSON: {reason}"))
+}
@@ -0,0 +1,244 @@
+//! The document model of Rich Text / Rich Content fields.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// URL schemes links and embeds may use. Relative URLs are always allowed.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Attributes which can never be set, on top of every `on*` event handler.
+const BLOCKED_ATTRIBUTES: &[&str] = &["style", "srcdoc", "formaction", "xlink:href"];
+
+/// Attributes containing a URL which has to be safe.
+const URL_ATTRIBUTES: &[&str] = &[
+    "href",
+    "src",
+    "action",
+    "poster",
+    "cite",
+    "data",
+    "background",
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichDocument {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    /// Level 1 - 6
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    /// Each item is its own list of blocks so lists can be nested.
+    List {
+        ordered: bool,
+        items: Vec<Vec<Block>>,
+    },
+    Quote(Vec<Block>),
+    Code {
+        language: Option<String>,
+        text: String,
+    },
+    Embed(Embed),
+    Divider,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Inline {
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        marks: Vec<Mark>,
+    },
+    Link {
+        href: String,
+        content: Vec<Inline>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        attributes: BTreeMap<String, String>,
+    },
+    LineBreak,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Mark {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Code,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Embed {
+    pub kind: EmbedKind,
+    pub url: String,
+    /// Alternative text, also used for the plain text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EmbedKind {
+    Image,
+    Video,
+    Audio,
+    /// An external page, eg. a YouTube player. Only HTTPS is allowed.
+    Frame,
+}
+
+impl RichDocument {
+    /// Remove everything which could run scripts once rendered.
+    ///
+    /// - Links to unsafe URLs (eg. `javascript:`) are replaced with their content.
+    /// - Embeds of unsafe URLs, and frames not using HTTPS, are removed.
+    /// - Event handler (`on*`) and other blocked attributes are removed, as are URL attributes
+    ///   (eg. `src`) containing unsafe URLs.
+    /// - Heading levels are clamped to 1 - 6.
+    pub fn sanitize(&mut self) {
+        sanitize_blocks(&mut self.blocks);
+    }
+
+    /// The text of the document without any formatting, for search indexing.
+    ///
+    /// Blocks and list items are separated by new lines.
+    pub fn plain_text(&self) -> String {
+        let mut lines = Vec::new();
+
+        blocks_text(&self.blocks, &mut lines);
+
+        lines.join("\n")
+    }
+}
+
+fn sanitize_blocks(blocks: &mut Vec<Block>) {
+    blocks.retain(|block| match block {
+        Block::Embed(embed) => {
+            is_safe_url(&embed.url)
+                && (embed.kind != EmbedKind::Frame || has_scheme(&embed.url, "https"))
+        }
+        _ => true,
+    });
+
+    for block in blocks {
+        match block {
+            Block::Paragraph(content) => sanitize_inlines(content),
+            Block::Heading { level, content } => {
+                *level = (*level).clamp(1, 6);
+                sanitize_inlines(content);
+            }
+            Block::List { items, .. } => items.iter_mut().for_each(sanitize_blocks),
+            Block::Quote(blocks) => sanitize_blocks(blocks),
+            Block::Embed(embed) => sanitize_attributes(&mut embed.attributes),
+            Block::Code { .. } | Block::Divider => (),
+        }
+    }
+}
+
+fn sanitize_inlines(inlines: &mut Vec<Inline>) {
+    let mut sanitized = Vec::with_capacity(inlines.len());
+
+    for inline in inlines.drain(..) {
+        match inline {
+            Inline::Link {
+                href,
+                mut content,
+                mut attributes,
+            } => {
+                sanitize_inlines(&mut content);
+
+                if is_safe_url(&href) {
+                    sanitize_attributes(&mut attributes);
+
+                    sanitized.push(Inline::Link {
+                        href,
+                        content,
+                        attributes,
+                    });
+                } else {
+                    sanitized.extend(content);
+                }
+            }
+            v => sanitized.push(v),
+        }
+    }
+
+    *inlines = sanitized;
+}
+
+fn sanitize_attributes(attributes: &mut BTreeMap<String, String>) {
+    attributes.retain(|name, value| {
+        let name = name.trim().to_lowercase();
+
+        !name.starts_with("on")
+            && !BLOCKED_ATTRIBUTES.contains(&name.as_str())
+            && (!URL_ATTRIBUTES.contains(&name.as_str()) || is_safe_url(value))
+    });
+}
+
+/// Whether the URL is relative or uses one of the [`SAFE_SCHEMES`].
+///
+/// Whitespace and control characters are ignored like browsers do, so `java\tscript:` is
+/// still caught.
+pub fn is_safe_url(url: &str) -> bool {
+    match url_scheme(url) {
+        Some(scheme) => SAFE_SCHEMES.contains(&scheme.as_str()),
+        None => true,
+    }
+}
+
+fn has_scheme(url: &str, expected: &str) -> bool {
+    url_scheme(url).is_some_and(|v| v == expected)
+}
+
+fn url_scheme(url: &str) -> Option<String> {
+    let cleaned = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>();
+
+    let (scheme, _) = cleaned.split_once(':')?;
+
+    // Anything else before the `:` (eg. `/`) means it's a relative path like `/a:b`.
+    let is_scheme = !scheme.is_empty()
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    is_scheme.then(|| scheme.to_lowercase())
+}
+
+fn blocks_text(blocks: &[Block], lines: &mut Vec<String>) {
+    for block in blocks {
+        match block {
+            Block::Paragraph(content) | Block::Heading { content, .. } => {
+                lines.push(inlines_text(content));
+            }
+            Block::List { items, .. } => items.iter().for_each(|v| blocks_text(v, lines)),
+            Block::Quote(blocks) => blocks_text(blocks, lines),
+            Block::Code { text, .. } => lines.push(text.clone()),
+            Block::Embed(embed) => lines.extend(embed.alt_text.clone()),
+            Block::Divider => (),
+        }
+    }
+}
+
+fn inlines_text(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text { text, .. } => text.clone(),
+            Inline::Link { content, .. } => inlines_text(content),
+            Inline::LineBreak => String::from("\n"),
+        })
+        .collect()
+}
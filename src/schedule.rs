@@ -0,0 +1,606 @@
+//! Recurring dates for bookings and events.
+//!
+//! [`RRule`] is the `RRULE` of iCalendar (RFC 5545), limited to what the dashboard can edit: a
+//! frequency, an interval, weekdays and an end. [`Recurrence`] gives it a start and a time zone
+//! so the server and the client expand it to the same occurrences.
+
+pub mod ics;
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use time::{
+    Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday,
+    format_description::BorrowedFormatItem, macros::format_description,
+};
+
+use crate::{
+    error::{GlobalError, Result},
+    tz,
+};
+
+/// The most occurrences [`Recurrence::between`] returns.
+pub const MAX_OCCURRENCES: usize = 1000;
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Monday),
+    ("TU", Weekday::Tuesday),
+    ("WE", Weekday::Wednesday),
+    ("TH", Weekday::Thursday),
+    ("FR", Weekday::Friday),
+    ("SA", Weekday::Saturday),
+    ("SU", Weekday::Sunday),
+];
+
+// Four digit years, which `[year]` isn't when `time` has large dates enabled (eg. by bson).
+const DATE_TIME_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year range:standard][month][day]T[hour][minute][second]");
+const DATE_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year range:standard][month][day]");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    /// The iCalendar name, eg. `WEEKLY`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [Self::Daily, Self::Weekly, Self::Monthly, Self::Yearly]
+            .into_iter()
+            .find(|v| v.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| GlobalError::InvalidRecurrence(format!("Unknown frequency {s}")))
+    }
+}
+
+/// A weekday, or the nth one of the month or year, eg. `MO`, `2TU` or `-1FR` for the last Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ByDay {
+    pub weekday: Weekday,
+    /// Counts from the end when negative. Only for monthly and yearly rules.
+    pub nth: Option<i8>,
+}
+
+impl ByDay {
+    pub fn every(weekday: Weekday) -> Self {
+        Self { weekday, nth: None }
+    }
+
+    pub fn nth(nth: i8, weekday: Weekday) -> Self {
+        Self {
+            weekday,
+            nth: Some(nth),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GlobalError::InvalidRecurrence(format!("Invalid day {value}"));
+
+        let value = value.trim();
+        let split = value.len().checked_sub(2).ok_or_else(invalid)?;
+        let (nth, code) = value.split_at_checked(split).ok_or_else(invalid)?;
+
+        let weekday = WEEKDAYS
+            .iter()
+            .find(|(v, _)| v.eq_ignore_ascii_case(code))
+            .map(|(_, v)| *v)
+            .ok_or_else(invalid)?;
+
+        let nth = match nth {
+            "" => None,
+            v => Some(
+                v.strip_prefix('+')
+                    .unwrap_or(v)
+                    .parse::<i8>()
+                    .ok()
+                    .filter(|v| *v != 0 && v.abs() <= 53)
+                    .ok_or_else(invalid)?,
+            ),
+        };
+
+        Ok(Self { weekday, nth })
+    }
+}
+
+impl Display for ByDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(nth) = self.nth {
+            write!(f, "{nth}")?;
+        }
+
+        let (code, _) = WEEKDAYS
+            .iter()
+            .find(|(_, v)| *v == self.weekday)
+            .expect("every weekday has a code");
+
+        f.write_str(code)
+    }
+}
+
+impl FromStr for ByDay {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for ByDay {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<ByDay> for String {
+    fn from(value: ByDay) -> Self {
+        value.to_string()
+    }
+}
+
+/// How a date repeats.
+///
+/// Deserializes from the fields or an iCalendar string such as
+/// `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE;COUNT=10`. It always serializes to the fields, use
+/// [`Display`] for the string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "RRuleRepr")]
+pub struct RRule {
+    pub freq: Frequency,
+    /// Every nth day, week, month or year.
+    pub interval: u32,
+    /// Weeks repeat on these days and days only on these, instead of the start's weekday.
+    /// Months and years repeat on these days instead of the start's day.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub by_day: Vec<ByDay>,
+    /// No occurrences after this. Can't be used with `count`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<OffsetDateTime>,
+    /// The number of occurrences, counting from the start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RRuleRepr {
+    Text(String),
+    Fields(RRuleFields),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RRuleFields {
+    freq: Frequency,
+    #[serde(default = "default_interval")]
+    interval: u32,
+    #[serde(default)]
+    by_day: Vec<ByDay>,
+    #[serde(default)]
+    until: Option<OffsetDateTime>,
+    #[serde(default)]
+    count: Option<u32>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+impl RRule {
+    pub fn new(freq: Frequency) -> Self {
+        Self {
+            freq,
+            interval: 1,
+            by_day: Vec::new(),
+            until: None,
+            count: None,
+        }
+    }
+
+    pub fn interval(mut self, value: u32) -> Self {
+        self.interval = value;
+        self
+    }
+
+    pub fn by_day(mut self, value: impl IntoIterator<Item = ByDay>) -> Self {
+        self.by_day = value.into_iter().collect();
+        self
+    }
+
+    pub fn until(mut self, value: OffsetDateTime) -> Self {
+        self.until = Some(value);
+        self
+    }
+
+    pub fn count(mut self, value: u32) -> Self {
+        self.count = Some(value);
+        self
+    }
+
+    /// Parse an iCalendar `RRULE`, with or without the `RRULE:` prefix.
+    ///
+    /// An `UNTIL` without a time is the end of that day, and one without a `Z` is taken as UTC.
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: String| GlobalError::InvalidRecurrence(format!("{value}: {reason}"));
+
+        let text = value.trim();
+        let text = text.strip_prefix("RRULE:").unwrap_or(text);
+
+        let mut freq = None;
+        let mut rule = Self::new(Frequency::Daily);
+
+        for part in text.split(';').filter(|v| !v.is_empty()) {
+            let (key, v) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected KEY=VALUE, found {part}")))?;
+
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => freq = Some(v.parse()?),
+                "INTERVAL" => {
+                    rule.interval = v
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid interval {v}")))?;
+                }
+                "BYDAY" => rule.by_day = v.split(',').map(ByDay::parse).collect::<Result<_>>()?,
+                "UNTIL" => {
+                    rule.until =
+                        Some(parse_until(v).ok_or_else(|| invalid(format!("invalid until {v}")))?);
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        v.parse()
+                            .map_err(|_| invalid(format!("invalid count {v}")))?,
+                    );
+                }
+                // Weeks always start on Monday.
+                "WKST" if v.eq_ignore_ascii_case("MO") => {}
+                _ => return Err(invalid(format!("{part} isn't supported"))),
+            }
+        }
+
+        rule.freq = freq.ok_or_else(|| invalid(String::from("missing FREQ")))?;
+        rule.validate()?;
+
+        Ok(rule)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(GlobalError::InvalidRecurrence(reason));
+
+        if self.interval == 0 {
+            return invalid(String::from("The interval has to be at least 1"));
+        }
+
+        if self.count == Some(0) {
+            return invalid(String::from("The count has to be at least 1"));
+        }
+
+        if self.until.is_some() && self.count.is_some() {
+            return invalid(String::from("Only one of until and count can be set"));
+        }
+
+        for by_day in &self.by_day {
+            let Some(nth) = by_day.nth else {
+                continue;
+            };
+
+            let max = match self.freq {
+                Frequency::Monthly => 5,
+                Frequency::Yearly => 53,
+                Frequency::Daily | Frequency::Weekly => {
+                    return invalid(format!("{by_day} needs a monthly or yearly frequency"));
+                }
+            };
+
+            if nth == 0 || nth.unsigned_abs() > max {
+                return invalid(format!(
+                    "{by_day} has to be within 1 to {max} from either end"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The local dates & times of the occurrences, in order, for the periods starting on or
+    /// before `last`.
+    fn local_occurrences(
+        &self,
+        start: PrimitiveDateTime,
+        last: Date,
+    ) -> impl Iterator<Item = PrimitiveDateTime> + '_ {
+        (0u32..)
+            .map_while(move |period| self.period(start.date(), period))
+            .take_while(move |(first, _)| *first <= last)
+            .flat_map(|(_, dates)| dates)
+            .filter(move |date| *date >= start.date())
+            .map(move |date| date.with_time(start.time()))
+            .take(self.count.map_or(usize::MAX, |v| v as usize))
+    }
+
+    /// The first day of the nth period and its dates. None once the dates are out of range.
+    fn period(&self, start: Date, period: u32) -> Option<(Date, Vec<Date>)> {
+        let step = i64::from(period) * i64::from(self.interval);
+
+        match self.freq {
+            Frequency::Daily => {
+                let date = add_days(start, step)?;
+                let dates = if self.by_day.is_empty()
+                    || self.by_day.iter().any(|v| v.weekday == date.weekday())
+                {
+                    vec![date]
+                } else {
+                    Vec::new()
+                };
+
+                Some((date, dates))
+            }
+            Frequency::Weekly => {
+                let monday =
+                    add_days(start, -i64::from(start.weekday().number_days_from_monday()))?;
+                let first = add_days(monday, step * 7)?;
+
+                let mut dates = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|v| v.weekday).collect()
+                }
+                .into_iter()
+                .filter_map(|v| add_days(first, v.number_days_from_monday().into()))
+                .collect::<Vec<_>>();
+
+                dates.sort();
+                dates.dedup();
+
+                Some((first, dates))
+            }
+            Frequency::Monthly => {
+                let index =
+                    i64::from(start.year()) * 12 + i64::from(u8::from(start.month())) - 1 + step;
+                let year = i32::try_from(index.div_euclid(12)).ok()?;
+                let month = Month::try_from(index.rem_euclid(12) as u8 + 1).ok()?;
+
+                let first = Date::from_calendar_date(year, month, 1).ok()?;
+                let last = Date::from_calendar_date(year, month, month.length(year)).ok()?;
+
+                Some((
+                    first,
+                    self.dates_within(first, last, year, month, start.day()),
+                ))
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+
+                let first = Date::from_calendar_date(year, Month::January, 1).ok()?;
+                let last = Date::from_calendar_date(year, Month::December, 31).ok()?;
+
+                Some((
+                    first,
+                    self.dates_within(first, last, year, start.month(), start.day()),
+                ))
+            }
+        }
+    }
+
+    /// The dates of a month or year, from `first` to `last`.
+    ///
+    /// Without any days it's the start's day, which some months and years don't have, eg. the
+    /// 31st or the 29th of February.
+    fn dates_within(&self, first: Date, last: Date, year: i32, month: Month, day: u8) -> Vec<Date> {
+        if self.by_day.is_empty() {
+            return Date::from_calendar_date(year, month, day)
+                .into_iter()
+                .collect();
+        }
+
+        let mut dates = Vec::new();
+
+        for by_day in &self.by_day {
+            let days_after_first = days_between(first.weekday(), by_day.weekday);
+            let days_before_last = days_between(by_day.weekday, last.weekday());
+
+            match by_day.nth {
+                None => {
+                    let mut date = add_days(first, days_after_first);
+
+                    while let Some(v) = date.filter(|v| *v <= last) {
+                        dates.push(v);
+                        date = add_days(v, 7);
+                    }
+                }
+                Some(nth) if nth > 0 => {
+                    dates.extend(
+                        add_days(first, days_after_first + (i64::from(nth) - 1) * 7)
+                            .filter(|v| *v <= last),
+                    );
+                }
+                Some(nth) => {
+                    dates.extend(
+                        add_days(last, -days_before_last + (i64::from(nth) + 1) * 7)
+                            .filter(|v| *v >= first),
+                    );
+                }
+            }
+        }
+
+        dates.sort();
+        dates.dedup();
+
+        dates
+    }
+}
+
+fn add_days(date: Date, days: i64) -> Option<Date> {
+    date.checked_add(Duration::days(days))
+}
+
+/// The days from one weekday to the next `to`, 0 when they're the same.
+fn days_between(from: Weekday, to: Weekday) -> i64 {
+    (i64::from(to.number_days_from_monday()) - i64::from(from.number_days_from_monday()))
+        .rem_euclid(7)
+}
+
+fn parse_until(value: &str) -> Option<OffsetDateTime> {
+    let value = value.strip_suffix(['Z', 'z']).unwrap_or(value);
+
+    if let Ok(v) = PrimitiveDateTime::parse(value, DATE_TIME_FORMAT) {
+        return Some(v.assume_utc());
+    }
+
+    let date = Date::parse(value, DATE_FORMAT).ok()?;
+
+    Some(
+        date.with_time(Time::from_hms(23, 59, 59).ok()?)
+            .assume_utc(),
+    )
+}
+
+impl Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.freq.as_str())?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+
+        if !self.by_day.is_empty() {
+            let days = self.by_day.iter().map(ByDay::to_string).collect::<Vec<_>>();
+
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+
+        if let Some(until) = self.until {
+            let until = until
+                .to_offset(UtcOffset::UTC)
+                .format(DATE_TIME_FORMAT)
+                .map_err(|_| fmt::Error)?;
+
+            write!(f, ";UNTIL={until}Z")?;
+        }
+
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for RRule {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<RRuleRepr> for RRule {
+    type Error = GlobalError;
+
+    fn try_from(value: RRuleRepr) -> Result<Self> {
+        match value {
+            RRuleRepr::Text(v) => Self::parse(&v),
+            RRuleRepr::Fields(v) => {
+                let rule = Self {
+                    freq: v.freq,
+                    interval: v.interval,
+                    by_day: v.by_day,
+                    until: v.until,
+                    count: v.count,
+                };
+
+                rule.validate()?;
+
+                Ok(rule)
+            }
+        }
+    }
+}
+
+/// A rule which repeats from a start in a time zone.
+///
+/// The occurrences keep the start's local time, so a 09:00 event stays at 09:00 when summer
+/// time starts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recurrence {
+    /// The first occurrence, in the time zone's local time. It only counts as one if it
+    /// matches the rule.
+    pub start: PrimitiveDateTime,
+    /// An IANA time zone, eg. `Europe/London`. None = UTC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    pub rule: RRule,
+}
+
+impl Recurrence {
+    pub fn validate(&self) -> Result<()> {
+        self.rule.validate()?;
+
+        if let Some(zone) = &self.time_zone
+            && tz::database().is_some()
+            && tz::find_zone_by_id(zone).is_none()
+        {
+            return Err(GlobalError::UnknownTimeZone(zone.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// The occurrences from `from` until before `to`, at most [`MAX_OCCURRENCES`].
+    pub fn between(&self, from: OffsetDateTime, to: OffsetDateTime) -> Result<Vec<OffsetDateTime>> {
+        self.rule.validate()?;
+
+        // Local dates can be a day ahead of UTC.
+        let last = to.date().next_day().unwrap_or(Date::MAX);
+        let mut found = Vec::new();
+
+        for local in self.rule.local_occurrences(self.start, last) {
+            let at = local.assume_offset(self.offset(local)?);
+
+            if at >= to || self.rule.until.is_some_and(|v| at > v) {
+                break;
+            }
+
+            if at >= from {
+                found.push(at);
+
+                if found.len() == MAX_OCCURRENCES {
+                    break;
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
+    fn offset(&self, local: PrimitiveDateTime) -> Result<UtcOffset> {
+        match &self.time_zone {
+            Some(zone) => tz::find_local_offset(zone, local)
+                .ok_or_else(|| GlobalError::UnknownTimeZone(zone.clone())),
+            None => Ok(UtcOffset::UTC),
+        }
+    }
+}
@@ -0,0 +1,677 @@
+//! iCalendar (RFC 5545) feeds, eg. for "Add to Calendar" links and calendar subscriptions.
+//!
+//! Only events are supported. Parsing reads the properties a [`CalendarEvent`] has and skips
+//! the rest.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+
+use super::{DATE_FORMAT, DATE_TIME_FORMAT, RRule, Recurrence, add_days, days_between};
+use crate::{
+    duration,
+    email::Mailbox,
+    error::{GlobalError, Result},
+    tz::{self, DstRule},
+};
+
+/// Lines longer than this many bytes are folded onto the next.
+const MAX_LINE_LENGTH: usize = 75;
+
+const PRODUCT_ID: &str = "-//Webby//Global Common//EN";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Calendar {
+    /// Shown by some apps as the name of a subscribed calendar.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub events: Vec<CalendarEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    /// Stays the same when the event changes, so calendar apps update it instead of adding
+    /// another.
+    pub uid: String,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// In the time zone's local time.
+    pub start: PrimitiveDateTime,
+    pub end: PrimitiveDateTime,
+    /// Only the dates of `start` and `end` are used, and `end` is the day after the last day.
+    #[serde(default)]
+    pub all_day: bool,
+    /// An IANA time zone, eg. `Europe/London`. None = UTC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<RRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organizer: Option<Mailbox>,
+}
+
+impl CalendarEvent {
+    pub fn new<U: Into<String>, S: Into<String>>(
+        uid: U,
+        summary: S,
+        start: PrimitiveDateTime,
+        end: PrimitiveDateTime,
+    ) -> Self {
+        Self {
+            uid: uid.into(),
+            summary: summary.into(),
+            description: None,
+            location: None,
+            start,
+            end,
+            all_day: false,
+            time_zone: None,
+            rule: None,
+            organizer: None,
+        }
+    }
+
+    pub fn description<S: Into<String>>(mut self, value: S) -> Self {
+        self.description = Some(value.into());
+        self
+    }
+
+    pub fn location<S: Into<String>>(mut self, value: S) -> Self {
+        self.location = Some(value.into());
+        self
+    }
+
+    pub fn all_day(mut self) -> Self {
+        self.all_day = true;
+        self
+    }
+
+    pub fn time_zone<S: Into<String>>(mut self, value: S) -> Self {
+        self.time_zone = Some(value.into());
+        self
+    }
+
+    pub fn rule(mut self, value: RRule) -> Self {
+        self.rule = Some(value);
+        self
+    }
+
+    pub fn organizer(mut self, value: Mailbox) -> Self {
+        self.organizer = Some(value);
+        self
+    }
+
+    /// When the event repeats, to expand its occurrences.
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        Some(Recurrence {
+            start: self.start,
+            time_zone: self.time_zone.clone(),
+            rule: self.rule.clone()?,
+        })
+    }
+}
+
+impl Calendar {
+    pub fn new(events: Vec<CalendarEvent>) -> Self {
+        Self { name: None, events }
+    }
+
+    pub fn name<S: Into<String>>(mut self, value: S) -> Self {
+        self.name = Some(value.into());
+        self
+    }
+
+    /// The `.ics` file, with a `VTIMEZONE` for each time zone the events use.
+    pub fn render(&self) -> Result<String> {
+        let stamp = format_utc(OffsetDateTime::now_utc());
+        let mut lines = Lines::default();
+
+        lines.push("BEGIN:VCALENDAR");
+        lines.push("VERSION:2.0");
+        lines.push(&format!("PRODID:{PRODUCT_ID}"));
+        lines.push("CALSCALE:GREGORIAN");
+
+        if let Some(name) = &self.name {
+            lines.push(&format!("X-WR-CALNAME:{}", escape(name)));
+        }
+
+        let zones = self
+            .events
+            .iter()
+            .filter(|v| !v.all_day)
+            .filter_map(|v| v.time_zone.as_deref())
+            .collect::<BTreeSet<_>>();
+
+        for zone in zones {
+            render_time_zone(&mut lines, zone)?;
+        }
+
+        for event in &self.events {
+            lines.push("BEGIN:VEVENT");
+            lines.push(&format!("UID:{}", escape(&event.uid)));
+            lines.push(&format!("DTSTAMP:{stamp}"));
+            lines.push(&date_property("DTSTART", event.start, event)?);
+            lines.push(&date_property("DTEND", event.end, event)?);
+
+            if let Some(rule) = &event.rule {
+                lines.push(&format!("RRULE:{rule}"));
+            }
+
+            lines.push(&format!("SUMMARY:{}", escape(&event.summary)));
+
+            if let Some(description) = &event.description {
+                lines.push(&format!("DESCRIPTION:{}", escape(description)));
+            }
+
+            if let Some(location) = &event.location {
+                lines.push(&format!("LOCATION:{}", escape(location)));
+            }
+
+            if let Some(organizer) = &event.organizer {
+                let name = match organizer.name() {
+                    Some(name) => format!(";CN={}", param_value(name)),
+                    None => String::new(),
+                };
+
+                lines.push(&format!("ORGANIZER{name}:mailto:{}", organizer.address()));
+            }
+
+            lines.push("END:VEVENT");
+        }
+
+        lines.push("END:VCALENDAR");
+
+        Ok(lines.0)
+    }
+
+    /// Read the events of an `.ics` file.
+    ///
+    /// Times without a time zone are taken as UTC. An end in another time zone than the start
+    /// is converted to the start's.
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: String| GlobalError::ParseError(format!("ICS: {reason}"));
+
+        let mut calendar = Self::default();
+        let mut components = Vec::<String>::new();
+        let mut event = None::<PartialEvent>;
+        let mut found = false;
+
+        for line in unfold(value) {
+            let property =
+                Property::parse(&line).ok_or_else(|| invalid(format!("invalid line {line}")))?;
+
+            match property.name.as_str() {
+                "BEGIN" => {
+                    let component = property.value.to_ascii_uppercase();
+
+                    if components.is_empty() && component != "VCALENDAR" {
+                        return Err(invalid(format!("expected a VCALENDAR, found {component}")));
+                    }
+
+                    if component == "VEVENT" {
+                        event = Some(PartialEvent::default());
+                    }
+
+                    found = true;
+                    components.push(component);
+                }
+                "END" => {
+                    let component = property.value.to_ascii_uppercase();
+
+                    if components.pop().as_deref() != Some(component.as_str()) {
+                        return Err(invalid(format!("unexpected END:{component}")));
+                    }
+
+                    if component == "VEVENT"
+                        && let Some(event) = event.take()
+                    {
+                        calendar.events.push(event.build()?);
+                    }
+                }
+                "X-WR-CALNAME" if components.len() == 1 => {
+                    calendar.name = Some(unescape(&property.value));
+                }
+                _ => {
+                    if components.last().map(String::as_str) == Some("VEVENT")
+                        && let Some(event) = &mut event
+                    {
+                        event.set(property)?;
+                    }
+                }
+            }
+        }
+
+        if !found {
+            return Err(invalid(String::from("expected a VCALENDAR")));
+        }
+
+        if let Some(component) = components.last() {
+            return Err(invalid(format!("missing END:{component}")));
+        }
+
+        Ok(calendar)
+    }
+}
+
+/// Output lines, folded and ending with CRLF as the RFC requires.
+#[derive(Default)]
+struct Lines(String);
+
+impl Lines {
+    fn push(&mut self, line: &str) {
+        let mut length = 0;
+
+        for c in line.chars() {
+            // Continuation lines start with a space, which counts towards their length.
+            if length + c.len_utf8() > MAX_LINE_LENGTH {
+                self.0.push_str("\r\n ");
+                length = 1;
+            }
+
+            self.0.push(c);
+            length += c.len_utf8();
+        }
+
+        self.0.push_str("\r\n");
+    }
+}
+
+fn date_property(name: &str, value: PrimitiveDateTime, event: &CalendarEvent) -> Result<String> {
+    if event.all_day {
+        let date = value
+            .date()
+            .format(DATE_FORMAT)
+            .map_err(|e| GlobalError::ParseError(e.to_string()))?;
+
+        return Ok(format!("{name};VALUE=DATE:{date}"));
+    }
+
+    let local = value
+        .format(DATE_TIME_FORMAT)
+        .map_err(|e| GlobalError::ParseError(e.to_string()))?;
+
+    Ok(match &event.time_zone {
+        Some(zone) => format!("{name};TZID={zone}:{local}"),
+        None => format!("{name}:{local}Z"),
+    })
+}
+
+fn format_utc(value: OffsetDateTime) -> String {
+    let value = value.to_offset(UtcOffset::UTC);
+
+    PrimitiveDateTime::new(value.date(), value.time())
+        .format(DATE_TIME_FORMAT)
+        .map(|v| v + "Z")
+        .unwrap_or_default()
+}
+
+/// A `VTIMEZONE` from the zone's standard offset and daylight saving time rule.
+fn render_time_zone(lines: &mut Lines, id: &str) -> Result<()> {
+    let zone = tz::find_zone_by_id(id).ok_or_else(|| GlobalError::UnknownTimeZone(id.into()))?;
+    let standard = zone.standard_offset();
+
+    lines.push("BEGIN:VTIMEZONE");
+    lines.push(&format!("TZID:{id}"));
+
+    match zone.dst_rule() {
+        None => {
+            lines.push("BEGIN:STANDARD");
+            lines.push("DTSTART:19700101T000000");
+            lines.push(&format!("TZOFFSETFROM:{}", format_offset(standard)));
+            lines.push(&format!("TZOFFSETTO:{}", format_offset(standard)));
+            lines.push("END:STANDARD");
+        }
+        Some(rule) => {
+            let daylight = UtcOffset::from_whole_seconds(standard.whole_seconds() + 3600)
+                .map_err(|e| GlobalError::ParseError(e.to_string()))?;
+
+            // (month, nth Sunday, local time) of the start and the end of daylight time.
+            let (start, end) = match rule {
+                DstRule::NorthAmerica => ((Month::March, 2, 2), (Month::November, 1, 2)),
+                DstRule::Australia => ((Month::October, 1, 2), (Month::April, 1, 3)),
+                DstRule::NewZealand => ((Month::September, -1, 2), (Month::April, 1, 3)),
+                // Changes at 01:00 UTC everywhere.
+                DstRule::Europe => {
+                    let hour = 1 + standard.whole_hours();
+
+                    ((Month::March, -1, hour), (Month::October, -1, hour + 1))
+                }
+            };
+
+            for (kind, (month, nth, hour), from, to) in [
+                ("DAYLIGHT", start, standard, daylight),
+                ("STANDARD", end, daylight, standard),
+            ] {
+                let date = nth_sunday(1970, month, nth)
+                    .and_then(|v| Some(v.with_time(Time::from_hms(hour as u8, 0, 0).ok()?)))
+                    .ok_or_else(|| GlobalError::UnknownTimeZone(id.into()))?;
+                let date = date
+                    .format(DATE_TIME_FORMAT)
+                    .map_err(|e| GlobalError::ParseError(e.to_string()))?;
+
+                lines.push(&format!("BEGIN:{kind}"));
+                lines.push(&format!("DTSTART:{date}"));
+                lines.push(&format!(
+                    "RRULE:FREQ=YEARLY;BYMONTH={};BYDAY={nth}SU",
+                    u8::from(month)
+                ));
+                lines.push(&format!("TZOFFSETFROM:{}", format_offset(from)));
+                lines.push(&format!("TZOFFSETTO:{}", format_offset(to)));
+                lines.push(&format!("END:{kind}"));
+            }
+        }
+    }
+
+    lines.push("END:VTIMEZONE");
+
+    Ok(())
+}
+
+/// The nth Sunday of the month, counting from the end when negative.
+fn nth_sunday(year: i32, month: Month, nth: i8) -> Option<Date> {
+    let nth = i64::from(nth);
+
+    if nth > 0 {
+        let first = Date::from_calendar_date(year, month, 1).ok()?;
+
+        add_days(
+            first,
+            days_between(first.weekday(), Weekday::Sunday) + (nth - 1) * 7,
+        )
+    } else {
+        let last = Date::from_calendar_date(year, month, month.length(year)).ok()?;
+
+        add_days(
+            last,
+            -days_between(Weekday::Sunday, last.weekday()) + (nth + 1) * 7,
+        )
+    }
+}
+
+/// `+0100` or `-0500`.
+fn format_offset(offset: UtcOffset) -> String {
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+
+    format!(
+        "{sign}{:02}{:02}",
+        hours.unsigned_abs(),
+        minutes.unsigned_abs()
+    )
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Parameter values can't contain quotes, and need them around `:`, `;` and `,`.
+fn param_value(value: &str) -> String {
+    let value = value.replace(['"', '\r', '\n'], "");
+
+    if value.contains([':', ';', ',']) {
+        format!("\"{value}\"")
+    } else {
+        value
+    }
+}
+
+/// Join folded lines back together.
+fn unfold(value: &str) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+
+    for line in value.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Split on a character which isn't in quotes.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(&value[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+
+    parts.push(&value[start..]);
+    parts
+}
+
+/// `NAME;PARAM=value:VALUE`
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        let mut quoted = false;
+
+        let colon = line.char_indices().find_map(|(i, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+
+            (c == ':' && !quoted).then_some(i)
+        })?;
+
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = split_unquoted(head, ';').into_iter();
+
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        let params = parts
+            .filter_map(|v| v.split_once('='))
+            .map(|(k, v)| {
+                (
+                    k.trim().to_ascii_uppercase(),
+                    v.trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+
+        (!name.is_empty()).then(|| Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A `DTSTART` or `DTEND`.
+struct DateValue {
+    local: PrimitiveDateTime,
+    time_zone: Option<String>,
+    date_only: bool,
+}
+
+impl DateValue {
+    fn parse(property: &Property) -> Result<Self> {
+        let invalid = || {
+            GlobalError::ParseError(format!("ICS: invalid {} {}", property.name, property.value))
+        };
+
+        let value = property.value.trim();
+
+        if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+            let date = Date::parse(value, DATE_FORMAT).map_err(|_| invalid())?;
+
+            return Ok(Self {
+                local: date.midnight(),
+                time_zone: None,
+                date_only: true,
+            });
+        }
+
+        let (value, utc) = match value.strip_suffix(['Z', 'z']) {
+            Some(v) => (v, true),
+            None => (value, false),
+        };
+
+        Ok(Self {
+            local: PrimitiveDateTime::parse(value, DATE_TIME_FORMAT).map_err(|_| invalid())?,
+            time_zone: (!utc)
+                .then(|| property.param("TZID").map(String::from))
+                .flatten(),
+            date_only: false,
+        })
+    }
+
+    /// The local date & time in another time zone.
+    fn local_in(&self, time_zone: Option<&str>) -> Result<PrimitiveDateTime> {
+        if self.date_only || self.time_zone.as_deref() == time_zone {
+            return Ok(self.local);
+        }
+
+        let unknown = |zone: &str| GlobalError::UnknownTimeZone(zone.into());
+
+        let offset = match &self.time_zone {
+            Some(zone) => tz::find_local_offset(zone, self.local).ok_or_else(|| unknown(zone))?,
+            None => UtcOffset::UTC,
+        };
+        let at = self.local.assume_offset(offset);
+
+        let offset = match time_zone {
+            Some(zone) => tz::find_offset_at(zone, at).ok_or_else(|| unknown(zone))?,
+            None => UtcOffset::UTC,
+        };
+        let at = at.to_offset(offset);
+
+        Ok(PrimitiveDateTime::new(at.date(), at.time()))
+    }
+}
+
+#[derive(Default)]
+struct PartialEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    start: Option<DateValue>,
+    end: Option<DateValue>,
+    duration: Option<Duration>,
+    rule: Option<RRule>,
+    organizer: Option<Mailbox>,
+}
+
+impl PartialEvent {
+    fn set(&mut self, property: Property) -> Result<()> {
+        match property.name.as_str() {
+            "UID" => self.uid = Some(unescape(&property.value)),
+            "SUMMARY" => self.summary = Some(unescape(&property.value)),
+            "DESCRIPTION" => self.description = Some(unescape(&property.value)),
+            "LOCATION" => self.location = Some(unescape(&property.value)),
+            "DTSTART" => self.start = Some(DateValue::parse(&property)?),
+            "DTEND" => self.end = Some(DateValue::parse(&property)?),
+            "DURATION" => self.duration = Some(duration::parse(&property.value)?),
+            "RRULE" => self.rule = Some(RRule::parse(&property.value)?),
+            "ORGANIZER" => {
+                let value = property.value.trim();
+                let address = value
+                    .get(..7)
+                    .filter(|v| v.eq_ignore_ascii_case("mailto:"))
+                    .map_or(value, |_| &value[7..]);
+
+                let mut organizer = Mailbox::parse(address)?;
+
+                if let Some(name) = property.param("CN").filter(|v| !v.trim().is_empty()) {
+                    organizer = organizer.with_name(name.trim())?;
+                }
+
+                self.organizer = Some(organizer);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn build(self) -> Result<CalendarEvent> {
+        let missing = |name: &str| GlobalError::ParseError(format!("ICS: an event has no {name}"));
+
+        let start = self.start.ok_or_else(|| missing("DTSTART"))?;
+        let uid = self.uid.ok_or_else(|| missing("UID"))?;
+
+        let end = match (self.end, self.duration) {
+            (Some(end), _) => end.local_in(start.time_zone.as_deref())?,
+            (None, Some(duration)) => start.local + duration,
+            // A day long for dates and instant for times.
+            (None, None) if start.date_only => start.local + Duration::DAY,
+            (None, None) => start.local,
+        };
+
+        Ok(CalendarEvent {
+            uid,
+            summary: self.summary.unwrap_or_default(),
+            description: self.description,
+            location: self.location,
+            start: start.local,
+            end,
+            all_day: start.date_only,
+            time_zone: start.time_zone,
+            rule: self.rule,
+            organizer: self.organizer,
+        })
+    }
+}
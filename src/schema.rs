@@ -1,52 +1,130 @@
 //! General schema definitions for the API.
 
+pub mod builder;
+pub mod computed;
+pub mod constraints;
+pub mod diff;
+pub mod field_map;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod json_schema;
+pub mod path;
+pub mod references;
+pub mod validation;
+pub mod version;
+
 use std::{
-    collections::HashMap,
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
     fmt::Display,
     hash::{Hash, Hasher},
+    ops::Deref,
+    str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 
-use eyre::{anyhow, Result};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use serde::{Deserialize, Serialize};
-use time::{macros::format_description, Date, OffsetDateTime, PrimitiveDateTime, Time};
+use serde::{
+    Deserialize, Serialize,
+    de::{DeserializeSeed, MapAccess, Visitor},
+};
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset,
+    format_description::well_known::Rfc3339, macros::format_description,
+};
 use url::Url;
 use uuid::Uuid;
 
+use self::{
+    computed::FieldSource, constraints::FieldConstraints, field_map::OrderedFieldMap,
+    version::SchemaVersion,
+};
 use crate::{
+    duration,
+    error::{GlobalError, Result},
+    geo::GeoPoint,
     id::WebsitePublicId,
+    money::Money,
+    slug::Slug,
+    tz,
+    upload::MediaGalleryItem,
+    validators::{EmailAddress, PhoneNumber, PostalAddress},
     value::{Number, SimpleValue},
 };
 
-pub type SchemaFieldMap = HashMap<SchematicFieldKey, SchematicField>;
+pub type SchemaFieldMap = OrderedFieldMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct Schematic {
     pub id: String,
     /// What the schema is for: Forms, Members, Marketing, Billing, etc.
     pub namespace: String,
     /// The field to display if it's being referenced.
+    #[serde(alias = "primary_field")]
     pub primary_field: String,
     /// The name of the schema.
+    #[serde(alias = "display_name")]
     pub display_name: String,
     /// The capabilities of the schema.
     pub permissions: SchematicPermissions,
-    pub version: f64,
+    pub version: SchemaVersion,
     /// The operations allowed on the schema.
-    pub allowed_operations: Vec<String>,
+    #[serde(alias = "allowed_operations")]
+    pub allowed_operations: Vec<Operations>,
+    #[serde(alias = "is_deleted")]
     pub is_deleted: bool,
+    #[serde(alias = "owner_app_id")]
     pub owner_app_id: WebsitePublicId,
     pub fields: SchemaFieldMap,
     // pub storage: String,
     /// Time to live
+    #[cfg_attr(feature = "ts", ts(type = "{ secs: number, nanos: number } | null"))]
     pub ttl: Option<Duration>,
+    #[serde(alias = "default_sort")]
     pub default_sort: Option<DefaultSort>,
     // pub paging_mode: Vec<String>,
     pub views: Vec<SchemaView>,
 }
 
+impl Schematic {
+    pub fn operations(&self) -> OperationSet {
+        self.allowed_operations.iter().cloned().collect()
+    }
+
+    /// Reject writes from clients built against an incompatible version of the schema.
+    ///
+    /// Clients which don't send their version are let through.
+    pub fn check_client_version(&self, client: Option<SchemaVersion>) -> Result<()> {
+        match client {
+            Some(v) => v.check_compatible(&self.version),
+            None => Ok(()),
+        }
+    }
+
+    /// The names of the fields, for reading rows which share them.
+    pub fn field_keys(&self) -> FieldKeys {
+        FieldKeys::new(self.fields.keys().map(SchematicFieldKey::as_str))
+    }
+
+    /// Fill in the default value of every field missing from a record which is being inserted.
+    pub fn apply_defaults(&self, record: &mut HashMap<SchematicFieldKey, SimpleValue>) {
+        for (key, field) in &self.fields {
+            if field.is_deleted || record.contains_key(key) {
+                continue;
+            }
+
+            if let Some(value) = &field.default_value {
+                record.insert(key.clone(), SimpleValue::from(value.clone()));
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaView {
     pub name: String,
@@ -65,6 +143,7 @@ impl Default for SchemaView {
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct SchemaViewTypes {
     pub form: SchemaViewItem,
     pub gallery: SchemaViewItem,
@@ -73,6 +152,7 @@ pub struct SchemaViewTypes {
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaViewItem {
     #[serde(default)]
@@ -80,12 +160,14 @@ pub struct SchemaViewItem {
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct SchemaViewQuery {
     pub sort: Vec<DefaultSort>,
     pub filter: Vec<SchemaFilter>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct SchematicPermissions {
     pub insert: PermissionsUser,
     pub update: PermissionsUser,
@@ -104,14 +186,23 @@ impl Default for SchematicPermissions {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Who may perform an action. Admins may always perform it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub enum PermissionsUser {
     Anyone,
     Admin,
+    /// The user who created the record.
     Owner,
+    /// Any signed in user.
+    Authenticated,
+    /// Users with the role.
+    Role(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Serialized as the names used in `allowed_operations`, eg. `BulkInsert`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
 pub enum Operations {
     BulkInsert,
     BulkSave,
@@ -132,19 +223,228 @@ pub enum Operations {
     BulkRemove,
     Aggregate,
     InsertReference,
+    /// An operation this version doesn't know, kept by name so it's written back unchanged.
+    Unknown(String),
+}
+
+impl Operations {
+    pub const ALL: [Self; 19] = [
+        Self::BulkInsert,
+        Self::BulkSave,
+        Self::QueryReferenced,
+        Self::Truncate,
+        Self::ReplaceReferences,
+        Self::Count,
+        Self::Get,
+        Self::Find,
+        Self::RemoveReference,
+        Self::IsReferenced,
+        Self::Distinct,
+        Self::Remove,
+        Self::BulkUpdate,
+        Self::Insert,
+        Self::Save,
+        Self::Update,
+        Self::BulkRemove,
+        Self::Aggregate,
+        Self::InsertReference,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::BulkInsert => "BulkInsert",
+            Self::BulkSave => "BulkSave",
+            Self::QueryReferenced => "QueryReferenced",
+            Self::Truncate => "Truncate",
+            Self::ReplaceReferences => "ReplaceReferences",
+            Self::Count => "Count",
+            Self::Get => "Get",
+            Self::Find => "Find",
+            Self::RemoveReference => "RemoveReference",
+            Self::IsReferenced => "IsReferenced",
+            Self::Distinct => "Distinct",
+            Self::Remove => "Remove",
+            Self::BulkUpdate => "BulkUpdate",
+            Self::Insert => "Insert",
+            Self::Save => "Save",
+            Self::Update => "Update",
+            Self::BulkRemove => "BulkRemove",
+            Self::Aggregate => "Aggregate",
+            Self::InsertReference => "InsertReference",
+            Self::Unknown(name) => name,
+        }
+    }
+
+    /// Whether the operation only reads records.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            Self::Count
+                | Self::Get
+                | Self::Find
+                | Self::QueryReferenced
+                | Self::IsReferenced
+                | Self::Distinct
+                | Self::Aggregate
+        )
+    }
+}
+
+impl Display for Operations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Operations {
+    type Err = GlobalError;
+
+    /// Case-insensitive, also accepting `bulk_insert` / `bulk-insert` style names. Unknown names
+    /// are an error, only stored operations are kept as [`Operations::Unknown`].
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.replace(['_', '-', ' '], "");
+
+        Self::ALL
+            .into_iter()
+            .find(|v| v.as_str().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| GlobalError::ParseError(format!("Unknown operation {s}")))
+    }
+}
+
+impl Serialize for Operations {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Operations {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        Ok(name.parse().unwrap_or(Self::Unknown(name)))
+    }
 }
 
+/// A set of [`Operations`], stored as bit flags. [`Operations::Unknown`] can't be stored, so
+/// it's never contained and inserting it does nothing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OperationSet(u32);
+
+impl OperationSet {
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn full() -> Self {
+        Self::ALL_BITS
+    }
+
+    /// Every operation which doesn't change records.
+    pub fn read_only() -> Self {
+        Operations::ALL
+            .into_iter()
+            .filter(|v| v.is_read_only())
+            .collect()
+    }
+
+    const ALL_BITS: Self = Self((1 << Operations::ALL.len()) - 1);
+
+    fn bit(op: &Operations) -> u32 {
+        Operations::ALL
+            .iter()
+            .position(|v| v == op)
+            .map_or(0, |i| 1 << i)
+    }
+
+    pub fn contains(self, op: Operations) -> bool {
+        self.0 & Self::bit(&op) != 0
+    }
+
+    pub fn insert(&mut self, op: Operations) {
+        self.0 |= Self::bit(&op);
+    }
+
+    pub fn remove(&mut self, op: Operations) {
+        self.0 &= !Self::bit(&op);
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// The operations in the order of [`Operations::ALL`].
+    pub fn iter(self) -> impl Iterator<Item = Operations> {
+        Operations::ALL
+            .into_iter()
+            .filter(move |v| self.0 & Self::bit(v) != 0)
+    }
+}
+
+impl FromIterator<Operations> for OperationSet {
+    fn from_iter<T: IntoIterator<Item = Operations>>(iter: T) -> Self {
+        let mut set = Self::empty();
+
+        for op in iter {
+            set.insert(op);
+        }
+
+        set
+    }
+}
+
+impl Serialize for OperationSet {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for OperationSet {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        Ok(Vec::<Operations>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+/// The key of a field.
 #[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
 pub enum SchematicFieldKey {
     Id,
     Owner,
     CreatedAt,
     UpdatedAt,
-    Other(String),
-    OtherStatic(&'static str),
+    Other(FieldName),
 }
 
 impl SchematicFieldKey {
+    /// A key which doesn't allocate, for names known at compile time.
+    pub fn from_static(value: &'static str) -> Self {
+        Self::system(value).unwrap_or(Self::Other(FieldName(Name::Static(value))))
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             Self::Id => "_id",
@@ -152,12 +452,176 @@ impl SchematicFieldKey {
             Self::CreatedAt => "_createdAt",
             Self::UpdatedAt => "_updatedAt",
             Self::Other(s) => s,
-            Self::OtherStatic(s) => s,
         }
     }
 
     pub fn is_other(&self) -> bool {
-        matches!(self, Self::Other(_) | Self::OtherStatic(_))
+        matches!(self, Self::Other(_))
+    }
+
+    fn system(value: &str) -> Option<Self> {
+        Some(match value {
+            "_id" => Self::Id,
+            "_owner" => Self::Owner,
+            "_createdAt" => Self::CreatedAt,
+            "_updatedAt" => Self::UpdatedAt,
+            _ => return None,
+        })
+    }
+}
+
+/// The name of a user-defined field. Compares, hashes and prints as the name itself, however
+/// it's stored.
+#[derive(Clone)]
+pub struct FieldName(Name);
+
+#[derive(Clone)]
+enum Name {
+    Static(&'static str),
+    /// Shared with every row read with the same [`FieldKeys`].
+    Shared(Arc<str>),
+}
+
+impl FieldName {
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Name::Static(s) => s,
+            Name::Shared(s) => s,
+        }
+    }
+}
+
+impl Deref for FieldName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Debug for FieldName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for FieldName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl PartialEq for FieldName {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for FieldName {}
+
+impl Hash for FieldName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl From<&str> for FieldName {
+    fn from(value: &str) -> Self {
+        Self(Name::Shared(Arc::from(value)))
+    }
+}
+
+impl From<String> for FieldName {
+    fn from(value: String) -> Self {
+        Self(Name::Shared(Arc::from(value)))
+    }
+}
+
+/// The field names of a schema, shared by the keys of every row read with it so they aren't
+/// allocated for each row.
+///
+/// Only the schema's names are shared. Anything else in a row is allocated as usual, so rows
+/// can't grow it.
+#[derive(Debug, Clone, Default)]
+pub struct FieldKeys(HashSet<Arc<str>>);
+
+impl FieldKeys {
+    pub fn new<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        Self(
+            names
+                .into_iter()
+                .filter(|v| SchematicFieldKey::system(v).is_none())
+                .map(Arc::from)
+                .collect(),
+        )
+    }
+
+    pub fn key(&self, name: &str) -> SchematicFieldKey {
+        SchematicFieldKey::system(name).unwrap_or_else(|| {
+            SchematicFieldKey::Other(match self.0.get(name) {
+                Some(shared) => FieldName(Name::Shared(shared.clone())),
+                None => FieldName::from(name),
+            })
+        })
+    }
+}
+
+/// Reads the fields of a row, eg. [`CmsRowResponse::fields`](crate::response::CmsRowResponse).
+impl<'de> DeserializeSeed<'de> for &FieldKeys {
+    type Value = HashMap<SchematicFieldKey, SimpleValue>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        struct FieldsVisitor<'a>(&'a FieldKeys);
+
+        impl<'de> Visitor<'de> for FieldsVisitor<'_> {
+            type Value = HashMap<SchematicFieldKey, SimpleValue>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of fields")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut fields =
+                    HashMap::with_capacity(map.size_hint().unwrap_or_default().min(256));
+
+                while let Some(key) = map.next_key_seed(KeySeed(self.0))? {
+                    fields.insert(key, map.next_value()?);
+                }
+
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor(self))
+    }
+}
+
+struct KeySeed<'a>(&'a FieldKeys);
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = SchematicFieldKey;
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeySeed<'_> {
+    type Value = SchematicFieldKey;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    // Borrowed or not, the name is only copied if it isn't one of the schema's.
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.0.key(v))
     }
 }
 
@@ -167,6 +631,12 @@ impl Hash for SchematicFieldKey {
     }
 }
 
+impl Borrow<str> for SchematicFieldKey {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
 impl Display for SchematicFieldKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.as_str().fmt(f)
@@ -199,47 +669,120 @@ impl Serialize for SchematicFieldKey {
 
 impl<'de> Deserialize<'de> for SchematicFieldKey {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let s = String::deserialize(deserializer)?;
+        Ok(Self::from(String::deserialize(deserializer)?))
+    }
+}
 
-        Ok(match s.as_str() {
-            "_id" => Self::Id,
-            "_owner" => Self::Owner,
-            "_createdAt" => Self::CreatedAt,
-            "_updatedAt" => Self::UpdatedAt,
-            _ => Self::Other(s),
-        })
+impl From<String> for SchematicFieldKey {
+    fn from(value: String) -> Self {
+        Self::system(&value).unwrap_or_else(|| Self::Other(FieldName::from(value)))
+    }
+}
+
+impl From<&str> for SchematicFieldKey {
+    fn from(value: &str) -> Self {
+        Self::system(value).unwrap_or_else(|| Self::Other(FieldName::from(value)))
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct SchematicField {
+    #[serde(alias = "display_name")]
     pub display_name: String,
     pub sortable: bool,
+    #[serde(alias = "is_deleted")]
     pub is_deleted: bool,
+    #[serde(alias = "system_field")]
     pub system_field: bool,
+    #[serde(alias = "field_type")]
     pub field_type: SchematicFieldType,
     pub index: u16,
 
     // Reference
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "referenced_schema", skip_serializing_if = "Option::is_none")]
     pub referenced_schema: Option<String>,
-    // TODO: Default value setter - used for when "duplicating another field"
+
+    /// The value used when a record is inserted without this field.
+    #[serde(
+        alias = "default_value",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default_value: Option<SchematicFieldValue>,
+
+    #[serde(default, skip_serializing_if = "FieldConstraints::is_empty")]
+    pub constraints: FieldConstraints,
+
+    /// Stores a [`LocalizedString`](crate::i18n::LocalizedString) per row instead of a single
+    /// value. Only for [localizable](SchematicFieldType::is_localizable) types.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub localized: bool,
+
+    /// Whether the value is written by clients or [computed](computed::FieldSource::Computed)
+    /// from the other fields.
+    #[serde(default, skip_serializing_if = "FieldSource::is_stored")]
+    pub source: FieldSource,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaFilter {
     pub field: String,
     pub condition: String,
     pub value: SchematicFieldValue,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub struct DefaultSort {
     pub field: String,
     pub order: SortOrder,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Written as `field:asc` or `field:desc`.
+impl Display for DefaultSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let order = match self.order {
+            SortOrder::Ascending => "asc",
+            SortOrder::Descending => "desc",
+        };
+
+        write!(f, "{}:{order}", self.field)
+    }
+}
+
+impl FromStr for DefaultSort {
+    type Err = GlobalError;
+
+    /// A field without an order is sorted ascending.
+    fn from_str(s: &str) -> Result<Self> {
+        let (field, order) = match s.rsplit_once(':') {
+            Some((field, order)) if order.eq_ignore_ascii_case("asc") => {
+                (field, SortOrder::Ascending)
+            }
+            Some((field, order)) if order.eq_ignore_ascii_case("desc") => {
+                (field, SortOrder::Descending)
+            }
+            Some(_) => return Err(GlobalError::ParseError(format!("Invalid sort {s}"))),
+            None => (s, SortOrder::Ascending),
+        };
+
+        if field.is_empty() {
+            return Err(GlobalError::ParseError(format!("Invalid sort {s}")));
+        }
+
+        Ok(Self {
+            field: field.to_string(),
+            order,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub enum SortOrder {
     #[serde(rename = "asc")]
     Ascending,
@@ -248,6 +791,7 @@ pub enum SortOrder {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 pub enum SchematicFieldBasicType {
     Text,
     Number,
@@ -260,6 +804,7 @@ pub enum SchematicFieldBasicType {
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TryFromPrimitive, IntoPrimitive,
 )]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[repr(i32)]
 pub enum SchematicFieldType {
     /// A string of text.
@@ -308,19 +853,26 @@ pub enum SchematicFieldType {
     Array,
     /// An object.
     Object,
+    /// An amount of money in a currency.
+    Currency,
+    /// A point on the earth.
+    Location,
+    /// The URL part of a row, eg. `my-first-post`.
+    Slug,
+    /// A length of time, which unlike a Time can be longer than a day.
+    Duration,
 }
 
 impl SchematicFieldType {
+    /// Whether the field can be [localized](SchematicField::localized).
+    pub fn is_localizable(self) -> bool {
+        matches!(self, Self::Text | Self::RichText | Self::RichContent)
+    }
+
     // TODO: Better Name. Used to determine if bytes being uploaded are a file or not.
+    /// Same as the field accepting any [`MediaKind`](crate::upload::mime::MediaKind).
     pub fn is_upload_file_type(&self) -> bool {
-        matches!(
-            self,
-            SchematicFieldType::Document
-                | SchematicFieldType::MultiDocument
-                | SchematicFieldType::Audio
-                | SchematicFieldType::Image
-                | SchematicFieldType::Video
-        )
+        !self.allowed_media_kinds().is_empty()
     }
 
     pub fn max_bytes_length(&self) -> Option<usize> {
@@ -348,6 +900,10 @@ impl SchematicFieldType {
             Self::Tags => None, // TODO
             Self::Array => None,
             Self::Object => None,
+            Self::Currency => Some(100),
+            Self::Location => Some(100),
+            Self::Slug => Some(Slug::MAX_LEN),
+            Self::Duration => Some(50),
         }
     }
 
@@ -367,18 +923,26 @@ impl SchematicFieldType {
             | SchematicFieldType::RichText
             | SchematicFieldType::Reference
             | SchematicFieldType::Array
-            | SchematicFieldType::Object => Ok(SimpleValue::Text(String::from_utf8(bytes)?)),
+            | SchematicFieldType::Object
+            | SchematicFieldType::Currency
+            | SchematicFieldType::Location
+            | SchematicFieldType::Slug
+            | SchematicFieldType::Duration => Ok(SimpleValue::Text(String::from_utf8(bytes)?)),
             SchematicFieldType::Document
             | SchematicFieldType::Image
             | SchematicFieldType::Video
             | SchematicFieldType::Audio => Ok(SimpleValue::ListNumber(
                 bytes.into_iter().map(|v| v.into()).collect(),
             )),
+            // Sent as a JSON array of items (or UUIDs).
+            SchematicFieldType::MediaGallery => {
+                Ok(SimpleValue::ArrayUnknown(serde_json::from_slice(&bytes)?))
+            }
+            // Sent as a JSON array of UUIDs/strings.
             SchematicFieldType::MultiReference
-            | SchematicFieldType::MediaGallery
             | SchematicFieldType::MultiDocument
             | SchematicFieldType::Tags => {
-                todo!("{:?} {bytes:?}", String::from_utf8_lossy(&bytes));
+                Ok(SimpleValue::ListString(serde_json::from_slice(&bytes)?))
             }
         }
     }
@@ -388,35 +952,19 @@ impl SchematicFieldType {
             Self::Text => SchematicFieldValue::Text(received.try_as_text()?),
             Self::Number => SchematicFieldValue::Number(received.try_as_number()?),
             Self::URL => SchematicFieldValue::Url(Url::parse(&received.try_as_text()?)?),
-            Self::Email => SchematicFieldValue::Email(received.try_as_text()?),
-            Self::Phone => SchematicFieldValue::Phone(received.try_as_text()?),
-            Self::Address => SchematicFieldValue::Address(received.try_as_text()?),
+            Self::Email => SchematicFieldValue::Email(received.try_as_text()?.parse()?),
+            Self::Phone => SchematicFieldValue::Phone(received.try_as_text()?.parse()?),
+            Self::Address => SchematicFieldValue::Address(PostalAddress::from_value(&received)?),
             Self::Boolean => SchematicFieldValue::Boolean(match received.try_as_text()?.as_str() {
                 "1" | "on" | "true" => true,
                 "0" | "off" | "false" => false,
                 v => v.parse()?,
             }),
-            Self::DateTime => SchematicFieldValue::DateTime({
-                if let Ok(v) = PrimitiveDateTime::parse(
-                    &received.any_as_text()?,
-                    format_description!("[year]-[month]-[day]T[hour]:[minute]"),
-                ) {
-                    v.assume_utc()
-                } else if let Ok(v) = PrimitiveDateTime::parse(
-                    &received.any_as_text()?,
-                    format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
-                ) {
-                    v.assume_utc()
-                } else {
-                    PrimitiveDateTime::parse(
-                        &received.any_as_text()?,
-                        format_description!(
-                            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"
-                        ),
-                    )?
-                    .assume_utc()
-                }
-            }),
+            Self::DateTime => {
+                SchematicFieldValue::DateTime(parse_date_time(&received.any_as_text()?, |_| {
+                    UtcOffset::UTC
+                })?)
+            }
             Self::Date => SchematicFieldValue::Date(Date::parse(
                 &received.any_as_text()?,
                 format_description!("[year]-[month]-[day]"),
@@ -444,18 +992,22 @@ impl SchematicFieldType {
                     .map(|v| v.parse())
                     .collect::<std::result::Result<Vec<_>, _>>()?,
             ),
-            Self::MediaGallery => SchematicFieldValue::MultiReference(
-                received
-                    .try_as_list_string()?
-                    .into_iter()
-                    .map(|v| v.parse())
-                    .collect::<std::result::Result<Vec<_>, _>>()?,
-            ),
+            Self::MediaGallery => {
+                SchematicFieldValue::MediaGallery(MediaGalleryItem::list_from_value(&received)?)
+            }
             Self::Document | Self::Image | Self::Video | Self::Audio => {
                 SchematicFieldValue::ListNumber(received.try_as_list_number()?)
             }
-            Self::MultiDocument => todo!("Multi Document"),
-            Self::Tags => SchematicFieldValue::ListNumber(received.try_as_list_number()?),
+            Self::MultiDocument => SchematicFieldValue::ListString(received.try_as_list_string()?),
+            Self::Tags => SchematicFieldValue::Tags(normalize_tags(
+                match received {
+                    // Tags used to be parsed into a `SchematicFieldValue::ListNumber`, which is
+                    // how values stored before they were strings are read back.
+                    SimpleValue::ListNumber(v) => v.into_iter().map(|v| v.to_string()).collect(),
+                    v => v.try_as_list_string()?,
+                },
+                false,
+            )),
             Self::Array => {
                 let value = match received {
                     SimpleValue::Text(v) => serde_json::from_str(&v)?,
@@ -472,9 +1024,36 @@ impl SchematicFieldType {
 
                 SchematicFieldValue::Object(value)
             }
+            Self::Currency => SchematicFieldValue::Money(Money::from_value(&received)?),
+            Self::Location => SchematicFieldValue::Location(GeoPoint::from_value(&received)?),
+            Self::Slug => SchematicFieldValue::Slug(Slug::parse(&received.try_as_text()?)?),
+            Self::Duration => {
+                SchematicFieldValue::Duration(duration::parse(&received.try_as_text()?)?)
+            }
         })
     }
 
+    /// Same as [`SchematicFieldType::parse_value`] but Date & Time values without an explicit
+    /// offset are in the IANA time zone `tz_id` instead of UTC.
+    pub fn parse_value_in_zone(
+        self,
+        received: SimpleValue,
+        tz_id: &str,
+    ) -> Result<SchematicFieldValue> {
+        match self {
+            Self::DateTime => {
+                let zone = tz::find_zone_by_id(tz_id)
+                    .ok_or_else(|| GlobalError::UnknownTimeZone(tz_id.to_string()))?;
+
+                Ok(SchematicFieldValue::DateTime(parse_date_time(
+                    &received.any_as_text()?,
+                    |local| zone.local_offset(local),
+                )?))
+            }
+            _ => self.parse_value(received),
+        }
+    }
+
     pub fn as_name(self) -> &'static str {
         match self {
             Self::Text => "Text",
@@ -500,11 +1079,45 @@ impl SchematicFieldType {
             Self::Tags => "Tags",
             Self::Array => "Array",
             Self::Object => "Object",
+            Self::Currency => "Currency",
+            Self::Location => "Location",
+            Self::Slug => "Slug",
+            Self::Duration => "Duration",
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Parse RFC3339, or a local date & time which is then assumed to be at the offset returned.
+fn parse_date_time(
+    value: &str,
+    offset: impl Fn(PrimitiveDateTime) -> UtcOffset,
+) -> Result<OffsetDateTime> {
+    if let Ok(v) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(v);
+    }
+
+    let local = if let Ok(v) = PrimitiveDateTime::parse(
+        value,
+        format_description!("[year]-[month]-[day]T[hour]:[minute]"),
+    ) {
+        v
+    } else if let Ok(v) = PrimitiveDateTime::parse(
+        value,
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+    ) {
+        v
+    } else {
+        PrimitiveDateTime::parse(
+            value,
+            format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"),
+        )?
+    };
+
+    Ok(local.assume_offset(offset(local)))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
 #[serde(tag = "type", content = "value")]
 pub enum SchematicFieldValue {
     // Url gets serialized/deserialized to/from a String
@@ -513,21 +1126,57 @@ pub enum SchematicFieldValue {
     Boolean(bool),
 
     Url(Url),
-    Email(String),
-    Phone(String),
-    Address(String),
+    Email(EmailAddress),
+    Phone(PhoneNumber),
+    Address(PostalAddress),
 
-    DateTime(OffsetDateTime),
-    Date(Date),
-    Time(Time),
+    DateTime(#[cfg_attr(feature = "ts", ts(type = "string"))] OffsetDateTime),
+    Date(#[cfg_attr(feature = "ts", ts(type = "string"))] Date),
+    Time(#[cfg_attr(feature = "ts", ts(type = "string"))] Time),
     // TODO: WebsiteUploadLinkPublicId ??
     Reference(Uuid),
     MultiReference(Vec<Uuid>),
+    MediaGallery(Vec<MediaGalleryItem>),
     ListString(Vec<String>),
     ListNumber(Vec<Number>),
+    /// Tags stored before they were strings are a [`SchematicFieldValue::ListNumber`], which
+    /// [`SchematicFieldType::parse_value`] turns into Tags.
+    Tags(Vec<String>),
 
     Array(Vec<serde_json::Value>),
     Object(serde_json::Value),
+
+    Money(Money),
+    Location(GeoPoint),
+    Slug(Slug),
+    Duration(
+        #[serde(with = "duration::iso8601")]
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        time::Duration,
+    ),
+}
+
+/// Trim tags, drop empty ones and remove duplicates, keeping the first occurrence.
+///
+/// With `lowercase` tags are also lowercased, which makes duplicates case-insensitive.
+pub fn normalize_tags<I: IntoIterator<Item = String>>(tags: I, lowercase: bool) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+
+    for tag in tags {
+        let tag = tag.trim();
+
+        let tag = if lowercase {
+            tag.to_lowercase()
+        } else {
+            tag.to_string()
+        };
+
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    normalized
 }
 
 // TODO: Casting - remove from SchematicFieldValue wrapper
@@ -536,11 +1185,39 @@ pub enum SchematicFieldValue {
 // SchematicFieldValue::cast_to_vec<V>() -> Result<V>
 
 impl SchematicFieldValue {
+    /// The name of the variant, used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "Text",
+            Self::Number(_) => "Number",
+            Self::Boolean(_) => "Boolean",
+            Self::Url(_) => "Url",
+            Self::Email(_) => "Email",
+            Self::Phone(_) => "Phone",
+            Self::Address(_) => "Address",
+            Self::DateTime(_) => "DateTime",
+            Self::Date(_) => "Date",
+            Self::Time(_) => "Time",
+            Self::Reference(_) => "Reference",
+            Self::MultiReference(_) => "Reference List",
+            Self::MediaGallery(_) => "Media Gallery",
+            Self::ListString(_) => "String List",
+            Self::ListNumber(_) => "Number List",
+            Self::Tags(_) => "Tags",
+            Self::Array(_) => "Object Array",
+            Self::Object(_) => "Object",
+            Self::Money(_) => "Money",
+            Self::Location(_) => "Location",
+            Self::Slug(_) => "Slug",
+            Self::Duration(_) => "Duration",
+        }
+    }
+
     pub fn try_as_reference(self) -> Result<Uuid> {
         if let Self::Reference(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Reference"))?
+            Err(GlobalError::type_mismatch("Reference", self.type_name()))
         }
     }
 
@@ -548,7 +1225,7 @@ impl SchematicFieldValue {
         if let Self::Text(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Text"))?
+            Err(GlobalError::type_mismatch("Text", self.type_name()))
         }
     }
 
@@ -556,7 +1233,7 @@ impl SchematicFieldValue {
         if let Self::Number(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Number"))?
+            Err(GlobalError::type_mismatch("Number", self.type_name()))
         }
     }
 
@@ -564,7 +1241,7 @@ impl SchematicFieldValue {
         if let Self::Boolean(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Boolean"))?
+            Err(GlobalError::type_mismatch("Boolean", self.type_name()))
         }
     }
 
@@ -572,31 +1249,31 @@ impl SchematicFieldValue {
         if let Self::Url(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Url"))?
+            Err(GlobalError::type_mismatch("Url", self.type_name()))
         }
     }
 
-    pub fn try_as_email(self) -> Result<String> {
+    pub fn try_as_email(self) -> Result<EmailAddress> {
         if let Self::Email(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Email"))?
+            Err(GlobalError::type_mismatch("Email", self.type_name()))
         }
     }
 
-    pub fn try_as_phone(self) -> Result<String> {
+    pub fn try_as_phone(self) -> Result<PhoneNumber> {
         if let Self::Phone(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Phone"))?
+            Err(GlobalError::type_mismatch("Phone", self.type_name()))
         }
     }
 
-    pub fn try_as_address(self) -> Result<String> {
+    pub fn try_as_address(self) -> Result<PostalAddress> {
         if let Self::Address(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Address"))?
+            Err(GlobalError::type_mismatch("Address", self.type_name()))
         }
     }
 
@@ -604,7 +1281,7 @@ impl SchematicFieldValue {
         if let Self::DateTime(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to DateTime"))?
+            Err(GlobalError::type_mismatch("DateTime", self.type_name()))
         }
     }
 
@@ -612,7 +1289,7 @@ impl SchematicFieldValue {
         if let Self::Date(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Date"))?
+            Err(GlobalError::type_mismatch("Date", self.type_name()))
         }
     }
 
@@ -620,7 +1297,7 @@ impl SchematicFieldValue {
         if let Self::Time(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Time"))?
+            Err(GlobalError::type_mismatch("Time", self.type_name()))
         }
     }
 
@@ -628,7 +1305,7 @@ impl SchematicFieldValue {
         if let Self::ListString(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to String List"))?
+            Err(GlobalError::type_mismatch("String List", self.type_name()))
         }
     }
 
@@ -636,7 +1313,15 @@ impl SchematicFieldValue {
         if let Self::ListNumber(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Number List"))?
+            Err(GlobalError::type_mismatch("Number List", self.type_name()))
+        }
+    }
+
+    pub fn try_as_tags(self) -> Result<Vec<String>> {
+        if let Self::Tags(v) = self {
+            Ok(v)
+        } else {
+            Err(GlobalError::type_mismatch("Tags", self.type_name()))
         }
     }
 
@@ -644,7 +1329,21 @@ impl SchematicFieldValue {
         if let Self::MultiReference(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Reference List"))?
+            Err(GlobalError::type_mismatch(
+                "Reference List",
+                self.type_name(),
+            ))
+        }
+    }
+
+    pub fn try_as_media_gallery(self) -> Result<Vec<MediaGalleryItem>> {
+        if let Self::MediaGallery(v) = self {
+            Ok(v)
+        } else {
+            Err(GlobalError::type_mismatch(
+                "Media Gallery",
+                self.type_name(),
+            ))
         }
     }
 
@@ -652,7 +1351,7 @@ impl SchematicFieldValue {
         if let Self::Array(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Object Array"))?
+            Err(GlobalError::type_mismatch("Object Array", self.type_name()))
         }
     }
 
@@ -660,7 +1359,239 @@ impl SchematicFieldValue {
         if let Self::Object(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Object"))?
+            Err(GlobalError::type_mismatch("Object", self.type_name()))
+        }
+    }
+
+    pub fn try_as_money(&self) -> Result<Money> {
+        if let Self::Money(v) = self {
+            Ok(*v)
+        } else {
+            Err(GlobalError::type_mismatch("Money", self.type_name()))
+        }
+    }
+
+    pub fn try_as_location(&self) -> Result<GeoPoint> {
+        if let Self::Location(v) = self {
+            Ok(*v)
+        } else {
+            Err(GlobalError::type_mismatch("Location", self.type_name()))
+        }
+    }
+
+    pub fn try_as_slug(self) -> Result<Slug> {
+        if let Self::Slug(v) = self {
+            Ok(v)
+        } else {
+            Err(GlobalError::type_mismatch("Slug", self.type_name()))
+        }
+    }
+
+    pub fn try_as_duration(&self) -> Result<time::Duration> {
+        if let Self::Duration(v) = self {
+            Ok(*v)
+        } else {
+            Err(GlobalError::type_mismatch("Duration", self.type_name()))
+        }
+    }
+
+    // Borrowing versions of the conversions above, for when the value is only inspected.
+
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        if let Self::Text(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_number(&self) -> Option<Number> {
+        if let Self::Number(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_boolean(&self) -> Option<bool> {
+        if let Self::Boolean(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_url(&self) -> Option<&Url> {
+        if let Self::Url(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_email(&self) -> Option<&EmailAddress> {
+        if let Self::Email(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_phone(&self) -> Option<&PhoneNumber> {
+        if let Self::Phone(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_address(&self) -> Option<&PostalAddress> {
+        if let Self::Address(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_date_time(&self) -> Option<OffsetDateTime> {
+        if let Self::DateTime(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_date(&self) -> Option<Date> {
+        if let Self::Date(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_time(&self) -> Option<Time> {
+        if let Self::Time(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_reference(&self) -> Option<Uuid> {
+        if let Self::Reference(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_list_reference(&self) -> Option<&[Uuid]> {
+        if let Self::MultiReference(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_media_gallery(&self) -> Option<&[MediaGalleryItem]> {
+        if let Self::MediaGallery(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_list_string(&self) -> Option<&[String]> {
+        if let Self::ListString(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_list_number(&self) -> Option<&[Number]> {
+        if let Self::ListNumber(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_tags(&self) -> Option<&[String]> {
+        if let Self::Tags(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[serde_json::Value]> {
+        if let Self::Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_object(&self) -> Option<&serde_json::Value> {
+        if let Self::Object(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_money(&self) -> Option<Money> {
+        if let Self::Money(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_location(&self) -> Option<GeoPoint> {
+        if let Self::Location(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_slug(&self) -> Option<&Slug> {
+        if let Self::Slug(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_duration(&self) -> Option<time::Duration> {
+        if let Self::Duration(v) = self {
+            Some(*v)
+        } else {
+            None
         }
     }
 }
@@ -670,16 +1601,16 @@ const _: () = {
     use std::result::Result;
 
     use sqlx::{
+        Decode, Encode, FromRow, Postgres, Row, Type,
         database::{HasArguments, HasValueRef},
         encode::IsNull,
         error::BoxDynError,
-        postgres::{PgRow, PgTypeInfo},
-        Decode, Encode, FromRow, Postgres, Row, Type,
+        postgres::{PgHasArrayType, PgRow, PgTypeInfo},
     };
 
     impl FromRow<'_, PgRow> for SchematicFieldType {
         fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
-            Ok(Self::try_from(row.try_get::<i32, _>(0)?).unwrap())
+            Self::try_from(row.try_get::<i32, _>(0)?).map_err(|e| sqlx::Error::Decode(Box::new(e)))
         }
     }
 
@@ -703,4 +1634,10 @@ const _: () = {
             <i32 as Type<Postgres>>::type_info()
         }
     }
+
+    impl PgHasArrayType for SchematicFieldType {
+        fn array_type_info() -> PgTypeInfo {
+            <i32 as PgHasArrayType>::array_type_info()
+        }
+    }
 };
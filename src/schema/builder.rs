@@ -0,0 +1,266 @@
+//! Fluent construction of a [`Schematic`].
+
+use std::{collections::HashSet, time::Duration};
+
+use super::{
+    DefaultSort, Operations, SchemaView, Schematic, SchematicField, SchematicFieldKey,
+    SchematicFieldType, SchematicFieldValue, SchematicPermissions, SortOrder,
+    computed::{FieldSource, check_sources},
+    constraints::FieldConstraints,
+    version::SchemaVersion,
+};
+use crate::{
+    error::{GlobalError, Result},
+    expr::Expression,
+    id::WebsitePublicId,
+};
+
+/// Builds a [`Schematic`], checking it's consistent in [`SchematicBuilder::build`].
+///
+/// Field modifiers (`sortable`, `reference`, `display_name`, `default_value`, `required`,
+/// `constraints`, `computed`, `localized`, `index`) apply to the last added field.
+#[derive(Debug, Clone)]
+pub struct SchematicBuilder {
+    id: String,
+    namespace: String,
+    display_name: String,
+    owner_app_id: WebsitePublicId,
+    primary_field: Option<String>,
+    permissions: SchematicPermissions,
+    version: SchemaVersion,
+    allowed_operations: Vec<Operations>,
+    fields: Vec<(SchematicFieldKey, SchematicField)>,
+    ttl: Option<Duration>,
+    default_sort: Option<DefaultSort>,
+    views: Vec<SchemaView>,
+    /// A modifier was called before any field was added.
+    dangling_modifier: Option<&'static str>,
+}
+
+impl SchematicBuilder {
+    pub fn new<I: Into<String>, N: Into<String>, D: Into<String>>(
+        id: I,
+        namespace: N,
+        display_name: D,
+        owner_app_id: WebsitePublicId,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            namespace: namespace.into(),
+            display_name: display_name.into(),
+            owner_app_id,
+            primary_field: None,
+            permissions: SchematicPermissions::default(),
+            version: SchemaVersion::default(),
+            allowed_operations: Vec::new(),
+            fields: Vec::new(),
+            ttl: None,
+            default_sort: None,
+            views: Vec::new(),
+            dangling_modifier: None,
+        }
+    }
+
+    /// Add a field. The display name defaults to the key and the index to the next free one.
+    pub fn field<K: Into<SchematicFieldKey>>(
+        mut self,
+        key: K,
+        field_type: SchematicFieldType,
+    ) -> Self {
+        let key = key.into();
+
+        let index = self
+            .fields
+            .iter()
+            .map(|(_, f)| f.index + 1)
+            .max()
+            .unwrap_or_default();
+
+        self.fields.push((
+            key.clone(),
+            SchematicField {
+                display_name: key.to_string(),
+                sortable: false,
+                is_deleted: false,
+                system_field: false,
+                field_type,
+                index,
+                referenced_schema: None,
+                default_value: None,
+                constraints: FieldConstraints::default(),
+                localized: false,
+                source: FieldSource::Stored,
+            },
+        ));
+
+        self
+    }
+
+    pub fn sortable(self) -> Self {
+        self.modify_last("sortable", |f| f.sortable = true)
+    }
+
+    pub fn reference<S: Into<String>>(self, schema: S) -> Self {
+        let schema = schema.into();
+
+        self.modify_last("reference", |f| f.referenced_schema = Some(schema))
+    }
+
+    pub fn display_name<S: Into<String>>(self, name: S) -> Self {
+        let name = name.into();
+
+        self.modify_last("display_name", |f| f.display_name = name)
+    }
+
+    pub fn default_value(self, value: SchematicFieldValue) -> Self {
+        self.modify_last("default_value", |f| f.default_value = Some(value))
+    }
+
+    pub fn required(self) -> Self {
+        self.modify_last("required", |f| f.constraints.required = true)
+    }
+
+    pub fn constraints(self, constraints: FieldConstraints) -> Self {
+        self.modify_last("constraints", |f| f.constraints = constraints)
+    }
+
+    /// Compute the value from the other fields instead of storing what clients write.
+    pub fn computed(self, expression: Expression) -> Self {
+        self.modify_last("computed", |f| f.source = FieldSource::Computed(expression))
+    }
+
+    /// Store a value per language, see [`SchematicField::localized`].
+    pub fn localized(self) -> Self {
+        self.modify_last("localized", |f| f.localized = true)
+    }
+
+    pub fn index(self, index: u16) -> Self {
+        self.modify_last("index", |f| f.index = index)
+    }
+
+    pub fn primary_field<S: Into<String>>(mut self, key: S) -> Self {
+        self.primary_field = Some(key.into());
+        self
+    }
+
+    pub fn permissions(mut self, permissions: SchematicPermissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn version(mut self, version: SchemaVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn allowed_operation(mut self, operation: Operations) -> Self {
+        self.allowed_operations.push(operation);
+        self
+    }
+
+    pub fn view(mut self, view: SchemaView) -> Self {
+        self.views.push(view);
+        self
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn default_sort<S: Into<String>>(mut self, field: S, order: SortOrder) -> Self {
+        self.default_sort = Some(DefaultSort {
+            field: field.into(),
+            order,
+        });
+        self
+    }
+
+    /// Check the schema is consistent and build it.
+    ///
+    /// Fails if a key or index is used twice, the primary field / default sort doesn't point
+    /// to one of the fields, or a computed field calls an unknown function or reads a field
+    /// which isn't stored.
+    pub fn build(self) -> Result<Schematic> {
+        if let Some(name) = self.dangling_modifier {
+            return Err(GlobalError::InvalidSchema(format!(
+                "{name} was called before a field was added"
+            )));
+        }
+
+        let mut keys = HashSet::new();
+        let mut indexes = HashSet::new();
+
+        for (key, field) in &self.fields {
+            if !keys.insert(key.as_str()) {
+                return Err(GlobalError::InvalidSchema(format!(
+                    "Field {key} is defined twice"
+                )));
+            }
+
+            if !indexes.insert(field.index) {
+                return Err(GlobalError::InvalidSchema(format!(
+                    "Field {key} uses index {} which is already taken",
+                    field.index
+                )));
+            }
+
+            if field.localized && !field.field_type.is_localizable() {
+                return Err(GlobalError::InvalidSchema(format!(
+                    "Field {key} is a {} field which can't be localized",
+                    field.field_type.as_name()
+                )));
+            }
+        }
+
+        check_sources(self.fields.iter().map(|(k, f)| (k, f)))?;
+
+        let Some(primary_field) = self.primary_field else {
+            return Err(GlobalError::InvalidSchema(String::from(
+                "A primary field is required",
+            )));
+        };
+
+        if !keys.contains(primary_field.as_str()) {
+            return Err(GlobalError::InvalidSchema(format!(
+                "Primary field {primary_field} doesn't exist"
+            )));
+        }
+
+        if let Some(sort) = &self.default_sort
+            && !keys.contains(sort.field.as_str())
+        {
+            return Err(GlobalError::InvalidSchema(format!(
+                "Default sort field {} doesn't exist",
+                sort.field
+            )));
+        }
+
+        Ok(Schematic {
+            id: self.id,
+            namespace: self.namespace,
+            primary_field,
+            display_name: self.display_name,
+            permissions: self.permissions,
+            version: self.version,
+            allowed_operations: self.allowed_operations,
+            is_deleted: false,
+            owner_app_id: self.owner_app_id,
+            fields: self.fields.into_iter().collect(),
+            ttl: self.ttl,
+            default_sort: self.default_sort,
+            views: self.views,
+        })
+    }
+
+    fn modify_last(mut self, name: &'static str, func: impl FnOnce(&mut SchematicField)) -> Self {
+        match self.fields.last_mut() {
+            Some((_, field)) => func(field),
+            None => {
+                self.dangling_modifier.get_or_insert(name);
+            }
+        }
+
+        self
+    }
+}
@@ -0,0 +1,122 @@
+//! Fields derived from other fields of the same row, eg. `Full Name = First + " " + Last`.
+//!
+//! Computed values aren't written by clients. They're recalculated with
+//! [`Schematic::compute_fields`] whenever the row changes, using the [`crate::expr`] evaluator.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Schematic, SchematicField, SchematicFieldKey};
+use crate::{
+    error::{GlobalError, Result},
+    expr::Expression,
+    value::SimpleValue,
+};
+
+/// Where the value of a field comes from.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(tag = "type", content = "value")]
+pub enum FieldSource {
+    /// Written by clients and stored as is.
+    #[default]
+    Stored,
+    /// Calculated from the other fields of the row.
+    Computed(Expression),
+}
+
+impl FieldSource {
+    pub fn is_stored(&self) -> bool {
+        matches!(self, Self::Stored)
+    }
+
+    pub fn expression(&self) -> Option<&Expression> {
+        match self {
+            Self::Stored => None,
+            Self::Computed(v) => Some(v),
+        }
+    }
+}
+
+impl Schematic {
+    /// Recalculate every computed field of a row, removing the ones without a value.
+    ///
+    /// Values are converted into the type of their field with [`SimpleValue::coerce_to`]. Fails
+    /// without changing anything if a computed field is invalid, eg. it reads another computed
+    /// field.
+    pub fn compute_fields(
+        &self,
+        fields: &mut HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<()> {
+        check_sources(&self.fields)?;
+
+        let computed = self
+            .fields
+            .iter()
+            .filter(|(_, f)| !f.is_deleted)
+            .filter_map(|(key, f)| Some((key, f.field_type, f.source.expression()?)))
+            .collect::<Vec<_>>();
+
+        // Computed fields only read stored fields so the order doesn't matter.
+        let values = computed
+            .iter()
+            .map(|(key, field_type, expression)| {
+                let value = match expression.evaluate_optional(fields)? {
+                    Some(v) => Some(SimpleValue::from(v.coerce_to(*field_type)?)),
+                    None => None,
+                };
+
+                Ok(((*key).clone(), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (key, value) in values {
+            match value {
+                Some(v) => fields.insert(key, v),
+                None => fields.remove(&key),
+            };
+        }
+
+        Ok(())
+    }
+}
+
+/// Check every computed field is a valid expression which only reads stored fields, which also
+/// rules out cycles.
+pub(super) fn check_sources<'a, I>(fields: I) -> Result<()>
+where
+    I: IntoIterator<Item = (&'a SchematicFieldKey, &'a SchematicField)> + Clone,
+{
+    for (key, field) in fields.clone() {
+        let Some(expression) = field.source.expression() else {
+            continue;
+        };
+
+        expression
+            .validate()
+            .map_err(|e| GlobalError::InvalidSchema(format!("Computed field {key}: {e}")))?;
+
+        for read in expression.fields() {
+            if !read.is_other() {
+                continue;
+            }
+
+            match fields.clone().into_iter().find(|(k, _)| *k == read) {
+                Some((_, f)) if f.source.is_stored() => {}
+                Some(_) => {
+                    return Err(GlobalError::InvalidSchema(format!(
+                        "Computed field {key} reads the computed field {read}"
+                    )));
+                }
+                None => {
+                    return Err(GlobalError::InvalidSchema(format!(
+                        "Computed field {key} reads the missing field {read}"
+                    )));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! User defined constraints on the values of a [`SchematicField`](super::SchematicField).
+
+use std::{fmt, ops::Deref, sync::OnceLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{SchematicFieldType, SchematicFieldValue, validation::ValidationErrorKind};
+use crate::{
+    error::{GlobalError, Result},
+    value::{Number, SimpleValue},
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FieldConstraints {
+    /// The field has to be set on every record.
+    #[serde(default)]
+    pub required: bool,
+
+    /// Minimum number of characters for text, or items for lists.
+    #[serde(alias = "min_length", default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    /// Maximum number of characters for text, or items for lists.
+    #[serde(alias = "max_length", default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+
+    /// Inclusive lower bound for numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<Number>,
+    /// Inclusive upper bound for numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<Number>,
+
+    /// A regular expression text (or every item of a text list) has to match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<Pattern>,
+
+    /// The only values allowed. Empty = Anything
+    #[serde(
+        alias = "allowed_values",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_values: Vec<String>,
+}
+
+impl FieldConstraints {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check a value which already passed the type check of the field.
+    ///
+    /// Empty values only have to satisfy `required`.
+    /// Fails if the pattern isn't a valid regular expression.
+    pub fn check(
+        &self,
+        field_type: SchematicFieldType,
+        value: &SimpleValue,
+    ) -> Result<Option<ValidationErrorKind>> {
+        if is_empty_value(value) {
+            return Ok(self.required.then_some(ValidationErrorKind::Required));
+        }
+
+        if let Some(actual) = value_length(value)
+            && (self.min_length.is_some_and(|min| actual < min)
+                || self.max_length.is_some_and(|max| actual > max))
+        {
+            return Ok(Some(ValidationErrorKind::LengthOutOfRange {
+                min: self.min_length,
+                max: self.max_length,
+                actual,
+            }));
+        }
+
+        if (self.min.is_some() || self.max.is_some())
+            && field_type == SchematicFieldType::Number
+            && let Ok(SchematicFieldValue::Number(actual)) =
+                value.clone().coerce_to(SchematicFieldType::Number)
+            && (self.min.is_some_and(|min| actual.total_cmp(&min).is_lt())
+                || self.max.is_some_and(|max| actual.total_cmp(&max).is_gt()))
+        {
+            return Ok(Some(ValidationErrorKind::OutOfRange {
+                min: self.min,
+                max: self.max,
+                actual,
+            }));
+        }
+
+        let texts = value_texts(value);
+
+        if let Some(pattern) = &self.pattern {
+            let regex = pattern.regex()?;
+
+            if !texts.iter().all(|v| regex.is_match(v)) {
+                return Ok(Some(ValidationErrorKind::PatternMismatch {
+                    pattern: pattern.to_string(),
+                }));
+            }
+        }
+
+        if !self.allowed_values.is_empty() && !texts.iter().all(|v| self.allowed_values.contains(v))
+        {
+            return Ok(Some(ValidationErrorKind::NotAllowed {
+                allowed: self.allowed_values.clone(),
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+fn is_empty_value(value: &SimpleValue) -> bool {
+    match value {
+        SimpleValue::Text(v) => v.is_empty(),
+        SimpleValue::ListString(v) => v.is_empty(),
+        SimpleValue::ListNumber(v) => v.is_empty(),
+        SimpleValue::ArrayUnknown(v) => v.is_empty(),
+        SimpleValue::ObjectUnknown(v) => v.is_null(),
+        _ => false,
+    }
+}
+
+fn value_length(value: &SimpleValue) -> Option<usize> {
+    match value {
+        SimpleValue::Text(v) => Some(v.chars().count()),
+        SimpleValue::ListString(v) => Some(v.len()),
+        SimpleValue::ListNumber(v) => Some(v.len()),
+        SimpleValue::ArrayUnknown(v) => Some(v.len()),
+        _ => None,
+    }
+}
+
+/// The regular expression of [`FieldConstraints::pattern`].
+///
+/// It's compiled the first time a value is checked, and kept with the field after that.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(from = "String", into = "String")]
+pub struct Pattern {
+    source: String,
+    regex: OnceLock<std::result::Result<Regex, regex::Error>>,
+}
+
+impl Pattern {
+    /// Fails if the pattern isn't a valid regular expression.
+    pub fn regex(&self) -> Result<&Regex> {
+        self.regex
+            .get_or_init(|| Regex::new(&self.source))
+            .as_ref()
+            .map_err(|e| GlobalError::InvalidSchema(format!("Invalid pattern {self}: {e}")))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Deref for Pattern {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.source
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl From<String> for Pattern {
+    fn from(value: String) -> Self {
+        Self {
+            source: value,
+            regex: OnceLock::new(),
+        }
+    }
+}
+
+impl From<&str> for Pattern {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(value: Pattern) -> Self {
+        value.source
+    }
+}
+
+/// The text a pattern or list of allowed values is checked against.
+fn value_texts(value: &SimpleValue) -> Vec<String> {
+    match value {
+        SimpleValue::ListString(v) => v.clone(),
+        SimpleValue::ListNumber(v) => v.iter().map(|v| v.to_string()).collect(),
+        SimpleValue::ArrayUnknown(_) | SimpleValue::ObjectUnknown(_) => Vec::new(),
+        v => v.any_as_text().into_iter().collect(),
+    }
+}
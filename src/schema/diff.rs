@@ -0,0 +1,130 @@
+//! Migration plans between two versions of a [`Schematic`].
+
+use serde::{Deserialize, Serialize};
+
+use super::{Schematic, SchematicField, SchematicFieldKey, SchematicFieldType};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchematicDiff {
+    pub operations: Vec<MigrationOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum MigrationOperation {
+    FieldAdded {
+        key: SchematicFieldKey,
+        field: Box<SchematicField>,
+    },
+    /// The field was removed from the map or marked as deleted.
+    FieldRemoved { key: SchematicFieldKey },
+    TypeChanged {
+        key: SchematicFieldKey,
+        from: SchematicFieldType,
+        to: SchematicFieldType,
+    },
+    /// The display name changed.
+    Renamed {
+        key: SchematicFieldKey,
+        from: String,
+        to: String,
+    },
+    IndexMoved {
+        key: SchematicFieldKey,
+        from: u16,
+        to: u16,
+    },
+    ReferenceChanged {
+        key: SchematicFieldKey,
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl MigrationOperation {
+    pub fn key(&self) -> &SchematicFieldKey {
+        match self {
+            Self::FieldAdded { key, .. }
+            | Self::FieldRemoved { key }
+            | Self::TypeChanged { key, .. }
+            | Self::Renamed { key, .. }
+            | Self::IndexMoved { key, .. }
+            | Self::ReferenceChanged { key, .. } => key,
+        }
+    }
+
+    /// Does the operation require the stored row data to be rewritten?
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Self::FieldRemoved { .. } | Self::TypeChanged { .. })
+    }
+}
+
+impl SchematicDiff {
+    /// Compare two schemas and produce the operations needed to migrate from `old` to `new`.
+    ///
+    /// Operations are ordered by field key.
+    pub fn between(old: &Schematic, new: &Schematic) -> Self {
+        let mut operations = Vec::new();
+
+        for (key, old_field) in old.fields.iter().filter(|(_, f)| !f.is_deleted) {
+            let Some(new_field) = new.fields.get(key).filter(|f| !f.is_deleted) else {
+                operations.push(MigrationOperation::FieldRemoved { key: key.clone() });
+                continue;
+            };
+
+            if old_field.field_type != new_field.field_type {
+                operations.push(MigrationOperation::TypeChanged {
+                    key: key.clone(),
+                    from: old_field.field_type,
+                    to: new_field.field_type,
+                });
+            }
+
+            if old_field.display_name != new_field.display_name {
+                operations.push(MigrationOperation::Renamed {
+                    key: key.clone(),
+                    from: old_field.display_name.clone(),
+                    to: new_field.display_name.clone(),
+                });
+            }
+
+            if old_field.index != new_field.index {
+                operations.push(MigrationOperation::IndexMoved {
+                    key: key.clone(),
+                    from: old_field.index,
+                    to: new_field.index,
+                });
+            }
+
+            if old_field.referenced_schema != new_field.referenced_schema {
+                operations.push(MigrationOperation::ReferenceChanged {
+                    key: key.clone(),
+                    from: old_field.referenced_schema.clone(),
+                    to: new_field.referenced_schema.clone(),
+                });
+            }
+        }
+
+        for (key, new_field) in new.fields.iter().filter(|(_, f)| !f.is_deleted) {
+            if old.fields.get(key).is_none_or(|f| f.is_deleted) {
+                operations.push(MigrationOperation::FieldAdded {
+                    key: key.clone(),
+                    field: Box::new(new_field.clone()),
+                });
+            }
+        }
+
+        // Stable sort keeps the per-field operation order from above.
+        operations.sort_by(|a, b| a.key().as_str().cmp(b.key().as_str()));
+
+        Self { operations }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn is_destructive(&self) -> bool {
+        self.operations.iter().any(|v| v.is_destructive())
+    }
+}
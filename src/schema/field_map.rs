@@ -0,0 +1,258 @@
+//! The fields of a schema, kept in [`SchematicField::index`] order.
+
+use std::{borrow::Borrow, collections::HashMap, fmt, hash::Hash, ops::Index};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+};
+
+use super::{SchemaViewItem, SchematicField, SchematicFieldKey};
+
+/// A map of fields which iterates in index order, fields with the same index in the order they
+/// were inserted.
+///
+/// Serialized as the same object as a `HashMap`, written in index order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OrderedFieldMap {
+    entries: Vec<(SchematicFieldKey, SchematicField)>,
+    positions: HashMap<SchematicFieldKey, usize>,
+}
+
+impl OrderedFieldMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&SchematicField>
+    where
+        SchematicFieldKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, f)| f)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&SchematicFieldKey, &SchematicField)>
+    where
+        SchematicFieldKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (key, field) = &self.entries[*self.positions.get(key)?];
+        Some((key, field))
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        SchematicFieldKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.positions.contains_key(key)
+    }
+
+    /// Insert or replace a field, moving it to the position of its index.
+    pub fn insert(
+        &mut self,
+        key: SchematicFieldKey,
+        field: SchematicField,
+    ) -> Option<SchematicField> {
+        let previous = self.remove(&key);
+
+        let position = self
+            .entries
+            .partition_point(|(_, f)| f.index <= field.index);
+
+        self.entries.insert(position, (key, field));
+        self.reindex(position);
+
+        previous
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<SchematicField>
+    where
+        SchematicFieldKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let position = self.positions.remove(key)?;
+        let (_, field) = self.entries.remove(position);
+        self.reindex(position);
+
+        Some(field)
+    }
+
+    /// Change a field in place, returning false if it doesn't exist. The field is moved if its
+    /// index changed.
+    pub fn update<Q>(&mut self, key: &Q, func: impl FnOnce(&mut SchematicField)) -> bool
+    where
+        SchematicFieldKey: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(position) = self.positions.get(key).copied() else {
+            return false;
+        };
+
+        let index = self.entries[position].1.index;
+        func(&mut self.entries[position].1);
+
+        if self.entries[position].1.index != index {
+            let (key, field) = self.entries.remove(position);
+            self.positions.remove::<str>(key.as_str());
+            self.reindex(position);
+            self.insert(key, field);
+        }
+
+        true
+    }
+
+    /// Every field in index order.
+    pub fn iter_ordered(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (&SchematicFieldKey, &SchematicField)> {
+        self.entries.iter().map(|(k, f)| (k, f))
+    }
+
+    /// The same as [`Self::iter_ordered`].
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&SchematicFieldKey, &SchematicField)> {
+        self.iter_ordered()
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &SchematicFieldKey> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &SchematicField> {
+        self.entries.iter().map(|(_, f)| f)
+    }
+
+    /// The fields shown in a view, in index order: not deleted and not hidden by it.
+    pub fn visible_for_view<'a>(
+        &'a self,
+        view: &'a SchemaViewItem,
+    ) -> impl Iterator<Item = (&'a SchematicFieldKey, &'a SchematicField)> {
+        self.iter_ordered().filter(|(key, field)| {
+            !field.is_deleted && !view.hidden_fields.iter().any(|v| key.as_str() == v)
+        })
+    }
+
+    /// Update the positions of the entries from `start` on.
+    fn reindex(&mut self, start: usize) {
+        for (position, (key, _)) in self.entries.iter().enumerate().skip(start) {
+            match self.positions.get_mut(key) {
+                Some(v) => *v = position,
+                None => {
+                    self.positions.insert(key.clone(), position);
+                }
+            }
+        }
+    }
+}
+
+impl<Q> Index<&Q> for OrderedFieldMap
+where
+    SchematicFieldKey: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = SchematicField;
+
+    fn index(&self, key: &Q) -> &SchematicField {
+        self.get(key).expect("no field for the key")
+    }
+}
+
+impl FromIterator<(SchematicFieldKey, SchematicField)> for OrderedFieldMap {
+    fn from_iter<I: IntoIterator<Item = (SchematicFieldKey, SchematicField)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(SchematicFieldKey, SchematicField)> for OrderedFieldMap {
+    fn extend<I: IntoIterator<Item = (SchematicFieldKey, SchematicField)>>(&mut self, iter: I) {
+        for (key, field) in iter {
+            self.insert(key, field);
+        }
+    }
+}
+
+impl<const N: usize> From<[(SchematicFieldKey, SchematicField); N]> for OrderedFieldMap {
+    fn from(value: [(SchematicFieldKey, SchematicField); N]) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl From<HashMap<SchematicFieldKey, SchematicField>> for OrderedFieldMap {
+    fn from(value: HashMap<SchematicFieldKey, SchematicField>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl IntoIterator for OrderedFieldMap {
+    type Item = (SchematicFieldKey, SchematicField);
+    type IntoIter = std::vec::IntoIter<(SchematicFieldKey, SchematicField)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a OrderedFieldMap {
+    type Item = (&'a SchematicFieldKey, &'a SchematicField);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (SchematicFieldKey, SchematicField)>,
+        fn(&'a (SchematicFieldKey, SchematicField)) -> (&'a SchematicFieldKey, &'a SchematicField),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(k, f)| (k, f))
+    }
+}
+
+impl Serialize for OrderedFieldMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (key, field) in self {
+            map.serialize_entry(key, field)?;
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderedFieldMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct FieldMapVisitor;
+
+        impl<'de> Visitor<'de> for FieldMapVisitor {
+            type Value = OrderedFieldMap;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of fields")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                let mut map = OrderedFieldMap::new();
+
+                while let Some((key, field)) = access.next_entry()? {
+                    map.insert(key, field);
+                }
+
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(FieldMapVisitor)
+    }
+}
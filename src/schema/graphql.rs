@@ -0,0 +1,358 @@
+//! GraphQL SDL generation for the records of a [`Schematic`].
+//!
+//! Every schema becomes an object type named after its id in PascalCase (`blog-posts` is
+//! `BlogPosts`), a `<Name>Filter` input mirroring [`FilterExpr`] and a `<Name>Connection` shaped
+//! like a [`ListResponse`]. The scalars and inputs they share are in [`SHARED_DEFINITIONS`].
+//!
+//! [`FilterExpr`]: crate::filter::FilterExpr
+//! [`ListResponse`]: crate::response::ListResponse
+
+use std::fmt::Write;
+
+use super::{Schematic, SchematicFieldType, SchematicFieldValue};
+use crate::value::SimpleValue;
+
+/// The name, type and filter of the fields every record has.
+const SYSTEM_FIELDS: [(&str, &str, &str); 4] = [
+    ("_id", "ID!", "IDFilter"),
+    ("_owner", "String", "StringFilter"),
+    ("_createdAt", "DateTime!", "DateTimeFilter"),
+    ("_updatedAt", "DateTime!", "DateTimeFilter"),
+];
+
+/// The custom scalars, value types and scalar filters used by every schema.
+pub const SHARED_DEFINITIONS: &str = r#""An RFC 3339 date and time."
+scalar DateTime
+
+"An ISO 8601 date, eg. 2024-01-31."
+scalar Date
+
+"An ISO 8601 time, eg. 13:45:00."
+scalar Time
+
+"An ISO 8601 duration, eg. PT1H30M."
+scalar Duration
+
+"Any JSON value."
+scalar JSON
+
+type Money {
+  amountMinor: Float!
+  currency: String!
+}
+
+type GeoPoint {
+  lat: Float!
+  lon: Float!
+}
+
+type MediaGalleryItem {
+  uploadId: ID!
+  caption: String
+  altText: String
+  order: Int!
+}
+
+input GeoPointInput {
+  lat: Float!
+  lon: Float!
+}
+
+"Within `radius` meters of the center."
+input NearFilter {
+  center: GeoPointInput!
+  radius: Float!
+}
+
+input IDFilter {
+  eq: ID
+  neq: ID
+}
+
+input StringFilter {
+  eq: String
+  neq: String
+  cont: String
+  dnc: String
+  gte: String
+  gt: String
+  lte: String
+  lt: String
+}
+
+input FloatFilter {
+  eq: Float
+  neq: Float
+  gte: Float
+  gt: Float
+  lte: Float
+  lt: Float
+  between: [Float!]
+}
+
+input BooleanFilter {
+  eq: Boolean
+  neq: Boolean
+}
+
+input DateTimeFilter {
+  eq: DateTime
+  neq: DateTime
+  gte: DateTime
+  gt: DateTime
+  lte: DateTime
+  lt: DateTime
+}
+
+input DateFilter {
+  eq: Date
+  neq: Date
+  gte: Date
+  gt: Date
+  lte: Date
+  lt: Date
+}
+
+input TimeFilter {
+  eq: Time
+  neq: Time
+  gte: Time
+  gt: Time
+  lte: Time
+  lt: Time
+}
+
+"Lists containing, or not containing, the value."
+input ListFilter {
+  cont: String
+  dnc: String
+}
+
+input GeoFilter {
+  near: NearFilter
+}
+"#;
+
+/// The shared definitions, the definitions of every schema and a `Query` type listing them.
+pub fn to_graphql_document<'a>(schemas: impl IntoIterator<Item = &'a Schematic>) -> String {
+    let mut document = String::from(SHARED_DEFINITIONS);
+    let mut query = String::from("type Query {\n");
+
+    for schema in schemas {
+        let name = schema.graphql_type_name();
+
+        document.push('\n');
+        document.push_str(&schema.to_graphql_sdl());
+
+        let _ = writeln!(
+            query,
+            "  {}(filter: {name}Filter, offset: Int, limit: Int, cursor: String): {name}Connection!",
+            field_name(&schema.id)
+        );
+    }
+
+    query.push_str("}\n");
+
+    document.push('\n');
+    document.push_str(&query);
+    document
+}
+
+impl Schematic {
+    /// The name of the object type, the id in PascalCase.
+    pub fn graphql_type_name(&self) -> String {
+        let name = self
+            .id
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                let mut chars = v.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<String>();
+
+        if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+            format!("_{name}")
+        } else {
+            name
+        }
+    }
+
+    /// Describe the records of the collection as the object, filter and connection types.
+    ///
+    /// Deleted fields are left out. The definitions reference [`SHARED_DEFINITIONS`].
+    pub fn to_graphql_sdl(&self) -> String {
+        let name = self.graphql_type_name();
+        let fields = self
+            .fields
+            .iter_ordered()
+            .filter(|(_, f)| !f.is_deleted)
+            .collect::<Vec<_>>();
+
+        let mut sdl = String::new();
+
+        let _ = writeln!(sdl, "{}", description(&self.display_name, ""));
+        let _ = writeln!(sdl, "type {name} {{");
+
+        for (key, field_type, _) in SYSTEM_FIELDS {
+            let _ = writeln!(sdl, "  {key}: {field_type}");
+        }
+
+        for (key, field) in &fields {
+            // Localized values are a LocalizedString.
+            let mut field_type = if field.localized {
+                "JSON"
+            } else {
+                field.field_type.graphql_type()
+            }
+            .to_string();
+
+            if field.constraints.required && field.source.is_stored() {
+                field_type.push('!');
+            }
+
+            let _ = writeln!(sdl, "{}", description(&field.display_name, "  "));
+            let _ = writeln!(sdl, "  {}: {field_type}", field_name(key.as_str()));
+        }
+
+        sdl.push_str("}\n\n");
+
+        let _ = writeln!(sdl, "input {name}Filter {{");
+        let _ = writeln!(sdl, "  and: [{name}Filter!]");
+        let _ = writeln!(sdl, "  or: [{name}Filter!]");
+        let _ = writeln!(sdl, "  not: {name}Filter");
+
+        for (key, _, filter) in SYSTEM_FIELDS {
+            let _ = writeln!(sdl, "  {key}: {filter}");
+        }
+
+        for (key, field) in fields.iter().filter(|(_, f)| !f.localized) {
+            if let Some(filter) = field.field_type.graphql_filter() {
+                let _ = writeln!(sdl, "  {}: {filter}", field_name(key.as_str()));
+            }
+        }
+
+        sdl.push_str("}\n\n");
+
+        // The same shape as a ListResponse.
+        let _ = writeln!(sdl, "type {name}Connection {{");
+        let _ = writeln!(sdl, "  items: [{name}!]!");
+
+        for field in [
+            "offset: Int!",
+            "limit: Int!",
+            "total: Int!",
+            "nextCursor: String",
+            "prevCursor: String",
+        ] {
+            let _ = writeln!(sdl, "  {field}");
+        }
+
+        sdl.push_str("}\n");
+
+        sdl
+    }
+}
+
+impl SchematicFieldType {
+    /// The GraphQL type of a single value of this type, without the non-null marker.
+    pub fn graphql_type(self) -> &'static str {
+        match self {
+            Self::Text
+            | Self::RichText
+            | Self::RichContent
+            | Self::Email
+            | Self::URL
+            | Self::Phone
+            | Self::Slug => "String",
+            Self::Number => "Float",
+            Self::Boolean => "Boolean",
+            Self::DateTime => "DateTime",
+            Self::Date => "Date",
+            Self::Time => "Time",
+            Self::Duration => "Duration",
+            Self::Reference => "ID",
+            Self::MultiReference => "[ID!]",
+            Self::MediaGallery => "[MediaGalleryItem!]",
+            Self::Tags | Self::MultiDocument => "[String!]",
+            Self::Document | Self::Image | Self::Video | Self::Audio => "[Int!]",
+            Self::Array => "[JSON]",
+            // Legacy addresses are a single text.
+            Self::Object | Self::Address => "JSON",
+            Self::Currency => "Money",
+            Self::Location => "GeoPoint",
+        }
+    }
+
+    /// The filter input for the type, `None` if it can't be filtered on.
+    pub fn graphql_filter(self) -> Option<&'static str> {
+        Some(match self {
+            Self::Text
+            | Self::RichText
+            | Self::RichContent
+            | Self::Email
+            | Self::URL
+            | Self::Phone
+            | Self::Slug => "StringFilter",
+            Self::Number => "FloatFilter",
+            Self::Boolean => "BooleanFilter",
+            Self::DateTime => "DateTimeFilter",
+            Self::Date => "DateFilter",
+            Self::Time => "TimeFilter",
+            Self::Reference => "IDFilter",
+            Self::MultiReference | Self::Tags | Self::MultiDocument => "ListFilter",
+            Self::Location => "GeoFilter",
+            Self::Duration
+            | Self::MediaGallery
+            | Self::Document
+            | Self::Image
+            | Self::Video
+            | Self::Audio
+            | Self::Array
+            | Self::Object
+            | Self::Address
+            | Self::Currency => return None,
+        })
+    }
+}
+
+impl SchematicFieldValue {
+    /// The value as the GraphQL response would hold it for [`SchematicFieldType::graphql_type`].
+    pub fn to_graphql(&self) -> serde_json::Value {
+        SimpleValue::from(self.clone()).into_json()
+    }
+}
+
+/// A valid GraphQL name: only `[_A-Za-z0-9]`, not starting with a digit.
+fn field_name(value: &str) -> String {
+    let name = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+fn description(value: &str, indent: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    format!("{indent}\"{escaped}\"")
+}
@@ -0,0 +1,210 @@
+//! JSON Schema (draft-07) generation for the records of a [`Schematic`].
+
+use serde_json::{Map, Value, json};
+
+use super::{Schematic, SchematicFieldKey, SchematicFieldType, constraints::FieldConstraints};
+use crate::slug::Slug;
+
+pub const JSON_SCHEMA_DRAFT_07: &str = "http://json-schema.org/draft-07/schema#";
+
+impl Schematic {
+    /// Describe a valid record of the collection as a draft-07 JSON Schema.
+    ///
+    /// Deleted fields are left out and unknown properties are rejected.
+    pub fn to_json_schema(&self) -> Value {
+        let mut properties = Map::new();
+
+        for key in [
+            SchematicFieldKey::Id,
+            SchematicFieldKey::Owner,
+            SchematicFieldKey::CreatedAt,
+            SchematicFieldKey::UpdatedAt,
+        ] {
+            let schema = match key {
+                SchematicFieldKey::CreatedAt | SchematicFieldKey::UpdatedAt => {
+                    SchematicFieldType::DateTime.to_json_schema()
+                }
+                _ => SchematicFieldType::Text.to_json_schema(),
+            };
+
+            properties.insert(key.to_string(), schema);
+        }
+
+        let mut required = Vec::new();
+
+        for (key, field) in self.fields.iter_ordered().filter(|(_, f)| !f.is_deleted) {
+            let mut schema = field.field_type.to_json_schema();
+
+            if let Value::Object(schema) = &mut schema {
+                add_constraints(schema, &field.constraints);
+            }
+
+            // Either plain text or a LocalizedString.
+            if field.localized {
+                schema = json!({
+                    "anyOf": [
+                        schema,
+                        {
+                            "type": "object",
+                            "properties": {
+                                "defaultLanguage": { "type": "string" },
+                                "values": { "type": "object", "additionalProperties": schema },
+                            },
+                            "required": ["defaultLanguage", "values"],
+                        },
+                    ],
+                });
+            }
+
+            if let Value::Object(schema) = &mut schema {
+                schema.insert(String::from("title"), json!(field.display_name));
+
+                if !field.source.is_stored() {
+                    schema.insert(String::from("readOnly"), json!(true));
+                }
+            }
+
+            if field.constraints.required && field.source.is_stored() {
+                required.push(key.to_string());
+            }
+
+            properties.insert(key.to_string(), schema);
+        }
+
+        json!({
+            "$schema": JSON_SCHEMA_DRAFT_07,
+            "$id": format!("{}/{}", self.namespace, self.id),
+            "title": self.display_name,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+}
+
+impl SchematicFieldType {
+    /// The JSON Schema of a single value of this type.
+    pub fn to_json_schema(self) -> Value {
+        match self {
+            Self::Text | Self::RichText | Self::RichContent => json!({ "type": "string" }),
+            // Legacy addresses are a single text.
+            Self::Address => json!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "line1": { "type": "string" },
+                            "line2": { "type": "string" },
+                            "city": { "type": "string" },
+                            "region": { "type": "string" },
+                            "postalCode": { "type": "string" },
+                            "country": { "type": "string", "pattern": "^[A-Z]{2}$" },
+                        },
+                        "additionalProperties": false,
+                    },
+                    { "type": "string" },
+                ],
+            }),
+            Self::Slug => json!({
+                "type": "string",
+                "pattern": "^[a-z0-9]+(-[a-z0-9]+)*$",
+                "maxLength": Slug::MAX_LEN,
+            }),
+            Self::Phone => json!({ "type": "string", "pattern": "^\\+[1-9][0-9]{6,14}$" }),
+            Self::Number => json!({ "type": "number" }),
+            Self::Boolean => json!({ "type": "boolean" }),
+            Self::URL => json!({ "type": "string", "format": "uri" }),
+            Self::Email => json!({ "type": "string", "format": "email" }),
+            Self::DateTime => json!({ "type": "string", "format": "date-time" }),
+            Self::Date => json!({ "type": "string", "format": "date" }),
+            Self::Time => json!({ "type": "string", "format": "time" }),
+            Self::Duration => json!({ "type": "string", "format": "duration" }),
+            Self::Reference => json!({ "type": "string", "format": "uuid" }),
+            Self::MultiReference => json!({
+                "type": "array",
+                "items": { "type": "string", "format": "uuid" },
+            }),
+            Self::MediaGallery => json!({
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "uploadId": { "type": "string", "format": "uuid" },
+                        "caption": { "type": "string" },
+                        "altText": { "type": "string" },
+                        "order": { "type": "integer", "minimum": 0 },
+                    },
+                    "required": ["uploadId"],
+                    "additionalProperties": false,
+                },
+            }),
+            Self::Tags | Self::MultiDocument => json!({
+                "type": "array",
+                "items": { "type": "string" },
+            }),
+            Self::Document | Self::Image | Self::Video | Self::Audio => json!({
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+            }),
+            Self::Array => json!({ "type": "array" }),
+            Self::Object => json!({ "type": "object" }),
+            Self::Currency => json!({
+                "type": "object",
+                "properties": {
+                    "amountMinor": { "type": "integer" },
+                    "currency": { "type": "string", "pattern": "^[A-Z]{3}$" },
+                },
+                "required": ["amountMinor", "currency"],
+                "additionalProperties": false,
+            }),
+            Self::Location => json!({
+                "type": "object",
+                "properties": {
+                    "lat": { "type": "number", "minimum": -90, "maximum": 90 },
+                    "lon": { "type": "number", "minimum": -180, "maximum": 180 },
+                },
+                "required": ["lat", "lon"],
+                "additionalProperties": false,
+            }),
+        }
+    }
+}
+
+fn add_constraints(schema: &mut Map<String, Value>, constraints: &FieldConstraints) {
+    let is_array = schema.get("type").is_some_and(|v| v == "array");
+
+    let (min_length, max_length) = if is_array {
+        ("minItems", "maxItems")
+    } else {
+        ("minLength", "maxLength")
+    };
+
+    if let Some(min) = constraints.min_length {
+        schema.insert(String::from(min_length), json!(min));
+    }
+
+    if let Some(max) = constraints.max_length {
+        schema.insert(String::from(max_length), json!(max));
+    }
+
+    if let Some(min) = constraints.min {
+        schema.insert(String::from("minimum"), Value::from(min));
+    }
+
+    if let Some(max) = constraints.max {
+        schema.insert(String::from("maximum"), Value::from(max));
+    }
+
+    if is_array {
+        return;
+    }
+
+    if let Some(pattern) = &constraints.pattern {
+        schema.insert(String::from("pattern"), json!(pattern.as_str()));
+    }
+
+    if !constraints.allowed_values.is_empty() {
+        schema.insert(String::from("enum"), json!(constraints.allowed_values));
+    }
+}
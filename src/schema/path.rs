@@ -0,0 +1,258 @@
+//! Paths to values inside Object and Array fields.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::SchematicFieldKey;
+use crate::{
+    error::{GlobalError, Result},
+    value::SimpleValue,
+};
+
+/// A field, optionally followed by the way into its value, eg. `address.city` or
+/// `items[0].price`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(try_from = "String", into = "String")]
+pub struct FieldPath {
+    key: SchematicFieldKey,
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl FieldPath {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| GlobalError::ParseError(format!("Field Path {value}: {reason}"));
+
+        let end = value.find(['.', '[']).unwrap_or(value.len());
+        let (key, mut rest) = value.split_at(end);
+
+        if key.is_empty() {
+            return Err(invalid("missing the field"));
+        }
+
+        if key.contains(']') {
+            return Err(invalid("unopened ]"));
+        }
+
+        let mut segments = Vec::new();
+
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+
+                if end == 0 {
+                    return Err(invalid("empty key"));
+                }
+
+                if after[..end].contains(']') {
+                    return Err(invalid("unopened ]"));
+                }
+
+                segments.push(PathSegment::Key(after[..end].to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (index, after) = after.split_once(']').ok_or_else(|| invalid("unclosed ["))?;
+
+                let index = index
+                    .parse()
+                    .map_err(|_| invalid("indexes have to be positive numbers"))?;
+
+                segments.push(PathSegment::Index(index));
+                rest = after;
+            } else {
+                return Err(invalid("expected . or ["));
+            }
+        }
+
+        Ok(Self {
+            key: SchematicFieldKey::from(key),
+            segments,
+        })
+    }
+
+    /// Whether the name contains a path rather than just a field.
+    pub fn is_nested(name: &str) -> bool {
+        name.contains(['.', '['])
+    }
+
+    pub fn key(&self) -> &SchematicFieldKey {
+        &self.key
+    }
+
+    /// The way into the value of the field. Empty for the field itself.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn get(&self, fields: &HashMap<SchematicFieldKey, SimpleValue>) -> Option<SimpleValue> {
+        self.get_in(fields.get(&self.key)?)
+    }
+
+    /// Follow the segments into the value of the field.
+    ///
+    /// Objects, Arrays and Lists can be walked into. Anything else, or a missing key or index,
+    /// gives None.
+    pub fn get_in(&self, value: &SimpleValue) -> Option<SimpleValue> {
+        let Some((first, rest)) = self.segments.split_first() else {
+            return Some(value.clone());
+        };
+
+        let value = match (value, first) {
+            (SimpleValue::ObjectUnknown(v), _) => get_json(v, &self.segments)?,
+            (SimpleValue::ArrayUnknown(v), PathSegment::Index(i)) => get_json(v.get(*i)?, rest)?,
+            (SimpleValue::ListString(v), PathSegment::Index(i)) if rest.is_empty() => {
+                return v.get(*i).cloned().map(SimpleValue::Text);
+            }
+            (SimpleValue::ListNumber(v), PathSegment::Index(i)) if rest.is_empty() => {
+                return v.get(*i).copied().map(SimpleValue::Number);
+            }
+            _ => return None,
+        };
+
+        SimpleValue::from_json(value.clone()).ok()
+    }
+
+    /// Set the value at the path, creating missing objects on the way. Nothing is changed if it
+    /// fails.
+    ///
+    /// An index can be at most the length of its array, in which case the value is appended.
+    pub fn set(
+        &self,
+        fields: &mut HashMap<SchematicFieldKey, SimpleValue>,
+        value: SimpleValue,
+    ) -> Result<()> {
+        let Some(first) = self.segments.first() else {
+            fields.insert(self.key.clone(), value);
+            return Ok(());
+        };
+
+        let mut json = match fields.get(&self.key) {
+            None => match first {
+                PathSegment::Key(_) => Value::Object(Default::default()),
+                PathSegment::Index(_) => Value::Array(Vec::new()),
+            },
+            Some(v @ (SimpleValue::ObjectUnknown(_) | SimpleValue::ArrayUnknown(_))) => {
+                v.clone().into_json()
+            }
+            Some(v) => return Err(GlobalError::type_mismatch("Object", v.type_name())),
+        };
+
+        set_json(&mut json, &self.segments, value.into_json())
+            .map_err(|reason| GlobalError::ParseError(format!("Field Path {self}: {reason}")))?;
+
+        fields.insert(
+            self.key.clone(),
+            match json {
+                Value::Array(v) => SimpleValue::ArrayUnknown(v),
+                v => SimpleValue::ObjectUnknown(v),
+            },
+        );
+
+        Ok(())
+    }
+}
+
+fn get_json<'a>(value: &'a Value, segments: &[PathSegment]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            PathSegment::Key(k) => value.get(k),
+            PathSegment::Index(i) => value.get(i),
+        })
+}
+
+fn set_json(
+    target: &mut Value,
+    segments: &[PathSegment],
+    value: Value,
+) -> std::result::Result<(), String> {
+    let Some((first, rest)) = segments.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+
+    if target.is_null() {
+        *target = match first {
+            PathSegment::Key(_) => Value::Object(Default::default()),
+            PathSegment::Index(_) => Value::Array(Vec::new()),
+        };
+    }
+
+    let next = match (target, first) {
+        (Value::Object(object), PathSegment::Key(k)) => {
+            object.entry(k.as_str()).or_insert(Value::Null)
+        }
+        (Value::Array(array), PathSegment::Index(i)) => {
+            if *i == array.len() {
+                array.push(Value::Null);
+            }
+
+            array
+                .get_mut(*i)
+                .ok_or_else(|| format!("index {i} is past the end"))?
+        }
+        (_, PathSegment::Key(k)) => return Err(format!("{k} isn't inside an object")),
+        (_, PathSegment::Index(i)) => return Err(format!("[{i}] isn't inside an array")),
+    };
+
+    set_json(next, rest, value)
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key.as_str())?;
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(k) => write!(f, ".{k}")?,
+                PathSegment::Index(i) => write!(f, "[{i}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for FieldPath {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<FieldPath> for String {
+    fn from(value: FieldPath) -> Self {
+        value.to_string()
+    }
+}
+
+impl From<SchematicFieldKey> for FieldPath {
+    fn from(key: SchematicFieldKey) -> Self {
+        Self {
+            key,
+            segments: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Integrity of the references between collections.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Schematic, SchematicFieldKey};
+use crate::error::{GlobalError, Result};
+
+/// A field of one schema pointing to another schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaReference {
+    pub schema: String,
+    pub field: SchematicFieldKey,
+    pub referenced_schema: String,
+}
+
+/// Which schemas reference which, built from the `referenced_schema` of every field.
+///
+/// Deleted schemas and fields are ignored. A schema referencing itself isn't a cycle since
+/// it can still be deleted on its own.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceGraph {
+    schemas: BTreeSet<String>,
+    references: Vec<SchemaReference>,
+    /// Schema -> the schemas it references, without self references.
+    edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ReferenceGraph {
+    pub fn from_schemas(schemas: &[Schematic]) -> Self {
+        let mut graph = Self::default();
+
+        for schema in schemas.iter().filter(|s| !s.is_deleted) {
+            graph.schemas.insert(schema.id.clone());
+            graph.edges.entry(schema.id.clone()).or_default();
+
+            for (key, field) in schema.fields.iter().filter(|(_, f)| !f.is_deleted) {
+                let Some(referenced) = &field.referenced_schema else {
+                    continue;
+                };
+
+                graph.references.push(SchemaReference {
+                    schema: schema.id.clone(),
+                    field: key.clone(),
+                    referenced_schema: referenced.clone(),
+                });
+
+                if *referenced != schema.id {
+                    graph
+                        .edges
+                        .entry(schema.id.clone())
+                        .or_default()
+                        .insert(referenced.clone());
+                }
+            }
+        }
+
+        graph
+            .references
+            .sort_by(|a, b| (&a.schema, a.field.as_str()).cmp(&(&b.schema, b.field.as_str())));
+
+        graph
+    }
+
+    pub fn references(&self) -> &[SchemaReference] {
+        &self.references
+    }
+
+    /// References to schemas which don't exist (or are deleted).
+    pub fn dangling(&self) -> Vec<&SchemaReference> {
+        self.references
+            .iter()
+            .filter(|r| !self.schemas.contains(&r.referenced_schema))
+            .collect()
+    }
+
+    /// The other schemas which reference this one and would break if it was deleted.
+    pub fn referenced_by(&self, schema: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(_, to)| to.contains(schema))
+            .map(|(from, _)| from.as_str())
+            .collect()
+    }
+
+    /// Groups of schemas which reference each other in a loop, each sorted by id.
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut tarjan = Tarjan {
+            edges: &self.edges,
+            index: 0,
+            indexes: BTreeMap::new(),
+            low_links: BTreeMap::new(),
+            stack: Vec::new(),
+            on_stack: BTreeSet::new(),
+            components: Vec::new(),
+        };
+
+        for schema in &self.schemas {
+            if !tarjan.indexes.contains_key(schema.as_str()) {
+                tarjan.visit(schema);
+            }
+        }
+
+        let mut cycles = tarjan
+            .components
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .map(|mut c| {
+                c.sort();
+                c
+            })
+            .collect::<Vec<_>>();
+
+        cycles.sort();
+
+        cycles
+    }
+
+    /// The order to delete every schema in without ever leaving a dangling reference:
+    /// a schema comes before every schema it references.
+    ///
+    /// Fails if there is a cycle, since none of its schemas can be deleted first.
+    pub fn deletion_order(&self) -> Result<Vec<String>> {
+        if let Some(cycle) = self.cycles().into_iter().next() {
+            return Err(GlobalError::InvalidSchema(format!(
+                "Reference cycle between {}",
+                cycle.join(", ")
+            )));
+        }
+
+        // Number of existing schemas still referencing each schema.
+        let mut referrers = self
+            .schemas
+            .iter()
+            .map(|s| (s.as_str(), 0usize))
+            .collect::<BTreeMap<_, _>>();
+
+        for to in self.edges.values() {
+            for to in to {
+                if let Some(count) = referrers.get_mut(to.as_str()) {
+                    *count += 1;
+                }
+            }
+        }
+
+        let mut ready = referrers
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(s, _)| *s)
+            .collect::<BTreeSet<_>>();
+
+        let mut order = Vec::with_capacity(self.schemas.len());
+
+        while let Some(schema) = ready.pop_first() {
+            order.push(schema.to_string());
+
+            for to in self.edges.get(schema).into_iter().flatten() {
+                if let Some(count) = referrers.get_mut(to.as_str()) {
+                    *count -= 1;
+
+                    if *count == 0 {
+                        ready.insert(to.as_str());
+                    }
+                }
+            }
+        }
+
+        Ok(order)
+    }
+}
+
+struct Tarjan<'a> {
+    edges: &'a BTreeMap<String, BTreeSet<String>>,
+    index: usize,
+    indexes: BTreeMap<&'a str, usize>,
+    low_links: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, schema: &'a str) {
+        self.indexes.insert(schema, self.index);
+        self.low_links.insert(schema, self.index);
+        self.index += 1;
+        self.stack.push(schema);
+        self.on_stack.insert(schema);
+
+        // Dangling references have no edges entry and are skipped.
+        for to in self.edges.get(schema).into_iter().flatten() {
+            let to = to.as_str();
+
+            if !self.edges.contains_key(to) {
+                continue;
+            }
+
+            if !self.indexes.contains_key(to) {
+                self.visit(to);
+
+                let low = self.low_links[schema].min(self.low_links[to]);
+                self.low_links.insert(schema, low);
+            } else if self.on_stack.contains(to) {
+                let low = self.low_links[schema].min(self.indexes[to]);
+                self.low_links.insert(schema, low);
+            }
+        }
+
+        if self.low_links[schema] == self.indexes[schema] {
+            let mut component = Vec::new();
+
+            while let Some(item) = self.stack.pop() {
+                self.on_stack.remove(item);
+                component.push(item.to_string());
+
+                if item == schema {
+                    break;
+                }
+            }
+
+            self.components.push(component);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Validation of full records against a [`Schematic`].
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Schematic, SchematicFieldKey, SchematicFieldType};
+use crate::{
+    error::Result,
+    i18n::LocalizedString,
+    value::{Number, SimpleValue},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationError {
+    pub field: SchematicFieldKey,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ValidationErrorKind {
+    /// The field doesn't exist in the schema or was deleted.
+    UnknownField,
+    /// The field is managed by the system and cannot be written to.
+    SystemField,
+    /// The field is computed from the other fields and cannot be written to.
+    ComputedField,
+    /// The value cannot be parsed into the field type.
+    InvalidType {
+        expected: SchematicFieldType,
+        reason: String,
+    },
+    /// The value is longer than [`SchematicFieldType::max_bytes_length`].
+    TooLong { max: usize, actual: usize },
+    /// The field is required but missing or empty.
+    Required,
+    /// The number of characters/items is outside of [`FieldConstraints`](super::constraints::FieldConstraints) `min_length`/`max_length`.
+    LengthOutOfRange {
+        min: Option<usize>,
+        max: Option<usize>,
+        actual: usize,
+    },
+    /// The number is outside of [`FieldConstraints`](super::constraints::FieldConstraints) `min`/`max`.
+    OutOfRange {
+        min: Option<Number>,
+        max: Option<Number>,
+        actual: Number,
+    },
+    /// The text doesn't match [`FieldConstraints`](super::constraints::FieldConstraints) `pattern`.
+    PatternMismatch { pattern: String },
+    /// The value isn't one of [`FieldConstraints`](super::constraints::FieldConstraints) `allowed_values`.
+    NotAllowed { allowed: Vec<String> },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}: {}", self.field, self.kind)
+    }
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnknownField => write!(f, "Unknown Field"),
+            Self::SystemField => write!(f, "System Fields cannot be set"),
+            Self::ComputedField => write!(f, "Computed Fields cannot be set"),
+            Self::InvalidType { expected, reason } => {
+                write!(f, "Expected {}: {reason}", expected.as_name())
+            }
+            Self::TooLong { max, actual } => {
+                write!(f, "Value is too long ({actual} > {max} bytes)")
+            }
+            Self::Required => write!(f, "Value is required"),
+            Self::LengthOutOfRange { min, max, actual } => write!(
+                f,
+                "Length {actual} is outside of {}..{}",
+                min.map(|v| v.to_string()).unwrap_or_default(),
+                max.map(|v| v.to_string()).unwrap_or_default()
+            ),
+            Self::OutOfRange { min, max, actual } => write!(
+                f,
+                "{actual} is outside of {}..{}",
+                min.map(|v| v.to_string()).unwrap_or_default(),
+                max.map(|v| v.to_string()).unwrap_or_default()
+            ),
+            Self::PatternMismatch { pattern } => write!(f, "Value doesn't match {pattern}"),
+            Self::NotAllowed { allowed } => {
+                write!(f, "Value must be one of: {}", allowed.join(", "))
+            }
+        }
+    }
+}
+
+impl Schematic {
+    /// Validate a record submitted by a user against every field of the schema in one pass.
+    ///
+    /// Returns an empty list if the record is valid.
+    pub fn validate_record(
+        &self,
+        fields: &HashMap<SchematicFieldKey, SimpleValue>,
+    ) -> Result<Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        for (key, value) in fields {
+            if !key.is_other() {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::SystemField,
+                });
+
+                continue;
+            }
+
+            let Some(field) = self.fields.get(key).filter(|f| !f.is_deleted) else {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::UnknownField,
+                });
+
+                continue;
+            };
+
+            if field.system_field {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::SystemField,
+                });
+
+                continue;
+            }
+
+            if !field.source.is_stored() {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::ComputedField,
+                });
+
+                continue;
+            }
+
+            // Plain text is checked like any other value, as the value of the default language.
+            if field.localized
+                && let SimpleValue::ObjectUnknown(json) = value
+            {
+                let kind = match LocalizedString::from_json(json) {
+                    Ok(localized) => localized
+                        .values
+                        .into_values()
+                        .map(|v| {
+                            let v = SimpleValue::Text(v);
+
+                            match too_long(field.field_type, &v) {
+                                Some(kind) => Ok(Some(kind)),
+                                None => field.constraints.check(field.field_type, &v),
+                            }
+                        })
+                        .find_map(Result::transpose)
+                        .transpose()?,
+                    Err(e) => Some(ValidationErrorKind::InvalidType {
+                        expected: field.field_type,
+                        reason: e.to_string(),
+                    }),
+                };
+
+                if let Some(kind) = kind {
+                    errors.push(ValidationError {
+                        field: key.clone(),
+                        kind,
+                    });
+                }
+
+                continue;
+            }
+
+            if let Some(kind) = too_long(field.field_type, value) {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind,
+                });
+
+                continue;
+            }
+
+            if let Err(reason) = check_type(field.field_type, value) {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::InvalidType {
+                        expected: field.field_type,
+                        reason,
+                    },
+                });
+
+                continue;
+            }
+
+            if let Some(kind) = field.constraints.check(field.field_type, value)? {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind,
+                });
+            }
+        }
+
+        for (key, field) in &self.fields {
+            if field.constraints.required
+                && !field.is_deleted
+                && field.source.is_stored()
+                && !fields.contains_key(key)
+            {
+                errors.push(ValidationError {
+                    field: key.clone(),
+                    kind: ValidationErrorKind::Required,
+                });
+            }
+        }
+
+        errors.sort_by(|a, b| a.field.as_str().cmp(b.field.as_str()));
+
+        Ok(errors)
+    }
+}
+
+fn too_long(field_type: SchematicFieldType, value: &SimpleValue) -> Option<ValidationErrorKind> {
+    let max = field_type.max_bytes_length()?;
+
+    let actual = match value {
+        SimpleValue::Text(v) => v.len(),
+        SimpleValue::ListNumber(v) => v.len(),
+        _ => 0,
+    };
+
+    (actual > max).then_some(ValidationErrorKind::TooLong { max, actual })
+}
+
+/// Values which are already typed are accepted as is, anything else has to go through
+/// [`SchematicFieldType::parse_value`].
+fn check_type(field_type: SchematicFieldType, value: &SimpleValue) -> Result<(), String> {
+    match (field_type, value) {
+        (SchematicFieldType::Number, SimpleValue::Number(_))
+        | (SchematicFieldType::Boolean, SimpleValue::Boolean(_))
+        | (SchematicFieldType::DateTime, SimpleValue::DateTime(_))
+        | (SchematicFieldType::Date, SimpleValue::Date(_))
+        | (SchematicFieldType::Time, SimpleValue::Time(_))
+        | (SchematicFieldType::Array, SimpleValue::ArrayUnknown(_))
+        | (SchematicFieldType::Object, SimpleValue::ObjectUnknown(_)) => Ok(()),
+
+        (field_type, value) => field_type
+            .parse_value(value.clone())
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
@@ -0,0 +1,137 @@
+//! The version of a [`Schematic`](super::Schematic).
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::error::{GlobalError, Result};
+
+/// `major.minor`
+///
+/// The minor version is bumped for changes older clients can still write with (eg. a new
+/// optional field), the major version for everything else.
+///
+/// Serialized as the float versions used to be, eg. `1.2`. Text like `"1.2"` is read too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "number"))]
+pub struct SchemaVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl SchemaVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || GlobalError::ParseError(format!("Schema Version: {value}"));
+
+        let (major, minor) = value.trim().split_once('.').unwrap_or((value.trim(), "0"));
+
+        Ok(Self {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+
+    /// Read a version stored as a float, eg. `1.2`.
+    ///
+    /// Floats can't tell `1.1` and `1.10` apart, both are read as `1.1`.
+    pub fn from_float(value: f64) -> Result<Self> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(GlobalError::ParseError(format!("Schema Version: {value}")));
+        }
+
+        Self::parse(&value.to_string())
+    }
+
+    /// Minor versions ending in 0 are skipped (`1.9` becomes `1.11`) since as a float `1.10`
+    /// would be read back as `1.1`.
+    pub fn bump_minor(self) -> Self {
+        let mut minor = self.minor + 1;
+
+        if minor.is_multiple_of(10) {
+            minor += 1;
+        }
+
+        Self::new(self.major, minor)
+    }
+
+    /// The version as it's serialized.
+    pub fn as_float(&self) -> f64 {
+        self.to_string()
+            .parse()
+            .expect("major.minor is a valid float")
+    }
+
+    pub fn bump_major(self) -> Self {
+        Self::new(self.major + 1, 0)
+    }
+
+    /// Whether a client built against this version can write to a schema at `current`.
+    ///
+    /// The major versions have to match and the client can't be ahead of the schema.
+    pub fn is_compatible_with(&self, current: &Self) -> bool {
+        self.major == current.major && self.minor <= current.minor
+    }
+
+    /// [`SchemaVersion::is_compatible_with`] as an error for rejecting writes.
+    pub fn check_compatible(&self, current: &Self) -> Result<()> {
+        if self.is_compatible_with(current) {
+            Ok(())
+        } else {
+            Err(GlobalError::IncompatibleSchema {
+                client: *self,
+                current: *current,
+            })
+        }
+    }
+}
+
+impl Default for SchemaVersion {
+    fn default() -> Self {
+        Self::new(1, 0)
+    }
+}
+
+impl Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for SchemaVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_float())
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Versions used to be stored as floats.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Text(String),
+            Float(f64),
+        }
+
+        match Stored::deserialize(deserializer)? {
+            Stored::Text(v) => Self::parse(&v),
+            Stored::Float(v) => Self::from_float(v),
+        }
+        .map_err(de::Error::custom)
+    }
+}
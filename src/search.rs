@@ -0,0 +1,235 @@
+//! Site-wide full-text search.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    id::SchemaDataPublicId,
+    response::CmsRowResponse,
+    richtext::RichDocument,
+    schema::{SchemaFieldMap, SchematicFieldKey, SchematicFieldType},
+    uuid::CollectionName,
+    validators::PostalAddress,
+    value::SimpleValue,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub text: String,
+    /// Empty = Every Collection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<CollectionName>,
+    /// Empty = Every Searchable Field
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub fuzziness: Fuzziness,
+    /// Return the matched parts of each result.
+    #[serde(default)]
+    pub highlight: bool,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl SearchQuery {
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            collections: Vec::new(),
+            fields: Vec::new(),
+            fuzziness: Fuzziness::default(),
+            highlight: false,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// Whether the field is searched by this query.
+    pub fn searches_field(&self, key: &str) -> bool {
+        self.fields.is_empty() || self.fields.iter().any(|v| v == key)
+    }
+}
+
+/// How many typos a term may have and still match.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Fuzziness {
+    Exact,
+    /// Depends on the length of the term: none up to 2 characters, 1 up to 5, otherwise 2.
+    #[default]
+    Auto,
+    Edits(u8),
+}
+
+impl Fuzziness {
+    /// The edit distance allowed for a term.
+    pub fn max_edits(self, term: &str) -> u8 {
+        match self {
+            Self::Exact => 0,
+            Self::Auto => match term.chars().count() {
+                0..=2 => 0,
+                3..=5 => 1,
+                _ => 2,
+            },
+            Self::Edits(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub collection: CollectionName,
+    pub row_id: SchemaDataPublicId,
+    /// Higher is more relevant. Only comparable within the same search.
+    pub score: f32,
+    /// Only filled in if [`SearchQuery::highlight`] is set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<SearchHighlight>,
+}
+
+/// The matched parts of a field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHighlight {
+    pub field: String,
+    /// An excerpt of the field.
+    pub text: String,
+    /// Byte ranges of `text` which matched, as `[start, end)`.
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// The text of every searchable field of the row, in field order.
+///
+/// Rich text is stripped of its formatting, tags are joined by spaces. Fields without any
+/// words (eg. numbers, references and uploads), deleted fields and empty values are skipped.
+pub fn indexable_text(
+    row: &CmsRowResponse,
+    fields: &SchemaFieldMap,
+) -> Vec<(SchematicFieldKey, String)> {
+    fields
+        .iter_ordered()
+        .filter(|(_, field)| !field.is_deleted)
+        .filter_map(|(key, field)| {
+            let text = field_text(field.field_type, row.fields.get(key)?)?;
+            let text = text.trim();
+
+            (!text.is_empty()).then(|| (key.clone(), text.to_string()))
+        })
+        .collect()
+}
+
+fn field_text(field_type: SchematicFieldType, value: &SimpleValue) -> Option<String> {
+    match (field_type, value) {
+        (
+            SchematicFieldType::Text
+            | SchematicFieldType::URL
+            | SchematicFieldType::Email
+            | SchematicFieldType::Phone,
+            SimpleValue::Text(v),
+        ) => Some(v.clone()),
+        (SchematicFieldType::Address, v) => {
+            PostalAddress::from_value(v).ok().map(|v| v.to_string())
+        }
+        (SchematicFieldType::RichText | SchematicFieldType::RichContent, v) => rich_text(v),
+        (SchematicFieldType::Tags, SimpleValue::ListString(v)) => Some(v.join(" ")),
+        _ => None,
+    }
+}
+
+/// Rich text is stored as a [`RichDocument`] or, for older values, HTML.
+fn rich_text(value: &SimpleValue) -> Option<String> {
+    match value {
+        SimpleValue::ObjectUnknown(v) => serde_json::from_value::<RichDocument>(v.clone())
+            .ok()
+            .map(|v| v.plain_text()),
+        SimpleValue::Text(v) => Some(
+            serde_json::from_str::<RichDocument>(v)
+                .map(|v| v.plain_text())
+                .unwrap_or_else(|_| strip_html(v)),
+        ),
+        _ => None,
+    }
+}
+
+/// Tags which start a new line in [`strip_html`].
+const BLOCK_TAGS: &[&str] = &[
+    "br",
+    "p",
+    "div",
+    "li",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "tr",
+    "blockquote",
+    "pre",
+];
+
+/// Remove the tags of an HTML fragment, keeping the text.
+///
+/// Block level tags and `<br>` become new lines, the common entities are decoded and the
+/// contents of `<script>` and `<style>` are removed.
+pub fn strip_html(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut skip_until = None;
+
+    while let Some(start) = rest.find('<') {
+        if skip_until.is_none() {
+            output.push_str(&decode_entities(&rest[..start]));
+        }
+
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+
+        let tag = rest[start + 1..start + end].trim().to_lowercase();
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        match skip_until {
+            Some(ref v) if tag.starts_with('/') && *v == name => skip_until = None,
+            Some(_) => (),
+            None if !tag.starts_with('/') && (name == "script" || name == "style") => {
+                skip_until = Some(name);
+            }
+            None if BLOCK_TAGS.contains(&name.as_str())
+                && !output.is_empty()
+                && !output.ends_with('\n') =>
+            {
+                output.push('\n');
+            }
+            None => (),
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    if skip_until.is_none() {
+        output.push_str(&decode_entities(rest));
+    }
+
+    output.trim().to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
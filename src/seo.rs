@@ -0,0 +1,221 @@
+//! Search engine and social media metadata of pages and CMS items.
+
+pub mod robots;
+pub mod sitemap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::{GlobalError, Result};
+
+/// Longer titles are cut off in search results.
+pub const MAX_TITLE_LEN: usize = 70;
+pub const MAX_DESCRIPTION_LEN: usize = 160;
+pub const MAX_SOCIAL_TITLE_LEN: usize = 95;
+pub const MAX_SOCIAL_DESCRIPTION_LEN: usize = 200;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SeoMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<Url>,
+    #[serde(default, skip_serializing_if = "RobotsFlags::is_default")]
+    pub robots: RobotsFlags,
+    #[serde(default, skip_serializing_if = "OpenGraph::is_empty")]
+    pub open_graph: OpenGraph,
+    #[serde(default, skip_serializing_if = "TwitterCard::is_empty")]
+    pub twitter: TwitterCard,
+    /// Structured data, added to the page as `application/ld+json`. An object or a list of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_ld: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RobotsFlags {
+    #[serde(default)]
+    pub no_index: bool,
+    #[serde(default)]
+    pub no_follow: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct OpenGraph {
+    /// Falls back to [`SeoMeta::title`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Falls back to [`SeoMeta::description`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<Url>,
+    /// `og:type`, eg. `website` or `article`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TwitterCard {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card: Option<TwitterCardKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<Url>,
+    /// The `@handle` of the site.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+pub enum TwitterCardKind {
+    Summary,
+    SummaryLargeImage,
+}
+
+impl SeoMeta {
+    /// Check the lengths of the texts and the shape of the JSON-LD.
+    pub fn validate(&self) -> Result<()> {
+        check_len("title", &self.title, MAX_TITLE_LEN)?;
+        check_len("description", &self.description, MAX_DESCRIPTION_LEN)?;
+        check_len(
+            "OpenGraph title",
+            &self.open_graph.title,
+            MAX_SOCIAL_TITLE_LEN,
+        )?;
+        check_len(
+            "OpenGraph description",
+            &self.open_graph.description,
+            MAX_SOCIAL_DESCRIPTION_LEN,
+        )?;
+        check_len("Twitter title", &self.twitter.title, MAX_TITLE_LEN)?;
+        check_len(
+            "Twitter description",
+            &self.twitter.description,
+            MAX_SOCIAL_DESCRIPTION_LEN,
+        )?;
+
+        if let Some(site) = &self.twitter.site
+            && !site.starts_with('@')
+        {
+            return Err(GlobalError::InvalidSeo(format!(
+                "Twitter site {site} doesn't start with @"
+            )));
+        }
+
+        match &self.json_ld {
+            None | Some(serde_json::Value::Object(_)) => {}
+            Some(serde_json::Value::Array(items)) if items.iter().all(|v| v.is_object()) => {}
+            Some(_) => {
+                return Err(GlobalError::InvalidSeo(String::from(
+                    "JSON-LD has to be an object or a list of objects",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill in everything which isn't set with the defaults, usually the ones of the site.
+    ///
+    /// Robots flags are combined, so a site which isn't indexed never has indexed pages. Social
+    /// titles and descriptions fall back to the merged title and description. The canonical URL
+    /// and JSON-LD describe a single page, so they're never taken from the defaults.
+    pub fn merge_with_defaults(&self, defaults: &SeoMeta) -> SeoMeta {
+        let title = or(&self.title, &defaults.title);
+        let description = or(&self.description, &defaults.description);
+
+        SeoMeta {
+            canonical_url: self.canonical_url.clone(),
+            robots: RobotsFlags {
+                no_index: self.robots.no_index || defaults.robots.no_index,
+                no_follow: self.robots.no_follow || defaults.robots.no_follow,
+            },
+            open_graph: OpenGraph {
+                title: or(&self.open_graph.title, &defaults.open_graph.title)
+                    .or_else(|| title.clone()),
+                description: or(
+                    &self.open_graph.description,
+                    &defaults.open_graph.description,
+                )
+                .or_else(|| description.clone()),
+                image: or(&self.open_graph.image, &defaults.open_graph.image),
+                kind: or(&self.open_graph.kind, &defaults.open_graph.kind),
+            },
+            twitter: TwitterCard {
+                card: self.twitter.card.or(defaults.twitter.card),
+                title: or(&self.twitter.title, &defaults.twitter.title).or_else(|| title.clone()),
+                description: or(&self.twitter.description, &defaults.twitter.description)
+                    .or_else(|| description.clone()),
+                image: or(&self.twitter.image, &defaults.twitter.image),
+                site: or(&self.twitter.site, &defaults.twitter.site),
+            },
+            json_ld: self.json_ld.clone(),
+            title,
+            description,
+        }
+    }
+
+    /// The `content` of the robots meta tag.
+    pub fn robots_content(&self) -> &'static str {
+        match (self.robots.no_index, self.robots.no_follow) {
+            (false, false) => "index, follow",
+            (true, false) => "noindex, follow",
+            (false, true) => "index, nofollow",
+            (true, true) => "noindex, nofollow",
+        }
+    }
+}
+
+impl RobotsFlags {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl OpenGraph {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl TwitterCard {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn or<T: Clone>(value: &Option<T>, default: &Option<T>) -> Option<T> {
+    value.as_ref().or(default.as_ref()).cloned()
+}
+
+fn check_len(name: &str, value: &Option<String>, max: usize) -> Result<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+
+    let len = value.chars().count();
+
+    if value.trim().is_empty() {
+        Err(GlobalError::InvalidSeo(format!("The {name} is empty")))
+    } else if len > max {
+        Err(GlobalError::InvalidSeo(format!(
+            "The {name} is {len} characters long, the maximum is {max}"
+        )))
+    } else {
+        Ok(())
+    }
+}
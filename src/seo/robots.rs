@@ -0,0 +1,133 @@
+//! Building `robots.txt` files.
+
+use std::fmt::{self, Display};
+
+use url::Url;
+
+/// Rendered with [`Display`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RobotsTxt {
+    groups: Vec<RobotsGroup>,
+    sitemaps: Vec<Url>,
+}
+
+/// Rules for one or more crawlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RobotsGroup {
+    user_agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RobotsRule {
+    Allow(String),
+    Disallow(String),
+}
+
+impl RobotsTxt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything can be crawled.
+    pub fn allow_all() -> Self {
+        Self::new().group(RobotsGroup::new("*").disallow(""))
+    }
+
+    /// Nothing can be crawled, eg. for staging sites.
+    pub fn disallow_all() -> Self {
+        Self::new().group(RobotsGroup::new("*").disallow("/"))
+    }
+
+    pub fn group(mut self, group: RobotsGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    pub fn sitemap(mut self, url: Url) -> Self {
+        self.sitemaps.push(url);
+        self
+    }
+}
+
+impl RobotsGroup {
+    pub fn new<I: Into<String>>(user_agent: I) -> Self {
+        Self {
+            user_agents: vec![user_agent.into()],
+            rules: Vec::new(),
+            crawl_delay: None,
+        }
+    }
+
+    /// Another crawler the rules apply to.
+    pub fn user_agent<I: Into<String>>(mut self, value: I) -> Self {
+        self.user_agents.push(value.into());
+        self
+    }
+
+    pub fn allow<I: Into<String>>(mut self, path: I) -> Self {
+        self.rules.push(RobotsRule::Allow(path.into()));
+        self
+    }
+
+    /// An empty path disallows nothing.
+    pub fn disallow<I: Into<String>>(mut self, path: I) -> Self {
+        self.rules.push(RobotsRule::Disallow(path.into()));
+        self
+    }
+
+    /// Seconds between requests. Ignored by some crawlers.
+    pub fn crawl_delay(mut self, seconds: u32) -> Self {
+        self.crawl_delay = Some(seconds);
+        self
+    }
+}
+
+impl Display for RobotsTxt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, group) in self.groups.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+
+            group.fmt(f)?;
+        }
+
+        if !self.sitemaps.is_empty() && !self.groups.is_empty() {
+            writeln!(f)?;
+        }
+
+        for url in &self.sitemaps {
+            writeln!(f, "Sitemap: {url}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RobotsGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for agent in &self.user_agents {
+            writeln!(f, "User-agent: {}", single_line(agent))?;
+        }
+
+        for rule in &self.rules {
+            match rule {
+                RobotsRule::Allow(path) => writeln!(f, "Allow: {}", single_line(path))?,
+                RobotsRule::Disallow(path) => writeln!(f, "Disallow: {}", single_line(path))?,
+            }
+        }
+
+        if let Some(seconds) = self.crawl_delay {
+            writeln!(f, "Crawl-delay: {seconds}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Values can't add lines of their own.
+fn single_line(value: &str) -> &str {
+    value.lines().next().unwrap_or_default().trim()
+}
@@ -0,0 +1,202 @@
+//! Rendering of `sitemap.xml` files.
+//!
+//! A sitemap holds up to [`MAX_URLS_PER_SITEMAP`] URLs. Larger sites are split into several
+//! sitemaps, listed by a sitemap index.
+
+use std::fmt::{self, Display, Write};
+
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use url::Url;
+
+use crate::{
+    error::Result,
+    pages::{PageTree, PageVisibility},
+};
+
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+const XMLNS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SitemapEntry {
+    pub loc: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<OffsetDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_frequency: Option<ChangeFrequency>,
+    /// Between 0.0 and 1.0, clamped when rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChangeFrequency {
+    Always,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+    Never,
+}
+
+/// The rendered sitemaps of a site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sitemaps {
+    pub sitemaps: Vec<String>,
+    /// Lists every sitemap. Only rendered if there's more than one.
+    pub index: Option<String>,
+}
+
+impl SitemapEntry {
+    /// An entry for a path of the site, eg. `/shop/cart`.
+    pub fn new(base_url: &Url, path: &str) -> Result<Self> {
+        Ok(Self {
+            loc: base_url.join(path)?,
+            last_modified: None,
+            change_frequency: None,
+            priority: None,
+        })
+    }
+
+    pub fn last_modified(mut self, value: OffsetDateTime) -> Self {
+        self.last_modified = Some(value);
+        self
+    }
+
+    pub fn change_frequency(mut self, value: ChangeFrequency) -> Self {
+        self.change_frequency = Some(value);
+        self
+    }
+
+    pub fn priority(mut self, value: f32) -> Self {
+        self.priority = Some(value);
+        self
+    }
+
+    /// Entries for the public pages of a site which can be indexed.
+    pub fn from_pages(tree: &PageTree, base_url: &Url) -> Result<Vec<Self>> {
+        tree.iter()
+            .filter(|node| node.page.visibility == PageVisibility::Public)
+            .filter(|node| !node.page.seo.as_ref().is_some_and(|v| v.robots.no_index))
+            .map(|node| Self::new(base_url, &node.path))
+            .collect()
+    }
+}
+
+impl ChangeFrequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+
+impl Display for ChangeFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Render the entries into as many sitemaps as needed.
+///
+/// `sitemap_url` gives the URL each sitemap is served from by its position. It's only used for
+/// the index.
+pub fn render(
+    entries: impl IntoIterator<Item = SitemapEntry>,
+    sitemap_url: impl Fn(usize) -> Url,
+) -> Sitemaps {
+    let mut sitemaps = Vec::new();
+    let mut entries = entries.into_iter().peekable();
+
+    // An empty site still gets an empty sitemap.
+    while sitemaps.is_empty() || entries.peek().is_some() {
+        sitemaps.push(render_urlset(entries.by_ref().take(MAX_URLS_PER_SITEMAP)));
+    }
+
+    let index = (sitemaps.len() > 1).then(|| render_index((0..sitemaps.len()).map(sitemap_url)));
+
+    Sitemaps { sitemaps, index }
+}
+
+/// A single sitemap. Callers have to keep it to [`MAX_URLS_PER_SITEMAP`] entries.
+pub fn render_urlset(entries: impl IntoIterator<Item = SitemapEntry>) -> String {
+    let mut xml = format!("{XML_HEADER}\n<urlset xmlns=\"{XMLNS}\">\n");
+
+    for entry in entries {
+        xml.push_str("  <url>\n");
+        push_element(&mut xml, "loc", entry.loc.as_str());
+
+        if let Some(value) = entry.last_modified {
+            push_element(&mut xml, "lastmod", &format_date(value));
+        }
+
+        if let Some(value) = entry.change_frequency {
+            push_element(&mut xml, "changefreq", value.as_str());
+        }
+
+        if let Some(value) = entry.priority {
+            push_element(
+                &mut xml,
+                "priority",
+                &format!("{:.1}", value.clamp(0.0, 1.0)),
+            );
+        }
+
+        xml.push_str("  </url>\n");
+    }
+
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+pub fn render_index(sitemaps: impl IntoIterator<Item = Url>) -> String {
+    let mut xml = format!("{XML_HEADER}\n<sitemapindex xmlns=\"{XMLNS}\">\n");
+
+    for url in sitemaps {
+        xml.push_str("  <sitemap>\n");
+        push_element(&mut xml, "loc", url.as_str());
+        xml.push_str("  </sitemap>\n");
+    }
+
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+fn push_element(xml: &mut String, name: &str, value: &str) {
+    let _ = writeln!(xml, "    <{name}>{}</{name}>", escape_xml(value));
+}
+
+fn format_date(value: OffsetDateTime) -> String {
+    value
+        .replace_nanosecond(0)
+        .unwrap_or(value)
+        .format(&Rfc3339)
+        .unwrap_or_else(|_| value.date().to_string())
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
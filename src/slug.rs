@@ -0,0 +1,140 @@
+//! The URL part of a row or page, eg. `my-first-post`.
+
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+use crate::error::{GlobalError, Result};
+
+/// Lowercase ASCII letters and digits, with single dashes between them.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(try_from = "String", into = "String")]
+pub struct Slug(String);
+
+impl Slug {
+    pub const MAX_LEN: usize = 100;
+
+    pub fn parse(value: &str) -> Result<Self> {
+        if !Self::is_valid(value) {
+            return Err(GlobalError::ParseError(format!(
+                "Slug {value:?}: expected 1 to {} lowercase letters, digits and single dashes \
+                 between them",
+                Self::MAX_LEN
+            )));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+
+    pub fn is_valid(value: &str) -> bool {
+        !value.is_empty()
+            && value.len() <= Self::MAX_LEN
+            && !value.starts_with('-')
+            && !value.ends_with('-')
+            && !value.contains("--")
+            && value
+                .bytes()
+                .all(|v| v.is_ascii_lowercase() || v.is_ascii_digit() || v == b'-')
+    }
+
+    /// Generate a slug from a title, eg. `Crème Brûlée & Co.` gives `creme-brulee-co`.
+    ///
+    /// Accents are removed and a few letters without a decomposition are transliterated (`ß`
+    /// to `ss`, `ø` to `o`, ..). Anything else which isn't a letter or digit separates words.
+    /// Long titles are cut at a word to fit [`Slug::MAX_LEN`]. Fails if nothing is left, eg.
+    /// for a title in a non-latin script.
+    pub fn from_title(title: &str) -> Result<Self> {
+        let mut slug = String::with_capacity(title.len());
+
+        for c in title.nfd().filter(|v| !is_combining_mark(*v)) {
+            let c = c.to_lowercase().next().unwrap_or(c);
+
+            match transliterate(c) {
+                Some(v) => slug.push_str(v),
+                None if c.is_ascii_alphanumeric() => slug.push(c),
+                // A dash is only kept if something follows it.
+                None if !slug.is_empty() && !slug.ends_with('-') => slug.push('-'),
+                None => {}
+            }
+        }
+
+        let mut slug = slug.trim_end_matches('-');
+
+        if slug.len() > Self::MAX_LEN {
+            // Including the next character, which is a dash if the cut is between words.
+            slug = slug[..=Self::MAX_LEN]
+                .rsplit_once('-')
+                .map_or(&slug[..Self::MAX_LEN], |(words, _)| words);
+        }
+
+        if slug.is_empty() {
+            return Err(GlobalError::ParseError(format!(
+                "Unable to create a slug from {title:?}"
+            )));
+        }
+
+        Ok(Self(slug.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The slug with a number appended, eg. `my-post-2`, shortened to fit the number if needed.
+    pub fn with_suffix(&self, number: u32) -> Self {
+        let suffix = format!("-{number}");
+        let base = &self.0[..self.0.len().min(Self::MAX_LEN - suffix.len())];
+        let base = base.trim_end_matches('-');
+
+        Self(format!("{base}{suffix}"))
+    }
+
+    /// The first of the slug, `slug-2`, `slug-3`, .. which isn't taken.
+    pub fn make_unique<F: Fn(&Slug) -> bool>(self, is_taken: F) -> Self {
+        if !is_taken(&self) {
+            return self;
+        }
+
+        (2..)
+            .map(|v| self.with_suffix(v))
+            .find(|v| !is_taken(v))
+            .expect("a free number")
+    }
+}
+
+/// Letters which don't decompose into an ASCII letter and an accent.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'ł' => "l",
+        'đ' | 'ð' => "d",
+        'þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+impl Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TryFrom<String> for Slug {
+    type Error = GlobalError;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Slug> for String {
+    fn from(value: Slug) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,56 @@
+//! Client-side sorting of CMS rows, matching the order the server returns.
+
+use std::cmp::Ordering;
+
+use crate::{
+    response::CmsRowResponse,
+    schema::{DefaultSort, SchemaFieldMap, SchematicFieldKey, SchematicFieldType, SortOrder},
+};
+
+/// Sort the rows by each sort in turn, using [`SimpleValue::compare`](crate::value::SimpleValue::compare).
+///
+/// Like the database, missing values are last when ascending and first when descending.
+/// The sort is stable so rows which compare equal keep their order.
+pub fn sort_rows(rows: &mut [CmsRowResponse], sorts: &[DefaultSort], fields: &SchemaFieldMap) {
+    let sorts = sorts
+        .iter()
+        .map(|sort| {
+            let key = SchematicFieldKey::from(sort.field.as_str());
+            let field_type = field_type(&key, fields);
+
+            (key, field_type, sort.order)
+        })
+        .collect::<Vec<_>>();
+
+    rows.sort_by(|a, b| {
+        for (key, field_type, order) in &sorts {
+            let ordering = match (a.fields.get(key), b.fields.get(key)) {
+                (Some(a), Some(b)) => a.compare(b, *field_type),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            };
+
+            let ordering = match order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            };
+
+            if ordering.is_ne() {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    });
+}
+
+fn field_type(key: &SchematicFieldKey, fields: &SchemaFieldMap) -> SchematicFieldType {
+    match key {
+        SchematicFieldKey::CreatedAt | SchematicFieldKey::UpdatedAt => SchematicFieldType::DateTime,
+        key => fields
+            .get(key)
+            .map(|f| f.field_type)
+            .unwrap_or(SchematicFieldType::Text),
+    }
+}
@@ -0,0 +1,182 @@
+//! Helpers for asserting the wire format of the public types, behind the `test-utils` feature.
+//!
+//! Fixtures live in `<dir>/<name>.json` and hold the exact JSON the other services send and
+//! expect back, `dir` usually being the [`fixture_dir`] of the package under test. With the
+//! `codec` feature, `<name>.cbor.hex` holds the binary encoding of the same value.
+//!
+//! [`arbitrary`] generates values for property tests.
+
+#[cfg(feature = "test-utils")]
+pub mod arbitrary;
+
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::value::SimpleValue;
+
+/// The `tests/fixtures` directory of the package at `base`, eg.
+/// `fixture_dir(env!("CARGO_MANIFEST_DIR"))` in its tests.
+pub fn fixture_dir(base: impl AsRef<Path>) -> PathBuf {
+    base.as_ref().join("tests").join("fixtures")
+}
+
+pub fn fixture_path(dir: impl AsRef<Path>, name: &str) -> PathBuf {
+    dir.as_ref().join(format!("{name}.json"))
+}
+
+/// Load the raw JSON of a fixture.
+pub fn load_fixture_value(dir: impl AsRef<Path>, name: &str) -> serde_json::Value {
+    let path = fixture_path(dir, name);
+
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read fixture {}: {e}", path.display()));
+
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|e| panic!("Fixture {} is not valid JSON: {e}", path.display()))
+}
+
+/// Load a fixture and deserialize it into `T`.
+pub fn load_fixture<T: DeserializeOwned>(dir: impl AsRef<Path>, name: &str) -> T {
+    serde_json::from_value(load_fixture_value(dir, name))
+        .unwrap_or_else(|e| panic!("Unable to deserialize fixture {name}: {e}"))
+}
+
+/// Serialize `value`, deserialize it back and ensure the second serialization is identical.
+///
+/// Compares the JSON output so it works for types which don't implement `PartialEq`.
+pub fn assert_roundtrip<T: Serialize + DeserializeOwned + Debug>(value: &T) -> T {
+    let first = serde_json::to_value(value)
+        .unwrap_or_else(|e| panic!("Unable to serialize {value:?}: {e}"));
+
+    let decoded: T = serde_json::from_value(first.clone())
+        .unwrap_or_else(|e| panic!("Unable to deserialize {first}: {e}"));
+
+    let second = serde_json::to_value(&decoded)
+        .unwrap_or_else(|e| panic!("Unable to serialize {decoded:?}: {e}"));
+
+    assert_eq!(
+        first, second,
+        "Round-trip changed the wire format of {value:?}"
+    );
+
+    decoded
+}
+
+/// Serialize `value` to a JSON string, parse it back and ensure it equals `value`.
+///
+/// Stricter than [`assert_roundtrip`], a value read back in another variant with the same
+/// JSON fails.
+pub fn assert_roundtrip_eq<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) -> T {
+    let json = serde_json::to_string(value)
+        .unwrap_or_else(|e| panic!("Unable to serialize {value:?}: {e}"));
+
+    let decoded: T =
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("Unable to deserialize {json}: {e}"));
+
+    assert_eq!(
+        value, &decoded,
+        "Round-trip through {json} changed the value"
+    );
+
+    decoded
+}
+
+/// Write the value with [`SimpleValue::into_json`] and read it back with
+/// [`SimpleValue::from_json`], the way values from other services are read, and ensure it's
+/// unchanged.
+pub fn assert_value_roundtrip(value: &SimpleValue) -> SimpleValue {
+    let json = value.clone().into_json();
+
+    let decoded = SimpleValue::from_json(json.clone())
+        .unwrap_or_else(|e| panic!("Unable to read {json}: {e}"));
+
+    assert_eq!(
+        value, &decoded,
+        "Round-trip through {json} changed the value"
+    );
+
+    decoded
+}
+
+/// Deserialize the fixture into `T` and ensure serializing it produces the fixture again.
+pub fn assert_golden<T: Serialize + DeserializeOwned + Debug>(
+    dir: impl AsRef<Path>,
+    name: &str,
+) -> T {
+    let expected = load_fixture_value(dir, name);
+
+    let decoded: T = serde_json::from_value(expected.clone())
+        .unwrap_or_else(|e| panic!("Unable to deserialize fixture {name}: {e}"));
+
+    let actual = serde_json::to_value(&decoded)
+        .unwrap_or_else(|e| panic!("Unable to serialize {decoded:?}: {e}"));
+
+    assert_eq!(expected, actual, "Wire format of fixture {name} changed");
+
+    decoded
+}
+
+/// Deserialize `legacy/<name>.json`, written with the field names used before they were all
+/// camelCase, and ensure it serializes to the fixture `<name>`.
+pub fn assert_legacy_golden<T: Serialize + DeserializeOwned + Debug>(
+    dir: impl AsRef<Path>,
+    name: &str,
+) -> T {
+    let dir = dir.as_ref();
+    let decoded: T = load_fixture(dir, &format!("legacy/{name}"));
+
+    let actual = serde_json::to_value(&decoded)
+        .unwrap_or_else(|e| panic!("Unable to serialize {decoded:?}: {e}"));
+
+    assert_eq!(
+        load_fixture_value(dir, name),
+        actual,
+        "Legacy fixture {name} isn't read as the current one"
+    );
+
+    decoded
+}
+
+/// Encode the fixture with [`codec`](crate::codec) and ensure it matches `<name>.cbor.hex`, and
+/// that the stored bytes still decode to the fixture.
+#[cfg(feature = "codec")]
+pub fn assert_codec_golden<T: Serialize + DeserializeOwned + Debug>(
+    dir: impl AsRef<Path>,
+    name: &str,
+) -> T {
+    let dir = dir.as_ref();
+    let path = dir.join(format!("{name}.cbor.hex"));
+
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read fixture {}: {e}", path.display()));
+
+    let value: T = load_fixture(dir, name);
+
+    let actual =
+        crate::codec::encode(&value).unwrap_or_else(|e| panic!("Unable to encode {value:?}: {e}"));
+
+    assert_eq!(
+        contents.trim(),
+        hex::encode(&actual),
+        "Binary encoding of fixture {name} changed"
+    );
+
+    let decoded: T = crate::codec::decode(&actual)
+        .unwrap_or_else(|e| panic!("Unable to decode fixture {name}: {e}"));
+
+    let json = serde_json::to_value(&decoded)
+        .unwrap_or_else(|e| panic!("Unable to serialize {decoded:?}: {e}"));
+
+    assert_eq!(
+        load_fixture_value(dir, name),
+        json,
+        "Decoding fixture {name} changed it"
+    );
+
+    decoded
+}
@@ -0,0 +1,405 @@
+//! proptest strategies and [`Arbitrary`] impls for the value, schema and filter types.
+//!
+//! Generated values are canonical, they come back unchanged from their wire format:
+//!
+//! - A Number is an UInteger above [`i64::MAX`] and an Integer otherwise, never a Byte.
+//!   Floats are finite and have at most 6 decimals, which serde_json reads back exactly without
+//!   `float_roundtrip`.
+//! - A [`SimpleValue`] Text never starts with a digit, so it isn't read as a date or time. A
+//!   Number List is never empty and an Array always holds a null or a boolean, otherwise they'd
+//!   be read as a String List.
+//! - Dates and times are UTC, between the years 1 and 9999.
+//! - A [`SchematicFieldValue`] is in the variant [`SchematicFieldType::parse_value`] gives for
+//!   its type.
+//!
+//! Check them with [`assert_roundtrip_eq`](super::assert_roundtrip_eq) and
+//! [`assert_value_roundtrip`](super::assert_value_roundtrip).
+
+use proptest::{
+    collection::{btree_map, vec},
+    option,
+    prelude::*,
+    sample::{Index, select, subsequence},
+};
+use serde_json::Value;
+use time::{Date, Duration, OffsetDateTime, Time, macros::date};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    filter::{Filter, FilterConditionType, FilterValue},
+    geo::GeoPoint,
+    money::{CurrencyCode, Money},
+    schema::{
+        Operations, Schematic, SchematicFieldType, SchematicFieldValue, builder::SchematicBuilder,
+        normalize_tags,
+    },
+    slug::Slug,
+    upload::MediaGalleryItem,
+    validators::{EmailAddress, PhoneNumber, PostalAddress},
+    value::{Number, SimpleValue},
+};
+
+/// `0001-01-01T00:00:00Z` and `9999-12-31T23:59:59Z`.
+const TIMESTAMPS: (i64, i64) = (-62_135_596_800, 253_402_300_799);
+
+impl Arbitrary for Number {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        number().boxed()
+    }
+}
+
+impl Arbitrary for SimpleValue {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        simple_value().boxed()
+    }
+}
+
+impl Arbitrary for SchematicFieldValue {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        field_type().prop_flat_map(field_value).boxed()
+    }
+}
+
+impl Arbitrary for Schematic {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        schematic().boxed()
+    }
+}
+
+impl Arbitrary for Filter {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        filter().boxed()
+    }
+}
+
+pub fn number() -> impl Strategy<Value = Number> {
+    prop_oneof![
+        any::<i64>().prop_map(Number::Integer),
+        (i64::MAX as u64 + 1..=u64::MAX).prop_map(Number::UInteger),
+        float().prop_map(Number::Float),
+    ]
+}
+
+pub fn simple_value() -> impl Strategy<Value = SimpleValue> {
+    prop_oneof![
+        text().prop_map(SimpleValue::Text),
+        number().prop_map(SimpleValue::Number),
+        any::<bool>().prop_map(SimpleValue::Boolean),
+        date_time().prop_map(SimpleValue::DateTime),
+        date().prop_map(SimpleValue::Date),
+        time().prop_map(SimpleValue::Time),
+        vec(any::<String>(), 0..4).prop_map(SimpleValue::ListString),
+        vec(number(), 1..4).prop_map(SimpleValue::ListNumber),
+        (
+            prop_oneof![Just(Value::Null), any::<bool>().prop_map(Value::Bool)],
+            vec(json(), 0..4),
+            any::<Index>(),
+        )
+            .prop_map(|(marker, mut items, at)| {
+                items.insert(at.index(items.len() + 1), marker);
+                SimpleValue::ArrayUnknown(items)
+            }),
+        json_object().prop_map(SimpleValue::ObjectUnknown),
+    ]
+}
+
+pub fn field_type() -> impl Strategy<Value = SchematicFieldType> {
+    select(
+        (0..)
+            .map_while(|v| SchematicFieldType::try_from(v).ok())
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// A value of the type, in the variant [`SchematicFieldType::parse_value`] gives.
+pub fn field_value(field_type: SchematicFieldType) -> BoxedStrategy<SchematicFieldValue> {
+    use SchematicFieldType as Type;
+    use SchematicFieldValue as FieldValue;
+
+    match field_type {
+        Type::Text | Type::RichText | Type::RichContent => {
+            any::<String>().prop_map(FieldValue::Text).boxed()
+        }
+        Type::Number => number().prop_map(FieldValue::Number).boxed(),
+        Type::URL => "[a-z0-9/-]{0,16}"
+            .prop_map(|v| FieldValue::Url(Url::parse(&format!("https://example.com/{v}")).unwrap()))
+            .boxed(),
+        Type::Email => "[a-z0-9._%+-]{1,12}@[a-z0-9]{1,12}\\.(com|org|io)"
+            .prop_filter_map("invalid email", |v| {
+                EmailAddress::parse(&v).ok().map(FieldValue::Email)
+            })
+            .boxed(),
+        Type::Phone => "\\+[1-9][0-9]{6,14}"
+            .prop_map(|v| FieldValue::Phone(PhoneNumber::parse(&v).unwrap()))
+            .boxed(),
+        Type::Address => (
+            any::<String>(),
+            option::of(any::<String>()),
+            any::<String>(),
+            option::of(any::<String>()),
+            option::of("[A-Z0-9 ]{3,10}"),
+            "[A-Z]{2}",
+        )
+            .prop_map(|(line1, line2, city, region, postal_code, country)| {
+                FieldValue::Address(PostalAddress {
+                    line1,
+                    line2,
+                    city,
+                    region,
+                    postal_code,
+                    country,
+                })
+            })
+            .boxed(),
+        Type::Boolean => any::<bool>().prop_map(FieldValue::Boolean).boxed(),
+        Type::DateTime => date_time().prop_map(FieldValue::DateTime).boxed(),
+        Type::Date => date().prop_map(FieldValue::Date).boxed(),
+        Type::Time => time().prop_map(FieldValue::Time).boxed(),
+        Type::Reference => uuid().prop_map(FieldValue::Reference).boxed(),
+        Type::MultiReference => vec(uuid(), 0..4)
+            .prop_map(FieldValue::MultiReference)
+            .boxed(),
+        Type::MediaGallery => vec(
+            (
+                uuid(),
+                option::of(any::<String>()),
+                option::of(any::<String>()),
+                any::<u32>(),
+            ),
+            0..4,
+        )
+        .prop_map(|items| {
+            FieldValue::MediaGallery(
+                items
+                    .into_iter()
+                    .map(|(upload_id, caption, alt_text, order)| MediaGalleryItem {
+                        upload_id,
+                        caption,
+                        alt_text,
+                        order,
+                    })
+                    .collect(),
+            )
+        })
+        .boxed(),
+        Type::Document | Type::Image | Type::Video | Type::Audio => {
+            vec(number(), 0..8).prop_map(FieldValue::ListNumber).boxed()
+        }
+        Type::MultiDocument => vec(any::<String>(), 0..4)
+            .prop_map(FieldValue::ListString)
+            .boxed(),
+        Type::Tags => vec("[a-z0-9]{1,8}", 0..6)
+            .prop_map(|v| FieldValue::Tags(normalize_tags(v, false)))
+            .boxed(),
+        Type::Array => vec(json(), 0..4).prop_map(FieldValue::Array).boxed(),
+        Type::Object => json_object().prop_map(FieldValue::Object).boxed(),
+        Type::Currency => (
+            any::<i64>(),
+            select(vec![
+                CurrencyCode::USD,
+                CurrencyCode::EUR,
+                CurrencyCode::GBP,
+                CurrencyCode::JPY,
+            ]),
+        )
+            .prop_map(|(amount, currency)| FieldValue::Money(Money::new(amount, currency)))
+            .boxed(),
+        Type::Location => geo_point().prop_map(FieldValue::Location).boxed(),
+        Type::Slug => "[a-z0-9]{1,10}(-[a-z0-9]{1,10}){0,3}"
+            .prop_map(|v| FieldValue::Slug(Slug::parse(&v).unwrap()))
+            .boxed(),
+        Type::Duration => (0..1_000_000_000_i64, 0..1_000_000_000_i32, any::<bool>())
+            .prop_map(|(seconds, nanos, negative)| {
+                let duration = Duration::new(seconds, nanos);
+                FieldValue::Duration(if negative { -duration } else { duration })
+            })
+            .boxed(),
+    }
+}
+
+/// A schema built with [`SchematicBuilder`], so it's always consistent. Fields may have a
+/// default value of their type.
+pub fn schematic() -> impl Strategy<Value = Schematic> {
+    let field = field_type().prop_flat_map(|field_type| {
+        (
+            Just(field_type),
+            option::of(field_value(field_type)),
+            any::<bool>(),
+            any::<bool>(),
+        )
+    });
+
+    (
+        "[a-z][a-z0-9-]{0,15}",
+        select(vec!["Forms", "Members", "Marketing", "Billing"]),
+        "[A-Z][A-Za-z ]{0,15}",
+        uuid(),
+        btree_map("[a-z][A-Za-z0-9]{0,11}", field, 1..8),
+        any::<Index>(),
+        subsequence(Operations::ALL.to_vec(), 0..=Operations::ALL.len()),
+    )
+        .prop_map(
+            |(id, namespace, display_name, owner, fields, primary, operations)| {
+                let primary = fields.keys().nth(primary.index(fields.len())).cloned();
+
+                let mut builder = SchematicBuilder::new(id, namespace, display_name, owner.into());
+
+                for (key, (field_type, default_value, sortable, required)) in fields {
+                    builder = builder.field(key.as_str(), field_type);
+
+                    if let Some(value) = default_value {
+                        builder = builder.default_value(value);
+                    }
+
+                    if sortable {
+                        builder = builder.sortable();
+                    }
+
+                    if required {
+                        builder = builder.required();
+                    }
+                }
+
+                for operation in operations {
+                    builder = builder.allowed_operation(operation);
+                }
+
+                builder
+                    .primary_field(primary.unwrap())
+                    .build()
+                    .expect("generated schemas are consistent")
+            },
+        )
+}
+
+/// A filter whose value suits its condition, eg. a Range for `between`.
+pub fn filter() -> impl Strategy<Value = Filter> {
+    use FilterConditionType as Cond;
+
+    let name = "[a-z][A-Za-z0-9_]{0,15}";
+    let compared = prop_oneof![
+        number().prop_map(FilterValue::Number),
+        any::<String>().prop_map(FilterValue::Text),
+    ];
+    let contained = prop_oneof![
+        any::<String>().prop_map(FilterValue::Text),
+        vec(any::<String>(), 0..4).prop_map(FilterValue::IdList),
+    ];
+
+    prop_oneof![
+        (
+            name,
+            select(vec![
+                Cond::Eq,
+                Cond::Neq,
+                Cond::Gte,
+                Cond::Gt,
+                Cond::Lte,
+                Cond::Lt
+            ]),
+            compared,
+        )
+            .prop_map(|(name, cond, value)| Filter::new(name, cond, value)),
+        (name, select(vec![Cond::Cont, Cond::Dnc]), contained)
+            .prop_map(|(name, cond, value)| Filter::new(name, cond, value)),
+        (name, number(), number()).prop_map(|(name, low, high)| Filter::new(
+            name,
+            Cond::Between,
+            FilterValue::Range((low, high)),
+        )),
+        (name, geo_point(), 0..=100_000_000_u32).prop_map(|(name, center, radius)| Filter::near(
+            name,
+            center,
+            radius as f64 / 1e3
+        )),
+    ]
+}
+
+/// Any JSON value, with the numbers [`number`] generates.
+pub fn json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        number().prop_map(Value::from),
+        any::<String>().prop_map(Value::String),
+    ];
+
+    leaf.prop_recursive(3, 16, 4, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..4).prop_map(Value::Array),
+            btree_map(any::<String>(), inner, 0..4)
+                .prop_map(|v| Value::Object(v.into_iter().collect())),
+        ]
+    })
+}
+
+pub fn json_object() -> impl Strategy<Value = Value> {
+    btree_map(any::<String>(), json(), 0..4).prop_map(|v| Value::Object(v.into_iter().collect()))
+}
+
+/// A text [`SimpleValue::from_json`] reads as a Text.
+pub fn text() -> impl Strategy<Value = String> {
+    any::<String>().prop_map(|v| {
+        if v.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("_{v}")
+        } else {
+            v
+        }
+    })
+}
+
+pub fn date_time() -> impl Strategy<Value = OffsetDateTime> {
+    (TIMESTAMPS.0..=TIMESTAMPS.1, 0..1_000_000_000_u32).prop_map(|(seconds, nanos)| {
+        OffsetDateTime::from_unix_timestamp(seconds)
+            .and_then(|v| v.replace_nanosecond(nanos))
+            .unwrap()
+    })
+}
+
+pub fn date() -> impl Strategy<Value = Date> {
+    (date!(0001 - 01 - 01).to_julian_day()..=date!(9999 - 12 - 31).to_julian_day())
+        .prop_map(|v| Date::from_julian_day(v).unwrap())
+}
+
+/// A time, with nanoseconds half of the time.
+pub fn time() -> impl Strategy<Value = Time> {
+    (
+        0..24_u8,
+        0..60_u8,
+        0..60_u8,
+        prop_oneof![Just(0), 0..1_000_000_000_u32],
+    )
+        .prop_map(|(hour, minute, second, nanos)| {
+            Time::from_hms_nano(hour, minute, second, nanos).unwrap()
+        })
+}
+
+pub fn geo_point() -> impl Strategy<Value = GeoPoint> {
+    (-90_000_000..=90_000_000_i32, -180_000_000..=180_000_000_i32)
+        .prop_map(|(lat, lon)| GeoPoint::new(lat as f64 / 1e6, lon as f64 / 1e6).unwrap())
+}
+
+fn float() -> impl Strategy<Value = f64> {
+    (any::<i32>(), 0..=6_i32)
+        .prop_map(|(mantissa, decimals)| mantissa as f64 / 10_f64.powi(decimals))
+}
+
+fn uuid() -> impl Strategy<Value = Uuid> {
+    any::<u128>().prop_map(Uuid::from_u128)
+}
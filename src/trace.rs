@@ -0,0 +1,216 @@
+//! Correlation data passed along with every request between services.
+//!
+//! The request ID is the W3C trace ID, so it's propagated with the standard `traceparent` header
+//! (`00-<trace id>-<parent span id>-<flags>`) and shows up in tracing tools as-is.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use uuid::Uuid;
+
+use crate::{
+    error::{GlobalError, Result},
+    id::{AddonInstanceUuid, WebsitePublicId},
+};
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+const SAMPLED_FLAG: u8 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceContext {
+    /// Also the W3C trace ID.
+    pub request_id: Uuid,
+    /// The span of the service which sent the request. None = Started Here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_span: Option<SpanId>,
+    #[serde(default)]
+    pub sampled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_id: Option<WebsitePublicId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addon_instance: Option<AddonInstanceUuid>,
+    /// The signed in user or member.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<Uuid>,
+}
+
+/// 8 bytes, written as 16 lowercase hex characters. Never all zeroes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpanId([u8; 8]);
+
+impl TraceContext {
+    /// The context of a request which starts here, eg. one from a browser.
+    pub fn new() -> Self {
+        Self {
+            request_id: Uuid::now_v7(),
+            parent_span: None,
+            sampled: false,
+            site_id: None,
+            addon_instance: None,
+            user_id: None,
+        }
+    }
+
+    /// Continue the trace of a received `traceparent` header.
+    ///
+    /// Versions other than `00` are read as `00` as long as they start the same way, as the
+    /// specification asks.
+    pub fn from_traceparent(header: &str) -> Result<Self> {
+        let invalid = |reason: &str| GlobalError::ParseError(format!("traceparent: {reason}"));
+
+        let mut parts = header.trim().split('-');
+
+        let (Some(version), Some(trace_id), Some(span_id), Some(flags)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("expected 4 parts"));
+        };
+
+        if version.len() != 2 || version == "ff" || !is_lower_hex(version) {
+            return Err(invalid("invalid version"));
+        }
+
+        if version == "00" && parts.next().is_some() {
+            return Err(invalid("expected 4 parts"));
+        }
+
+        let request_id = decode_hex::<16>(trace_id)
+            .map(Uuid::from_bytes)
+            .filter(|v| !v.is_nil())
+            .ok_or_else(|| invalid("invalid trace id"))?;
+
+        let parent_span = span_id.parse::<SpanId>()?;
+
+        let flags = decode_hex::<1>(flags).ok_or_else(|| invalid("invalid flags"))?[0];
+
+        Ok(Self {
+            request_id,
+            parent_span: Some(parent_span),
+            sampled: flags & SAMPLED_FLAG != 0,
+            ..Self::new()
+        })
+    }
+
+    /// The `traceparent` header for a request sent from the span.
+    pub fn traceparent(&self, span: SpanId) -> String {
+        format!(
+            "00-{}-{span}-{:02x}",
+            self.request_id.simple(),
+            if self.sampled { SAMPLED_FLAG } else { 0 }
+        )
+    }
+
+    /// The context of a request sent from the span to another service.
+    pub fn child(&self, span: SpanId) -> Self {
+        Self {
+            parent_span: Some(span),
+            ..*self
+        }
+    }
+
+    pub fn with_site(mut self, site_id: WebsitePublicId) -> Self {
+        self.site_id = Some(site_id);
+        self
+    }
+
+    pub fn with_addon_instance(mut self, addon_instance: AddonInstanceUuid) -> Self {
+        self.addon_instance = Some(addon_instance);
+        self
+    }
+
+    pub fn with_user(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpanId {
+    /// A new span ID, from the random part of a UUIDv7.
+    pub fn new() -> Self {
+        let bytes = Uuid::now_v7().into_bytes();
+        let mut id = [0; 8];
+        id.copy_from_slice(&bytes[8..]);
+
+        Self(id)
+    }
+
+    pub fn from_bytes(bytes: [u8; 8]) -> Result<Self> {
+        if bytes == [0; 8] {
+            return Err(GlobalError::ParseError(String::from(
+                "Span ID can't be all zeroes",
+            )));
+        }
+
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+}
+
+impl Default for SpanId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for SpanId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl FromStr for SpanId {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes =
+            decode_hex::<8>(s).ok_or_else(|| GlobalError::ParseError(format!("Span ID: {s}")))?;
+
+        Self::from_bytes(bytes)
+    }
+}
+
+impl Serialize for SpanId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpanId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+fn is_lower_hex(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|v| v.is_ascii_digit() || (b'a'..=b'f').contains(&v))
+}
+
+/// Exactly `N` bytes of lowercase hex.
+fn decode_hex<const N: usize>(value: &str) -> Option<[u8; N]> {
+    if value.len() != N * 2 || !is_lower_hex(value) {
+        return None;
+    }
+
+    let mut bytes = [0; N];
+    hex::decode_to_slice(value, &mut bytes).ok()?;
+
+    Some(bytes)
+}
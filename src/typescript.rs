@@ -0,0 +1,157 @@
+//! TypeScript definitions of the wire types, as ts-rs [`TS`] impls.
+//!
+//! Most types derive them. The few serde reads and writes by hand, eg. untagged values, are
+//! declared here. [`bundle`] collects every request, response and schema type with what they
+//! use into a single `.d.ts`, which the `ts-bindings` binary writes:
+//!
+//! `cargo run --features ts --bin ts-bindings -- webby.d.ts`
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use ts_rs::{TS, TypeVisitor};
+
+use crate::{
+    filter::{Filter, FilterExpr},
+    request::{
+        Aggregation, CmsAggregate, CmsBulkInsert, CmsBulkRemove, CmsBulkUpdate, CmsCreate,
+        CmsPurgeRequest, CmsQuery, CmsRestoreRequest, CmsUpdate, CmsUpdateDataCell,
+        CmsUpdateDataRow, CursorQuery, FieldPatch, TrashQuery,
+    },
+    response::{
+        AddonInstallResponse, ApiErrorResponse, BasicCmsInfo, BulkOperationResponse,
+        CmsAggregateResponse, CmsCreateResponse, CmsResponse, CmsRowResponse, ListResponse,
+        PublicSchema, SingleValue, WrappingResponse,
+    },
+    schema::{Schematic, SchematicField, SchematicFieldValue, field_map::OrderedFieldMap},
+    value::{Number, SimpleValue},
+};
+
+/// Declare a type as `type $name = $ts;`, visiting the types the declaration names.
+macro_rules! declare {
+    ($($type:ident => $ts:literal $(, [$($dependency:ty),+])?;)+) => {$(
+        impl TS for $type {
+            type WithoutGenerics = Self;
+            type OptionInnerType = Self;
+
+            fn name() -> String {
+                stringify!($type).to_owned()
+            }
+
+            fn decl() -> String {
+                format!("type {} = {};", Self::name(), Self::inline())
+            }
+
+            fn decl_concrete() -> String {
+                Self::decl()
+            }
+
+            fn inline() -> String {
+                $ts.to_owned()
+            }
+
+            fn inline_flattened() -> String {
+                panic!("{} cannot be flattened", Self::name())
+            }
+
+            fn visit_dependencies(_visitor: &mut impl TypeVisitor)
+            where
+                Self: 'static,
+            {
+                $($(_visitor.visit::<$dependency>();)+)?
+            }
+
+            fn output_path() -> Option<PathBuf> {
+                Some(PathBuf::from(concat!(stringify!($type), ".ts")))
+            }
+        }
+    )+};
+}
+
+declare! {
+    Number => "number";
+    OrderedFieldMap => "{ [key in SchematicFieldKey]?: SchematicField }", [SchematicField];
+    SimpleValue => "string | number | boolean | Array<string> | Array<number> | Array<JsonValue> \
+        | { [key in string]?: JsonValue }", [serde_json::Value];
+    FilterExpr => "Filter | { and: Array<FilterExpr> } | { or: Array<FilterExpr> } \
+        | { not: FilterExpr } | Array<FilterExpr>", [Filter];
+}
+
+/// Every request, response and schema type, with the types they use, sorted by name.
+pub fn bundle() -> String {
+    let mut declarations = Declarations::default();
+
+    macro_rules! visit {
+        ($($type:ty),+ $(,)?) => {$(
+            declarations.visit::<$type>();
+        )+};
+    }
+
+    visit!(
+        CmsCreate,
+        CmsUpdate,
+        CmsQuery,
+        CursorQuery,
+        CmsAggregate,
+        Aggregation,
+        CmsBulkInsert,
+        CmsBulkUpdate,
+        CmsBulkRemove,
+        CmsRestoreRequest,
+        CmsPurgeRequest,
+        TrashQuery,
+        CmsUpdateDataCell,
+        CmsUpdateDataRow,
+        FieldPatch,
+        FilterExpr,
+        AddonInstallResponse,
+        CmsCreateResponse,
+        CmsResponse,
+        CmsRowResponse,
+        CmsAggregateResponse,
+        BasicCmsInfo,
+        PublicSchema,
+        ListResponse<CmsRowResponse>,
+        BulkOperationResponse,
+        SingleValue<SimpleValue>,
+        WrappingResponse<CmsRowResponse>,
+        ApiErrorResponse,
+        Schematic,
+        SchematicFieldValue,
+    );
+
+    let mut bundle = String::from(
+        "// Generated by the `ts-bindings` binary of webby-global-common. Don't edit.\n",
+    );
+
+    for declaration in declarations.0.into_values() {
+        bundle.push('\n');
+        bundle.push_str(&declaration);
+        bundle.push('\n');
+    }
+
+    bundle
+}
+
+/// The declarations of the visited types by name. Types which are only inlined, eg. `string`
+/// or a `Vec`, have none.
+#[derive(Default)]
+struct Declarations(BTreeMap<String, String>);
+
+impl TypeVisitor for Declarations {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if T::output_path().is_none() || self.0.contains_key(&T::ident()) {
+            T::visit_generics(self);
+            return;
+        }
+
+        let mut declaration = T::docs().unwrap_or_default();
+        declaration.push_str("export ");
+        // serde_json writes 64 bit integers as JSON numbers, which parse as a `number`.
+        declaration.push_str(&T::decl().replace("bigint", "number"));
+
+        self.0.insert(T::ident(), declaration);
+
+        T::visit_dependencies(self);
+        T::visit_generics(self);
+    }
+}
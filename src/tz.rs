@@ -1,27 +1,155 @@
-use std::sync::LazyLock;
+//! IANA time zones and their offsets.
+//!
+//! With the `tz-embedded` feature (on by default) the bundled `TimeZones-2024b.json` is used.
+//! Otherwise load the data at runtime with [`ZoneContainer::from_path`] and [`set_database`].
+
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    sync::OnceLock,
+};
 
 use serde::{Deserialize, Deserializer};
-use time::{macros::format_description, UtcOffset};
+use serde_json::value::RawValue;
+use time::{
+    Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, macros::format_description,
+};
+
+use crate::error::Result;
+
+static TZ_DATABASE: OnceLock<ZoneContainer> = OnceLock::new();
 
-static TZ_DATABASE: LazyLock<ZoneContainer> = LazyLock::new(|| {
-    let data = include_str!("../TimeZones-2024b.json");
-    serde_json::from_str(data).unwrap()
-});
+/// Use the zones instead of the bundled ones.
+///
+/// Has to be called before any zone is looked up. Returns the zones back if the database
+/// is already set.
+pub fn set_database(zones: ZoneContainer) -> std::result::Result<(), ZoneContainer> {
+    TZ_DATABASE.set(zones)
+}
+
+/// The zones in use. None if the `tz-embedded` feature is off and nothing was set.
+pub fn database() -> Option<&'static ZoneContainer> {
+    #[cfg(feature = "tz-embedded")]
+    {
+        Some(TZ_DATABASE.get_or_init(|| {
+            let data = include_str!("../TimeZones-2024b.json");
+            serde_json::from_str(data).unwrap()
+        }))
+    }
 
+    #[cfg(not(feature = "tz-embedded"))]
+    {
+        TZ_DATABASE.get()
+    }
+}
+
+pub fn find_zone_by_id(id: &str) -> Option<&'static Zone> {
+    database()?.find(id)
+}
+
+/// The lowest offset the zone has ever used. See [`find_offset_at`] for the actual offset.
 pub fn find_offset_by_id(id: &str) -> Option<UtcOffset> {
-    TZ_DATABASE
-        .zones
-        .iter()
-        .find(|z| z.id == id || z.aliases.iter().any(|a| a == id))
-        .map(|z| z.offsets[0])
+    find_zone_by_id(id).map(|z| z.offsets[0])
+}
+
+/// The offset in use in the zone at the instant, following daylight saving time.
+pub fn find_offset_at(id: &str, at: OffsetDateTime) -> Option<UtcOffset> {
+    find_zone_by_id(id).map(|z| z.offset_at(at))
 }
 
+/// The offset of a local date & time in the zone. See [`Zone::local_offset`].
+pub fn find_local_offset(id: &str, local: PrimitiveDateTime) -> Option<UtcOffset> {
+    find_zone_by_id(id).map(|z| z.local_offset(local))
+}
+
+/// Every zone sorted by id, for time zone pickers.
+pub fn list_zones() -> Vec<&'static Zone> {
+    database().map(|v| v.zones()).unwrap_or_default()
+}
+
+/// A time zone database.
+///
+/// Only the ids and aliases are read up front, each zone is parsed the first time it's used.
+/// Zones which fail to parse are treated as missing.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ZoneContainer {
-    // iana_version: String,
+    iana_version: String,
     // full_version_id: String,
-    zones: Vec<Zone>,
+    zones: Vec<LazyZone>,
+}
+
+impl ZoneContainer {
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Eg. `2024b`
+    pub fn iana_version(&self) -> &str {
+        &self.iana_version
+    }
+
+    /// Find a zone by its id or one of its aliases.
+    pub fn find(&self, id: &str) -> Option<&Zone> {
+        self.zones
+            .iter()
+            .find(|z| z.key.id == id || z.key.aliases.iter().any(|a| a == id))
+            .and_then(LazyZone::get)
+    }
+
+    /// Every zone sorted by id.
+    pub fn zones(&self) -> Vec<&Zone> {
+        let mut zones = self
+            .zones
+            .iter()
+            .filter_map(LazyZone::get)
+            .collect::<Vec<_>>();
+
+        zones.sort_by(|a, b| a.id.cmp(&b.id));
+
+        zones
+    }
+}
+
+#[derive(Debug)]
+struct LazyZone {
+    key: ZoneKey,
+    raw: Box<RawValue>,
+    parsed: OnceLock<Option<Zone>>,
+}
+
+impl LazyZone {
+    fn get(&self) -> Option<&Zone> {
+        self.parsed
+            .get_or_init(|| serde_json::from_str(self.raw.get()).ok())
+            .as_ref()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ZoneKey {
+    id: String,
+    aliases: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for LazyZone {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+
+        Ok(Self {
+            key: serde_json::from_str(raw.get()).map_err(serde::de::Error::custom)?,
+            raw,
+            parsed: OnceLock::new(),
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,27 +157,351 @@ pub struct ZoneContainer {
 pub struct Zone {
     id: String,
     aliases: Vec<String>,
-    // location
+    /// None for zones which aren't tied to a place. Eg. `Etc/UTC`
+    location: Option<ZoneLocation>,
     #[serde(deserialize_with = "from_str_to_utc_offset")]
     offsets: Vec<UtcOffset>,
-    // current_offset: String,
-    // nextTransition
+    /// The offset and abbreviation when the data was generated. Eg. `-05 (EST)`
+    #[serde(deserialize_with = "from_str_to_current_offset")]
+    current_offset: (UtcOffset, String),
+    // The bundled data has no transitions (`nextTransition` is always empty)
+    // so daylight saving time follows DST_RULES instead.
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneLocation {
+    pub country_code: String,
+    pub country_name: String,
+    pub comment: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Zone {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    pub fn location(&self) -> Option<&ZoneLocation> {
+        self.location.as_ref()
+    }
+
+    /// Every offset the zone has used.
+    pub fn offsets(&self) -> &[UtcOffset] {
+        &self.offsets
+    }
+
+    /// The abbreviation when the data was generated. Eg. `EST`
+    pub fn abbreviation(&self) -> &str {
+        &self.current_offset.1
+    }
+
+    pub fn dst_rule(&self) -> Option<DstRule> {
+        self.dst_entry().map(|(rule, _)| rule)
+    }
+
+    /// The offset outside of daylight saving time.
+    pub fn standard_offset(&self) -> UtcOffset {
+        match self.dst_entry() {
+            Some((_, offset)) => offset,
+            None => self.current_offset.0,
+        }
+    }
+
+    pub fn offset_at(&self, at: OffsetDateTime) -> UtcOffset {
+        let standard = self.standard_offset();
+
+        match self.dst_rule() {
+            Some(rule) if rule.is_daylight(standard, at) => daylight(standard),
+            _ => standard,
+        }
+    }
+
+    /// The offset of a local date & time.
+    ///
+    /// Local times skipped when the clocks go forward are treated as daylight time and
+    /// local times repeated when they go back as standard time.
+    pub fn local_offset(&self, local: PrimitiveDateTime) -> UtcOffset {
+        self.offset_at(local.assume_offset(self.standard_offset()))
+    }
+
+    fn dst_entry(&self) -> Option<(DstRule, UtcOffset)> {
+        DST_RULES.iter().find_map(|(rule, offset, zones)| {
+            zones.contains(&self.id.as_str()).then(|| {
+                let (hours, minutes) = *offset;
+
+                (*rule, UtcOffset::from_hms(hours, minutes, 0).unwrap())
+            })
+        })
+    }
+}
+
+/// The daylight saving time rules currently in use. Daylight time is one hour ahead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DstRule {
+    /// Second Sunday of March 02:00 to first Sunday of November 02:00 local time.
+    NorthAmerica,
+    /// Last Sunday of March to last Sunday of October, 01:00 UTC.
+    Europe,
+    /// First Sunday of October 02:00 to first Sunday of April 03:00 local time.
+    Australia,
+    /// Last Sunday of September 02:00 to first Sunday of April 03:00 local time.
+    NewZealand,
+}
+
+impl DstRule {
+    pub fn is_daylight(self, standard: UtcOffset, at: OffsetDateTime) -> bool {
+        let year = at.to_offset(standard).year();
+        let dst = daylight(standard);
+
+        let (start, end) = match self {
+            Self::NorthAmerica => (
+                local(nth_sunday(year, Month::March, 2), 2, standard),
+                local(nth_sunday(year, Month::November, 1), 2, dst),
+            ),
+            Self::Europe => (
+                local(last_sunday(year, Month::March), 1, UtcOffset::UTC),
+                local(last_sunday(year, Month::October), 1, UtcOffset::UTC),
+            ),
+            Self::Australia => (
+                local(nth_sunday(year, Month::October, 1), 2, standard),
+                local(nth_sunday(year, Month::April, 1), 3, dst),
+            ),
+            Self::NewZealand => (
+                local(last_sunday(year, Month::September), 2, standard),
+                local(nth_sunday(year, Month::April, 1), 3, dst),
+            ),
+        };
+
+        if start < end {
+            start <= at && at < end
+        } else {
+            // Southern hemisphere, daylight time spans the new year.
+            at >= start || at < end
+        }
+    }
 }
 
-fn from_str_to_utc_offset<'de, D>(v: D) -> Result<Vec<UtcOffset>, D::Error>
+type DstRuleEntry = (DstRule, (i8, i8), &'static [&'static str]);
+
+/// Zones which currently observe daylight saving time, grouped by rule and standard offset.
+const DST_RULES: &[DstRuleEntry] = &[
+    (DstRule::NorthAmerica, (-10, 0), &["America/Adak"]),
+    (
+        DstRule::NorthAmerica,
+        (-9, 0),
+        &[
+            "America/Anchorage",
+            "America/Juneau",
+            "America/Metlakatla",
+            "America/Nome",
+            "America/Sitka",
+            "America/Yakutat",
+        ],
+    ),
+    (
+        DstRule::NorthAmerica,
+        (-8, 0),
+        &[
+            "America/Los_Angeles",
+            "America/Tijuana",
+            "America/Vancouver",
+        ],
+    ),
+    (
+        DstRule::NorthAmerica,
+        (-7, 0),
+        &[
+            "America/Boise",
+            "America/Cambridge_Bay",
+            "America/Ciudad_Juarez",
+            "America/Denver",
+            "America/Edmonton",
+            "America/Inuvik",
+        ],
+    ),
+    (
+        DstRule::NorthAmerica,
+        (-6, 0),
+        &[
+            "America/Chicago",
+            "America/Indiana/Knox",
+            "America/Indiana/Tell_City",
+            "America/Matamoros",
+            "America/Menominee",
+            "America/North_Dakota/Beulah",
+            "America/North_Dakota/Center",
+            "America/North_Dakota/New_Salem",
+            "America/Ojinaga",
+            "America/Rankin_Inlet",
+            "America/Resolute",
+            "America/Winnipeg",
+        ],
+    ),
+    (
+        DstRule::NorthAmerica,
+        (-5, 0),
+        &[
+            "America/Detroit",
+            "America/Grand_Turk",
+            "America/Indiana/Indianapolis",
+            "America/Indiana/Marengo",
+            "America/Indiana/Petersburg",
+            "America/Indiana/Vevay",
+            "America/Indiana/Vincennes",
+            "America/Indiana/Winamac",
+            "America/Iqaluit",
+            "America/Kentucky/Louisville",
+            "America/Kentucky/Monticello",
+            "America/New_York",
+            "America/Port-au-Prince",
+            "America/Toronto",
+        ],
+    ),
+    (
+        DstRule::NorthAmerica,
+        (-4, 0),
+        &[
+            "America/Glace_Bay",
+            "America/Goose_Bay",
+            "America/Halifax",
+            "America/Moncton",
+            "America/Thule",
+            "Atlantic/Bermuda",
+        ],
+    ),
+    (DstRule::NorthAmerica, (-3, -30), &["America/St_Johns"]),
+    (DstRule::NorthAmerica, (-3, 0), &["America/Miquelon"]),
+    (DstRule::Europe, (-1, 0), &["Atlantic/Azores"]),
+    (
+        DstRule::Europe,
+        (0, 0),
+        &[
+            "Atlantic/Canary",
+            "Atlantic/Faroe",
+            "Atlantic/Madeira",
+            "Europe/Dublin",
+            "Europe/Lisbon",
+            "Europe/London",
+        ],
+    ),
+    (
+        DstRule::Europe,
+        (1, 0),
+        &[
+            "Africa/Ceuta",
+            "Europe/Andorra",
+            "Europe/Belgrade",
+            "Europe/Berlin",
+            "Europe/Brussels",
+            "Europe/Budapest",
+            "Europe/Gibraltar",
+            "Europe/Madrid",
+            "Europe/Malta",
+            "Europe/Paris",
+            "Europe/Prague",
+            "Europe/Rome",
+            "Europe/Tirane",
+            "Europe/Vienna",
+            "Europe/Warsaw",
+            "Europe/Zurich",
+        ],
+    ),
+    (
+        DstRule::Europe,
+        (2, 0),
+        &[
+            "Asia/Famagusta",
+            "Asia/Nicosia",
+            "Europe/Athens",
+            "Europe/Bucharest",
+            "Europe/Chisinau",
+            "Europe/Helsinki",
+            "Europe/Kyiv",
+            "Europe/Riga",
+            "Europe/Sofia",
+            "Europe/Tallinn",
+            "Europe/Vilnius",
+        ],
+    ),
+    (
+        DstRule::Australia,
+        (9, 30),
+        &["Australia/Adelaide", "Australia/Broken_Hill"],
+    ),
+    (
+        DstRule::Australia,
+        (10, 0),
+        &[
+            "Antarctica/Macquarie",
+            "Australia/Hobart",
+            "Australia/Melbourne",
+            "Australia/Sydney",
+        ],
+    ),
+    (DstRule::NewZealand, (12, 0), &["Pacific/Auckland"]),
+];
+
+fn daylight(standard: UtcOffset) -> UtcOffset {
+    UtcOffset::from_whole_seconds(standard.whole_seconds() + 3600).unwrap()
+}
+
+fn local(date: Date, hour: u8, offset: UtcOffset) -> OffsetDateTime {
+    PrimitiveDateTime::new(date, Time::from_hms(hour, 0, 0).unwrap()).assume_offset(offset)
+}
+
+/// Days 1..=7 include exactly one Sunday, so the nth is found from the first.
+fn nth_sunday(year: i32, month: Month, nth: u8) -> Date {
+    let first = Date::from_calendar_date(year, month, 1).unwrap();
+    let days = (7 - first.weekday().number_days_from_sunday()) % 7;
+
+    first + time::Duration::days(days as i64 + 7 * (nth as i64 - 1))
+}
+
+fn last_sunday(year: i32, month: Month) -> Date {
+    let last = Date::from_calendar_date(year, month, month.length(year)).unwrap();
+
+    last - time::Duration::days(last.weekday().number_days_from_sunday() as i64)
+}
+
+fn from_str_to_utc_offset<'de, D>(v: D) -> std::result::Result<Vec<UtcOffset>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let v: Vec<String> = Deserialize::deserialize(v)?;
 
+    v.iter().map(|s| parse_offset::<D>(s)).collect()
+}
+
+fn from_str_to_current_offset<'de, D>(v: D) -> std::result::Result<(UtcOffset, String), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: String = Deserialize::deserialize(v)?;
+
+    let (offset, abbreviation) = v.split_once(' ').unwrap_or((&v, ""));
+
+    Ok((
+        parse_offset::<D>(offset)?,
+        abbreviation
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .to_string(),
+    ))
+}
+
+fn parse_offset<'de, D: Deserializer<'de>>(
+    value: &str,
+) -> std::result::Result<UtcOffset, D::Error> {
     let format = format_description!("[offset_hour]:[offset_minute]");
     let format_hour = format_description!("[offset_hour]");
 
-    Ok(v.iter()
-        .map(|s| {
-            UtcOffset::parse(s, format)
-                .or_else(|_| UtcOffset::parse(s, format_hour))
-                .unwrap()
-        })
-        .collect())
+    UtcOffset::parse(value, format)
+        .or_else(|_| UtcOffset::parse(value, format_hour))
+        .map_err(serde::de::Error::custom)
 }
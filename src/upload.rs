@@ -1,9 +1,26 @@
-use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use time::OffsetDateTime;
+use uuid::Uuid;
+
+use self::mime::{MediaKind, MimeType};
+use crate::{
+    error::{GlobalError, Result},
+    value::SimpleValue,
+};
+
+pub mod mime;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct WebsiteUpload {
     // TODO: This shouldn't be a reference to the b2 store path
+    #[serde(alias = "public_id")]
     pub public_id: String,
 
     /// Where the upload is from.
@@ -12,16 +29,85 @@ pub struct WebsiteUpload {
     /// Some() = Addon
     pub namespace: Option<String>,
 
+    #[serde(alias = "upload_type")]
     pub upload_type: String,
+    #[serde(alias = "display_name")]
     pub display_name: String,
+    #[serde(alias = "created_at")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub created_at: OffsetDateTime,
+    #[serde(alias = "deleted_at")]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
     pub deleted_at: Option<OffsetDateTime>,
     pub media: Option<WebsiteUploadFile>,
+    #[serde(alias = "using_variant")]
     pub using_variant: Option<WebsiteUploadVariant>,
+    /// Every resized / converted version of the upload.
+    #[serde(default)]
+    pub variants: Vec<WebsiteUploadVariant>,
+}
+
+impl WebsiteUpload {
+    /// The variant to serve for a box of `width` x `height` (0 = Any).
+    ///
+    /// The first format of `format_preference` which has variants is used, falling back to
+    /// every variant if none match. Of those the smallest variant covering the box is picked,
+    /// or the largest one if none are big enough.
+    pub fn best_variant_for(
+        &self,
+        width: i32,
+        height: i32,
+        format_preference: &[&str],
+    ) -> Option<&WebsiteUploadVariant> {
+        let candidates = format_preference
+            .iter()
+            .map(|format| self.variants_of(format))
+            .find(|v| !v.is_empty())
+            .unwrap_or_else(|| self.variants.iter().collect());
+
+        let area = |v: &WebsiteUploadVariant| (v.width as i64 * v.height as i64, v.size);
+
+        candidates
+            .iter()
+            .copied()
+            .filter(|v| v.width >= width && v.height >= height)
+            .min_by_key(|v| area(v))
+            .or_else(|| candidates.iter().copied().max_by_key(|v| area(v)))
+    }
+
+    /// The `srcset` attribute for the variants of a format, eg. `a.webp 640w, b.webp 1280w`.
+    ///
+    /// Variants with the same width are only listed once, the smallest file wins.
+    pub fn srcset<F: Fn(&WebsiteUploadVariant) -> String>(
+        &self,
+        file_type: &str,
+        url_for: F,
+    ) -> String {
+        let mut variants = self.variants_of(file_type);
+
+        variants.sort_by_key(|v| (v.width, v.size));
+        variants.dedup_by_key(|v| v.width);
+
+        variants
+            .into_iter()
+            .map(|v| format!("{} {}w", url_for(v), v.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn variants_of(&self, file_type: &str) -> Vec<&WebsiteUploadVariant> {
+        self.variants
+            .iter()
+            .filter(|v| v.file_type.eq_ignore_ascii_case(file_type))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct WebsiteUploadVariant {
+    #[serde(alias = "file_type")]
     pub file_type: String,
     pub size: i64,
 
@@ -31,16 +117,345 @@ pub struct WebsiteUploadVariant {
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
 pub struct WebsiteUploadFile {
+    #[serde(alias = "file_size")]
     pub file_size: i64,
+    #[serde(alias = "file_type")]
     pub file_type: String,
 
+    #[serde(alias = "media_width")]
     pub media_width: Option<i32>,
+    #[serde(alias = "media_height")]
     pub media_height: Option<i32>,
+    #[serde(alias = "media_duration")]
     pub media_duration: Option<i32>,
 
+    #[serde(alias = "is_editable")]
     pub is_editable: bool,
+    #[serde(alias = "has_thumbnail")]
     pub has_thumbnail: bool,
 
+    #[serde(alias = "is_global")]
     pub is_global: bool,
 }
+
+impl WebsiteUploadFile {
+    pub fn mime_type(&self) -> MimeType {
+        MimeType::parse(&self.file_type)
+    }
+}
+
+/// An upload in a Media Gallery field, with the metadata the gallery editor sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct MediaGalleryItem {
+    pub upload_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+    /// Position in the gallery, lowest first.
+    #[serde(default)]
+    pub order: u32,
+}
+
+impl MediaGalleryItem {
+    pub fn new(upload_id: Uuid, order: u32) -> Self {
+        Self {
+            upload_id,
+            caption: None,
+            alt_text: None,
+            order,
+        }
+    }
+
+    /// Read a gallery sorted by `order`.
+    ///
+    /// Accepts an array of items, or a String List / array of upload ids which galleries used
+    /// to be stored as. Ids are ordered by their position.
+    pub fn list_from_value(value: &SimpleValue) -> Result<Vec<Self>> {
+        let mut items = match value {
+            SimpleValue::ListString(ids) => ids
+                .iter()
+                .zip(0..)
+                .map(|(id, order)| Ok(Self::new(id.parse()?, order)))
+                .collect::<Result<Vec<_>>>()?,
+            SimpleValue::ArrayUnknown(values) => values
+                .iter()
+                .zip(0..)
+                .map(|(value, order)| {
+                    Ok(match value {
+                        serde_json::Value::String(id) => Self::new(id.parse()?, order),
+                        v => serde_json::from_value(v.clone())?,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+            v => return Err(GlobalError::type_mismatch("Media Gallery", v.type_name())),
+        };
+
+        items.sort_by_key(|v| v.order);
+
+        Ok(items)
+    }
+}
+
+/// How an image is resized when both a width and height are given.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResizeMode {
+    /// Fit inside the box, keeping the aspect ratio.
+    #[default]
+    Fit,
+    /// Cover the box, keeping the aspect ratio and cropping the rest.
+    Cover,
+    /// Fit inside the box and pad the rest.
+    Pad,
+    /// Stretch to exactly the box.
+    Stretch,
+}
+
+impl ResizeMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fit => "fit",
+            Self::Cover => "cover",
+            Self::Pad => "pad",
+            Self::Stretch => "stretch",
+        }
+    }
+}
+
+/// A rectangle of the original image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The processing to apply to an image before serving it.
+///
+/// Equal transforms give the same [`ImageTransform::to_query_string`], and parsing normalizes
+/// aliases, so the query string of a parsed transform can be used as a cache key.
+/// Deserializing [validates](ImageTransform::validate) like parsing does.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(remote = "Self", rename_all = "camelCase")]
+pub struct ImageTransform {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub resize: ResizeMode,
+    /// Applied before resizing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<CropRect>,
+    /// 1 - 100. None = Default of the format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    /// Image type to convert to. None = Keep
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<MimeType>,
+    /// Clockwise degrees: 0, 90, 180 or 270.
+    #[serde(default)]
+    pub rotation: u16,
+}
+
+impl ImageTransform {
+    /// Whether the original image would be served as is.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check every value is one [`ImageTransform::parse_query`] accepts.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(GlobalError::ParseError(format!("Image Transform: {msg}")));
+
+        if self.width == Some(0) || self.height == Some(0) {
+            return invalid("dimensions can't be 0");
+        }
+
+        if self.crop.is_some_and(|v| v.width == 0 || v.height == 0) {
+            return invalid("crop can't be empty");
+        }
+
+        if self.quality.is_some_and(|v| !(1..=100).contains(&v)) {
+            return invalid("quality has to be between 1 and 100");
+        }
+
+        if self
+            .format
+            .as_ref()
+            .is_some_and(|v| v.kind() != Some(MediaKind::Image) || v.extensions().is_empty())
+        {
+            return invalid("the format has to be a known image format");
+        }
+
+        if !self.rotation.is_multiple_of(90) || self.rotation >= 360 {
+            return invalid("rotation has to be 0, 90, 180 or 270");
+        }
+
+        Ok(())
+    }
+
+    /// Eg. `fit=cover&fmt=webp&h=480&w=640`
+    ///
+    /// Keys are sorted and default values left out. Formats without an extension
+    /// ([`MimeType::Other`]) are left out too, [`ImageTransform::validate`] rejects them.
+    pub fn to_query_string(&self) -> String {
+        let mut pairs = Vec::new();
+
+        if let Some(crop) = self.crop {
+            pairs.push(format!(
+                "crop={},{},{},{}",
+                crop.x, crop.y, crop.width, crop.height
+            ));
+        }
+
+        if self.resize != ResizeMode::Fit {
+            pairs.push(format!("fit={}", self.resize.as_str()));
+        }
+
+        if let Some(extension) = self.format.as_ref().and_then(|v| v.extensions().first()) {
+            pairs.push(format!("fmt={extension}"));
+        }
+
+        if let Some(height) = self.height {
+            pairs.push(format!("h={height}"));
+        }
+
+        if let Some(quality) = self.quality {
+            pairs.push(format!("q={quality}"));
+        }
+
+        if !self.rotation.is_multiple_of(360) {
+            pairs.push(format!("rot={}", self.rotation % 360));
+        }
+
+        if let Some(width) = self.width {
+            pairs.push(format!("w={width}"));
+        }
+
+        pairs.join("&")
+    }
+
+    /// Parse a query string in any key order, with or without the leading `?`.
+    ///
+    /// Fails on unknown or repeated keys and invalid values. Aliases are normalized, eg.
+    /// `fmt=jpeg` into `fmt=jpg` and `rot=-90` or `rot=630` into `rot=270`.
+    pub fn parse_query(query: &str) -> Result<Self> {
+        let invalid = |msg: String| GlobalError::ParseError(format!("Image Transform: {msg}"));
+
+        let mut transform = Self::default();
+        let mut seen = Vec::new();
+
+        for pair in query
+            .trim_start_matches('?')
+            .split('&')
+            .filter(|v| !v.is_empty())
+        {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("{pair} is missing a value")))?;
+
+            if seen.contains(&key) {
+                return Err(invalid(format!("{key} is repeated")));
+            }
+
+            seen.push(key);
+
+            match key {
+                "w" => transform.width = Some(parse_dimension(value)?),
+                "h" => transform.height = Some(parse_dimension(value)?),
+                "fit" => {
+                    transform.resize = match value {
+                        "fit" => ResizeMode::Fit,
+                        "cover" => ResizeMode::Cover,
+                        "pad" => ResizeMode::Pad,
+                        "stretch" => ResizeMode::Stretch,
+                        v => return Err(invalid(format!("unknown resize mode {v}"))),
+                    }
+                }
+                "crop" => {
+                    let parts = value
+                        .split(',')
+                        .map(|v| v.parse::<u32>())
+                        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                    let [x, y, width, height] = parts[..] else {
+                        return Err(invalid(String::from("crop needs x,y,width,height")));
+                    };
+
+                    if width == 0 || height == 0 {
+                        return Err(invalid(String::from("crop can't be empty")));
+                    }
+
+                    transform.crop = Some(CropRect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    });
+                }
+                "q" => match value.parse::<u8>()? {
+                    v @ 1..=100 => transform.quality = Some(v),
+                    v => return Err(invalid(format!("quality {v} isn't between 1 and 100"))),
+                },
+                "fmt" => match MimeType::from_extension(value) {
+                    Some(v) if v.kind() == Some(MediaKind::Image) => transform.format = Some(v),
+                    _ => return Err(invalid(format!("unknown image format {value}"))),
+                },
+                "rot" => match value.parse::<i32>()?.rem_euclid(360) {
+                    v @ (0 | 90 | 180 | 270) => transform.rotation = v as u16,
+                    _ => return Err(invalid(String::from("rotation has to be a multiple of 90"))),
+                },
+                v => return Err(invalid(format!("unknown key {v}"))),
+            }
+        }
+
+        Ok(transform)
+    }
+}
+
+fn parse_dimension(value: &str) -> Result<u32> {
+    match value.parse()? {
+        0 => Err(GlobalError::ParseError(String::from(
+            "Image Transform: dimensions can't be 0",
+        ))),
+        v => Ok(v),
+    }
+}
+
+impl Serialize for ImageTransform {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageTransform {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let transform = Self::deserialize(deserializer)?;
+
+        transform.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(transform)
+    }
+}
+
+impl Display for ImageTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_query_string())
+    }
+}
+
+impl FromStr for ImageTransform {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_query(s)
+    }
+}
@@ -0,0 +1,407 @@
+//! MIME types of uploads and which fields accept them.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{GlobalError, Result},
+    schema::SchematicFieldType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MediaKind {
+    Image,
+    Video,
+    Audio,
+    Document,
+}
+
+/// The MIME types uploads are expected to have. Anything else is kept as [`MimeType::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum MimeType {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Avif,
+    Heic,
+    Svg,
+    Bmp,
+    Ico,
+    Tiff,
+
+    Mp4,
+    Webm,
+    Quicktime,
+
+    Mp3,
+    M4a,
+    Wav,
+    Ogg,
+    Flac,
+    Aac,
+
+    Pdf,
+    Doc,
+    Docx,
+    Xls,
+    Xlsx,
+    Ppt,
+    Pptx,
+    Odt,
+    Rtf,
+    PlainText,
+    Csv,
+
+    Zip,
+    Json,
+
+    /// Lowercased and without parameters.
+    Other(String),
+}
+
+/// Every type except [`MimeType::Other`], with its essence and extensions (preferred first).
+const KNOWN: &[(MimeType, &str, &[&str])] = &[
+    (MimeType::Jpeg, "image/jpeg", &["jpg", "jpeg", "jfif"]),
+    (MimeType::Png, "image/png", &["png"]),
+    (MimeType::Gif, "image/gif", &["gif"]),
+    (MimeType::Webp, "image/webp", &["webp"]),
+    (MimeType::Avif, "image/avif", &["avif"]),
+    (MimeType::Heic, "image/heic", &["heic", "heif"]),
+    (MimeType::Svg, "image/svg+xml", &["svg"]),
+    (MimeType::Bmp, "image/bmp", &["bmp"]),
+    (MimeType::Ico, "image/x-icon", &["ico"]),
+    (MimeType::Tiff, "image/tiff", &["tif", "tiff"]),
+    (MimeType::Mp4, "video/mp4", &["mp4", "m4v"]),
+    (MimeType::Webm, "video/webm", &["webm"]),
+    (MimeType::Quicktime, "video/quicktime", &["mov"]),
+    (MimeType::Mp3, "audio/mpeg", &["mp3"]),
+    (MimeType::M4a, "audio/mp4", &["m4a"]),
+    (MimeType::Wav, "audio/wav", &["wav"]),
+    (MimeType::Ogg, "audio/ogg", &["ogg", "oga", "opus"]),
+    (MimeType::Flac, "audio/flac", &["flac"]),
+    (MimeType::Aac, "audio/aac", &["aac"]),
+    (MimeType::Pdf, "application/pdf", &["pdf"]),
+    (MimeType::Doc, "application/msword", &["doc"]),
+    (
+        MimeType::Docx,
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        &["docx"],
+    ),
+    (MimeType::Xls, "application/vnd.ms-excel", &["xls"]),
+    (
+        MimeType::Xlsx,
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        &["xlsx"],
+    ),
+    (MimeType::Ppt, "application/vnd.ms-powerpoint", &["ppt"]),
+    (
+        MimeType::Pptx,
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        &["pptx"],
+    ),
+    (
+        MimeType::Odt,
+        "application/vnd.oasis.opendocument.text",
+        &["odt"],
+    ),
+    (MimeType::Rtf, "application/rtf", &["rtf"]),
+    (MimeType::PlainText, "text/plain", &["txt"]),
+    (MimeType::Csv, "text/csv", &["csv"]),
+    (MimeType::Zip, "application/zip", &["zip"]),
+    (MimeType::Json, "application/json", &["json"]),
+];
+
+/// Other names in use for the known types.
+const ALIASES: &[(&str, MimeType)] = &[
+    ("image/jpg", MimeType::Jpeg),
+    ("image/pjpeg", MimeType::Jpeg),
+    ("image/heif", MimeType::Heic),
+    ("image/vnd.microsoft.icon", MimeType::Ico),
+    ("audio/mp3", MimeType::Mp3),
+    ("audio/x-m4a", MimeType::M4a),
+    ("audio/x-wav", MimeType::Wav),
+    ("audio/wave", MimeType::Wav),
+    ("audio/x-flac", MimeType::Flac),
+    ("text/rtf", MimeType::Rtf),
+    ("application/x-zip-compressed", MimeType::Zip),
+];
+
+impl MimeType {
+    pub fn parse(value: &str) -> Self {
+        let essence = value
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        KNOWN
+            .iter()
+            .find(|(_, name, _)| *name == essence)
+            .map(|(v, _, _)| v)
+            .or_else(|| {
+                ALIASES
+                    .iter()
+                    .find(|(name, _)| *name == essence)
+                    .map(|(_, v)| v)
+            })
+            .cloned()
+            .unwrap_or(Self::Other(essence))
+    }
+
+    /// Eg. `image/png`
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(v) => v,
+            v => KNOWN.iter().find(|(k, _, _)| k == v).unwrap().1,
+        }
+    }
+
+    /// File extensions without the dot, preferred first. Empty for [`MimeType::Other`].
+    pub fn extensions(&self) -> &'static [&'static str] {
+        KNOWN
+            .iter()
+            .find(|(k, _, _)| k == self)
+            .map(|(_, _, v)| *v)
+            .unwrap_or_default()
+    }
+
+    /// The type of a file extension (with or without the dot), case-insensitive.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.trim_start_matches('.').to_lowercase();
+
+        KNOWN
+            .iter()
+            .find(|(_, _, v)| v.contains(&extension.as_str()))
+            .map(|(v, _, _)| v.clone())
+    }
+
+    /// The kind of media of the type. SVG has none, since it can carry scripts and isn't accepted
+    /// by any field without being sanitized first.
+    pub fn kind(&self) -> Option<MediaKind> {
+        Some(match self {
+            Self::Jpeg
+            | Self::Png
+            | Self::Gif
+            | Self::Webp
+            | Self::Avif
+            | Self::Heic
+            | Self::Bmp
+            | Self::Ico
+            | Self::Tiff => MediaKind::Image,
+            Self::Mp4 | Self::Webm | Self::Quicktime => MediaKind::Video,
+            Self::Mp3 | Self::M4a | Self::Wav | Self::Ogg | Self::Flac | Self::Aac => {
+                MediaKind::Audio
+            }
+            Self::Pdf
+            | Self::Doc
+            | Self::Docx
+            | Self::Xls
+            | Self::Xlsx
+            | Self::Ppt
+            | Self::Pptx
+            | Self::Odt
+            | Self::Rtf
+            | Self::PlainText
+            | Self::Csv => MediaKind::Document,
+            Self::Svg | Self::Zip | Self::Json => return None,
+            Self::Other(v) => match v.split_once('/')?.0 {
+                "image" => MediaKind::Image,
+                "video" => MediaKind::Video,
+                "audio" => MediaKind::Audio,
+                _ => return None,
+            },
+        })
+    }
+
+    /// Detect the type from the first bytes of a file.
+    ///
+    /// Office Open XML and OpenDocument files are detected as [`MimeType::Zip`], and the legacy
+    /// Office formats as [`MimeType::Doc`], since telling them apart needs the whole file.
+    /// Text formats (other than SVG) can't be detected.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        let at =
+            |offset: usize, magic: &[u8]| bytes.get(offset..offset + magic.len()) == Some(magic);
+
+        Some(if at(0, b"\xFF\xD8\xFF") {
+            Self::Jpeg
+        } else if at(0, b"\x89PNG\r\n\x1A\n") {
+            Self::Png
+        } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+            Self::Gif
+        } else if at(0, b"RIFF") && at(8, b"WEBP") {
+            Self::Webp
+        } else if at(0, b"RIFF") && at(8, b"WAVE") {
+            Self::Wav
+        } else if at(4, b"ftyp") {
+            match bytes.get(8..12)? {
+                b"avif" | b"avis" => Self::Avif,
+                b"heic" | b"heix" | b"mif1" | b"msf1" => Self::Heic,
+                b"qt  " => Self::Quicktime,
+                b"M4A " => Self::M4a,
+                _ => Self::Mp4,
+            }
+        } else if at(0, b"BM") {
+            Self::Bmp
+        } else if at(0, b"\x00\x00\x01\x00") {
+            Self::Ico
+        } else if at(0, b"II*\x00") || at(0, b"MM\x00*") {
+            Self::Tiff
+        } else if at(0, b"\x1A\x45\xDF\xA3") {
+            Self::Webm
+        } else if at(0, b"ID3") || at(0, b"\xFF\xFB") || at(0, b"\xFF\xF3") || at(0, b"\xFF\xF2") {
+            Self::Mp3
+        } else if at(0, b"\xFF\xF1") || at(0, b"\xFF\xF9") {
+            Self::Aac
+        } else if at(0, b"OggS") {
+            Self::Ogg
+        } else if at(0, b"fLaC") {
+            Self::Flac
+        } else if at(0, b"%PDF-") {
+            Self::Pdf
+        } else if at(0, b"{\\rtf") {
+            Self::Rtf
+        } else if at(0, b"PK\x03\x04") {
+            Self::Zip
+        } else if at(0, b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1") {
+            Self::Doc
+        } else if is_svg(bytes) {
+            Self::Svg
+        } else {
+            return None;
+        })
+    }
+
+    /// Whether a file detected by [`MimeType::sniff`] as `sniffed` can be of this type.
+    fn is_compatible_with(&self, sniffed: &Self) -> bool {
+        self == sniffed
+            || match sniffed {
+                Self::Zip => matches!(self, Self::Docx | Self::Xlsx | Self::Pptx | Self::Odt),
+                Self::Doc => matches!(self, Self::Xls | Self::Ppt),
+                Self::Mp4 => matches!(self, Self::M4a),
+                // Containers used for both audio and video.
+                Self::Webm | Self::Ogg => {
+                    matches!(self.kind(), Some(MediaKind::Audio | MediaKind::Video))
+                }
+                _ => false,
+            }
+    }
+}
+
+fn is_svg(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]);
+    let head = head.trim_start_matches('\u{FEFF}').trim_start();
+
+    head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg"))
+}
+
+impl Display for MimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MimeType {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
+}
+
+impl From<String> for MimeType {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<MimeType> for String {
+    fn from(value: MimeType) -> Self {
+        match value {
+            MimeType::Other(v) => v,
+            v => v.as_str().to_string(),
+        }
+    }
+}
+
+impl SchematicFieldType {
+    /// The kinds of files which can be uploaded to the field. Empty if it doesn't take uploads.
+    pub fn allowed_media_kinds(&self) -> &'static [MediaKind] {
+        match self {
+            Self::Document | Self::MultiDocument => &[MediaKind::Document],
+            Self::Image => &[MediaKind::Image],
+            Self::Video => &[MediaKind::Video],
+            Self::Audio => &[MediaKind::Audio],
+            _ => &[],
+        }
+    }
+
+    pub fn accepts_mime_type(&self, mime_type: &MimeType) -> bool {
+        mime_type
+            .kind()
+            .is_some_and(|v| self.allowed_media_kinds().contains(&v))
+    }
+
+    /// The extensions of every known type the field accepts, for file pickers.
+    pub fn allowed_extensions(&self) -> Vec<&'static str> {
+        KNOWN
+            .iter()
+            .filter(|(v, _, _)| self.accepts_mime_type(v))
+            .flat_map(|(_, _, v)| v.iter().copied())
+            .collect()
+    }
+
+    /// Check an uploaded file can be stored in the field and return its actual type.
+    ///
+    /// The type is detected from the bytes when possible. Otherwise the declared type is used,
+    /// or the extension of the file name if the declared type is unknown. Fails if the bytes
+    /// don't match the declared type, or the field doesn't accept the type. Image, video and
+    /// audio files must always be detected, since their declared type can't be trusted.
+    pub fn validate_upload(
+        &self,
+        declared: &str,
+        file_name: Option<&str>,
+        bytes: &[u8],
+    ) -> Result<MimeType> {
+        let mut claimed = MimeType::parse(declared);
+
+        if claimed.kind().is_none()
+            && let Some(v) = file_name
+                .and_then(|v| v.rsplit_once('.'))
+                .and_then(|(_, ext)| MimeType::from_extension(ext))
+        {
+            claimed = v;
+        }
+
+        let actual = match MimeType::sniff(bytes) {
+            Some(sniffed) if claimed.is_compatible_with(&sniffed) => claimed,
+            Some(sniffed) if claimed.kind().is_some() => {
+                return Err(GlobalError::InvalidUpload(format!(
+                    "File is declared as {claimed} but contains {sniffed}"
+                )));
+            }
+            Some(sniffed) => sniffed,
+            None if claimed.kind() == Some(MediaKind::Document) => claimed,
+            None => {
+                return Err(GlobalError::InvalidUpload(format!(
+                    "File is declared as {claimed} but its contents aren't a known format"
+                )));
+            }
+        };
+
+        if !self.accepts_mime_type(&actual) {
+            return Err(GlobalError::InvalidUpload(format!(
+                "{} fields don't accept {actual} files",
+                self.as_name()
+            )));
+        }
+
+        Ok(actual)
+    }
+}
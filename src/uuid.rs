@@ -3,14 +3,96 @@ use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::id::{AddonUuid, WebsitePublicId};
+use crate::{
+    error::{GlobalError, Result},
+    id::{AddonUuid, TemplatePublicId, WebsitePublicId},
+};
 
-#[derive(Debug, Clone)]
+/// Collection names which can't be used, compared case-insensitively.
+const RESERVED_NAMES: &[&str] = &["admin", "api", "collections", "schema", "system", "uploads"];
+
+const MAX_NAME_LEN: usize = 64;
+
+/// The name of a collection, written as `[addon:][Local/]name`.
+///
+/// - `addon` is the namespace of the addon which owns the collection.
+/// - `Local` is a built-in namespace, eg. `Forms/Contact`. It stays part of the `id`.
+///
+/// Only [`CollectionName::parse`] validates, stored names deserialize as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
 pub struct CollectionName {
     pub id: String,
     pub ns: Option<String>,
 }
 
+impl CollectionName {
+    /// Parse and validate a name, for names coming from users.
+    ///
+    /// Every part has to start with a letter, only contain ASCII letters, digits, `_` and `-`,
+    /// and be at most 64 characters. The reserved names (eg. `system`) can't be used as the
+    /// name of a collection outside of a namespace.
+    pub fn parse(value: &str) -> Result<Self> {
+        let name = Self::from(value);
+
+        if let Some(ns) = &name.ns {
+            validate_name_part(ns, "Namespace")?;
+        }
+
+        let local = name.local();
+
+        if let Some(Namespace::Custom(local)) = &local {
+            validate_name_part(local, "Local Namespace")?;
+        }
+
+        validate_name_part(name.name(), "Collection Name")?;
+
+        if name.ns.is_none()
+            && local.is_none()
+            && RESERVED_NAMES.contains(&name.id.to_lowercase().as_str())
+        {
+            return Err(GlobalError::ParseError(format!(
+                "Collection Name {} is reserved",
+                name.id
+            )));
+        }
+
+        Ok(name)
+    }
+
+    /// The built-in namespace of the collection, eg. `Forms` for `Forms/Contact`.
+    pub fn local(&self) -> Option<Namespace> {
+        self.id.split_once('/').map(|(v, _)| Namespace::from(v))
+    }
+
+    /// The name without its built-in namespace, eg. `Contact` for `Forms/Contact`.
+    pub fn name(&self) -> &str {
+        self.id.split_once('/').map_or(&self.id, |(_, v)| v)
+    }
+}
+
+fn validate_name_part(value: &str, what: &str) -> Result<()> {
+    let invalid = |reason: &str| GlobalError::ParseError(format!("{what} {value:?} {reason}"));
+
+    if value.is_empty() || value.len() > MAX_NAME_LEN {
+        return Err(invalid("has to be between 1 and 64 characters"));
+    }
+
+    if !value.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err(invalid("has to start with a letter"));
+    }
+
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(invalid("can only contain letters, digits, _ and -"));
+    }
+
+    Ok(())
+}
+
+/// Doesn't validate the name, see [`CollectionName::parse`].
 impl From<&str> for CollectionName {
     fn from(value: &str) -> Self {
         if let Some((a, b)) = value.split_once(":") {
@@ -27,6 +109,14 @@ impl From<&str> for CollectionName {
     }
 }
 
+impl FromStr for CollectionName {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
 impl<'de> Deserialize<'de> for CollectionName {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -34,18 +124,7 @@ impl<'de> Deserialize<'de> for CollectionName {
     {
         let value = String::deserialize(deserializer)?;
 
-        // TODO: Also parse Local Namespaces like "Forms/Name" ??
-        if let Some((a, b)) = value.split_once(":") {
-            Ok(Self {
-                id: b.to_string(),
-                ns: Some(a.to_string()),
-            })
-        } else {
-            Ok(Self {
-                id: value,
-                ns: None,
-            })
-        }
+        Ok(Self::from(value.as_str()))
     }
 }
 
@@ -54,28 +133,92 @@ impl Serialize for CollectionName {
     where
         S: serde::Serializer,
     {
-        if let Some(ns) = self.ns.as_deref() {
-            format!("{ns}:{}", self.id).serialize(serializer)
-        } else {
-            self.id.serialize(serializer)
-        }
+        serializer.collect_str(self)
     }
 }
 
 impl std::fmt::Display for CollectionName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(ns) = self.ns.as_deref() {
-            format!("{ns}:{}", self.id).fmt(f)
-        } else {
-            self.id.fmt(f)
+            write!(f, "{ns}:")?;
         }
+
+        f.write_str(&self.id)
     }
 }
 
+/// The built-in namespaces collections can be grouped in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    Forms,
+    Members,
+    Marketing,
+    Billing,
+    Custom(String),
+}
+
+impl Namespace {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Forms => "Forms",
+            Self::Members => "Members",
+            Self::Marketing => "Marketing",
+            Self::Billing => "Billing",
+            Self::Custom(v) => v,
+        }
+    }
+}
+
+impl From<&str> for Namespace {
+    fn from(value: &str) -> Self {
+        match value {
+            "Forms" => Self::Forms,
+            "Members" => Self::Members,
+            "Marketing" => Self::Marketing,
+            "Billing" => Self::Billing,
+            v => Self::Custom(v.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Namespace {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        validate_name_part(&value, "Namespace").map_err(serde::de::Error::custom)?;
+
+        Ok(Self::from(value.as_str()))
+    }
+}
+
+impl Serialize for Namespace {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+/// Who owns something, written as `s:<uuid>`, `a:<uuid>`, `t:<uuid>` or `system`.
+///
+/// A plain UUID is read as a Site for older values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UuidType {
     Site(WebsitePublicId),
     Addon(AddonUuid),
+    Template(TemplatePublicId),
+    /// Built into the platform.
+    System,
 }
 
 impl UuidType {
@@ -87,19 +230,57 @@ impl UuidType {
         matches!(self, UuidType::Addon(_))
     }
 
+    pub fn is_template(&self) -> bool {
+        matches!(self, UuidType::Template(_))
+    }
+
+    pub fn is_system(&self) -> bool {
+        matches!(self, UuidType::System)
+    }
+
+    /// The nil UUID for System.
     pub fn get_uuid(&self) -> Uuid {
         match *self {
             UuidType::Site(uuid) => *uuid,
             UuidType::Addon(uuid) => *uuid,
+            UuidType::Template(uuid) => *uuid,
+            UuidType::System => Uuid::nil(),
         }
     }
 }
 
-impl ToString for UuidType {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for UuidType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            UuidType::Site(uuid) => format!("s:{uuid}"),
-            UuidType::Addon(uuid) => format!("a:{uuid}"),
+            UuidType::Site(uuid) => write!(f, "s:{uuid}"),
+            UuidType::Addon(uuid) => write!(f, "a:{uuid}"),
+            UuidType::Template(uuid) => write!(f, "t:{uuid}"),
+            UuidType::System => write!(f, "system"),
+        }
+    }
+}
+
+impl FromStr for UuidType {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "system" {
+            return Ok(Self::System);
+        }
+
+        let Some((scope, uuid)) = s.split_once(':') else {
+            return Ok(Self::Site(WebsitePublicId::from(Uuid::parse_str(s)?)));
+        };
+
+        let uuid = Uuid::parse_str(uuid)?;
+
+        match scope {
+            "s" => Ok(Self::Site(WebsitePublicId::from(uuid))),
+            "a" => Ok(Self::Addon(AddonUuid::from(uuid))),
+            "t" => Ok(Self::Template(TemplatePublicId::from(uuid))),
+            _ => Err(GlobalError::ParseError(format!(
+                "Unknown UUID scope {scope}"
+            ))),
         }
     }
 }
@@ -111,23 +292,7 @@ impl<'de> Deserialize<'de> for UuidType {
     {
         let value = String::deserialize(deserializer)?;
 
-        if let Ok(uuid) = WebsitePublicId::from_str(&value) {
-            Ok(Self::Site(uuid))
-        } else {
-            let bytes = value.as_bytes();
-
-            if bytes[0] == b's' {
-                Ok(Self::Site(WebsitePublicId::from(
-                    Uuid::try_parse_ascii(&bytes[2..]).unwrap(),
-                )))
-            } else if bytes[0] == b'a' {
-                Ok(Self::Addon(AddonUuid::from(
-                    Uuid::try_parse_ascii(&bytes[2..]).unwrap(),
-                )))
-            } else {
-                Err(serde::de::Error::custom("Unknown"))
-            }
-        }
+        value.parse().map_err(serde::de::Error::custom)
     }
 }
 
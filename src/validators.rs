@@ -0,0 +1,412 @@
+//! Validated and normalized contact details.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    error::{GlobalError, Result},
+    value::SimpleValue,
+};
+
+/// Calling codes which are two digits long. `1` and `7` are the only single digit ones,
+/// everything else is three digits. The codes are prefix free so this is enough to split them.
+const TWO_DIGIT_CALLING_CODES: &[u16] = &[
+    20, 27, 30, 31, 32, 33, 34, 36, 39, 40, 41, 43, 44, 45, 46, 47, 48, 49, 51, 52, 53, 54, 55, 56,
+    57, 58, 60, 61, 62, 63, 64, 65, 66, 81, 82, 84, 86, 90, 91, 92, 93, 94, 95, 98,
+];
+
+/// A phone number in E.164 format, eg. `+14155552671`.
+///
+/// Parsing accepts the international format with the usual separators (`+1 (415) 555-2671`)
+/// and `00` instead of the `+`. Numbers without a calling code are rejected since the country
+/// can't be known.
+///
+/// Only parsing validates. Stored numbers which aren't valid, eg. saved before numbers were
+/// validated, deserialize as they are.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(from = "String", into = "String")]
+pub struct PhoneNumber {
+    /// Without the `+`, or the number as it was stored if it isn't valid.
+    digits: String,
+    /// 0 if the number isn't valid.
+    calling_code_len: usize,
+}
+
+impl PhoneNumber {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: &str| GlobalError::InvalidPhoneNumber(format!("{value}: {reason}"));
+
+        let trimmed = value.trim();
+
+        let rest = if let Some(v) = trimmed.strip_prefix('+') {
+            v
+        } else if let Some(v) = trimmed.strip_prefix("00") {
+            v
+        } else {
+            return Err(invalid("missing the country calling code"));
+        };
+
+        let mut digits = String::with_capacity(rest.len());
+
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                ' ' | '-' | '.' | '(' | ')' => (),
+                _ => return Err(invalid("unexpected character")),
+            }
+        }
+
+        // E.164 allows at most 15 digits. The shortest numbers in use are 7.
+        if !(7..=15).contains(&digits.len()) {
+            return Err(invalid("has to be between 7 and 15 digits"));
+        }
+
+        let calling_code_len = match digits.as_bytes()[0] {
+            b'0' => return Err(invalid("calling codes can't start with 0")),
+            b'1' | b'7' => 1,
+            _ if TWO_DIGIT_CALLING_CODES.contains(&digits[..2].parse().unwrap()) => 2,
+            _ => 3,
+        };
+
+        Ok(Self {
+            digits,
+            calling_code_len,
+        })
+    }
+
+    /// False for a stored number which didn't parse.
+    pub fn is_valid(&self) -> bool {
+        self.calling_code_len > 0
+    }
+
+    /// The country calling code, eg. `44` for the UK.
+    pub fn calling_code(&self) -> Option<u16> {
+        self.is_valid()
+            .then(|| self.digits[..self.calling_code_len].parse().unwrap())
+    }
+
+    /// The number without the calling code.
+    pub fn national_number(&self) -> Option<&str> {
+        self.is_valid()
+            .then(|| &self.digits[self.calling_code_len..])
+    }
+
+    /// The number in E.164 format.
+    pub fn to_e164(&self) -> Option<String> {
+        self.is_valid().then(|| format!("+{}", self.digits))
+    }
+}
+
+impl Display for PhoneNumber {
+    /// E.164 format, or the number as it was stored if it isn't valid.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            f.write_str("+")?;
+        }
+
+        f.write_str(&self.digits)
+    }
+}
+
+impl FromStr for PhoneNumber {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl From<String> for PhoneNumber {
+    /// Parse a stored number, keeping it as it is if it isn't valid.
+    fn from(value: String) -> Self {
+        Self::parse(&value).unwrap_or(Self {
+            digits: value,
+            calling_code_len: 0,
+        })
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(value: PhoneNumber) -> Self {
+        value.to_string()
+    }
+}
+
+/// An email address with the domain lowercased.
+///
+/// The syntax is the dot-atom form of RFC 5322 (plus UTF-8 from RFC 6531), which is what every
+/// address in use looks like. Quoted local parts and IP address domains are rejected.
+/// The local part keeps its case since mail servers are allowed to treat it case-sensitively.
+///
+/// Only parsing validates. Stored addresses which aren't valid, eg. saved before addresses were
+/// validated, deserialize as they are.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS), ts(type = "string"))]
+#[serde(from = "String", into = "String")]
+pub struct EmailAddress {
+    address: String,
+    /// Index of the last `@`, if there is one.
+    at: Option<usize>,
+    valid: bool,
+}
+
+impl EmailAddress {
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = |reason: &str| GlobalError::InvalidEmail(format!("{value}: {reason}"));
+
+        let trimmed = value.trim();
+
+        let Some((local, domain)) = trimmed.rsplit_once('@') else {
+            return Err(invalid("missing the @"));
+        };
+
+        if local.is_empty() || local.len() > 64 {
+            return Err(invalid(
+                "the local part has to be between 1 and 64 characters",
+            ));
+        }
+
+        if local.split('.').any(|v| v.is_empty()) {
+            return Err(invalid("the local part has an empty segment"));
+        }
+
+        if !local.chars().all(|c| {
+            c.is_ascii_alphanumeric() || !c.is_ascii() || ".!#$%&'*+/=?^_`{|}~-".contains(c)
+        }) {
+            return Err(invalid("the local part has an unexpected character"));
+        }
+
+        if domain.is_empty() || domain.len() > 253 {
+            return Err(invalid("the domain has to be between 1 and 253 characters"));
+        }
+
+        let labels = domain.split('.').collect::<Vec<_>>();
+
+        if labels.len() < 2 {
+            return Err(invalid("the domain needs a top level domain"));
+        }
+
+        for label in labels {
+            if label.is_empty()
+                || label.len() > 63
+                || label.starts_with('-')
+                || label.ends_with('-')
+                || !label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || !c.is_ascii() || c == '-')
+            {
+                return Err(invalid("the domain isn't valid"));
+            }
+        }
+
+        Ok(Self {
+            address: format!("{local}@{}", domain.to_lowercase()),
+            at: Some(local.len()),
+            valid: true,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+
+    /// False for a stored address which didn't parse.
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    /// The part before the `@`, or the whole address if it has none.
+    pub fn local_part(&self) -> &str {
+        match self.at {
+            Some(at) => &self.address[..at],
+            None => &self.address,
+        }
+    }
+
+    /// The part after the `@`, or empty if it has none.
+    pub fn domain(&self) -> &str {
+        match self.at {
+            Some(at) => &self.address[at + 1..],
+            None => "",
+        }
+    }
+
+    /// The `+tag` of the local part, eg. `news` for `jane+news@example.com`.
+    pub fn plus_tag(&self) -> Option<&str> {
+        self.local_part().split_once('+').map(|(_, tag)| tag)
+    }
+
+    /// The address without its `+tag`, for spotting the same inbox signing up twice.
+    pub fn without_plus_tag(&self) -> Self {
+        match self.local_part().split_once('+') {
+            Some((local, _)) if self.valid && !local.is_empty() => Self {
+                address: format!("{local}@{}", self.domain()),
+                at: Some(local.len()),
+                valid: true,
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.address)
+    }
+}
+
+impl FromStr for EmailAddress {
+    type Err = GlobalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl From<String> for EmailAddress {
+    /// Parse a stored address, keeping it as it is if it isn't valid.
+    fn from(value: String) -> Self {
+        Self::parse(&value).unwrap_or_else(|_| Self {
+            at: value.rfind('@'),
+            address: value,
+            valid: false,
+        })
+    }
+}
+
+impl From<EmailAddress> for String {
+    fn from(value: EmailAddress) -> Self {
+        value.address
+    }
+}
+
+/// A postal address split into its parts so it can be filtered by country or region.
+///
+/// Addresses used to be stored as a single text which still deserializes, into `line1`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(ts_rs::TS))]
+#[serde(remote = "Self", rename_all = "camelCase")]
+pub struct PostalAddress {
+    #[serde(default)]
+    pub line1: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line2: Option<String>,
+    #[serde(default)]
+    pub city: String,
+    /// State, province, county, etc.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    /// ISO 3166-1 alpha-2 code, eg. `US`. Empty for legacy addresses.
+    #[serde(default)]
+    pub country: String,
+}
+
+impl PostalAddress {
+    /// A legacy address stored as a single text.
+    pub fn from_text<S: Into<String>>(value: S) -> Self {
+        Self {
+            line1: value.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Read an address from an object, a text containing a JSON object, or legacy text.
+    ///
+    /// Every part is trimmed (empty optional parts are removed) and the country is uppercased.
+    /// Fails if the country isn't a two letter code.
+    pub fn from_value(value: &SimpleValue) -> Result<Self> {
+        let address = match value {
+            SimpleValue::ObjectUnknown(v) => serde_json::from_value(v.clone())?,
+            SimpleValue::Text(v) if v.trim_start().starts_with('{') => serde_json::from_str(v)?,
+            SimpleValue::Text(v) => Self::from_text(v.as_str()),
+            v => return Err(GlobalError::type_mismatch("Address", v.type_name())),
+        };
+
+        address.normalize()
+    }
+
+    fn normalize(self) -> Result<Self> {
+        let optional =
+            |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let country = self.country.trim().to_uppercase();
+
+        if !country.is_empty()
+            && (country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()))
+        {
+            return Err(GlobalError::ParseError(format!(
+                "Invalid country code {country}, expected ISO 3166-1 alpha-2"
+            )));
+        }
+
+        Ok(Self {
+            line1: self.line1.trim().to_string(),
+            line2: optional(self.line2),
+            city: self.city.trim().to_string(),
+            region: optional(self.region),
+            postal_code: optional(self.postal_code),
+            country,
+        })
+    }
+}
+
+impl Display for PostalAddress {
+    /// One line, eg. `1 Main St, Springfield, IL 62701, US`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let region = [self.region.as_deref(), self.postal_code.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let parts = [
+            self.line1.as_str(),
+            self.line2.as_deref().unwrap_or_default(),
+            self.city.as_str(),
+            region.as_str(),
+            self.country.as_str(),
+        ];
+
+        let mut first = true;
+
+        for part in parts.into_iter().filter(|v| !v.is_empty()) {
+            if !first {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(part)?;
+            first = false;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for PostalAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PostalAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Legacy(String),
+            Structured(#[serde(with = "PostalAddress")] PostalAddress),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Legacy(v) => Self::from_text(v),
+            Repr::Structured(v) => v,
+        })
+    }
+}
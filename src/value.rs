@@ -1,23 +1,72 @@
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::{
+    borrow::{Borrow, Cow},
+    cmp::Ordering,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Deref,
+};
 
-use eyre::{Result, anyhow, bail};
-use serde::{Deserialize, Serialize};
-use time::{Date, OffsetDateTime, Time};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{self, MapAccess, SeqAccess, Visitor, value::MapAccessDeserializer},
+};
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, Time,
+    format_description::{BorrowedFormatItem, well_known::Rfc3339},
+    macros::format_description,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+use url::Url;
+
+use crate::{
+    duration,
+    error::{GlobalError, Result},
+    geo::GeoPoint,
+    money::Money,
+    schema::{SchematicFieldType, SchematicFieldValue, normalize_tags},
+    slug::Slug,
+    upload::MediaGalleryItem,
+    validators::PostalAddress,
+};
+
+/// Deserializing gives an Integer for integers which fit in an i64 and an UInteger for those
+/// above [`i64::MAX`], so every integer keeps its exact value. A Byte is never read back, it
+/// only comes from converting a u8.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize)]
 #[serde(untagged)]
 pub enum Number {
     Byte(u8),
     Integer(i64),
+    UInteger(u64),
     Float(f64),
 }
 
 impl Number {
-    pub fn into_u8(self) -> eyre::Result<u8> {
+    pub fn into_u8(self) -> Result<u8> {
         if let Self::Byte(v) = self {
             Ok(v)
         } else {
-            eyre::bail!("Not u8")
+            Err(GlobalError::type_mismatch("Byte", self.type_name()))
+        }
+    }
+
+    /// The value if it's a whole number between 0 and [`u64::MAX`], in any variant but Float.
+    pub fn into_u64(self) -> Result<u64> {
+        match self {
+            Number::Byte(v) => Ok(v.into()),
+            Number::Integer(v) => u64::try_from(v).map_err(|_| {
+                GlobalError::ParseError(format!("{v} is out of range for an unsigned integer"))
+            }),
+            Number::UInteger(v) => Ok(v),
+            Number::Float(_) => Err(GlobalError::type_mismatch("Integer", self.type_name())),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Number::Byte(_) => "Byte",
+            Number::Integer(_) => "Integer",
+            Number::UInteger(_) => "Unsigned Integer",
+            Number::Float(_) => "Float",
         }
     }
 
@@ -26,17 +75,155 @@ impl Number {
         match self {
             Number::Byte(v) => v as f64,
             Number::Integer(v) => v as f64,
+            Number::UInteger(v) => v as f64,
             Number::Float(v) => v,
         }
     }
 
+    /// Out of range values saturate.
     pub fn convert_i64(self) -> i64 {
         match self {
             Number::Byte(v) => v as i64,
             Number::Integer(v) => v,
+            Number::UInteger(v) => i64::try_from(v).unwrap_or(i64::MAX),
             Number::Float(v) => v as i64,
         }
     }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Number::Float(_))
+    }
+
+    /// The value as an i128 if it's not a Float, which holds every i64 and u64.
+    fn integral(self) -> Option<i128> {
+        match self {
+            Number::Byte(v) => Some(v.into()),
+            Number::Integer(v) => Some(v.into()),
+            Number::UInteger(v) => Some(v.into()),
+            Number::Float(_) => None,
+        }
+    }
+
+    /// The result of an operation on two integers: an Integer, or an UInteger above
+    /// [`i64::MAX`] if one of them was an UInteger. None if it doesn't fit.
+    fn integral_result(self, rhs: Self, value: i128) -> Option<Self> {
+        if let Ok(v) = i64::try_from(value) {
+            Some(Number::Integer(v))
+        } else if matches!(self, Number::UInteger(_)) || matches!(rhs, Number::UInteger(_)) {
+            u64::try_from(value).ok().map(Number::UInteger)
+        } else {
+            None
+        }
+    }
+
+    fn checked_op(
+        self,
+        rhs: Self,
+        int_op: fn(i128, i128) -> Option<i128>,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Option<Self> {
+        match (self.integral(), rhs.integral()) {
+            (Some(a), Some(b)) => int_op(a, b).and_then(|v| self.integral_result(rhs, v)),
+            _ => {
+                let value = float_op(self.convert_f64(), rhs.convert_f64());
+
+                value.is_finite().then_some(Number::Float(value))
+            }
+        }
+    }
+
+    /// Integers are added as i64, or u64 when one of them is an UInteger, anything involving a
+    /// Float as f64.
+    ///
+    /// Returns None on overflow or a non-finite result.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_add, |a, b| a + b)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_sub, |a, b| a - b)
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.checked_op(rhs, i128::checked_mul, |a, b| a * b)
+    }
+
+    /// Dividing two integers only stays an Integer if there's no remainder.
+    ///
+    /// Returns None when dividing by zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        match (self.integral(), rhs.integral()) {
+            (_, Some(0)) => None,
+            (Some(a), Some(b)) if a.checked_rem(b) == Some(0) => {
+                a.checked_div(b).and_then(|v| self.integral_result(rhs, v))
+            }
+            _ => {
+                let value = self.convert_f64() / rhs.convert_f64();
+
+                value.is_finite().then_some(Number::Float(value))
+            }
+        }
+    }
+
+    /// Compare the values regardless of variant.
+    ///
+    /// Unlike the derived `PartialOrd` (which orders by variant first) this gives
+    /// `Byte(200) > Integer(1)`. Floats are ordered using [`f64::total_cmp`].
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self.integral(), other.integral()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => self.convert_f64().total_cmp(&other.convert_f64()),
+        }
+    }
+
+    /// Are the values equal regardless of variant?
+    pub fn value_eq(&self, other: &Self) -> bool {
+        self.total_cmp(other).is_eq()
+    }
+}
+
+impl TryFrom<serde_json::Number> for Number {
+    type Error = GlobalError;
+
+    /// Uses the same variant deserializing would.
+    fn try_from(value: serde_json::Number) -> Result<Self> {
+        if let Some(v) = value.as_i64() {
+            Ok(Number::Integer(v))
+        } else if let Some(v) = value.as_u64() {
+            Ok(Number::UInteger(v))
+        } else if value.is_f64() {
+            Ok(Number::Float(value.as_f64().unwrap_or_default()))
+        } else {
+            Err(GlobalError::ParseError(format!("{value} is out of range")))
+        }
+    }
+}
+
+impl TryFrom<Number> for serde_json::Number {
+    type Error = GlobalError;
+
+    /// Fails for NaN and infinite Floats.
+    fn try_from(value: Number) -> Result<Self> {
+        match value {
+            Number::Byte(v) => Ok(v.into()),
+            Number::Integer(v) => Ok(v.into()),
+            Number::UInteger(v) => Ok(v.into()),
+            Number::Float(v) => serde_json::Number::from_f64(v)
+                .ok_or_else(|| GlobalError::ParseError(format!("{v} is not a valid JSON number"))),
+        }
+    }
+}
+
+impl From<Number> for serde_json::Value {
+    /// NaN and infinite Floats become `null`, same as `serde_json` does for `f64`.
+    fn from(value: Number) -> Self {
+        match value {
+            Number::Byte(v) => v.into(),
+            Number::Integer(v) => v.into(),
+            Number::UInteger(v) => v.into(),
+            Number::Float(v) => v.into(),
+        }
+    }
 }
 
 impl From<u8> for Number {
@@ -57,6 +244,12 @@ impl From<i64> for Number {
     }
 }
 
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Self::UInteger(value)
+    }
+}
+
 impl From<f64> for Number {
     fn from(value: f64) -> Self {
         Self::Float(value)
@@ -68,6 +261,7 @@ impl From<Number> for i32 {
         match val {
             Number::Byte(v) => v as i32,
             Number::Integer(v) => v as i32,
+            Number::UInteger(v) => i32::try_from(v).unwrap_or(i32::MAX),
             Number::Float(v) => v as i32,
         }
     }
@@ -75,11 +269,7 @@ impl From<Number> for i32 {
 
 impl From<Number> for i64 {
     fn from(val: Number) -> Self {
-        match val {
-            Number::Byte(v) => v as i64,
-            Number::Integer(v) => v,
-            Number::Float(v) => v as i64,
-        }
+        val.convert_i64()
     }
 }
 
@@ -88,11 +278,43 @@ impl Display for Number {
         match self {
             Number::Byte(v) => v.fmt(f),
             Number::Integer(v) => v.fmt(f),
+            Number::UInteger(v) => v.fmt(f),
             Number::Float(v) => v.fmt(f),
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = Number;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a number")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Number, E> {
+        Ok(Number::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Number, E> {
+        Ok(match i64::try_from(v) {
+            Ok(v) => Number::Integer(v),
+            Err(_) => Number::UInteger(v),
+        })
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Number, E> {
+        Ok(Number::Float(v))
+    }
+}
+
 impl Default for Number {
     fn default() -> Self {
         Self::Integer(0)
@@ -100,16 +322,27 @@ impl Default for Number {
 }
 
 /// A Simple Value is always untagged and the value will go into their respective variant w/o any fuss.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Deserializing picks the variant from the kind of value, in this order:
+///
+/// - Booleans are a Boolean.
+/// - Numbers are read like a [`Number`]: integers are an Integer or UInteger and everything
+///   else a Float.
+/// - Strings are Text. With [`detect_temporal`] strings in the formats
+///   [`SimpleValue::into_json`] writes are a DateTime, Date or Time instead.
+/// - Arrays are classified like [`SimpleValue::from_json`] does.
+/// - Objects are an ObjectUnknown.
+/// - `null` is an ObjectUnknown holding `null`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum SimpleValue {
     Text(String),
     Number(Number),
     Boolean(bool),
 
-    DateTime(OffsetDateTime),
-    Date(Date),
-    Time(Time),
+    DateTime(#[serde(serialize_with = "as_text::serialize")] OffsetDateTime),
+    Date(#[serde(serialize_with = "as_text::serialize")] Date),
+    Time(#[serde(serialize_with = "as_text::serialize")] Time),
 
     ListString(Vec<String>),
     ListNumber(Vec<Number>),
@@ -119,6 +352,98 @@ pub enum SimpleValue {
 }
 
 impl SimpleValue {
+    /// The name of the variant, used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "Text",
+            Self::Number(_) => "Number",
+            Self::Boolean(_) => "Boolean",
+            Self::DateTime(_) => "DateTime",
+            Self::Date(_) => "Date",
+            Self::Time(_) => "Time",
+            Self::ListString(_) => "String List",
+            Self::ListNumber(_) => "Number List",
+            Self::ArrayUnknown(_) => "Array",
+            Self::ObjectUnknown(_) => "Object",
+        }
+    }
+
+    /// Deterministically convert JSON into a value.
+    ///
+    /// - Strings in the exact formats [`SimpleValue::into_json`] writes (RFC3339 date times,
+    ///   `YYYY-MM-DD` dates and `HH:MM:SS[.fraction]` times) become DateTime/Date/Time,
+    ///   any other string is Text.
+    /// - Numbers keep their precision: integers are an Integer, or an UInteger above
+    ///   [`i64::MAX`], everything else a Float.
+    /// - Arrays of only strings or only numbers become ListString/ListNumber, an empty array is a
+    ///   ListString and anything else is an ArrayUnknown.
+    /// - `null` cannot be represented and is an error.
+    pub fn from_json(value: serde_json::Value) -> Result<Self> {
+        Ok(match value {
+            serde_json::Value::Null => {
+                return Err(GlobalError::type_mismatch("SimpleValue", "Null"));
+            }
+            serde_json::Value::Bool(v) => Self::Boolean(v),
+            serde_json::Value::Number(v) => Self::Number(Number::try_from(v)?),
+            serde_json::Value::String(v) => parse_temporal(&v).unwrap_or(Self::Text(v)),
+            serde_json::Value::Array(items) => {
+                if items.iter().all(|v| v.is_string()) {
+                    Self::ListString(
+                        items
+                            .into_iter()
+                            .filter_map(|v| match v {
+                                serde_json::Value::String(v) => Some(v),
+                                _ => None,
+                            })
+                            .collect(),
+                    )
+                } else if items.iter().all(|v| v.is_number()) {
+                    Self::ListNumber(
+                        items
+                            .into_iter()
+                            .filter_map(|v| match v {
+                                serde_json::Value::Number(v) => Some(Number::try_from(v)),
+                                _ => None,
+                            })
+                            .collect::<Result<_>>()?,
+                    )
+                } else {
+                    Self::ArrayUnknown(items)
+                }
+            }
+            v @ serde_json::Value::Object(_) => Self::ObjectUnknown(v),
+        })
+    }
+
+    /// Convert the value into JSON. Dates and times are written in their canonical formats.
+    ///
+    /// NaN and infinite Floats become `null`.
+    pub fn into_json(self) -> serde_json::Value {
+        match self {
+            Self::Text(v) => serde_json::Value::String(v),
+            Self::Number(v) => v.into(),
+            Self::Boolean(v) => serde_json::Value::Bool(v),
+            Self::DateTime(v) => {
+                serde_json::Value::String(v.format(&Rfc3339).unwrap_or_else(|_| v.to_string()))
+            }
+            Self::Date(v) => {
+                serde_json::Value::String(v.format(DATE_FORMAT).unwrap_or_else(|_| v.to_string()))
+            }
+            Self::Time(v) => serde_json::Value::String(
+                if v.nanosecond() == 0 {
+                    v.format(TIME_FORMAT)
+                } else {
+                    v.format(TIME_SUBSECOND_FORMAT)
+                }
+                .unwrap_or_else(|_| v.to_string()),
+            ),
+            Self::ListString(v) => v.into(),
+            Self::ListNumber(v) => v.into_iter().map(serde_json::Value::from).collect(),
+            Self::ArrayUnknown(v) => serde_json::Value::Array(v),
+            Self::ObjectUnknown(v) => v,
+        }
+    }
+
     pub fn any_as_text(&self) -> Result<String> {
         Ok(match self {
             Self::Text(s) => s.to_string(),
@@ -130,7 +455,9 @@ impl SimpleValue {
             Self::ListString(_)
             | Self::ListNumber(_)
             | Self::ArrayUnknown(_)
-            | Self::ObjectUnknown(_) => return Err(anyhow!("Unable to convert to String"))?,
+            | Self::ObjectUnknown(_) => {
+                return Err(GlobalError::type_mismatch("Text", self.type_name()));
+            }
         })
     }
 
@@ -138,7 +465,7 @@ impl SimpleValue {
         if let Self::Text(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Text"))?
+            Err(GlobalError::type_mismatch("Text", self.type_name()))
         }
     }
 
@@ -146,7 +473,7 @@ impl SimpleValue {
         if let Self::Number(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Number"))?
+            Err(GlobalError::type_mismatch("Number", self.type_name()))
         }
     }
 
@@ -154,7 +481,7 @@ impl SimpleValue {
         if let Self::Boolean(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Boolean"))?
+            Err(GlobalError::type_mismatch("Boolean", self.type_name()))
         }
     }
 
@@ -162,7 +489,7 @@ impl SimpleValue {
         if let Self::DateTime(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to DateTime"))?
+            Err(GlobalError::type_mismatch("DateTime", self.type_name()))
         }
     }
 
@@ -170,7 +497,7 @@ impl SimpleValue {
         if let Self::Date(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Date"))?
+            Err(GlobalError::type_mismatch("Date", self.type_name()))
         }
     }
 
@@ -178,7 +505,7 @@ impl SimpleValue {
         if let Self::Time(v) = self {
             Ok(*v)
         } else {
-            Err(anyhow!("Unable to convert to Time"))?
+            Err(GlobalError::type_mismatch("Time", self.type_name()))
         }
     }
 
@@ -186,7 +513,7 @@ impl SimpleValue {
         if let Self::ListString(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to String List"))?
+            Err(GlobalError::type_mismatch("String List", self.type_name()))
         }
     }
 
@@ -194,7 +521,7 @@ impl SimpleValue {
         if let Self::ListNumber(v) = self {
             Ok(v)
         } else {
-            Err(anyhow!("Unable to convert to Number List"))?
+            Err(GlobalError::type_mismatch("Number List", self.type_name()))
         }
     }
 
@@ -202,7 +529,99 @@ impl SimpleValue {
         if let Self::ListNumber(v) = self {
             Ok(v.into_iter().map(|v| v.into_u8()).collect::<Result<_>>()?)
         } else {
-            Err(anyhow!("Unable to convert to Number List"))?
+            Err(GlobalError::type_mismatch("Number List", self.type_name()))
+        }
+    }
+
+    // The same without consuming the value, None for any other variant.
+
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        if let Self::Text(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_number(&self) -> Option<Number> {
+        if let Self::Number(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_boolean(&self) -> Option<bool> {
+        if let Self::Boolean(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_date_time(&self) -> Option<OffsetDateTime> {
+        if let Self::DateTime(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_date(&self) -> Option<Date> {
+        if let Self::Date(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_time(&self) -> Option<Time> {
+        if let Self::Time(v) = self {
+            Some(*v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_list_string(&self) -> Option<&[String]> {
+        if let Self::ListString(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_list_number(&self) -> Option<&[Number]> {
+        if let Self::ListNumber(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[serde_json::Value]> {
+        if let Self::ArrayUnknown(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn as_object(&self) -> Option<&serde_json::Value> {
+        if let Self::ObjectUnknown(v) = self {
+            Some(v)
+        } else {
+            None
         }
     }
 
@@ -210,7 +629,7 @@ impl SimpleValue {
         if matches!(self, Self::Text(_)) {
             Ok(self)
         } else {
-            bail!("Not Text")
+            Err(GlobalError::type_mismatch("Text", self.type_name()))
         }
     }
 
@@ -218,7 +637,7 @@ impl SimpleValue {
         if matches!(self, Self::Number(_)) {
             Ok(self)
         } else {
-            bail!("Not Number")
+            Err(GlobalError::type_mismatch("Number", self.type_name()))
         }
     }
 
@@ -226,7 +645,7 @@ impl SimpleValue {
         if matches!(self, Self::Boolean(_)) {
             Ok(self)
         } else {
-            bail!("Not Boolean")
+            Err(GlobalError::type_mismatch("Boolean", self.type_name()))
         }
     }
 
@@ -234,7 +653,7 @@ impl SimpleValue {
         if matches!(self, Self::DateTime(_)) {
             Ok(self)
         } else {
-            bail!("Not Date Time")
+            Err(GlobalError::type_mismatch("DateTime", self.type_name()))
         }
     }
 
@@ -242,7 +661,7 @@ impl SimpleValue {
         if matches!(self, Self::Date(_)) {
             Ok(self)
         } else {
-            bail!("Not Date")
+            Err(GlobalError::type_mismatch("Date", self.type_name()))
         }
     }
 
@@ -250,7 +669,7 @@ impl SimpleValue {
         if matches!(self, Self::Time(_)) {
             Ok(self)
         } else {
-            bail!("Not Time")
+            Err(GlobalError::type_mismatch("Time", self.type_name()))
         }
     }
 
@@ -258,7 +677,7 @@ impl SimpleValue {
         if matches!(self, Self::ListString(_)) {
             Ok(self)
         } else {
-            bail!("Not String List")
+            Err(GlobalError::type_mismatch("String List", self.type_name()))
         }
     }
 
@@ -266,11 +685,500 @@ impl SimpleValue {
         if matches!(self, Self::ListNumber(_)) {
             Ok(self)
         } else {
-            bail!("Not Number List")
+            Err(GlobalError::type_mismatch("Number List", self.type_name()))
         }
     }
 }
 
+impl SimpleValue {
+    /// Leniently convert the value into a field value, for CSV/form imports where everything
+    /// arrives as text. Use [`SchematicFieldType::parse_value`] for the strict version.
+    ///
+    /// | Target                          | Accepted                                                        |
+    /// |---------------------------------|-----------------------------------------------------------------|
+    /// | Text, Rich Text, Rich Content   | Any non-list value, using its text representation                |
+    /// | Number                          | Number, Text which parses as a number, Boolean as 1/0            |
+    /// | Boolean                         | Boolean, Number 0/1, Text `true/false/1/0/on/off/yes/no`         |
+    /// | URL                             | Text which parses as a URL                                       |
+    /// | Email                           | Text which parses as an email, with the domain lowercased        |
+    /// | Address                         | Object, Text containing a JSON object, legacy Text as line 1    |
+    /// | Phone                           | Text in international format, normalized to E.164                |
+    /// | Date & Time                     | DateTime, Date (midnight UTC), Number (unix seconds), Text as RFC3339, `YYYY-MM-DD[T ]HH:MM[:SS[.f]]` (UTC) or `YYYY-MM-DD` |
+    /// | Date                            | Date, DateTime, Text as `YYYY-MM-DD` or RFC3339                  |
+    /// | Time                            | Time, DateTime, Text as `HH:MM[:SS[.f]]`                         |
+    /// | Reference                       | Text UUID                                                        |
+    /// | Multi Reference                 | String List of UUIDs, Text of comma separated UUIDs              |
+    /// | Media Gallery                   | Array of items or UUIDs, String List / Text of comma separated UUIDs, Text containing a JSON array |
+    /// | Tags, Multi Document            | String List, Text of comma separated values (tags normalized)   |
+    /// | Document, Image, Video, Audio   | Number List of bytes                                             |
+    /// | Array                           | Any list, Text containing a JSON array                           |
+    /// | Object                          | Object, Text containing a JSON object                            |
+    /// | Currency                        | Object, Text containing a JSON object, Text like `12.34 USD`     |
+    /// | Location                        | Object, Text containing a JSON object, Text like `51.5,-0.12`    |
+    /// | Slug                            | Text, other text is turned into a slug like a title              |
+    /// | Duration                        | Text as ISO 8601 or `HH:MM[:SS[.f]]`, Number of seconds, Time    |
+    pub fn coerce_to(&self, target: SchematicFieldType) -> Result<SchematicFieldValue> {
+        let mismatch = || GlobalError::type_mismatch(target.as_name(), self.type_name());
+
+        Ok(match target {
+            SchematicFieldType::Text
+            | SchematicFieldType::RichText
+            | SchematicFieldType::RichContent => {
+                SchematicFieldValue::Text(self.any_as_text().map_err(|_| mismatch())?)
+            }
+
+            SchematicFieldType::Number => SchematicFieldValue::Number(match self {
+                Self::Number(v) => *v,
+                Self::Boolean(v) => Number::Byte(*v as u8),
+                Self::Text(v) => {
+                    let v = v.trim();
+
+                    if let Ok(v) = v.parse::<i64>() {
+                        Number::Integer(v)
+                    } else {
+                        Number::Float(v.parse()?)
+                    }
+                }
+                _ => return Err(mismatch()),
+            }),
+
+            SchematicFieldType::Boolean => SchematicFieldValue::Boolean(match self {
+                Self::Boolean(v) => *v,
+                Self::Number(v) if v.value_eq(&Number::Byte(0)) => false,
+                Self::Number(v) if v.value_eq(&Number::Byte(1)) => true,
+                Self::Text(v) => match v.trim().to_lowercase().as_str() {
+                    "1" | "on" | "true" | "yes" => true,
+                    "0" | "off" | "false" | "no" => false,
+                    v => v.parse()?,
+                },
+                _ => return Err(mismatch()),
+            }),
+
+            SchematicFieldType::URL => match self {
+                Self::Text(v) => SchematicFieldValue::Url(Url::parse(v.trim())?),
+                _ => return Err(mismatch()),
+            },
+            SchematicFieldType::Email => match self {
+                Self::Text(v) => SchematicFieldValue::Email(v.parse()?),
+                _ => return Err(mismatch()),
+            },
+            SchematicFieldType::Phone => match self {
+                Self::Text(v) => SchematicFieldValue::Phone(v.parse()?),
+                _ => return Err(mismatch()),
+            },
+            SchematicFieldType::Address => {
+                SchematicFieldValue::Address(PostalAddress::from_value(self)?)
+            }
+
+            SchematicFieldType::DateTime => SchematicFieldValue::DateTime(match self {
+                Self::DateTime(v) => *v,
+                Self::Date(v) => v.midnight().assume_utc(),
+                Self::Number(v) => OffsetDateTime::from_unix_timestamp(v.convert_i64())
+                    .map_err(|e| GlobalError::ParseError(e.to_string()))?,
+                Self::Text(v) => coerce_date_time(v.trim())?,
+                _ => return Err(mismatch()),
+            }),
+            SchematicFieldType::Date => SchematicFieldValue::Date(match self {
+                Self::Date(v) => *v,
+                Self::DateTime(v) => v.date(),
+                Self::Text(v) => {
+                    let v = v.trim();
+
+                    match Date::parse(v, DATE_FORMAT) {
+                        Ok(v) => v,
+                        Err(_) => OffsetDateTime::parse(v, &Rfc3339)?.date(),
+                    }
+                }
+                _ => return Err(mismatch()),
+            }),
+            SchematicFieldType::Time => SchematicFieldValue::Time(match self {
+                Self::Time(v) => *v,
+                Self::DateTime(v) => v.time(),
+                Self::Text(v) => {
+                    let v = v.trim();
+
+                    if let Ok(v) = Time::parse(v, TIME_FORMAT) {
+                        v
+                    } else if let Ok(v) = Time::parse(v, TIME_SUBSECOND_FORMAT) {
+                        v
+                    } else {
+                        Time::parse(v, format_description!("[hour]:[minute]"))?
+                    }
+                }
+                _ => return Err(mismatch()),
+            }),
+
+            SchematicFieldType::Reference => match self {
+                Self::Text(v) => SchematicFieldValue::Reference(v.trim().parse()?),
+                _ => return Err(mismatch()),
+            },
+            SchematicFieldType::MediaGallery => {
+                SchematicFieldValue::MediaGallery(MediaGalleryItem::list_from_value(&match self {
+                    Self::Text(v) if v.trim_start().starts_with('[') => {
+                        Self::ArrayUnknown(serde_json::from_str(v)?)
+                    }
+                    Self::Text(_) => {
+                        Self::ListString(self.coerce_string_list().ok_or_else(mismatch)?)
+                    }
+                    v => v.clone(),
+                })?)
+            }
+            SchematicFieldType::MultiReference => SchematicFieldValue::MultiReference(
+                self.coerce_string_list()
+                    .ok_or_else(mismatch)?
+                    .iter()
+                    .map(|v| v.parse())
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            SchematicFieldType::Tags => SchematicFieldValue::Tags(normalize_tags(
+                self.coerce_string_list().ok_or_else(mismatch)?,
+                false,
+            )),
+            SchematicFieldType::MultiDocument => {
+                SchematicFieldValue::ListString(self.coerce_string_list().ok_or_else(mismatch)?)
+            }
+            SchematicFieldType::Document
+            | SchematicFieldType::Image
+            | SchematicFieldType::Video
+            | SchematicFieldType::Audio => match self {
+                Self::ListNumber(v) => SchematicFieldValue::ListNumber(v.clone()),
+                _ => return Err(mismatch()),
+            },
+
+            SchematicFieldType::Array => SchematicFieldValue::Array(match self {
+                Self::ArrayUnknown(v) => v.clone(),
+                Self::ListString(v) => v.iter().map(|v| v.as_str().into()).collect(),
+                Self::ListNumber(v) => v.iter().map(|v| (*v).into()).collect(),
+                Self::Text(v) => serde_json::from_str(v)?,
+                _ => return Err(mismatch()),
+            }),
+            SchematicFieldType::Object => SchematicFieldValue::Object(match self {
+                Self::ObjectUnknown(v) => v.clone(),
+                Self::Text(v) => match serde_json::from_str(v)? {
+                    v @ serde_json::Value::Object(_) => v,
+                    _ => return Err(mismatch()),
+                },
+                _ => return Err(mismatch()),
+            }),
+            SchematicFieldType::Currency => SchematicFieldValue::Money(Money::from_value(self)?),
+            SchematicFieldType::Location => {
+                SchematicFieldValue::Location(GeoPoint::from_value(self)?)
+            }
+            SchematicFieldType::Duration => SchematicFieldValue::Duration(match self {
+                Self::Text(v) => duration::parse(v)?,
+                Self::Number(v) => {
+                    time::Duration::checked_seconds_f64(v.convert_f64()).ok_or_else(mismatch)?
+                }
+                // Durations used to be stored in Time fields.
+                Self::Time(v) => *v - Time::MIDNIGHT,
+                _ => return Err(mismatch()),
+            }),
+            SchematicFieldType::Slug => match self {
+                Self::Text(v) => SchematicFieldValue::Slug(match Slug::parse(v.trim()) {
+                    Ok(v) => v,
+                    Err(_) => Slug::from_title(v)?,
+                }),
+                _ => return Err(mismatch()),
+            },
+        })
+    }
+
+    /// A String List as is, or Text split on commas. Empty items are skipped.
+    fn coerce_string_list(&self) -> Option<Vec<String>> {
+        match self {
+            Self::ListString(v) => Some(v.clone()),
+            Self::Text(v) => Some(
+                v.split(',')
+                    .map(|v| v.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.to_string())
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+fn coerce_date_time(value: &str) -> Result<OffsetDateTime> {
+    if let Ok(v) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(v);
+    }
+
+    for format in [
+        format_description!("[year]-[month]-[day]T[hour]:[minute]"),
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond]"),
+        format_description!("[year]-[month]-[day] [hour]:[minute]"),
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]"),
+    ] {
+        if let Ok(v) = PrimitiveDateTime::parse(value, format) {
+            return Ok(v.assume_utc());
+        }
+    }
+
+    Ok(Date::parse(value, DATE_FORMAT)?.midnight().assume_utc())
+}
+
+impl SimpleValue {
+    /// Compare two values of a field the same way the server sorts them.
+    ///
+    /// Both values are first coerced into the field type (so numbers stored as text and dates
+    /// stored as text sort correctly) with [`SimpleValue::coerce_to`]. Text is compared
+    /// case-insensitively. Values which can't be coerced sort after every valid value.
+    pub fn compare(&self, other: &SimpleValue, field_type: SchematicFieldType) -> Ordering {
+        match (self.sort_key(field_type), other.sort_key(field_type)) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(&b),
+            (SortKey::Boolean(a), SortKey::Boolean(b)) => a.cmp(&b),
+            (SortKey::DateTime(a), SortKey::DateTime(b)) => a.cmp(&b),
+            (SortKey::Date(a), SortKey::Date(b)) => a.cmp(&b),
+            (SortKey::Time(a), SortKey::Time(b)) => a.cmp(&b),
+            (SortKey::Text(a), SortKey::Text(b)) | (SortKey::Invalid(a), SortKey::Invalid(b)) => a
+                .to_lowercase()
+                .cmp(&b.to_lowercase())
+                .then_with(|| a.cmp(&b)),
+            (SortKey::Invalid(_), _) => Ordering::Greater,
+            (_, SortKey::Invalid(_)) => Ordering::Less,
+            // Unreachable since both keys come from the same field type.
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn sort_key(&self, field_type: SchematicFieldType) -> SortKey {
+        let target = match field_type {
+            SchematicFieldType::Number
+            | SchematicFieldType::Boolean
+            | SchematicFieldType::DateTime
+            | SchematicFieldType::Date
+            | SchematicFieldType::Time => field_type,
+            _ => return SortKey::Text(self.to_string()),
+        };
+
+        match self.coerce_to(target) {
+            Ok(SchematicFieldValue::Number(v)) => SortKey::Number(v),
+            Ok(SchematicFieldValue::Boolean(v)) => SortKey::Boolean(v),
+            Ok(SchematicFieldValue::DateTime(v)) => SortKey::DateTime(v),
+            Ok(SchematicFieldValue::Date(v)) => SortKey::Date(v),
+            Ok(SchematicFieldValue::Time(v)) => SortKey::Time(v),
+            _ => SortKey::Invalid(self.to_string()),
+        }
+    }
+}
+
+enum SortKey {
+    Number(Number),
+    Boolean(bool),
+    DateTime(OffsetDateTime),
+    Date(Date),
+    Time(Time),
+    Text(String),
+    Invalid(String),
+}
+
+impl<'de> Deserialize<'de> for SimpleValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(SimpleValueVisitor {
+            detect_temporal: false,
+        })
+    }
+}
+
+/// Read a [`SimpleValue`] like [`SimpleValue::from_json`] does, strings in the formats
+/// [`SimpleValue::into_json`] writes are a DateTime, Date or Time instead of Text.
+///
+/// Use it with `#[serde(with = "detect_temporal")]`. Dates and times are written in those
+/// formats too, so they read back unchanged.
+pub mod detect_temporal {
+    use serde::{Deserializer, Serialize, Serializer};
+
+    use super::{SimpleValue, SimpleValueVisitor};
+
+    pub fn serialize<S: Serializer>(value: &SimpleValue, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            SimpleValue::DateTime(_) | SimpleValue::Date(_) | SimpleValue::Time(_) => {
+                value.clone().into_json().serialize(serializer)
+            }
+            v => v.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SimpleValue, D::Error> {
+        deserializer.deserialize_any(SimpleValueVisitor {
+            detect_temporal: true,
+        })
+    }
+}
+
+struct SimpleValueVisitor {
+    detect_temporal: bool,
+}
+
+impl SimpleValueVisitor {
+    fn text(&self, value: String) -> SimpleValue {
+        if self.detect_temporal {
+            parse_temporal(&value).unwrap_or(SimpleValue::Text(value))
+        } else {
+            SimpleValue::Text(value)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for SimpleValueVisitor {
+    type Value = SimpleValue;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("a text, number, boolean, array, object or null")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<SimpleValue, E> {
+        Ok(SimpleValue::Boolean(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<SimpleValue, E> {
+        NumberVisitor.visit_i64(v).map(SimpleValue::Number)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<SimpleValue, E> {
+        NumberVisitor.visit_u64(v).map(SimpleValue::Number)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<SimpleValue, E> {
+        NumberVisitor.visit_f64(v).map(SimpleValue::Number)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<SimpleValue, E> {
+        Ok(SimpleValue::ObjectUnknown(serde_json::Value::Null))
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<SimpleValue, E> {
+        self.visit_unit()
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<SimpleValue, E> {
+        Ok(self.text(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> std::result::Result<SimpleValue, E> {
+        Ok(self.text(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> std::result::Result<SimpleValue, A::Error> {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(4096));
+
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+
+        SimpleValue::from_json(serde_json::Value::Array(items)).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<SimpleValue, A::Error> {
+        Ok(SimpleValue::ObjectUnknown(serde_json::Value::deserialize(
+            MapAccessDeserializer::new(map),
+        )?))
+    }
+}
+
+/// The dates & times of [`SimpleValue`] are text in every format. It's untagged, so in binary
+/// formats where `time` writes them as numbers a list of numbers could read back as a date.
+mod as_text {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error as _, ser::Error as _};
+    use time::{
+        Date, OffsetDateTime, Time, format_description::BorrowedFormatItem,
+        macros::format_description,
+    };
+
+    /// A type written in the format `time` uses in human readable formats such as JSON.
+    pub trait Text: Sized {
+        const FORMAT: &'static [BorrowedFormatItem<'static>];
+
+        fn format(&self) -> Result<String, time::error::Format>;
+
+        fn parse(value: &str) -> Result<Self, time::error::Parse>;
+    }
+
+    impl Text for OffsetDateTime {
+        const FORMAT: &'static [BorrowedFormatItem<'static>] = format_description!(
+            "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond] \
+             [offset_hour sign:mandatory]:[offset_minute]:[offset_second]"
+        );
+
+        fn format(&self) -> Result<String, time::error::Format> {
+            OffsetDateTime::format(*self, Self::FORMAT)
+        }
+
+        fn parse(value: &str) -> Result<Self, time::error::Parse> {
+            OffsetDateTime::parse(value, Self::FORMAT)
+        }
+    }
+
+    impl Text for Date {
+        const FORMAT: &'static [BorrowedFormatItem<'static>] =
+            format_description!("[year]-[month]-[day]");
+
+        fn format(&self) -> Result<String, time::error::Format> {
+            Date::format(*self, Self::FORMAT)
+        }
+
+        fn parse(value: &str) -> Result<Self, time::error::Parse> {
+            Date::parse(value, Self::FORMAT)
+        }
+    }
+
+    impl Text for Time {
+        const FORMAT: &'static [BorrowedFormatItem<'static>] =
+            format_description!("[hour]:[minute]:[second].[subsecond]");
+
+        fn format(&self) -> Result<String, time::error::Format> {
+            Time::format(*self, Self::FORMAT)
+        }
+
+        fn parse(value: &str) -> Result<Self, time::error::Parse> {
+            Time::parse(value, Self::FORMAT)
+        }
+    }
+
+    pub fn serialize<T: Text, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.format().map_err(S::Error::custom)?)
+    }
+
+    pub fn deserialize<'de, T: Text, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let text = String::deserialize(deserializer)?;
+
+        T::parse(&text).map_err(D::Error::custom)
+    }
+}
+
+const DATE_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day]");
+const TIME_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+const TIME_SUBSECOND_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[hour]:[minute]:[second].[subsecond]");
+
+/// Parse a string in the exact formats [`SimpleValue::into_json`] writes.
+fn parse_temporal(value: &str) -> Option<SimpleValue> {
+    // Quick reject so plain text doesn't go through three parsers.
+    if !value.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    if let Ok(v) = OffsetDateTime::parse(value, &Rfc3339) {
+        Some(SimpleValue::DateTime(v))
+    } else if let Ok(v) = Date::parse(value, DATE_FORMAT) {
+        Some(SimpleValue::Date(v))
+    } else if let Ok(v) = Time::parse(value, TIME_FORMAT) {
+        Some(SimpleValue::Time(v))
+    } else {
+        Time::parse(value, TIME_SUBSECOND_FORMAT)
+            .ok()
+            .map(SimpleValue::Time)
+    }
+}
+
 impl Display for SimpleValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
@@ -376,3 +1284,245 @@ impl From<serde_json::Value> for SimpleValue {
         Self::ObjectUnknown(value)
     }
 }
+
+impl From<SchematicFieldValue> for SimpleValue {
+    fn from(value: SchematicFieldValue) -> Self {
+        match value {
+            SchematicFieldValue::Text(v) => Self::Text(v),
+            SchematicFieldValue::Address(v) => {
+                Self::ObjectUnknown(serde_json::to_value(v).unwrap())
+            }
+            SchematicFieldValue::Email(v) => Self::Text(v.into()),
+            SchematicFieldValue::Phone(v) => Self::Text(v.to_string()),
+            SchematicFieldValue::Number(v) => Self::Number(v),
+            SchematicFieldValue::Boolean(v) => Self::Boolean(v),
+            SchematicFieldValue::Url(v) => Self::Text(v.into()),
+            SchematicFieldValue::DateTime(v) => Self::DateTime(v),
+            SchematicFieldValue::Date(v) => Self::Date(v),
+            SchematicFieldValue::Time(v) => Self::Time(v),
+            SchematicFieldValue::Reference(v) => Self::Text(v.to_string()),
+            SchematicFieldValue::MultiReference(v) => {
+                Self::ListString(v.into_iter().map(|v| v.to_string()).collect())
+            }
+            SchematicFieldValue::MediaGallery(v) => Self::ArrayUnknown(
+                v.into_iter()
+                    .map(|v| serde_json::to_value(v).unwrap())
+                    .collect(),
+            ),
+            SchematicFieldValue::ListString(v) | SchematicFieldValue::Tags(v) => {
+                Self::ListString(v)
+            }
+            SchematicFieldValue::ListNumber(v) => Self::ListNumber(v),
+            SchematicFieldValue::Array(v) => Self::ArrayUnknown(v),
+            SchematicFieldValue::Object(v) => Self::ObjectUnknown(v),
+            SchematicFieldValue::Money(v) => v.into(),
+            SchematicFieldValue::Location(v) => v.into(),
+            SchematicFieldValue::Slug(v) => Self::Text(v.into()),
+            SchematicFieldValue::Duration(v) => Self::Text(duration::to_iso8601(v)),
+        }
+    }
+}
+
+/// A borrowed version of [`SimpleValue`] used on the hot read path.
+///
+/// Strings borrow from the input whenever they don't contain escape sequences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SimpleValueRef<'a> {
+    Text(#[serde(borrow)] Cow<'a, str>),
+    Number(Number),
+    Boolean(bool),
+
+    DateTime(#[serde(with = "as_text")] OffsetDateTime),
+    Date(#[serde(with = "as_text")] Date),
+    Time(#[serde(with = "as_text")] Time),
+
+    ListString(#[serde(borrow)] Vec<CowStr<'a>>),
+    ListNumber(Vec<Number>),
+
+    ArrayUnknown(Vec<serde_json::Value>),
+    ObjectUnknown(serde_json::Value),
+}
+
+impl SimpleValueRef<'_> {
+    pub fn into_owned(self) -> SimpleValue {
+        match self {
+            Self::Text(v) => SimpleValue::Text(v.into_owned()),
+            Self::Number(v) => SimpleValue::Number(v),
+            Self::Boolean(v) => SimpleValue::Boolean(v),
+            Self::DateTime(v) => SimpleValue::DateTime(v),
+            Self::Date(v) => SimpleValue::Date(v),
+            Self::Time(v) => SimpleValue::Time(v),
+            Self::ListString(v) => {
+                SimpleValue::ListString(v.into_iter().map(|v| v.0.into_owned()).collect())
+            }
+            Self::ListNumber(v) => SimpleValue::ListNumber(v),
+            Self::ArrayUnknown(v) => SimpleValue::ArrayUnknown(v),
+            Self::ObjectUnknown(v) => SimpleValue::ObjectUnknown(v),
+        }
+    }
+}
+
+impl<'a> From<&'a SimpleValue> for SimpleValueRef<'a> {
+    fn from(value: &'a SimpleValue) -> Self {
+        match value {
+            SimpleValue::Text(v) => Self::Text(Cow::Borrowed(v)),
+            SimpleValue::Number(v) => Self::Number(*v),
+            SimpleValue::Boolean(v) => Self::Boolean(*v),
+            SimpleValue::DateTime(v) => Self::DateTime(*v),
+            SimpleValue::Date(v) => Self::Date(*v),
+            SimpleValue::Time(v) => Self::Time(*v),
+            SimpleValue::ListString(v) => {
+                Self::ListString(v.iter().map(|v| CowStr(Cow::Borrowed(v))).collect())
+            }
+            SimpleValue::ListNumber(v) => Self::ListNumber(v.clone()),
+            SimpleValue::ArrayUnknown(v) => Self::ArrayUnknown(v.clone()),
+            SimpleValue::ObjectUnknown(v) => Self::ObjectUnknown(v.clone()),
+        }
+    }
+}
+
+/// A string which borrows from the input when possible.
+///
+/// `Cow<str>` only borrows when it's marked with `#[serde(borrow)]` directly, which isn't
+/// possible for the items of a `Vec` or the keys of a `HashMap`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CowStr<'a>(#[serde(borrow)] pub Cow<'a, str>);
+
+impl CowStr<'_> {
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Cow::Borrowed(_))
+    }
+}
+
+impl Deref for CowStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<str> for CowStr<'_> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CowStr<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.fmt(f)
+    }
+}
+
+impl<'a> From<&'a str> for CowStr<'a> {
+    fn from(value: &'a str) -> Self {
+        Self(Cow::Borrowed(value))
+    }
+}
+
+#[cfg(feature = "sqlx")]
+const _: () = {
+    use std::result::Result;
+
+    use sqlx::{
+        Decode, Encode, Postgres, Type, ValueRef,
+        encode::IsNull,
+        error::BoxDynError,
+        postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+        types::Json,
+    };
+
+    /// Integers are written as INT8 and Floats as FLOAT8, either can be read from any integer
+    /// or float column. Postgres has no unsigned INT8, UIntegers above [`i64::MAX`] are written
+    /// as FLOAT8.
+    impl Type<Postgres> for Number {
+        fn type_info() -> PgTypeInfo {
+            <f64 as Type<Postgres>>::type_info()
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            <i16 as Type<Postgres>>::compatible(ty)
+                || <i32 as Type<Postgres>>::compatible(ty)
+                || <i64 as Type<Postgres>>::compatible(ty)
+                || <f32 as Type<Postgres>>::compatible(ty)
+                || <f64 as Type<Postgres>>::compatible(ty)
+        }
+    }
+
+    impl Encode<'_, Postgres> for Number {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+            match *self {
+                Self::Byte(v) => Encode::<Postgres>::encode_by_ref(&i64::from(v), buf),
+                Self::Integer(v) => Encode::<Postgres>::encode_by_ref(&v, buf),
+                Self::UInteger(v) => match i64::try_from(v) {
+                    Ok(v) => Encode::<Postgres>::encode_by_ref(&v, buf),
+                    Err(_) => Encode::<Postgres>::encode_by_ref(&(v as f64), buf),
+                },
+                Self::Float(v) => Encode::<Postgres>::encode_by_ref(&v, buf),
+            }
+        }
+
+        fn produces(&self) -> Option<PgTypeInfo> {
+            Some(match self {
+                Self::Byte(_) | Self::Integer(_) => <i64 as Type<Postgres>>::type_info(),
+                Self::UInteger(v) if i64::try_from(*v).is_ok() => {
+                    <i64 as Type<Postgres>>::type_info()
+                }
+                Self::UInteger(_) | Self::Float(_) => <f64 as Type<Postgres>>::type_info(),
+            })
+        }
+    }
+
+    impl Decode<'_, Postgres> for Number {
+        fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+            let ty = value.type_info().into_owned();
+
+            Ok(if <i16 as Type<Postgres>>::compatible(&ty) {
+                Self::Integer(<i16 as Decode<Postgres>>::decode(value)?.into())
+            } else if <i32 as Type<Postgres>>::compatible(&ty) {
+                Self::Integer(<i32 as Decode<Postgres>>::decode(value)?.into())
+            } else if <i64 as Type<Postgres>>::compatible(&ty) {
+                Self::Integer(<i64 as Decode<Postgres>>::decode(value)?)
+            } else if <f32 as Type<Postgres>>::compatible(&ty) {
+                Self::Float(<f32 as Decode<Postgres>>::decode(value)?.into())
+            } else {
+                Self::Float(<f64 as Decode<Postgres>>::decode(value)?)
+            })
+        }
+    }
+
+    /// Stored as JSONB, read from JSON or JSONB.
+    impl Type<Postgres> for SimpleValue {
+        fn type_info() -> PgTypeInfo {
+            <Json<Self> as Type<Postgres>>::type_info()
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            <Json<Self> as Type<Postgres>>::compatible(ty)
+        }
+    }
+
+    impl PgHasArrayType for SimpleValue {
+        fn array_type_info() -> PgTypeInfo {
+            <Json<Self> as PgHasArrayType>::array_type_info()
+        }
+
+        fn array_compatible(ty: &PgTypeInfo) -> bool {
+            <Json<Self> as PgHasArrayType>::array_compatible(ty)
+        }
+    }
+
+    impl Encode<'_, Postgres> for SimpleValue {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+            Encode::<Postgres>::encode(Json(self), buf)
+        }
+    }
+
+    impl Decode<'_, Postgres> for SimpleValue {
+        fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+            Ok(<Json<Self> as Decode<Postgres>>::decode(value)?.0)
+        }
+    }
+};
@@ -0,0 +1,47 @@
+//! wasm-bindgen functions for the browser editor, so it checks filters, queries and values
+//! with the same code as the API.
+//!
+//! Each takes JSON, parses it [strictly](ParseMode::Strict) like the API gateway, and returns
+//! what was parsed written back as JSON, eg. a Date value in its canonical format. Errors are
+//! thrown with the message the API would respond with.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    filter::{Filter, FilterExpr},
+    parse::{self, ParseMode},
+    request::CmsQuery,
+    value::SimpleValue,
+};
+
+#[wasm_bindgen(js_name = parseFilter)]
+pub fn parse_filter(json: &str) -> Result<String, JsError> {
+    let filter: Filter = parse::from_str(json, ParseMode::Strict)?;
+    Ok(serde_json::to_string(&filter)?)
+}
+
+/// A filter with `and`, `or` and `not`.
+#[wasm_bindgen(js_name = parseFilterExpr)]
+pub fn parse_filter_expr(json: &str) -> Result<String, JsError> {
+    let expr: FilterExpr = parse::from_str(json, ParseMode::Strict)?;
+    Ok(serde_json::to_string(&expr)?)
+}
+
+#[wasm_bindgen(js_name = parseQuery)]
+pub fn parse_query(json: &str) -> Result<String, JsError> {
+    let query: CmsQuery = parse::from_str(json, ParseMode::Strict)?;
+    Ok(serde_json::to_string(&query)?)
+}
+
+/// The query string the API expects for the query, without the leading `?`.
+#[wasm_bindgen(js_name = queryString)]
+pub fn query_string(json: &str) -> Result<String, JsError> {
+    let query: CmsQuery = parse::from_str(json, ParseMode::Strict)?;
+    Ok(query.to_query_string())
+}
+
+#[wasm_bindgen(js_name = parseValue)]
+pub fn parse_value(json: &str) -> Result<String, JsError> {
+    let value = SimpleValue::from_json(serde_json::from_str(json)?)?;
+    Ok(value.into_json().to_string())
+}
@@ -0,0 +1,114 @@
+use serde_json::json;
+use webby_global_common::{
+    addon::{AddonManifest, AddonSchema, AddonScope, AddonVersion},
+    permissions::{NamespaceGrant, Permission},
+    schema::{
+        SchemaFieldMap, SchematicField, SchematicFieldKey, SchematicFieldType,
+        computed::FieldSource,
+    },
+};
+
+fn manifest() -> AddonManifest {
+    serde_json::from_value(json!({
+        "id": "0190c8a4-6c1e-7bb0-8f4a-1d2e3f405060",
+        "name": "Shop",
+        "version": "1.2.3",
+        "scopes": [
+            { "type": "Collections", "value": { "namespace": "Shop", "permissions": ["Read", "Insert"] } },
+            { "type": "Uploads", "value": ["Read"] }
+        ],
+        "webhooks": [
+            { "url": "https://shop.example.com/hook", "events": ["RowCreated"] }
+        ],
+        "widgets": [
+            { "key": "cart", "name": "Cart", "entry": "widgets/cart.js", "placement": "Page" }
+        ],
+        "settings": [
+            { "key": "currency", "label": "Currency", "fieldType": "Text" }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn parses_versions() {
+    let version = AddonVersion::parse("v1.2.3").unwrap();
+
+    assert_eq!(version, AddonVersion::new(1, 2, 3));
+    assert_eq!(version.to_string(), "1.2.3");
+    assert!(AddonVersion::new(1, 10, 0) > version);
+    assert!(AddonVersion::parse("1.2").is_err());
+    assert!(AddonVersion::parse("1.2.3.4").is_err());
+}
+
+#[test]
+fn validates_manifests() {
+    let mut manifest = manifest();
+
+    manifest.validate().unwrap();
+    assert_eq!(manifest.version, AddonVersion::new(1, 2, 3));
+
+    manifest.schemas.push(AddonSchema {
+        id: String::from("orders"),
+        display_name: String::from("Orders"),
+        primary_field: String::from("title"),
+        version: Default::default(),
+        fields: SchemaFieldMap::from([(
+            SchematicFieldKey::from("title"),
+            SchematicField {
+                display_name: String::from("Title"),
+                sortable: true,
+                is_deleted: false,
+                system_field: false,
+                field_type: SchematicFieldType::Text,
+                index: 0,
+                referenced_schema: None,
+                default_value: None,
+                constraints: Default::default(),
+                localized: false,
+                source: FieldSource::Stored,
+            },
+        )]),
+    });
+    manifest.validate().unwrap();
+
+    let mut invalid = manifest.clone();
+    invalid.schemas[0].primary_field = String::from("missing");
+    assert!(invalid.validate().is_err());
+
+    let mut invalid = manifest.clone();
+    invalid.schemas.push(invalid.schemas[0].clone());
+    assert!(invalid.validate().is_err());
+
+    let mut invalid = manifest.clone();
+    invalid.webhooks[0].url = "http://shop.example.com/hook".parse().unwrap();
+    assert!(invalid.validate().is_err());
+
+    let mut invalid = manifest.clone();
+    invalid.scopes.remove(0);
+    assert!(invalid.validate().is_err());
+
+    let mut invalid = manifest.clone();
+    invalid.widgets[0].entry = String::from("../secret.js");
+    assert!(invalid.validate().is_err());
+
+    let mut invalid = manifest.clone();
+    invalid.settings.push(invalid.settings[0].clone());
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn lists_new_scopes() {
+    let previous = manifest();
+    let mut next = manifest();
+
+    next.scopes
+        .push(AddonScope::Members(vec![Permission::Read]));
+    next.scopes[0] = AddonScope::Collections(NamespaceGrant {
+        namespace: String::from("Shop"),
+        permissions: vec![Permission::Read],
+    });
+
+    assert_eq!(next.new_scopes(&previous).len(), 2);
+    assert!(next.new_scopes(&next).is_empty());
+}
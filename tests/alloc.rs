@@ -0,0 +1,92 @@
+//! Counts the allocations made while parsing, so it's the only test in its binary.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde::de::DeserializeSeed;
+use webby_global_common::schema::{FieldKeys, SchematicFieldKey};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(func: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let value = func();
+
+    (value, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+#[test]
+fn field_keys_are_shared() {
+    const ROWS: usize = 1000;
+    const KEYS: usize = 8;
+
+    let names = (0..KEYS).map(|i| format!("field{i}")).collect::<Vec<_>>();
+    let keys = FieldKeys::new(names.iter().map(String::as_str));
+    let row = format!(
+        "{{{}}}",
+        (0..KEYS)
+            .map(|i| format!(r#""field{i}":{i}"#))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    // Only the maps allocate, the names are the schema's.
+    let (rows, count) = allocations(|| {
+        (0..ROWS)
+            .map(|_| keys.deserialize(&mut serde_json::Deserializer::from_str(&row)))
+            .collect::<Result<Vec<_>, _>>()
+    });
+    let rows = rows.unwrap();
+
+    assert_eq!(rows.len(), ROWS);
+    assert!(count < ROWS * KEYS / 2, "{count} allocations");
+
+    let name = |row: &HashMap<SchematicFieldKey, _>| {
+        row.get_key_value("field3").unwrap().0.as_str().as_ptr()
+    };
+
+    assert_eq!(name(&rows[0]), name(&rows[ROWS - 1]));
+
+    // Names which aren't in the schema aren't kept.
+    let row = keys
+        .deserialize(&mut serde_json::Deserializer::from_str(
+            r#"{"other":1,"_id":"a"}"#,
+        ))
+        .unwrap();
+
+    assert!(matches!(
+        row.get_key_value("other"),
+        Some((SchematicFieldKey::Other(_), _))
+    ));
+    assert!(row.contains_key(&SchematicFieldKey::Id));
+    assert_ne!(
+        keys.key("other").as_str().as_ptr(),
+        keys.key("other").as_str().as_ptr()
+    );
+
+    let (key, count) = allocations(|| SchematicFieldKey::from_static("static"));
+
+    assert_eq!(key, "static");
+    assert_eq!(key, SchematicFieldKey::from("static"));
+    assert_eq!(count, 0);
+    assert_eq!(SchematicFieldKey::from_static("_id"), SchematicFieldKey::Id);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "test-utils")]
+
+use proptest::prelude::*;
+
+use webby_global_common::{
+    filter::Filter,
+    schema::{Schematic, SchematicFieldValue},
+    testing::{assert_roundtrip, assert_roundtrip_eq, assert_value_roundtrip},
+    value::SimpleValue,
+};
+
+proptest! {
+    #[test]
+    fn simple_value_roundtrip(value: SimpleValue) {
+        assert_value_roundtrip(&value);
+    }
+
+    #[test]
+    fn field_value_roundtrip(value: SchematicFieldValue) {
+        assert_roundtrip_eq(&value);
+    }
+
+    #[test]
+    fn schematic_roundtrip(schema: Schematic) {
+        assert_roundtrip(&schema);
+        prop_assert!(schema.fields.contains_key(schema.primary_field.as_str()));
+    }
+
+    #[test]
+    fn filter_roundtrip(filter: Filter) {
+        assert_roundtrip_eq(&filter);
+    }
+}
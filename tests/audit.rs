@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use time::macros::datetime;
+use uuid::Uuid;
+use webby_global_common::{
+    audit::{self, AuditEntry, AuditTarget},
+    id::{AddonUuid, AnyPublicId, MemberPublicId, SchemaDataPublicId},
+    schema::{Operations, SchematicFieldKey},
+    testing::assert_roundtrip,
+    uuid::{CollectionName, UuidType},
+    value::SimpleValue,
+};
+
+fn fields(values: &[(&str, &str)]) -> HashMap<SchematicFieldKey, SimpleValue> {
+    values
+        .iter()
+        .map(|(k, v)| {
+            (
+                SchematicFieldKey::from(*k),
+                SimpleValue::Text(v.to_string()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn audit_diffs() {
+    let before = fields(&[("title", "Hello"), ("body", "Text")]);
+    let after = fields(&[("title", "Hello World"), ("body", "Text"), ("tag", "news")]);
+
+    let changes = audit::diff(Some(&before), Some(&after));
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].field, SchematicFieldKey::from("tag"));
+    assert!(changes[0].is_added());
+    assert_eq!(changes[1].field, SchematicFieldKey::from("title"));
+
+    let inserted = audit::diff(None, Some(&after));
+    assert_eq!(inserted.len(), 3);
+    assert!(inserted.iter().all(|v| v.is_added()));
+
+    let removed = audit::diff(Some(&before), None);
+    assert!(removed.iter().all(|v| v.is_removed()));
+    assert!(audit::diff(Some(&before), Some(&before)).is_empty());
+}
+
+#[test]
+fn audit_entries() {
+    let collection = CollectionName::parse("orders").unwrap();
+    let row = SchemaDataPublicId::new();
+    let request_id = Uuid::now_v7();
+
+    let mut entry = AuditEntry::row_change(
+        UuidType::Addon(AddonUuid::new()),
+        Operations::Update,
+        collection.clone(),
+        row,
+        Some(&fields(&[("card", "4242"), ("status", "Open")])),
+        Some(&fields(&[("card", "5555"), ("status", "Paid")])),
+        datetime!(2024-05-01 12:00 UTC),
+    )
+    .with_request_id(request_id);
+
+    entry.redact(&[SchematicFieldKey::from("card")]);
+
+    let card = entry.change_of(&SchematicFieldKey::from("card")).unwrap();
+    assert_eq!(
+        card.after,
+        Some(SimpleValue::Text(String::from("[redacted]")))
+    );
+
+    let status = entry.change_of(&SchematicFieldKey::from("status")).unwrap();
+    assert_eq!(status.after, Some(SimpleValue::Text(String::from("Paid"))));
+
+    let entry = assert_roundtrip(&entry);
+    let value = serde_json::to_value(&entry).unwrap();
+
+    assert_eq!(value["action"], "Update");
+    assert_eq!(
+        value["target"],
+        json!({ "type": "Row", "value": { "collection": collection, "id": row } })
+    );
+    assert_eq!(value["requestId"], json!(request_id));
+
+    let member = AuditEntry::new(
+        UuidType::System,
+        Operations::Remove,
+        AuditTarget::Entity(AnyPublicId::from(MemberPublicId::new())),
+        datetime!(2024-05-01 12:00 UTC),
+    );
+
+    let value = serde_json::to_value(assert_roundtrip(&member)).unwrap();
+    assert!(value.get("changes").is_none());
+    assert_eq!(value["actor"], "system");
+}
@@ -0,0 +1,78 @@
+use serde_json::json;
+use time::{Duration, macros::datetime};
+use webby_global_common::{
+    auth::{ApiKey, ApiKeyToken, Scope, constant_time_eq},
+    id::ApiKeyId,
+    schema::Operations,
+};
+
+#[test]
+fn verifies_api_keys() {
+    let now = datetime!(2024-05-01 12:00 UTC);
+    let token = ApiKeyToken::parse("wbk_3f9a1c2e_s3cr3tV4lue").unwrap();
+    let key = ApiKey::new(
+        ApiKeyId::new(),
+        &token,
+        Scope::read_only(),
+        Some(now + Duration::days(30)),
+    );
+
+    assert_eq!(token.prefix(), "3f9a1c2e");
+    assert_eq!(token.expose(), "wbk_3f9a1c2e_s3cr3tV4lue");
+    assert_ne!(key.hashed_secret, "s3cr3tV4lue");
+
+    key.verify(&token, now).unwrap();
+    assert!(key.verify(&token, now + Duration::days(30)).is_err());
+
+    let wrong = ApiKeyToken::new("3f9a1c2e", "s3cr3tV4luf").unwrap();
+    assert!(key.verify(&wrong, now).is_err());
+
+    let other_prefix = ApiKeyToken::new("00000000", "s3cr3tV4lue").unwrap();
+    assert!(key.verify(&other_prefix, now).is_err());
+
+    for malformed in [
+        "3f9a1c2e_s3cr3t",
+        "wbk_3f9a1c2e",
+        "wbk__s3cr3t",
+        "wbk_a_b_c",
+    ] {
+        assert!(ApiKeyToken::parse(malformed).is_err(), "{malformed}");
+    }
+
+    assert!(constant_time_eq(b"abc", b"abc"));
+    assert!(!constant_time_eq(b"abc", b"abd"));
+    assert!(!constant_time_eq(b"abc", b"abcd"));
+}
+
+#[test]
+fn api_keys_only_reveal_the_prefix() {
+    let token = ApiKeyToken::new("3f9a1c2e", "s3cr3tV4lue").unwrap();
+    let key = ApiKey::new(ApiKeyId::new(), &token, vec![Scope::Admin], None);
+
+    assert_eq!(token.to_string(), "wbk_3f9a1c2e_…");
+    assert_eq!(key.to_string(), "wbk_3f9a1c2e_…");
+    assert!(!format!("{token:?}").contains("s3cr3t"));
+}
+
+#[test]
+fn api_key_scopes() {
+    let token = ApiKeyToken::new("3f9a1c2e", "s3cr3tV4lue").unwrap();
+    let mut key = ApiKey::new(ApiKeyId::new(), &token, Scope::read_only(), None);
+
+    assert!(key.allows(Operations::Find));
+    assert!(!key.allows(Operations::Insert));
+    assert!(!key.has_scope(Scope::ManageSchemas));
+
+    key.scopes = vec![Scope::Admin];
+    assert!(key.allows(Operations::Truncate));
+    assert!(key.has_scope(Scope::ManageMembers));
+
+    assert_eq!(
+        serde_json::to_value(Scope::Cms(Operations::BulkInsert)).unwrap(),
+        json!({ "type": "Cms", "value": "BulkInsert" })
+    );
+    assert_eq!(
+        serde_json::to_value(Scope::ManageSchemas).unwrap(),
+        json!({ "type": "ManageSchemas" })
+    );
+}
@@ -0,0 +1,311 @@
+use std::collections::{BTreeMap, HashMap};
+
+use time::{
+    Duration,
+    macros::{datetime, offset},
+};
+use webby_global_common::{
+    automation::{
+        Action, Automation, Condition, Schedule, Step, StepKind, Trigger, cron::CronSchedule,
+    },
+    events::WebhookEventType,
+    expr::Expression,
+    filter::{Filter, FilterConditionType, FilterExpr},
+    id::{AutomationPublicId, FormPublicId},
+    schema::SchematicFieldKey,
+    testing::assert_roundtrip,
+    uuid::CollectionName,
+    value::SimpleValue,
+};
+
+fn automation() -> Automation {
+    Automation {
+        id: AutomationPublicId::new(),
+        name: String::from("Large leads"),
+        enabled: true,
+        trigger: Trigger::FormSubmitted {
+            form: FormPublicId::new(),
+        },
+        condition: Some(Condition::Filter(FilterExpr::Condition(Filter::new(
+            "email",
+            FilterConditionType::Cont,
+            "@",
+        )))),
+        start: Some(String::from("large")),
+        steps: vec![
+            Step::branch(
+                "large",
+                Condition::Expression(Expression::parse("budget >= 1000").unwrap()),
+                Some(String::from("email")),
+                Some(String::from("tag")),
+            ),
+            Step::action(
+                "email",
+                Action::SendEmail {
+                    to: vec![String::from("sales@example.com")],
+                    subject: String::from("New lead: {{name}}"),
+                    body: String::from("{{name}} has a budget of {{budget}}"),
+                },
+                Some(String::from("wait")),
+            ),
+            Step::action(
+                "wait",
+                Action::Delay {
+                    duration: Duration::days(1),
+                },
+                Some(String::from("crm")),
+            ),
+            Step::action(
+                "crm",
+                Action::CallWebhook {
+                    url: "https://crm.example.com/leads".parse().unwrap(),
+                    headers: BTreeMap::from([(
+                        String::from("Authorization"),
+                        String::from("Bearer token"),
+                    )]),
+                    body: None,
+                },
+                None,
+            ),
+            Step::action(
+                "tag",
+                Action::UpdateRow {
+                    collection: None,
+                    filter: None,
+                    values: HashMap::from([(
+                        SchematicFieldKey::from("label"),
+                        Expression::parse("concat('small: ', name)").unwrap(),
+                    )]),
+                },
+                None,
+            ),
+        ],
+    }
+}
+
+fn row(budget: i64) -> HashMap<SchematicFieldKey, SimpleValue> {
+    HashMap::from([
+        (SchematicFieldKey::from("name"), SimpleValue::from("Jane")),
+        (
+            SchematicFieldKey::from("email"),
+            SimpleValue::from("jane@example.com"),
+        ),
+        (SchematicFieldKey::from("budget"), SimpleValue::from(budget)),
+    ])
+}
+
+/// The ids of the steps a run goes through.
+fn run(automation: &Automation, fields: &HashMap<SchematicFieldKey, SimpleValue>) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut step = automation.first_step(fields).unwrap();
+
+    while let Some(current) = step {
+        ids.push(current.id.clone());
+        step = automation.next_step(current, fields).unwrap();
+    }
+
+    ids
+}
+
+#[test]
+fn walks_the_graph() {
+    let automation = automation();
+
+    automation.validate().unwrap();
+    assert_roundtrip(&automation);
+
+    assert_eq!(
+        run(&automation, &row(5000)),
+        ["large", "email", "wait", "crm"]
+    );
+    assert_eq!(run(&automation, &row(10)), ["large", "tag"]);
+
+    // The condition isn't met.
+    let mut fields = row(5000);
+    fields.insert(SchematicFieldKey::from("email"), SimpleValue::from("none"));
+
+    assert!(run(&automation, &fields).is_empty());
+}
+
+#[test]
+fn validates_the_graph() {
+    let invalid = |change: fn(&mut Automation)| {
+        let mut automation = automation();
+        change(&mut automation);
+        automation.validate().is_err()
+    };
+
+    assert!(invalid(|v| v.name = String::new()));
+    assert!(invalid(|v| v.start = Some(String::from("missing"))));
+    assert!(invalid(|v| v.steps[1].id = String::from("large")));
+    // crm -> large -> email -> wait -> crm
+    assert!(invalid(|v| {
+        v.steps[3].kind = StepKind::Action {
+            action: Action::Delay {
+                duration: Duration::hours(1),
+            },
+            next: Some(String::from("large")),
+        }
+    }));
+    assert!(invalid(|v| {
+        v.steps[2].kind = StepKind::Action {
+            action: Action::Delay {
+                duration: Duration::ZERO,
+            },
+            next: None,
+        }
+    }));
+    assert!(invalid(|v| {
+        v.condition = Some(Condition::Expression(Expression::call("shout", [])))
+    }));
+    assert!(invalid(|v| {
+        v.steps[4].kind = StepKind::Action {
+            action: Action::UpdateRow {
+                collection: None,
+                filter: Some(FilterExpr::And(Vec::new())),
+                values: HashMap::from([(
+                    SchematicFieldKey::from("label"),
+                    Expression::literal("x"),
+                )]),
+            },
+            next: None,
+        }
+    }));
+    assert!(invalid(|v| {
+        v.trigger = Trigger::Schedule(Schedule {
+            cron: CronSchedule::parse("@daily").unwrap(),
+            time_zone: Some(String::from("Mars/Olympus_Mons")),
+        })
+    }));
+}
+
+#[test]
+fn triggers() {
+    let trigger = Trigger::RowUpdated {
+        collection: CollectionName::from("orders"),
+        fields: vec![SchematicFieldKey::from("status")],
+    };
+
+    assert_eq!(trigger.event_type(), Some(WebhookEventType::RowUpdated));
+    assert_eq!(trigger.collection(), Some(&CollectionName::from("orders")));
+    assert!(trigger.watches_update(&[SchematicFieldKey::from("status")]));
+    assert!(!trigger.watches_update(&[SchematicFieldKey::from("total")]));
+
+    assert_eq!(
+        serde_json::to_value(&trigger).unwrap(),
+        serde_json::json!({
+            "type": "RowUpdated",
+            "value": { "collection": "orders", "fields": ["status"] },
+        })
+    );
+    assert_roundtrip(&Trigger::WebhookReceived {
+        key: String::from("abc"),
+    });
+}
+
+#[test]
+fn parses_cron() {
+    let next = |cron: &str, after| CronSchedule::parse(cron).unwrap().next_after(after);
+
+    assert_eq!(
+        next("*/15 * * * *", datetime!(2024-03-01 10:07:30)),
+        Some(datetime!(2024-03-01 10:15))
+    );
+    assert_eq!(
+        next("0 9 * * MON-FRI", datetime!(2024-03-01 09:00)),
+        // 2024-03-01 is a Friday.
+        Some(datetime!(2024-03-04 09:00))
+    );
+    assert_eq!(
+        next("30 23 31 * *", datetime!(2024-04-01 00:00)),
+        Some(datetime!(2024-05-31 23:30))
+    );
+    assert_eq!(
+        next("@yearly", datetime!(2024-06-01 00:00)),
+        Some(datetime!(2025-01-01 00:00))
+    );
+    assert_eq!(
+        next("0 0 29 FEB *", datetime!(2024-03-01 00:00)),
+        Some(datetime!(2028-02-29 00:00))
+    );
+    // Either the 1st or a Sunday.
+    assert_eq!(
+        next("0 0 1 * 7", datetime!(2024-03-01 00:00)),
+        Some(datetime!(2024-03-03 00:00))
+    );
+    assert_eq!(next("0 0 31 2 *", datetime!(2024-03-01 00:00)), None);
+
+    assert!(
+        CronSchedule::parse("0 12 * * *")
+            .unwrap()
+            .matches(datetime!(2024-03-01 12:00:59))
+    );
+
+    for invalid in [
+        "",
+        "* * * *",
+        "60 * * * *",
+        "* 24 * * *",
+        "* * 0 * *",
+        "* * * 13 *",
+        "* * * * 8",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "* * * FOO *",
+        "@often",
+    ] {
+        assert!(CronSchedule::parse(invalid).is_err(), "{invalid}");
+    }
+
+    assert_roundtrip(&CronSchedule::parse("0 9 * * MON-FRI").unwrap());
+}
+
+#[test]
+fn cron_steps_restrict_days() {
+    let matches = |cron: &str, at| CronSchedule::parse(cron).unwrap().matches(at);
+
+    // Odd days of the month, on any day of the week.
+    assert!(matches("0 0 */2 * *", datetime!(2024-03-01 00:00)));
+    assert!(!matches("0 0 */2 * *", datetime!(2024-03-02 00:00)));
+    assert_eq!(
+        CronSchedule::parse("0 0 */2 * *")
+            .unwrap()
+            .next_after(datetime!(2024-03-01 00:00)),
+        Some(datetime!(2024-03-03 00:00))
+    );
+
+    // Sundays, Tuesdays, Thursdays and Saturdays, on any day of the month.
+    assert!(matches("0 0 * * */2", datetime!(2024-03-02 00:00)));
+    assert!(!matches("0 0 * * */2", datetime!(2024-03-01 00:00)));
+    assert!(!matches("0 0 * * */2", datetime!(2024-03-04 00:00)));
+
+    // Both restricted, so either one.
+    assert!(matches("0 0 */2 * */2", datetime!(2024-03-02 00:00)));
+    assert!(matches("0 0 */2 * */2", datetime!(2024-03-03 00:00)));
+    assert!(!matches("0 0 */2 * */2", datetime!(2024-03-04 00:00)));
+    assert!(matches("0 0 */2 * MON", datetime!(2024-03-04 00:00)));
+    assert!(!matches("0 0 */2 * MON", datetime!(2024-03-02 00:00)));
+}
+
+#[test]
+fn schedules_in_time_zones() {
+    let schedule = Schedule {
+        cron: CronSchedule::parse("0 9 * * *").unwrap(),
+        time_zone: Some(String::from("America/New_York")),
+    };
+
+    // Summer time ends on 2024-11-03.
+    assert_eq!(
+        schedule
+            .next_after(datetime!(2024-11-02 14:00 UTC))
+            .unwrap(),
+        Some(datetime!(2024-11-03 09:00 -5))
+    );
+    assert_eq!(
+        schedule
+            .next_after(datetime!(2024-07-01 12:00 UTC))
+            .unwrap()
+            .map(|v| v.offset()),
+        Some(offset!(-4))
+    );
+}
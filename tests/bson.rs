@@ -0,0 +1,108 @@
+#![cfg(feature = "bson")]
+
+use std::collections::HashMap;
+
+use bson::{Bson, Document, doc, oid::ObjectId, spec::BinarySubtype};
+use time::macros::{date, datetime, time};
+use uuid::Uuid;
+use webby_global_common::{
+    error::GlobalError,
+    response::{CmsRowResponse, RowMeta},
+    schema::SchematicFieldKey,
+    value::{Number, SimpleValue},
+};
+
+#[test]
+fn values_keep_their_type() {
+    let values = [
+        SimpleValue::Text(String::from("text")),
+        SimpleValue::Number(Number::Byte(5)),
+        SimpleValue::Number(Number::Integer(5)),
+        SimpleValue::Number(Number::Float(1.5)),
+        SimpleValue::Boolean(true),
+        SimpleValue::DateTime(datetime!(2024-03-01 12:30:15.123 UTC)),
+        SimpleValue::Date(date!(2024 - 03 - 01)),
+        SimpleValue::Time(time!(12:30)),
+        SimpleValue::ListString(vec![String::from("a")]),
+        SimpleValue::ListNumber(vec![Number::Byte(1), Number::Integer(-1)]),
+        SimpleValue::Number(Number::UInteger(u64::MAX)),
+        SimpleValue::ArrayUnknown(vec![serde_json::json!(1), serde_json::json!("a")]),
+        SimpleValue::ObjectUnknown(serde_json::json!({ "a": [1, { "b": null }] })),
+    ];
+
+    for value in values {
+        let bson = Bson::from(value.clone());
+
+        assert_eq!(SimpleValue::try_from(bson).unwrap(), value);
+    }
+
+    assert!(matches!(
+        Bson::from(SimpleValue::DateTime(datetime!(2024-03-01 12:30 UTC))),
+        Bson::DateTime(_)
+    ));
+    assert_eq!(
+        Bson::from(SimpleValue::Number(Number::Integer(5))),
+        Bson::Int64(5)
+    );
+    assert_eq!(
+        Bson::from(SimpleValue::Number(Number::UInteger(5))),
+        Bson::Int64(5)
+    );
+    assert!(matches!(
+        Bson::from(SimpleValue::Number(Number::UInteger(u64::MAX))),
+        Bson::Decimal128(_)
+    ));
+    assert!(matches!(
+        SimpleValue::try_from(Bson::Null),
+        Err(GlobalError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn ids_are_read_as_text() {
+    let id = Uuid::now_v7();
+    let object_id = ObjectId::new();
+
+    assert_eq!(
+        SimpleValue::try_from(Bson::from(bson::Uuid::from(id))).unwrap(),
+        SimpleValue::Text(id.to_string())
+    );
+    assert_eq!(
+        SimpleValue::try_from(Bson::ObjectId(object_id)).unwrap(),
+        SimpleValue::Text(object_id.to_hex())
+    );
+}
+
+#[test]
+fn rows_become_documents() {
+    let id = Uuid::now_v7();
+    let row = CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::from([
+            (SchematicFieldKey::Id, SimpleValue::Text(id.to_string())),
+            (
+                SchematicFieldKey::from("title"),
+                SimpleValue::Text(String::from("Hello")),
+            ),
+        ]),
+        meta: Some(RowMeta::new(datetime!(2024-03-01 12:30 UTC), None)),
+        seo: None,
+    };
+
+    let document = Document::try_from(row.clone()).unwrap();
+
+    match document.get("_id") {
+        Some(Bson::Binary(v)) => assert_eq!(v.subtype, BinarySubtype::Uuid),
+        v => panic!("{v:?}"),
+    }
+
+    assert_eq!(document.get_str("title").unwrap(), "Hello");
+    assert!(document.contains_key("_meta"));
+    assert!(!document.contains_key("_files"));
+    assert_eq!(CmsRowResponse::try_from(document).unwrap(), row);
+
+    let row = CmsRowResponse::try_from(doc! { "title": "Hello", "empty": null }).unwrap();
+
+    assert_eq!(row.fields.len(), 1);
+    assert!(row.meta.is_none());
+}
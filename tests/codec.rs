@@ -0,0 +1,141 @@
+#![cfg(feature = "codec")]
+
+use std::{collections::HashMap, path::PathBuf};
+
+use time::macros::{date, datetime, time};
+use webby_global_common::{
+    codec::{self, FORMAT_VERSION},
+    filter::Filter,
+    response::CmsRowResponse,
+    schema::{Schematic, SchematicFieldKey},
+    testing::{assert_codec_golden, fixture_dir, load_fixture, load_fixture_value},
+    value::{Number, SimpleValue},
+};
+
+fn fixtures() -> PathBuf {
+    fixture_dir(env!("CARGO_MANIFEST_DIR"))
+}
+
+// Maps in the fixtures have a single key since `HashMap`s don't keep an order.
+
+#[test]
+fn simple_value_golden() {
+    let values: Vec<SimpleValue> = assert_codec_golden(fixtures(), "simple_value");
+
+    assert_eq!(
+        values,
+        load_fixture::<Vec<SimpleValue>>(fixtures(), "simple_value")
+    );
+}
+
+#[test]
+fn filter_golden() {
+    assert_codec_golden::<Vec<Filter>>(fixtures(), "filter");
+}
+
+#[test]
+fn row_golden() {
+    let row: CmsRowResponse = assert_codec_golden(fixtures(), "cms_row");
+
+    assert_eq!(row.meta.unwrap().revision, 3);
+}
+
+#[test]
+fn schematic_golden() {
+    let schema: Schematic = assert_codec_golden(fixtures(), "schematic");
+
+    assert_eq!(schema.primary_field, "title");
+}
+
+#[test]
+fn decodes_version_1() {
+    let bytes = hex::decode(include_str!("fixtures/legacy/schematic.cbor.hex").trim()).unwrap();
+
+    assert_eq!(bytes[0], 1);
+
+    let schema: Schematic = codec::decode(&bytes).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&schema).unwrap(),
+        load_fixture_value(fixtures(), "schematic")
+    );
+}
+
+#[test]
+fn keeps_untagged_values_apart() {
+    // Dates are text, so a list of numbers which looks like one stays a list.
+    let values = vec![
+        SimpleValue::ListNumber(vec![Number::Integer(2024), Number::Integer(60)]),
+        SimpleValue::ListNumber(vec![
+            Number::Integer(12),
+            Number::Integer(30),
+            Number::Integer(0),
+            Number::Integer(0),
+        ]),
+        SimpleValue::DateTime(datetime!(2024-03-01 12:00 UTC)),
+    ];
+
+    let decoded: Vec<SimpleValue> = codec::decode(&codec::encode(&values).unwrap()).unwrap();
+
+    assert_eq!(decoded[..2], values[..2]);
+    // The same as JSON.
+    assert_eq!(
+        decoded[2],
+        serde_json::from_value(serde_json::to_value(&values[2]).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn writes_dates_as_time_does_in_json() {
+    let datetime = datetime!(2024-03-01 12:00:00.25 +02:00);
+    let date = date!(2024 - 03 - 01);
+    let time = time!(12:30:05.5);
+
+    for (value, json) in [
+        (
+            SimpleValue::DateTime(datetime),
+            serde_json::to_value(datetime),
+        ),
+        (SimpleValue::Date(date), serde_json::to_value(date)),
+        (SimpleValue::Time(time), serde_json::to_value(time)),
+    ] {
+        let json = json.unwrap();
+        let text: String = codec::decode(&codec::encode(&value).unwrap()).unwrap();
+
+        assert_eq!(serde_json::Value::String(text), json);
+        assert_eq!(serde_json::to_value(&value).unwrap(), json);
+    }
+}
+
+#[test]
+fn transfers_rows_in_bulk() {
+    let rows = (0..100)
+        .map(|i| CmsRowResponse {
+            files: Vec::new(),
+            fields: HashMap::from([
+                (SchematicFieldKey::from("title"), SimpleValue::from("Hello")),
+                (SchematicFieldKey::from("views"), SimpleValue::from(i)),
+            ]),
+            meta: None,
+            seo: None,
+        })
+        .collect::<Vec<_>>();
+
+    let bytes = codec::encode(&rows).unwrap();
+
+    assert_eq!(bytes[0], FORMAT_VERSION);
+    assert!(bytes.len() < serde_json::to_vec(&rows).unwrap().len());
+
+    let decoded: Vec<CmsRowResponse> = codec::decode(&bytes).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&decoded).unwrap(),
+        serde_json::to_value(&rows).unwrap()
+    );
+
+    let mut future = bytes;
+    future[0] = FORMAT_VERSION + 1;
+
+    assert!(codec::decode::<Vec<CmsRowResponse>>(&future).is_err());
+    assert!(codec::decode::<Vec<CmsRowResponse>>(&[]).is_err());
+}
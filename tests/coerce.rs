@@ -0,0 +1,206 @@
+use serde_json::json;
+use time::macros::{date, datetime, time};
+use uuid::Uuid;
+
+use webby_global_common::{
+    schema::{SchematicFieldType, SchematicFieldValue},
+    value::{Number, SimpleValue},
+};
+
+fn coerce(value: SimpleValue, target: SchematicFieldType) -> SchematicFieldValue {
+    value.coerce_to(target).unwrap()
+}
+
+fn text(value: &str, target: SchematicFieldType) -> SchematicFieldValue {
+    coerce(SimpleValue::Text(String::from(value)), target)
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(
+        coerce(SimpleValue::from(12), SchematicFieldType::Text),
+        SchematicFieldValue::Text(String::from("12"))
+    );
+    assert_eq!(
+        coerce(SimpleValue::Boolean(true), SchematicFieldType::RichText),
+        SchematicFieldValue::Text(String::from("true"))
+    );
+
+    assert_eq!(
+        text(" 42 ", SchematicFieldType::Number),
+        SchematicFieldValue::Number(Number::Integer(42))
+    );
+    assert_eq!(
+        text("1.5", SchematicFieldType::Number),
+        SchematicFieldValue::Number(Number::Float(1.5))
+    );
+    assert_eq!(
+        coerce(SimpleValue::Boolean(true), SchematicFieldType::Number),
+        SchematicFieldValue::Number(Number::Byte(1))
+    );
+
+    for (value, expected) in [
+        ("Yes", true),
+        ("on", true),
+        ("1", true),
+        ("TRUE", true),
+        ("no", false),
+        ("off", false),
+        (" 0 ", false),
+    ] {
+        assert_eq!(
+            text(value, SchematicFieldType::Boolean),
+            SchematicFieldValue::Boolean(expected),
+            "{value}"
+        );
+    }
+    assert_eq!(
+        coerce(
+            SimpleValue::Number(Number::Float(0.0)),
+            SchematicFieldType::Boolean
+        ),
+        SchematicFieldValue::Boolean(false)
+    );
+    assert!(
+        SimpleValue::Number(Number::Byte(2))
+            .coerce_to(SchematicFieldType::Boolean)
+            .is_err()
+    );
+    assert!(
+        SimpleValue::from("maybe")
+            .coerce_to(SchematicFieldType::Boolean)
+            .is_err()
+    );
+
+    let SchematicFieldValue::Email(email) = text(" Jane@Example.COM ", SchematicFieldType::Email)
+    else {
+        panic!("not an email");
+    };
+    assert_eq!(email.as_str(), "Jane@example.com");
+}
+
+#[test]
+fn temporal() {
+    let expected = SchematicFieldValue::DateTime(datetime!(2024-05-01 12:30 UTC));
+
+    for value in [
+        "2024-05-01T12:30:00Z",
+        "2024-05-01T14:30:00+02:00",
+        "2024-05-01T12:30",
+        "2024-05-01 12:30:00",
+    ] {
+        assert_eq!(
+            text(value, SchematicFieldType::DateTime),
+            expected,
+            "{value}"
+        );
+    }
+    assert_eq!(
+        text("2024-05-01", SchematicFieldType::DateTime),
+        SchematicFieldValue::DateTime(datetime!(2024-05-01 0:00 UTC))
+    );
+    assert_eq!(
+        coerce(
+            SimpleValue::from(datetime!(2024-05-01 12:30 UTC).unix_timestamp()),
+            SchematicFieldType::DateTime
+        ),
+        expected
+    );
+
+    assert_eq!(
+        coerce(
+            SimpleValue::DateTime(datetime!(2024-05-01 12:30 UTC)),
+            SchematicFieldType::Date
+        ),
+        SchematicFieldValue::Date(date!(2024 - 05 - 01))
+    );
+    assert_eq!(
+        text("2024-05-01T23:00:00Z", SchematicFieldType::Date),
+        SchematicFieldValue::Date(date!(2024 - 05 - 01))
+    );
+
+    assert_eq!(
+        text("09:15", SchematicFieldType::Time),
+        SchematicFieldValue::Time(time!(9:15))
+    );
+    assert_eq!(
+        text("09:15:30.5", SchematicFieldType::Time),
+        SchematicFieldValue::Time(time!(9:15:30.5))
+    );
+    assert!(
+        SimpleValue::from("tomorrow")
+            .coerce_to(SchematicFieldType::Date)
+            .is_err()
+    );
+}
+
+#[test]
+fn lists() {
+    let first = Uuid::now_v7();
+    let second = Uuid::now_v7();
+
+    assert_eq!(
+        text(&format!(" {first} "), SchematicFieldType::Reference),
+        SchematicFieldValue::Reference(first)
+    );
+    assert_eq!(
+        text(
+            &format!("{first}, ,{second}"),
+            SchematicFieldType::MultiReference
+        ),
+        SchematicFieldValue::MultiReference(vec![first, second])
+    );
+    assert!(
+        SimpleValue::from("not-a-uuid")
+            .coerce_to(SchematicFieldType::MultiReference)
+            .is_err()
+    );
+
+    assert_eq!(
+        text(" news, tech,news ", SchematicFieldType::Tags),
+        SchematicFieldValue::Tags(vec![String::from("news"), String::from("tech")])
+    );
+    assert_eq!(
+        coerce(
+            SimpleValue::ListNumber(vec![Number::Byte(1), Number::Byte(2)]),
+            SchematicFieldType::Array
+        ),
+        SchematicFieldValue::Array(vec![json!(1), json!(2)])
+    );
+    assert_eq!(
+        text(r#"[1, "a"]"#, SchematicFieldType::Array),
+        SchematicFieldValue::Array(vec![json!(1), json!("a")])
+    );
+    assert_eq!(
+        text(r#"{ "a": 1 }"#, SchematicFieldType::Object),
+        SchematicFieldValue::Object(json!({ "a": 1 }))
+    );
+    assert!(
+        SimpleValue::from("[1]")
+            .coerce_to(SchematicFieldType::Object)
+            .is_err()
+    );
+}
+
+#[test]
+fn mismatches() {
+    let list = SimpleValue::ListString(vec![String::from("a")]);
+
+    assert!(list.coerce_to(SchematicFieldType::Text).is_err());
+    assert!(list.coerce_to(SchematicFieldType::Number).is_err());
+    assert!(
+        SimpleValue::Boolean(true)
+            .coerce_to(SchematicFieldType::URL)
+            .is_err()
+    );
+    assert!(
+        SimpleValue::from("a")
+            .coerce_to(SchematicFieldType::Image)
+            .is_err()
+    );
+    assert!(
+        SimpleValue::Time(time!(1:00))
+            .coerce_to(SchematicFieldType::Date)
+            .is_err()
+    );
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use time::macros::{date, datetime};
+use webby_global_common::{
+    expr::{DateUnit, Expression},
+    id::WebsitePublicId,
+    schema::{
+        Schematic, SchematicFieldKey, SchematicFieldType, builder::SchematicBuilder,
+        computed::FieldSource, validation::ValidationErrorKind,
+    },
+    testing::assert_roundtrip,
+    value::{Number, SimpleValue},
+};
+
+fn row(values: &[(&str, SimpleValue)]) -> HashMap<SchematicFieldKey, SimpleValue> {
+    values
+        .iter()
+        .map(|(k, v)| (SchematicFieldKey::from(*k), v.clone()))
+        .collect()
+}
+
+fn full_name() -> Expression {
+    Expression::concat([
+        Expression::field("first"),
+        Expression::literal(" "),
+        Expression::field("last"),
+    ])
+}
+
+fn schematic() -> Schematic {
+    SchematicBuilder::new("people", "CRM", "People", WebsitePublicId::new())
+        .field("first", SchematicFieldType::Text)
+        .required()
+        .field("last", SchematicFieldType::Text)
+        .field("fullName", SchematicFieldType::Text)
+        .computed(full_name())
+        .required()
+        .field("price", SchematicFieldType::Number)
+        .field("quantity", SchematicFieldType::Number)
+        .field("total", SchematicFieldType::Number)
+        .computed(Expression::Mul(
+            Box::new(Expression::field("price")),
+            Box::new(Expression::field("quantity")),
+        ))
+        .primary_field("fullName")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn evaluates_expressions() {
+    let fields = row(&[
+        ("first", SimpleValue::from("Jane")),
+        ("age", SimpleValue::from(30)),
+        ("start", SimpleValue::Date(date!(2024 - 02 - 27))),
+        (
+            "end",
+            SimpleValue::DateTime(datetime!(2024-03-02 12:00 UTC)),
+        ),
+    ]);
+
+    assert_eq!(
+        full_name().evaluate_optional(&fields).unwrap(),
+        Some(SimpleValue::from("Jane "))
+    );
+    assert_eq!(
+        Expression::concat([Expression::field("missing")])
+            .evaluate_optional(&fields)
+            .unwrap(),
+        None
+    );
+
+    let next_year = Expression::Add(
+        Box::new(Expression::field("age")),
+        Box::new(Expression::literal(1)),
+    );
+
+    assert_eq!(
+        next_year.evaluate_optional(&fields).unwrap(),
+        Some(SimpleValue::Number(Number::Integer(31)))
+    );
+
+    let half = Expression::Div(
+        Box::new(Expression::field("age")),
+        Box::new(Expression::literal(Number::Integer(4))),
+    );
+
+    assert_eq!(
+        half.evaluate_optional(&fields).unwrap(),
+        Some(SimpleValue::Number(Number::Float(7.5)))
+    );
+
+    let days = Expression::date_diff(
+        Expression::field("start"),
+        Expression::field("end"),
+        DateUnit::Days,
+    );
+
+    // 2024 is a leap year.
+    assert_eq!(
+        days.evaluate_optional(&fields).unwrap(),
+        Some(SimpleValue::Number(Number::Integer(4)))
+    );
+
+    let invalid = Expression::Sub(
+        Box::new(Expression::field("first")),
+        Box::new(Expression::literal(1)),
+    );
+    assert!(invalid.evaluate_optional(&fields).is_err());
+
+    let by_zero = Expression::Div(
+        Box::new(Expression::field("age")),
+        Box::new(Expression::literal(0)),
+    );
+    assert!(by_zero.evaluate_optional(&fields).is_err());
+
+    assert_eq!(
+        full_name().fields(),
+        [
+            &SchematicFieldKey::from("first"),
+            &SchematicFieldKey::from("last")
+        ]
+    );
+    assert_roundtrip(&FieldSource::Computed(days));
+}
+
+#[test]
+fn computes_fields() {
+    let schematic = schematic();
+
+    let mut fields = row(&[
+        ("first", SimpleValue::from("Jane")),
+        ("last", SimpleValue::from("Doe")),
+        ("price", SimpleValue::from(3)),
+        ("quantity", SimpleValue::from(4)),
+        ("total", SimpleValue::from(1)),
+    ]);
+
+    schematic.compute_fields(&mut fields).unwrap();
+
+    assert_eq!(
+        fields[&SchematicFieldKey::from("fullName")],
+        SimpleValue::from("Jane Doe")
+    );
+    assert_eq!(
+        fields[&SchematicFieldKey::from("total")],
+        SimpleValue::Number(Number::Integer(12))
+    );
+
+    // A stale value is removed once its inputs are gone.
+    fields.remove(&SchematicFieldKey::from("quantity"));
+    schematic.compute_fields(&mut fields).unwrap();
+
+    assert!(!fields.contains_key(&SchematicFieldKey::from("total")));
+}
+
+#[test]
+fn loaded_schemas_are_checked_when_computing() {
+    // Written without the builder, eg. by an older version.
+    let mut json = serde_json::to_value(schematic()).unwrap();
+    json["fields"]["total"]["source"] =
+        serde_json::to_value(FieldSource::Computed(Expression::field("fullName"))).unwrap();
+
+    let schematic: Schematic = serde_json::from_value(json).unwrap();
+    let mut fields = row(&[
+        ("first", SimpleValue::from("Jane")),
+        ("total", SimpleValue::from(1)),
+    ]);
+    let before = fields.clone();
+
+    assert!(schematic.compute_fields(&mut fields).is_err());
+    assert_eq!(fields, before);
+}
+
+#[test]
+fn computed_fields_are_read_only() {
+    let schematic = schematic();
+
+    let errors = schematic
+        .validate_record(&row(&[
+            ("first", SimpleValue::from("Jane")),
+            ("fullName", SimpleValue::from("Someone Else")),
+        ]))
+        .unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, SchematicFieldKey::from("fullName"));
+    assert_eq!(errors[0].kind, ValidationErrorKind::ComputedField);
+
+    let json = schematic.to_json_schema();
+
+    assert_eq!(json["properties"]["fullName"]["readOnly"], true);
+    assert_eq!(json["required"], serde_json::json!(["first"]));
+}
+
+#[test]
+fn computed_fields_read_stored_fields() {
+    let builder = || {
+        SchematicBuilder::new("people", "CRM", "People", WebsitePublicId::new())
+            .field("first", SchematicFieldType::Text)
+            .field("name", SchematicFieldType::Text)
+            .computed(Expression::field("first"))
+            .primary_field("first")
+    };
+
+    assert!(builder().build().is_ok());
+
+    assert!(
+        builder()
+            .field("other", SchematicFieldType::Text)
+            .computed(Expression::field("name"))
+            .build()
+            .is_err()
+    );
+    assert!(
+        builder()
+            .field("other", SchematicFieldType::Text)
+            .computed(Expression::field("missing"))
+            .build()
+            .is_err()
+    );
+    assert!(
+        builder()
+            .field("other", SchematicFieldType::Text)
+            .computed(Expression::call("shout", [Expression::field("first")]))
+            .build()
+            .is_err()
+    );
+    // System fields are always there.
+    assert!(
+        builder()
+            .field("age", SchematicFieldType::Number)
+            .computed(Expression::date_diff(
+                Expression::field("_createdAt"),
+                Expression::field("_updatedAt"),
+                DateUnit::Days,
+            ))
+            .build()
+            .is_ok()
+    );
+}
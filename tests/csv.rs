@@ -0,0 +1,86 @@
+#![cfg(feature = "csv")]
+
+use std::collections::HashMap;
+
+use webby_global_common::{
+    csv::{CsvHeader, columns_from_str, rows_to_string},
+    response::CmsRowResponse,
+    schema::{
+        SchemaFieldMap, SchematicField, SchematicFieldKey, SchematicFieldType,
+        computed::FieldSource,
+    },
+    value::{Number, SimpleValue},
+};
+
+fn field(display_name: &str, field_type: SchematicFieldType, index: u16) -> SchematicField {
+    SchematicField {
+        display_name: display_name.to_string(),
+        sortable: true,
+        is_deleted: false,
+        system_field: false,
+        field_type,
+        index,
+        referenced_schema: None,
+        default_value: None,
+        constraints: Default::default(),
+        localized: false,
+        source: FieldSource::Stored,
+    }
+}
+
+#[test]
+fn rows_round_trip() {
+    let fields = SchemaFieldMap::from([
+        (
+            SchematicFieldKey::from("title"),
+            field("Title", SchematicFieldType::Text, 0),
+        ),
+        (
+            SchematicFieldKey::from("count"),
+            field("Count", SchematicFieldType::Number, 1),
+        ),
+        (
+            SchematicFieldKey::from("tags"),
+            field("Tags", SchematicFieldType::Tags, 2),
+        ),
+    ]);
+
+    let rows = vec![CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::from([
+            (
+                SchematicFieldKey::from("title"),
+                SimpleValue::from("Hello, World"),
+            ),
+            (SchematicFieldKey::from("count"), SimpleValue::from(3)),
+            (
+                SchematicFieldKey::from("tags"),
+                SimpleValue::ListString(vec![String::from("a"), String::from("b")]),
+            ),
+        ]),
+        meta: None,
+        seo: None,
+    }];
+
+    let output = rows_to_string(&rows, &fields, CsvHeader::DisplayName).unwrap();
+
+    assert_eq!(output, "Title,Count,Tags\n\"Hello, World\",3,\"a,b\"\n");
+
+    let columns = columns_from_str(&output, &fields).unwrap();
+
+    assert_eq!(
+        columns["title"],
+        vec![SimpleValue::Text(String::from("Hello, World"))]
+    );
+    assert_eq!(
+        columns["count"],
+        vec![SimpleValue::Number(Number::Integer(3))]
+    );
+    assert_eq!(
+        columns["tags"],
+        vec![SimpleValue::ListString(vec![
+            String::from("a"),
+            String::from("b")
+        ])]
+    );
+}
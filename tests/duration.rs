@@ -0,0 +1,116 @@
+use time::{Duration, macros::time};
+use webby_global_common::{
+    duration,
+    schema::{SchematicFieldType, SchematicFieldValue},
+    testing::assert_roundtrip,
+    value::{Number, SimpleValue},
+};
+
+#[test]
+fn parses_iso8601() {
+    let cases = [
+        ("PT90M", Duration::minutes(90)),
+        ("P1DT2H30M", Duration::hours(26) + Duration::minutes(30)),
+        ("P2W", Duration::weeks(2)),
+        ("pt1.5s", Duration::milliseconds(1500)),
+        ("PT0,25S", Duration::milliseconds(250)),
+        ("-PT1H", Duration::hours(-1)),
+        ("P3D", Duration::days(3)),
+    ];
+
+    for (value, expected) in cases {
+        assert_eq!(duration::parse(value).unwrap(), expected, "{value}");
+    }
+
+    for invalid in [
+        "",
+        "P",
+        "PT",
+        "P1Y",
+        "P1M",
+        "P1H",
+        "PT1D",
+        "PT1M1H",
+        "PT1H1H",
+        "P1.5D",
+        "PT1.0000000001S",
+        "P1DT",
+        "1H",
+    ] {
+        assert!(duration::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn parses_clock_times() {
+    assert_eq!(duration::parse("01:30").unwrap(), Duration::minutes(90));
+    assert_eq!(duration::parse("36:00:00").unwrap(), Duration::hours(36));
+    assert_eq!(
+        duration::parse("00:00:01.5").unwrap(),
+        Duration::milliseconds(1500)
+    );
+    assert_eq!(duration::parse("-0:15").unwrap(), Duration::minutes(-15));
+
+    for invalid in ["1:60", "1:5", "1:00:60", "a:00", "1:00:00:00", "+1:00"] {
+        assert!(duration::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn formats() {
+    let cases = [
+        (Duration::ZERO, "PT0S", "0 seconds"),
+        (Duration::minutes(90), "PT1H30M", "1 hour 30 minutes"),
+        (
+            Duration::hours(50) + Duration::seconds(1),
+            "P2DT2H1S",
+            "2 days 2 hours 1 second",
+        ),
+        (Duration::days(1), "P1D", "1 day"),
+        (Duration::milliseconds(2250), "PT2.25S", "2 seconds"),
+        (Duration::minutes(-5), "-PT5M", "-5 minutes"),
+    ];
+
+    for (value, iso, human) in cases {
+        assert_eq!(duration::to_iso8601(value), iso);
+        assert_eq!(duration::humanize(value), human);
+        assert_eq!(duration::parse(iso).unwrap(), value);
+    }
+}
+
+#[test]
+fn duration_fields() {
+    let value = SchematicFieldType::Duration
+        .parse_value(SimpleValue::Text(String::from("P1DT12H")))
+        .unwrap();
+
+    assert_eq!(value, SchematicFieldValue::Duration(Duration::hours(36)));
+    assert_eq!(
+        SimpleValue::from(value.clone()),
+        SimpleValue::Text(String::from("P1DT12H"))
+    );
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({ "type": "Duration", "value": "P1DT12H" })
+    );
+    assert_roundtrip(&value);
+
+    // Values stored in Time fields before Duration existed.
+    assert_eq!(
+        SimpleValue::Time(time!(1:30))
+            .coerce_to(SchematicFieldType::Duration)
+            .unwrap(),
+        SchematicFieldValue::Duration(Duration::minutes(90))
+    );
+    assert_eq!(
+        SimpleValue::Number(Number::Integer(90))
+            .coerce_to(SchematicFieldType::Duration)
+            .unwrap(),
+        SchematicFieldValue::Duration(Duration::seconds(90))
+    );
+    assert!(
+        SimpleValue::Number(Number::Float(f64::NAN))
+            .coerce_to(SchematicFieldType::Duration)
+            .is_err()
+    );
+}
@@ -0,0 +1,49 @@
+use webby_global_common::Either;
+
+#[test]
+fn combinators() {
+    let left: Either<i32, &str> = Either::Left(2);
+    let right: Either<i32, &str> = Either::Right("two");
+
+    assert!(left.is_left() && !left.is_right());
+    assert!(right.is_right() && !right.is_left());
+    assert_eq!(left.left(), Some(2));
+    assert_eq!(left.right(), None);
+    assert_eq!(right.right(), Some("two"));
+    assert_eq!(left.as_ref(), Either::Left(&2));
+    assert_eq!(left.flip(), Either::Right(2));
+    assert_eq!(left.map_left(|v| v * 2), Either::Left(4));
+    assert_eq!(left.map_right(str::len), Either::Left(2));
+    assert_eq!(right.map_right(str::len), Either::Right(3));
+    assert_eq!(right.either(|v| v.to_string(), String::from), "two");
+    assert_eq!(Either::<u8, u8>::Right(1).into_inner(), 1);
+
+    let mut value: Either<i32, String> = Either::Right(String::from("a"));
+    value.as_mut().map_right(|v| v.push('b'));
+    assert_eq!(value, Either::Right(String::from("ab")));
+}
+
+#[test]
+fn results() {
+    let either: Either<String, u8> = Ok(1).into();
+    assert_eq!(either, Either::Right(1));
+
+    let either: Either<String, u8> = Err(String::from("failed")).into();
+    assert_eq!(Result::from(either), Err(String::from("failed")));
+}
+
+#[test]
+fn iterates() {
+    let items = |reverse: bool| {
+        if reverse {
+            Either::Left((1..4).rev())
+        } else {
+            Either::Right(1..4)
+        }
+    };
+
+    assert_eq!(items(false).collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(items(true).collect::<Vec<_>>(), [3, 2, 1]);
+    assert_eq!(items(true).rev().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(items(false).len(), 3);
+}
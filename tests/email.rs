@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use webby_global_common::{
+    email::{AddressList, EmailMessage, EmailTemplate, MAX_RECIPIENTS, Mailbox},
+    id::TemplatePublicId,
+    schema::SchematicFieldKey,
+    testing::assert_roundtrip,
+    validators::EmailAddress,
+    value::SimpleValue,
+};
+
+fn sender() -> Mailbox {
+    Mailbox::parse("Webby <noreply@webby.example>").unwrap()
+}
+
+#[test]
+fn parses_mailboxes() {
+    let mailbox = Mailbox::parse("  Jane Doe <jane@Example.com> ").unwrap();
+
+    assert_eq!(mailbox.name(), Some("Jane Doe"));
+    assert_eq!(mailbox.address().as_str(), "jane@example.com");
+    assert_eq!(mailbox.to_string(), "Jane Doe <jane@example.com>");
+
+    let quoted = Mailbox::parse(r#""Doe, \"JD\" Jane" <jane@example.com>"#).unwrap();
+
+    assert_eq!(quoted.name(), Some(r#"Doe, "JD" Jane"#));
+    assert_eq!(Mailbox::parse(&quoted.to_string()).unwrap(), quoted);
+
+    assert_eq!(Mailbox::parse("jane@example.com").unwrap().name(), None);
+    assert_eq!(Mailbox::parse("<jane@example.com>").unwrap().name(), None);
+
+    let address = EmailAddress::parse("jane@example.com").unwrap();
+    let named = Mailbox::new(address.clone()).with_name("J. Doe").unwrap();
+
+    assert_eq!(named.to_string(), r#""J. Doe" <jane@example.com>"#);
+    assert_eq!(Mailbox::parse(&named.to_string()).unwrap(), named);
+    assert!(
+        Mailbox::new(address.clone())
+            .with_name("Jane\r\nBcc: everyone@example.com")
+            .is_err()
+    );
+    assert!(Mailbox::new(address).with_name("Jane\u{7}").is_err());
+
+    for invalid in [
+        "",
+        "Jane",
+        "Jane <jane>",
+        "Jane\r\nBcc: x <jane@example.com>",
+    ] {
+        assert!(Mailbox::parse(invalid).is_err(), "{invalid:?}");
+    }
+
+    assert_roundtrip(&quoted);
+}
+
+#[test]
+fn parses_address_lists() {
+    let list = AddressList::parse(
+        r#"jane@example.com; "Doe, John" <john@example.com>, jane@EXAMPLE.COM,"#,
+    )
+    .unwrap();
+
+    // Domains are case insensitive so the third address is the first one again.
+    assert_eq!(list.len(), 2);
+    assert_eq!(
+        list.to_string(),
+        r#"jane@example.com, "Doe, John" <john@example.com>"#
+    );
+    assert_eq!(AddressList::parse(&list.to_string()).unwrap(), list);
+    assert_roundtrip(&list);
+
+    assert!(AddressList::parse("jane@example.com, nope").is_err());
+    assert!(AddressList::parse("").unwrap().is_empty());
+
+    let too_many = (0..=MAX_RECIPIENTS)
+        .map(|i| format!("user{i}@example.com"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    assert!(AddressList::parse(&too_many).is_err());
+}
+
+#[test]
+fn renders_messages() {
+    let row = HashMap::from([(
+        SchematicFieldKey::from("name"),
+        SimpleValue::from("<Jane & Co>"),
+    )]);
+
+    let message = EmailMessage::new(
+        sender(),
+        AddressList::parse("jane@example.com").unwrap(),
+        "Hi {{name}}\r\n",
+    )
+    .html_body("<p>Hello {{ name }}, your code is {{code}}{{missing}}</p>")
+    .text_body("Hello {{name}}, your code is {{code}}")
+    .merge_row(&row)
+    .merge_var("code", 1234);
+
+    let rendered = message.render(None).unwrap();
+
+    assert_eq!(rendered.subject, "Hi <Jane & Co>");
+    assert_eq!(
+        rendered.html_body.as_deref(),
+        Some("<p>Hello &lt;Jane &amp; Co&gt;, your code is 1234</p>")
+    );
+    assert_eq!(
+        rendered.text_body.as_deref(),
+        Some("Hello <Jane & Co>, your code is 1234")
+    );
+    assert_roundtrip(&message);
+
+    // A line break in a value can't add headers.
+    let injected = message
+        .clone()
+        .merge_var("name", "x\r\nBcc: everyone@example.com");
+
+    assert_eq!(
+        injected.render(None).unwrap().subject,
+        "Hi x  Bcc: everyone@example.com"
+    );
+}
+
+#[test]
+fn renders_templates() {
+    let template = EmailTemplate {
+        id: TemplatePublicId::new(),
+        name: String::from("Welcome"),
+        subject: String::from("Welcome {{name}}"),
+        html_body: Some(String::from("<h1>{{name}}</h1><p>{{plan}}</p>")),
+        text_body: None,
+    };
+
+    assert_eq!(template.variables(), ["name", "plan"]);
+
+    let message = EmailMessage::new(
+        sender(),
+        AddressList::parse("jane@example.com").unwrap(),
+        "",
+    )
+    .template(template.id)
+    .merge_var("name", "Jane")
+    .merge_var("plan", "Pro");
+
+    let rendered = message.render(Some(&template)).unwrap();
+
+    assert_eq!(rendered.subject, "Welcome Jane");
+    assert_eq!(
+        rendered.html_body.as_deref(),
+        Some("<h1>Jane</h1><p>Pro</p>")
+    );
+    assert_eq!(rendered.text_body, None);
+
+    // The message overrides the template.
+    let rendered = message
+        .clone()
+        .text_body("Hi {{name}}")
+        .render(Some(&template))
+        .unwrap();
+
+    assert_eq!(rendered.text_body.as_deref(), Some("Hi Jane"));
+
+    // The wrong or a missing template.
+    assert!(message.render(None).is_err());
+    assert!(
+        message
+            .render(Some(&EmailTemplate {
+                id: TemplatePublicId::new(),
+                ..template.clone()
+            }))
+            .is_err()
+    );
+}
+
+#[test]
+fn validates_messages() {
+    let to = AddressList::parse("jane@example.com").unwrap();
+
+    assert!(
+        EmailMessage::new(sender(), to.clone(), "Subject")
+            .text_body("Body")
+            .validate()
+            .is_ok()
+    );
+    assert!(
+        EmailMessage::new(sender(), AddressList::default(), "Subject")
+            .text_body("Body")
+            .validate()
+            .is_err()
+    );
+    assert!(
+        EmailMessage::new(sender(), to.clone(), "Subject")
+            .validate()
+            .is_err()
+    );
+    assert!(
+        EmailMessage::new(sender(), to.clone(), " ")
+            .text_body("Body")
+            .validate()
+            .is_err()
+    );
+    assert!(
+        EmailMessage::new(sender(), to, "")
+            .template(TemplatePublicId::new())
+            .validate()
+            .is_ok()
+    );
+
+    // Stored addresses aren't validated until they're sent to.
+    let stored: EmailAddress = serde_json::from_str("\"jane@localhost\"").unwrap();
+    assert!(
+        EmailMessage::new(
+            sender(),
+            AddressList::new(vec![Mailbox::new(stored)]).unwrap(),
+            "Subject"
+        )
+        .text_body("Body")
+        .validate()
+        .is_err()
+    );
+}
@@ -0,0 +1,83 @@
+use serde_json::json;
+use time::{Duration, OffsetDateTime, macros::datetime};
+use webby_global_common::events::{
+    SIGNATURE_TOLERANCE, WEBHOOK_EVENT_VERSION, WebhookEvent, WebhookEventType, sign_body,
+    verify_body,
+};
+
+#[test]
+fn signs_and_verifies_events() {
+    let event = WebhookEvent::new(
+        WebhookEventType::RowCreated,
+        None,
+        &json!({ "collection": "posts", "id": "a" }),
+    )
+    .unwrap();
+
+    assert_eq!(event.version, WEBHOOK_EVENT_VERSION);
+
+    let secret = b"whsec_test";
+    let now = OffsetDateTime::now_utc();
+    let header = event.sign(secret, now).unwrap();
+
+    assert!(header.starts_with(&format!("t={},sha256=", now.unix_timestamp())));
+    assert!(event.verify(secret, &header, now).is_ok());
+    assert!(event.verify(b"wrong", &header, now).is_err());
+    assert!(event.verify(secret, "sha256=zz", now).is_err());
+
+    let body = event.to_body().unwrap();
+    let received = WebhookEvent::from_signed_body(secret, &body, &header, now).unwrap();
+
+    assert_eq!(received, event);
+    assert_eq!(
+        received.payload_as::<serde_json::Value>().unwrap()["collection"],
+        "posts"
+    );
+
+    let mut tampered = body.clone();
+    tampered.push(b' ');
+    assert!(WebhookEvent::from_signed_body(secret, &tampered, &header, now).is_err());
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "RowCreated");
+    assert!(json.get("occurredAt").is_some());
+}
+
+#[test]
+fn signature_matches_reference() {
+    // echo -n '1700000000.{}' | openssl dgst -sha256 -hmac secret
+    assert_eq!(
+        sign_body(b"secret", b"{}", datetime!(2023-11-14 22:13:20 UTC)),
+        "t=1700000000,sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+    );
+}
+
+#[test]
+fn rejects_old_and_future_signatures() {
+    let signed_at = datetime!(2024-05-01 12:00 UTC);
+    let header = sign_body(b"secret", b"{}", signed_at);
+    let verify = |now| verify_body(b"secret", b"{}", &header, now);
+
+    assert!(verify(signed_at).is_ok());
+    assert!(verify(signed_at + SIGNATURE_TOLERANCE).is_ok());
+    assert!(verify(signed_at - SIGNATURE_TOLERANCE).is_ok());
+    assert!(verify(signed_at + SIGNATURE_TOLERANCE + Duration::seconds(1)).is_err());
+    assert!(verify(signed_at - SIGNATURE_TOLERANCE - Duration::seconds(1)).is_err());
+
+    // The timestamp is signed, so it can't be moved forward.
+    let replayed = header.replace("t=1714564800", "t=1714568400");
+    assert!(verify_body(b"secret", b"{}", &replayed, signed_at + Duration::hours(1)).is_err());
+
+    for malformed in [
+        "",
+        "sha256=00",
+        "t=1714564800",
+        "t=x,sha256=00",
+        "t=-9223372036854775808,sha256=00",
+    ] {
+        assert!(
+            verify_body(b"secret", b"{}", malformed, signed_at).is_err(),
+            "{malformed}"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use time::macros::{date, datetime};
+use webby_global_common::{
+    expr::{Expression, functions},
+    filter::{Filter, FilterConditionType, FilterExpr, FilterValue},
+    schema::SchematicFieldKey,
+    testing::assert_roundtrip,
+    value::{Number, SimpleValue},
+};
+
+fn row() -> HashMap<SchematicFieldKey, SimpleValue> {
+    HashMap::from([
+        (SchematicFieldKey::from("first"), SimpleValue::from("Jane")),
+        (SchematicFieldKey::from("last"), SimpleValue::from("Doe")),
+        (SchematicFieldKey::from("age"), SimpleValue::from(30)),
+        (
+            SchematicFieldKey::from("price"),
+            SimpleValue::Number(Number::Float(2.5)),
+        ),
+        (
+            SchematicFieldKey::from("tags"),
+            SimpleValue::ListString(vec![String::from("vip"), String::from("new")]),
+        ),
+        (
+            SchematicFieldKey::from("signedUp"),
+            SimpleValue::Date(date!(2024 - 02 - 27)),
+        ),
+        (
+            SchematicFieldKey::from("lastSeen"),
+            SimpleValue::DateTime(datetime!(2024-03-02 12:00 UTC)),
+        ),
+        (
+            SchematicFieldKey::from("address"),
+            SimpleValue::ObjectUnknown(serde_json::json!({ "city": "Springfield" })),
+        ),
+        (
+            SchematicFieldKey::from("Full Name"),
+            SimpleValue::from("Jane Doe"),
+        ),
+    ])
+}
+
+fn eval(text: &str) -> SimpleValue {
+    Expression::parse(text)
+        .unwrap()
+        .evaluate(&row())
+        .unwrap_or_else(|e| panic!("{text}: {e}"))
+}
+
+fn number(value: i64) -> SimpleValue {
+    SimpleValue::Number(Number::Integer(value))
+}
+
+#[test]
+fn parses_and_evaluates() {
+    assert_eq!(eval("1 + 2 * 3"), number(7));
+    assert_eq!(eval("(1 + 2) * 3"), number(9));
+    assert_eq!(eval("10 - 4 - 3"), number(3));
+    assert_eq!(eval("-age + 1"), number(-29));
+    assert_eq!(
+        eval("18446744073709551615 - 1"),
+        SimpleValue::Number(Number::UInteger(u64::MAX - 1))
+    );
+    assert_eq!(eval("-9223372036854775808"), number(i64::MIN));
+    assert_eq!(eval("age / 4"), SimpleValue::Number(Number::Float(7.5)));
+    assert_eq!(eval("price * 2"), SimpleValue::Number(Number::Float(5.0)));
+    assert_eq!(eval("address.city"), SimpleValue::from("Springfield"));
+    assert_eq!(eval("`Full Name`"), SimpleValue::from("Jane Doe"));
+    assert_eq!(eval(r#"'it\'s'"#), SimpleValue::from("it's"));
+
+    assert_eq!(
+        Expression::parse("concat(first, ' ', last)").unwrap(),
+        Expression::concat([
+            Expression::field("first"),
+            Expression::literal(" "),
+            Expression::field("last"),
+        ])
+    );
+
+    for invalid in [
+        "",
+        "1 +",
+        "(1",
+        "1 2",
+        "shout(first)",
+        "lower()",
+        "if(true, 1)",
+        "'unclosed",
+        "a..b",
+        "1 # 2",
+    ] {
+        assert!(Expression::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn conditions() {
+    assert_eq!(
+        eval("age >= 18 && first == 'Jane'"),
+        SimpleValue::Boolean(true)
+    );
+    assert_eq!(
+        eval("age < 18 or not (last != 'Doe')"),
+        SimpleValue::Boolean(true)
+    );
+    assert_eq!(eval("!(age > 18)"), SimpleValue::Boolean(false));
+    assert_eq!(
+        eval("lastSeen > '2024-03-01' && signedUp == '2024-02-27'"),
+        SimpleValue::Boolean(true)
+    );
+    assert_eq!(eval("contains(tags, 'vip')"), SimpleValue::Boolean(true));
+
+    // A missing value is false, and only unequal to things.
+    let fields = row();
+    let parse = |v: &str| Expression::parse(v).unwrap();
+
+    assert!(!parse("missing == 1").evaluate_condition(&fields).unwrap());
+    assert!(parse("missing != 1").evaluate_condition(&fields).unwrap());
+    assert!(parse("!missing").evaluate_condition(&fields).unwrap());
+    assert!(
+        parse("age > 1 || missing")
+            .evaluate_condition(&fields)
+            .unwrap()
+    );
+    assert!(parse("first && true").evaluate_condition(&fields).is_err());
+
+    // Only the chosen branch is evaluated.
+    assert_eq!(eval("if(age == 0, 0, 60 / age)"), number(2));
+    assert_eq!(eval("if(missing, 1, 2)"), number(2));
+}
+
+#[test]
+fn functions() {
+    let cases = [
+        ("lower(first)", SimpleValue::from("jane")),
+        ("upper(first)", SimpleValue::from("JANE")),
+        ("trim('  a b ')", SimpleValue::from("a b")),
+        ("length(first)", number(4)),
+        ("length(tags)", number(2)),
+        ("starts_with(first, 'ja')", SimpleValue::Boolean(true)),
+        ("ends_with(last, 'x')", SimpleValue::Boolean(false)),
+        ("replace('a-b-c', '-', '+')", SimpleValue::from("a+b+c")),
+        ("abs(-3)", number(3)),
+        ("round(price)", number(3)),
+        ("round(2.346, 2)", SimpleValue::Number(Number::Float(2.35))),
+        ("floor(price)", number(2)),
+        ("ceil(-price)", number(-2)),
+        (
+            "min(age, missing, 3.5)",
+            SimpleValue::Number(Number::Float(3.5)),
+        ),
+        ("max(age, 3)", number(30)),
+        ("year(signedUp)", number(2024)),
+        ("month(lastSeen)", number(3)),
+        ("day('2024-05-06')", number(6)),
+        ("date_diff(signedUp, lastSeen, 'days')", number(4)),
+        ("date_diff(lastSeen, signedUp, 'hour')", number(-108)),
+        (
+            "date_add(signedUp, 3, 'days')",
+            SimpleValue::Date(date!(2024 - 03 - 01)),
+        ),
+        (
+            "date_add(lastSeen, -1, 'weeks')",
+            SimpleValue::DateTime(datetime!(2024-02-24 12:00 UTC)),
+        ),
+        ("coalesce(missing, last)", SimpleValue::from("Doe")),
+        ("concat(first, missing, '!')", SimpleValue::from("Jane!")),
+    ];
+
+    for (text, expected) in cases {
+        assert_eq!(eval(text), expected, "{text}");
+    }
+
+    let fields = row();
+    let parse = |v: &str| Expression::parse(v).unwrap();
+
+    assert_eq!(
+        parse("upper(missing)").evaluate_optional(&fields).unwrap(),
+        None
+    );
+    assert!(parse("upper(missing)").evaluate(&fields).is_err());
+    assert!(parse("abs(first)").evaluate(&fields).is_err());
+    assert!(
+        parse("date_diff(first, last, 'days')")
+            .evaluate(&fields)
+            .is_err()
+    );
+    assert!(
+        parse("date_add(signedUp, 1, 'years')")
+            .evaluate(&fields)
+            .is_err()
+    );
+
+    assert!(functions::get("concat").is_ok());
+    assert!(functions::get("shout").is_err());
+    assert!(
+        functions::FUNCTIONS
+            .iter()
+            .all(|f| f.max_args.is_none_or(|max| max >= f.min_args))
+    );
+}
+
+#[test]
+fn filters_become_expressions() {
+    let filter = |name: &str, cond, value| FilterExpr::Condition(Filter::new(name, cond, value));
+
+    let filters = [
+        filter("first", FilterConditionType::Eq, FilterValue::from("jane")),
+        filter("age", FilterConditionType::Gt, FilterValue::from(40)),
+        filter("tags", FilterConditionType::Cont, FilterValue::from("vip")),
+        filter("missing", FilterConditionType::Dnc, FilterValue::from("a")),
+        FilterExpr::Condition(Filter::path(
+            "address.city".parse().unwrap(),
+            FilterConditionType::Neq,
+            "Paris",
+        )),
+        filter(
+            "age",
+            FilterConditionType::Between,
+            FilterValue::from((Number::Integer(18), Number::Integer(65))),
+        ),
+        filter(
+            "lastSeen",
+            FilterConditionType::Lte,
+            FilterValue::from("2024-03-02T12:00:00Z"),
+        ),
+        FilterExpr::Or(vec![
+            filter("age", FilterConditionType::Lt, FilterValue::from(18)),
+            FilterExpr::Not(Box::new(filter(
+                "last",
+                FilterConditionType::Eq,
+                FilterValue::from("Doe"),
+            ))),
+        ]),
+    ];
+
+    let fields = row();
+
+    for filter in filters {
+        let expression = Expression::try_from(filter.clone()).unwrap();
+
+        assert_eq!(
+            expression.evaluate_condition(&fields).unwrap(),
+            filter.evaluate(&fields).unwrap(),
+            "{expression:?}"
+        );
+    }
+}
+
+#[test]
+fn serializes() {
+    let expression =
+        Expression::parse("if(age >= 18 && contains(tags, 'vip'), round(price * 1.2, 2), 0)")
+            .unwrap();
+
+    assert_roundtrip(&expression);
+    assert_eq!(
+        serde_json::to_value(Expression::parse("age > 1").unwrap()).unwrap(),
+        serde_json::json!({
+            "type": "Compare",
+            "value": {
+                "op": "gt",
+                "left": { "type": "Field", "value": "age" },
+                "right": { "type": "Literal", "value": 1 },
+            },
+        })
+    );
+    assert_eq!(
+        Expression::parse("price * quantity + tax")
+            .unwrap()
+            .fields(),
+        [
+            &SchematicFieldKey::from("price"),
+            &SchematicFieldKey::from("quantity"),
+            &SchematicFieldKey::from("tax"),
+        ]
+    );
+}
+
+#[test]
+fn limits_nesting() {
+    let nested = |open: &str, close: &str, depth: usize| {
+        format!("{}1{}", open.repeat(depth), close.repeat(depth))
+    };
+
+    assert_eq!(eval(&nested("(", ")", 64)), number(1));
+    assert_eq!(eval(&nested("abs(", ")", 64)), number(1));
+    assert_eq!(eval(&nested("-", "", 64)), number(1));
+
+    for (open, close) in [
+        ("(", ")"),
+        ("abs(", ")"),
+        ("!", ""),
+        ("-", ""),
+        ("not ", ""),
+    ] {
+        assert!(
+            Expression::parse(&nested(open, close, 65)).is_err(),
+            "{open}"
+        );
+        assert!(
+            Expression::parse(&nested(open, close, 100_000)).is_err(),
+            "{open}"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use time::macros::{date, datetime};
+use webby_global_common::{
+    filter::{Filter, FilterConditionType, FilterValue},
+    id::WebsitePublicId,
+    response::CmsRowResponse,
+    schema::{
+        DefaultSort, SchematicFieldKey, SchematicFieldType, SortOrder, builder::SchematicBuilder,
+    },
+    sort::sort_rows,
+    value::{Number, SimpleValue},
+};
+
+fn filter(name: &str, cond: FilterConditionType, value: FilterValue) -> Filter {
+    Filter::new(name, cond, value)
+}
+
+#[test]
+fn matches_row() {
+    let row = CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::from([
+            (
+                SchematicFieldKey::from("title"),
+                SimpleValue::from("Hello World"),
+            ),
+            (SchematicFieldKey::from("age"), SimpleValue::from(30)),
+            (
+                SchematicFieldKey::from("published"),
+                SimpleValue::DateTime(datetime!(2024-03-01 12:00 UTC)),
+            ),
+            (
+                SchematicFieldKey::from("birthday"),
+                SimpleValue::Date(date!(1994 - 05 - 06)),
+            ),
+            (
+                SchematicFieldKey::from("address"),
+                SimpleValue::ObjectUnknown(serde_json::json!({ "city": "Springfield" })),
+            ),
+        ]),
+        meta: None,
+        seo: None,
+    };
+
+    let text = |v: &str| FilterValue::Text(v.to_string());
+
+    assert!(
+        filter("title", FilterConditionType::Cont, text("world"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        filter("title", FilterConditionType::Dnc, text("bye"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        filter(
+            "age",
+            FilterConditionType::Between,
+            FilterValue::Range((Number::Byte(18), Number::Byte(30)))
+        )
+        .matches(&row)
+        .unwrap()
+    );
+    assert!(
+        filter(
+            "published",
+            FilterConditionType::Gt,
+            text("2024-02-29T23:59:59Z")
+        )
+        .matches(&row)
+        .unwrap()
+    );
+    assert!(
+        !filter("published", FilterConditionType::Lt, text("2024-03-01"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        filter("birthday", FilterConditionType::Eq, text("1994-05-06"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        filter("missing", FilterConditionType::Neq, text("x"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        filter("title", FilterConditionType::Between, text("x"))
+            .matches(&row)
+            .is_err()
+    );
+    assert!(
+        Filter::path(
+            "address.city".parse().unwrap(),
+            FilterConditionType::Eq,
+            text("Springfield")
+        )
+        .matches(&row)
+        .unwrap()
+    );
+    // Only filters made as paths look inside fields.
+    assert!(
+        !filter("address.city", FilterConditionType::Eq, text("Springfield"))
+            .matches(&row)
+            .unwrap()
+    );
+    assert!(
+        Filter {
+            is_path: true,
+            ..filter("address..city", FilterConditionType::Eq, text("x"))
+        }
+        .matches(&row)
+        .is_err()
+    );
+}
+
+#[test]
+fn sorts_rows_by_field_type() {
+    let fields = SchematicBuilder::new("products", "Shop", "Products", WebsitePublicId::new())
+        .field("price", SchematicFieldType::Number)
+        .field("name", SchematicFieldType::Text)
+        .primary_field("name")
+        .build()
+        .unwrap()
+        .fields;
+
+    let row = |name: &str, price: Option<SimpleValue>| CmsRowResponse {
+        files: Vec::new(),
+        fields: [(SchematicFieldKey::from("name"), SimpleValue::from(name))]
+            .into_iter()
+            .chain(price.map(|v| (SchematicFieldKey::from("price"), v)))
+            .collect(),
+        meta: None,
+        seo: None,
+    };
+
+    let mut rows = vec![
+        row("b", Some(SimpleValue::from("10"))),
+        row("c", None),
+        row("d", Some(SimpleValue::Number(Number::Float(2.5)))),
+        row("A", Some(SimpleValue::Number(Number::Byte(5)))),
+        row("a", Some(SimpleValue::Number(Number::Integer(5)))),
+    ];
+
+    let names = |rows: &[CmsRowResponse]| {
+        rows.iter()
+            .map(|r| r.fields["name"].to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let sort = |field: &str, order: SortOrder| DefaultSort {
+        field: field.to_string(),
+        order,
+    };
+
+    sort_rows(
+        &mut rows,
+        &[
+            sort("price", SortOrder::Ascending),
+            sort("name", SortOrder::Descending),
+        ],
+        &fields,
+    );
+
+    assert_eq!(names(&rows), ["d", "a", "A", "b", "c"]);
+
+    sort_rows(&mut rows, &[sort("price", SortOrder::Descending)], &fields);
+
+    assert_eq!(names(&rows), ["c", "b", "a", "A", "d"]);
+}
@@ -0,0 +1,501 @@
+#![cfg(feature = "sqlx")]
+
+use std::collections::HashMap;
+
+use serde_json::json;
+use sqlx::{Connection, Row, SqliteConnection};
+use time::{OffsetDateTime, macros::datetime};
+use webby_global_common::{
+    filter::{
+        Filter, FilterConditionType, FilterExpr, FilterValue,
+        sql::{Placeholder, SqlBind, SqlFilterBuilder},
+    },
+    id::WebsitePublicId,
+    schema::{SchemaFieldMap, SchematicFieldKey, SchematicFieldType, builder::SchematicBuilder},
+    value::{Number, SimpleValue},
+};
+
+/// `(id, name, age)`, None being a field without a value.
+const ROWS: &[(i64, Option<&str>, Option<i64>)] = &[
+    (1, Some("Jane Doe"), Some(30)),
+    (2, Some("JANE"), Some(17)),
+    (3, Some("bob"), None),
+    (4, None, Some(30)),
+    (5, None, None),
+    (6, Some("100% Jane_"), Some(-1)),
+];
+
+fn fields() -> SchemaFieldMap {
+    SchematicBuilder::new("people", "Club", "People", WebsitePublicId::new())
+        .field("name", SchematicFieldType::Text)
+        .field("age", SchematicFieldType::Number)
+        .primary_field("name")
+        .build()
+        .unwrap()
+        .fields
+}
+
+fn row(name: Option<&str>, age: Option<i64>) -> HashMap<SchematicFieldKey, SimpleValue> {
+    name.map(|v| (SchematicFieldKey::from("name"), SimpleValue::from(v)))
+        .into_iter()
+        .chain(age.map(|v| (SchematicFieldKey::from("age"), SimpleValue::from(v))))
+        .collect()
+}
+
+fn filter(name: &str, cond: FilterConditionType, value: FilterValue) -> FilterExpr {
+    FilterExpr::Condition(Filter::new(name, cond, value))
+}
+
+/// The ids of the rows the expression matches in SQLite and with `evaluate`.
+fn both(connection: &mut SqliteConnection, expr: &FilterExpr) -> (Vec<i64>, Vec<i64>) {
+    let evaluated = ROWS
+        .iter()
+        .filter(|(_, name, age)| expr.evaluate(&row(*name, *age)).unwrap())
+        .map(|(id, _, _)| *id)
+        .collect();
+
+    (select(connection, "people", &fields(), expr), evaluated)
+}
+
+/// The ids of the rows of the table the expression matches in SQLite.
+fn select(
+    connection: &mut SqliteConnection,
+    table: &str,
+    fields: &SchemaFieldMap,
+    expr: &FilterExpr,
+) -> Vec<i64> {
+    let sql = SqlFilterBuilder::new(fields, Placeholder::Question)
+        .build(expr)
+        .unwrap();
+
+    let text = format!("SELECT id FROM {table} WHERE {} ORDER BY id", sql.clause);
+    let mut query = sqlx::query(&text);
+
+    for bind in sql.binds {
+        query = match bind {
+            SqlBind::Text(v) => query.bind(v),
+            SqlBind::Integer(v) => query.bind(v),
+            SqlBind::Float(v) => query.bind(v),
+            SqlBind::Boolean(v) => query.bind(v),
+            SqlBind::DateTime(v) => query.bind(v),
+            v => panic!("Unexpected bind {v:?}"),
+        };
+    }
+
+    futures_executor::block_on(query.fetch_all(&mut *connection))
+        .unwrap_or_else(|e| panic!("{text}: {e}"))
+        .iter()
+        .map(|v| v.get(0))
+        .collect()
+}
+
+#[test]
+fn sql_matches_evaluate() {
+    let mut connection = futures_executor::block_on(async {
+        let mut connection = SqliteConnection::connect("sqlite::memory:").await?;
+
+        sqlx::query("CREATE TABLE people (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)")
+            .execute(&mut connection)
+            .await?;
+
+        for (id, name, age) in ROWS {
+            sqlx::query("INSERT INTO people VALUES (?, ?, ?)")
+                .bind(id)
+                .bind(name)
+                .bind(age)
+                .execute(&mut connection)
+                .await?;
+        }
+
+        Ok::<_, sqlx::Error>(connection)
+    })
+    .unwrap();
+
+    let text = |v: &str| FilterValue::Text(v.to_string());
+    let ids = |v: &[&str]| FilterValue::IdList(v.iter().map(|v| v.to_string()).collect());
+
+    let exprs = [
+        filter("name", FilterConditionType::Cont, text("jane")),
+        filter("name", FilterConditionType::Cont, text("DOE")),
+        filter("name", FilterConditionType::Cont, text("0% j")),
+        filter("name", FilterConditionType::Cont, text("e_")),
+        filter("name", FilterConditionType::Dnc, text("jane")),
+        filter("name", FilterConditionType::Eq, text("JANE")),
+        filter("name", FilterConditionType::Neq, text("JANE")),
+        filter("name", FilterConditionType::Eq, ids(&["bob", "JANE"])),
+        filter("name", FilterConditionType::Neq, ids(&["bob", "JANE"])),
+        filter("name", FilterConditionType::Neq, ids(&[])),
+        filter("age", FilterConditionType::Eq, FilterValue::from(30)),
+        filter("age", FilterConditionType::Neq, FilterValue::from(30)),
+        filter("age", FilterConditionType::Gte, FilterValue::from(17)),
+        filter("age", FilterConditionType::Lt, FilterValue::from(18)),
+        filter("age", FilterConditionType::Neq, text("30")),
+        FilterExpr::Not(Box::new(filter(
+            "age",
+            FilterConditionType::Gte,
+            FilterValue::from(18),
+        ))),
+        FilterExpr::Or(vec![
+            filter("name", FilterConditionType::Cont, text("bob")),
+            filter("age", FilterConditionType::Neq, FilterValue::from(30)),
+        ]),
+        FilterExpr::And(vec![
+            filter("name", FilterConditionType::Dnc, text("bob")),
+            FilterExpr::Not(Box::new(filter(
+                "name",
+                FilterConditionType::Cont,
+                text("doe"),
+            ))),
+        ]),
+    ];
+
+    for expr in exprs {
+        let (in_sql, evaluated) = both(&mut connection, &expr);
+
+        assert_eq!(in_sql, evaluated, "{expr:?}");
+    }
+}
+
+#[test]
+fn sql_reads_inside_json() {
+    let addresses = [
+        (1, json!({ "city": "Springfield", "zip": 12345 })),
+        (
+            2,
+            json!({ "city": "Shelbyville", "lines": ["1 Main St", "Flat 2"] }),
+        ),
+        (3, json!({ "zip": 500 })),
+    ];
+
+    let mut connection = futures_executor::block_on(async {
+        let mut connection = SqliteConnection::connect("sqlite::memory:").await?;
+
+        sqlx::query("CREATE TABLE places (id INTEGER PRIMARY KEY, address TEXT)")
+            .execute(&mut connection)
+            .await?;
+
+        for (id, address) in &addresses {
+            sqlx::query("INSERT INTO places VALUES (?, ?)")
+                .bind(id)
+                .bind(address.to_string())
+                .execute(&mut connection)
+                .await?;
+        }
+
+        Ok::<_, sqlx::Error>(connection)
+    })
+    .unwrap();
+
+    let fields = SchematicBuilder::new("places", "Club", "Places", WebsitePublicId::new())
+        .field("address", SchematicFieldType::Object)
+        .primary_field("address")
+        .build()
+        .unwrap()
+        .fields;
+
+    let path = |cond, path: &str, value: FilterValue| {
+        FilterExpr::Condition(Filter::path(path.parse().unwrap(), cond, value))
+    };
+
+    let exprs = [
+        path(
+            FilterConditionType::Eq,
+            "address.city",
+            FilterValue::from("Springfield"),
+        ),
+        path(
+            FilterConditionType::Neq,
+            "address.city",
+            FilterValue::from("Springfield"),
+        ),
+        path(
+            FilterConditionType::Cont,
+            "address.city",
+            FilterValue::from("VILLE"),
+        ),
+        path(
+            FilterConditionType::Gt,
+            "address.zip",
+            FilterValue::from(1000),
+        ),
+        path(
+            FilterConditionType::Eq,
+            "address.lines[1]",
+            FilterValue::from("Flat 2"),
+        ),
+    ];
+
+    for expr in exprs {
+        let evaluated = addresses
+            .iter()
+            .filter(|(_, address)| {
+                expr.evaluate(&HashMap::from([(
+                    SchematicFieldKey::from("address"),
+                    SimpleValue::ObjectUnknown(address.clone()),
+                )]))
+                .unwrap()
+            })
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        assert!(!evaluated.is_empty(), "{expr:?}");
+        assert_eq!(
+            select(&mut connection, "places", &fields, &expr),
+            evaluated,
+            "{expr:?}"
+        );
+    }
+
+    // Without the path the name is a field, which doesn't exist.
+    assert!(
+        SqlFilterBuilder::new(&fields, Placeholder::Question)
+            .build_flat(&[Filter::new(
+                "address.city",
+                FilterConditionType::Eq,
+                "Springfield"
+            )])
+            .is_err()
+    );
+
+    let postgres = SqlFilterBuilder::new(&fields, Placeholder::Dollar)
+        .build_flat(&[
+            Filter::path(
+                "address.lines[0]".parse().unwrap(),
+                FilterConditionType::Eq,
+                "1 Main St",
+            ),
+            Filter::path("address.zip".parse().unwrap(), FilterConditionType::Gte, 10),
+        ])
+        .unwrap();
+    assert_eq!(
+        postgres.clause,
+        "((\"address\" #>> '{lines,0}') = $1 AND ((\"address\" #>> '{zip}')::numeric) >= $2)"
+    );
+
+    assert!(
+        SqlFilterBuilder::new(&fields, Placeholder::Question)
+            .build_flat(&[Filter::path(
+                "address.x'y".parse().unwrap(),
+                FilterConditionType::Eq,
+                "a"
+            )])
+            .is_err()
+    );
+}
+
+/// `(id, published, tags, at, address)`, None being a field without a value.
+type Post = (
+    i64,
+    Option<bool>,
+    Option<&'static [&'static str]>,
+    Option<OffsetDateTime>,
+    Option<serde_json::Value>,
+);
+
+fn posts() -> Vec<Post> {
+    vec![
+        (
+            1,
+            Some(true),
+            Some(&["news", "tech"]),
+            Some(datetime!(2024-05-01 12:00 UTC)),
+            Some(json!({ "city": "London", "country": "GB" })),
+        ),
+        (
+            2,
+            Some(false),
+            Some(&["tech"]),
+            Some(datetime!(2024-05-02 00:00 UTC)),
+            Some(json!({ "country": "US" })),
+        ),
+        (3, None, None, None, None),
+        (
+            4,
+            Some(true),
+            Some(&[]),
+            Some(datetime!(2024-05-01 11:59:59 UTC)),
+            Some(json!({ "city": "Paris" })),
+        ),
+    ]
+}
+
+fn post_fields(post: &Post) -> HashMap<SchematicFieldKey, SimpleValue> {
+    let (_, published, tags, at, address) = post.clone();
+
+    [
+        ("published", published.map(SimpleValue::Boolean)),
+        (
+            "tags",
+            tags.map(|v| SimpleValue::ListString(v.iter().map(|v| v.to_string()).collect())),
+        ),
+        ("at", at.map(SimpleValue::DateTime)),
+        ("address", address.map(SimpleValue::ObjectUnknown)),
+    ]
+    .into_iter()
+    .filter_map(|(k, v)| Some((SchematicFieldKey::from(k), v?)))
+    .collect()
+}
+
+#[test]
+fn sql_matches_evaluate_for_other_types() {
+    let mut connection = futures_executor::block_on(async {
+        let mut connection = SqliteConnection::connect("sqlite::memory:").await?;
+
+        sqlx::query(
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY, published BOOLEAN, tags TEXT, at TEXT, \
+             address TEXT)",
+        )
+        .execute(&mut connection)
+        .await?;
+
+        for (id, published, tags, at, address) in posts() {
+            sqlx::query("INSERT INTO posts VALUES (?, ?, ?, ?, ?)")
+                .bind(id)
+                .bind(published)
+                .bind(tags.map(|v| json!(v).to_string()))
+                .bind(at)
+                .bind(address.map(|v| v.to_string()))
+                .execute(&mut connection)
+                .await?;
+        }
+
+        Ok::<_, sqlx::Error>(connection)
+    })
+    .unwrap();
+
+    let fields = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("published", SchematicFieldType::Boolean)
+        .field("tags", SchematicFieldType::Tags)
+        .field("at", SchematicFieldType::DateTime)
+        .field("address", SchematicFieldType::Address)
+        .primary_field("tags")
+        .build()
+        .unwrap()
+        .fields;
+
+    let noon = datetime!(2024-05-01 12:00 UTC).unix_timestamp();
+    let range = |start: Number, end: Number| FilterValue::Range((start, end));
+    let path = |cond, path: &str, value: &str| {
+        FilterExpr::Condition(Filter::path(path.parse().unwrap(), cond, value))
+    };
+
+    let exprs = [
+        filter("published", FilterConditionType::Eq, "true".into()),
+        filter("published", FilterConditionType::Neq, "true".into()),
+        filter("published", FilterConditionType::Eq, "false".into()),
+        filter("published", FilterConditionType::Gt, "false".into()),
+        filter("published", FilterConditionType::Lte, "false".into()),
+        filter("tags", FilterConditionType::Eq, "tech".into()),
+        filter("tags", FilterConditionType::Neq, "tech".into()),
+        filter("tags", FilterConditionType::Cont, "news".into()),
+        filter("tags", FilterConditionType::Dnc, "news".into()),
+        filter(
+            "at",
+            FilterConditionType::Between,
+            range(Number::Integer(noon), Number::Integer(noon + 43_200)),
+        ),
+        filter(
+            "at",
+            FilterConditionType::Between,
+            range(
+                Number::Float(noon as f64 - 0.5),
+                Number::Float(noon as f64 + 43_200.5),
+            ),
+        ),
+        filter(
+            "at",
+            FilterConditionType::Between,
+            range(
+                Number::Float(noon as f64 - 1.5),
+                Number::Float(noon as f64 - 0.5),
+            ),
+        ),
+        filter("at", FilterConditionType::Gte, "2024-05-01T12:00".into()),
+        path(FilterConditionType::Eq, "address.country", "GB"),
+        path(FilterConditionType::Neq, "address.country", "GB"),
+        path(FilterConditionType::Cont, "address.city", "LON"),
+    ];
+
+    for expr in exprs {
+        let evaluated = posts()
+            .iter()
+            .filter(|v| expr.evaluate(&post_fields(v)).unwrap())
+            .map(|v| v.0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            select(&mut connection, "posts", &fields, &expr),
+            evaluated,
+            "{expr:?}"
+        );
+    }
+
+    // Filters `evaluate` never matches.
+    for filter in [
+        Filter::new("published", FilterConditionType::Eq, "1"),
+        Filter::new("published", FilterConditionType::Eq, 1),
+        Filter::new("tags", FilterConditionType::Gt, "a"),
+        Filter::new("tags", FilterConditionType::Eq, vec![String::from("a")]),
+        Filter::new("address", FilterConditionType::Eq, "London"),
+        Filter::new("address", FilterConditionType::Cont, "London"),
+        Filter::new(
+            "tags",
+            FilterConditionType::Between,
+            (Number::Integer(1), Number::Integer(2)),
+        ),
+        Filter::new(
+            "at",
+            FilterConditionType::Between,
+            (Number::Float(f64::NAN), Number::Integer(2)),
+        ),
+    ] {
+        assert!(
+            SqlFilterBuilder::new(&fields, Placeholder::Question)
+                .build_flat(std::slice::from_ref(&filter))
+                .is_err(),
+            "{filter:?}"
+        );
+    }
+
+    let postgres = SqlFilterBuilder::new(&fields, Placeholder::Dollar)
+        .build_flat(&[Filter::new("tags", FilterConditionType::Neq, "news")])
+        .unwrap();
+    assert_eq!(
+        postgres.clause,
+        "(NOT EXISTS (SELECT 1 FROM jsonb_array_elements_text(\"tags\") AS item WHERE item = $1))"
+    );
+}
+
+#[test]
+fn numbers_only_match_number_fields() {
+    let fields = fields();
+
+    for filter in [
+        Filter::new("name", FilterConditionType::Eq, 30),
+        Filter::new("name", FilterConditionType::Gt, 30),
+        Filter::new(
+            "name",
+            FilterConditionType::Between,
+            (Number::Integer(1), Number::Integer(2)),
+        ),
+    ] {
+        assert!(
+            SqlFilterBuilder::new(&fields, Placeholder::Question)
+                .build_flat(std::slice::from_ref(&filter))
+                .is_err(),
+            "{filter:?}"
+        );
+        assert!(
+            !FilterExpr::from(filter)
+                .evaluate(&row(Some("30"), Some(30)))
+                .unwrap()
+        );
+    }
+
+    // Numbers are searched as text by `evaluate`, which SQL doesn't do the same way everywhere.
+    assert!(
+        SqlFilterBuilder::new(&fields, Placeholder::Question)
+            .build_flat(&[Filter::new("age", FilterConditionType::Cont, "3")])
+            .is_err()
+    );
+}
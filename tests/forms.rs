@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use webby_global_common::{
+    forms::{FormDefinition, FormField, FormStep, FormSubmission, SubmissionMetadata},
+    id::{FormPublicId, MemberPublicId},
+    schema::{
+        SchematicFieldKey, SchematicFieldType, constraints::FieldConstraints,
+        validation::ValidationErrorKind,
+    },
+    uuid::CollectionName,
+    value::{Number, SimpleValue},
+};
+
+fn field(key: &str, field_type: SchematicFieldType, required: bool) -> FormField {
+    FormField {
+        key: key.to_string(),
+        field_type,
+        label: key.to_string(),
+        placeholder: None,
+        help_text: None,
+        validation: FieldConstraints {
+            required,
+            ..Default::default()
+        },
+    }
+}
+
+fn form() -> FormDefinition {
+    FormDefinition {
+        id: FormPublicId::new(),
+        name: String::from("Contact"),
+        collection: CollectionName::from("contact"),
+        steps: vec![
+            FormStep {
+                title: None,
+                fields: vec![
+                    field("email", SchematicFieldType::Email, true),
+                    field("name", SchematicFieldType::Text, false),
+                ],
+            },
+            FormStep {
+                title: Some(String::from("Details")),
+                fields: vec![
+                    field("age", SchematicFieldType::Number, false),
+                    field("subscribe", SchematicFieldType::Boolean, false),
+                ],
+            },
+        ],
+        submit_label: None,
+        success_message: None,
+    }
+}
+
+fn answers(values: &[(&str, &str)]) -> HashMap<String, SimpleValue> {
+    values
+        .iter()
+        .map(|(k, v)| (k.to_string(), SimpleValue::Text(v.to_string())))
+        .collect()
+}
+
+#[test]
+fn validates_submissions() {
+    let form = form();
+
+    let submission = FormSubmission {
+        form_id: form.id,
+        answers: answers(&[("age", "abc"), ("extra", "1")]),
+        metadata: Default::default(),
+        idempotency_key: None,
+    };
+
+    let errors = form.validate(&submission).unwrap();
+    let kinds = errors
+        .iter()
+        .map(|e| (e.field.as_str(), &e.kind))
+        .collect::<Vec<_>>();
+
+    assert_eq!(kinds.len(), 3);
+    assert!(matches!(
+        kinds[0],
+        ("age", ValidationErrorKind::InvalidType { .. })
+    ));
+    assert_eq!(kinds[1], ("email", &ValidationErrorKind::Required));
+    assert_eq!(kinds[2], ("extra", &ValidationErrorKind::UnknownField));
+
+    // Only the first step is checked.
+    let errors = form
+        .validate_step(0, &answers(&[("email", "a@example.com"), ("age", "abc")]))
+        .unwrap();
+
+    assert!(errors.is_empty());
+    assert!(form.validate_step(2, &HashMap::new()).is_err());
+}
+
+#[test]
+fn submissions_become_rows() {
+    let form = form();
+
+    let submission = FormSubmission {
+        form_id: form.id,
+        answers: answers(&[
+            ("email", "a@example.com"),
+            ("age", "42"),
+            ("subscribe", "on"),
+            ("extra", "dropped"),
+        ]),
+        metadata: Default::default(),
+        idempotency_key: None,
+    };
+
+    assert_eq!(form.validate(&submission).unwrap().len(), 1);
+
+    let insert = submission.to_insert(&form).unwrap();
+    let row = &insert.rows[0];
+
+    assert_eq!(row.len(), 3);
+    assert_eq!(
+        row[&SchematicFieldKey::from("age")],
+        SimpleValue::Number(Number::Integer(42))
+    );
+    assert_eq!(
+        row[&SchematicFieldKey::from("subscribe")],
+        SimpleValue::Boolean(true)
+    );
+
+    let other = FormSubmission {
+        form_id: FormPublicId::new(),
+        ..submission
+    };
+
+    assert!(other.to_row(&form).is_err());
+}
+
+#[test]
+fn clients_cannot_send_metadata() {
+    let form_id = FormPublicId::new();
+
+    let submission: FormSubmission = serde_json::from_value(serde_json::json!({
+        "formId": form_id,
+        "answers": { "name": "Jane" },
+        "metadata": {
+            "ipAddress": "10.0.0.1",
+            "member": MemberPublicId::new(),
+            "userAgent": "Spoofed",
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(submission.form_id, form_id);
+    assert_eq!(submission.metadata, SubmissionMetadata::default());
+}
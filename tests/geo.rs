@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use webby_global_common::{
+    filter::{Filter, FilterConditionType},
+    geo::GeoPoint,
+    request::query::CmsQueryBuilder,
+    schema::{SchematicFieldKey, SchematicFieldType, SchematicFieldValue},
+    testing::assert_roundtrip,
+    value::SimpleValue,
+};
+
+fn point(lat: f64, lon: f64) -> GeoPoint {
+    GeoPoint::new(lat, lon).unwrap()
+}
+
+const LONDON: (f64, f64) = (51.5072, -0.1276);
+const PARIS: (f64, f64) = (48.8566, 2.3522);
+
+#[test]
+fn validates_ranges() {
+    assert!(GeoPoint::new(90.0, 180.0).is_ok());
+    assert!(GeoPoint::new(-90.0, -180.0).is_ok());
+
+    for (lat, lon) in [
+        (90.1, 0.0),
+        (0.0, -180.5),
+        (f64::NAN, 0.0),
+        (0.0, f64::INFINITY),
+    ] {
+        assert!(GeoPoint::new(lat, lon).is_err(), "{lat},{lon}");
+    }
+
+    assert!(serde_json::from_str::<GeoPoint>(r#"{"lat":91,"lon":0}"#).is_err());
+    assert_eq!(
+        "51.5072, -0.1276".parse::<GeoPoint>().unwrap(),
+        point(LONDON.0, LONDON.1)
+    );
+    assert!("51.5072".parse::<GeoPoint>().is_err());
+
+    assert_roundtrip(&point(LONDON.0, LONDON.1));
+}
+
+#[test]
+fn distances() {
+    let london = point(LONDON.0, LONDON.1);
+    let paris = point(PARIS.0, PARIS.1);
+
+    let distance = london.distance_to(&paris);
+
+    assert!((distance - 343_900.0).abs() < 1_000.0, "{distance}");
+    assert_eq!(distance, paris.distance_to(&london));
+    assert_eq!(london.distance_to(&london), 0.0);
+
+    assert!(paris.is_within(&london, 350_000.0));
+    assert!(!paris.is_within(&london, 300_000.0));
+
+    // Across the antimeridian.
+    let distance = point(0.0, 179.5).distance_to(&point(0.0, -179.5));
+    assert!((distance - 111_195.0).abs() < 100.0, "{distance}");
+}
+
+#[test]
+fn location_fields() {
+    let value = SchematicFieldType::Location
+        .parse_value(SimpleValue::Text(String::from("48.8566,2.3522")))
+        .unwrap();
+
+    assert_eq!(
+        value,
+        SchematicFieldValue::Location(point(PARIS.0, PARIS.1))
+    );
+
+    let stored = SimpleValue::from(value.clone());
+
+    assert_eq!(
+        stored,
+        SimpleValue::ObjectUnknown(serde_json::json!({ "lat": 48.8566, "lon": 2.3522 }))
+    );
+    assert_eq!(
+        SchematicFieldType::Location.parse_value(stored).unwrap(),
+        value
+    );
+    assert!(
+        SchematicFieldType::Location
+            .parse_value(SimpleValue::Text(String::from("100,0")))
+            .is_err()
+    );
+}
+
+#[test]
+fn near_filters() {
+    let store = |lat: f64, lon: f64| {
+        HashMap::from([(
+            SchematicFieldKey::from("location"),
+            SimpleValue::from(point(lat, lon)),
+        )])
+    };
+
+    let filter = Filter::near("location", point(LONDON.0, LONDON.1), 10_000.0);
+
+    assert!(filter.evaluate(&store(51.5, -0.12)).unwrap());
+    assert!(!filter.evaluate(&store(PARIS.0, PARIS.1)).unwrap());
+    assert!(!filter.evaluate(&HashMap::new()).unwrap());
+
+    let invalid = Filter::near("location", point(0.0, 0.0), -1.0);
+    assert!(invalid.evaluate(&store(0.0, 0.0)).is_err());
+
+    let json = serde_json::to_value(&filter).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "name": "location",
+            "cond": "near",
+            "value": { "lat": 51.5072, "lon": -0.1276 },
+            "radius": 10000.0,
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<Filter>(json.clone()).unwrap(),
+        filter
+    );
+    assert_eq!(filter.cond, FilterConditionType::Near);
+    assert_eq!(filter.cond.as_str(), "near");
+
+    let query = CmsQueryBuilder::new()
+        .near("location", point(LONDON.0, LONDON.1), 10_000.0)
+        .build()
+        .to_query_string();
+
+    assert_eq!(
+        query,
+        "filters%5B0%5D%5Bname%5D=location&filters%5B0%5D%5Bcond%5D=near\
+         &filters%5B0%5D%5Bvalue%5D=51.5072%2C-0.1276&filters%5B0%5D%5Bradius%5D=10000"
+    );
+
+    // The query string has the same fields as the JSON.
+    let pairs = url::form_urlencoded::parse(query.as_bytes())
+        .map(|(k, v)| {
+            (
+                k.trim_start_matches("filters[0][")
+                    .trim_end_matches(']')
+                    .to_owned(),
+                v,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    assert_eq!(pairs.len(), json.as_object().unwrap().len());
+    assert_eq!(json["name"], *pairs["name"]);
+    assert_eq!(json["cond"], *pairs["cond"]);
+    assert_eq!(
+        pairs["value"].parse::<GeoPoint>().unwrap(),
+        serde_json::from_value::<GeoPoint>(json["value"].clone()).unwrap()
+    );
+    assert_eq!(pairs["radius"].parse::<f64>().unwrap(), json["radius"]);
+}
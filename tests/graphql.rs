@@ -0,0 +1,113 @@
+#![cfg(feature = "graphql")]
+
+use serde_json::json;
+use time::macros::date;
+use webby_global_common::{
+    geo::GeoPoint,
+    id::WebsitePublicId,
+    schema::{
+        SchematicFieldType, SchematicFieldValue,
+        builder::SchematicBuilder,
+        graphql::{SHARED_DEFINITIONS, to_graphql_document},
+    },
+};
+
+#[test]
+fn schema_becomes_sdl() {
+    let schematic = SchematicBuilder::new(
+        "blog-posts",
+        "Blog",
+        "Blog \"Posts\"",
+        WebsitePublicId::new(),
+    )
+    .field("title", SchematicFieldType::Text)
+    .required()
+    .field("summary", SchematicFieldType::Text)
+    .localized()
+    .field("author", SchematicFieldType::Reference)
+    .reference("authors")
+    .field("tags", SchematicFieldType::Tags)
+    .field("price", SchematicFieldType::Currency)
+    .field("2nd-title", SchematicFieldType::Text)
+    .display_name("Second title")
+    .primary_field("title")
+    .build()
+    .unwrap();
+
+    assert_eq!(schematic.graphql_type_name(), "BlogPosts");
+    assert_eq!(
+        schematic.to_graphql_sdl(),
+        r#""Blog \"Posts\""
+type BlogPosts {
+  _id: ID!
+  _owner: String
+  _createdAt: DateTime!
+  _updatedAt: DateTime!
+  "title"
+  title: String!
+  "summary"
+  summary: JSON
+  "author"
+  author: ID
+  "tags"
+  tags: [String!]
+  "price"
+  price: Money
+  "Second title"
+  _2nd_title: String
+}
+
+input BlogPostsFilter {
+  and: [BlogPostsFilter!]
+  or: [BlogPostsFilter!]
+  not: BlogPostsFilter
+  _id: IDFilter
+  _owner: StringFilter
+  _createdAt: DateTimeFilter
+  _updatedAt: DateTimeFilter
+  title: StringFilter
+  author: IDFilter
+  tags: ListFilter
+  _2nd_title: StringFilter
+}
+
+type BlogPostsConnection {
+  items: [BlogPosts!]!
+  offset: Int!
+  limit: Int!
+  total: Int!
+  nextCursor: String
+  prevCursor: String
+}
+"#
+    );
+
+    let document = to_graphql_document([&schematic]);
+
+    assert!(document.starts_with(SHARED_DEFINITIONS));
+    assert!(document.ends_with(
+        "type Query {\n  blog_posts(filter: BlogPostsFilter, offset: Int, limit: Int, cursor: String): BlogPostsConnection!\n}\n"
+    ));
+}
+
+#[test]
+fn values_become_scalars() {
+    assert_eq!(
+        SchematicFieldValue::Date(date!(2024 - 01 - 31)).to_graphql(),
+        json!("2024-01-31")
+    );
+    assert_eq!(
+        SchematicFieldValue::Location(GeoPoint {
+            lat: 1.5,
+            lon: -2.0
+        })
+        .to_graphql(),
+        json!({ "lat": 1.5, "lon": -2.0 })
+    );
+    assert_eq!(
+        SchematicFieldValue::Tags(vec![String::from("rust")]).to_graphql(),
+        json!(["rust"])
+    );
+    assert_eq!(SchematicFieldType::Location.graphql_type(), "GeoPoint");
+    assert_eq!(SchematicFieldType::Object.graphql_filter(), None);
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use webby_global_common::{
+    i18n::{LanguageTag, LocalizedString},
+    id::WebsitePublicId,
+    schema::{
+        SchematicFieldKey, SchematicFieldType, builder::SchematicBuilder,
+        constraints::FieldConstraints, validation::ValidationErrorKind,
+    },
+    value::SimpleValue,
+};
+
+fn tag(value: &str) -> LanguageTag {
+    LanguageTag::parse(value).unwrap()
+}
+
+#[test]
+fn parses_language_tags() {
+    assert_eq!(tag("EN-us").as_str(), "en-US");
+    assert_eq!(tag("zh_hant_tw").as_str(), "zh-Hant-TW");
+    assert_eq!(tag("es-419").as_str(), "es-419");
+    assert_eq!(tag("de-CH-1901").language(), "de");
+    assert_eq!(
+        tag("zh-Hant-TW").fallbacks().collect::<Vec<_>>(),
+        ["zh-Hant-TW", "zh-Hant", "zh"]
+    );
+
+    for invalid in [
+        "",
+        "e",
+        "english",
+        "en-",
+        "en--US",
+        "en-US!",
+        "en-toolongsubtag",
+    ] {
+        assert!(LanguageTag::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn resolves_localized_strings() {
+    let mut value = LocalizedString::new(tag("en"), "Hello");
+    value.set(tag("de"), "Hallo");
+    value.set(tag("de-CH"), "Grüezi");
+
+    assert_eq!(value.resolve(&tag("de-CH")), "Grüezi");
+    assert_eq!(value.resolve(&tag("de-AT")), "Hallo");
+    assert_eq!(value.resolve(&tag("fr")), "Hello");
+    assert_eq!(value.resolve_preferred(&[tag("fr"), tag("de")]), "Hallo");
+    assert_eq!(value.get(&tag("de-AT")), None);
+    assert_eq!(value.missing(&[tag("en"), tag("fr")]), [&tag("fr")]);
+
+    let stored = SimpleValue::from(value.clone());
+    assert_eq!(
+        LocalizedString::from_value(&stored, &tag("fr")).unwrap(),
+        value
+    );
+
+    // Values stored before the field was localized.
+    let legacy = SimpleValue::Text(String::from("Bonjour"));
+    assert_eq!(
+        LocalizedString::from_value(&legacy, &tag("fr")).unwrap(),
+        LocalizedString::new(tag("fr"), "Bonjour")
+    );
+
+    let without_default = SimpleValue::ObjectUnknown(json!({
+        "defaultLanguage": "en",
+        "values": { "de": "Hallo" }
+    }));
+    assert!(LocalizedString::from_value(&without_default, &tag("en")).is_err());
+}
+
+#[test]
+fn localized_fields() {
+    assert!(
+        SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+            .field("rating", SchematicFieldType::Number)
+            .localized()
+            .primary_field("rating")
+            .build()
+            .is_err()
+    );
+
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .localized()
+        .constraints(FieldConstraints {
+            max_length: Some(8),
+            ..Default::default()
+        })
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let title = SchematicFieldKey::from("title");
+    assert!(schematic.fields[&title].localized);
+
+    let validate = |value: SimpleValue| {
+        schematic
+            .validate_record(&HashMap::from([(title.clone(), value)]))
+            .unwrap()
+    };
+
+    let mut value = LocalizedString::new(tag("en"), "Hello");
+    value.set(tag("de"), "Hallo");
+
+    assert!(validate(SimpleValue::from(value.clone())).is_empty());
+    assert!(validate(SimpleValue::Text(String::from("Hello"))).is_empty());
+
+    value.set(tag("fr"), "Bonjour à tous");
+    assert!(matches!(
+        validate(SimpleValue::from(value))[0].kind,
+        ValidationErrorKind::LengthOutOfRange { .. }
+    ));
+
+    assert!(matches!(
+        validate(SimpleValue::ObjectUnknown(json!({ "en": "Hello" })))[0].kind,
+        ValidationErrorKind::InvalidType { .. }
+    ));
+
+    let schema = schematic.to_json_schema();
+    let property = &schema["properties"]["title"];
+    assert_eq!(property["title"], "title");
+    assert_eq!(property["anyOf"][0]["maxLength"], 8);
+    assert_eq!(
+        property["anyOf"][1]["properties"]["values"]["additionalProperties"]["type"],
+        "string"
+    );
+
+    let value = serde_json::to_value(&schematic.fields[&title]).unwrap();
+    assert_eq!(value["localized"], true);
+}
+
+#[test]
+fn localized_values_have_a_max_length() {
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("body", SchematicFieldType::RichText)
+        .localized()
+        .primary_field("body")
+        .build()
+        .unwrap();
+
+    let max = SchematicFieldType::RichText.max_bytes_length().unwrap();
+    let mut value = LocalizedString::new(tag("en"), "Hello");
+    value.set(tag("de"), "a".repeat(max + 1));
+
+    let errors = schematic
+        .validate_record(&HashMap::from([(
+            SchematicFieldKey::from("body"),
+            SimpleValue::from(value),
+        )]))
+        .unwrap();
+
+    assert_eq!(
+        errors[0].kind,
+        ValidationErrorKind::TooLong {
+            max,
+            actual: max + 1
+        }
+    );
+}
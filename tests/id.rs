@@ -0,0 +1,184 @@
+use uuid::Uuid;
+use webby_global_common::{
+    create_prefixed_id,
+    id::{
+        AnyPublicId, ApiKeyId, FormPublicId, IdKind, MemberPublicId, Short, decode_short,
+        encode_short,
+    },
+    testing::assert_roundtrip,
+    uuid::{CollectionName, Namespace, UuidType},
+};
+
+create_prefixed_id!(TestFormId, "form", TestPageId, "page");
+
+#[test]
+fn prefixed_ids() {
+    let uuid = Uuid::parse_str("0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a").unwrap();
+    let id = TestFormId::from(uuid);
+
+    assert_eq!(id.to_string(), "form_0190a5b87c4e7d219c3a2f1e5d6c7b8a");
+    assert_eq!(*id, uuid);
+
+    assert_eq!(
+        "form_0190a5b87c4e7d219c3a2f1e5d6c7b8a"
+            .parse::<TestFormId>()
+            .unwrap(),
+        id
+    );
+    assert_eq!(
+        "0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a"
+            .parse::<TestFormId>()
+            .unwrap(),
+        id
+    );
+    assert!(
+        "page_0190a5b87c4e7d219c3a2f1e5d6c7b8a"
+            .parse::<TestFormId>()
+            .is_err()
+    );
+    assert!("form_nope".parse::<TestFormId>().is_err());
+
+    assert_eq!(
+        serde_json::to_value(id).unwrap(),
+        "form_0190a5b87c4e7d219c3a2f1e5d6c7b8a"
+    );
+    assert_eq!(assert_roundtrip(&id), id);
+    assert!(
+        serde_json::from_str::<TestPageId>("\"form_0190a5b87c4e7d219c3a2f1e5d6c7b8a\"").is_err()
+    );
+}
+
+#[test]
+fn short_ids() {
+    assert_eq!(encode_short(0), "0000000000000000000000");
+    assert_eq!(encode_short(u128::MAX), "7n42DGM5Tflk9n8mt7Fhc7");
+    assert_eq!(decode_short("7n42DGM5Tflk9n8mt7Fhc7").unwrap(), u128::MAX);
+    assert!(decode_short("7n42DGM5Tflk9n8mt7Fhc8").is_err());
+    assert!(decode_short("7n42DGM5Tflk9n8mt7Fhc-").is_err());
+    assert!(decode_short("abc").is_err());
+
+    let id = FormPublicId::new();
+    let short = id.to_short_string();
+
+    assert_eq!(short.len(), 22);
+    assert_eq!(FormPublicId::from_short_string(&short).unwrap(), id);
+
+    let json = serde_json::to_value(Short(id)).unwrap();
+    assert_eq!(json, short.as_str());
+    assert_eq!(assert_roundtrip(&Short(id)).0, id);
+    assert_eq!(
+        serde_json::from_value::<Short<FormPublicId>>(serde_json::json!(id.to_string()))
+            .unwrap()
+            .0,
+        id
+    );
+
+    let prefixed = TestFormId::new();
+    assert_eq!(
+        TestFormId::from_short_string(&prefixed.to_short_string()).unwrap(),
+        prefixed
+    );
+}
+
+#[test]
+fn any_public_id_round_trip() {
+    let member = MemberPublicId::new();
+    let any = AnyPublicId::from(member);
+
+    assert_eq!(any.kind(), IdKind::Member);
+    assert_eq!(any.uuid(), *member);
+    assert_eq!(AnyPublicId::new(IdKind::Member, *member), any);
+    assert_eq!(
+        serde_json::to_value(any).unwrap(),
+        serde_json::json!({ "type": "Member", "value": member.to_string() })
+    );
+    assert_eq!(assert_roundtrip(&any), any);
+
+    let key = AnyPublicId::from(ApiKeyId::new());
+    assert_ne!(AnyPublicId::new(IdKind::Page, key.uuid()), key);
+}
+
+#[test]
+fn parses_uuid_types() {
+    let uuid = "0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a";
+
+    for value in [
+        format!("s:{uuid}"),
+        format!("a:{uuid}"),
+        format!("t:{uuid}"),
+        String::from("system"),
+    ] {
+        let parsed = value.parse::<UuidType>().unwrap();
+
+        assert_eq!(parsed.to_string(), value);
+        assert_eq!(
+            serde_json::from_value::<UuidType>(serde_json::to_value(parsed).unwrap()).unwrap(),
+            parsed
+        );
+    }
+
+    assert!(uuid.parse::<UuidType>().unwrap().is_site());
+    assert!("system".parse::<UuidType>().unwrap().get_uuid().is_nil());
+
+    for invalid in [
+        "",
+        "s",
+        "s:",
+        "a:nope",
+        "x:0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a",
+        "é",
+    ] {
+        assert!(invalid.parse::<UuidType>().is_err(), "{invalid}");
+        assert!(serde_json::from_value::<UuidType>(serde_json::json!(invalid)).is_err());
+    }
+}
+
+#[test]
+fn collection_names() {
+    for value in [
+        "posts",
+        "shop:products",
+        "Forms/Contact",
+        "shop:Billing/invoices",
+        "shop:Extras/notes",
+    ] {
+        let name: CollectionName = serde_json::from_value(serde_json::json!(value)).unwrap();
+
+        assert_eq!(serde_json::to_value(&name).unwrap(), value);
+        assert_eq!(value.parse::<CollectionName>().unwrap(), name);
+    }
+
+    let name = CollectionName::parse("shop:Forms/Contact").unwrap();
+    assert_eq!(name.ns.as_deref(), Some("shop"));
+    assert_eq!(name.id, "Forms/Contact");
+    assert_eq!(name.local(), Some(Namespace::Forms));
+    assert_eq!(name.name(), "Contact");
+
+    assert_eq!(
+        CollectionName::parse("Extras/notes").unwrap().local(),
+        Some(Namespace::Custom(String::from("Extras")))
+    );
+    assert_eq!(CollectionName::from("posts").local(), None);
+    assert_eq!(CollectionName::from("posts").name(), "posts");
+
+    for invalid in [
+        "",
+        "1posts",
+        "po sts",
+        "posts!",
+        ":posts",
+        "Forms/",
+        "System",
+        "a:b:c",
+        &"a".repeat(65),
+    ] {
+        assert!(CollectionName::parse(invalid).is_err(), "{invalid}");
+
+        // Names stored before they were validated still load.
+        let stored: CollectionName = serde_json::from_value(serde_json::json!(invalid)).unwrap();
+        assert_eq!(serde_json::to_value(&stored).unwrap(), invalid);
+    }
+
+    // Reserved names are fine inside a namespace.
+    assert!(CollectionName::parse("shop:system").is_ok());
+}
@@ -0,0 +1,135 @@
+use serde_json::json;
+
+use webby_global_common::{
+    id::WebsitePublicId,
+    schema::{
+        Schematic, SchematicFieldKey, SchematicFieldType,
+        builder::SchematicBuilder,
+        constraints::{FieldConstraints, Pattern},
+        json_schema::JSON_SCHEMA_DRAFT_07,
+    },
+};
+
+fn schematic() -> Schematic {
+    SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .display_name("Title")
+        .constraints(FieldConstraints {
+            required: true,
+            max_length: Some(80),
+            pattern: Some(Pattern::from("^[A-Z]")),
+            ..Default::default()
+        })
+        .field("status", SchematicFieldType::Text)
+        .constraints(FieldConstraints {
+            allowed_values: vec![String::from("draft"), String::from("live")],
+            ..Default::default()
+        })
+        .field("rating", SchematicFieldType::Number)
+        .constraints(FieldConstraints {
+            min: Some(1.into()),
+            max: Some(5.into()),
+            ..Default::default()
+        })
+        .field("tags", SchematicFieldType::Tags)
+        .constraints(FieldConstraints {
+            max_length: Some(3),
+            pattern: Some(Pattern::from("^[a-z]+$")),
+            ..Default::default()
+        })
+        .field("author", SchematicFieldType::Reference)
+        .reference("people")
+        .field("old", SchematicFieldType::Text)
+        .primary_field("title")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn describes_records() {
+    let mut schematic = schematic();
+
+    let mut old = schematic.fields.get("old").unwrap().clone();
+    old.is_deleted = true;
+    schematic.fields.insert(SchematicFieldKey::from("old"), old);
+
+    let schema = schematic.to_json_schema();
+
+    assert_eq!(schema["$schema"], JSON_SCHEMA_DRAFT_07);
+    assert_eq!(schema["$id"], "Blog/posts");
+    assert_eq!(schema["title"], "Posts");
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(schema["required"], json!(["title"]));
+
+    let mut keys = schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    keys.sort();
+
+    // Deleted fields are left out.
+    assert_eq!(
+        keys,
+        [
+            "_createdAt",
+            "_id",
+            "_owner",
+            "_updatedAt",
+            "author",
+            "rating",
+            "status",
+            "tags",
+            "title",
+        ]
+    );
+    assert_eq!(schema["properties"]["_createdAt"]["format"], "date-time");
+    assert_eq!(schema["properties"]["_id"]["type"], "string");
+}
+
+#[test]
+fn describes_fields() {
+    let schema = schematic().to_json_schema();
+    let properties = &schema["properties"];
+
+    assert_eq!(
+        properties["title"],
+        json!({ "type": "string", "title": "Title", "maxLength": 80, "pattern": "^[A-Z]" })
+    );
+    assert_eq!(properties["status"]["enum"], json!(["draft", "live"]));
+    assert_eq!(properties["rating"]["type"], "number");
+    assert_eq!(properties["rating"]["minimum"], 1);
+    assert_eq!(properties["rating"]["maximum"], 5);
+    // Lengths of lists limit the number of items, and patterns only apply to text.
+    assert_eq!(properties["tags"]["maxItems"], 3);
+    assert!(properties["tags"].get("maxLength").is_none());
+    assert!(properties["tags"].get("pattern").is_none());
+    assert_eq!(properties["author"]["format"], "uuid");
+    assert!(properties["author"].get("readOnly").is_none());
+}
+
+#[test]
+fn field_types() {
+    assert_eq!(
+        SchematicFieldType::Email.to_json_schema(),
+        json!({ "type": "string", "format": "email" })
+    );
+    assert_eq!(
+        SchematicFieldType::MultiReference.to_json_schema()["items"]["format"],
+        "uuid"
+    );
+    assert_eq!(
+        SchematicFieldType::Currency.to_json_schema()["required"],
+        json!(["amountMinor", "currency"])
+    );
+    assert_eq!(
+        SchematicFieldType::Image.to_json_schema()["items"]["maximum"],
+        255
+    );
+    assert_eq!(
+        SchematicFieldType::Address.to_json_schema()["oneOf"][1]["type"],
+        "string"
+    );
+}
@@ -0,0 +1,84 @@
+use webby_global_common::limits::{Limit, LimitKind, PlanLimits, QuotaError, enforce};
+
+fn plan() -> PlanLimits {
+    PlanLimits {
+        max_collections: Some(10),
+        max_rows_per_collection: Some(1000),
+        max_storage_bytes: None,
+        max_requests_per_minute: Some(60),
+    }
+}
+
+#[test]
+fn enforces_limits() {
+    let plan = plan();
+
+    assert!(plan.enforce(LimitKind::Collections, 9, 1).is_ok());
+    assert_eq!(
+        plan.enforce(LimitKind::Collections, 9, 2),
+        Err(QuotaError::Exceeded {
+            kind: LimitKind::Collections,
+            limit: 10,
+            current: 9,
+            requested: 2,
+        })
+    );
+    assert!(plan.enforce(LimitKind::RowsPerCollection, 1000, 0).is_ok());
+    // Already over the limit, eg. after a downgrade.
+    assert!(plan.enforce(LimitKind::RowsPerCollection, 1200, 0).is_err());
+
+    assert!(
+        plan.enforce(LimitKind::StorageBytes, u64::MAX, u64::MAX)
+            .is_ok()
+    );
+    assert!(
+        PlanLimits::unlimited()
+            .enforce(LimitKind::Collections, 1_000_000, 1)
+            .is_ok()
+    );
+
+    // Doesn't overflow.
+    let limit = Limit {
+        kind: LimitKind::RequestsPerMinute,
+        max: Some(u64::MAX - 1),
+    };
+    assert!(enforce(limit, u64::MAX, u64::MAX).is_err());
+}
+
+#[test]
+fn remaining() {
+    let plan = plan();
+    let collections = plan.limit(LimitKind::Collections);
+
+    assert_eq!(collections.max, Some(10));
+    assert!(!collections.is_unlimited());
+    assert_eq!(collections.remaining(4), Some(6));
+    assert_eq!(collections.remaining(12), Some(0));
+
+    let storage = plan.limit(LimitKind::StorageBytes);
+
+    assert!(storage.is_unlimited());
+    assert_eq!(storage.remaining(4), None);
+}
+
+#[test]
+fn serializes() {
+    let value = serde_json::to_value(plan()).unwrap();
+
+    assert_eq!(value["maxRowsPerCollection"], 1000);
+    assert!(value["maxStorageBytes"].is_null());
+    assert_eq!(serde_json::from_value::<PlanLimits>(value).unwrap(), plan());
+
+    let error = plan()
+        .enforce(LimitKind::RequestsPerMinute, 60, 1)
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Quota Exceeded for requests per minute: 60 used + 1 requested > 60"
+    );
+    assert_eq!(
+        serde_json::to_value(&error).unwrap()["value"]["kind"],
+        "requestsPerMinute"
+    );
+}
@@ -0,0 +1,93 @@
+use serde_json::json;
+use time::{Duration, macros::datetime};
+use webby_global_common::{
+    id::{MemberPublicId, WebsitePublicId},
+    members::{MemberStatus, PublicMember, SessionClaims},
+    validators::EmailAddress,
+};
+
+fn member() -> PublicMember {
+    PublicMember {
+        id: MemberPublicId::new(),
+        email: EmailAddress::parse("jane@Example.com").unwrap(),
+        display_name: None,
+        avatar: None,
+        roles: vec![String::from("subscriber")],
+        status: MemberStatus::Active,
+        created_at: datetime!(2024-01-01 0:00 UTC),
+    }
+}
+
+#[test]
+fn member_names() {
+    let mut member = member();
+
+    assert_eq!(member.name(), "jane");
+    assert!(member.has_role("subscriber"));
+    assert!(member.can_sign_in());
+
+    member.display_name = Some(String::from("Jane Doe"));
+    assert_eq!(member.name(), "Jane Doe");
+
+    let value = serde_json::to_value(&member).unwrap();
+    assert_eq!(value["email"], "jane@example.com");
+    assert_eq!(value["displayName"], "Jane Doe");
+    assert_eq!(
+        serde_json::from_value::<PublicMember>(value.clone()).unwrap(),
+        member
+    );
+
+    // Members stored before addresses were validated still load.
+    let mut value = value;
+    value["email"] = json!("Jane Doe");
+    let stored = serde_json::from_value::<PublicMember>(value).unwrap();
+    assert!(!stored.email.is_valid());
+    assert_eq!(stored.email.as_str(), "Jane Doe");
+}
+
+#[test]
+fn session_claims() {
+    let member = member();
+    let site = WebsitePublicId::new();
+    let issued_at = datetime!(2024-05-01 12:00 UTC);
+
+    let claims = SessionClaims::new(&member, site, issued_at, Duration::hours(1)).unwrap();
+    let value = serde_json::to_value(&claims).unwrap();
+
+    assert_eq!(value["sub"], json!(member.id));
+    assert_eq!(value["site"], json!(site));
+    assert_eq!(value["iat"], issued_at.unix_timestamp());
+    assert_eq!(value["exp"], issued_at.unix_timestamp() + 3600);
+    assert_eq!(
+        serde_json::from_value::<SessionClaims>(value).unwrap(),
+        claims
+    );
+
+    assert!(claims.is_valid_for(site, issued_at + Duration::minutes(59)));
+    assert!(!claims.is_valid_for(site, issued_at + Duration::hours(1)));
+    assert!(!claims.is_valid_for(WebsitePublicId::new(), issued_at));
+
+    let ctx = claims.request_context();
+    assert_eq!(ctx.user_id, Some(*member.id));
+    assert_eq!(ctx.roles, member.roles);
+    assert!(!ctx.is_admin);
+}
+
+#[test]
+fn only_active_members_get_claims() {
+    let issued_at = datetime!(2024-05-01 12:00 UTC);
+
+    for status in [MemberStatus::Pending, MemberStatus::Blocked] {
+        let member = PublicMember { status, ..member() };
+
+        assert!(
+            SessionClaims::new(
+                &member,
+                WebsitePublicId::new(),
+                issued_at,
+                Duration::hours(1)
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use webby_global_common::{
+    error::GlobalError,
+    i18n::LanguageTag,
+    money::{CurrencyCode, Money},
+    schema::{SchematicFieldType, SchematicFieldValue},
+    testing::assert_roundtrip,
+    value::{Number, SimpleValue},
+};
+
+fn money(value: &str) -> Money {
+    value.parse().unwrap()
+}
+
+fn tag(value: &str) -> LanguageTag {
+    LanguageTag::parse(value).unwrap()
+}
+
+#[test]
+fn currency_codes() {
+    assert_eq!(CurrencyCode::parse("usd").unwrap(), CurrencyCode::USD);
+    assert_eq!(CurrencyCode::USD.minor_units(), 2);
+    assert_eq!(CurrencyCode::JPY.minor_units(), 0);
+    assert_eq!("KWD".parse::<CurrencyCode>().unwrap().minor_units(), 3);
+
+    for invalid in ["", "US", "USDD", "XYZ", "12$"] {
+        assert!(CurrencyCode::parse(invalid).is_err(), "{invalid}");
+    }
+
+    assert!(serde_json::from_str::<CurrencyCode>("\"ABC\"").is_err());
+}
+
+#[test]
+fn parses_decimal_amounts() {
+    assert_eq!(money("12.34 USD"), Money::new(1234, CurrencyCode::USD));
+    assert_eq!(money("EUR 0.5"), Money::new(50, CurrencyCode::EUR));
+    assert_eq!(money("-3 GBP"), Money::new(-300, CurrencyCode::GBP));
+    assert_eq!(money("1500 JPY"), Money::new(1500, CurrencyCode::JPY));
+
+    // Never rounded.
+    assert!(Money::from_decimal_str("0.001", CurrencyCode::USD).is_err());
+    assert!(Money::from_decimal_str("1.5", CurrencyCode::JPY).is_err());
+
+    for invalid in ["12.34", "1,000.00 USD", "abc USD", ".5 USD", "1e3 USD"] {
+        assert!(invalid.parse::<Money>().is_err(), "{invalid}");
+    }
+
+    assert_eq!(money("-0.05 USD").to_string(), "-0.05 USD");
+    assert_eq!(money("1.250 KWD").to_decimal_string(), "1.250");
+    assert_eq!(money("7 JPY").to_decimal_string(), "7");
+}
+
+#[test]
+fn checked_arithmetic() {
+    let price = money("19.99 USD");
+
+    assert_eq!(price.checked_mul(3).unwrap(), money("59.97 USD"));
+    assert_eq!(
+        price.checked_add(money("0.01 USD")).unwrap(),
+        money("20 USD")
+    );
+    assert_eq!(
+        price.checked_sub(money("20 USD")).unwrap(),
+        money("-0.01 USD")
+    );
+
+    assert!(matches!(
+        price.checked_add(money("1 EUR")),
+        Err(GlobalError::InvalidMoney(_))
+    ));
+    assert!(matches!(
+        Money::new(i64::MAX, CurrencyCode::USD).checked_add(money("0.01 USD")),
+        Err(GlobalError::InvalidMoney(_))
+    ));
+    assert!(
+        Money::new(i64::MIN, CurrencyCode::USD)
+            .checked_neg()
+            .is_err()
+    );
+
+    assert_eq!(
+        Money::checked_sum([price, price, money("0.02 USD")]).unwrap(),
+        Some(money("40 USD"))
+    );
+    assert_eq!(Money::checked_sum([]).unwrap(), None);
+}
+
+#[test]
+fn formats_per_locale() {
+    let amount = money("1234567.89 EUR");
+
+    assert_eq!(amount.format(&tag("en-US")), "€1,234,567.89");
+    assert_eq!(amount.format(&tag("de-DE")), "1.234.567,89\u{a0}€");
+    assert_eq!(amount.format(&tag("fr")), "1\u{a0}234\u{a0}567,89\u{a0}€");
+    assert_eq!(amount.format(&tag("nl")), "€\u{a0}1.234.567,89");
+
+    assert_eq!(money("-5.5 USD").format(&tag("en")), "-$5.50");
+    assert_eq!(money("1000 JPY").format(&tag("ja")), "¥1,000");
+    assert_eq!(
+        money("1234.5 CHF").format(&tag("de-CH")),
+        "CHF\u{a0}1'234.50"
+    );
+    // Codes are separated from the number.
+    assert_eq!(money("12 SEK").format(&tag("en")), "SEK\u{a0}12.00");
+}
+
+#[test]
+fn currency_fields() {
+    let value = SchematicFieldType::Currency
+        .parse_value(SimpleValue::Text(String::from("12.34 USD")))
+        .unwrap();
+
+    assert_eq!(value, SchematicFieldValue::Money(money("12.34 USD")));
+
+    let stored = SimpleValue::from(value.clone());
+
+    assert_eq!(
+        stored,
+        SimpleValue::ObjectUnknown(serde_json::json!({ "amountMinor": 1234, "currency": "USD" }))
+    );
+    assert_eq!(
+        SchematicFieldType::Currency.parse_value(stored).unwrap(),
+        value
+    );
+
+    // Floats are what caused the rounding bugs.
+    assert!(
+        SchematicFieldType::Currency
+            .parse_value(SimpleValue::Number(Number::Float(12.34)))
+            .is_err()
+    );
+
+    assert_eq!(
+        SimpleValue::Text(String::from(r#"{"amountMinor":5,"currency":"eur"}"#))
+            .coerce_to(SchematicFieldType::Currency)
+            .unwrap(),
+        SchematicFieldValue::Money(Money::new(5, CurrencyCode::EUR))
+    );
+
+    assert_roundtrip(&value);
+    assert_roundtrip(&money("-1.234 BHD"));
+}
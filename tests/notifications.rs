@@ -0,0 +1,78 @@
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+use webby_global_common::{
+    id::{MemberPublicId, NotificationPublicId},
+    notifications::{Notification, NotificationReadRequest, NotificationSelection, Recipient},
+    notify::NotificationChannel,
+    testing::assert_roundtrip,
+};
+
+fn notification(created_at: time::OffsetDateTime) -> Notification {
+    Notification::new(
+        NotificationPublicId::new(),
+        Recipient::User(Uuid::now_v7()),
+        NotificationChannel::InApp,
+        "New order",
+        "Order #1024 was placed",
+        created_at,
+    )
+}
+
+#[test]
+fn serializes() {
+    let id = NotificationPublicId::new();
+    let created_at = datetime!(2024-03-01 11:00 UTC);
+    let notification = Notification::new(
+        id,
+        Recipient::Member(MemberPublicId::new()),
+        NotificationChannel::Push,
+        "Welcome",
+        "Thanks for signing up",
+        created_at,
+    )
+    .action_url("https://example.com/account".parse().unwrap());
+
+    assert_roundtrip(&notification);
+    assert_eq!(notification.id, id);
+    assert_eq!(notification.created_at, created_at);
+
+    let value = serde_json::to_value(&notification).unwrap();
+
+    assert_eq!(value["channel"], "push");
+    assert_eq!(value["actionUrl"], "https://example.com/account");
+    assert!(value.get("readAt").is_none());
+
+    assert_roundtrip(&NotificationReadRequest::read(NotificationSelection::All {
+        before: Some(datetime!(2024-03-01 12:00 UTC)),
+    }));
+}
+
+#[test]
+fn marks_read_and_unread() {
+    let at = datetime!(2024-03-01 12:00 UTC);
+    let mut notifications = vec![
+        notification(at - Duration::hours(2)),
+        notification(at - Duration::hours(1)),
+        notification(at + Duration::hours(1)),
+    ];
+
+    let request =
+        NotificationReadRequest::read(NotificationSelection::Ids(vec![notifications[0].id]));
+
+    assert_eq!(request.apply(&mut notifications, at), 1);
+    // Already read.
+    assert_eq!(request.apply(&mut notifications, at + Duration::DAY), 0);
+    assert_eq!(notifications[0].read_at, Some(at));
+
+    // Leaves out the one which arrived after.
+    let all = NotificationReadRequest::read(NotificationSelection::All { before: Some(at) });
+
+    assert_eq!(all.apply(&mut notifications, at), 1);
+    assert!(notifications[1].is_read());
+    assert!(!notifications[2].is_read());
+
+    let unread = NotificationReadRequest::unread(NotificationSelection::All { before: None });
+
+    assert_eq!(unread.apply(&mut notifications, at), 2);
+    assert!(notifications.iter().all(|v| !v.is_read()));
+}
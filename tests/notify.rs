@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use webby_global_common::{
+    notify::{NotificationChannel, NotificationTemplate, render_template, render_template_with},
+    schema::SchematicFieldKey,
+    value::SimpleValue,
+};
+
+fn row() -> HashMap<SchematicFieldKey, SimpleValue> {
+    HashMap::from([
+        (SchematicFieldKey::from("name"), SimpleValue::from("Jane")),
+        (SchematicFieldKey::from("count"), SimpleValue::from(3)),
+    ])
+}
+
+#[test]
+fn renders_placeholders() {
+    let row = row();
+
+    assert_eq!(
+        render_template(
+            "Hi {{name}}, you have {{ count }} new {{missing}}messages",
+            &row
+        ),
+        "Hi Jane, you have 3 new messages"
+    );
+    assert_eq!(render_template("No placeholders", &row), "No placeholders");
+    assert_eq!(render_template("{{name}}{{name}}", &row), "JaneJane");
+    // Unclosed placeholders are kept.
+    assert_eq!(render_template("Hi {{name", &row), "Hi {{name");
+    assert_eq!(render_template("{{name}} }}", &row), "Jane }}");
+}
+
+#[test]
+fn renders_with_a_lookup() {
+    let mut names = Vec::new();
+
+    let output = render_template_with("{{ a }}-{{b}}-{{}}", |name| {
+        names.push(name.to_string());
+        (name == "a").then(|| String::from("1"))
+    });
+
+    assert_eq!(output, "1--");
+    assert_eq!(names, ["a", "b", ""]);
+}
+
+#[test]
+fn renders_templates() {
+    let template = NotificationTemplate {
+        channel: NotificationChannel::Email,
+        subject: Some(String::from("Welcome {{name}}")),
+        body: String::from("You have {{count}} items"),
+    };
+
+    let rendered = template.render(&row());
+
+    assert_eq!(rendered.channel, NotificationChannel::Email);
+    assert_eq!(rendered.subject.as_deref(), Some("Welcome Jane"));
+    assert_eq!(rendered.body, "You have 3 items");
+
+    let value = serde_json::to_value(&template).unwrap();
+
+    assert_eq!(value["channel"], "email");
+    assert_eq!(
+        serde_json::from_value::<NotificationTemplate>(value).unwrap(),
+        template
+    );
+
+    let sms = NotificationTemplate {
+        channel: NotificationChannel::Sms,
+        subject: None,
+        body: String::from("{{name}}"),
+    };
+
+    assert_eq!(sms.render(&row()).subject, None);
+}
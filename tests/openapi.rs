@@ -0,0 +1,84 @@
+#![cfg(feature = "openapi")]
+
+use serde_json::json;
+use utoipa::{OpenApi, PartialSchema, ToSchema};
+use webby_global_common::{
+    request::{CmsCreate, CmsQuery},
+    response::{ApiErrorResponse, ListResponse, WrappingResponse},
+};
+
+#[test]
+fn schemas_are_composed() {
+    #[derive(OpenApi)]
+    #[openapi(components(schemas(
+        CmsQuery,
+        CmsCreate,
+        WrappingResponse<ListResponse<String>>
+    )))]
+    struct Api;
+
+    let api = serde_json::to_value(Api::openapi()).unwrap();
+    let schemas = &api["components"]["schemas"];
+
+    // Registered by the WrappingResponse.
+    assert_eq!(
+        schemas["ApiErrorResponse"]["required"],
+        json!(["description"])
+    );
+    assert_eq!(
+        schemas["CmsQuery"]["properties"]["includeFiles"],
+        json!({ "type": "boolean", "default": false })
+    );
+    // The views of the flattened CmsUpdate.
+    assert_eq!(
+        schemas["CmsCreate"]["properties"]["views"]["type"],
+        json!(["array", "null"])
+    );
+
+    let wrapping = &schemas["WrappingResponse_ListResponse_String"]["oneOf"];
+
+    assert_eq!(wrapping[0]["properties"]["type"]["enum"], json!(["Resp"]));
+    assert_eq!(
+        wrapping[0]["properties"]["value"]["properties"]["items"],
+        json!({ "type": "array", "items": { "type": "string" } })
+    );
+    assert_eq!(
+        wrapping[1]["properties"]["value"],
+        json!({ "$ref": "#/components/schemas/ApiErrorResponse" })
+    );
+}
+
+#[test]
+fn list_schema_matches_serde() {
+    let schema = serde_json::to_value(ListResponse::<u64>::schema()).unwrap();
+    let value = serde_json::to_value(ListResponse::<u64>::empty()).unwrap();
+
+    let mut properties = schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut required = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    let mut written = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    properties.sort();
+    required.sort();
+    written.sort();
+
+    // The cursors are skipped when empty.
+    assert_eq!(required, written);
+    assert_eq!(properties.len(), written.len() + 2);
+    assert_eq!(ListResponse::<u64>::name(), "ListResponse");
+    assert_eq!(ApiErrorResponse::name(), "ApiErrorResponse");
+}
@@ -0,0 +1,164 @@
+use serde_json::json;
+use url::Url;
+use webby_global_common::{
+    id::PagePublicId,
+    object_id::{ObjectGuid, ObjectId, ObjectIdTuple},
+    pages::{Page, PageTree, PageVisibility, PublicPage},
+    response::AddonInstallResponse,
+    seo::sitemap::SitemapEntry,
+};
+
+fn page(id: &str, slug: &str, parent: Option<&str>, sort_index: u32) -> Page {
+    Page {
+        id: ObjectIdTuple {
+            id: ObjectId::from(id.to_string()),
+            guid: ObjectGuid::new(),
+        },
+        slug: slug.to_string(),
+        parent: parent.map(|v| ObjectId::from(v.to_string())),
+        title: id.to_string(),
+        seo: None,
+        visibility: PageVisibility::Public,
+        sort_index,
+    }
+}
+
+#[test]
+fn install_response_pages() {
+    let page = PublicPage {
+        id: PagePublicId::new(),
+        path: String::from("/shop/cart"),
+        title: String::from("Cart"),
+        layout: None,
+        visibility: PageVisibility::Hidden,
+    };
+
+    let value = json!({
+        "instanceUuid": "0190c8a4-6c1e-7bb0-8f4a-1d2e3f405060",
+        "newPages": [page, { "legacy": true }],
+        "isUpdate": false,
+    });
+
+    let response: AddonInstallResponse = serde_json::from_value(value).unwrap();
+
+    assert_eq!(response.new_pages, vec![page.clone()]);
+    assert!(!response.new_pages[0].visibility.is_listed());
+
+    // Older services sent anything.
+    for legacy in [json!(null), json!({ "cart": "/shop/cart" })] {
+        let response: AddonInstallResponse = serde_json::from_value(json!({
+            "instanceUuid": "0190c8a4-6c1e-7bb0-8f4a-1d2e3f405060",
+            "newPages": legacy,
+            "isUpdate": true,
+        }))
+        .unwrap();
+
+        assert!(response.new_pages.is_empty());
+    }
+}
+
+#[test]
+fn builds_page_tree() {
+    let tree = PageTree::build(vec![
+        page("cart", "cart", Some("shop"), 1),
+        page("home", "", None, 0),
+        page("shop", "shop", None, 1),
+        page("products", "products", Some("shop"), 0),
+        page("about", "about", None, 2),
+    ])
+    .unwrap();
+
+    let paths = tree.iter().map(|v| v.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["/", "/shop", "/shop/products", "/shop/cart", "/about"]
+    );
+
+    let cart = ObjectId::from(String::from("cart"));
+    assert_eq!(tree.path_of(&cart), Some("/shop/cart"));
+    assert_eq!(tree.find_by_path("/shop/cart/").unwrap().page.id.id, cart);
+    assert_eq!(tree.find_by_path("").unwrap().path, "/");
+    assert_eq!(tree.roots().len(), 3);
+
+    let public = tree.public_pages();
+    assert_eq!(public.len(), 5);
+    assert_eq!(public[3].path, "/shop/cart");
+}
+
+#[test]
+fn children_of_home_are_at_the_root() {
+    let tree = PageTree::build(vec![
+        page("home", "", None, 0),
+        page("welcome", "welcome", Some("home"), 0),
+        page("tour", "tour", Some("welcome"), 0),
+    ])
+    .unwrap();
+
+    let paths = tree.iter().map(|v| v.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, ["/", "/welcome", "/welcome/tour"]);
+
+    let base = Url::parse("https://shop.example.com").unwrap();
+    let locs = SitemapEntry::from_pages(&tree, &base)
+        .unwrap()
+        .into_iter()
+        .map(|v| v.loc.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        locs,
+        [
+            "https://shop.example.com/",
+            "https://shop.example.com/welcome",
+            "https://shop.example.com/welcome/tour",
+        ]
+    );
+}
+
+#[test]
+fn rejects_invalid_page_trees() {
+    // Same slug on the same level.
+    assert!(
+        PageTree::build(vec![
+            page("shop", "shop", None, 0),
+            page("store", "shop", None, 1),
+        ])
+        .is_err()
+    );
+
+    // Same slug on different levels is fine.
+    assert!(
+        PageTree::build(vec![
+            page("shop", "shop", None, 0),
+            page("inner", "shop", Some("shop"), 0),
+        ])
+        .is_ok()
+    );
+
+    // Only top level pages can have an empty slug.
+    assert!(
+        PageTree::build(vec![
+            page("shop", "shop", None, 0),
+            page("inner", "", Some("shop"), 0),
+        ])
+        .is_err()
+    );
+
+    for slug in ["Shop", "shop page", "-shop", "shop--page", "shop/page"] {
+        assert!(PageTree::build(vec![page("shop", slug, None, 0)]).is_err());
+    }
+
+    // Missing parent.
+    assert!(PageTree::build(vec![page("cart", "cart", Some("shop"), 0)]).is_err());
+
+    // Cycle.
+    assert!(
+        PageTree::build(vec![
+            page("a", "a", Some("b"), 0),
+            page("b", "b", Some("a"), 0),
+        ])
+        .is_err()
+    );
+
+    // Duplicate ID.
+    assert!(PageTree::build(vec![page("a", "a", None, 0), page("a", "b", None, 1)]).is_err());
+}
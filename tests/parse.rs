@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use serde_json::json;
+use webby_global_common::{
+    error::GlobalError,
+    parse::{self, ParseMode},
+    request::{CmsCreate, CmsQuery},
+    schema::Schematic,
+    testing::{fixture_dir, load_fixture_value},
+};
+
+fn fixtures() -> PathBuf {
+    fixture_dir(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn unknown_keys<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> Option<String> {
+    match parse::from_value::<T>(value, ParseMode::Strict) {
+        Ok(_) => None,
+        Err(GlobalError::UnknownKeys(keys)) => Some(keys),
+        Err(e) => panic!("{e}"),
+    }
+}
+
+#[test]
+fn strict_parsing_rejects_unknown_keys() {
+    let query = json!({
+        "filters": [{ "name": "title", "cond": "eq", "value": "Hello", "negate": true }],
+        "limt": 10,
+        "includeFiles": true,
+    });
+
+    assert_eq!(
+        unknown_keys::<CmsQuery>(query.clone()).as_deref(),
+        Some("filters.0.negate, limt")
+    );
+
+    let lenient: CmsQuery = parse::from_value(query, ParseMode::Lenient).unwrap();
+
+    assert_eq!(lenient.limit, None);
+    assert!(lenient.include_files);
+
+    let text = r#"{ "limit": 10, "include_files": true }"#;
+    let query: CmsQuery = parse::from_str(text, ParseMode::Strict).unwrap();
+
+    // Old names aren't unknown.
+    assert!(query.include_files);
+    assert!(parse::from_slice::<CmsQuery>(b"{} {}", ParseMode::Strict).is_err());
+
+    let mut schematic = load_fixture_value(fixtures(), "schematic");
+
+    assert_eq!(unknown_keys::<Schematic>(schematic.clone()), None);
+
+    schematic["fields"]["title"]["sortabel"] = json!(true);
+
+    assert_eq!(
+        unknown_keys::<Schematic>(schematic).as_deref(),
+        Some("fields.title.sortabel")
+    );
+}
+
+#[test]
+fn strict_parsing_sees_through_cms_create() {
+    let create = json!({
+        "id": "posts",
+        "name": "Posts",
+        "isExternal": false,
+        "isSingle": false,
+        "views": [],
+        "columns": null,
+        "data": null,
+    });
+
+    let parsed: CmsCreate = parse::from_value(create.clone(), ParseMode::Strict).unwrap();
+
+    assert_eq!(parsed.update.views.as_ref().map(Vec::len), Some(0));
+
+    let mut typo = create.clone();
+    typo["viewz"] = json!([]);
+
+    assert_eq!(unknown_keys::<CmsCreate>(typo).as_deref(), Some("viewz"));
+
+    // Still serialized with the views at the top level.
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), create);
+}
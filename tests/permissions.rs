@@ -0,0 +1,99 @@
+use uuid::Uuid;
+
+use webby_global_common::{
+    permissions::{CustomRole, Permission, RequestContext, Role},
+    schema::{Operations, PermissionsUser, SchematicPermissions},
+};
+
+#[test]
+fn evaluates_permissions() {
+    let permissions = SchematicPermissions {
+        insert: PermissionsUser::Authenticated,
+        update: PermissionsUser::Owner,
+        remove: PermissionsUser::Role(String::from("editor")),
+        read: PermissionsUser::Anyone,
+    };
+
+    let user = Uuid::now_v7();
+    let other = Uuid::now_v7();
+
+    let anonymous = RequestContext::anonymous();
+    assert!(permissions.can(Operations::Find, &anonymous));
+    assert!(!permissions.can(Operations::Insert, &anonymous));
+
+    let owner = RequestContext::user(user).with_owner(user);
+    assert!(permissions.can(Operations::Insert, &owner));
+    assert!(permissions.can(Operations::Save, &owner));
+    assert!(permissions.can(Operations::InsertReference, &owner));
+    assert!(!permissions.can(Operations::Remove, &owner));
+
+    let editor = RequestContext::user(other)
+        .with_owner(user)
+        .with_roles(["editor"]);
+    assert!(permissions.can(Operations::BulkRemove, &editor));
+    assert!(!permissions.can(Operations::Update, &editor));
+    assert!(!permissions.can(Operations::BulkSave, &editor));
+
+    let admin = RequestContext {
+        is_admin: true,
+        ..RequestContext::anonymous()
+    };
+    assert!(permissions.can(Operations::Truncate, &admin));
+    assert!(SchematicPermissions::default().can(Operations::Get, &admin));
+    assert!(!SchematicPermissions::default().can(Operations::Get, &owner));
+}
+
+#[test]
+fn roles_grant_operations() {
+    assert!(Role::Admin.grants(Operations::Truncate, "Blog"));
+    assert!(Role::Editor.grants(Operations::BulkRemove, "Blog"));
+    assert!(!Role::Editor.grants(Operations::Truncate, "Blog"));
+    assert!(Role::Contributor.grants(Operations::Save, "Blog"));
+    assert!(!Role::Contributor.grants(Operations::Remove, "Blog"));
+
+    let unknown = Operations::Unknown(String::from("Explode"));
+    assert!(Role::Admin.grants(unknown.clone(), "Blog"));
+    assert!(!Role::Editor.grants(unknown, "Blog"));
+
+    let role: Role = serde_json::from_value(serde_json::json!({
+        "type": "Custom",
+        "value": {
+            "name": "marketing",
+            "grants": [
+                { "namespace": "*", "permissions": ["Read"] },
+                { "namespace": "Marketing", "permissions": ["Insert", "Update", "Read"] },
+            ],
+        },
+    }))
+    .unwrap();
+
+    assert_eq!(role.name(), "marketing");
+    assert!(role.grants(Operations::Find, "Billing"));
+    assert!(!role.grants(Operations::Insert, "Billing"));
+    assert!(role.grants(Operations::BulkSave, "Marketing"));
+    assert_eq!(
+        role.permissions_in("Marketing"),
+        [Permission::Read, Permission::Insert, Permission::Update]
+    );
+
+    for name in ["admin", "Editor", " contributor", ""] {
+        assert!(CustomRole::new(name, Vec::new()).is_err(), "{name:?}");
+        assert!(
+            serde_json::from_value::<Role>(serde_json::json!({
+                "type": "Custom",
+                "value": { "name": name, "grants": [] },
+            }))
+            .is_err()
+        );
+    }
+
+    let ctx = RequestContext::user(Uuid::now_v7()).with_site_roles(&[Role::Editor, role]);
+    assert!(!ctx.is_admin);
+    assert_eq!(ctx.roles, ["editor", "marketing"]);
+    assert!(PermissionsUser::Role(String::from("marketing")).allows(&ctx));
+    assert!(
+        RequestContext::anonymous()
+            .with_site_roles(&[Role::Admin])
+            .is_admin
+    );
+}
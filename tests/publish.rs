@@ -0,0 +1,129 @@
+use time::{Duration, macros::datetime};
+use uuid::Uuid;
+use webby_global_common::{
+    publish::{PublishState, RowFields, RowRevision, diff_fields},
+    schema::SchematicFieldKey,
+    value::SimpleValue,
+};
+
+fn fields(values: &[(&str, SimpleValue)]) -> RowFields {
+    values
+        .iter()
+        .map(|(k, v)| (SchematicFieldKey::from(*k), v.clone()))
+        .collect()
+}
+
+#[test]
+fn publish_states() {
+    let now = datetime!(2024-05-01 12:00 UTC);
+
+    assert!(PublishState::default().is_draft());
+    assert!(!PublishState::Draft.is_live_at(now));
+    assert!(!PublishState::Archived.is_live_at(now));
+    assert!(PublishState::Archived.is_archived());
+    assert!(PublishState::Published.is_live_at(now));
+
+    let scheduled = PublishState::Scheduled(now);
+
+    assert!(!scheduled.is_live_at(now - Duration::SECOND));
+    assert!(scheduled.is_live_at(now));
+    assert_eq!(scheduled.resolve(now - Duration::SECOND), scheduled);
+    assert_eq!(scheduled.resolve(now), PublishState::Published);
+    assert_eq!(PublishState::Draft.resolve(now), PublishState::Draft);
+
+    let value = serde_json::to_value(scheduled).unwrap();
+
+    assert_eq!(value["type"], "Scheduled");
+    assert_eq!(
+        serde_json::from_value::<PublishState>(value).unwrap(),
+        scheduled
+    );
+    assert_eq!(
+        serde_json::to_value(PublishState::Draft).unwrap(),
+        serde_json::json!({ "type": "Draft" })
+    );
+}
+
+#[test]
+fn diffs_fields() {
+    let before = fields(&[
+        ("title", SimpleValue::from("Hello")),
+        ("body", SimpleValue::from("Old")),
+        ("views", SimpleValue::from(1)),
+    ]);
+    let after = fields(&[
+        ("title", SimpleValue::from("Hello")),
+        ("body", SimpleValue::from("New")),
+        ("tags", SimpleValue::from("news")),
+    ]);
+
+    let changes = diff_fields(&before, &after);
+
+    assert_eq!(
+        changes.iter().map(|v| v.field.as_str()).collect::<Vec<_>>(),
+        ["body", "tags", "views"]
+    );
+    assert_eq!(changes[0].before, Some(SimpleValue::from("Old")));
+    assert_eq!(changes[0].after, Some(SimpleValue::from("New")));
+    assert!(changes[1].is_added());
+    assert!(changes[2].is_removed());
+
+    assert!(diff_fields(&before, &before).is_empty());
+}
+
+#[test]
+fn restores_revisions() {
+    let user = Uuid::now_v7();
+    let old = RowRevision::new(
+        fields(&[
+            ("title", SimpleValue::from("Old")),
+            ("body", SimpleValue::from("Text")),
+        ]),
+        Some(user),
+    );
+    let new = RowRevision::new(fields(&[("title", SimpleValue::from("New"))]), None);
+
+    assert_ne!(old.revision_id, new.revision_id);
+    assert_eq!(old.created_by, Some(user));
+    assert_eq!(old.diff(&new).len(), 2);
+    assert_eq!(old.restore(), old.data);
+
+    let mut current = fields(&[
+        ("title", SimpleValue::from("New")),
+        ("extra", SimpleValue::from(1)),
+    ]);
+
+    old.restore_fields(
+        &mut current,
+        &[
+            SchematicFieldKey::from("title"),
+            SchematicFieldKey::from("extra"),
+        ],
+    );
+
+    // Only the given fields, and ones missing from the revision are removed.
+    assert_eq!(current, fields(&[("title", SimpleValue::from("Old"))]));
+
+    let value = serde_json::to_value(&old).unwrap();
+
+    assert_eq!(value["data"]["title"], "Old");
+    assert!(value["createdAt"].is_string());
+    assert_eq!(serde_json::from_value::<RowRevision>(value).unwrap(), old);
+}
+
+#[test]
+fn revisions_at() {
+    let id = Uuid::nil();
+    let at = datetime!(2024-05-01 12:00 UTC);
+    let data = fields(&[("title", SimpleValue::from("Title"))]);
+
+    let revision = RowRevision::new_at(data.clone(), None, id, at);
+
+    assert_eq!(revision, RowRevision::new_at(data, None, id, at));
+    assert_eq!(revision.revision_id, id);
+    assert_eq!(revision.created_at, at);
+    assert_eq!(
+        serde_json::from_value::<RowRevision>(serde_json::to_value(&revision).unwrap()).unwrap(),
+        revision
+    );
+}
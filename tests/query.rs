@@ -0,0 +1,217 @@
+use serde_json::json;
+use webby_global_common::{
+    filter::FilterConditionType::{Between, Eq, Gte},
+    request::{CmsQuery, projection::Projection, query::CmsQueryBuilder},
+    response::CmsRowResponse,
+    schema::{
+        SchemaFieldMap, SchematicField, SchematicFieldKey, SchematicFieldType, SortOrder,
+        computed::FieldSource, constraints::FieldConstraints,
+    },
+    value::SimpleValue,
+};
+
+fn field(field_type: SchematicFieldType) -> SchematicField {
+    SchematicField {
+        display_name: String::new(),
+        sortable: true,
+        is_deleted: false,
+        system_field: false,
+        field_type,
+        index: 0,
+        referenced_schema: None,
+        default_value: None,
+        constraints: FieldConstraints::default(),
+        localized: false,
+        source: FieldSource::Stored,
+    }
+}
+
+#[test]
+fn builds_queries() {
+    let builder = CmsQueryBuilder::new()
+        .filter("age", Gte, 18)
+        .filter("id", Eq, vec![String::from("a"), String::from("b")])
+        .filter("score", Between, (1.into(), 5.into()))
+        .sort_asc("name")
+        .sort_desc("_createdAt")
+        .limit(50)
+        .columns(["name", "email"]);
+
+    let query = builder.clone().build();
+    assert_eq!(query.columns.as_ref().unwrap().to_string(), "name,email");
+    assert_eq!(
+        query.sort.as_deref().unwrap(),
+        [
+            (String::from("name"), SortOrder::Ascending),
+            (String::from("_createdAt"), SortOrder::Descending),
+        ]
+    );
+
+    assert_eq!(
+        query.to_query_string(),
+        [
+            "filters%5B0%5D%5Bname%5D=age",
+            "filters%5B0%5D%5Bcond%5D=gte",
+            "filters%5B0%5D%5Bvalue%5D=18",
+            "filters%5B1%5D%5Bname%5D=id",
+            "filters%5B1%5D%5Bcond%5D=eq",
+            "filters%5B1%5D%5Bvalue%5D%5B0%5D=a",
+            "filters%5B1%5D%5Bvalue%5D%5B1%5D=b",
+            "filters%5B2%5D%5Bname%5D=score",
+            "filters%5B2%5D%5Bcond%5D=between",
+            "filters%5B2%5D%5Bvalue%5D%5B0%5D=1",
+            "filters%5B2%5D%5Bvalue%5D%5B1%5D=5",
+            "sort%5Bname%5D=ASC",
+            "sort%5B_createdAt%5D=DESC",
+            "columns=name%2Cemail",
+            "limit=50",
+        ]
+        .join("&")
+    );
+
+    let mut fields = SchemaFieldMap::new();
+    fields.insert(
+        SchematicFieldKey::from("age"),
+        field(SchematicFieldType::Number),
+    );
+    fields.insert(
+        SchematicFieldKey::from("id"),
+        field(SchematicFieldType::Text),
+    );
+    fields.insert(
+        SchematicFieldKey::from("score"),
+        field(SchematicFieldType::Number),
+    );
+    fields.insert(
+        SchematicFieldKey::from("name"),
+        field(SchematicFieldType::Text),
+    );
+
+    assert!(builder.clone().build_checked(&fields).is_err());
+
+    fields.insert(
+        SchematicFieldKey::from("email"),
+        field(SchematicFieldType::Email),
+    );
+    assert!(builder.build_checked(&fields).is_ok());
+
+    let nested =
+        CmsQueryBuilder::new().filter_path("address.city".parse().unwrap(), Eq, "Springfield");
+    assert_eq!(
+        nested.clone().build().to_query_string(),
+        [
+            "filters%5B0%5D%5Bname%5D=address.city",
+            "filters%5B0%5D%5Bcond%5D=eq",
+            "filters%5B0%5D%5BisPath%5D=true",
+            "filters%5B0%5D%5Bvalue%5D=Springfield",
+        ]
+        .join("&")
+    );
+    assert!(nested.clone().build_checked(&fields).is_err());
+
+    fields.insert(
+        SchematicFieldKey::from("address"),
+        field(SchematicFieldType::Object),
+    );
+    assert!(nested.build_checked(&fields).is_ok());
+}
+
+#[test]
+fn keeps_sort_priority() {
+    let query = CmsQueryBuilder::new()
+        .sort_desc("price")
+        .sort_asc("name")
+        .sort_asc("age")
+        .sort_desc("name")
+        .build();
+
+    assert_eq!(
+        query.to_query_string(),
+        "sort%5Bprice%5D=DESC&sort%5Bage%5D=ASC&sort%5Bname%5D=DESC"
+    );
+
+    let value = serde_json::to_string(&query).unwrap();
+    assert!(value.contains(r#""sort":{"price":"DESC","age":"ASC","name":"DESC"}"#));
+    assert_eq!(
+        serde_json::from_str::<CmsQuery>(&value).unwrap().sort,
+        query.sort
+    );
+
+    let query: CmsQuery =
+        serde_json::from_str(r#"{ "sort": { "zeta": "desc", "alpha": "ASC" } }"#).unwrap();
+    assert_eq!(
+        query.sort.unwrap(),
+        [
+            (String::from("zeta"), SortOrder::Descending),
+            (String::from("alpha"), SortOrder::Ascending),
+        ]
+    );
+
+    assert!(
+        serde_json::from_str::<CmsQuery>(r#"{ "sort": null }"#)
+            .unwrap()
+            .sort
+            .is_none()
+    );
+    assert!(
+        serde_json::from_str::<CmsQuery>("{}")
+            .unwrap()
+            .sort
+            .is_none()
+    );
+    assert!(serde_json::from_str::<CmsQuery>(r#"{ "sort": { "a": "up" } }"#).is_err());
+}
+
+#[test]
+fn projects_rows() {
+    let query: CmsQuery =
+        serde_json::from_value(json!({ "columns": "title, address.city,-address.city.zip" }))
+            .unwrap();
+    let projection = query.columns.unwrap();
+
+    assert_eq!(
+        projection.to_string(),
+        "title,address.city,-address.city.zip"
+    );
+    assert_eq!(
+        projection.keys(),
+        [SchematicFieldKey::from("title"), "address".into()]
+    );
+
+    let row: CmsRowResponse = serde_json::from_value(json!({
+        "fields": {
+            "_id": "a",
+            "title": "Hello",
+            "notes": "Secret",
+            "address": {
+                "line1": "1 Main St",
+                "city": { "name": "Springfield", "zip": "62701" }
+            }
+        }
+    }))
+    .unwrap();
+
+    let projected = projection.apply(&row);
+    assert_eq!(projected.fields.len(), 3);
+    assert_eq!(
+        projected.fields["address"],
+        SimpleValue::ObjectUnknown(json!({ "city": { "name": "Springfield" } }))
+    );
+
+    let all_but_notes = Projection::parse("-notes");
+    assert!(all_but_notes.selects("title"));
+    assert!(!all_but_notes.selects("notes"));
+    assert_eq!(all_but_notes.apply(&row).fields.len(), 3);
+    assert!(Projection::parse("*").is_all());
+
+    let items: CmsRowResponse = serde_json::from_value(json!({
+        "fields": { "items": [{ "price": 1, "name": "a" }, { "price": 2, "name": "b" }] }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        Projection::parse("items[1].price").apply(&items).fields["items"],
+        SimpleValue::ArrayUnknown(vec![json!({ "price": 2 })])
+    );
+    assert!(Projection::parse("items[x]").paths().is_err());
+}
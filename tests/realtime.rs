@@ -0,0 +1,93 @@
+use serde_json::json;
+use webby_global_common::{
+    realtime::{ClientMessage, ServerMessage},
+    response::CmsRowResponse,
+    value::SimpleValue,
+};
+
+const ID: &str = "0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a";
+
+#[test]
+fn applies_server_messages() {
+    let subscribe: ClientMessage = serde_json::from_value(json!({
+        "type": "Subscribe",
+        "value": {
+            "collection": "addon:posts",
+            "filters": { "name": "views", "cond": "gte", "value": 10 }
+        }
+    }))
+    .unwrap();
+
+    let ClientMessage::Subscribe {
+        collection,
+        filters,
+    } = &subscribe
+    else {
+        panic!("Expected Subscribe");
+    };
+    assert_eq!(collection.ns.as_deref(), Some("addon"));
+    assert!(filters.is_some());
+
+    let messages: Vec<ServerMessage> = serde_json::from_value(json!([
+        {
+            "type": "RowInserted",
+            "value": { "collection": "posts", "row": { "fields": { "_id": ID, "views": 10 } } }
+        },
+        {
+            "type": "RowPatched",
+            "value": {
+                "collection": "posts",
+                "id": ID,
+                "patches": { "views": { "type": "Increment", "value": 5 } }
+            }
+        },
+        { "type": "Pong" }
+    ]))
+    .unwrap();
+
+    let mut rows = Vec::<CmsRowResponse>::new();
+
+    for message in &messages {
+        message.apply_to(&mut rows).unwrap();
+    }
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].fields["views"], SimpleValue::from(15));
+    assert_eq!(messages[1].collection().unwrap().id, "posts");
+    assert!(messages[2].collection().is_none());
+
+    let remove: ServerMessage = serde_json::from_value(json!({
+        "type": "RowRemoved",
+        "value": { "collection": "posts", "id": ID }
+    }))
+    .unwrap();
+
+    remove.apply_to(&mut rows).unwrap();
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn patches_apply_atomically() {
+    let mut rows: Vec<CmsRowResponse> = serde_json::from_value(json!([
+        { "fields": { "_id": ID, "title": "Hello", "views": 10 } }
+    ]))
+    .unwrap();
+
+    let patch: ServerMessage = serde_json::from_value(json!({
+        "type": "RowPatched",
+        "value": {
+            "collection": "posts",
+            "id": ID,
+            "patches": {
+                "views": { "type": "Increment", "value": 5 },
+                "title": { "type": "Increment", "value": 1 }
+            },
+            "revision": 4
+        }
+    }))
+    .unwrap();
+
+    assert!(patch.apply_to(&mut rows).is_err());
+    assert_eq!(rows[0].fields["views"], SimpleValue::from(10));
+    assert_eq!(rows[0].fields["title"], SimpleValue::from("Hello"));
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "redis")]
+
+use std::{collections::HashMap, path::PathBuf};
+
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use webby_global_common::{
+    id::WebsitePublicId,
+    redis::{
+        CACHE_VERSION, list_cache_key, list_cache_pattern, public_schema_cache_key,
+        schema_cache_key,
+    },
+    request::CmsQuery,
+    response::{CmsRowResponse, ListResponse},
+    schema::{Schematic, SchematicFieldKey, SortOrder},
+    testing::{fixture_dir, load_fixture},
+    uuid::CollectionName,
+    value::SimpleValue,
+};
+
+fn fixtures() -> PathBuf {
+    fixture_dir(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn cached<T: ToRedisArgs + FromRedisValue>(value: &T) -> T {
+    let mut args = value.to_redis_args();
+
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0][0], CACHE_VERSION);
+
+    T::from_redis_value(&Value::BulkString(args.remove(0))).unwrap()
+}
+
+#[test]
+fn values_round_trip() {
+    let schema: Schematic = load_fixture(fixtures(), "schematic");
+
+    assert_eq!(
+        serde_json::to_value(cached(&schema)).unwrap(),
+        serde_json::to_value(&schema).unwrap()
+    );
+
+    let mut list = ListResponse::<CmsRowResponse>::empty();
+    list.items.push(CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::from([(
+            SchematicFieldKey::from("title"),
+            SimpleValue::Text(String::from("Hello")),
+        )]),
+        meta: None,
+        seo: None,
+    });
+
+    assert_eq!(cached(&list), list);
+
+    let mut bytes = list.to_redis_args().remove(0);
+    bytes[0] = CACHE_VERSION + 1;
+
+    assert!(ListResponse::<CmsRowResponse>::from_redis_value(&Value::BulkString(bytes)).is_err());
+    assert!(Schematic::from_redis_value(&Value::Nil).is_err());
+    assert!(
+        Option::<Schematic>::from_redis_value(&Value::Nil)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn keys() {
+    let site = WebsitePublicId::new();
+    let collection = CollectionName::parse("shop:Local/products").unwrap();
+
+    assert_eq!(
+        schema_cache_key(site, &collection),
+        format!("webby:schema:{site}:shop.Local/products")
+    );
+    assert_eq!(
+        public_schema_cache_key(site, &collection),
+        format!("webby:public-schema:{site}:shop.Local/products")
+    );
+
+    let query = |sort: &[(&str, SortOrder)]| CmsQuery {
+        sort: Some(sort.iter().map(|(k, v)| (k.to_string(), *v)).collect()),
+        limit: Some(10),
+        ..Default::default()
+    };
+
+    let key = list_cache_key(
+        site,
+        &collection,
+        &query(&[("a", SortOrder::Ascending), ("b", SortOrder::Descending)]),
+    )
+    .unwrap();
+
+    // The order of the sort fields is their priority.
+    assert_ne!(
+        key,
+        list_cache_key(
+            site,
+            &collection,
+            &query(&[("b", SortOrder::Descending), ("a", SortOrder::Ascending)])
+        )
+        .unwrap()
+    );
+    assert_ne!(
+        key,
+        list_cache_key(site, &collection, &query(&[("a", SortOrder::Descending)])).unwrap()
+    );
+    assert!(key.starts_with(list_cache_pattern(site, &collection).trim_end_matches('*')));
+}
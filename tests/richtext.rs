@@ -0,0 +1,76 @@
+use webby_global_common::richtext::{Block, RichDocument, is_safe_url};
+
+#[test]
+fn sanitizes_and_extracts_text() {
+    let mut document: RichDocument = serde_json::from_value(serde_json::json!({
+        "blocks": [
+            {
+                "type": "Heading",
+                "value": { "level": 9, "content": [{ "type": "Text", "value": { "text": "Title" } }] },
+            },
+            {
+                "type": "Paragraph",
+                "value": [
+                    { "type": "Text", "value": { "text": "Hello ", "marks": ["Bold"] } },
+                    {
+                        "type": "Link",
+                        "value": {
+                            "href": "java\tscript:alert(1)",
+                            "content": [{ "type": "Text", "value": { "text": "world" } }],
+                        },
+                    },
+                    { "type": "LineBreak" },
+                    {
+                        "type": "Link",
+                        "value": {
+                            "href": "https://example.com",
+                            "content": [{ "type": "Text", "value": { "text": "link" } }],
+                            "attributes": { "target": "_blank", "onclick": "alert(1)" },
+                        },
+                    },
+                ],
+            },
+            {
+                "type": "List",
+                "value": {
+                    "ordered": false,
+                    "items": [
+                        [{ "type": "Paragraph", "value": [{ "type": "Text", "value": { "text": "One" } }] }],
+                        [{ "type": "Paragraph", "value": [{ "type": "Text", "value": { "text": "Two" } }] }],
+                    ],
+                },
+            },
+            {
+                "type": "Embed",
+                "value": { "kind": "Frame", "url": "http://example.com/player", "altText": "Video" },
+            },
+            {
+                "type": "Embed",
+                "value": { "kind": "Image", "url": "/cat.png", "altText": "A cat", "attributes": { "src": "data:text/html,x" } },
+            },
+        ],
+    }))
+    .unwrap();
+
+    document.sanitize();
+
+    let serialized = serde_json::to_string(&document).unwrap();
+    assert!(!serialized.contains("script"));
+    assert!(!serialized.contains("onclick"));
+    assert!(!serialized.contains("data:"));
+    assert!(serialized.contains("_blank"));
+    assert!(matches!(
+        document.blocks[0],
+        Block::Heading { level: 6, .. }
+    ));
+    assert_eq!(document.blocks.len(), 4);
+
+    assert_eq!(
+        document.plain_text(),
+        "Title\nHello world\nlink\nOne\nTwo\nA cat"
+    );
+
+    assert!(is_safe_url("mailto:jane@example.com"));
+    assert!(is_safe_url("/pages/a:b"));
+    assert!(!is_safe_url(" JavaScript:alert(1)"));
+}
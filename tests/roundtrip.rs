@@ -0,0 +1,673 @@
+use std::{borrow::Cow, collections::HashMap, path::PathBuf};
+
+use time::macros::datetime;
+
+use webby_global_common::{
+    error::GlobalError,
+    filter::{Filter, FilterConditionType, FilterExpr, FilterValue},
+    id::SchemaDataPublicId,
+    idempotency::IdempotencyKey,
+    limits::RateLimit,
+    request::{
+        AggregateFunction, Aggregation, CmsAggregate, CmsBulkInsert, CmsBulkUpdate,
+        CmsPurgeRequest, CmsUpdateDataCell, CmsUpdateDataRow, FieldPatch,
+    },
+    response::{
+        ApiErrorCode, ApiErrorResponse, BulkOperationResponse, CmsAggregateResponse,
+        CmsRowResponse, CmsRowResponseRef, Cursor, ListResponse, RawRow, RowMeta, WrappingResponse,
+    },
+    schema::{Schematic, SchematicFieldKey, constraints::FieldConstraints},
+    testing::{
+        assert_golden, assert_legacy_golden, assert_roundtrip, fixture_dir, load_fixture,
+        load_fixture_value,
+    },
+    upload::WebsiteUpload,
+    value::{Number, SimpleValue, SimpleValueRef},
+};
+
+fn fixtures() -> PathBuf {
+    fixture_dir(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn simple_value_golden() {
+    let values: Vec<SimpleValue> = assert_golden(fixtures(), "simple_value");
+
+    assert_eq!(
+        values,
+        vec![
+            SimpleValue::Text(String::from("Hello World")),
+            SimpleValue::Text(String::from("2024-01-02")),
+            SimpleValue::Number(Number::Integer(7)),
+            SimpleValue::Number(Number::Integer(-12)),
+            SimpleValue::Number(Number::Integer(300)),
+            SimpleValue::Number(Number::Float(1.5)),
+            SimpleValue::Boolean(true),
+            SimpleValue::ListString(vec![String::from("a"), String::from("b")]),
+            SimpleValue::ListNumber(vec![Number::Integer(1), Number::Float(2.5)]),
+            SimpleValue::ArrayUnknown(vec![serde_json::json!({ "a": 1 }), serde_json::json!("b")]),
+            SimpleValue::ObjectUnknown(serde_json::json!({ "key": "value" })),
+        ]
+    );
+
+    for value in &values {
+        assert_roundtrip(value);
+    }
+}
+
+#[test]
+fn filter_golden() {
+    let filters: Vec<Filter> = assert_golden(fixtures(), "filter");
+
+    let conds = filters.iter().map(|f| f.cond.clone()).collect::<Vec<_>>();
+
+    assert_eq!(
+        conds,
+        vec![
+            FilterConditionType::Eq,
+            FilterConditionType::Gte,
+            FilterConditionType::Lt,
+            FilterConditionType::Eq,
+            FilterConditionType::Between,
+        ]
+    );
+
+    assert!(matches!(filters[0].value, FilterValue::Text(_)));
+    assert!(matches!(
+        filters[1].value,
+        FilterValue::Number(Number::Integer(18))
+    ));
+    assert!(matches!(
+        filters[2].value,
+        FilterValue::Number(Number::Float(_))
+    ));
+    assert!(matches!(filters[3].value, FilterValue::IdList(_)));
+    assert!(filters[4].value.is_range());
+}
+
+#[test]
+fn wrapping_response_golden() {
+    let responses: Vec<WrappingResponse<ListResponse<CmsRowResponse>>> =
+        assert_golden(fixtures(), "wrapping_response");
+
+    let list = responses[0].as_ok().unwrap();
+
+    assert_eq!(list.total, 1);
+    assert_eq!(
+        list.items[0].fields.get(&SchematicFieldKey::Id),
+        Some(&SimpleValue::Text(String::from(
+            "0190e2b0-4d2a-7c1e-9a53-6f0b1d2e3f40"
+        )))
+    );
+
+    let error = responses[1].as_ok().unwrap_err();
+
+    assert_eq!(error.description, "Collection not found");
+    assert_eq!(error.code, ApiErrorCode::NotFound);
+
+    let error = responses[2].as_ok().unwrap_err();
+
+    assert_eq!(error.code, ApiErrorCode::Validation);
+    assert_eq!(error.field_errors[0].field, "count");
+
+    let error: ApiErrorResponse = assert_golden(fixtures(), "rate_limited_response");
+    let rate_limit = error.rate_limit.unwrap();
+
+    assert_eq!(error.code, ApiErrorCode::RateLimited);
+    assert_eq!(rate_limit.limit, 60);
+    assert_eq!(rate_limit.reset_at, datetime!(2024-05-01 12:00 UTC));
+
+    let legacy: ApiErrorResponse = serde_json::from_str(r#"{ "description": "Oops" }"#).unwrap();
+
+    assert_eq!(legacy.code, ApiErrorCode::Unknown);
+    assert!(!legacy.code.is_retryable());
+    assert_eq!(legacy.code.status_code(), 500);
+
+    let newer: ApiErrorResponse =
+        serde_json::from_str(r#"{ "code": "PaymentRequired", "description": "Oops" }"#).unwrap();
+
+    assert_eq!(newer.code, ApiErrorCode::Unknown);
+    assert!(ApiErrorCode::Internal.is_retryable());
+}
+
+#[test]
+fn borrowed_row_matches_owned() {
+    let payload = r#"{
+        "fields": {
+            "_id": "0190e2b0-4d2a-7c1e-9a53-6f0b1d2e3f40",
+            "title": "Hello World",
+            "escaped": "Hello \"World\"",
+            "tags": ["a", "b"],
+            "count": 3
+        }
+    }"#;
+
+    let borrowed: CmsRowResponseRef<'_> = serde_json::from_str(payload).unwrap();
+
+    let Some(SimpleValueRef::Text(title)) = borrowed.get("title") else {
+        panic!("Expected title to be Text");
+    };
+
+    assert!(matches!(title, Cow::Borrowed("Hello World")));
+    assert!(matches!(
+        borrowed.get("escaped"),
+        Some(SimpleValueRef::Text(Cow::Owned(_)))
+    ));
+    assert!(matches!(
+        borrowed.get("tags"),
+        Some(SimpleValueRef::ListString(v)) if v.iter().all(|v| v.is_borrowed())
+    ));
+
+    let owned: CmsRowResponse = serde_json::from_str(payload).unwrap();
+
+    assert_eq!(borrowed.into_owned(), owned);
+}
+
+#[test]
+fn raw_row_parses_on_read() {
+    let payload = r#"{
+        "fields": {
+            "title": "Hello World",
+            "tags": ["a", "b"],
+            "count": 3
+        }
+    }"#;
+
+    let list = format!(r#"{{ "items": [{payload}], "offset": 0, "limit": 1, "total": 1 }}"#);
+    let list: ListResponse<RawRow<'_>> = serde_json::from_str(&list).unwrap();
+    let raw = &list.items[0];
+
+    assert_eq!(raw.raw("tags").map(|v| v.get()), Some(r#"["a", "b"]"#));
+    assert!(matches!(
+        raw.get("title").unwrap(),
+        Some(SimpleValueRef::Text(Cow::Borrowed("Hello World")))
+    ));
+    assert_eq!(raw.get_as::<&str>("title").unwrap(), Some("Hello World"));
+    assert_eq!(raw.get_as::<u8>("count").unwrap(), Some(3));
+    assert!(raw.get("missing").unwrap().is_none());
+    assert!(raw.get_as::<u8>("title").is_err());
+
+    // Values are written back as they came.
+    assert_eq!(
+        serde_json::to_value(raw).unwrap()["fields"],
+        serde_json::from_str::<serde_json::Value>(payload).unwrap()["fields"]
+    );
+
+    let owned: CmsRowResponse = serde_json::from_str(payload).unwrap();
+
+    assert_eq!(raw.clone().into_owned().unwrap(), owned);
+}
+
+#[test]
+fn filter_expr_golden() {
+    let expr: FilterExpr = assert_golden(fixtures(), "filter_expr");
+
+    let row = |title: &str, age: i64, status: &str| {
+        HashMap::from([
+            (SchematicFieldKey::from("title"), SimpleValue::from(title)),
+            (SchematicFieldKey::from("age"), SimpleValue::from(age)),
+            (SchematicFieldKey::from("status"), SimpleValue::from(status)),
+        ])
+    };
+
+    assert!(expr.evaluate(&row("Hello World", 10, "banned")).unwrap());
+    assert!(expr.evaluate(&row("Goodbye", 30, "active")).unwrap());
+    assert!(!expr.evaluate(&row("Goodbye", 30, "banned")).unwrap());
+    assert!(!expr.evaluate(&row("Goodbye", 70, "active")).unwrap());
+
+    // The flat form is an implicit AND.
+    let flat: Vec<Filter> = load_fixture(fixtures(), "filter");
+    let expr: FilterExpr =
+        serde_json::from_value(load_fixture_value(fixtures(), "filter")).unwrap();
+
+    assert!(matches!(&expr, FilterExpr::And(v) if v.len() == flat.len()));
+}
+
+#[test]
+fn simple_value_json_conversion() {
+    let values = vec![
+        SimpleValue::Text(String::from("Hello")),
+        SimpleValue::Number(Number::Integer(7)),
+        SimpleValue::Number(Number::Integer(i64::MAX)),
+        SimpleValue::Number(Number::Float(0.1)),
+        SimpleValue::Boolean(false),
+        SimpleValue::DateTime(time::macros::datetime!(2024-01-02 03:04:05.5 +02:00)),
+        SimpleValue::Date(time::macros::date!(2024 - 01 - 02)),
+        SimpleValue::Time(time::macros::time!(03:04:05)),
+        SimpleValue::Time(time::macros::time!(03:04:05.25)),
+        SimpleValue::ListString(vec![String::from("a")]),
+        SimpleValue::ListNumber(vec![Number::Integer(1), Number::Float(2.5)]),
+        SimpleValue::ArrayUnknown(vec![serde_json::json!(1), serde_json::json!("a")]),
+        SimpleValue::ObjectUnknown(serde_json::json!({ "a": [1, 2] })),
+    ];
+
+    for value in values {
+        let json = value.clone().into_json();
+
+        assert_eq!(SimpleValue::from_json(json).unwrap(), value);
+    }
+
+    assert_eq!(
+        SimpleValue::from_json(serde_json::json!("2024-01-01")).unwrap(),
+        SimpleValue::Date(time::macros::date!(2024 - 01 - 01))
+    );
+    assert!(SimpleValue::from_json(serde_json::Value::Null).is_err());
+}
+
+#[test]
+fn cursor_round_trip() {
+    let cursor = Cursor::encode(&("2024-01-02T03:04:05Z", 42)).unwrap();
+
+    assert_eq!(
+        cursor.decode::<(String, i64)>().unwrap(),
+        (String::from("2024-01-02T03:04:05Z"), 42)
+    );
+    assert_eq!(cursor.as_str().parse::<Cursor>().unwrap(), cursor);
+    assert!("not a cursor!".parse::<Cursor>().is_err());
+    assert!(serde_json::from_str::<Cursor>(r#""not a cursor!""#).is_err());
+    assert_eq!(
+        serde_json::from_value::<Cursor>(serde_json::to_value(&cursor).unwrap()).unwrap(),
+        cursor
+    );
+
+    let list = ListResponse::<i64>::all(vec![1, 2]).with_cursors(Some(cursor), None);
+    let json = serde_json::to_value(&list).unwrap();
+
+    assert!(json.get("prev_cursor").is_none());
+    assert_eq!(
+        serde_json::from_value::<ListResponse<i64>>(json).unwrap(),
+        list
+    );
+}
+
+#[test]
+fn aggregate_golden() {
+    let request: CmsAggregate = assert_golden(fixtures(), "aggregate_request");
+
+    assert_eq!(request.aggregations[0], Aggregation::count());
+    assert_eq!(request.aggregations[0].name(), "count");
+    assert_eq!(request.aggregations[1].name(), "average");
+    assert_eq!(
+        Aggregation::new(AggregateFunction::Sum, "price").name(),
+        "sum_price"
+    );
+
+    let response: CmsAggregateResponse = assert_golden(fixtures(), "aggregate_response");
+
+    assert_eq!(
+        response.groups[0].values["count"],
+        SimpleValue::Number(Number::Integer(12))
+    );
+}
+
+#[test]
+fn bulk_golden() {
+    let response: BulkOperationResponse = assert_golden(fixtures(), "bulk_response");
+
+    assert!(!response.is_success());
+    assert_eq!(response.succeeded.len(), 2);
+    assert_eq!(
+        response.error_for(1).unwrap().code,
+        ApiErrorCode::Validation
+    );
+    assert!(response.error_for(0).is_none());
+
+    let rebuilt = BulkOperationResponse::from_results(vec![
+        Ok(response.succeeded[0].1),
+        Err(response.failed[0].1.clone()),
+        Ok(response.succeeded[1].1),
+    ]);
+
+    assert_eq!(
+        serde_json::to_value(&rebuilt).unwrap(),
+        load_fixture_value(fixtures(), "bulk_response")
+    );
+
+    let update: CmsBulkUpdate = serde_json::from_value(serde_json::json!({
+        "rows": [{
+            "id": response.succeeded[0].1,
+            "patches": {
+                "title": { "type": "Set", "value": "Hello" },
+                "notes": { "type": "Unset" }
+            }
+        }]
+    }))
+    .unwrap();
+
+    let mut fields = HashMap::from([(
+        SchematicFieldKey::from("notes"),
+        SimpleValue::Text(String::from("Old")),
+    )]);
+
+    update.rows[0].apply_to(&mut fields).unwrap();
+
+    assert_eq!(fields["title"], SimpleValue::Text(String::from("Hello")));
+    assert!(!fields.contains_key("notes"));
+    assert_roundtrip(&update);
+}
+
+#[test]
+fn field_patches() {
+    let row: CmsUpdateDataRow = serde_json::from_value(serde_json::json!({
+        "id": "0190a5b8-7c4e-7d21-9c3a-2f1e5d6c7b8a",
+        "patches": {
+            "title": { "type": "Set", "value": "Hello" },
+            "notes": { "type": "Unset" },
+            "views": { "type": "Increment", "value": 5 },
+            "tags": { "type": "Append", "value": "new" },
+            "scores": { "type": "RemoveFromList", "value": 2.0 }
+        }
+    }))
+    .unwrap();
+
+    let mut fields = HashMap::new();
+    fields.insert(
+        SchematicFieldKey::from("notes"),
+        SimpleValue::Text(String::from("Old")),
+    );
+    fields.insert(
+        SchematicFieldKey::from("views"),
+        SimpleValue::Number(Number::Integer(10)),
+    );
+    fields.insert(
+        SchematicFieldKey::from("scores"),
+        SimpleValue::ListNumber(vec![Number::Byte(1), Number::Byte(2)]),
+    );
+
+    row.apply_to(&mut fields).unwrap();
+
+    assert!(!fields.contains_key("notes"));
+    assert_eq!(fields["title"], SimpleValue::Text(String::from("Hello")));
+    assert_eq!(fields["views"], SimpleValue::Number(Number::Integer(15)));
+    assert_eq!(
+        fields["tags"],
+        SimpleValue::ListString(vec![String::from("new")])
+    );
+    assert_eq!(
+        fields["scores"],
+        SimpleValue::ListNumber(vec![Number::Byte(1)])
+    );
+
+    let text = SimpleValue::Text(String::from("a"));
+    assert!(
+        FieldPatch::Increment(Number::Byte(1))
+            .apply(Some(&text))
+            .is_err()
+    );
+    assert!(
+        FieldPatch::Increment(Number::Integer(1))
+            .apply(Some(&SimpleValue::Number(Number::Integer(i64::MAX))))
+            .is_err()
+    );
+    assert_eq!(FieldPatch::RemoveFromList(text).apply(None).unwrap(), None);
+}
+
+#[test]
+fn row_meta_revisions() {
+    let mut meta = RowMeta::new(time::macros::datetime!(2024-01-02 03:04:05 UTC), None);
+    meta.bump(time::macros::datetime!(2024-01-03 03:04:05 UTC));
+    meta.revision = 3;
+
+    let row = CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::new(),
+        meta: Some(meta),
+        seo: None,
+    };
+
+    let mut meta = assert_roundtrip(&row).meta.unwrap();
+
+    assert!(meta.check_revision(None).is_ok());
+    assert!(meta.check_revision(Some(3)).is_ok());
+
+    meta.bump(time::macros::datetime!(2024-01-04 00:00 UTC));
+
+    assert_eq!(meta.revision, 4);
+    assert!(matches!(
+        meta.check_revision(Some(3)),
+        Err(GlobalError::RevisionConflict {
+            expected: 3,
+            found: 4
+        })
+    ));
+
+    let legacy: CmsRowResponse =
+        serde_json::from_value(serde_json::json!({ "fields": {} })).unwrap();
+    assert_eq!(legacy.meta, None::<RowMeta>);
+}
+
+#[test]
+fn trash_round_trip() {
+    let id = SchemaDataPublicId::new();
+    let mut meta = RowMeta::new(time::macros::datetime!(2024-01-01 00:00 UTC), None);
+
+    let purge = CmsPurgeRequest {
+        ids: vec![id],
+        deleted_before: None,
+    };
+    assert!(!purge.matches(id, &meta));
+
+    meta.soft_delete(time::macros::datetime!(2024-01-02 00:00 UTC));
+    meta.soft_delete(time::macros::datetime!(2024-01-03 00:00 UTC));
+
+    assert_eq!(meta.revision, 1);
+    assert_eq!(
+        meta.deleted_at,
+        Some(time::macros::datetime!(2024-01-02 00:00 UTC))
+    );
+    assert!(purge.matches(id, &meta));
+
+    let row = assert_roundtrip(&CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::new(),
+        meta: Some(meta),
+        seo: None,
+    });
+    assert!(row.is_deleted());
+
+    let purge_old = CmsPurgeRequest {
+        ids: Vec::new(),
+        deleted_before: Some(time::macros::datetime!(2024-01-02 00:00 UTC)),
+    };
+    assert!(!purge_old.matches(id, &meta));
+
+    meta.restore(time::macros::datetime!(2024-01-04 00:00 UTC));
+
+    assert!(!meta.is_deleted());
+    assert_eq!(meta.revision, 2);
+    assert!(!purge.matches(id, &meta));
+}
+
+#[test]
+fn rate_limit_headers() {
+    let now = datetime!(2024-05-01 11:59:30.5 UTC);
+    let rate_limit = RateLimit {
+        limit: 60,
+        remaining: 0,
+        reset_at: datetime!(2024-05-01 12:00 UTC),
+    };
+
+    let headers = rate_limit.to_headers(now);
+
+    assert_eq!(
+        headers,
+        [
+            ("X-RateLimit-Limit", String::from("60")),
+            ("X-RateLimit-Remaining", String::from("0")),
+            ("X-RateLimit-Reset", String::from("1714564800")),
+            ("Retry-After", String::from("30")),
+        ]
+    );
+
+    let get = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+
+    assert_eq!(RateLimit::from_headers(get, now), Some(rate_limit));
+
+    let retry_only =
+        RateLimit::from_headers(|name| (name == "Retry-After").then_some("10"), now).unwrap();
+
+    assert!(retry_only.is_exceeded());
+    assert_eq!(retry_only.retry_after(now), time::Duration::seconds(10));
+    assert_eq!(RateLimit::from_headers(|_| None, now), None);
+
+    // Overflowing values are ignored instead of panicking.
+    let huge = |name: &str| (name == "Retry-After").then_some("9223372036854775807");
+
+    assert_eq!(RateLimit::from_headers(huge, now), None);
+    assert_eq!(
+        RateLimit::from_headers(
+            |name| match name {
+                "X-RateLimit-Limit" => Some("60"),
+                "X-RateLimit-Remaining" => Some("0"),
+                _ => huge(name),
+            },
+            now
+        )
+        .unwrap()
+        .reset_at,
+        now
+    );
+
+    let available = RateLimit {
+        remaining: 5,
+        ..rate_limit
+    };
+
+    assert_eq!(available.to_headers(now).len(), 3);
+    assert_eq!(available.retry_after(now), time::Duration::ZERO);
+
+    let error = ApiErrorResponse::rate_limited(rate_limit);
+
+    assert_eq!(error.code, ApiErrorCode::RateLimited);
+    assert_eq!(error.rate_limit, Some(rate_limit));
+}
+
+#[test]
+fn idempotent_requests() {
+    let key = IdempotencyKey::parse("order:2024-05-01_42").unwrap();
+
+    assert_eq!(key.scoped("member-1"), "member-1:order:2024-05-01_42");
+    assert!(IdempotencyKey::parse("").is_err());
+    assert!(IdempotencyKey::parse("order 42").is_err());
+    assert!(IdempotencyKey::parse(&"a".repeat(256)).is_err());
+    assert_ne!(IdempotencyKey::new(), IdempotencyKey::new());
+
+    let insert: CmsBulkInsert = serde_json::from_value(serde_json::json!({
+        "rows": [],
+        "idempotencyKey": "order:2024-05-01_42"
+    }))
+    .unwrap();
+
+    assert_eq!(insert.idempotency_key, Some(key.clone()));
+    assert!(
+        serde_json::from_value::<CmsBulkInsert>(serde_json::json!({
+            "rows": [],
+            "idempotencyKey": "order 42"
+        }))
+        .is_err()
+    );
+
+    let response = assert_roundtrip(&WrappingResponse::replayed(
+        key.clone(),
+        datetime!(2024-05-01 12:00 UTC),
+        3u64,
+    ));
+
+    assert!(response.is_replayed());
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({
+            "type": "Replayed",
+            "value": {
+                "idempotencyKey": "order:2024-05-01_42",
+                "handledAt": "2024-05-01 12:00:00.0 +00:00:00",
+                "response": 3
+            }
+        })
+    );
+
+    let mapped = response.map(|v| v * 2);
+
+    assert!(mapped.is_replayed());
+    assert_eq!(mapped.ok().unwrap(), 6);
+}
+
+#[test]
+fn legacy_field_names_are_accepted() {
+    let schematic: Schematic = assert_legacy_golden(fixtures(), "schematic");
+
+    assert_eq!(schematic.primary_field, "title");
+    assert!(schematic.fields["title"].constraints.required);
+
+    assert_legacy_golden::<BulkOperationResponse>(fixtures(), "bulk_response");
+    assert_legacy_golden::<Vec<WrappingResponse<ListResponse<CmsRowResponse>>>>(
+        fixtures(),
+        "wrapping_response",
+    );
+    assert_legacy_golden::<ApiErrorResponse>(fixtures(), "rate_limited_response");
+
+    let cursor = Cursor::encode(&("title", 3)).unwrap();
+    let list: ListResponse<u8> = serde_json::from_value(serde_json::json!({
+        "items": [1],
+        "offset": 0,
+        "limit": 1,
+        "total": 2,
+        "next_cursor": cursor,
+    }))
+    .unwrap();
+
+    assert_eq!(list.next_cursor, Some(cursor.clone()));
+    assert_eq!(
+        serde_json::to_value(&list).unwrap()["nextCursor"],
+        serde_json::to_value(&cursor).unwrap()
+    );
+
+    let cell: CmsUpdateDataCell =
+        serde_json::from_str(r#"{ "field_name": "title", "value": null }"#).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&cell).unwrap(),
+        serde_json::json!({ "fieldName": "title", "value": null })
+    );
+
+    let constraints: FieldConstraints =
+        serde_json::from_str(r#"{ "min_length": 2, "maxLength": 5 }"#).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&constraints).unwrap(),
+        serde_json::json!({ "required": false, "minLength": 2, "maxLength": 5 })
+    );
+
+    let upload: WebsiteUpload = serde_json::from_value(serde_json::json!({
+        "public_id": "abc",
+        "namespace": null,
+        "upload_type": "Image",
+        "display_name": "Beach",
+        "created_at": serde_json::to_value(datetime!(2024-05-01 12:00 UTC)).unwrap(),
+        "deleted_at": null,
+        "media": {
+            "file_size": 1024,
+            "file_type": "image/png",
+            "media_width": 640,
+            "media_height": 480,
+            "media_duration": null,
+            "is_editable": true,
+            "has_thumbnail": false,
+            "is_global": false,
+        },
+        "using_variant": null,
+    }))
+    .unwrap();
+
+    assert_eq!(assert_roundtrip(&upload), upload);
+
+    let json = serde_json::to_value(&upload).unwrap();
+
+    assert_eq!(json["publicId"], "abc");
+    assert_eq!(json["media"]["mediaWidth"], 640);
+}
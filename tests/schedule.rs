@@ -0,0 +1,338 @@
+use time::{
+    OffsetDateTime, Weekday,
+    macros::{datetime, offset},
+};
+use webby_global_common::{
+    email::Mailbox,
+    schedule::{
+        ByDay, Frequency, RRule, Recurrence,
+        ics::{Calendar, CalendarEvent},
+    },
+    testing::assert_roundtrip,
+};
+
+fn recurrence(start: time::PrimitiveDateTime, rule: &str) -> Recurrence {
+    Recurrence {
+        start,
+        time_zone: None,
+        rule: rule.parse().unwrap(),
+    }
+}
+
+fn dates(recurrence: &Recurrence, from: OffsetDateTime, to: OffsetDateTime) -> Vec<String> {
+    recurrence
+        .between(from, to)
+        .unwrap()
+        .into_iter()
+        .map(|v| v.date().to_string())
+        .collect()
+}
+
+#[test]
+fn parses_rules() {
+    let rule = RRule::parse("RRULE:FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU,-1FR;COUNT=6").unwrap();
+
+    assert_eq!(
+        rule,
+        RRule::new(Frequency::Monthly)
+            .interval(2)
+            .by_day([
+                ByDay::nth(2, Weekday::Tuesday),
+                ByDay::nth(-1, Weekday::Friday)
+            ])
+            .count(6)
+    );
+    assert_eq!(
+        rule.to_string(),
+        "FREQ=MONTHLY;INTERVAL=2;BYDAY=2TU,-1FR;COUNT=6"
+    );
+
+    assert_eq!(
+        RRule::parse("FREQ=DAILY;UNTIL=20240310").unwrap().until,
+        Some(datetime!(2024-03-10 23:59:59 UTC))
+    );
+    assert_eq!(
+        RRule::parse("FREQ=DAILY;UNTIL=20240310T120000Z")
+            .unwrap()
+            .to_string(),
+        "FREQ=DAILY;UNTIL=20240310T120000Z"
+    );
+
+    for invalid in [
+        "",
+        "INTERVAL=2",
+        "FREQ=HOURLY",
+        "FREQ=DAILY;INTERVAL=0",
+        "FREQ=DAILY;COUNT=2;UNTIL=20240310",
+        "FREQ=WEEKLY;BYDAY=2MO",
+        "FREQ=MONTHLY;BYDAY=6MO",
+        "FREQ=MONTHLY;BYDAY=XX",
+        "FREQ=MONTHLY;BYSETPOS=1",
+    ] {
+        assert!(RRule::parse(invalid).is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn deserializes_fields_and_strings() {
+    let rule = RRule::new(Frequency::Weekly)
+        .by_day([ByDay::every(Weekday::Monday)])
+        .until(datetime!(2024-06-01 0:00 UTC));
+
+    assert_roundtrip(&rule);
+    assert_eq!(
+        serde_json::to_value(&rule).unwrap(),
+        serde_json::json!({
+            "freq": "weekly",
+            "interval": 1,
+            "byDay": ["MO"],
+            "until": "2024-06-01 00:00:00.0 +00:00:00",
+        })
+    );
+
+    let from_string: RRule = serde_json::from_value(serde_json::json!(
+        "FREQ=WEEKLY;BYDAY=MO;UNTIL=20240601T000000Z"
+    ))
+    .unwrap();
+    let from_fields: RRule = serde_json::from_value(serde_json::json!({
+        "freq": "weekly",
+        "byDay": ["MO"],
+        "until": "2024-06-01 00:00:00.0 +00:00:00",
+    }))
+    .unwrap();
+
+    assert_eq!(from_string, rule);
+    assert_eq!(from_fields, rule);
+    assert!(
+        serde_json::from_value::<RRule>(serde_json::json!({ "freq": "daily", "interval": 0 }))
+            .is_err()
+    );
+}
+
+#[test]
+fn expands_occurrences() {
+    let from = datetime!(2024-01-01 0:00 UTC);
+    let to = datetime!(2025-01-01 0:00 UTC);
+
+    // 2024-01-01 is a Monday.
+    assert_eq!(
+        dates(
+            &recurrence(
+                datetime!(2024-01-03 10:00),
+                "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4"
+            ),
+            from,
+            to
+        ),
+        ["2024-01-03", "2024-01-08", "2024-01-10", "2024-01-15"]
+    );
+    assert_eq!(
+        dates(
+            &recurrence(
+                datetime!(2024-01-01 10:00),
+                "FREQ=MONTHLY;BYDAY=-1FR;COUNT=3"
+            ),
+            from,
+            to
+        ),
+        ["2024-01-26", "2024-02-23", "2024-03-29"]
+    );
+    // Months without a 31st are skipped.
+    assert_eq!(
+        dates(
+            &recurrence(datetime!(2024-01-31 10:00), "FREQ=MONTHLY;COUNT=3"),
+            from,
+            to
+        ),
+        ["2024-01-31", "2024-03-31", "2024-05-31"]
+    );
+    assert_eq!(
+        dates(
+            &recurrence(datetime!(2020-02-29 10:00), "FREQ=YEARLY"),
+            datetime!(2020-01-01 0:00 UTC),
+            datetime!(2030-01-01 0:00 UTC)
+        ),
+        ["2020-02-29", "2024-02-29", "2028-02-29"]
+    );
+    assert_eq!(
+        dates(
+            &recurrence(
+                datetime!(2024-01-01 10:00),
+                "FREQ=DAILY;INTERVAL=2;UNTIL=20240107T100000Z"
+            ),
+            from,
+            to
+        ),
+        ["2024-01-01", "2024-01-03", "2024-01-05", "2024-01-07"]
+    );
+    // The first Monday of the year.
+    assert_eq!(
+        dates(
+            &recurrence(datetime!(2024-01-01 10:00), "FREQ=YEARLY;BYDAY=1MO"),
+            from,
+            datetime!(2026-01-01 0:00 UTC)
+        ),
+        ["2024-01-01", "2025-01-06"]
+    );
+}
+
+#[test]
+fn counts_from_the_start() {
+    let recurrence = recurrence(datetime!(2024-01-01 9:00), "FREQ=DAILY;COUNT=10");
+
+    // The window only has the last 3 of the 10.
+    assert_eq!(
+        dates(
+            &recurrence,
+            datetime!(2024-01-08 0:00 UTC),
+            datetime!(2024-02-01 0:00 UTC)
+        ),
+        ["2024-01-08", "2024-01-09", "2024-01-10"]
+    );
+}
+
+#[test]
+fn keeps_the_local_time() {
+    let recurrence = Recurrence {
+        start: datetime!(2024-03-08 9:00),
+        time_zone: Some(String::from("America/New_York")),
+        rule: RRule::new(Frequency::Daily).count(4),
+    };
+
+    recurrence.validate().unwrap();
+    assert_roundtrip(&recurrence);
+
+    let occurrences = recurrence
+        .between(
+            datetime!(2024-03-01 0:00 UTC),
+            datetime!(2024-04-01 0:00 UTC),
+        )
+        .unwrap();
+
+    // Summer time starts on 2024-03-10.
+    assert_eq!(
+        occurrences,
+        [
+            datetime!(2024-03-08 9:00 -5),
+            datetime!(2024-03-09 9:00 -5),
+            datetime!(2024-03-10 9:00 -4),
+            datetime!(2024-03-11 9:00 -4),
+        ]
+    );
+    assert!(occurrences.iter().all(|v| v.hour() == 9));
+    assert_eq!(occurrences[3].offset(), offset!(-4));
+
+    assert!(
+        Recurrence {
+            time_zone: Some(String::from("Mars/Olympus_Mons")),
+            ..recurrence
+        }
+        .validate()
+        .is_err()
+    );
+}
+
+fn calendar() -> Calendar {
+    Calendar::new(vec![
+        CalendarEvent::new(
+            "standup@example.com",
+            "Standup; daily, at 9",
+            datetime!(2024-03-08 9:00),
+            datetime!(2024-03-08 9:15),
+        )
+        .time_zone("America/New_York")
+        .rule(
+            RRule::new(Frequency::Weekly)
+                .by_day([ByDay::every(Weekday::Monday), ByDay::every(Weekday::Friday)]),
+        )
+        .description("Line one\nLine two with a long enough text to be folded onto the next line")
+        .organizer(Mailbox::parse("\"Doe, Jane\" <jane@example.com>").unwrap()),
+        CalendarEvent::new(
+            "launch@example.com",
+            "Launch",
+            datetime!(2024-04-01 0:00),
+            datetime!(2024-04-02 0:00),
+        )
+        .all_day()
+        .location("Berlin"),
+    ])
+    .name("Team")
+}
+
+#[test]
+fn renders_ics() {
+    let ics = calendar().render().unwrap();
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.lines().all(|v| v.len() <= 76));
+
+    for line in [
+        "X-WR-CALNAME:Team",
+        "TZID:America/New_York",
+        "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU",
+        "TZOFFSETFROM:-0500",
+        "TZOFFSETTO:-0400",
+        "DTSTART;TZID=America/New_York:20240308T090000",
+        "RRULE:FREQ=WEEKLY;BYDAY=MO,FR",
+        "SUMMARY:Standup\\; daily\\, at 9",
+        "ORGANIZER;CN=\"Doe, Jane\":mailto:jane@example.com",
+        "DTSTART;VALUE=DATE:20240401",
+        "DTEND;VALUE=DATE:20240402",
+    ] {
+        assert!(ics.contains(&format!("\r\n{line}\r\n")), "{line}");
+    }
+
+    assert_eq!(Calendar::parse(&ics).unwrap(), calendar());
+}
+
+#[test]
+fn parses_ics() {
+    let ics = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:1\r
+SUMMARY:Call\r
+DTSTART:20240310T140000Z\r
+DURATION:PT30M\r
+X-UNKNOWN;FOO=bar:ignored\r
+ORGANIZER:MAILTO:jane@example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2\r
+SUMMARY:Lunch with\r
+  the team\r
+DTSTART;TZID=Europe/Berlin:20240310T120000\r
+DTEND:20240310T120000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    let calendar = Calendar::parse(ics).unwrap();
+    let [call, lunch] = &calendar.events[..] else {
+        panic!("expected 2 events");
+    };
+
+    assert_eq!(call.time_zone, None);
+    assert_eq!(call.end, datetime!(2024-03-10 14:30));
+    assert_eq!(
+        call.organizer.as_ref().map(|v| v.address().as_str()),
+        Some("jane@example.com")
+    );
+
+    assert_eq!(lunch.summary, "Lunch with the team");
+    assert_eq!(lunch.time_zone.as_deref(), Some("Europe/Berlin"));
+    // 12:00 UTC is 13:00 in Berlin.
+    assert_eq!(lunch.end, datetime!(2024-03-10 13:00));
+    assert!(lunch.recurrence().is_none());
+
+    for invalid in [
+        "",
+        "BEGIN:VEVENT\r\nEND:VEVENT",
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nEND:VEVENT",
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nEND:VEVENT\r\nEND:VCALENDAR",
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:1\r\nDTSTART:nope\r\nEND:VEVENT\r\nEND:VCALENDAR",
+    ] {
+        assert!(Calendar::parse(invalid).is_err(), "{invalid:?}");
+    }
+}
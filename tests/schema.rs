@@ -0,0 +1,982 @@
+use std::{collections::HashMap, time::Duration};
+
+use time::macros::datetime;
+
+use webby_global_common::{
+    id::WebsitePublicId,
+    response::{PublicSchema, PublicSchemaMeta},
+    schema::{
+        DefaultSort, OperationSet, Operations, SchemaFieldMap, SchemaViewItem, Schematic,
+        SchematicFieldKey, SchematicFieldType, SchematicFieldValue, SortOrder,
+        builder::SchematicBuilder,
+        constraints::{FieldConstraints, Pattern},
+        normalize_tags,
+        path::FieldPath,
+        references::ReferenceGraph,
+        validation::ValidationErrorKind,
+        version::SchemaVersion,
+    },
+    upload::MediaGalleryItem,
+    validators::{EmailAddress, PhoneNumber, PostalAddress},
+    value::{Number, SimpleValue},
+};
+
+#[test]
+fn builder_checks_consistency() {
+    let builder = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .sortable()
+        .field("author", SchematicFieldType::Reference)
+        .reference("authors");
+
+    let schematic = builder.clone().primary_field("title").build().unwrap();
+
+    assert_eq!(schematic.fields.len(), 2);
+    assert!(schematic.fields["title"].sortable);
+    assert_eq!(schematic.fields["author"].index, 1);
+    assert_eq!(
+        schematic.fields["author"].referenced_schema.as_deref(),
+        Some("authors")
+    );
+
+    assert!(builder.clone().primary_field("body").build().is_err());
+    assert!(
+        builder
+            .field("body", SchematicFieldType::RichText)
+            .index(0)
+            .primary_field("title")
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn defaults_fill_missing_fields() {
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .default_value(SchematicFieldValue::Text(String::from("Untitled")))
+        .field("published", SchematicFieldType::Boolean)
+        .default_value(SchematicFieldValue::Boolean(false))
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let mut record = HashMap::from([(
+        SchematicFieldKey::from("title"),
+        SimpleValue::Text(String::from("Hello")),
+    )]);
+
+    schematic.apply_defaults(&mut record);
+
+    assert_eq!(record["title"], SimpleValue::Text(String::from("Hello")));
+    assert_eq!(record["published"], SimpleValue::Boolean(false));
+}
+
+#[test]
+fn constraints_are_validated() {
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .required()
+        .field("rating", SchematicFieldType::Number)
+        .constraints(FieldConstraints {
+            min: Some(1.into()),
+            max: Some(5.into()),
+            ..Default::default()
+        })
+        .field("slug", SchematicFieldType::Text)
+        .constraints(FieldConstraints {
+            max_length: Some(8),
+            pattern: Some(Pattern::from("^[a-z-]+$")),
+            ..Default::default()
+        })
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let record = HashMap::from([
+        (
+            SchematicFieldKey::from("rating"),
+            SimpleValue::Number(7.into()),
+        ),
+        (
+            SchematicFieldKey::from("slug"),
+            SimpleValue::Text(String::from("Hello")),
+        ),
+    ]);
+
+    let kinds = schematic
+        .validate_record(&record)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.field.to_string(), e.kind))
+        .collect::<Vec<_>>();
+
+    assert!(matches!(kinds[0], (ref f, ValidationErrorKind::OutOfRange { .. }) if f == "rating"));
+    assert!(
+        matches!(kinds[1], (ref f, ValidationErrorKind::PatternMismatch { .. }) if f == "slug")
+    );
+    assert!(matches!(kinds[2], (ref f, ValidationErrorKind::Required) if f == "title"));
+}
+
+#[test]
+fn each_constraint_is_validated() {
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .required()
+        .field("summary", SchematicFieldType::Text)
+        .constraints(FieldConstraints {
+            min_length: Some(3),
+            max_length: Some(5),
+            ..Default::default()
+        })
+        .field("rating", SchematicFieldType::Number)
+        .constraints(FieldConstraints {
+            min: Some(1.into()),
+            max: Some(5.into()),
+            ..Default::default()
+        })
+        .field("slug", SchematicFieldType::Text)
+        .constraints(FieldConstraints {
+            pattern: Some(Pattern::from("^[a-z-]+$")),
+            ..Default::default()
+        })
+        .field("status", SchematicFieldType::Text)
+        .constraints(FieldConstraints {
+            allowed_values: vec![String::from("draft"), String::from("live")],
+            ..Default::default()
+        })
+        .field("tags", SchematicFieldType::Tags)
+        .constraints(FieldConstraints {
+            max_length: Some(2),
+            allowed_values: vec![String::from("a"), String::from("b")],
+            ..Default::default()
+        })
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let check = |key: &str, value: SimpleValue| {
+        let mut record =
+            HashMap::from([(SchematicFieldKey::from("title"), SimpleValue::from("Hello"))]);
+        record.insert(SchematicFieldKey::from(key), value);
+
+        let errors = schematic.validate_record(&record).unwrap();
+        assert!(errors.iter().all(|e| e.field.as_str() == key), "{errors:?}");
+
+        errors.into_iter().next().map(|e| e.kind)
+    };
+    let tags = |v: &[&str]| SimpleValue::ListString(v.iter().map(|v| v.to_string()).collect());
+
+    assert_eq!(check("title", SimpleValue::from("Hello")), None);
+    assert_eq!(
+        check("title", SimpleValue::from("")),
+        Some(ValidationErrorKind::Required)
+    );
+
+    assert_eq!(check("summary", SimpleValue::from("abc")), None);
+    assert_eq!(check("summary", SimpleValue::from("abcde")), None);
+    assert_eq!(
+        check("summary", SimpleValue::from("ab")),
+        Some(ValidationErrorKind::LengthOutOfRange {
+            min: Some(3),
+            max: Some(5),
+            actual: 2,
+        })
+    );
+    // Characters are counted, not bytes.
+    assert_eq!(check("summary", SimpleValue::from("éééé")), None);
+    assert!(matches!(
+        check("summary", SimpleValue::from("abcdef")),
+        Some(ValidationErrorKind::LengthOutOfRange { actual: 6, .. })
+    ));
+    // Empty values only have to satisfy `required`.
+    assert_eq!(check("summary", SimpleValue::from("")), None);
+
+    assert_eq!(check("rating", SimpleValue::from(1)), None);
+    assert_eq!(check("rating", SimpleValue::from(5)), None);
+    assert_eq!(
+        check("rating", SimpleValue::from(0)),
+        Some(ValidationErrorKind::OutOfRange {
+            min: Some(1.into()),
+            max: Some(5.into()),
+            actual: 0.into(),
+        })
+    );
+    assert!(matches!(
+        check("rating", SimpleValue::Number(Number::Float(5.5))),
+        Some(ValidationErrorKind::OutOfRange { .. })
+    ));
+
+    assert_eq!(check("slug", SimpleValue::from("hello-world")), None);
+    assert_eq!(
+        check("slug", SimpleValue::from("Hello World")),
+        Some(ValidationErrorKind::PatternMismatch {
+            pattern: String::from("^[a-z-]+$"),
+        })
+    );
+
+    // Compiled once and kept with the field.
+    let pattern = schematic.fields["slug"]
+        .constraints
+        .pattern
+        .as_ref()
+        .unwrap();
+    assert!(std::ptr::eq(
+        pattern.regex().unwrap(),
+        pattern.regex().unwrap()
+    ));
+    assert!(Pattern::from("(").regex().is_err());
+    assert_eq!(
+        serde_json::to_value(pattern).unwrap(),
+        serde_json::json!("^[a-z-]+$")
+    );
+
+    assert_eq!(check("status", SimpleValue::from("live")), None);
+    assert_eq!(
+        check("status", SimpleValue::from("archived")),
+        Some(ValidationErrorKind::NotAllowed {
+            allowed: vec![String::from("draft"), String::from("live")],
+        })
+    );
+
+    assert_eq!(check("tags", tags(&["a", "b"])), None);
+    assert!(matches!(
+        check("tags", tags(&["a", "b", "a"])),
+        Some(ValidationErrorKind::LengthOutOfRange { actual: 3, .. })
+    ));
+    assert!(matches!(
+        check("tags", tags(&["a", "c"])),
+        Some(ValidationErrorKind::NotAllowed { .. })
+    ));
+}
+
+#[test]
+fn absent_required_fields_are_reported() {
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .required()
+        .field("body", SchematicFieldType::RichText)
+        .required()
+        .field("notes", SchematicFieldType::Text)
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let missing = |record: &[(&str, &str)]| {
+        let record = record
+            .iter()
+            .map(|(k, v)| (SchematicFieldKey::from(*k), SimpleValue::from(*v)))
+            .collect();
+
+        schematic
+            .validate_record(&record)
+            .unwrap()
+            .into_iter()
+            .map(|e| {
+                assert_eq!(e.kind, ValidationErrorKind::Required);
+                e.field.to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(missing(&[]), ["body", "title"]);
+    assert_eq!(missing(&[("notes", "a")]), ["body", "title"]);
+    assert_eq!(missing(&[("title", "Hello")]), ["body"]);
+    assert_eq!(missing(&[("title", "Hello"), ("body", "")]), ["body"]);
+    assert!(missing(&[("title", "Hello"), ("body", "<p>Hi</p>")]).is_empty());
+
+    let mut schematic = schematic.clone();
+    schematic.fields.update("body", |f| f.is_deleted = true);
+
+    let errors = schematic
+        .validate_record(&HashMap::from([(
+            SchematicFieldKey::from("title"),
+            SimpleValue::from("Hello"),
+        )]))
+        .unwrap();
+    assert!(
+        errors.is_empty(),
+        "deleted fields aren't required: {errors:?}"
+    );
+}
+
+#[test]
+fn reference_graph() {
+    let schema = |id: &str, references: &[&str]| {
+        references
+            .iter()
+            .fold(
+                SchematicBuilder::new(id, "Blog", id, WebsitePublicId::new())
+                    .field("title", SchematicFieldType::Text),
+                |builder, to| {
+                    builder
+                        .field(*to, SchematicFieldType::Reference)
+                        .reference(*to)
+                },
+            )
+            .primary_field("title")
+            .build()
+            .unwrap()
+    };
+
+    let graph = ReferenceGraph::from_schemas(&[
+        schema("authors", &[]),
+        schema("categories", &["categories"]),
+        schema("posts", &["authors", "categories", "missing"]),
+        schema("comments", &["posts"]),
+    ]);
+
+    assert_eq!(graph.dangling().len(), 1);
+    assert_eq!(graph.dangling()[0].referenced_schema, "missing");
+    assert_eq!(graph.referenced_by("posts"), ["comments"]);
+    assert!(graph.cycles().is_empty());
+    assert_eq!(
+        graph.deletion_order().unwrap(),
+        ["comments", "posts", "authors", "categories"]
+    );
+
+    let graph = ReferenceGraph::from_schemas(&[
+        schema("a", &["b"]),
+        schema("b", &["c"]),
+        schema("c", &["a"]),
+        schema("d", &["a"]),
+    ]);
+
+    assert_eq!(graph.cycles(), [["a", "b", "c"]]);
+    assert!(graph.deletion_order().is_err());
+}
+
+#[test]
+fn parses_multi_value_bytes() {
+    let id = "0190e2b0-4d2a-7c1e-9a53-6f0b1d2e3f40";
+    let bytes = format!(r#"["{id}"]"#).into_bytes();
+
+    let value = SchematicFieldType::MultiReference
+        .parse_value_bytes(bytes.clone())
+        .unwrap();
+
+    assert_eq!(
+        SchematicFieldType::MultiReference
+            .parse_value(value)
+            .unwrap(),
+        SchematicFieldValue::MultiReference(vec![id.parse().unwrap()])
+    );
+
+    let value = SchematicFieldType::MediaGallery
+        .parse_value_bytes(bytes)
+        .unwrap();
+
+    assert_eq!(
+        SchematicFieldType::MediaGallery.parse_value(value).unwrap(),
+        SchematicFieldValue::MediaGallery(vec![MediaGalleryItem::new(id.parse().unwrap(), 0)])
+    );
+
+    let value = SchematicFieldType::MultiDocument
+        .parse_value_bytes(br#"["a.pdf", "b.pdf"]"#.to_vec())
+        .unwrap();
+
+    assert_eq!(
+        SchematicFieldType::MultiDocument
+            .parse_value(value)
+            .unwrap(),
+        SchematicFieldValue::ListString(vec![String::from("a.pdf"), String::from("b.pdf")])
+    );
+
+    assert!(
+        SchematicFieldType::Tags
+            .parse_value_bytes(b"not json".to_vec())
+            .is_err()
+    );
+}
+
+#[test]
+fn tags_are_strings() {
+    let value = SchematicFieldType::Tags
+        .parse_value(SimpleValue::ListString(vec![
+            String::from(" rust "),
+            String::from("Rust"),
+            String::from("rust"),
+            String::new(),
+        ]))
+        .unwrap();
+
+    assert_eq!(
+        value,
+        SchematicFieldValue::Tags(vec![String::from("rust"), String::from("Rust")])
+    );
+    assert_eq!(
+        normalize_tags(vec![String::from("Rust"), String::from("rust ")], true),
+        [String::from("rust")]
+    );
+
+    // Tags used to be parsed into a Number List, which is how they were stored.
+    let stored: SchematicFieldValue =
+        serde_json::from_str(r#"{ "type": "ListNumber", "value": [3, 12] }"#).unwrap();
+
+    assert_eq!(
+        SchematicFieldType::Tags
+            .parse_value(SimpleValue::from(stored))
+            .unwrap(),
+        SchematicFieldValue::Tags(vec![String::from("3"), String::from("12")])
+    );
+    assert!(
+        serde_json::from_str::<SchematicFieldValue>(r#"{ "type": "Tags", "value": [1, "two"] }"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn parses_date_time_in_zone() {
+    let local = || SimpleValue::from("2024-01-15T09:30");
+
+    assert_eq!(
+        SchematicFieldType::DateTime
+            .parse_value_in_zone(local(), "Asia/Tokyo")
+            .unwrap(),
+        SchematicFieldValue::DateTime(datetime!(2024-01-15 09:30 +09:00))
+    );
+    assert_eq!(
+        SchematicFieldType::DateTime.parse_value(local()).unwrap(),
+        SchematicFieldValue::DateTime(datetime!(2024-01-15 09:30 UTC))
+    );
+    assert_eq!(
+        SchematicFieldType::DateTime
+            .parse_value_in_zone(SimpleValue::from("2024-01-15T09:30:00-02:00"), "Asia/Tokyo")
+            .unwrap(),
+        SchematicFieldValue::DateTime(datetime!(2024-01-15 09:30 -02:00))
+    );
+    assert!(
+        SchematicFieldType::DateTime
+            .parse_value_in_zone(local(), "Mars/Olympus_Mons")
+            .is_err()
+    );
+}
+
+#[test]
+fn phone_numbers_are_normalized() {
+    let phone = SchematicFieldType::Phone
+        .parse_value(SimpleValue::Text(String::from("+44 (20) 7946-0958")))
+        .unwrap()
+        .try_as_phone()
+        .unwrap();
+
+    assert_eq!(phone.to_e164().unwrap(), "+442079460958");
+    assert_eq!(phone.calling_code(), Some(44));
+    assert_eq!(phone.national_number(), Some("2079460958"));
+
+    let phone: PhoneNumber = "001 415 555 2671".parse().unwrap();
+    assert_eq!(
+        (phone.calling_code(), phone.to_string()),
+        (Some(1), String::from("+14155552671"))
+    );
+    assert_eq!(
+        "+352 621 123 456"
+            .parse::<PhoneNumber>()
+            .unwrap()
+            .calling_code(),
+        Some(352)
+    );
+    assert_eq!(serde_json::to_string(&phone).unwrap(), "\"+14155552671\"");
+
+    for invalid in ["415 555 2671", "+0 123 4567", "+1 555", "+1 555 CALL NOW"] {
+        assert!(invalid.parse::<PhoneNumber>().is_err(), "{invalid}");
+        assert!(
+            SchematicFieldType::Phone
+                .parse_value(SimpleValue::from(invalid))
+                .is_err(),
+            "{invalid}"
+        );
+
+        let stored: PhoneNumber = serde_json::from_value(serde_json::json!(invalid)).unwrap();
+        assert!(!stored.is_valid());
+        assert_eq!(stored.calling_code(), None);
+        assert_eq!(stored.to_string(), invalid);
+        assert_eq!(
+            serde_json::to_value(&stored).unwrap(),
+            serde_json::json!(invalid)
+        );
+    }
+
+    let stored: PhoneNumber = serde_json::from_str("\"+14155552671\"").unwrap();
+    assert!(stored.is_valid());
+    assert_eq!(stored, phone);
+}
+
+#[test]
+fn email_addresses_are_normalized() {
+    let email = SchematicFieldType::Email
+        .parse_value(SimpleValue::Text(String::from(
+            " Jane.Doe+News@Example.COM ",
+        )))
+        .unwrap()
+        .try_as_email()
+        .unwrap();
+
+    assert_eq!(email.as_str(), "Jane.Doe+News@example.com");
+    assert_eq!(email.local_part(), "Jane.Doe+News");
+    assert_eq!(email.domain(), "example.com");
+    assert_eq!(email.plus_tag(), Some("News"));
+    assert_eq!(
+        email.without_plus_tag(),
+        "Jane.Doe@example.com".parse::<EmailAddress>().unwrap()
+    );
+
+    for invalid in [
+        "jane",
+        "@example.com",
+        "jane@localhost",
+        "jane..doe@example.com",
+        "jane doe@example.com",
+        "jane@-example.com",
+        "jane@example..com",
+    ] {
+        assert!(invalid.parse::<EmailAddress>().is_err(), "{invalid}");
+
+        let stored: EmailAddress = serde_json::from_value(serde_json::json!(invalid)).unwrap();
+        assert!(!stored.is_valid());
+        assert_eq!(stored.as_str(), invalid);
+        assert_eq!(stored.without_plus_tag(), stored);
+        assert_eq!(
+            serde_json::to_value(&stored).unwrap(),
+            serde_json::json!(invalid)
+        );
+    }
+
+    let stored: EmailAddress = serde_json::from_str("\"jane@localhost\"").unwrap();
+    assert_eq!(
+        (stored.local_part(), stored.domain()),
+        ("jane", "localhost")
+    );
+
+    let stored: EmailAddress = serde_json::from_str("\"jane\"").unwrap();
+    assert_eq!((stored.local_part(), stored.domain()), ("jane", ""));
+
+    let stored: EmailAddress = serde_json::from_str("\"Jane.Doe+News@example.com\"").unwrap();
+    assert!(stored.is_valid());
+    assert_eq!(stored, email);
+}
+
+#[test]
+fn postal_addresses_are_structured() {
+    let address = SchematicFieldType::Address
+        .parse_value(SimpleValue::ObjectUnknown(serde_json::json!({
+            "line1": " 1 Main St ",
+            "line2": "",
+            "city": "Springfield",
+            "region": "IL",
+            "postalCode": "62701",
+            "country": "us",
+        })))
+        .unwrap()
+        .try_as_address()
+        .unwrap();
+
+    assert_eq!(address.country, "US");
+    assert_eq!(address.line2, None);
+    assert_eq!(address.to_string(), "1 Main St, Springfield, IL 62701, US");
+
+    let value = SimpleValue::from(SchematicFieldValue::Address(address.clone()));
+    assert_eq!(
+        SchematicFieldType::Address.parse_value(value).unwrap(),
+        SchematicFieldValue::Address(address)
+    );
+
+    // Addresses stored before they were structured.
+    let legacy: SchematicFieldValue =
+        serde_json::from_str(r#"{"type":"Address","value":"1 Main St, Springfield"}"#).unwrap();
+    assert_eq!(
+        legacy,
+        SchematicFieldValue::Address(PostalAddress::from_text("1 Main St, Springfield"))
+    );
+
+    assert!(
+        SchematicFieldType::Address
+            .parse_value(SimpleValue::Text(String::from(r#"{"country":"USA"}"#)))
+            .is_err()
+    );
+}
+
+#[test]
+fn media_gallery_items_keep_metadata() {
+    let first = "0190e2b0-4d2a-7c1e-9a53-6f0b1d2e3f40";
+    let second = "0190e2b0-4d2a-7c1e-9a53-6f0b1d2e3f41";
+
+    let bytes = serde_json::to_vec(&serde_json::json!([
+        { "uploadId": first, "caption": "Sunset", "order": 2 },
+        { "uploadId": second, "altText": "A beach", "order": 1 },
+    ]))
+    .unwrap();
+
+    let field_type = SchematicFieldType::MediaGallery;
+    let value = field_type
+        .parse_value(field_type.parse_value_bytes(bytes).unwrap())
+        .unwrap();
+
+    let items = value.as_media_gallery().unwrap();
+    assert_eq!(items[0].upload_id.to_string(), second);
+    assert_eq!(items[0].alt_text.as_deref(), Some("A beach"));
+    assert_eq!(items[1].caption.as_deref(), Some("Sunset"));
+
+    assert_eq!(
+        field_type
+            .parse_value(SimpleValue::from(value.clone()))
+            .unwrap(),
+        value
+    );
+
+    // Galleries stored as a list of upload ids.
+    let legacy = SimpleValue::Text(format!("{first}, {second}"))
+        .coerce_to(field_type)
+        .unwrap()
+        .try_as_media_gallery()
+        .unwrap();
+    assert_eq!(legacy[1], MediaGalleryItem::new(second.parse().unwrap(), 1));
+}
+
+#[test]
+fn operations_parse_and_combine() {
+    assert_eq!(
+        "BulkInsert".parse::<Operations>().unwrap(),
+        Operations::BulkInsert
+    );
+    assert_eq!(
+        "bulk_insert".parse::<Operations>().unwrap(),
+        Operations::BulkInsert
+    );
+    assert_eq!(Operations::QueryReferenced.to_string(), "QueryReferenced");
+    assert!("Explode".parse::<Operations>().is_err());
+
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .primary_field("title")
+        .allowed_operation(Operations::Get)
+        .allowed_operation(Operations::Insert)
+        .build()
+        .unwrap();
+
+    let mut value = serde_json::to_value(&schematic).unwrap();
+    assert_eq!(
+        value["allowedOperations"],
+        serde_json::json!(["Get", "Insert"])
+    );
+
+    // Names stored before they were typed, including ones which no longer exist.
+    value["allowedOperations"] = serde_json::json!(["get", "Find", "Explode"]);
+    let schematic: Schematic = serde_json::from_value(value).unwrap();
+    assert_eq!(
+        schematic.allowed_operations,
+        [
+            Operations::Get,
+            Operations::Find,
+            Operations::Unknown(String::from("Explode"))
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(&schematic).unwrap()["allowedOperations"],
+        serde_json::json!(["Get", "Find", "Explode"])
+    );
+
+    let operations = schematic.operations();
+    assert_eq!(operations.len(), 2);
+    assert!(!operations.contains(Operations::Unknown(String::from("Explode"))));
+    assert!(operations.contains(Operations::Find));
+    assert!(!operations.contains(Operations::Insert));
+    assert_eq!(
+        operations.intersection(OperationSet::read_only()),
+        operations
+    );
+
+    let mut set = OperationSet::full();
+    assert_eq!(set.len(), Operations::ALL.len());
+    set.remove(Operations::Truncate);
+    assert!(!set.contains(Operations::Truncate));
+    assert!(OperationSet::read_only().iter().all(|v| v.is_read_only()));
+    assert!(OperationSet::empty().is_empty());
+}
+
+#[test]
+fn field_paths_walk_objects() {
+    let path = FieldPath::parse("items[1].price").unwrap();
+
+    assert_eq!(path.key().as_str(), "items");
+    assert_eq!(path.to_string(), "items[1].price");
+
+    for invalid in [
+        "", ".a", "a.", "a..b", "a[", "a[-1]", "a[x]", "a]", "a[0].b]",
+    ] {
+        assert!(FieldPath::parse(invalid).is_err(), "{invalid}");
+    }
+
+    let mut fields = HashMap::from([
+        (
+            SchematicFieldKey::from("items"),
+            SimpleValue::ArrayUnknown(vec![
+                serde_json::json!({ "price": 5 }),
+                serde_json::json!({ "price": 7.5 }),
+            ]),
+        ),
+        (
+            SchematicFieldKey::from("tags"),
+            SimpleValue::ListString(vec![String::from("a")]),
+        ),
+    ]);
+
+    assert_eq!(
+        path.get(&fields),
+        Some(SimpleValue::Number(Number::Float(7.5)))
+    );
+    assert_eq!(
+        FieldPath::parse("tags[0]").unwrap().get(&fields),
+        Some(SimpleValue::from("a"))
+    );
+    assert_eq!(
+        FieldPath::parse("items[5].price").unwrap().get(&fields),
+        None
+    );
+
+    path.set(&mut fields, SimpleValue::Number(Number::Integer(9)))
+        .unwrap();
+    assert_eq!(
+        path.get(&fields),
+        Some(SimpleValue::Number(Number::Integer(9)))
+    );
+
+    FieldPath::parse("address.city.name")
+        .unwrap()
+        .set(&mut fields, SimpleValue::from("Springfield"))
+        .unwrap();
+    assert_eq!(
+        fields["address"],
+        SimpleValue::ObjectUnknown(serde_json::json!({ "city": { "name": "Springfield" } }))
+    );
+
+    assert!(
+        FieldPath::parse("items[3]")
+            .unwrap()
+            .set(&mut fields, SimpleValue::from(1))
+            .is_err()
+    );
+    assert!(
+        FieldPath::parse("tags.a")
+            .unwrap()
+            .set(&mut fields, SimpleValue::from(1))
+            .is_err()
+    );
+    assert_eq!(
+        fields["tags"],
+        SimpleValue::ListString(vec![String::from("a")])
+    );
+}
+
+#[test]
+fn schema_versions() {
+    let v1_2 = SchemaVersion::new(1, 2);
+
+    assert_eq!(v1_2.to_string(), "1.2");
+    assert_eq!("1.2".parse::<SchemaVersion>().unwrap(), v1_2);
+    assert_eq!(
+        "3".parse::<SchemaVersion>().unwrap(),
+        SchemaVersion::new(3, 0)
+    );
+    assert!("1.x".parse::<SchemaVersion>().is_err());
+
+    // Floats are still read.
+    assert_eq!(serde_json::from_str::<SchemaVersion>("1.2").unwrap(), v1_2);
+    assert_eq!(
+        serde_json::from_str::<SchemaVersion>("2.0").unwrap(),
+        SchemaVersion::new(2, 0)
+    );
+    assert_eq!(serde_json::to_string(&v1_2).unwrap(), "1.2");
+    assert_eq!(
+        serde_json::to_string(&SchemaVersion::default()).unwrap(),
+        "1.0"
+    );
+    assert_eq!(
+        serde_json::from_str::<SchemaVersion>(r#""1.10""#)
+            .unwrap()
+            .minor,
+        10
+    );
+
+    assert!(SchemaVersion::new(1, 10) > SchemaVersion::new(1, 9));
+    assert_eq!(v1_2.bump_minor(), SchemaVersion::new(1, 3));
+    // 1.10 can't be told apart from 1.1 as a float.
+    let v1_11 = SchemaVersion::new(1, 9).bump_minor();
+    assert_eq!(v1_11, SchemaVersion::new(1, 11));
+    assert_eq!(
+        serde_json::from_str::<SchemaVersion>(&serde_json::to_string(&v1_11).unwrap()).unwrap(),
+        v1_11
+    );
+    assert_eq!(v1_2.bump_major(), SchemaVersion::new(2, 0));
+
+    assert!(SchemaVersion::new(1, 1).is_compatible_with(&v1_2));
+    assert!(!SchemaVersion::new(1, 3).is_compatible_with(&v1_2));
+    assert!(!SchemaVersion::new(0, 9).is_compatible_with(&v1_2));
+
+    let schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .primary_field("title")
+        .version(v1_2)
+        .build()
+        .unwrap();
+
+    assert!(schematic.check_client_version(None).is_ok());
+    assert!(
+        schematic
+            .check_client_version(Some(SchemaVersion::new(1, 0)))
+            .is_ok()
+    );
+    assert!(
+        schematic
+            .check_client_version(Some(SchemaVersion::new(2, 0)))
+            .is_err()
+    );
+}
+
+#[test]
+fn fields_keep_their_index_order() {
+    let mut schematic = SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .field("body", SchematicFieldType::RichText)
+        .index(5)
+        .field("author", SchematicFieldType::Text)
+        .index(2)
+        .field("slug", SchematicFieldType::Text)
+        .index(3)
+        .primary_field("title")
+        .build()
+        .unwrap();
+
+    let keys = |fields: &SchemaFieldMap| fields.keys().map(|k| k.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(keys(&schematic.fields), ["title", "author", "slug", "body"]);
+
+    // Written in order, and read back in index order whatever the order of the object.
+    let json = serde_json::to_value(&schematic.fields).unwrap();
+    let object = json.as_object().unwrap();
+    let reversed = object
+        .iter()
+        .rev()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect::<serde_json::Map<_, _>>();
+
+    let fields: SchemaFieldMap = serde_json::from_value(reversed.into()).unwrap();
+
+    assert_eq!(keys(&fields), ["title", "author", "slug", "body"]);
+    assert_eq!(fields, schematic.fields);
+
+    // After the fields which already had the index.
+    assert!(schematic.fields.update("body", |f| f.index = 0));
+    assert!(!schematic.fields.update("missing", |f| f.index = 0));
+    assert_eq!(keys(&schematic.fields), ["title", "body", "author", "slug"]);
+    assert_eq!(schematic.fields["body"].display_name, "body");
+
+    schematic.fields.remove("title");
+    assert_eq!(keys(&schematic.fields), ["body", "author", "slug"]);
+    assert!(schematic.fields.get("title").is_none());
+    assert_eq!(schematic.fields["slug"].index, 3);
+
+    schematic.fields.update("author", |f| f.is_deleted = true);
+
+    let view = SchemaViewItem {
+        hidden_fields: vec![String::from("body")],
+    };
+    let visible = schematic
+        .fields
+        .visible_for_view(&view)
+        .map(|(k, _)| k.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(visible, ["slug"]);
+}
+
+#[test]
+fn values_are_borrowed() {
+    let value = SchematicFieldValue::Tags(vec![String::from("news")]);
+
+    assert_eq!(value.as_tags(), Some(&[String::from("news")][..]));
+    assert_eq!(value.as_list_string(), None);
+    assert_eq!(value.as_text(), None);
+
+    let value = SchematicFieldValue::Text(String::from("Hello"));
+
+    assert_eq!(value.as_text(), Some("Hello"));
+    assert_eq!(value.try_as_text().unwrap(), "Hello");
+
+    let value = SimpleValue::ListNumber(vec![Number::Byte(1), Number::Integer(300)]);
+
+    assert_eq!(value.as_list_number().map(<[_]>::len), Some(2));
+    assert_eq!(value.as_text(), None);
+    assert_eq!(
+        SimpleValue::Boolean(true).as_boolean(),
+        SimpleValue::Boolean(true).try_as_boolean().ok()
+    );
+    assert_eq!(
+        SimpleValue::ObjectUnknown(serde_json::json!({ "a": 1 }))
+            .as_object()
+            .and_then(|v| v.get("a")),
+        Some(&serde_json::json!(1))
+    );
+}
+
+#[test]
+fn public_schemas_convert() {
+    let owner = WebsitePublicId::new();
+    let schematic = SchematicBuilder::new("posts", "", "Posts", owner)
+        .field("title", SchematicFieldType::Text)
+        .primary_field("title")
+        .allowed_operation(Operations::Get)
+        .ttl(Duration::from_secs(3600))
+        .default_sort("title", SortOrder::Descending)
+        .build()
+        .unwrap();
+
+    let meta = PublicSchemaMeta {
+        is_single: true,
+        created_at: datetime!(2024-05-01 12:00 UTC),
+        updated_at: datetime!(2024-05-02 12:00 UTC),
+        deleted_at: None,
+    };
+
+    let public = PublicSchema::try_from((schematic.clone(), meta)).unwrap();
+
+    assert_eq!(public.schema_id, "posts");
+    assert_eq!(public.namespace, None);
+    assert_eq!(public.ttl, Some(3600));
+    assert_eq!(public.default_sort.as_deref(), Some("title:desc"));
+    assert_eq!(public.meta(), meta);
+
+    let back = Schematic::try_from((public.clone(), owner)).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&back).unwrap(),
+        serde_json::to_value(&schematic).unwrap()
+    );
+
+    // Deleted schemas need to say when.
+    let mut deleted = schematic.clone();
+    deleted.is_deleted = true;
+
+    assert!(PublicSchema::try_from((deleted, meta)).is_err());
+
+    let mut fractional = schematic;
+    fractional.ttl = Some(Duration::from_millis(1500));
+
+    assert!(PublicSchema::try_from((fractional, meta)).is_err());
+
+    let mut negative = public;
+    negative.ttl = Some(-1);
+
+    assert!(Schematic::try_from((negative.clone(), owner)).is_err());
+
+    negative.ttl = None;
+    negative.default_sort = Some(String::from("title"));
+
+    let sort = Schematic::try_from((negative, owner))
+        .unwrap()
+        .default_sort
+        .unwrap();
+
+    assert_eq!(sort.order, SortOrder::Ascending);
+    assert!("title:sideways".parse::<DefaultSort>().is_err());
+}
@@ -0,0 +1,149 @@
+use webby_global_common::{
+    id::WebsitePublicId,
+    schema::{
+        Schematic, SchematicFieldKey, SchematicFieldType,
+        builder::SchematicBuilder,
+        diff::{MigrationOperation, SchematicDiff},
+    },
+};
+
+fn builder() -> SchematicBuilder {
+    SchematicBuilder::new("posts", "Blog", "Posts", WebsitePublicId::new())
+        .field("title", SchematicFieldType::Text)
+        .primary_field("title")
+}
+
+fn key(value: &str) -> SchematicFieldKey {
+    SchematicFieldKey::from(value)
+}
+
+fn mark_deleted(schema: &mut Schematic, name: &str) {
+    let mut field = schema.fields.get(name).unwrap().clone();
+    field.is_deleted = true;
+    schema.fields.insert(key(name), field);
+}
+
+#[test]
+fn unchanged_schemas() {
+    let schema = builder()
+        .field("body", SchematicFieldType::RichText)
+        .build()
+        .unwrap();
+
+    let diff = SchematicDiff::between(&schema, &schema);
+
+    assert!(diff.is_empty());
+    assert!(!diff.is_destructive());
+}
+
+#[test]
+fn changed_fields() {
+    let old = builder()
+        .field("author", SchematicFieldType::Reference)
+        .reference("people")
+        .field("body", SchematicFieldType::Text)
+        .field("views", SchematicFieldType::Text)
+        .build()
+        .unwrap();
+    let new = builder()
+        .field("author", SchematicFieldType::Reference)
+        .reference("members")
+        .field("body", SchematicFieldType::Text)
+        .display_name("Content")
+        .index(9)
+        .field("views", SchematicFieldType::Number)
+        .index(3)
+        .build()
+        .unwrap();
+
+    let diff = SchematicDiff::between(&old, &new);
+
+    assert_eq!(
+        diff.operations,
+        [
+            MigrationOperation::ReferenceChanged {
+                key: key("author"),
+                from: Some(String::from("people")),
+                to: Some(String::from("members")),
+            },
+            MigrationOperation::Renamed {
+                key: key("body"),
+                from: String::from("body"),
+                to: String::from("Content"),
+            },
+            MigrationOperation::IndexMoved {
+                key: key("body"),
+                from: 2,
+                to: 9,
+            },
+            MigrationOperation::TypeChanged {
+                key: key("views"),
+                from: SchematicFieldType::Text,
+                to: SchematicFieldType::Number,
+            },
+        ]
+    );
+    assert!(diff.is_destructive());
+    assert!(!diff.operations[0].is_destructive());
+    assert_eq!(diff.operations[3].key(), &key("views"));
+}
+
+#[test]
+fn added_and_removed_fields() {
+    let old = builder()
+        .field("body", SchematicFieldType::Text)
+        .field("draft", SchematicFieldType::Boolean)
+        .build()
+        .unwrap();
+    let mut new = builder()
+        .field("body", SchematicFieldType::Text)
+        .field("tags", SchematicFieldType::Tags)
+        .build()
+        .unwrap();
+
+    let diff = SchematicDiff::between(&old, &new);
+
+    assert_eq!(diff.operations.len(), 2);
+    assert_eq!(
+        diff.operations[0],
+        MigrationOperation::FieldRemoved { key: key("draft") }
+    );
+    assert!(matches!(
+        &diff.operations[1],
+        MigrationOperation::FieldAdded { key: added, field }
+            if added == &key("tags") && field.field_type == SchematicFieldType::Tags
+    ));
+    assert!(diff.is_destructive());
+
+    // Deleted fields count as removed, and as added once they're back.
+    mark_deleted(&mut new, "body");
+
+    assert_eq!(
+        SchematicDiff::between(&old, &new).operations[0],
+        MigrationOperation::FieldRemoved { key: key("body") }
+    );
+    assert!(matches!(
+        &SchematicDiff::between(&new, &old).operations[0],
+        MigrationOperation::FieldAdded { key: added, .. } if added == &key("body")
+    ));
+}
+
+#[test]
+fn serializes() {
+    let diff = SchematicDiff {
+        operations: vec![MigrationOperation::TypeChanged {
+            key: key("views"),
+            from: SchematicFieldType::Text,
+            to: SchematicFieldType::Number,
+        }],
+    };
+
+    let value = serde_json::to_value(&diff).unwrap();
+
+    assert_eq!(value["operations"][0]["type"], "TypeChanged");
+    assert_eq!(value["operations"][0]["value"]["key"], "views");
+    assert_eq!(
+        serde_json::from_value::<SchematicDiff>(value).unwrap(),
+        diff
+    );
+}
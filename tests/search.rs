@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use webby_global_common::{
+    response::CmsRowResponse,
+    richtext::{Block, Inline, RichDocument},
+    schema::{
+        SchemaFieldMap, SchematicField, SchematicFieldKey, SchematicFieldType,
+        computed::FieldSource,
+    },
+    search::{Fuzziness, SearchQuery, indexable_text, strip_html},
+    value::{Number, SimpleValue},
+};
+
+fn field(field_type: SchematicFieldType, index: u16) -> SchematicField {
+    SchematicField {
+        display_name: String::new(),
+        sortable: true,
+        is_deleted: false,
+        system_field: false,
+        field_type,
+        index,
+        referenced_schema: None,
+        default_value: None,
+        constraints: Default::default(),
+        localized: false,
+        source: FieldSource::Stored,
+    }
+}
+
+#[test]
+fn extracts_indexable_text() {
+    let document = RichDocument {
+        blocks: vec![
+            Block::Heading {
+                level: 1,
+                content: vec![Inline::Text {
+                    text: String::from("Intro"),
+                    marks: Vec::new(),
+                }],
+            },
+            Block::Paragraph(vec![Inline::Text {
+                text: String::from("Hello world"),
+                marks: Vec::new(),
+            }]),
+        ],
+    };
+
+    let mut deleted = field(SchematicFieldType::Text, 5);
+    deleted.is_deleted = true;
+
+    let fields = SchemaFieldMap::from([
+        (
+            SchematicFieldKey::from("title"),
+            field(SchematicFieldType::Text, 0),
+        ),
+        (
+            SchematicFieldKey::from("body"),
+            field(SchematicFieldType::RichText, 1),
+        ),
+        (
+            SchematicFieldKey::from("legacy"),
+            field(SchematicFieldType::RichContent, 2),
+        ),
+        (
+            SchematicFieldKey::from("tags"),
+            field(SchematicFieldType::Tags, 3),
+        ),
+        (
+            SchematicFieldKey::from("count"),
+            field(SchematicFieldType::Number, 4),
+        ),
+        (SchematicFieldKey::from("old"), deleted),
+        (
+            SchematicFieldKey::from("empty"),
+            field(SchematicFieldType::Text, 6),
+        ),
+    ]);
+
+    let row = CmsRowResponse {
+        files: Vec::new(),
+        fields: HashMap::from([
+            (
+                SchematicFieldKey::from("title"),
+                SimpleValue::Text(String::from("Post")),
+            ),
+            (
+                SchematicFieldKey::from("body"),
+                SimpleValue::Text(serde_json::to_string(&document).unwrap()),
+            ),
+            (
+                SchematicFieldKey::from("legacy"),
+                SimpleValue::Text(String::from(
+                    "<p>Tom &amp; Jerry</p><script>alert(1)</script><p>Bye<br>now</p>",
+                )),
+            ),
+            (
+                SchematicFieldKey::from("tags"),
+                SimpleValue::ListString(vec![String::from("news"), String::from("rust")]),
+            ),
+            (
+                SchematicFieldKey::from("count"),
+                SimpleValue::Number(Number::Byte(3)),
+            ),
+            (
+                SchematicFieldKey::from("old"),
+                SimpleValue::Text(String::from("hidden")),
+            ),
+            (
+                SchematicFieldKey::from("empty"),
+                SimpleValue::Text(String::from("  ")),
+            ),
+        ]),
+        meta: None,
+        seo: None,
+    };
+
+    let text = indexable_text(&row, &fields)
+        .into_iter()
+        .map(|(k, v)| (k.as_str().to_string(), v))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        text,
+        vec![
+            (String::from("title"), String::from("Post")),
+            (String::from("body"), String::from("Intro\nHello world")),
+            (
+                String::from("legacy"),
+                String::from("Tom & Jerry\nBye\nnow")
+            ),
+            (String::from("tags"), String::from("news rust")),
+        ]
+    );
+}
+
+#[test]
+fn strips_html() {
+    assert_eq!(strip_html("plain"), "plain");
+    assert_eq!(strip_html("<b>a</b> &lt;b&gt;"), "a <b>");
+    assert_eq!(strip_html("<style>p {}</style>text<unclosed"), "text");
+}
+
+#[test]
+fn query_defaults() {
+    let query: SearchQuery = serde_json::from_str(r#"{"text":"hello"}"#).unwrap();
+
+    assert_eq!(query.fuzziness, Fuzziness::Auto);
+    assert!(!query.highlight);
+    assert!(query.searches_field("title"));
+
+    assert_eq!(Fuzziness::Auto.max_edits("ab"), 0);
+    assert_eq!(Fuzziness::Auto.max_edits("hello"), 1);
+    assert_eq!(Fuzziness::Auto.max_edits("welcome"), 2);
+    assert_eq!(Fuzziness::Exact.max_edits("welcome"), 0);
+}
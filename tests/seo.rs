@@ -0,0 +1,184 @@
+use serde_json::json;
+use time::macros::datetime;
+use url::Url;
+use webby_global_common::seo::{
+    RobotsFlags, SeoMeta, TwitterCardKind,
+    robots::{RobotsGroup, RobotsTxt},
+    sitemap::{self, ChangeFrequency, MAX_URLS_PER_SITEMAP, SitemapEntry},
+};
+
+#[test]
+fn validates_seo_lengths() {
+    let mut seo = SeoMeta {
+        title: Some(String::from("Shop")),
+        description: Some(String::from("Everything we sell")),
+        json_ld: Some(json!({ "@type": "Organization", "name": "Shop" })),
+        ..Default::default()
+    };
+
+    seo.validate().unwrap();
+
+    seo.title = Some("a".repeat(71));
+    assert!(seo.validate().is_err());
+
+    seo.title = Some(String::from("  "));
+    assert!(seo.validate().is_err());
+
+    seo.title = None;
+    seo.json_ld = Some(json!("Shop"));
+    assert!(seo.validate().is_err());
+
+    seo.json_ld = Some(json!([{ "@type": "Product" }]));
+    seo.validate().unwrap();
+
+    seo.twitter.site = Some(String::from("shop"));
+    assert!(seo.validate().is_err());
+}
+
+#[test]
+fn merges_seo_with_defaults() {
+    let defaults: SeoMeta = serde_json::from_value(json!({
+        "title": "Shop",
+        "description": "Everything we sell",
+        "robots": { "noIndex": true },
+        "openGraph": { "image": "https://shop.example.com/og.png", "kind": "website" },
+        "twitter": { "card": "SummaryLargeImage", "site": "@shop" },
+        "canonicalUrl": "https://shop.example.com/",
+        "jsonLd": { "@type": "Organization", "name": "Shop" }
+    }))
+    .unwrap();
+
+    let page = SeoMeta {
+        title: Some(String::from("Cart")),
+        robots: RobotsFlags {
+            no_index: false,
+            no_follow: true,
+        },
+        ..Default::default()
+    };
+
+    let merged = page.merge_with_defaults(&defaults);
+
+    assert_eq!(merged.title.as_deref(), Some("Cart"));
+    assert_eq!(merged.description.as_deref(), Some("Everything we sell"));
+    assert_eq!(merged.open_graph.title.as_deref(), Some("Cart"));
+    assert_eq!(merged.open_graph.kind.as_deref(), Some("website"));
+    assert_eq!(merged.twitter.title.as_deref(), Some("Cart"));
+    assert_eq!(
+        merged.twitter.card,
+        Some(TwitterCardKind::SummaryLargeImage)
+    );
+    assert_eq!(merged.robots_content(), "noindex, nofollow");
+    assert_eq!(
+        merged.open_graph.image.unwrap().as_str(),
+        "https://shop.example.com/og.png"
+    );
+
+    // Every page would otherwise claim to be the home page.
+    assert_eq!(merged.canonical_url, None);
+    assert_eq!(merged.json_ld, None);
+
+    // Empty parts aren't serialized.
+    assert_eq!(
+        serde_json::to_value(&page).unwrap(),
+        json!({ "title": "Cart", "robots": { "noIndex": false, "noFollow": true } })
+    );
+
+    let own = SeoMeta {
+        canonical_url: Some(Url::parse("https://shop.example.com/cart").unwrap()),
+        json_ld: Some(json!({ "@type": "WebPage" })),
+        ..Default::default()
+    };
+    let merged = own.merge_with_defaults(&defaults);
+
+    assert_eq!(merged.canonical_url, own.canonical_url);
+    assert_eq!(merged.json_ld, own.json_ld);
+}
+
+#[test]
+fn renders_sitemaps() {
+    let base = Url::parse("https://shop.example.com").unwrap();
+
+    let entry = SitemapEntry::new(&base, "/search?q=a&b")
+        .unwrap()
+        .last_modified(datetime!(2024-05-01 12:30:15.5 UTC))
+        .change_frequency(ChangeFrequency::Weekly)
+        .priority(1.5);
+
+    let output = sitemap::render([entry], |_| unreachable!());
+
+    assert_eq!(output.sitemaps.len(), 1);
+    assert!(output.index.is_none());
+    assert_eq!(
+        output.sitemaps[0],
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://shop.example.com/search?q=a&amp;b</loc>
+    <lastmod>2024-05-01T12:30:15Z</lastmod>
+    <changefreq>weekly</changefreq>
+    <priority>1.0</priority>
+  </url>
+</urlset>
+"#
+    );
+
+    // Empty sites still get a sitemap.
+    assert_eq!(sitemap::render([], |_| unreachable!()).sitemaps.len(), 1);
+}
+
+#[test]
+fn splits_large_sitemaps() {
+    let base = Url::parse("https://shop.example.com").unwrap();
+    let entries = (0..MAX_URLS_PER_SITEMAP + 1)
+        .map(|i| SitemapEntry::new(&base, &format!("/products/{i}")).unwrap());
+
+    let output = sitemap::render(entries, |i| {
+        base.join(&format!("/sitemap-{i}.xml")).unwrap()
+    });
+
+    assert_eq!(output.sitemaps.len(), 2);
+    assert_eq!(output.sitemaps[1].matches("<url>").count(), 1);
+
+    let index = output.index.unwrap();
+    assert!(index.contains("<sitemapindex"));
+    assert!(index.contains("<loc>https://shop.example.com/sitemap-1.xml</loc>"));
+    assert_eq!(index.matches("<sitemap>").count(), 2);
+}
+
+#[test]
+fn builds_robots_txt() {
+    let robots = RobotsTxt::new()
+        .group(
+            RobotsGroup::new("*")
+                .disallow("/admin\nAllow: /")
+                .allow("/admin/public"),
+        )
+        .group(
+            RobotsGroup::new("BadBot")
+                .user_agent("OtherBot")
+                .disallow("/")
+                .crawl_delay(10),
+        )
+        .sitemap(Url::parse("https://shop.example.com/sitemap.xml").unwrap());
+
+    assert_eq!(
+        robots.to_string(),
+        "User-agent: *
+Disallow: /admin
+Allow: /admin/public
+
+User-agent: BadBot
+User-agent: OtherBot
+Disallow: /
+Crawl-delay: 10
+
+Sitemap: https://shop.example.com/sitemap.xml
+"
+    );
+
+    assert_eq!(
+        RobotsTxt::disallow_all().to_string(),
+        "User-agent: *\nDisallow: /\n"
+    );
+}
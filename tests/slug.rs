@@ -0,0 +1,118 @@
+use webby_global_common::{
+    schema::{SchematicFieldType, SchematicFieldValue},
+    slug::Slug,
+    testing::assert_roundtrip,
+    value::SimpleValue,
+};
+
+fn slug(value: &str) -> Slug {
+    Slug::parse(value).unwrap()
+}
+
+#[test]
+fn validates() {
+    for valid in ["a", "my-first-post", "2024", "a-1-b"] {
+        assert!(Slug::is_valid(valid), "{valid}");
+    }
+
+    for invalid in [
+        "",
+        "-a",
+        "a-",
+        "a--b",
+        "My-Post",
+        "a_b",
+        "café",
+        &"a".repeat(101),
+    ] {
+        assert!(Slug::parse(invalid).is_err(), "{invalid}");
+    }
+
+    assert!(serde_json::from_str::<Slug>("\"Not A Slug\"").is_err());
+    assert_roundtrip(&slug("my-first-post"));
+}
+
+#[test]
+fn generates_from_titles() {
+    let cases = [
+        ("Hello, World!", "hello-world"),
+        ("  Crème Brûlée & Co. ", "creme-brulee-co"),
+        ("Straße in Łódź", "strasse-in-lodz"),
+        ("Ærøskøbing Smørrebrød", "aeroskobing-smorrebrod"),
+        ("10% off -- today only", "10-off-today-only"),
+        ("ÀÉÎÕÜ", "aeiou"),
+    ];
+
+    for (title, expected) in cases {
+        assert_eq!(
+            Slug::from_title(title).unwrap().as_str(),
+            expected,
+            "{title}"
+        );
+    }
+
+    assert!(Slug::from_title("东京").is_err());
+    assert!(Slug::from_title("!!!").is_err());
+
+    // Cut at a word.
+    let long = "word ".repeat(40);
+    let slug = Slug::from_title(&long).unwrap();
+
+    assert!(slug.as_str().len() <= Slug::MAX_LEN);
+    assert!(slug.as_str().ends_with("word"));
+
+    let single = "a".repeat(150);
+    assert_eq!(
+        Slug::from_title(&single).unwrap().as_str().len(),
+        Slug::MAX_LEN
+    );
+}
+
+#[test]
+fn unique_suffixes() {
+    let taken = ["post", "post-2", "post-3"].map(slug);
+
+    assert_eq!(
+        slug("post").make_unique(|v| taken.contains(v)),
+        slug("post-4")
+    );
+    assert_eq!(
+        slug("other").make_unique(|v| taken.contains(v)),
+        slug("other")
+    );
+    assert_eq!(slug("release-2024").with_suffix(2), slug("release-2024-2"));
+
+    let long = Slug::parse(&"a".repeat(Slug::MAX_LEN)).unwrap();
+    let suffixed = long.with_suffix(12);
+
+    assert_eq!(suffixed.as_str().len(), Slug::MAX_LEN);
+    assert!(suffixed.as_str().ends_with("a-12"));
+}
+
+#[test]
+fn slug_fields() {
+    let text = |v: &str| SimpleValue::Text(v.to_string());
+
+    assert_eq!(
+        SchematicFieldType::Slug
+            .parse_value(text("my-post"))
+            .unwrap(),
+        SchematicFieldValue::Slug(slug("my-post"))
+    );
+    assert!(
+        SchematicFieldType::Slug
+            .parse_value(text("My Post"))
+            .is_err()
+    );
+
+    // Imports get slugified.
+    assert_eq!(
+        text("My Post").coerce_to(SchematicFieldType::Slug).unwrap(),
+        SchematicFieldValue::Slug(slug("my-post"))
+    );
+
+    assert_eq!(
+        SimpleValue::from(SchematicFieldValue::Slug(slug("my-post"))),
+        text("my-post")
+    );
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "sqlx")]
+
+use sqlx::{
+    Encode, Postgres, Type, TypeInfo,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo},
+};
+use time::OffsetDateTime;
+use webby_global_common::{
+    id::WebsitePublicId,
+    schema::SchematicFieldType,
+    value::{Number, SimpleValue},
+};
+
+fn encode<'q, T: Encode<'q, Postgres>>(value: T) -> (Vec<u8>, Option<String>) {
+    let mut buf = PgArgumentBuffer::default();
+    let produces = value.produces().map(|v| v.name().to_string());
+
+    let _ = value.encode_by_ref(&mut buf);
+
+    (buf.to_vec(), produces)
+}
+
+fn type_name<T: Type<Postgres>>() -> String {
+    T::type_info().name().to_string()
+}
+
+#[test]
+fn numbers_keep_their_kind() {
+    assert_eq!(
+        encode(Number::Integer(5)),
+        (5i64.to_be_bytes().to_vec(), Some(String::from("INT8")))
+    );
+    assert_eq!(
+        encode(Number::Byte(5)),
+        (5i64.to_be_bytes().to_vec(), Some(String::from("INT8")))
+    );
+    assert_eq!(
+        encode(Number::Float(1.5)),
+        (1.5f64.to_be_bytes().to_vec(), Some(String::from("FLOAT8")))
+    );
+
+    for ty in ["INT2", "INT4", "INT8", "FLOAT4", "FLOAT8"] {
+        assert!(Number::compatible(&PgTypeInfo::with_name(ty)));
+    }
+
+    assert!(!Number::compatible(&PgTypeInfo::with_name("TEXT")));
+}
+
+#[test]
+fn values_are_jsonb() {
+    let (bytes, _) = encode(SimpleValue::ListString(vec![String::from("a")]));
+
+    // The JSONB version, then the JSON.
+    assert_eq!(bytes, b"\x01[\"a\"]");
+    assert_eq!(type_name::<SimpleValue>(), "JSONB");
+    assert_eq!(SimpleValue::array_type_info().name(), "JSONB[]");
+    assert!(SimpleValue::compatible(&PgTypeInfo::with_name("JSON")));
+}
+
+#[test]
+fn column_types() {
+    assert_eq!(type_name::<SchematicFieldType>(), "INT4");
+    assert_eq!(SchematicFieldType::array_type_info().name(), "INT4[]");
+    assert_eq!(type_name::<WebsitePublicId>(), "UUID");
+    assert_eq!(type_name::<OffsetDateTime>(), "TIMESTAMPTZ");
+
+    let (bytes, _) = encode(SchematicFieldType::Location);
+
+    assert_eq!(bytes, i32::from(SchematicFieldType::Location).to_be_bytes());
+}
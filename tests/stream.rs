@@ -0,0 +1,118 @@
+use std::io::Cursor as IoCursor;
+
+use webby_global_common::response::{
+    Cursor, ListResponse,
+    stream::{NdjsonReader, NdjsonWriter, StreamRecord, StreamSummary, StreamedList},
+};
+
+#[test]
+fn writes_and_reads_streams() {
+    let next = Cursor::encode(&("title", 3)).unwrap();
+
+    let bytes = StreamedList::new(["a", "b", "c"].into_iter(), 10)
+        .next_cursor(Some(next.clone()))
+        .write_ndjson(Vec::new())
+        .unwrap();
+    let text = String::from_utf8(bytes).unwrap();
+
+    assert_eq!(
+        text.lines().collect::<Vec<_>>(),
+        [
+            r#"{"type":"Item","value":"a"}"#,
+            r#"{"type":"Item","value":"b"}"#,
+            r#"{"type":"Item","value":"c"}"#,
+            &format!(
+                r#"{{"type":"Summary","value":{{"total":10,"count":3,"nextCursor":"{next}"}}}}"#
+            ),
+        ]
+    );
+
+    let mut reader = NdjsonReader::<_, String>::new(IoCursor::new(&text));
+
+    assert_eq!(reader.next().unwrap().unwrap(), "a");
+    assert!(reader.summary().is_none());
+    assert_eq!(reader.by_ref().count(), 2);
+    assert_eq!(
+        reader.summary(),
+        Some(&StreamSummary {
+            total: 10,
+            count: 3,
+            next_cursor: Some(next.clone()),
+        })
+    );
+
+    let list = NdjsonReader::<_, String>::new(IoCursor::new(&text))
+        .into_list()
+        .unwrap();
+
+    assert_eq!(list.items, ["a", "b", "c"]);
+    assert_eq!(list.total, 10);
+    assert_eq!(list.next_cursor, Some(next));
+
+    // A list response streams the same way.
+    let bytes = StreamedList::from(ListResponse::all(vec![1, 2]))
+        .write_ndjson(Vec::new())
+        .unwrap();
+
+    assert_eq!(
+        NdjsonReader::<_, i32>::new(bytes.as_slice())
+            .into_list()
+            .unwrap(),
+        ListResponse::all(vec![1, 2])
+    );
+}
+
+#[test]
+fn writes_items_as_they_load() {
+    let mut writer = NdjsonWriter::new(Vec::new());
+
+    for i in 0..1000 {
+        writer.write_item(&i).unwrap();
+    }
+
+    assert_eq!(writer.count(), 1000);
+
+    let bytes = writer.finish(1000, None).unwrap();
+    let sum = NdjsonReader::<_, u64>::new(bytes.as_slice())
+        .map(|v| v.unwrap())
+        .sum::<u64>();
+
+    assert_eq!(sum, 499_500);
+}
+
+#[test]
+fn detects_broken_streams() {
+    let line = |record: &StreamRecord<u8>| serde_json::to_string(record).unwrap() + "\n";
+    let summary = |count| {
+        line(&StreamRecord::Summary(StreamSummary {
+            total: 2,
+            count,
+            next_cursor: None,
+        }))
+    };
+
+    let item = line(&StreamRecord::Item(1));
+    let read = |text: String| {
+        NdjsonReader::<_, u8>::new(IoCursor::new(text))
+            .collect::<Result<Vec<_>, _>>()
+            .is_ok()
+    };
+
+    assert!(read(format!("{item}\n{item}{}", summary(2))));
+    // Cut off.
+    assert!(!read(format!("{item}{item}")));
+    assert!(!read(String::new()));
+    // Missing items.
+    assert!(!read(format!("{item}{}", summary(2))));
+    // Something after the summary.
+    assert!(!read(format!("{item}{item}{}{item}", summary(2))));
+    assert!(!read(format!("{item}nope\n{}", summary(2))));
+
+    // Iteration stops at the first error.
+    let mut reader = NdjsonReader::<_, u8>::new(IoCursor::new(format!("{item}{item}")));
+
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
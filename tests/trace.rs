@@ -0,0 +1,70 @@
+use uuid::Uuid;
+use webby_global_common::{
+    id::WebsitePublicId,
+    testing::assert_roundtrip,
+    trace::{SpanId, TraceContext},
+};
+
+const HEADER: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn parses_traceparent() {
+    let ctx = TraceContext::from_traceparent(HEADER).unwrap();
+
+    assert_eq!(
+        ctx.request_id,
+        Uuid::parse_str("4bf92f35-77b3-4da6-a3ce-929d0e0e4736").unwrap()
+    );
+    assert_eq!(ctx.parent_span.unwrap().to_string(), "00f067aa0ba902b7");
+    assert!(ctx.sampled);
+
+    let span: SpanId = "00f067aa0ba902b7".parse().unwrap();
+    assert_eq!(ctx.traceparent(span), HEADER);
+
+    // Later versions can add parts.
+    assert!(
+        TraceContext::from_traceparent(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra"
+        )
+        .is_ok()
+    );
+
+    for invalid in [
+        "",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+    ] {
+        assert!(
+            TraceContext::from_traceparent(invalid).is_err(),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn propagates_trace_context() {
+    let site = WebsitePublicId::new();
+    let ctx = TraceContext::new().with_site(site);
+    let span = SpanId::new();
+
+    assert_ne!(span, SpanId::new());
+
+    let child = ctx.child(span);
+    assert_eq!(child.request_id, ctx.request_id);
+    assert_eq!(child.parent_span, Some(span));
+    assert_eq!(child.site_id, Some(site));
+
+    let received = TraceContext::from_traceparent(&ctx.traceparent(span)).unwrap();
+    assert_eq!(received.request_id, ctx.request_id);
+    assert_eq!(received.parent_span, Some(span));
+    assert!(!received.sampled);
+
+    let value = serde_json::to_value(assert_roundtrip(&child)).unwrap();
+    assert_eq!(value["parentSpan"], span.to_string());
+    assert!(value.get("userId").is_none());
+}
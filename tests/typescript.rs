@@ -0,0 +1,47 @@
+#![cfg(feature = "ts")]
+
+use webby_global_common::typescript::bundle;
+
+#[test]
+fn bundle_declares_used_types() {
+    let bundle = bundle();
+
+    for declaration in [
+        "export type CmsQuery = {",
+        "export type Filter = { name: string, cond: FilterConditionType, value: FilterValue, ",
+        "isPath?: boolean, };",
+        "export type FilterExpr = Filter | { and: Array<FilterExpr> }",
+        "export type ListResponse<T> = { items: Array<T>, offset: number,",
+        "export type SchematicField = {",
+        "export type SchemaDataPublicId = string;",
+        "export type JsonValue = ",
+    ] {
+        assert!(bundle.contains(declaration), "missing {declaration}");
+    }
+
+    assert_eq!(bundle.matches("export type CmsRowResponse =").count(), 1);
+}
+
+#[test]
+fn wire_representations() {
+    let bundle = bundle();
+
+    assert!(!bundle.contains("bigint"));
+    assert!(bundle.contains("export type Number = number;"));
+    assert!(bundle.contains("createdAt: string, updatedAt: string,"));
+    assert!(bundle.contains(r#"{ "type": "Duration", "value": string }"#));
+}
+
+#[test]
+fn declarations_are_sorted() {
+    let names = bundle()
+        .lines()
+        .filter_map(|v| v.strip_prefix("export type "))
+        .map(|v| v.split([' ', '<']).next().unwrap().to_owned())
+        .collect::<Vec<_>>();
+
+    let mut sorted = names.clone();
+    sorted.sort();
+
+    assert_eq!(names, sorted);
+}
@@ -0,0 +1,97 @@
+use time::macros::{datetime, offset};
+use webby_global_common::tz::{self, DstRule, ZoneContainer};
+
+#[test]
+fn offsets_follow_daylight_saving_time() {
+    let at = |id: &str, at| tz::find_offset_at(id, at).unwrap();
+
+    assert_eq!(
+        at("America/New_York", datetime!(2024-01-15 12:00 UTC)),
+        offset!(-5)
+    );
+    assert_eq!(
+        at("US/Eastern", datetime!(2024-07-15 12:00 UTC)),
+        offset!(-4)
+    );
+    // 2024-03-10 02:00 EST
+    assert_eq!(
+        at("America/New_York", datetime!(2024-03-10 06:59 UTC)),
+        offset!(-5)
+    );
+    assert_eq!(
+        at("America/New_York", datetime!(2024-03-10 07:00 UTC)),
+        offset!(-4)
+    );
+    // 2024-11-03 02:00 EDT
+    assert_eq!(
+        at("America/New_York", datetime!(2024-11-03 05:59 UTC)),
+        offset!(-4)
+    );
+    assert_eq!(
+        at("America/New_York", datetime!(2024-11-03 06:00 UTC)),
+        offset!(-5)
+    );
+
+    // 2024-03-31 01:00 UTC
+    assert_eq!(
+        at("Europe/Berlin", datetime!(2024-03-31 00:59 UTC)),
+        offset!(+1)
+    );
+    assert_eq!(
+        at("Europe/Berlin", datetime!(2024-03-31 01:00 UTC)),
+        offset!(+2)
+    );
+    assert_eq!(
+        at("Europe/London", datetime!(2024-10-27 01:00 UTC)),
+        offset!(+0)
+    );
+
+    assert_eq!(
+        at("Australia/Sydney", datetime!(2024-01-15 12:00 UTC)),
+        offset!(+11)
+    );
+    assert_eq!(
+        at("Australia/Sydney", datetime!(2024-07-15 12:00 UTC)),
+        offset!(+10)
+    );
+    assert_eq!(
+        at("Pacific/Auckland", datetime!(2024-12-25 00:00 UTC)),
+        offset!(+13)
+    );
+
+    assert_eq!(
+        at("Asia/Tokyo", datetime!(2024-07-15 12:00 UTC)),
+        offset!(+9)
+    );
+    assert_eq!(
+        at("America/Phoenix", datetime!(2024-07-15 12:00 UTC)),
+        offset!(-7)
+    );
+
+    assert!(tz::find_offset_at("Mars/Olympus_Mons", datetime!(2024-01-01 00:00 UTC)).is_none());
+}
+
+#[test]
+fn lists_zones() {
+    let zones = tz::list_zones();
+
+    assert!(zones.windows(2).all(|w| w[0].id() < w[1].id()));
+
+    let new_york = zones.iter().find(|z| z.id() == "America/New_York").unwrap();
+
+    assert_eq!(new_york.location().unwrap().country_code, "US");
+    assert_eq!(new_york.dst_rule(), Some(DstRule::NorthAmerica));
+    assert_eq!(new_york.standard_offset(), offset!(-5));
+}
+
+#[test]
+fn loads_zones_from_path() {
+    let zones = ZoneContainer::from_path(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("TimeZones-2024b.json"),
+    )
+    .unwrap();
+
+    assert_eq!(zones.iana_version(), "2024b");
+    assert_eq!(zones.find("Eire").unwrap().id(), "Europe/Dublin");
+    assert!(zones.find("Mars/Olympus_Mons").is_none());
+}
@@ -0,0 +1,244 @@
+use time::OffsetDateTime;
+
+use webby_global_common::{
+    schema::SchematicFieldType,
+    upload::{
+        ImageTransform, WebsiteUpload, WebsiteUploadVariant,
+        mime::{MediaKind, MimeType},
+    },
+};
+
+fn variant(file_type: &str, width: i32, height: i32, size: i64) -> WebsiteUploadVariant {
+    WebsiteUploadVariant {
+        file_type: file_type.to_string(),
+        size,
+        width,
+        height,
+        ratio: width as f32 / height as f32,
+    }
+}
+
+#[test]
+fn selects_variants() {
+    let upload = WebsiteUpload {
+        public_id: String::from("cat"),
+        namespace: None,
+        upload_type: String::from("image"),
+        display_name: String::from("Cat"),
+        created_at: OffsetDateTime::UNIX_EPOCH,
+        deleted_at: None,
+        media: None,
+        using_variant: None,
+        variants: vec![
+            variant("image/jpeg", 640, 480, 60_000),
+            variant("image/jpeg", 1280, 960, 200_000),
+            variant("image/webp", 640, 480, 40_000),
+            variant("image/webp", 1280, 960, 120_000),
+            variant("image/webp", 1280, 960, 150_000),
+            variant("image/webp", 2560, 1920, 400_000),
+        ],
+    };
+
+    let best = upload
+        .best_variant_for(800, 600, &["image/avif", "image/webp"])
+        .unwrap();
+    assert_eq!(
+        (best.file_type.as_str(), best.width, best.size),
+        ("image/webp", 1280, 120_000)
+    );
+
+    let best = upload.best_variant_for(4000, 0, &["image/jpeg"]).unwrap();
+    assert_eq!((best.file_type.as_str(), best.width), ("image/jpeg", 1280));
+
+    let best = upload.best_variant_for(0, 0, &["image/avif"]).unwrap();
+    assert_eq!(best.size, 40_000);
+
+    assert_eq!(
+        upload.srcset("image/webp", |v| format!("/cat-{}.webp", v.width)),
+        "/cat-640.webp 640w, /cat-1280.webp 1280w, /cat-2560.webp 2560w"
+    );
+}
+
+#[test]
+fn validates_upload_types() {
+    let png = b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR";
+
+    assert_eq!(MimeType::parse("Image/JPG; q=1"), MimeType::Jpeg);
+    assert_eq!(MimeType::from_extension(".JPEG"), Some(MimeType::Jpeg));
+    assert_eq!(MimeType::sniff(png), Some(MimeType::Png));
+    assert_eq!(MimeType::Png.kind(), Some(MediaKind::Image));
+    assert_eq!(
+        serde_json::to_string(&MimeType::parse("application/x-custom")).unwrap(),
+        "\"application/x-custom\""
+    );
+
+    assert!(
+        SchematicFieldType::Image
+            .allowed_extensions()
+            .contains(&"webp")
+    );
+    assert!(
+        !SchematicFieldType::Image
+            .allowed_extensions()
+            .contains(&"pdf")
+    );
+    assert!(SchematicFieldType::Document.is_upload_file_type());
+    assert!(!SchematicFieldType::Text.is_upload_file_type());
+
+    let image = SchematicFieldType::Image;
+    assert_eq!(
+        image.validate_upload("image/png", None, png).unwrap(),
+        MimeType::Png
+    );
+    assert_eq!(
+        image
+            .validate_upload("application/octet-stream", Some("cat.png"), png)
+            .unwrap(),
+        MimeType::Png
+    );
+    assert!(image.validate_upload("image/jpeg", None, png).is_err());
+    assert!(
+        image
+            .validate_upload("application/pdf", None, b"%PDF-1.7")
+            .is_err()
+    );
+
+    let docx = SchematicFieldType::Document.validate_upload(
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("cv.docx"),
+        b"PK\x03\x04",
+    );
+    assert_eq!(docx.unwrap(), MimeType::Docx);
+
+    let csv = SchematicFieldType::Document.validate_upload("text/csv", None, b"a,b\n1,2");
+    assert_eq!(csv.unwrap(), MimeType::Csv);
+}
+
+#[test]
+fn rejects_undetectable_media() {
+    for field in [
+        SchematicFieldType::Image,
+        SchematicFieldType::Video,
+        SchematicFieldType::Audio,
+    ] {
+        for declared in ["image/x-foo", "image/png", "video/mp4", "audio/mpeg"] {
+            assert!(
+                field
+                    .validate_upload(declared, None, b"<html><script></script>")
+                    .is_err(),
+                "{declared}"
+            );
+        }
+    }
+
+    assert!(
+        SchematicFieldType::Image
+            .validate_upload("application/octet-stream", Some("cat.png"), b"\0\0\0")
+            .is_err()
+    );
+}
+
+#[test]
+fn rejects_svg() {
+    let svg = br#"<?xml version="1.0"?><svg><script>alert(1)</script></svg>"#;
+
+    assert_eq!(MimeType::sniff(svg), Some(MimeType::Svg));
+    assert_eq!(MimeType::Svg.kind(), None);
+    assert!(
+        !SchematicFieldType::Image
+            .allowed_extensions()
+            .contains(&"svg")
+    );
+
+    for declared in ["image/svg+xml", "image/png", "application/octet-stream"] {
+        for field in [SchematicFieldType::Image, SchematicFieldType::Document] {
+            assert!(
+                field.validate_upload(declared, None, svg).is_err(),
+                "{declared}"
+            );
+        }
+    }
+}
+
+#[test]
+fn image_transforms_are_canonical() {
+    let transform: ImageTransform = "?w=640&rot=-90&fmt=WEBP&h=480&fit=cover&crop=10,20,300,200"
+        .parse()
+        .unwrap();
+
+    assert_eq!(transform.rotation, 270);
+    assert_eq!(transform.format, Some(MimeType::Webp));
+    assert_eq!(
+        transform.to_query_string(),
+        "crop=10,20,300,200&fit=cover&fmt=webp&h=480&rot=270&w=640"
+    );
+    assert_eq!(
+        transform.to_string().parse::<ImageTransform>().unwrap(),
+        transform
+    );
+
+    assert!(ImageTransform::parse_query("").unwrap().is_identity());
+
+    for invalid in [
+        "w=0",
+        "w=1&w=2",
+        "q=101",
+        "fmt=pdf",
+        "rot=45",
+        "crop=1,2,3",
+        "blur=5",
+    ] {
+        assert!(invalid.parse::<ImageTransform>().is_err(), "{invalid}");
+    }
+
+    // Aliases give the same string.
+    for (alias, canonical) in [
+        ("rot=360", ""),
+        ("rot=-270", "rot=90"),
+        ("fmt=jpeg", "fmt=jpg"),
+        ("fmt=JFIF", "fmt=jpg"),
+    ] {
+        assert_eq!(
+            alias.parse::<ImageTransform>().unwrap().to_query_string(),
+            canonical
+        );
+    }
+
+    let other = ImageTransform {
+        format: Some(MimeType::parse("image/x-foo")),
+        rotation: 360,
+        ..Default::default()
+    };
+    assert_eq!(other.to_query_string(), "");
+    assert!(other.validate().is_err());
+}
+
+#[test]
+fn image_transforms_are_validated_when_deserialized() {
+    let transform: ImageTransform =
+        serde_json::from_str(r#"{ "width": 640, "format": "image/webp", "rotation": 90 }"#)
+            .unwrap();
+
+    assert_eq!(transform.to_query_string(), "fmt=webp&rot=90&w=640");
+    assert_eq!(
+        serde_json::from_value::<ImageTransform>(serde_json::to_value(&transform).unwrap())
+            .unwrap(),
+        transform
+    );
+
+    for invalid in [
+        r#"{ "width": 0 }"#,
+        r#"{ "quality": 0 }"#,
+        r#"{ "quality": 101 }"#,
+        r#"{ "rotation": 45 }"#,
+        r#"{ "rotation": 360 }"#,
+        r#"{ "format": "application/pdf" }"#,
+        r#"{ "format": "image/x-foo" }"#,
+        r#"{ "crop": { "x": 0, "y": 0, "width": 0, "height": 5 } }"#,
+    ] {
+        assert!(
+            serde_json::from_str::<ImageTransform>(invalid).is_err(),
+            "{invalid}"
+        );
+    }
+}
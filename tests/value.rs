@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::macros::{date, datetime, time};
+
+use webby_global_common::value::{Number, SimpleValue, detect_temporal};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Detected {
+    #[serde(with = "detect_temporal")]
+    value: SimpleValue,
+}
+
+fn plain(value: serde_json::Value) -> SimpleValue {
+    serde_json::from_value(value).unwrap()
+}
+
+fn detected(value: serde_json::Value) -> SimpleValue {
+    serde_json::from_value::<Detected>(json!({ "value": value }))
+        .unwrap()
+        .value
+}
+
+#[test]
+fn json_numbers() {
+    let cases = [
+        ("0", Number::Integer(0)),
+        ("255", Number::Integer(255)),
+        ("256", Number::Integer(256)),
+        ("-1", Number::Integer(-1)),
+        ("9223372036854775807", Number::Integer(i64::MAX)),
+        ("-9223372036854775808", Number::Integer(i64::MIN)),
+        ("9223372036854775808", Number::UInteger(i64::MAX as u64 + 1)),
+        ("18446744073709551615", Number::UInteger(u64::MAX)),
+        ("1.0", Number::Float(1.0)),
+        ("-0.5", Number::Float(-0.5)),
+        ("1e3", Number::Float(1000.0)),
+    ];
+
+    for (json, number) in cases {
+        let value: SimpleValue = serde_json::from_str(json).unwrap();
+
+        assert_eq!(value, SimpleValue::Number(number), "{json}");
+        assert_eq!(
+            serde_json::from_str::<Number>(json).unwrap(),
+            number,
+            "{json}"
+        );
+        assert_eq!(
+            serde_json::to_string(&number).unwrap(),
+            json.replace("1e3", "1000.0")
+        );
+        assert_eq!(
+            SimpleValue::from_json(serde_json::from_str(json).unwrap()).unwrap(),
+            value,
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn small_integers_are_integers() {
+    let value: SimpleValue = serde_json::from_str("5").unwrap();
+
+    assert_eq!(value, SimpleValue::Number(Number::Integer(5)));
+    assert_eq!(serde_json::to_string(&value).unwrap(), "5");
+    assert_eq!(serde_json::from_str::<SimpleValue>("5").unwrap(), value);
+    // A Byte is still written as a number, but never read back as one.
+    assert_eq!(
+        serde_json::from_value::<SimpleValue>(
+            serde_json::to_value(SimpleValue::Number(Number::Byte(5))).unwrap()
+        )
+        .unwrap(),
+        value
+    );
+    assert_eq!(
+        plain(json!([5, 255])),
+        SimpleValue::ListNumber(vec![Number::Integer(5), Number::Integer(255)])
+    );
+}
+
+#[test]
+fn null_cells() {
+    let row: HashMap<String, SimpleValue> =
+        serde_json::from_value(json!({ "title": "Hello", "notes": null })).unwrap();
+
+    assert_eq!(row["title"], SimpleValue::from("Hello"));
+    assert_eq!(
+        row["notes"],
+        SimpleValue::ObjectUnknown(serde_json::Value::Null)
+    );
+    assert_eq!(serde_json::to_value(&row["notes"]).unwrap(), json!(null));
+}
+
+#[test]
+fn temporal_strings_are_text_by_default() {
+    for text in [
+        "2024-01-02T03:04:05Z",
+        "2024-01-02T03:04:05.123+02:00",
+        "2024-01-02",
+        "03:04:05",
+        "03:04:05.25",
+    ] {
+        assert_eq!(plain(json!(text)), SimpleValue::Text(String::from(text)));
+    }
+}
+
+#[test]
+fn detect_rfc3339() {
+    assert_eq!(
+        detected(json!("2024-01-02T03:04:05Z")),
+        SimpleValue::DateTime(datetime!(2024-01-02 03:04:05 UTC))
+    );
+    assert_eq!(
+        detected(json!("2024-01-02T03:04:05.123+02:00")),
+        SimpleValue::DateTime(datetime!(2024-01-02 03:04:05.123 +02:00))
+    );
+    assert_eq!(
+        detected(json!("2024-01-02t03:04:05z")),
+        SimpleValue::DateTime(datetime!(2024-01-02 03:04:05 UTC))
+    );
+}
+
+#[test]
+fn detect_dates_and_times() {
+    assert_eq!(
+        detected(json!("2024-01-02")),
+        SimpleValue::Date(date!(2024 - 01 - 02))
+    );
+    assert_eq!(
+        detected(json!("03:04:05")),
+        SimpleValue::Time(time!(03:04:05))
+    );
+    assert_eq!(
+        detected(json!("03:04:05.25")),
+        SimpleValue::Time(time!(03:04:05.25))
+    );
+}
+
+#[test]
+fn detect_leaves_other_strings() {
+    for text in [
+        "",
+        "Hello",
+        "2024",
+        "2024-13-01",
+        "2024-01-02 03:04:05",
+        "2024-01-02T03:04:05",
+        "24:00:00",
+        "1:02:03",
+        " 2024-01-02",
+    ] {
+        assert_eq!(
+            detected(json!(text)),
+            SimpleValue::Text(String::from(text)),
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn detect_matches_from_json() {
+    for value in [
+        json!("2024-01-02T03:04:05Z"),
+        json!("2024-01-02"),
+        json!("03:04:05.5"),
+        json!("Hello"),
+        json!(12),
+        json!(-12),
+        json!(1.5),
+        json!(true),
+        json!(["2024-01-02", "a"]),
+        json!([1, 2.5]),
+        json!([1, "a"]),
+        json!([]),
+        json!({ "a": "2024-01-02" }),
+    ] {
+        assert_eq!(
+            detected(value.clone()),
+            SimpleValue::from_json(value.clone()).unwrap(),
+            "{value}"
+        );
+    }
+}
+
+#[test]
+fn detect_round_trip() {
+    for value in [
+        SimpleValue::DateTime(datetime!(2024-01-02 03:04:05.5 +02:00)),
+        SimpleValue::Date(date!(2024 - 01 - 02)),
+        SimpleValue::Time(time!(03:04:05)),
+        SimpleValue::Text(String::from("Hello")),
+        SimpleValue::Number(Number::Integer(-3)),
+    ] {
+        let json = serde_json::to_string(&Detected {
+            value: value.clone(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Detected>(&json).unwrap().value,
+            value,
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn other_kinds() {
+    assert_eq!(plain(json!(false)), SimpleValue::Boolean(false));
+    assert_eq!(
+        plain(json!(["a", "2024-01-02"])),
+        SimpleValue::ListString(vec![String::from("a"), String::from("2024-01-02")])
+    );
+    assert_eq!(plain(json!([])), SimpleValue::ListString(Vec::new()));
+    assert_eq!(
+        plain(json!([1, 300, -1, 1.5])),
+        SimpleValue::ListNumber(vec![
+            Number::Integer(1),
+            Number::Integer(300),
+            Number::Integer(-1),
+            Number::Float(1.5),
+        ])
+    );
+    assert_eq!(
+        plain(json!([1, "a", null])),
+        SimpleValue::ArrayUnknown(vec![json!(1), json!("a"), json!(null)])
+    );
+    assert_eq!(
+        plain(json!({ "a": [1, { "b": null }] })),
+        SimpleValue::ObjectUnknown(json!({ "a": [1, { "b": null }] }))
+    );
+
+    assert_eq!(
+        plain(json!(null)),
+        SimpleValue::ObjectUnknown(serde_json::Value::Null)
+    );
+    assert_eq!(
+        detected(json!(null)),
+        SimpleValue::ObjectUnknown(serde_json::Value::Null)
+    );
+}
+
+#[test]
+fn unsigned_integers() {
+    let max = Number::UInteger(u64::MAX);
+
+    assert_eq!(max.to_string(), "18446744073709551615");
+    assert_eq!(max.type_name(), "Unsigned Integer");
+    assert_eq!(max.into_u64().unwrap(), u64::MAX);
+    assert_eq!(Number::Integer(7).into_u64().unwrap(), 7);
+    assert!(Number::Integer(-1).into_u64().is_err());
+    assert!(Number::Float(1.0).into_u64().is_err());
+    assert_eq!(max.convert_i64(), i64::MAX);
+    assert_eq!(max.convert_f64(), u64::MAX as f64);
+    assert_eq!(Number::from(u64::MAX), max);
+
+    assert!(max.total_cmp(&Number::Integer(i64::MAX)).is_gt());
+    assert!(max.total_cmp(&Number::Float(f64::MAX)).is_lt());
+    assert!(Number::UInteger(5).value_eq(&Number::Byte(5)));
+
+    assert_eq!(
+        Number::Integer(i64::MAX).checked_add(Number::UInteger(1)),
+        Some(Number::UInteger(i64::MAX as u64 + 1))
+    );
+    assert_eq!(Number::Integer(i64::MAX).checked_add(Number::Byte(1)), None);
+    assert_eq!(
+        max.checked_sub(Number::UInteger(u64::MAX - 3)),
+        Some(Number::Integer(3))
+    );
+    assert_eq!(max.checked_add(Number::Byte(1)), None);
+    assert_eq!(
+        max.checked_div(Number::Byte(1)),
+        Some(Number::UInteger(u64::MAX))
+    );
+
+    assert_eq!(
+        serde_json::Value::from(max),
+        serde_json::from_str::<serde_json::Value>("18446744073709551615").unwrap()
+    );
+    assert_eq!(
+        SimpleValue::from_json(json!(u64::MAX)).unwrap(),
+        SimpleValue::Number(max)
+    );
+    assert_eq!(
+        plain(json!([1, u64::MAX])),
+        SimpleValue::ListNumber(vec![Number::Integer(1), max])
+    );
+}
+
+#[test]
+fn checked_arithmetic() {
+    let byte = Number::Byte(200);
+
+    assert_eq!(
+        byte.checked_add(Number::Byte(100)),
+        Some(Number::Integer(300))
+    );
+    assert_eq!(
+        Number::Byte(1).checked_sub(byte),
+        Some(Number::Integer(-199))
+    );
+    assert_eq!(
+        byte.checked_mul(Number::Integer(-2)),
+        Some(Number::Integer(-400))
+    );
+    assert_eq!(Number::Integer(i64::MIN).checked_sub(Number::Byte(1)), None);
+    assert_eq!(Number::Integer(i64::MAX).checked_mul(Number::Byte(2)), None);
+
+    assert_eq!(
+        byte.checked_add(Number::Float(0.5)),
+        Some(Number::Float(200.5))
+    );
+    assert_eq!(Number::Float(f64::MAX).checked_mul(Number::Byte(2)), None);
+    assert_eq!(Number::Float(f64::NAN).checked_add(Number::Byte(1)), None);
+
+    assert_eq!(byte.checked_div(Number::Byte(8)), Some(Number::Integer(25)));
+    assert_eq!(
+        Number::Byte(7).checked_div(Number::Byte(2)),
+        Some(Number::Float(3.5))
+    );
+    assert_eq!(Number::Byte(7).checked_div(Number::Byte(0)), None);
+    assert_eq!(Number::Float(1.0).checked_div(Number::Float(0.0)), None);
+    assert_eq!(
+        Number::Integer(i64::MIN).checked_div(Number::Integer(-1)),
+        None
+    );
+}
+
+#[test]
+fn compares_across_variants() {
+    let mut numbers = vec![
+        Number::Float(2.5),
+        Number::Byte(200),
+        Number::Integer(-3),
+        Number::UInteger(7),
+        Number::Float(f64::NEG_INFINITY),
+    ];
+
+    numbers.sort_by(Number::total_cmp);
+
+    assert_eq!(
+        numbers,
+        [
+            Number::Float(f64::NEG_INFINITY),
+            Number::Integer(-3),
+            Number::Float(2.5),
+            Number::UInteger(7),
+            Number::Byte(200),
+        ]
+    );
+    // The derived ordering goes by variant first.
+    assert!(Number::Byte(200) < Number::Integer(1));
+    assert!(Number::Byte(200).total_cmp(&Number::Integer(1)).is_gt());
+    assert!(Number::Byte(1).value_eq(&Number::Float(1.0)));
+    assert!(!Number::Byte(1).value_eq(&Number::Float(1.5)));
+    // Integers beyond f64's precision are compared exactly.
+    assert!(
+        Number::Integer(i64::MAX)
+            .total_cmp(&Number::Integer(i64::MAX - 1))
+            .is_gt()
+    );
+}
+
+#[test]
+fn converts_json_numbers() {
+    let cases = [
+        (serde_json::Number::from(3u8), Number::Integer(3)),
+        (serde_json::Number::from(-3), Number::Integer(-3)),
+        (
+            serde_json::Number::from(u64::MAX),
+            Number::UInteger(u64::MAX),
+        ),
+        (
+            serde_json::Number::from_f64(0.25).unwrap(),
+            Number::Float(0.25),
+        ),
+    ];
+
+    for (json, number) in cases {
+        assert_eq!(Number::try_from(json.clone()).unwrap(), number, "{json}");
+        assert_eq!(serde_json::Number::try_from(number).unwrap(), json);
+        assert_eq!(
+            serde_json::Value::from(number),
+            serde_json::Value::Number(json)
+        );
+    }
+
+    assert!(serde_json::Number::try_from(Number::Float(f64::NAN)).is_err());
+    assert!(serde_json::Number::try_from(Number::Float(f64::INFINITY)).is_err());
+    assert_eq!(
+        serde_json::Value::from(Number::Float(f64::NAN)),
+        serde_json::Value::Null
+    );
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "wasm")]
+
+// Errors are JavaScript values, which only exist on wasm32, so only what parses is tested here.
+
+use webby_global_common::wasm::{parse_filter, parse_query, parse_value, query_string};
+
+#[test]
+fn filters_and_queries_round_trip() {
+    let filter = r#"{"name":"age","cond":"gte","value":18}"#;
+
+    assert_eq!(parse_filter(filter).unwrap(), filter);
+
+    let query =
+        parse_query(r#"{"filters":[{"name":"age","cond":"gte","value":18}],"limit":50}"#).unwrap();
+    let query = serde_json::from_str::<serde_json::Value>(&query).unwrap();
+
+    assert_eq!(query["limit"], 50);
+    assert_eq!(query["filters"][0]["name"], "age");
+}
+
+#[test]
+fn query_strings() {
+    assert_eq!(
+        query_string(r#"{"sort":{"name":"ASC"},"limit":10}"#).unwrap(),
+        "sort%5Bname%5D=ASC&limit=10"
+    );
+}
+
+#[test]
+fn values_are_normalized() {
+    assert_eq!(parse_value("12").unwrap(), "12");
+    assert_eq!(parse_value(r#"["a","b"]"#).unwrap(), r#"["a","b"]"#);
+    assert_eq!(parse_value(r#""2024-01-31""#).unwrap(), r#""2024-01-31""#);
+}